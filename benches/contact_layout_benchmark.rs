@@ -0,0 +1,122 @@
+use std::rc::Rc;
+
+use a_sabr::{
+    bundle::Bundle,
+    contact::{Contact, ContactInfo, ContactMetadata},
+    contact_manager::legacy::evl::EVLManager,
+    node::{ExclusionScope, Node, NodeInfo},
+    node_manager::none::NoManagement,
+    routing::aliases::*,
+    types::{rate_from_f64, time_from_f64, volume_from_f64, HashMap, NodeID, TIME_ZERO},
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const NODE_COUNT: usize = 200;
+const CONTACT_COUNT: usize = 40000;
+
+/// A large synthetic plan: each node has contacts to the next ones, spread over a day.
+fn large_plan(
+    with_metadata: bool,
+) -> (
+    Vec<Node<NoManagement>>,
+    Vec<Contact<NoManagement, EVLManager>>,
+) {
+    let nodes = (0..NODE_COUNT)
+        .map(|id| {
+            let info = NodeInfo {
+                id: id as NodeID,
+                name: format!("node{}", id),
+                excluded: false,
+                exclusion_scope: ExclusionScope::Node,
+                delivery_windows: Vec::new(),
+                attributes: HashMap::new(),
+                aliases: Vec::new(),
+            };
+            Node::try_new(info, NoManagement {}).unwrap()
+        })
+        .collect();
+
+    let metadata = Rc::new(ContactMetadata {
+        attributes: HashMap::from([("band".to_string(), "X".to_string())]),
+    });
+    let mut state: u64 = 42;
+    let contacts = (0..CONTACT_COUNT)
+        .map(|i| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let tx = i % NODE_COUNT;
+            let rx = (tx + 1 + (state >> 33) as usize % 5) % NODE_COUNT;
+            let start = time_from_f64(((state >> 17) % 86400) as f64);
+            let info = ContactInfo::new(
+                tx as NodeID,
+                rx as NodeID,
+                start,
+                start + time_from_f64(600.0),
+            );
+            let manager = EVLManager::new(rate_from_f64(1e6), time_from_f64(0.1));
+            let contact = Contact::try_new(info, manager).unwrap();
+            if with_metadata {
+                contact.with_metadata(metadata.clone())
+            } else {
+                contact
+            }
+        })
+        .collect();
+
+    (nodes, contacts)
+}
+
+pub fn benchmark(c: &mut Criterion) {
+    let bundle = Bundle {
+        source: 0,
+        destinations: vec![(NODE_COUNT / 2) as NodeID],
+        priority: 0,
+        size: volume_from_f64(1000.0),
+        expiration: time_from_f64(200000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    };
+    let excluded_nodes: Vec<NodeID> = vec![];
+    let spsn_opts = SpsnOptions {
+        check_size: false,
+        check_priority: false,
+        max_entries: 10,
+    };
+
+    let mut group = c.benchmark_group("ContactLayout");
+
+    for (label, with_metadata) in [("without metadata", false), ("with metadata", true)] {
+        group.bench_function(label, |b| {
+            b.iter_batched(
+                || {
+                    let (nodes, contacts) = large_plan(with_metadata);
+                    build_generic_router(
+                        "SpsnNodeParenting",
+                        nodes,
+                        contacts,
+                        Some(spsn_opts.clone()),
+                    )
+                    .unwrap()
+                },
+                |mut router| {
+                    black_box(router.route(
+                        black_box(0),
+                        black_box(&bundle),
+                        black_box(TIME_ZERO),
+                        black_box(&excluded_nodes),
+                    ));
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+}
+
+criterion_group! {
+    name=benches;
+    config=Criterion::default().sample_size(20);
+    targets=benchmark
+}
+criterion_main!(benches);
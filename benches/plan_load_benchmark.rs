@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use a_sabr::{
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{
+        asabr_buffer_lexer::BufferLexer, asabr_file_lexer::FileLexer,
+        from_asabr_lexer::ASABRContactPlan,
+    },
+    node_manager::none::NoManagement,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const NODE_COUNT: usize = 200;
+const CONTACT_COUNT: usize = 100000;
+
+/// Writes a large synthetic plan: each node has contacts to the next ones, spread over a day.
+fn write_large_plan() -> String {
+    let mut plan = String::from("# synthetic plan\n");
+    for id in 0..NODE_COUNT {
+        writeln!(plan, "node {} node{}", id, id).unwrap();
+    }
+    let mut state: u64 = 42;
+    for i in 0..CONTACT_COUNT {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let tx = i % NODE_COUNT;
+        let rx = (tx + 1 + (state >> 33) as usize % 5) % NODE_COUNT;
+        let start = (state >> 17) % 86400;
+        writeln!(
+            plan,
+            "contact {} {} {} {} 1000000 0.1",
+            tx,
+            rx,
+            start,
+            start + 600
+        )
+        .unwrap();
+    }
+    let path = std::env::temp_dir().join("asabr_plan_load_benchmark.cp");
+    std::fs::write(&path, plan).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+pub fn benchmark(c: &mut Criterion) {
+    let path = write_large_plan();
+    let mut group = c.benchmark_group("PlanLoad");
+
+    group.bench_function("FileLexer", |b| {
+        b.iter(|| {
+            let mut lexer = FileLexer::new(&path).unwrap();
+            black_box(
+                ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None)
+                    .unwrap(),
+            )
+        });
+    });
+    group.bench_function("BufferLexer", |b| {
+        b.iter(|| {
+            let content = BufferLexer::read_file(&path).unwrap();
+            let mut lexer = BufferLexer::new(&content);
+            black_box(
+                ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None)
+                    .unwrap(),
+            )
+        });
+    });
+    group.finish();
+
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group! {
+    name=benches;
+    config=Criterion::default().sample_size(10);
+    targets=benchmark
+}
+criterion_main!(benches);
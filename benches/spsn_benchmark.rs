@@ -1,7 +1,10 @@
 use a_sabr::{
-    bundle::Bundle, contact_manager::seg::SegmentationManager,
-    contact_plan::from_tvgutil_file::TVGUtilContactPlan, node_manager::none::NoManagement,
-    routing::aliases::*, types::NodeID,
+    bundle::Bundle,
+    contact_manager::seg::SegmentationManager,
+    contact_plan::from_tvgutil_file::TVGUtilContactPlan,
+    node_manager::none::NoManagement,
+    routing::aliases::*,
+    types::{time_from_f64, volume_from_f64, NodeID},
 };
 use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
@@ -13,10 +16,13 @@ pub fn benchmark(c: &mut Criterion) {
         source: 178,
         destinations: vec![159],
         priority: 0,
-        size: 47419533.0,
-        expiration: 24060.0,
+        size: volume_from_f64(47419533.0),
+        expiration: time_from_f64(24060.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
-    let curr_time = 60.0;
+    let curr_time = time_from_f64(60.0);
     let excluded_nodes: Vec<NodeID> = vec![];
     let spsn_opts = SpsnOptions {
         check_size: false,
@@ -82,6 +88,7 @@ pub fn benchmark(c: &mut Criterion) {
                     .unwrap();
 
                     build_generic_router(router_type, nodes, contacts, Some(spsn_opts.clone()))
+                        .unwrap()
                 },
                 |mut router| {
                     black_box(router.route(
@@ -11,6 +11,7 @@ use a_sabr::pathfinding::Pathfinding;
 use a_sabr::types::Date;
 use a_sabr::types::Priority;
 use a_sabr::types::Token;
+use a_sabr::types::{time_from_f64, volume_from_f64, TIME_ZERO};
 use a_sabr::utils::{init_pathfinding, pretty_print};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -24,7 +25,7 @@ impl NodeManager for Compressing {
         let mut earliest_tx_time = at_time;
         if bundle.priority <= self.max_priority {
             bundle.size *= 0.75;
-            earliest_tx_time += 2.0;
+            earliest_tx_time += time_from_f64(2.0);
         }
         return earliest_tx_time;
     }
@@ -34,7 +35,7 @@ impl NodeManager for Compressing {
         let mut earliest_tx_time = at_time;
         if bundle.priority <= self.max_priority {
             bundle.size *= 0.75;
-            earliest_tx_time += 2.0;
+            earliest_tx_time += time_from_f64(2.0);
         }
         return earliest_tx_time;
     }
@@ -99,8 +100,11 @@ fn edge_case_example<NM: NodeManager + Parser<NM> + DispatchParser<NM>>(
         source: 0,
         destinations: vec![3],
         priority: bundle_priority,
-        size: 100.0,
-        expiration: 1000.0,
+        size: volume_from_f64(100.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     let mut mpt_graph = init_pathfinding::<NM, EVLManager, HybridParentingPath<NM, EVLManager, SABR>>(
@@ -115,7 +119,7 @@ fn edge_case_example<NM: NodeManager + Parser<NM> + DispatchParser<NM>>(
         cp_path, bundle_priority
     );
 
-    let res = mpt_graph.get_next(0.0, 0, &bundle, &vec![]);
+    let res = mpt_graph.get_next(TIME_ZERO, 0, &bundle, &vec![]);
 
     match res.by_destination[3].clone() {
         Some(route) => pretty_print(route),
@@ -6,7 +6,7 @@ use a_sabr::{
     pathfinding::{
         hybrid_parenting::HybridParentingPath, node_parenting::NodeParentingPath, Pathfinding,
     },
-    types::NodeID,
+    types::{time_from_f64, NodeID, TIME_ZERO, VOLUME_ZERO},
     utils::{init_pathfinding, pretty_print},
 };
 
@@ -18,8 +18,11 @@ fn edge_case_example(cp_path: &str, dest: NodeID) {
         source: 0,
         destinations: vec![dest],
         priority: 0,
-        size: 0.0,
-        expiration: 1000.0,
+        size: VOLUME_ZERO,
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     let mut node_graph = init_pathfinding::<
@@ -46,18 +49,18 @@ fn edge_case_example(cp_path: &str, dest: NodeID) {
         cp_path, dest
     );
     println!("");
-    let res = node_graph.get_next(0.0, 0, &bundle, &vec![]);
+    let res = node_graph.get_next(TIME_ZERO, 0, &bundle, &vec![]);
     print!("With NodeParentingPath pathfinding. ");
     pretty_print(res.by_destination[dest as usize].clone().unwrap());
 
     #[cfg(feature = "contact_work_area")]
     {
-        let res = contact_graph.get_next(0.0, 0, &bundle, &vec![]);
+        let res = contact_graph.get_next(TIME_ZERO, 0, &bundle, &vec![]);
         print!("With ContactParentingPath pathfinding. ");
         pretty_print(res.by_destination[dest as usize].clone().unwrap());
     }
 
-    let res = mpt_graph.get_next(0.0, 0, &bundle, &vec![]);
+    let res = mpt_graph.get_next(TIME_ZERO, 0, &bundle, &vec![]);
     print!("With HybridParentingPath pathfinding. ");
     pretty_print(res.by_destination[dest as usize].clone().unwrap());
 }
@@ -10,6 +10,7 @@ use a_sabr::parsing::coerce_cm;
 use a_sabr::parsing::ContactMarkerMap;
 use a_sabr::routing::aliases::build_generic_router;
 use a_sabr::routing::aliases::SpsnOptions;
+use a_sabr::types::{time_from_f64, volume_from_f64};
 use a_sabr::utils::pretty_print;
 
 fn main() {
@@ -40,19 +41,25 @@ fn main() {
             check_size: true,
             max_entries: 10,
         }),
-    );
+    )
+    .unwrap();
 
     // We route a bundle
     let bundle_1 = Bundle {
         source: 0,
         destinations: vec![3],
         priority: 0,
-        size: 20.0,
-        expiration: 10000.0,
+        size: volume_from_f64(20.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     // let's route with current time == 15
-    let out = router.route(0, &bundle_1, 15.0, &Vec::new()).unwrap();
+    let out = router
+        .route(0, &bundle_1, time_from_f64(15.0), &Vec::new())
+        .unwrap();
     let (first_hop_contact, route) = out.lazy_get_for_unicast(3).unwrap();
 
     // Retain a ref to the first_hop manager
@@ -72,12 +79,17 @@ fn main() {
         source: 0,
         destinations: vec![3],
         priority: 0,
-        size: 20.0,
-        expiration: 10000.0,
+        size: volume_from_f64(20.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     // let's route with current time == 15, and ensure that the queueing is taken into account
-    let out = router.route(0, &bundle_2, 15.0, &Vec::new()).unwrap();
+    let out = router
+        .route(0, &bundle_2, time_from_f64(15.0), &Vec::new())
+        .unwrap();
     let (first_hop_contact, route) = out.lazy_get_for_unicast(3).unwrap();
     pretty_print(route);
 
@@ -97,10 +109,13 @@ fn main() {
         source: 0,
         destinations: vec![4],
         priority: 0,
-        size: 20.0,
-        expiration: 10000.0,
+        size: volume_from_f64(20.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
-    let out = router.route(0, &bundle_3, 15.0, &Vec::new());
+    let out = router.route(0, &bundle_3, time_from_f64(15.0), &Vec::new());
     println!(
         "Sending bundle 3 to node 4, the routing output should be None: {}",
         out.is_none()
@@ -117,7 +132,9 @@ fn main() {
             .manual_dequeue(&bundle_1)
     );
     println!("Retry for bundle 3");
-    let out = router.route(0, &bundle_3, 15.0, &Vec::new()).unwrap();
+    let out = router
+        .route(0, &bundle_3, time_from_f64(15.0), &Vec::new())
+        .unwrap();
     let (_, route) = out.lazy_get_for_unicast(4).unwrap();
     pretty_print(route);
 }
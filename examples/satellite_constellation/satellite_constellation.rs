@@ -11,6 +11,7 @@ use a_sabr::pathfinding::Pathfinding;
 use a_sabr::types::Date;
 use a_sabr::types::Duration;
 use a_sabr::types::Token;
+use a_sabr::types::{time_from_f64, TIME_ZERO, VOLUME_ZERO};
 use a_sabr::utils::{init_pathfinding, pretty_print};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -90,8 +91,11 @@ fn edge_case_example<NM: NodeManager + Parser<NM> + DispatchParser<NM>>(
         source: 0,
         destinations: vec![2],
         priority: 0,
-        size: 0.0,
-        expiration: 1000.0,
+        size: VOLUME_ZERO,
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     let mut mpt_graph = init_pathfinding::<NM, EVLManager, HybridParentingPath<NM, EVLManager, SABR>>(
@@ -106,7 +110,7 @@ fn edge_case_example<NM: NodeManager + Parser<NM> + DispatchParser<NM>>(
         cp_path
     );
 
-    let res = mpt_graph.get_next(0.0, 0, &bundle, &vec![]);
+    let res = mpt_graph.get_next(TIME_ZERO, 0, &bundle, &vec![]);
 
     match res.by_destination[2].clone() {
         Some(route) => pretty_print(route),
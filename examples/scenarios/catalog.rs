@@ -0,0 +1,59 @@
+//! The scenarios, each a contact plan, a workload and the expected metrics of the replay. Shared
+//! by the `scenarios` example and the `scenarios` integration tests, the bounds are the
+//! regression ground truth of the routing.
+
+use a_sabr::scenario::{Scenario, ScenarioMetric};
+
+/// LEO constellation downlink: the science products wait for a pass over a ground station, or
+/// cross the inter-satellite links toward the next pass.
+pub fn leo_downlink() -> Scenario {
+    Scenario::new(
+        "leo_downlink",
+        include_str!("leo_downlink/plan.cp"),
+        include_str!("leo_downlink/workload.trace"),
+    )
+    .unwrap()
+    .expect_at_least(ScenarioMetric::DeliveryRatio, 1.0)
+    .expect_at_most(ScenarioMetric::MeanLatency, 300.0)
+    .expect_at_most(ScenarioMetric::MaxLatency, 900.0)
+    .expect_at_most(ScenarioMetric::MeanHopCount, 3.0)
+    .expect_at_least(ScenarioMetric::UniqueRoutes, 8.0)
+}
+
+/// Deep-space relay: the long light times and the sparse passes dominate the latency, and a
+/// command expiring before the next pass over the rover cannot be routed.
+pub fn deep_space_relay() -> Scenario {
+    Scenario::new(
+        "deep_space_relay",
+        include_str!("deep_space_relay/plan.cp"),
+        include_str!("deep_space_relay/workload.trace"),
+    )
+    .unwrap()
+    .expect_at_least(ScenarioMetric::DeliveryRatio, 6.0 / 7.0)
+    .expect_at_most(ScenarioMetric::DeliveryRatio, 6.0 / 7.0)
+    .expect_at_least(ScenarioMetric::MeanLatency, 1440.0)
+    .expect_at_most(ScenarioMetric::MeanLatency, 8000.0)
+    .expect_at_most(ScenarioMetric::MaxLatency, 25000.0)
+    .expect_at_most(ScenarioMetric::MeanHopCount, 3.5)
+}
+
+/// Disaster-response ad-hoc network: a drone ferries the reports of the field teams, an alert is
+/// multicast, and a report expiring before the drone returns cannot be routed.
+pub fn disaster_response() -> Scenario {
+    Scenario::new(
+        "disaster_response",
+        include_str!("disaster_response/plan.cp"),
+        include_str!("disaster_response/workload.trace"),
+    )
+    .unwrap()
+    .expect_at_least(ScenarioMetric::DeliveryRatio, 0.9)
+    .expect_at_most(ScenarioMetric::MeanLatency, 400.0)
+    .expect_at_most(ScenarioMetric::MaxLatency, 700.0)
+    .expect_at_most(ScenarioMetric::MeanHopCount, 3.0)
+}
+
+/// All the scenarios.
+#[allow(dead_code)]
+pub fn all() -> Vec<Scenario> {
+    vec![leo_downlink(), deep_space_relay(), disaster_response()]
+}
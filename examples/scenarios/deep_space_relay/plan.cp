@@ -0,0 +1,29 @@
+# Deep-space relay: a rover on Mars reached through an orbiter, the orbiter being in view of a
+# Deep Space Network (DSN) station at a time, with a one-way light time of 12 minutes.
+node 0 moc
+node 1 dsn_goldstone
+node 2 dsn_madrid
+node 3 mars_orbiter
+node 4 rover
+
+# Ground network, always available
+contact 0 1 0 86400 100000 0.05
+contact 1 0 0 86400 100000 0.05
+contact 0 2 0 86400 100000 0.1
+contact 2 0 0 86400 100000 0.1
+
+# DSN tracking passes, 720 s of one-way light time
+contact 1 3 0 14400 500 720
+contact 3 1 0 14400 2000 720
+contact 2 3 28800 43200 500 720
+contact 3 2 28800 43200 2000 720
+contact 1 3 57600 72000 500 720
+contact 3 1 57600 72000 2000 720
+
+# Orbiter passes over the rover
+contact 3 4 3600 4200 2000 0.01
+contact 4 3 3600 4200 2000 0.01
+contact 3 4 30000 30600 2000 0.01
+contact 4 3 30000 30600 2000 0.01
+contact 3 4 60000 60600 2000 0.01
+contact 4 3 60000 60600 2000 0.01
@@ -0,0 +1,37 @@
+# Disaster response: the field teams have no infrastructure, a drone ferries the data between
+# them and a command post, the command post reaching a hospital through a relay truck.
+node 0 command_post
+node 1 drone
+node 2 team_a
+node 3 team_b
+node 4 hospital
+node 5 relay_truck
+
+# Command post and relay truck, radio link always available
+contact 0 5 0 3600 5000 0.01
+contact 5 0 0 3600 5000 0.01
+# Relay truck and hospital, cellular link with an outage
+contact 5 4 0 1200 2000 0.1
+contact 4 5 0 1200 2000 0.1
+contact 5 4 1800 3600 2000 0.1
+contact 4 5 1800 3600 2000 0.1
+
+# Drone rounds: command post, team_a, team_b, back to the command post
+contact 0 1 0 120 1000 0.01
+contact 1 0 0 120 1000 0.01
+contact 1 2 300 420 1000 0.01
+contact 2 1 300 420 1000 0.01
+contact 1 3 600 720 1000 0.01
+contact 3 1 600 720 1000 0.01
+contact 0 1 900 1020 1000 0.01
+contact 1 0 900 1020 1000 0.01
+contact 1 2 1200 1320 1000 0.01
+contact 2 1 1200 1320 1000 0.01
+contact 1 3 1500 1620 1000 0.01
+contact 3 1 1500 1620 1000 0.01
+contact 0 1 1800 1920 1000 0.01
+contact 1 0 1800 1920 1000 0.01
+
+# The teams see each other briefly
+contact 2 3 1000 1060 500 0.01
+contact 3 2 1000 1060 500 0.01
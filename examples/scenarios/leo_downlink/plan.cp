@@ -0,0 +1,40 @@
+# LEO constellation downlink: three satellites in a ring of inter-satellite links, two ground
+# stations with passes of ten minutes, and a mission operations center (MOC) on the ground network.
+node 0 moc
+node 1 gs_north
+node 2 gs_south
+node 3 sat_a
+node 4 sat_b
+node 5 sat_c
+
+# Ground network, always available
+contact 0 1 0 7200 10000 0.05
+contact 1 0 0 7200 10000 0.05
+contact 0 2 0 7200 10000 0.08
+contact 2 0 0 7200 10000 0.08
+
+# Inter-satellite links, always available with a low rate
+contact 3 4 0 7200 100 0.01
+contact 4 3 0 7200 100 0.01
+contact 4 5 0 7200 100 0.01
+contact 5 4 0 7200 100 0.01
+contact 5 3 0 7200 100 0.01
+contact 3 5 0 7200 100 0.01
+
+# Passes over gs_north
+contact 3 1 0 600 1000 0.01
+contact 1 3 0 600 1000 0.01
+contact 5 1 1800 2400 1000 0.01
+contact 1 5 1800 2400 1000 0.01
+contact 4 1 3600 4200 1000 0.01
+contact 1 4 3600 4200 1000 0.01
+contact 3 1 5400 6000 1000 0.01
+contact 1 3 5400 6000 1000 0.01
+
+# Passes over gs_south
+contact 4 2 900 1500 1000 0.01
+contact 2 4 900 1500 1000 0.01
+contact 3 2 2700 3300 1000 0.01
+contact 2 3 2700 3300 1000 0.01
+contact 5 2 4500 5100 1000 0.01
+contact 2 5 4500 5100 1000 0.01
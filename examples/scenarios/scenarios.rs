@@ -0,0 +1,42 @@
+use a_sabr::contact_manager::legacy::evl::EVLManager;
+use a_sabr::faults::FaultModel;
+use a_sabr::node_manager::none::NoManagement;
+use a_sabr::types::time_from_f64;
+
+mod catalog;
+
+fn main() {
+    let mut failed = 0;
+    for scenario in catalog::all() {
+        // Each scenario parses its plan, builds a fresh router and replays its workload
+        let report = scenario.run::<NoManagement, EVLManager>().unwrap();
+        if !report.passed() {
+            failed += 1;
+        }
+        println!("{}", report);
+    }
+
+    // The unicast workloads (the Cgr routers do not route multicast bundles), routed from the
+    // plans but facing contacts failing, starting late, ending early or delivering at half rate
+    let faults = FaultModel::new(42)
+        .failures(0.05)
+        .late_starts(0.2, time_from_f64(60.0))
+        .early_ends(0.2, time_from_f64(60.0))
+        .reduced_rates(0.2, 0.5);
+    let router_types = [
+        "SpsnNodeParenting",
+        "SpsnHybridParenting",
+        "VolCgrNodeParenting",
+        "VolCgrHybridParenting",
+    ];
+    for scenario in [catalog::leo_downlink(), catalog::deep_space_relay()] {
+        let report = scenario
+            .compare_robustness::<NoManagement, EVLManager>(&router_types, &faults)
+            .unwrap();
+        println!("{}", report);
+    }
+    if failed > 0 {
+        println!("{} scenario(s) out of their expected bounds", failed);
+        std::process::exit(1);
+    }
+}
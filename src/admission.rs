@@ -0,0 +1,234 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    routing::Router,
+    types::{time_to_f64, Date, Duration, NodeID, Priority, Volume, VOLUME_ZERO},
+};
+
+/// Identifies a commitment of an `AdmissionControl`.
+pub type CommitmentID = usize;
+
+/// A flow accepted by the admission control, i.e. a daily volume to deliver to a destination.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+pub struct Commitment {
+    /// The source node of the flow.
+    pub source: NodeID,
+    /// The destination node of the flow.
+    pub destination: NodeID,
+    /// The priority of the flow.
+    pub priority: Priority,
+    /// The volume per day (see `AdmissionControl::new`) of the flow.
+    pub daily_volume: Volume,
+}
+
+/// The reason why a flow cannot be accepted, for the first window (day) of the horizon failing.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum AdmissionRefusal {
+    /// No route reaches the destination for the window starting at `window_start`.
+    NoRoute { window_start: Date },
+    /// The volume of the window cannot be delivered before the end of the window.
+    LateDelivery {
+        window_start: Date,
+        delivery_time: Date,
+    },
+    /// The contacts of the route lack `missing` volume for the window.
+    InsufficientVolume { window_start: Date, missing: Volume },
+}
+
+/// Tracks the volume committed per destination over the horizon of a contact plan, and decides
+/// whether new flows can be accepted (mission planning, flow control).
+///
+/// The horizon is split in windows of one day, the volume committed for a window (plus the
+/// requested volume) must be deliverable within the window. The decision relies on
+/// `Router::estimate`, no resource is consumed: the commitments are volumes promised to the
+/// flows, not bundles scheduled on the contacts.
+///
+/// Only the commitments to the requested destination with a priority higher or equal to the
+/// requested one are accounted for, lower priority flows can be preempted.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AdmissionControl {
+    /// The start of the horizon.
+    horizon_start: Date,
+    /// The end of the horizon (e.g. the end of the last contact of the plan).
+    horizon_end: Date,
+    /// The length of a day, in the time unit of the plan.
+    day: Duration,
+    /// The commitments, `None` once released.
+    commitments: Vec<Option<Commitment>>,
+}
+
+impl AdmissionControl {
+    /// Creates an admission control without commitments.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon_start` - The start of the horizon.
+    /// * `horizon_end` - The end of the horizon.
+    /// * `day` - The length of a day in the time unit of the plan (e.g. 86400 for seconds), the
+    ///   volumes are expressed per day.
+    ///
+    /// # Returns
+    ///
+    /// The admission control, or an error if the horizon or the day is empty.
+    pub fn new(horizon_start: Date, horizon_end: Date, day: Duration) -> Result<Self, String> {
+        if horizon_end <= horizon_start {
+            return Err(String::from("The horizon must end after its start"));
+        }
+        if day <= Duration::default() {
+            return Err(String::from("The day length must be positive"));
+        }
+        Ok(Self {
+            horizon_start,
+            horizon_end,
+            day,
+            commitments: Vec::new(),
+        })
+    }
+
+    /// Returns the commitment with the given id, `None` if unknown or released.
+    pub fn get_commitment(&self, id: CommitmentID) -> Option<&Commitment> {
+        self.commitments.get(id)?.as_ref()
+    }
+
+    /// Returns the active commitments with their ids.
+    pub fn commitments(&self) -> impl Iterator<Item = (CommitmentID, &Commitment)> {
+        self.commitments
+            .iter()
+            .enumerate()
+            .filter_map(|(id, commitment)| commitment.as_ref().map(|c| (id, c)))
+    }
+
+    /// Returns the daily volume committed to a destination, for flows of priority higher or equal
+    /// to `priority`.
+    pub fn committed_daily_volume(&self, destination: NodeID, priority: Priority) -> Volume {
+        self.commitments()
+            .filter(|(_, c)| c.destination == destination && c.priority >= priority)
+            .map(|(_, c)| c.daily_volume)
+            .sum()
+    }
+
+    /// Returns the volume committed to a destination over the whole horizon, for flows of priority
+    /// higher or equal to `priority`.
+    pub fn committed_volume(&self, destination: NodeID, priority: Priority) -> Volume {
+        self.committed_daily_volume(destination, priority)
+            * (time_to_f64(self.horizon_end - self.horizon_start) / time_to_f64(self.day))
+    }
+
+    /// Checks whether `daily_volume` more bytes per day can be delivered from `source` to
+    /// `destination` at `priority`, over the whole horizon.
+    ///
+    /// For each window, a bundle of the volume of the window (committed and requested) is
+    /// estimated at the start of the window, and must be delivered before its end without
+    /// exceeding the residual volumes of the contacts.
+    ///
+    /// # Parameters
+    ///
+    /// * `router` - The router used for the estimations.
+    /// * `source` - The source node of the flow.
+    /// * `destination` - The destination node of the flow.
+    /// * `daily_volume` - The requested volume per day.
+    /// * `priority` - The priority of the flow.
+    /// * `excluded_nodes` - The nodes to exclude from the routes.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the flow can be accepted, the refusal reason of the first failing window otherwise.
+    pub fn check<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+        &self,
+        router: &mut R,
+        source: NodeID,
+        destination: NodeID,
+        daily_volume: Volume,
+        priority: Priority,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Result<(), AdmissionRefusal> {
+        let total_daily_volume = self.committed_daily_volume(destination, priority) + daily_volume;
+        let mut window_start = self.horizon_start;
+
+        while window_start < self.horizon_end {
+            let window_end = Date::min(window_start + self.day, self.horizon_end);
+            let bundle = Bundle {
+                source,
+                destinations: vec![destination],
+                priority,
+                size: total_daily_volume * time_to_f64(window_end - window_start)
+                    / time_to_f64(self.day),
+                expiration: window_end,
+                soft_deadline: None,
+                critical: false,
+                destination_priorities: Vec::new(),
+            };
+
+            let Some(estimate) = router.estimate(source, &bundle, window_start, excluded_nodes)
+            else {
+                return Err(AdmissionRefusal::NoRoute { window_start });
+            };
+            if estimate.delivery_time > window_end {
+                return Err(AdmissionRefusal::LateDelivery {
+                    window_start,
+                    delivery_time: estimate.delivery_time,
+                });
+            }
+            if let Some(margin) = estimate.min_volume_margin() {
+                if margin < VOLUME_ZERO {
+                    return Err(AdmissionRefusal::InsufficientVolume {
+                        window_start,
+                        missing: -margin,
+                    });
+                }
+            }
+            window_start = window_end;
+        }
+        Ok(())
+    }
+
+    /// Checks a flow like `check` and commits it if it can be accepted.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new commitment, or the refusal reason.
+    pub fn admit<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+        &mut self,
+        router: &mut R,
+        source: NodeID,
+        destination: NodeID,
+        daily_volume: Volume,
+        priority: Priority,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Result<CommitmentID, AdmissionRefusal> {
+        self.check(
+            router,
+            source,
+            destination,
+            daily_volume,
+            priority,
+            excluded_nodes,
+        )?;
+        Ok(self.commit(Commitment {
+            source,
+            destination,
+            priority,
+            daily_volume,
+        }))
+    }
+
+    /// Commits a flow without checking it (e.g. flows decided beforehand).
+    ///
+    /// # Returns
+    ///
+    /// The id of the new commitment.
+    pub fn commit(&mut self, commitment: Commitment) -> CommitmentID {
+        self.commitments.push(Some(commitment));
+        self.commitments.len() - 1
+    }
+
+    /// Releases a commitment, returns it if it was active.
+    pub fn release(&mut self, id: CommitmentID) -> Option<Commitment> {
+        self.commitments.get_mut(id)?.take()
+    }
+}
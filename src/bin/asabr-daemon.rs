@@ -0,0 +1,306 @@
+//! A minimal HTTP routing daemon, for bundle protocol agents that are not written in Rust.
+//!
+//! Usage: `asabr-daemon [<address>] [<router_type>]` (defaults: `127.0.0.1:4556`, `SpsnHybridParenting`).
+//!
+//! Endpoints (JSON responses):
+//! - `POST /plan[?router=<router_type>]`: loads (or replaces) the contact plan, the body is an A-SABR
+//!   contact plan using the "evl", "qd", "eto", "seg", "hybrid" or "part" contact markers.
+//! - `POST /route`: routes and schedules a bundle, the body is a JSON object with the `source`,
+//!   `destinations`, `priority`, `size`, `expiration`, `curr_time` and (optional) `excluded_nodes` fields.
+//! - `GET /stats`: returns the daemon statistics.
+//! - `GET /stats/audit`: returns the audit records of the last routing calls (see `AuditLog`),
+//!   from the oldest to the newest.
+//!
+//! The request bodies are limited to `MAX_BODY_SIZE` bytes (64 MiB). The router is not
+//! thread-safe, requests are served one at a time, a connection idle for `IO_TIMEOUT` being
+//! dropped. A plan that fails to load leaves the previous router and statistics in place.
+
+use std::{
+    env,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::{
+        hybrid::HybridManager,
+        legacy::{eto::ETOManager, evl::EVLManager, qd::QDManager},
+        partition::coerce_partitioned,
+        seg::SegmentationManager,
+        ContactManager,
+    },
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    parsing::{coerce_cm, ContactMarkerMap},
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        audit::AuditedRouter,
+        Router,
+    },
+    types::{Date, NodeID, Priority, Volume},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// The count of routing calls kept in the audit log.
+const AUDIT_CAPACITY: usize = 1024;
+
+/// The maximum size of a request body in bytes (e.g. a contact plan), larger requests are refused.
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// The maximum wait for a read or a write on a connection, for a stalled client not to block the
+/// daemon.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize)]
+struct RouteRequest {
+    source: NodeID,
+    destinations: Vec<NodeID>,
+    priority: Priority,
+    size: Volume,
+    expiration: Date,
+    #[serde(default)]
+    soft_deadline: Option<Date>,
+    curr_time: Date,
+    #[serde(default)]
+    excluded_nodes: Vec<NodeID>,
+    #[serde(default)]
+    critical: bool,
+    #[serde(default)]
+    destination_priorities: Vec<(NodeID, Priority)>,
+}
+
+#[derive(Default, Serialize)]
+struct Stats {
+    router: String,
+    node_count: usize,
+    contact_count: usize,
+    plan_loads: usize,
+    route_requests: usize,
+    routed: usize,
+    unrouted: usize,
+    errors: usize,
+}
+
+struct Daemon {
+    default_router: String,
+    router: Option<AuditedRouter<NoManagement, Box<dyn ContactManager>>>,
+    stats: Stats,
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            body,
+        }
+    }
+
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+impl Daemon {
+    fn load_plan(&mut self, router_type: &str, plan: &str) -> Response {
+        let mut lexer = StrLexer::new(plan);
+        let mut contact_dispatch: ContactMarkerMap = ContactMarkerMap::new();
+        contact_dispatch.add("evl", coerce_cm::<EVLManager>);
+        contact_dispatch.add("qd", coerce_cm::<QDManager>);
+        contact_dispatch.add("eto", coerce_cm::<ETOManager>);
+        contact_dispatch.add("seg", coerce_cm::<SegmentationManager>);
+        contact_dispatch.add("hybrid", coerce_cm::<HybridManager>);
+        contact_dispatch.add("part", coerce_partitioned);
+
+        let (nodes, contacts) = match ASABRContactPlan::parse::<NoManagement, Box<dyn ContactManager>>(
+            &mut lexer,
+            None,
+            Some(&contact_dispatch),
+        ) {
+            Ok(parsed) => parsed,
+            Err(e) => return Response::error("400 Bad Request", &e.to_string()),
+        };
+
+        let spsn_options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+            check_size: false,
+            check_priority: false,
+            max_entries: 10,
+        });
+
+        let (node_count, contact_count) = (nodes.len(), contacts.len());
+        match build_generic_router(router_type, nodes, contacts, spsn_options) {
+            Ok(router) => {
+                self.router = Some(AuditedRouter::new(router, AUDIT_CAPACITY));
+                self.stats.router = router_type.to_string();
+                self.stats.node_count = node_count;
+                self.stats.contact_count = contact_count;
+                self.stats.plan_loads += 1;
+                Response::ok(json!({ "loaded": true }).to_string())
+            }
+            Err(e) => Response::error("400 Bad Request", &e.to_string()),
+        }
+    }
+
+    fn route(&mut self, body: &str) -> Response {
+        let Some(router) = self.router.as_mut() else {
+            return Response::error("409 Conflict", "no contact plan loaded");
+        };
+        let request: RouteRequest = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(e) => return Response::error("400 Bad Request", &e.to_string()),
+        };
+        if request.destinations.is_empty() {
+            return Response::error("400 Bad Request", "no destination");
+        }
+        let node_count = self.stats.node_count;
+        if let Some(node) = std::iter::once(&request.source)
+            .chain(&request.destinations)
+            .find(|node| usize::from(**node) >= node_count)
+        {
+            return Response::error("400 Bad Request", &format!("unknown node {}", node));
+        }
+        let mut excluded_nodes = request.excluded_nodes;
+        excluded_nodes.sort_unstable();
+        let bundle = Bundle {
+            source: request.source,
+            destinations: request.destinations,
+            priority: request.priority,
+            size: request.size,
+            expiration: request.expiration,
+            soft_deadline: request.soft_deadline,
+            critical: request.critical,
+            destination_priorities: request.destination_priorities,
+        };
+
+        self.stats.route_requests += 1;
+        match router.route(request.source, &bundle, request.curr_time, &excluded_nodes) {
+            Some(out) => {
+                self.stats.routed += 1;
+                Response::ok(serde_json::to_string(&out.summarize()).unwrap())
+            }
+            None => {
+                self.stats.unrouted += 1;
+                Response::ok(json!({ "first_hops": [] }).to_string())
+            }
+        }
+    }
+
+    fn dispatch(&mut self, method: &str, target: &str, body: &str) -> Response {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        match (method, path) {
+            ("POST", "/plan") => {
+                let router_type = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("router="))
+                    .unwrap_or(&self.default_router)
+                    .to_string();
+                self.load_plan(&router_type, body)
+            }
+            ("POST", "/route") => self.route(body),
+            ("GET", "/stats") => Response::ok(serde_json::to_string(&self.stats).unwrap()),
+            ("GET", "/stats/audit") => {
+                let records: Vec<_> = self
+                    .router
+                    .iter()
+                    .flat_map(|router| router.log().records())
+                    .collect();
+                Response::ok(serde_json::to_string(&records).unwrap())
+            }
+            _ => Response::error("404 Not Found", "unknown endpoint"),
+        }
+    }
+
+    fn handle(&mut self, stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or_default().to_string();
+
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let response = if content_length > MAX_BODY_SIZE {
+            // the body is not read, the connection being closed after the response
+            Response::error("413 Payload Too Large", "the body is too large")
+        } else {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            match String::from_utf8(body) {
+                Ok(body) => self.dispatch(&method, &target, &body),
+                Err(_) => Response::error("400 Bad Request", "the body is not valid UTF-8"),
+            }
+        };
+        if !response.status.starts_with("200") {
+            self.stats.errors += 1;
+        }
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.body.len(),
+            response.body
+        )?;
+        stream.flush()
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let address = args.get(1).map_or("127.0.0.1:4556", String::as_str);
+    let default_router = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| "SpsnHybridParenting".to_string());
+
+    let listener = TcpListener::bind(address).unwrap_or_else(|e| {
+        eprintln!("Unable to bind {}: {}", address, e);
+        std::process::exit(1);
+    });
+    // the address bound, e.g. the port picked for "127.0.0.1:0"
+    let address = listener
+        .local_addr()
+        .map_or(address.to_string(), |address| address.to_string());
+    println!("A-SABR daemon listening on {}.", address);
+
+    let mut daemon = Daemon {
+        default_router,
+        router: None,
+        stats: Stats::default(),
+    };
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = daemon.handle(stream) {
+                    eprintln!("Connection error: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Connection failed: {}", e),
+        }
+    }
+}
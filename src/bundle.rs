@@ -1,4 +1,9 @@
-use crate::types::{Date, NodeID, Priority, Volume};
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{
+    qos::QosProfile,
+    types::{Date, NodeID, Priority, Volume},
+};
 
 /// A structure representing a routing bundle containing essential information for pathfinding.
 ///
@@ -17,9 +22,54 @@ pub struct Bundle {
     pub size: Volume,
     /// The expiration date for the bundle.
     pub expiration: Date,
+    /// The soft deadline of the bundle, if any: the routes delivering the bundle by this date are
+    /// preferred by the `SoftDeadline` distance, whatever their other criteria, while the
+    /// expiration still prunes the routes. Suited to the latency-sensitive traffic that is not
+    /// critical.
+    pub soft_deadline: Option<Date>,
+    /// Whether the bundle is critical: a critical unicast bundle is forwarded to every neighbor
+    /// with a feasible route to the destination, instead of the best route only.
+    pub critical: bool,
+    /// The priorities of some destinations of a multicast bundle, overriding `priority` for these
+    /// destinations (usually empty). A branch of the multicast tree inherits the highest priority
+    /// among the destinations it serves. Ignored for unicast bundles.
+    pub destination_priorities: Vec<(NodeID, Priority)>,
 }
 
 impl Bundle {
+    /// Creates a bundle of a class of traffic, with the priority, the expiration and the
+    /// criticality of its profile (see `QosRegistry`).
+    ///
+    /// # Parameters
+    ///
+    /// * `profile` - The profile of the class of the bundle.
+    /// * `source` - The source node.
+    /// * `size` - The size of the bundle.
+    /// * `destinations` - The destination nodes.
+    /// * `now` - The creation time, the expiration being `now + profile.expiration_offset`.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `Bundle`.
+    pub fn from_profile(
+        profile: &QosProfile,
+        source: NodeID,
+        size: Volume,
+        destinations: Vec<NodeID>,
+        now: Date,
+    ) -> Self {
+        Self {
+            source,
+            destinations,
+            priority: profile.priority,
+            size,
+            expiration: now + profile.expiration_offset,
+            soft_deadline: None,
+            critical: profile.critical,
+            destination_priorities: Vec::new(),
+        }
+    }
+
     /// Determines if the current bundle "shadows" existing routes based on size and priority checks.
     ///
     /// This method is used to enhance volume-aware pathfinding by tracking possible paths that
@@ -42,9 +92,82 @@ impl Bundle {
         }
 
         // a higher priority volume can claim lower priority volume congested paths
-        if check_by_priority && self.priority > other.priority {
+        if check_by_priority && self.max_priority() > other.min_priority() {
             return true;
         }
         false
     }
+
+    /// Returns the priority of the bundle toward a destination, i.e. the override of the
+    /// destination for a multicast bundle, `priority` otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination.
+    ///
+    /// # Returns
+    ///
+    /// * `Priority` - The priority toward `dest`.
+    pub fn priority_for(&self, dest: NodeID) -> Priority {
+        if self.destinations.len() < 2 {
+            return self.priority;
+        }
+        self.destination_priorities
+            .iter()
+            .find(|(node, _)| *node == dest)
+            .map_or(self.priority, |(_, priority)| *priority)
+    }
+
+    /// Returns the priority inherited by a branch serving some destinations, i.e. the highest
+    /// priority toward these destinations (`priority` if there is none).
+    ///
+    /// # Parameters
+    ///
+    /// * `dests` - The destinations served by the branch.
+    ///
+    /// # Returns
+    ///
+    /// * `Priority` - The priority of the branch.
+    pub fn inherited_priority(&self, dests: &[NodeID]) -> Priority {
+        dests
+            .iter()
+            .map(|dest| self.priority_for(*dest))
+            .max()
+            .unwrap_or(self.priority)
+    }
+
+    /// Returns the highest priority toward the destinations, the priority of the root of a
+    /// multicast tree.
+    pub fn max_priority(&self) -> Priority {
+        self.inherited_priority(&self.destinations)
+    }
+
+    /// Returns the lowest priority toward the destinations.
+    pub fn min_priority(&self) -> Priority {
+        self.destinations
+            .iter()
+            .map(|dest| self.priority_for(*dest))
+            .min()
+            .unwrap_or(self.priority)
+    }
+
+    /// Returns the bundle to consider for a branch serving some destinations, with the priority
+    /// inherited by the branch. The bundle is only copied if the priority differs.
+    ///
+    /// # Parameters
+    ///
+    /// * `dests` - The destinations served by the branch.
+    ///
+    /// # Returns
+    ///
+    /// * `Cow<'_, Bundle>` - The bundle with the inherited priority.
+    pub fn for_destinations(&self, dests: &[NodeID]) -> Cow<'_, Bundle> {
+        let priority = self.inherited_priority(dests);
+        if priority == self.priority {
+            return Cow::Borrowed(self);
+        }
+        let mut bundle = self.clone();
+        bundle.priority = priority;
+        Cow::Owned(bundle)
+    }
 }
@@ -1,17 +1,20 @@
-use crate::contact_manager::ContactManager;
+use crate::bundle::Bundle;
+use crate::contact_manager::{ContactManager, ContactManagerTxData, TxPlan};
+use crate::contact_plan::semantics::ContactSemantics;
+use crate::epoch::parse_date;
 use crate::node_manager::NodeManager;
 use crate::parsing::{Lexer, Parser, ParsingState};
-#[cfg(feature = "contact_work_area")]
-use crate::route_stage::RouteStage;
-use crate::types::{Date, NodeID, Token};
-#[cfg(feature = "contact_work_area")]
-use std::cell::RefCell;
-use std::cmp::Ordering;
-use std::marker::PhantomData;
-#[cfg(feature = "contact_work_area")]
-use std::rc::Rc;
+use crate::types::{ContactID, Date, HashMap, NodeID, Token};
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
 
 /// Represents basic information about a contact between two nodes.
+///
+/// `ContactInfo` only holds the fields read by the pathfinding and the scheduling, and is passed
+/// to the contact managers by reference. The other contact data belongs in `ContactMetadata`.
 #[derive(Clone, Copy)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct ContactInfo {
@@ -23,8 +26,14 @@ pub struct ContactInfo {
     pub start: Date,
     /// The end time of the contact.
     pub end: Date,
+    /// The interpretation of the plan assumptions by the contact managers (volume rounding, queue
+    /// accounting), the default semantics if not set.
+    pub semantics: ContactSemantics,
 }
 
+// the hot fields must stay within a cache line fraction, see `ContactMetadata`
+const _: () = assert!(core::mem::size_of::<ContactInfo>() <= 32);
+
 impl ContactInfo {
     /// Creates a new `ContactInfo` instance.
     ///
@@ -44,9 +53,25 @@ impl ContactInfo {
             rx_node,
             start,
             end,
+            semantics: ContactSemantics::default(),
         }
     }
 
+    /// Sets the semantics of the contact, to be set before `Contact::try_new` (the managers round
+    /// the volume at initialization).
+    ///
+    /// # Parameters
+    ///
+    /// * `semantics` - The semantics of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The contact information with the semantics.
+    pub fn with_semantics(mut self, semantics: ContactSemantics) -> Self {
+        self.semantics = semantics;
+        self
+    }
+
     /// Checks if the contact is valid based on its start and end times.
     ///
     /// # Returns
@@ -57,6 +82,31 @@ impl ContactInfo {
     }
 }
 
+/// The cold data of a contact (e.g. MTU, link band, provenance), never read by the pathfinding.
+///
+/// The metadata is shared by reference, the contacts without metadata do not allocate.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default)]
+pub struct ContactMetadata {
+    /// Arbitrary key/value attributes.
+    pub attributes: HashMap<String, String>,
+}
+
+impl ContactMetadata {
+    /// Retrieves the value of an attribute of the contact.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the attribute.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The value of the attribute, or `None` if the contact does not declare it.
+    pub fn get_attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+}
+
 /// Represents a contact with associated management information.
 ///
 ///  # Type Parameters
@@ -68,9 +118,10 @@ pub struct Contact<NM: NodeManager, CM: ContactManager> {
     pub info: ContactInfo,
     /// The manager handling the contact's operations.
     pub manager: CM,
-    #[cfg(feature = "contact_work_area")]
-    /// The work area for managing path construction stages (compilation option).
-    pub work_area: Option<Rc<RefCell<RouteStage<NM, CM>>>>,
+    /// The identifier of the contact, assigned when the contact is added to a `Multigraph`.
+    pub id: ContactID,
+    /// The cold data of the contact, if any.
+    pub metadata: Option<Rc<ContactMetadata>>,
     #[cfg(feature = "contact_suppression")]
     /// Suppression option for path construction (compilation option).
     pub suppressed: bool,
@@ -96,8 +147,8 @@ impl<NM: NodeManager, CM: ContactManager> Contact<NM, CM> {
             return Some(Contact {
                 info,
                 manager,
-                #[cfg(feature = "contact_work_area")]
-                work_area: None,
+                id: 0,
+                metadata: None,
                 #[cfg(feature = "contact_suppression")]
                 suppressed: false,
                 // for compilation
@@ -107,6 +158,20 @@ impl<NM: NodeManager, CM: ContactManager> Contact<NM, CM> {
         None
     }
 
+    /// Attaches cold data to the contact.
+    ///
+    /// # Parameters
+    ///
+    /// * `metadata` - The metadata, possibly shared with other contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The contact with the metadata.
+    pub fn with_metadata(mut self, metadata: Rc<ContactMetadata>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
     /// Retrieves the transmitting node's ID.
     ///
     /// # Returns
@@ -126,8 +191,55 @@ impl<NM: NodeManager, CM: ContactManager> Contact<NM, CM> {
     pub fn get_rx_node(&self) -> NodeID {
         self.info.rx_node
     }
+
+    /// Dry runs the transmission of a bundle, and returns the plan to book it with `commit_tx`.
+    ///
+    /// # Parameters
+    ///
+    /// * `at_time` - The time the bundle is ready for the transmission.
+    /// * `bundle` - The bundle to transmit.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<TxPlan>` - The plan, or `None` if the contact cannot carry the bundle.
+    pub fn plan_tx(&self, at_time: Date, bundle: &Bundle) -> Option<TxPlan> {
+        let tx_data = self.manager.dry_run_tx(&self.info, at_time, bundle)?;
+        Some(TxPlan::new(self.id, at_time, bundle.clone(), tx_data))
+    }
+
+    /// Books a transmission planned by `plan_tx`.
+    ///
+    /// # Parameters
+    ///
+    /// * `plan` - The plan, consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ContactManagerTxData, String>` - The transmission booked, or an error message if
+    ///   the plan is for another contact or the contact can no longer carry the bundle (e.g.
+    ///   another booking since the plan).
+    pub fn commit_tx(&mut self, plan: TxPlan) -> Result<ContactManagerTxData, String> {
+        let (contact, at_time, bundle) = plan.into_parts();
+        if contact != self.id {
+            return Err(format!(
+                "Transmission planned on contact {}, committed on contact {}",
+                contact, self.id
+            ));
+        }
+        self.manager
+            .schedule_tx(&self.info, at_time, &bundle)
+            .ok_or_else(|| {
+                format!(
+                    "Contact {} {}->{} [{}, {}] can no longer carry the planned transmission",
+                    self.id, self.info.tx_node, self.info.rx_node, self.info.start, self.info.end
+                )
+            })
+    }
 }
 
+/// The contacts are ordered by transmitting node, receiving node, start time, then end time: the
+/// contacts of a link are sorted by start time, the overlapping contacts with the same start
+/// ending first.
 impl<NM: NodeManager, CM: ContactManager> Ord for Contact<NM, CM> {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.info.tx_node > other.info.tx_node {
@@ -148,6 +260,12 @@ impl<NM: NodeManager, CM: ContactManager> Ord for Contact<NM, CM> {
         if self.info.start < other.info.start {
             return Ordering::Less;
         }
+        if self.info.end > other.info.end {
+            return Ordering::Greater;
+        }
+        if self.info.end < other.info.end {
+            return Ordering::Less;
+        }
         Ordering::Equal
     }
 }
@@ -160,9 +278,8 @@ impl<NM: NodeManager, CM: ContactManager> PartialOrd for Contact<NM, CM> {
 
 impl<NM: NodeManager, CM: ContactManager> PartialEq for Contact<NM, CM> {
     fn eq(&self, other: &Self) -> bool {
-        self.info.tx_node == other.info.tx_node
-            && self.info.rx_node == other.info.rx_node
-            && self.info.start < other.info.start
+        // consistent with `Ord`
+        self.cmp(other) == Ordering::Equal
     }
 }
 impl<NM: NodeManager, CM: ContactManager> Eq for Contact<NM, CM> {}
@@ -207,7 +324,7 @@ impl Parser<ContactInfo> for ContactInfo {
             }
         }
 
-        let start_state = Date::parse(lexer);
+        let start_state = parse_date(lexer);
         match start_state {
             ParsingState::Finished(value) => start = value,
             ParsingState::Error(msg) => return ParsingState::Error(msg),
@@ -219,7 +336,7 @@ impl Parser<ContactInfo> for ContactInfo {
             }
         }
 
-        let end_state = Date::parse(lexer);
+        let end_state = parse_date(lexer);
         match end_state {
             ParsingState::Finished(value) => end = value,
             ParsingState::Error(msg) => return ParsingState::Error(msg),
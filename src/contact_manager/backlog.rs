@@ -0,0 +1,262 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact::{Contact, ContactInfo},
+    node_manager::NodeManager,
+    types::{duration_from_f64, DataRate, Date, Duration, HashMap, NodeID, Volume, RATE_ZERO},
+};
+
+use super::{ContactManager, ContactManagerTxData};
+
+/// The earliest transmission opportunities (ETO) toward the neighbors of the nodes, shared by the
+/// contacts wrapped in `BacklogAware` managers.
+///
+/// The ETO of a (transmitting node, receiving node) pair is the time at which the backlog queued
+/// toward the neighbor is expected to be drained. It is supplied by the application, e.g. from the
+/// local queues or from backlog reports of the neighbors, and/or learned from the transmissions
+/// scheduled through the wrapped contacts.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Default)]
+pub struct NeighborBacklog {
+    /// The ETO of each (transmitting node, receiving node) pair with a backlog.
+    etos: HashMap<(NodeID, NodeID), Date>,
+    /// Whether the scheduled transmissions push the ETO of their pair.
+    learning: bool,
+}
+
+impl NeighborBacklog {
+    /// Creates an empty backlog table.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning` - Whether the transmissions scheduled through the wrapped contacts push the ETO
+    ///   of their pair to their end (use it with managers that do not model the queueing delay).
+    ///
+    /// # Returns
+    ///
+    /// * `Rc<RefCell<Self>>` - A shared pointer to the table, to provide to the `BacklogAware` managers.
+    pub fn new(learning: bool) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            etos: HashMap::new(),
+            learning,
+        }))
+    }
+
+    /// Sets the ETO toward a neighbor.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx_node` - The node holding the backlog.
+    /// * `rx_node` - The neighbor the backlog is queued for.
+    /// * `eto` - The time at which the backlog is expected to be drained.
+    pub fn set_eto(&mut self, tx_node: NodeID, rx_node: NodeID, eto: Date) {
+        self.etos.insert((tx_node, rx_node), eto);
+    }
+
+    /// Sets the ETO toward a neighbor from a backlog volume and its drain rate.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx_node` - The node holding the backlog.
+    /// * `rx_node` - The neighbor the backlog is queued for.
+    /// * `volume` - The volume queued toward the neighbor.
+    /// * `rate` - The rate at which the backlog is drained, must be positive.
+    /// * `curr_time` - The current time.
+    pub fn set_backlog(
+        &mut self,
+        tx_node: NodeID,
+        rx_node: NodeID,
+        volume: Volume,
+        rate: DataRate,
+        curr_time: Date,
+    ) {
+        if rate > RATE_ZERO {
+            self.set_eto(
+                tx_node,
+                rx_node,
+                curr_time + duration_from_f64(volume / rate),
+            );
+        }
+    }
+
+    /// Removes the backlog toward a neighbor.
+    pub fn clear(&mut self, tx_node: NodeID, rx_node: NodeID) {
+        self.etos.remove(&(tx_node, rx_node));
+    }
+
+    /// Retrieves the ETO toward a neighbor, `None` if no backlog is known.
+    pub fn get_eto(&self, tx_node: NodeID, rx_node: NodeID) -> Option<Date> {
+        self.etos.get(&(tx_node, rx_node)).copied()
+    }
+
+    /// Applies the ETO of a pair to a transmission time.
+    fn earliest_tx(&self, contact_data: &ContactInfo, at_time: Date) -> Date {
+        match self.get_eto(contact_data.tx_node, contact_data.rx_node) {
+            Some(eto) if eto > at_time => eto,
+            _ => at_time,
+        }
+    }
+}
+
+/// A contact manager wrapper delaying the transmissions to the ETO of the neighbor, for
+/// queueing-delay-aware arrival estimations on the whole path.
+///
+/// Without the wrapper, the backlog is only known by the managers of the contacts of the local
+/// node. With it, each hop of the path starts its transmission after the backlog toward its
+/// receiving node (see `NeighborBacklog`), during the pathfinding, the dry runs and the scheduling.
+///
+/// # Type Parameters
+///
+/// * `CM` - The wrapped contact manager.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct BacklogAware<CM: ContactManager> {
+    /// The wrapped contact manager.
+    pub inner: CM,
+    /// The backlog table shared by the wrapped contacts.
+    backlog: Rc<RefCell<NeighborBacklog>>,
+}
+
+impl<CM: ContactManager> BacklogAware<CM> {
+    /// Wraps a contact manager.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The contact manager to wrap.
+    /// * `backlog` - The backlog table shared by the wrapped contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `BacklogAware`.
+    pub fn new(inner: CM, backlog: Rc<RefCell<NeighborBacklog>>) -> Self {
+        Self { inner, backlog }
+    }
+}
+
+/// Wraps the managers of parsed contacts (before the creation of the multigraph).
+///
+/// # Parameters
+///
+/// * `contacts` - The contacts of the contact plan.
+/// * `backlog` - The backlog table shared by the wrapped contacts.
+///
+/// # Returns
+///
+/// The contacts with `BacklogAware` managers.
+pub fn wrap_contacts<NM: NodeManager, CM: ContactManager>(
+    contacts: Vec<Contact<NM, CM>>,
+    backlog: &Rc<RefCell<NeighborBacklog>>,
+) -> Vec<Contact<NM, BacklogAware<CM>>> {
+    contacts
+        .into_iter()
+        .filter_map(|contact| {
+            Contact::try_new(
+                contact.info,
+                BacklogAware::new(contact.manager, backlog.clone()),
+            )
+        })
+        .collect()
+}
+
+impl<CM: ContactManager> ContactManager for BacklogAware<CM> {
+    /// Simulates the transmission after the ETO of the receiving node.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let at_time = self.backlog.borrow().earliest_tx(contact_data, at_time);
+        self.inner.dry_run_tx(contact_data, at_time, bundle)
+    }
+
+    /// Schedules the transmission after the ETO of the receiving node, and pushes the ETO to the
+    /// end of the transmission if the table is learning.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let at_time = self.backlog.borrow().earliest_tx(contact_data, at_time);
+        let tx_data = self.inner.schedule_tx(contact_data, at_time, bundle)?;
+        let mut backlog = self.backlog.borrow_mut();
+        if backlog.learning {
+            backlog.set_eto(contact_data.tx_node, contact_data.rx_node, tx_data.tx_end);
+        }
+        Some(tx_data)
+    }
+
+    #[cfg(feature = "first_depleted")]
+    fn get_original_volume(&self) -> Volume {
+        self.inner.get_original_volume()
+    }
+
+    #[cfg(feature = "manual_queueing")]
+    fn manual_enqueue(&mut self, bundle: &Bundle) -> bool {
+        self.inner.manual_enqueue(bundle)
+    }
+
+    #[cfg(feature = "manual_queueing")]
+    fn manual_dequeue(&mut self, bundle: &Bundle) -> bool {
+        self.inner.manual_dequeue(bundle)
+    }
+
+    /// Releases the booking of the wrapped manager, the ETO of the pair is not rolled back.
+    fn release_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        self.inner.release_tx(contact_data, tx_data, bundle)
+    }
+
+    /// Imports the transmission into the wrapped manager, the ETO of the pair is not updated.
+    fn import_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        self.inner.import_tx(contact_data, tx_data, bundle)
+    }
+
+    /// Wraps the repetition of the wrapped manager, with the same backlog table.
+    fn repeat(&self, offset: Duration) -> Option<Self> {
+        self.inner
+            .repeat(offset)
+            .map(|inner| Self::new(inner, self.backlog.clone()))
+    }
+
+    /// Wraps the merge of the wrapped managers, if they share the backlog table.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        if !Rc::ptr_eq(&self.backlog, &next.backlog) {
+            return None;
+        }
+        self.inner
+            .merge(&next.inner)
+            .map(|inner| Self::new(inner, self.backlog.clone()))
+    }
+
+    fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+        self.inner.advance_time(contact_data, now)
+    }
+
+    fn get_residual_volume(&self, contact_data: &ContactInfo, bundle: &Bundle) -> Option<Volume> {
+        self.inner.get_residual_volume(contact_data, bundle)
+    }
+
+    fn get_booked_volume(&self, contact_data: &ContactInfo) -> Option<Volume> {
+        self.inner.get_booked_volume(contact_data)
+    }
+
+    fn get_confidence(&self) -> f32 {
+        self.inner.get_confidence()
+    }
+
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        self.inner.try_init(contact_data)
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    parsing::{DispatchParser, Lexer, Parser, ParsingState},
+    types::{
+        duration_from_f64, ends_by, parse_required, settle_volume, time_to_f64, DataRate, Date,
+        Duration, Volume, RATE_ZERO, VOLUME_EPSILON, VOLUME_ZERO,
+    },
+};
+
+/// The count of priority levels of the queues, the higher priorities are counted in the highest
+/// level (like `PETOManager`).
+const LEVELS: usize = 3;
+
+/// The order in which a transmission queue is drained.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueDiscipline {
+    /// First in, first out: a bundle waits for the whole queue.
+    #[default]
+    Fifo,
+    /// Strict priority: a bundle waits for the bundles of its priority or higher only.
+    StrictPriority,
+    /// Weighted fair queuing: the priority levels share the link in proportion to their weights
+    /// (from the lowest priority), modeled as generalized processor sharing.
+    WeightedFair([u32; LEVELS]),
+}
+
+impl QueueDiscipline {
+    /// Computes the volume transmitted before a bundle, i.e. its queueing delay at the link rate.
+    ///
+    /// # Parameters
+    ///
+    /// * `queue` - The volume queued for each priority level.
+    /// * `level` - The priority level of the bundle, queued after the volume of its level.
+    ///
+    /// # Returns
+    ///
+    /// * `Volume` - The volume transmitted before the bundle.
+    pub fn volume_ahead(&self, queue: &[Volume; LEVELS], level: usize) -> Volume {
+        match self {
+            Self::Fifo => queue.iter().sum(),
+            Self::StrictPriority => queue[level..].iter().sum(),
+            // while both levels are backlogged, their services are in the ratio of their weights,
+            // a level emptied first was entirely served
+            Self::WeightedFair(weights) => {
+                let own = queue[level];
+                let weight = weights[level] as f64;
+                let mut ahead = own;
+                for (other, volume) in queue.iter().enumerate() {
+                    if other != level {
+                        ahead += Volume::min(*volume, own * weights[other] as f64 / weight);
+                    }
+                }
+                ahead
+            }
+        }
+    }
+
+    /// Drains a volume from a queue, in the order of the discipline.
+    ///
+    /// # Parameters
+    ///
+    /// * `queue` - The volume queued for each priority level.
+    /// * `volume` - The volume transmitted.
+    pub fn drain(&self, queue: &mut [Volume; LEVELS], volume: Volume) {
+        let mut volume = volume;
+        match self {
+            // the arrival order is not tracked, the levels are drained in proportion to their
+            // volumes
+            Self::Fifo => {
+                let total: Volume = queue.iter().sum();
+                if total <= volume {
+                    *queue = [VOLUME_ZERO; LEVELS];
+                    return;
+                }
+                for queued in queue.iter_mut() {
+                    *queued = settle_volume(*queued - volume * (*queued / total));
+                }
+            }
+            Self::StrictPriority => {
+                for queued in queue.iter_mut().rev() {
+                    let served = Volume::min(*queued, volume);
+                    *queued = settle_volume(*queued - served);
+                    volume -= served;
+                }
+            }
+            Self::WeightedFair(weights) => {
+                // the backlogged levels share the volume until one of them empties
+                while volume > VOLUME_EPSILON {
+                    let backlogged = || (0..LEVELS).filter(|level| queue[*level] > VOLUME_ZERO);
+                    let total_weight: f64 = backlogged().map(|level| weights[level] as f64).sum();
+                    if total_weight <= 0.0 {
+                        break;
+                    }
+                    let to_first_empty = backlogged()
+                        .map(|level| queue[level] / weights[level] as f64)
+                        .fold(Volume::INFINITY, Volume::min);
+                    let share = Volume::min(to_first_empty, volume / total_weight);
+                    for level in 0..LEVELS {
+                        if queue[level] > VOLUME_ZERO {
+                            queue[level] =
+                                settle_volume(queue[level] - share * weights[level] as f64);
+                        }
+                    }
+                    volume -= share * total_weight;
+                }
+            }
+        }
+    }
+}
+
+/// An ETO manager draining its transmission queue with a `QueueDiscipline`.
+///
+/// Like `ETOManager`, the queue is the actual queue of the local node (updated with
+/// `manual_enqueue`/`manual_dequeue` or `set_observed_queue`, the scheduled transmissions are not
+/// queued) and the transmissions start after the queueing delay from the current time. The
+/// queueing delay depends on the discipline: with `StrictPriority` or `WeightedFair`, a
+/// high-priority bundle does not wait for the whole low-priority backlog. The transmission of the
+/// bundle itself lasts `bundle.size / rate` once its turn has come.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct DisciplinedETOManager {
+    /// The data transmission rate.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The queue discipline.
+    discipline: QueueDiscipline,
+    /// The volume queued for each priority level.
+    queue: [Volume; LEVELS],
+    /// The total volume at initialization.
+    original_volume: Volume,
+    /// The time until which the queue was drained (see `advance_time`).
+    drained_until: Date,
+}
+
+impl DisciplinedETOManager {
+    /// Creates a new `DisciplinedETOManager`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The average data rate for this contact.
+    /// * `delay` - The link delay for this contact.
+    /// * `discipline` - The queue discipline.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `DisciplinedETOManager`.
+    pub fn new(rate: DataRate, delay: Duration, discipline: QueueDiscipline) -> Self {
+        Self {
+            rate,
+            delay,
+            discipline,
+            queue: [VOLUME_ZERO; LEVELS],
+            original_volume: VOLUME_ZERO,
+            drained_until: Date::MIN,
+        }
+    }
+
+    /// Retrieves the queue discipline.
+    pub fn get_discipline(&self) -> QueueDiscipline {
+        self.discipline
+    }
+
+    /// The priority level of a bundle.
+    fn level(bundle: &Bundle) -> usize {
+        usize::min(bundle.priority as usize, LEVELS - 1)
+    }
+}
+
+impl ContactManager for DisciplinedETOManager {
+    /// Simulates the transmission of a bundle, after the volume transmitted before it according
+    /// to the discipline.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let queued: Volume = self.queue.iter().sum();
+        if !contact_data.semantics.accepts(
+            bundle.size,
+            self.original_volume - queued,
+            self.original_volume,
+        ) {
+            return None;
+        }
+
+        // the queue was drained until drained_until (see advance_time)
+        let contact_start = Date::max(contact_data.start, self.drained_until);
+        let ahead = self
+            .discipline
+            .volume_ahead(&self.queue, Self::level(bundle));
+        let tx_start = Date::max(contact_start, at_time) + duration_from_f64(ahead / self.rate);
+        let tx_end = tx_start + duration_from_f64(bundle.size / self.rate);
+        if !ends_by(tx_end, contact_data.end) {
+            return None;
+        }
+        Some(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            delay: self.delay,
+            expiration: contact_data.end,
+            arrival: self.delay + tx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle, the queue is not updated (see `manual_enqueue`).
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx(contact_data, at_time, bundle)
+    }
+
+    /// Adds the bundle to the queue of its priority level.
+    #[cfg(feature = "manual_queueing")]
+    fn manual_enqueue(&mut self, bundle: &Bundle) -> bool {
+        self.queue[Self::level(bundle)] += bundle.size;
+        true
+    }
+
+    /// Removes the bundle from the queue of its priority level.
+    #[cfg(feature = "manual_queueing")]
+    fn manual_dequeue(&mut self, bundle: &Bundle) -> bool {
+        let level = Self::level(bundle);
+        self.queue[level] = settle_volume(self.queue[level] - bundle.size);
+        true
+    }
+
+    /// The scheduled transmissions are not queued, nothing to release.
+    fn release_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        _bundle: &Bundle,
+    ) -> bool {
+        true
+    }
+
+    /// Imports a committed transmission, the bundle is added to the queue of its priority level.
+    fn import_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        self.queue[Self::level(bundle)] += bundle.size;
+        true
+    }
+
+    /// Creates a fresh manager with the same rate, delay and discipline.
+    fn repeat(&self, _offset: Duration) -> Option<Self> {
+        Some(Self::new(self.rate, self.delay, self.discipline))
+    }
+
+    /// Creates a fresh manager if both managers have the same rate, delay and discipline.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        (self.rate == next.rate && self.delay == next.delay && self.discipline == next.discipline)
+            .then(|| Self::new(self.rate, self.delay, self.discipline))
+    }
+
+    /// Drains the queue at the contact rate from the previous call, in the order of the
+    /// discipline.
+    fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+        if now <= self.drained_until {
+            return;
+        }
+        let from = Date::max(self.drained_until, contact_data.start);
+        let to = Date::min(now, contact_data.end);
+        if from < to {
+            self.discipline
+                .drain(&mut self.queue, time_to_f64(to - from) * self.rate);
+        }
+        self.drained_until = now;
+    }
+
+    /// Replaces the queue by the observed one, the levels above the priority count are counted in
+    /// the highest level.
+    fn set_observed_queue(
+        &mut self,
+        _contact_data: &ContactInfo,
+        volume_per_priority: &[Volume],
+        observed_at: Date,
+    ) -> bool {
+        self.queue = [VOLUME_ZERO; LEVELS];
+        for (level, volume) in volume_per_priority.iter().enumerate() {
+            self.queue[usize::min(level, LEVELS - 1)] += *volume;
+        }
+        self.drained_until = observed_at;
+        true
+    }
+
+    /// Returns the volume of the contact minus the volume queued.
+    fn get_residual_volume(&self, _contact_data: &ContactInfo, _bundle: &Bundle) -> Option<Volume> {
+        Some(self.original_volume - self.queue.iter().sum::<Volume>())
+    }
+
+    /// Returns the volume queued, all priorities included.
+    fn get_booked_volume(&self, _contact_data: &ContactInfo) -> Option<Volume> {
+        Some(self.queue.iter().sum())
+    }
+
+    /// Initializes the volume of the contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the rate is not positive, or if a weight of a weighted fair queuing is
+    /// zero (the level would never be served).
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        self.original_volume = contact_data
+            .semantics
+            .round_volume(time_to_f64(contact_data.end - contact_data.start) * self.rate);
+        if let QueueDiscipline::WeightedFair(weights) = self.discipline {
+            if weights.contains(&0) {
+                return false;
+            }
+        }
+        self.rate > RATE_ZERO
+    }
+
+    /// Returns the original volume of the object.
+    #[cfg(feature = "first_depleted")]
+    fn get_original_volume(&self) -> Volume {
+        self.original_volume
+    }
+}
+
+impl DispatchParser<DisciplinedETOManager> for DisciplinedETOManager {}
+
+impl Parser<DisciplinedETOManager> for DisciplinedETOManager {
+    /// Parses a `DisciplinedETOManager` from the lexer: the rate, the delay, then the discipline
+    /// (FIFO if omitted).
+    ///
+    /// ```text
+    /// contact <from> <to> <start> <end> [marker] <rate> <delay> [fifo|strict|wfq <w_0> <w_1> <w_2>]
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `lexer` - The lexer used for parsing tokens.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ParsingState` indicating whether parsing was successful (`Finished`) or encountered an error (`Error`).
+    fn parse(lexer: &mut dyn Lexer) -> ParsingState<DisciplinedETOManager> {
+        let rate = match parse_required::<DataRate>(lexer) {
+            Ok(rate) => rate,
+            Err(msg) => return ParsingState::Error(msg),
+        };
+        let delay = match parse_required::<Duration>(lexer) {
+            Ok(delay) => delay,
+            Err(msg) => return ParsingState::Error(msg),
+        };
+
+        let discipline = match lexer.lookup() {
+            ParsingState::Error(msg) => return ParsingState::Error(msg),
+            ParsingState::Finished(token) if token == "fifo" => {
+                lexer.consume_next_token();
+                QueueDiscipline::Fifo
+            }
+            ParsingState::Finished(token) if token == "strict" => {
+                lexer.consume_next_token();
+                QueueDiscipline::StrictPriority
+            }
+            ParsingState::Finished(token) if token == "wfq" => {
+                lexer.consume_next_token();
+                let mut weights = [0; LEVELS];
+                for weight in &mut weights {
+                    *weight = match parse_required::<u32>(lexer) {
+                        Ok(value) => value,
+                        Err(msg) => return ParsingState::Error(msg),
+                    };
+                }
+                QueueDiscipline::WeightedFair(weights)
+            }
+            _ => QueueDiscipline::Fifo,
+        };
+        ParsingState::Finished(DisciplinedETOManager::new(rate, delay, discipline))
+    }
+}
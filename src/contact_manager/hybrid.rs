@@ -0,0 +1,222 @@
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    types::{
+        duration_from_f64, ends_by, settle_volume, time_to_f64, DataRate, Date, Duration, Volume,
+        RATE_ZERO, VOLUME_ZERO,
+    },
+};
+
+/// A contact manager for the nodes whose bottleneck is the processing rather than the link.
+///
+/// The volume booked is tracked like with `QDManager` (the residual volume is the volume of the
+/// contact at the link rate minus the booked volume), but the booked volume is drained from the
+/// queue at the service rate: the earliest transmission opportunity is delayed by
+/// `queue_size / service_rate` from the contact start, while the transmission itself lasts
+/// `bundle.size / rate`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct HybridManager {
+    /// The data transmission rate of the link.
+    rate: DataRate,
+    /// The rate at which the queue is drained (e.g. the processing rate of the node).
+    service_rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The volume scheduled for this contact, i.e. the queue size.
+    queue_size: Volume,
+    /// The total volume at initialization.
+    original_volume: Volume,
+    /// The time until which the queue was drained (see `advance_time`).
+    drained_until: Date,
+}
+
+impl HybridManager {
+    /// Creates a new `HybridManager` with specified link rate, service rate and delay.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The average data rate of the link.
+    /// * `service_rate` - The rate at which the queue is drained.
+    /// * `delay` - The link delay for this contact.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `HybridManager`.
+    pub fn new(rate: DataRate, service_rate: DataRate, delay: Duration) -> Self {
+        Self {
+            rate,
+            service_rate,
+            delay,
+            queue_size: VOLUME_ZERO,
+            original_volume: VOLUME_ZERO,
+            drained_until: Date::MIN,
+        }
+    }
+}
+
+impl ContactManager for HybridManager {
+    /// Simulates the transmission of a bundle, after the drain of the queue at the service rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        if !contact_data.semantics.accepts(
+            bundle.size,
+            self.original_volume - self.queue_size,
+            self.original_volume,
+        ) {
+            return None;
+        }
+
+        // the queue was drained until drained_until (see advance_time)
+        let contact_start = Date::max(contact_data.start, self.drained_until)
+            + duration_from_f64(self.queue_size / self.service_rate);
+        let tx_start = Date::max(contact_start, at_time);
+        let tx_end = tx_start + duration_from_f64(bundle.size / self.rate);
+        if !ends_by(tx_end, contact_data.end) {
+            return None;
+        }
+        Some(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            delay: self.delay,
+            expiration: contact_data.end,
+            arrival: self.delay + tx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle, the bundle is added to the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let data = self.dry_run_tx(contact_data, at_time, bundle)?;
+        self.queue_size += bundle.size;
+        Some(data)
+    }
+
+    /// Releases a transmission booked by `schedule_tx`, the volume is removed from the queue.
+    fn release_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        self.queue_size = settle_volume(self.queue_size - bundle.size);
+        true
+    }
+
+    /// Imports a committed transmission, its volume is added to the queue.
+    fn import_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        self.queue_size += bundle.size;
+        true
+    }
+
+    /// Creates a fresh manager with the same rates and delay.
+    fn repeat(&self, _offset: Duration) -> Option<Self> {
+        Some(Self::new(self.rate, self.service_rate, self.delay))
+    }
+
+    /// Creates a fresh manager if both managers have the same rates and delay.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        (self.rate == next.rate
+            && self.service_rate == next.service_rate
+            && self.delay == next.delay)
+            .then(|| Self::new(self.rate, self.service_rate, self.delay))
+    }
+
+    /// Drains the queue at the service rate from the previous call.
+    fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+        if now <= self.drained_until {
+            return;
+        }
+        let from = Date::max(self.drained_until, contact_data.start);
+        let to = Date::min(now, contact_data.end);
+        if from < to {
+            let drained = time_to_f64(to - from) * self.service_rate;
+            self.queue_size = settle_volume(self.queue_size - drained);
+        }
+        self.drained_until = now;
+    }
+
+    /// Replaces the queue by the observed one, all priorities included, the queue drains at the
+    /// service rate from the observation.
+    fn set_observed_queue(
+        &mut self,
+        _contact_data: &ContactInfo,
+        volume_per_priority: &[Volume],
+        observed_at: Date,
+    ) -> bool {
+        self.queue_size = volume_per_priority.iter().sum();
+        self.drained_until = observed_at;
+        true
+    }
+
+    /// Returns the volume of the contact minus the volume already queued.
+    fn get_residual_volume(&self, _contact_data: &ContactInfo, _bundle: &Bundle) -> Option<Volume> {
+        Some(self.original_volume - self.queue_size)
+    }
+
+    /// Returns the volume queued.
+    fn get_booked_volume(&self, _contact_data: &ContactInfo) -> Option<Volume> {
+        Some(self.queue_size)
+    }
+
+    /// Initializes the volume of the contact at the link rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the service rate is not positive, the queue would never be drained.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        self.original_volume = contact_data
+            .semantics
+            .round_volume(time_to_f64(contact_data.end - contact_data.start) * self.rate);
+        self.service_rate > RATE_ZERO
+    }
+
+    /// Returns the original volume of the object.
+    #[cfg(feature = "first_depleted")]
+    fn get_original_volume(&self) -> Volume {
+        self.original_volume
+    }
+}
+
+// contact <from> <to> <start> <end> [marker] <rate> <service_rate> <delay>
+crate::contact_manager_parser!(
+    HybridManager,
+    new(rate: DataRate, service_rate: DataRate, delay: Duration)
+);
@@ -28,6 +28,8 @@ macro_rules! generate_struct_management {
             queue_size: crate::types::Volume,
             /// The total volume at initialization.
             original_volume: crate::types::Volume,
+            /// The time until which the queue was drained (see `advance_time`).
+            drained_until: crate::types::Date,
         }
         impl $manager_name {
             #[doc = concat!( "Creates a new `", stringify!($manager_name),"`  with specified average rate and delay.")]
@@ -44,14 +46,19 @@ macro_rules! generate_struct_management {
                 Self {
                     rate,
                     delay,
-                    queue_size: 0.0,
-                    original_volume: 0.0,
+                    queue_size: crate::types::VOLUME_ZERO,
+                    original_volume: crate::types::VOLUME_ZERO,
+                    drained_until: crate::types::Date::MIN,
                 }
             }
             #[inline(always)]
             fn get_queue_size(&self, _bundle: &crate::bundle::Bundle) -> crate::types::Volume {
                     self.queue_size
             }
+            #[inline(always)]
+            fn get_total_queue_size(&self) -> crate::types::Volume {
+                    self.queue_size
+            }
            #[inline(always)]
             fn enqueue(&mut self, bundle: &crate::bundle::Bundle)  {
                  self.queue_size += bundle.size;
@@ -59,12 +66,31 @@ macro_rules! generate_struct_management {
             #[allow(dead_code)]
             #[inline(always)]
             fn dequeue(&mut self, bundle: &crate::bundle::Bundle)  {
-                self.queue_size -= bundle.size;
+                self.queue_size = crate::types::settle_volume(self.queue_size - bundle.size);
+            }
+            #[allow(dead_code)]
+            #[inline(always)]
+            fn drain(&mut self, volume: crate::types::Volume)  {
+                self.queue_size = crate::types::settle_volume(self.queue_size - volume);
+            }
+            #[inline(always)]
+            fn set_queue(&mut self, volume_per_priority: &[crate::types::Volume])  {
+                self.queue_size = volume_per_priority.iter().sum();
             }
             #[inline(always)]
             fn get_budget(&self, _bundle: &crate::bundle::Bundle) -> crate::types::Volume  {
                return self.original_volume;
             }
+            // a fresh manager with the same parameters, see `repeat`
+            #[inline(always)]
+            fn replicate(&self) -> Self {
+                Self::new(self.rate, self.delay)
+            }
+            // whether the managers have the same parameters, see `merge`
+            #[inline(always)]
+            fn same_parameters(&self, other: &Self) -> bool {
+                self.rate == other.rate && self.delay == other.delay
+            }
             #[inline(always)]
             fn build_parsing_output(rate: crate::types::DataRate, delay: crate::types::Duration, _lexer: &mut dyn crate::parsing::Lexer) -> crate::parsing::ParsingState<Self>{
                 return crate::parsing::ParsingState::Finished($manager_name::new(rate, delay));
@@ -83,6 +109,8 @@ macro_rules! generate_struct_management {
             queue_size: [crate::types::Volume; $prio_count],
             /// The total volume at initialization.
             original_volume: crate::types::Volume,
+            /// The time until which the queue was drained (see `advance_time`).
+            drained_until: crate::types::Date,
         }
 
         impl $manager_name {
@@ -100,8 +128,9 @@ macro_rules! generate_struct_management {
                 Self {
                     rate,
                     delay,
-                    queue_size: [0.0; $prio_count],
-                    original_volume: 0.0,
+                    queue_size: [crate::types::VOLUME_ZERO; $prio_count],
+                    original_volume: crate::types::VOLUME_ZERO,
+                    drained_until: crate::types::Date::MIN,
                 }
             }
 
@@ -109,6 +138,11 @@ macro_rules! generate_struct_management {
             fn get_queue_size(&self, bundle: &crate::bundle::Bundle) -> crate::types::Volume {
                     self.queue_size[bundle.priority as usize]
             }
+            // queue_size[0] is the volume of priority 0 or higher
+            #[inline(always)]
+            fn get_total_queue_size(&self) -> crate::types::Volume {
+                    self.queue_size[0]
+            }
             #[inline(always)]
             fn enqueue(&mut self, bundle: &crate::bundle::Bundle)  {
                 for prio in 0..bundle.priority as usize + 1 {
@@ -119,13 +153,43 @@ macro_rules! generate_struct_management {
             #[inline(always)]
             fn dequeue(&mut self, bundle: &crate::bundle::Bundle)  {
                 for prio in 0..bundle.priority as usize + 1 {
-                    self.queue_size[prio] -= bundle.size;
+                    self.queue_size[prio] = crate::types::settle_volume(self.queue_size[prio] - bundle.size);
+                }
+            }
+            // the highest priorities are drained first, queue_size[prio] being the volume of
+            // priority prio or higher
+            #[allow(dead_code)]
+            #[inline(always)]
+            fn drain(&mut self, volume: crate::types::Volume)  {
+                for queue_size in self.queue_size.iter_mut() {
+                    *queue_size = crate::types::settle_volume(*queue_size - volume);
+                }
+            }
+            // the levels above the priority count are counted in the highest priority
+            #[inline(always)]
+            fn set_queue(&mut self, volume_per_priority: &[crate::types::Volume])  {
+                self.queue_size = [crate::types::VOLUME_ZERO; $prio_count];
+                for (level, volume) in volume_per_priority.iter().enumerate() {
+                    let level = usize::min(level, $prio_count - 1);
+                    for prio in 0..level + 1 {
+                        self.queue_size[prio] += *volume;
+                    }
                 }
             }
             #[inline(always)]
             fn get_budget(&self, _bundle: &crate::bundle::Bundle) -> crate::types::Volume  {
                return self.original_volume;
             }
+            // a fresh manager with the same parameters, see `repeat`
+            #[inline(always)]
+            fn replicate(&self) -> Self {
+                Self::new(self.rate, self.delay)
+            }
+            // whether the managers have the same parameters, see `merge`
+            #[inline(always)]
+            fn same_parameters(&self, other: &Self) -> bool {
+                self.rate == other.rate && self.delay == other.delay
+            }
             #[inline(always)]
             fn build_parsing_output(rate: crate::types::DataRate, delay: crate::types::Duration, _lexer: &mut dyn crate::parsing::Lexer) -> crate::parsing::ParsingState<Self>{
                 return crate::parsing::ParsingState::Finished($manager_name::new(rate, delay));
@@ -147,6 +211,8 @@ macro_rules! generate_struct_management {
             budgets: [crate::types::Volume; $prio_count],
             /// The total volume at initialization.
             original_volume: crate::types::Volume,
+            /// The time until which the queue was drained (see `advance_time`).
+            drained_until: crate::types::Date,
         }
 
         impl $manager_name {
@@ -165,9 +231,10 @@ macro_rules! generate_struct_management {
                 Self {
                     rate,
                     delay,
-                    queue_size: [0.0; $prio_count],
+                    queue_size: [crate::types::VOLUME_ZERO; $prio_count],
                     budgets: budgets,
-                    original_volume: 0.0,
+                    original_volume: crate::types::VOLUME_ZERO,
+                    drained_until: crate::types::Date::MIN,
                 }
             }
 
@@ -175,6 +242,11 @@ macro_rules! generate_struct_management {
             fn get_queue_size(&self, bundle: &crate::bundle::Bundle) -> crate::types::Volume {
                     self.queue_size[bundle.priority as usize]
             }
+            // queue_size[0] is the volume of priority 0 or higher
+            #[inline(always)]
+            fn get_total_queue_size(&self) -> crate::types::Volume {
+                    self.queue_size[0]
+            }
             #[inline(always)]
             fn enqueue(&mut self, bundle: &crate::bundle::Bundle)  {
                 for prio in 0..bundle.priority as usize + 1 {
@@ -185,16 +257,46 @@ macro_rules! generate_struct_management {
             #[inline(always)]
             fn dequeue(&mut self, bundle: &crate::bundle::Bundle)  {
                 for prio in 0..bundle.priority as usize + 1 {
-                    self.queue_size[prio] -= bundle.size;
+                    self.queue_size[prio] = crate::types::settle_volume(self.queue_size[prio] - bundle.size);
+                }
+            }
+            // the highest priorities are drained first, queue_size[prio] being the volume of
+            // priority prio or higher
+            #[allow(dead_code)]
+            #[inline(always)]
+            fn drain(&mut self, volume: crate::types::Volume)  {
+                for queue_size in self.queue_size.iter_mut() {
+                    *queue_size = crate::types::settle_volume(*queue_size - volume);
+                }
+            }
+            // the levels above the priority count are counted in the highest priority
+            #[inline(always)]
+            fn set_queue(&mut self, volume_per_priority: &[crate::types::Volume])  {
+                self.queue_size = [crate::types::VOLUME_ZERO; $prio_count];
+                for (level, volume) in volume_per_priority.iter().enumerate() {
+                    let level = usize::min(level, $prio_count - 1);
+                    for prio in 0..level + 1 {
+                        self.queue_size[prio] += *volume;
+                    }
                 }
             }
             #[inline(always)]
             fn get_budget(&self, bundle: &crate::bundle::Bundle) -> crate::types::Volume  {
                return self.budgets[bundle.priority as usize];
             }
+            // a fresh manager with the same parameters, see `repeat`
+            #[inline(always)]
+            fn replicate(&self) -> Self {
+                Self::new(self.rate, self.delay, self.budgets)
+            }
+            // whether the managers have the same parameters, see `merge`
+            #[inline(always)]
+            fn same_parameters(&self, other: &Self) -> bool {
+                self.rate == other.rate && self.delay == other.delay && self.budgets == other.budgets
+            }
             #[inline(always)]
             fn build_parsing_output(rate: crate::types::DataRate, delay: crate::types::Duration, lexer: &mut dyn crate::parsing::Lexer) -> crate::parsing::ParsingState<Self>{
-                let mut budgets = [0.0; 3];
+                let mut budgets = [crate::types::VOLUME_ZERO; 3];
                 for i in 0..$prio_count {
 
                     let budget_state = <crate::types::Volume as crate::types::Token<crate::types::Volume>>::parse(lexer);
@@ -202,7 +304,7 @@ macro_rules! generate_struct_management {
                         crate::parsing::ParsingState::Finished(value) => budgets[i] = value,
                         crate::parsing::ParsingState::Error(msg) => return crate::parsing::ParsingState::Error(msg),
                         crate::parsing::ParsingState::EOF => {
-                            return crate::parsing::ParsingState::Error(format!(
+                            return crate::parsing::ParsingState::Error(alloc::format!(
                                 "Parsing failed ({})",
                                 lexer.get_current_position()
                             ))
@@ -284,14 +386,19 @@ macro_rules! generate_prio_volume_manager {
                 // This function call should be expanded at compile time
                 let queue_size = self.get_queue_size(&bundle);
 
-                if bundle.size > self.get_budget(&bundle) - queue_size {
+                let budget = self.get_budget(&bundle);
+                if !contact_data.semantics.accepts(bundle.size, budget - queue_size, budget) {
                     return None;
                 }
 
                 let mut contact_start = contact_data.start;
+                // the queue was drained until drained_until (see advance_time)
+                if ($add_delay && self.drained_until > contact_start) {
+                    contact_start = self.drained_until;
+                }
                 // add_delay case 1 : if not eto, we push the eto from the contact start time
                 if ($add_delay && $auto_update) {
-                    contact_start += queue_size / self.rate;
+                    contact_start += crate::types::duration_from_f64(queue_size / self.rate);
                 }
                 let mut tx_start = if (contact_start > at_time) {
                     contact_start
@@ -301,11 +408,11 @@ macro_rules! generate_prio_volume_manager {
 
                 // add_delay case 2 : eto, bundles are still in queue
                 if ($add_delay && !$auto_update) {
-                    tx_start += queue_size / self.rate;
+                    tx_start += crate::types::duration_from_f64(queue_size / self.rate);
                 }
 
-                let tx_end = tx_start + bundle.size / self.rate;
-                if tx_end > contact_data.end {
+                let tx_end = tx_start + crate::types::duration_from_f64(bundle.size / self.rate);
+                if !crate::types::ends_by(tx_end, contact_data.end) {
                     return None;
                 }
                 Some(crate::contact_manager::ContactManagerTxData {
@@ -348,6 +455,91 @@ macro_rules! generate_prio_volume_manager {
                 None
             }
 
+            /// Releases a transmission booked by `schedule_tx`, the volume is removed from the queue
+            /// if the manager updates it.
+            fn release_tx(
+                &mut self,
+                _contact_data: &crate::contact::ContactInfo,
+                _tx_data: &crate::contact_manager::ContactManagerTxData,
+                bundle: &crate::bundle::Bundle,
+            ) -> bool {
+                if $auto_update {
+                    self.dequeue(bundle);
+                }
+                true
+            }
+
+            /// Imports a committed transmission, its volume is added to the queue (the ETO
+            /// managers included, the transmission being in the queue of the node).
+            fn import_tx(
+                &mut self,
+                _contact_data: &crate::contact::ContactInfo,
+                _tx_data: &crate::contact_manager::ContactManagerTxData,
+                bundle: &crate::bundle::Bundle,
+            ) -> bool {
+                self.enqueue(bundle);
+                true
+            }
+
+            /// Creates a fresh manager with the same rate, delay and budgets, the manager holding no
+            /// dates.
+            fn repeat(&self, _offset: crate::types::Duration) -> Option<Self> {
+                Some(self.replicate())
+            }
+
+            /// Creates a fresh manager if both managers have the same rate, delay and budgets.
+            fn merge(&self, next: &Self) -> Option<Self> {
+                self.same_parameters(next).then(|| self.replicate())
+            }
+
+            /// Drains the queue at the contact rate from the previous call, if the manager models
+            /// the queueing delay (the volume booked by the other managers is not a queue).
+            fn advance_time(
+                &mut self,
+                contact_data: &crate::contact::ContactInfo,
+                now: crate::types::Date,
+            ) {
+                if !$add_delay || now <= self.drained_until {
+                    return;
+                }
+                let from = crate::types::Date::max(self.drained_until, contact_data.start);
+                let to = crate::types::Date::min(now, contact_data.end);
+                if from < to {
+                    self.drain(crate::types::time_to_f64(to - from) * self.rate);
+                }
+                self.drained_until = now;
+            }
+
+            /// Replaces the queue by the observed one, the queue drains from the observation if
+            /// the manager models the queueing delay.
+            fn set_observed_queue(
+                &mut self,
+                _contact_data: &crate::contact::ContactInfo,
+                volume_per_priority: &[crate::types::Volume],
+                observed_at: crate::types::Date,
+            ) -> bool {
+                self.set_queue(volume_per_priority);
+                self.drained_until = observed_at;
+                true
+            }
+
+            /// Returns the budget of the bundle minus the volume already queued.
+            fn get_residual_volume(
+                &self,
+                _contact_data: &crate::contact::ContactInfo,
+                bundle: &crate::bundle::Bundle,
+            ) -> Option<crate::types::Volume> {
+                Some(self.get_budget(bundle) - self.get_queue_size(bundle))
+            }
+
+            /// Returns the volume queued, all priorities included.
+            fn get_booked_volume(
+                &self,
+                _contact_data: &crate::contact::ContactInfo,
+            ) -> Option<crate::types::Volume> {
+                Some(self.get_total_queue_size())
+            }
+
             /// Initializes the segmentation manager by checking that rate and delay intervals have no gaps.
             ///
             /// # Arguments
@@ -358,7 +550,9 @@ macro_rules! generate_prio_volume_manager {
             ///
             /// Returns `true` if initialization is successful, or `false` if there are gaps in the intervals.
             fn try_init(&mut self, contact_data: &crate::contact::ContactInfo) -> bool {
-                self.original_volume = (contact_data.end - contact_data.start) * self.rate;
+                self.original_volume = contact_data.semantics.round_volume(
+                    crate::types::time_to_f64(contact_data.end - contact_data.start) * self.rate,
+                );
                 true
             }
 
@@ -400,7 +594,7 @@ macro_rules! generate_prio_volume_manager {
                     crate::parsing::ParsingState::Finished(value) => rate = value,
                     crate::parsing::ParsingState::Error(msg) => return crate::parsing::ParsingState::Error(msg),
                     crate::parsing::ParsingState::EOF => {
-                        return crate::parsing::ParsingState::Error(format!(
+                        return crate::parsing::ParsingState::Error(alloc::format!(
                             "Parsing failed ({})",
                             lexer.get_current_position()
                         ))
@@ -412,7 +606,7 @@ macro_rules! generate_prio_volume_manager {
                     crate::parsing::ParsingState::Finished(value) => delay = value,
                     crate::parsing::ParsingState::Error(msg) => return crate::parsing::ParsingState::Error(msg),
                     crate::parsing::ParsingState::EOF => {
-                        return crate::parsing::ParsingState::Error(format!(
+                        return crate::parsing::ParsingState::Error(alloc::format!(
                             "Parsing failed ({})",
                             lexer.get_current_position()
                         ))
@@ -1,15 +1,40 @@
-#[cfg(feature = "first_depleted")]
-use crate::types::Volume;
+//! # Implementing a contact manager
+//!
+//! A custom link model implements `ContactManager` and the parsing traits:
+//!
+//! * `dry_run_tx`, `schedule_tx` and `try_init` are required, the other methods have defaults,
+//!   whatever the compilation features (e.g. `get_original_volume` for "first_depleted",
+//!   `manual_enqueue`/`manual_dequeue` for "manual_queueing").
+//! * `Parser` and `DispatchParser` can be generated with `contact_manager_parser!` if the manager
+//!   is built from a sequence of tokens (e.g. `contact 0 1 60 7260 mymanager 10000 10`).
+//! * With the "debug" feature, the manager must implement `Debug`.
+//!
+//! # Booking a transmission
+//!
+//! `Contact::plan_tx` dry runs a transmission and returns a `TxPlan`, the only way to book it
+//! with `Contact::commit_tx`: the plan cannot be forged nor copied, and is consumed by the
+//! booking. The `dry_run_tx`/`schedule_tx` pair of the managers remains available.
+
+use alloc::boxed::Box;
+
 use crate::{
     bundle::Bundle,
     contact::ContactInfo,
-    types::{Date, Duration},
+    types::{ContactID, Date, Duration, Volume},
 };
 
+pub mod backlog;
+pub mod discipline;
+pub mod hybrid;
 pub mod legacy;
+pub mod partition;
+pub mod playback;
+pub mod predicted;
 pub mod seg;
 
 /// Data structure representing the transmission (tx) start, end, and related timing information.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
 pub struct ContactManagerTxData {
     /// The start time of the transmission.
     pub tx_start: Date,
@@ -23,6 +48,65 @@ pub struct ContactManagerTxData {
     pub arrival: Date,
 }
 
+/// A transmission validated by a dry run, to be booked with `Contact::commit_tx`.
+///
+/// A plan is only created by `Contact::plan_tx`, cannot be copied, and is consumed by the booking:
+/// booking without a successful dry run, or booking the same plan twice, does not compile.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[must_use = "nothing is booked until the plan is committed"]
+pub struct TxPlan {
+    /// The contact the transmission was planned on.
+    contact: ContactID,
+    /// The time the bundle is ready for the transmission.
+    at_time: Date,
+    /// The bundle to transmit.
+    bundle: Bundle,
+    /// The transmission computed by the dry run.
+    tx_data: ContactManagerTxData,
+}
+
+impl TxPlan {
+    /// Creates a plan from a successful dry run, see `Contact::plan_tx`.
+    pub(crate) fn new(
+        contact: ContactID,
+        at_time: Date,
+        bundle: Bundle,
+        tx_data: ContactManagerTxData,
+    ) -> Self {
+        Self {
+            contact,
+            at_time,
+            bundle,
+            tx_data,
+        }
+    }
+
+    /// Returns the ID of the contact the transmission was planned on.
+    pub fn contact(&self) -> ContactID {
+        self.contact
+    }
+
+    /// Returns the time the bundle is ready for the transmission.
+    pub fn at_time(&self) -> Date {
+        self.at_time
+    }
+
+    /// Returns the bundle to transmit.
+    pub fn bundle(&self) -> &Bundle {
+        &self.bundle
+    }
+
+    /// Returns the transmission computed by the dry run.
+    pub fn tx_data(&self) -> &ContactManagerTxData {
+        &self.tx_data
+    }
+
+    /// Splits the plan for the booking.
+    pub(crate) fn into_parts(self) -> (ContactID, Date, Bundle) {
+        (self.contact, self.at_time, self.bundle)
+    }
+}
+
 macro_rules! define_contact_manager {
     ($($bounds:tt)*) => {
         /// Trait for managing contact resources and scheduling data transmissions.
@@ -66,13 +150,16 @@ macro_rules! define_contact_manager {
                 bundle: &Bundle,
             ) -> Option<ContactManagerTxData>;
 
-            /// For first depleted compatibility. Required with "first_depleted" compilation feature.
+            /// For first depleted compatibility, used with "first_depleted" compilation feature.
             ///
             /// # Returns
             ///
-            /// Returns the maximum volume the contact had at initialization.
+            /// Returns the maximum volume the contact had at initialization. Infinite by default,
+            /// i.e. the contacts of the managers without volume are depleted last.
             #[cfg(feature = "first_depleted")]
-            fn get_original_volume(&self) -> Volume;
+            fn get_original_volume(&self) -> Volume {
+                Volume::INFINITY
+            }
 
             /// For ETO compatibility. Required with "manual_queueing" compilation feature.
             ///
@@ -102,6 +189,142 @@ macro_rules! define_contact_manager {
                 false
             }
 
+            /// Releases a transmission booked by `schedule_tx`, e.g. for a cancelled reservation.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `tx_data` - The transmission returned by `schedule_tx`.
+            /// * `bundle` - The bundle scheduled.
+            ///
+            /// # Returns
+            ///
+            /// true if the booking was released, false if the manager cannot release bookings.
+            fn release_tx(&mut self, _contact_data: &ContactInfo, _tx_data: &ContactManagerTxData, _bundle: &Bundle) -> bool {
+                false
+            }
+
+            /// Books a transmission committed outside of the manager (e.g. by a previous instance
+            /// of the router), the inverse of `release_tx`: the resources of the transmission are
+            /// consumed as if `schedule_tx` had returned `tx_data`.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `tx_data` - The committed transmission, its start and end times being used.
+            /// * `bundle` - The bundle transmitted, for its size and priority.
+            ///
+            /// # Returns
+            ///
+            /// true if the transmission was booked, false if its resources are not available or if
+            /// the manager cannot import transmissions, the default.
+            fn import_tx(&mut self, _contact_data: &ContactInfo, _tx_data: &ContactManagerTxData, _bundle: &Bundle) -> bool {
+                false
+            }
+
+            /// Creates the manager of a repetition of the contact (see `PeriodicContact`), from a
+            /// manager not yet initialized: the dates of the manager (e.g. its intervals) are
+            /// shifted by `offset`, the other parameters are copied. The dynamic managers
+            /// (`Box<dyn ContactManager>`) cannot be repeated.
+            ///
+            /// # Arguments
+            ///
+            /// * `offset` - The offset of the repetition from the contact.
+            ///
+            /// # Returns
+            ///
+            /// The manager of the repetition, or `None` if the manager cannot be repeated, the default.
+            fn repeat(&self, _offset: Duration) -> Option<Self>
+            where
+                Self: Sized,
+            {
+                None
+            }
+
+            /// Creates the manager of the contact merging this contact with the next contact of the
+            /// link (starting at its end), for the plan compression (see `compress_contacts`). The
+            /// managers are not initialized, the bookings are not merged.
+            ///
+            /// # Arguments
+            ///
+            /// * `next` - The manager of the next contact.
+            ///
+            /// # Returns
+            ///
+            /// The manager of the merged contact, or `None` if the managers differ (e.g. different
+            /// rates) or cannot be merged, the default.
+            fn merge(&self, _next: &Self) -> Option<Self>
+            where
+                Self: Sized,
+            {
+                None
+            }
+
+            /// Advances the notion of "now" of the manager: the queues modeled by the manager drain
+            /// at the contact rate as time passes, instead of accumulating.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `now` - The current time, the calls with an earlier time have no effect.
+            fn advance_time(&mut self, _contact_data: &ContactInfo, _now: Date) {}
+
+            /// Returns the volume that remains available to the bundle on the contact.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `bundle` - The bundle considered (e.g. for its priority).
+            ///
+            /// # Returns
+            ///
+            /// The residual volume, or `None` if the manager does not track volumes.
+            fn get_residual_volume(&self, _contact_data: &ContactInfo, _bundle: &Bundle) -> Option<Volume> {
+                None
+            }
+
+            /// Returns the volume booked on the contact by the scheduled transmissions, all
+            /// priorities included.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            ///
+            /// # Returns
+            ///
+            /// The booked volume, or `None` if the manager does not track volumes.
+            fn get_booked_volume(&self, _contact_data: &ContactInfo) -> Option<Volume> {
+                None
+            }
+
+            /// Returns the probability that the contact occurs as planned, 1.0 by default.
+            fn get_confidence(&self) -> f32 {
+                1.0
+            }
+
+            /// Replaces the queue of the contact by a queue observed outside of the manager (e.g.
+            /// the queue depths of a neighbor received by telemetry), for the queueing delays to
+            /// reflect the actual queue rather than the bookkeeping of the manager.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `volume_per_priority` - The volume queued for each priority level, from the
+            ///   lowest priority (the missing levels are empty).
+            /// * `observed_at` - The time of the observation, the queue drains from this time.
+            ///
+            /// # Returns
+            ///
+            /// Returns `false` if the manager does not model a queue, the default.
+            fn set_observed_queue(
+                &mut self,
+                _contact_data: &ContactInfo,
+                _volume_per_priority: &[Volume],
+                _observed_at: Date,
+            ) -> bool {
+                false
+            }
+
             /// Finalize the initialize of the contact and notify if the initailization is consistent.
             ///
             /// # Arguments
@@ -155,6 +378,51 @@ macro_rules! define_contact_manager {
             fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
                 (**self).try_init(contact_data)
             }
+
+            /// Delegates the release_tx method to the boxed object.
+            fn release_tx(&mut self, contact_data: &ContactInfo, tx_data: &ContactManagerTxData, bundle: &Bundle) -> bool {
+                (**self).release_tx(contact_data, tx_data, bundle)
+            }
+
+            /// Delegates the import_tx method to the boxed object.
+            fn import_tx(&mut self, contact_data: &ContactInfo, tx_data: &ContactManagerTxData, bundle: &Bundle) -> bool {
+                (**self).import_tx(contact_data, tx_data, bundle)
+            }
+
+            /// Delegates the repeat method to the boxed object.
+            fn repeat(&self, offset: Duration) -> Option<Self> {
+                (**self).repeat(offset).map(Box::new)
+            }
+
+            /// Delegates the merge method to the boxed object.
+            fn merge(&self, next: &Self) -> Option<Self> {
+                (**self).merge(next).map(Box::new)
+            }
+
+            /// Delegates the advance_time method to the boxed object.
+            fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+                (**self).advance_time(contact_data, now)
+            }
+
+            /// Delegates the get_residual_volume method to the boxed object.
+            fn get_residual_volume(&self, contact_data: &ContactInfo, bundle: &Bundle) -> Option<Volume> {
+                (**self).get_residual_volume(contact_data, bundle)
+            }
+
+            /// Delegates the get_booked_volume method to the boxed object.
+            fn get_booked_volume(&self, contact_data: &ContactInfo) -> Option<Volume> {
+                (**self).get_booked_volume(contact_data)
+            }
+
+            /// Delegates the get_confidence method to the boxed object.
+            fn get_confidence(&self) -> f32 {
+                (**self).get_confidence()
+            }
+
+            /// Delegates the set_observed_queue method to the boxed object.
+            fn set_observed_queue(&mut self, contact_data: &ContactInfo, volume_per_priority: &[Volume], observed_at: Date) -> bool {
+                (**self).set_observed_queue(contact_data, volume_per_priority, observed_at)
+            }
         }
 
         /// Implementation of `ContactManager` for boxed dynamic types (`Box<dyn ContactManager>`).
@@ -183,6 +451,41 @@ macro_rules! define_contact_manager {
                 (**self).try_init(contact_data)
             }
 
+            /// Delegates the release_tx method to the boxed object.
+            fn release_tx(&mut self, contact_data: &ContactInfo, tx_data: &ContactManagerTxData, bundle: &Bundle) -> bool {
+                (**self).release_tx(contact_data, tx_data, bundle)
+            }
+
+            /// Delegates the import_tx method to the boxed object.
+            fn import_tx(&mut self, contact_data: &ContactInfo, tx_data: &ContactManagerTxData, bundle: &Bundle) -> bool {
+                (**self).import_tx(contact_data, tx_data, bundle)
+            }
+
+            /// Delegates the advance_time method to the boxed object.
+            fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+                (**self).advance_time(contact_data, now)
+            }
+
+            /// Delegates the get_residual_volume method to the boxed object.
+            fn get_residual_volume(&self, contact_data: &ContactInfo, bundle: &Bundle) -> Option<Volume> {
+                (**self).get_residual_volume(contact_data, bundle)
+            }
+
+            /// Delegates the get_booked_volume method to the boxed object.
+            fn get_booked_volume(&self, contact_data: &ContactInfo) -> Option<Volume> {
+                (**self).get_booked_volume(contact_data)
+            }
+
+            /// Delegates the get_confidence method to the boxed object.
+            fn get_confidence(&self) -> f32 {
+                (**self).get_confidence()
+            }
+
+            /// Delegates the set_observed_queue method to the boxed object.
+            fn set_observed_queue(&mut self, contact_data: &ContactInfo, volume_per_priority: &[Volume], observed_at: Date) -> bool {
+                (**self).set_observed_queue(contact_data, volume_per_priority, observed_at)
+            }
+
             #[cfg(feature = "first_depleted")]
             /// Delegates the get_original_volume method to the boxed object.
             fn get_original_volume(&self) -> Volume {
@@ -203,7 +506,38 @@ macro_rules! define_contact_manager {
 }
 
 #[cfg(feature = "debug")]
-define_contact_manager!(std::fmt::Debug);
+define_contact_manager!(core::fmt::Debug);
 
 #[cfg(not(feature = "debug"))]
 define_contact_manager!();
+
+/// Generates the `Parser` and `DispatchParser` implementations of a contact manager built from a
+/// sequence of tokens, e.g. for the custom link models.
+///
+/// The tokens are parsed in order with `Token` (i.e. `FromStr`), then given to a constructor of the
+/// manager. The manager can be registered in a marker map with `coerce_cm` for dynamic parsing.
+///
+/// # Example
+///
+/// ```ignore
+/// // contact <from> <to> <start> <end> <rate> <delay>
+/// a_sabr::contact_manager_parser!(MyEVL, new(rate: DataRate, delay: Duration));
+/// ```
+#[macro_export]
+macro_rules! contact_manager_parser {
+    ($manager:ty, $constructor:ident($($field:ident: $field_type:ty),* $(,)?)) => {
+        impl $crate::parsing::DispatchParser<$manager> for $manager {}
+
+        impl $crate::parsing::Parser<$manager> for $manager {
+            fn parse(lexer: &mut dyn $crate::parsing::Lexer) -> $crate::parsing::ParsingState<$manager> {
+                $(
+                    let $field = match $crate::types::parse_required::<$field_type>(lexer) {
+                        Ok(value) => value,
+                        Err(msg) => return $crate::parsing::ParsingState::Error(msg),
+                    };
+                )*
+                $crate::parsing::ParsingState::Finished(<$manager>::$constructor($($field),*))
+            }
+        }
+    };
+}
@@ -0,0 +1,320 @@
+use alloc::{boxed::Box, format};
+
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    parsing::{DispatchParser, Lexer, Parser, ParsingState},
+    types::{parse_required, settle_volume, volume_fits, Date, Duration, Volume, VOLUME_ZERO},
+};
+
+use super::{
+    legacy::{eto::ETOManager, evl::EVLManager, qd::QDManager},
+    seg::SegmentationManager,
+    ContactManager, ContactManagerTxData,
+};
+
+/// The count of priority classes of a `PartitionedManager` (bulk, normal, expedited).
+pub const PARTITION_CLASSES: usize = 3;
+
+/// A contact manager wrapper dedicating fractions of the contact volume to the priority classes,
+/// e.g. 20% reserved for the expedited traffic whatever the load of the other classes.
+///
+/// A bundle of priority `p` is refused if the volume booked by its class would exceed the
+/// fraction `p` of the volume of the contact, the bundles of priority higher than the last class
+/// being counted in the last class. The volume of the contact is the booked volume plus the
+/// residual volume of the wrapped manager, which must track volumes (e.g. the EVL, QD, ETO or
+/// Segmentation managers): the bundles are refused otherwise. The share of a class is consumed by
+/// its bookings until they are released.
+///
+/// # Type Parameters
+///
+/// * `CM` - The wrapped contact manager.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PartitionedManager<CM: ContactManager> {
+    /// The wrapped contact manager.
+    pub inner: CM,
+    /// The fraction of the volume dedicated to each class, from the lowest priority.
+    fractions: [f64; PARTITION_CLASSES],
+    /// The volume booked by each class.
+    booked: [Volume; PARTITION_CLASSES],
+}
+
+impl<CM: ContactManager> PartitionedManager<CM> {
+    /// Wraps a contact manager.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The contact manager to wrap.
+    /// * `fractions` - The fraction of the volume dedicated to each class, from the lowest
+    ///   priority, summing to 1 at most.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `PartitionedManager`.
+    pub fn new(inner: CM, fractions: [f64; PARTITION_CLASSES]) -> Self {
+        Self {
+            inner,
+            fractions,
+            booked: [VOLUME_ZERO; PARTITION_CLASSES],
+        }
+    }
+
+    /// Retrieves the fraction of the volume dedicated to each class, from the lowest priority.
+    pub fn fractions(&self) -> &[f64; PARTITION_CLASSES] {
+        &self.fractions
+    }
+
+    /// The class of a bundle.
+    fn class(bundle: &Bundle) -> usize {
+        (bundle.priority as usize).min(PARTITION_CLASSES - 1)
+    }
+
+    /// The volume left to the class of a bundle, `None` if the wrapped manager does not track
+    /// volumes.
+    fn class_residual(&self, contact_data: &ContactInfo, bundle: &Bundle) -> Option<Volume> {
+        let capacity = self.inner.get_booked_volume(contact_data)?
+            + self.inner.get_residual_volume(contact_data, bundle)?;
+        let class = Self::class(bundle);
+        Some(settle_volume(
+            capacity * self.fractions[class] - self.booked[class],
+        ))
+    }
+
+    /// Tells whether the bundle fits in the volume left to its class.
+    fn fits(&self, contact_data: &ContactInfo, bundle: &Bundle) -> bool {
+        self.class_residual(contact_data, bundle)
+            .is_some_and(|residual| volume_fits(bundle.size, residual))
+    }
+}
+
+impl<CM: ContactManager> ContactManager for PartitionedManager<CM> {
+    /// Simulates the transmission with the wrapped manager if the bundle fits in the volume left
+    /// to its class.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        if !self.fits(contact_data, bundle) {
+            return None;
+        }
+        self.inner.dry_run_tx(contact_data, at_time, bundle)
+    }
+
+    /// Schedules the transmission with the wrapped manager if the bundle fits in the volume left
+    /// to its class, the volume being booked by the class.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        if !self.fits(contact_data, bundle) {
+            return None;
+        }
+        let tx_data = self.inner.schedule_tx(contact_data, at_time, bundle)?;
+        self.booked[Self::class(bundle)] += bundle.size;
+        Some(tx_data)
+    }
+
+    #[cfg(feature = "first_depleted")]
+    fn get_original_volume(&self) -> Volume {
+        self.inner.get_original_volume()
+    }
+
+    #[cfg(feature = "manual_queueing")]
+    fn manual_enqueue(&mut self, bundle: &Bundle) -> bool {
+        self.inner.manual_enqueue(bundle)
+    }
+
+    #[cfg(feature = "manual_queueing")]
+    fn manual_dequeue(&mut self, bundle: &Bundle) -> bool {
+        self.inner.manual_dequeue(bundle)
+    }
+
+    /// Releases the booking of the wrapped manager, and the volume booked by the class.
+    fn release_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        if !self.inner.release_tx(contact_data, tx_data, bundle) {
+            return false;
+        }
+        let class = Self::class(bundle);
+        self.booked[class] = settle_volume(self.booked[class] - bundle.size);
+        true
+    }
+
+    /// Imports the transmission into the wrapped manager if the bundle fits in the volume left to
+    /// its class, the volume being booked by the class.
+    fn import_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        if !self.fits(contact_data, bundle) || !self.inner.import_tx(contact_data, tx_data, bundle)
+        {
+            return false;
+        }
+        self.booked[Self::class(bundle)] += bundle.size;
+        true
+    }
+
+    /// Wraps the repetition of the wrapped manager, with the same fractions.
+    fn repeat(&self, offset: Duration) -> Option<Self> {
+        self.inner
+            .repeat(offset)
+            .map(|inner| Self::new(inner, self.fractions))
+    }
+
+    /// Wraps the merge of the wrapped managers, if they have the same fractions.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        if self.fractions != next.fractions {
+            return None;
+        }
+        self.inner
+            .merge(&next.inner)
+            .map(|inner| Self::new(inner, self.fractions))
+    }
+
+    fn advance_time(&mut self, contact_data: &ContactInfo, now: Date) {
+        self.inner.advance_time(contact_data, now)
+    }
+
+    /// Returns the volume left to the class of the bundle, bounded by the residual volume of the
+    /// wrapped manager.
+    fn get_residual_volume(&self, contact_data: &ContactInfo, bundle: &Bundle) -> Option<Volume> {
+        let residual = self.inner.get_residual_volume(contact_data, bundle)?;
+        self.class_residual(contact_data, bundle)
+            .map(|class_residual| class_residual.min(residual))
+    }
+
+    fn get_booked_volume(&self, contact_data: &ContactInfo) -> Option<Volume> {
+        self.inner.get_booked_volume(contact_data)
+    }
+
+    fn get_confidence(&self) -> f32 {
+        self.inner.get_confidence()
+    }
+
+    fn set_observed_queue(
+        &mut self,
+        contact_data: &ContactInfo,
+        volume_per_priority: &[Volume],
+        observed_at: Date,
+    ) -> bool {
+        self.inner
+            .set_observed_queue(contact_data, volume_per_priority, observed_at)
+    }
+
+    /// Initializes the wrapped manager.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if a fraction is not within [0, 1], if the fractions sum to more than 1,
+    /// or if the wrapped manager is inconsistent.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        if self
+            .fractions
+            .iter()
+            .any(|fraction| !(0.0..=1.0).contains(fraction))
+            || self.fractions.iter().sum::<f64>() > 1.0 + f64::EPSILON * 4.0
+        {
+            return false;
+        }
+        self.booked = [VOLUME_ZERO; PARTITION_CLASSES];
+        self.inner.try_init(contact_data)
+    }
+}
+
+impl<CM: ContactManager + Parser<CM>> DispatchParser<PartitionedManager<CM>>
+    for PartitionedManager<CM>
+{
+}
+
+impl<CM: ContactManager + Parser<CM>> Parser<PartitionedManager<CM>> for PartitionedManager<CM> {
+    /// Parses a `PartitionedManager` from the lexer: the fraction of each class, from the lowest
+    /// priority, then the parameters of the wrapped manager.
+    ///
+    /// ```text
+    /// contact <from> <to> <start> <end> [marker] <bulk> <normal> <expedited> <manager parameters>
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `lexer` - The lexer used for parsing tokens.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ParsingState` indicating whether parsing was successful (`Finished`) or encountered an error (`Error`).
+    fn parse(lexer: &mut dyn Lexer) -> ParsingState<PartitionedManager<CM>> {
+        let mut fractions = [0.0; PARTITION_CLASSES];
+        for fraction in fractions.iter_mut() {
+            match parse_required::<f64>(lexer) {
+                Ok(value) => *fraction = value,
+                Err(msg) => return ParsingState::Error(msg),
+            }
+        }
+        match CM::parse(lexer) {
+            ParsingState::Finished(inner) => {
+                ParsingState::Finished(PartitionedManager::new(inner, fractions))
+            }
+            ParsingState::Error(msg) => ParsingState::Error(msg),
+            ParsingState::EOF => {
+                ParsingState::Error(format!("Parsing failed ({})", lexer.get_current_position()))
+            }
+        }
+    }
+}
+
+/// Parses a partitioned manager for the dynamic parsing, to register with the "part" marker: the
+/// marker of the wrapped manager ("evl", "qd", "eto" or "seg") is followed by the fractions and
+/// the parameters of the wrapped manager (see `PartitionedManager`).
+///
+/// ```text
+/// contact <from> <to> <start> <end> part evl 0.2 0.3 0.5 <rate> <delay>
+/// ```
+///
+/// # Arguments
+///
+/// * `lexer` - The lexer used for parsing tokens.
+///
+/// # Returns
+///
+/// Returns a `ParsingState` with the boxed manager, or an error if the wrapped marker is unknown.
+pub fn coerce_partitioned(lexer: &mut dyn Lexer) -> ParsingState<Box<dyn ContactManager>> {
+    fn boxed<CM: ContactManager + Parser<CM> + 'static>(
+        lexer: &mut dyn Lexer,
+    ) -> ParsingState<Box<dyn ContactManager>> {
+        match PartitionedManager::<CM>::parse(lexer) {
+            ParsingState::Finished(manager) => ParsingState::Finished(Box::new(manager)),
+            ParsingState::Error(msg) => ParsingState::Error(msg),
+            ParsingState::EOF => ParsingState::EOF,
+        }
+    }
+
+    let marker = match lexer.consume_next_token() {
+        ParsingState::Finished(marker) => marker,
+        ParsingState::Error(msg) => return ParsingState::Error(msg),
+        ParsingState::EOF => {
+            return ParsingState::Error(format!(
+                "Parsing failed ({})",
+                lexer.get_current_position()
+            ))
+        }
+    };
+    match marker.as_str() {
+        "evl" => boxed::<EVLManager>(lexer),
+        "qd" => boxed::<QDManager>(lexer),
+        "eto" => boxed::<ETOManager>(lexer),
+        "seg" => boxed::<SegmentationManager>(lexer),
+        _ => ParsingState::Error(format!(
+            "Unrecognized partitioned marker ({})",
+            lexer.get_current_position()
+        )),
+    }
+}
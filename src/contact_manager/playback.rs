@@ -0,0 +1,365 @@
+use alloc::{format, vec::Vec};
+
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    epoch::parse_date,
+    parsing::{DispatchParser, Lexer, Parser, ParsingState},
+    types::{
+        duration_from_f64, ends_by, parse_required, settle_volume, time_to_f64, DataRate, Date,
+        Duration, Volume, RATE_ZERO, TIME_ZERO, VOLUME_ZERO,
+    },
+};
+
+/// A receive window of a relay paired with the window during which the data received is played
+/// back on the contact.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct PlaybackWindow {
+    /// The start of the receive window.
+    pub receive_start: Date,
+    /// The end of the receive window, the data received until then is played back in this window.
+    pub receive_end: Date,
+    /// The start of the playback window.
+    pub playback_start: Date,
+    /// The end of the playback window.
+    pub playback_end: Date,
+}
+
+/// A contact manager for the store-and-forward relays playing back their stored data during
+/// dedicated windows only (e.g. a satellite recording during its passes over the users and
+/// dumping its recorder during its passes over a ground station).
+///
+/// The manager is set on the contact of the relay toward the destination of the playback. A
+/// bundle ready at the relay at `at_time` (i.e. received by the relay) is transmitted during the
+/// playback window paired with the first receive window ending after `at_time`, whatever the
+/// availability of the contact before that window. The playback windows are queues: the volume
+/// booked in a window delays the next transmissions of the window, like `QDManager`, and a bundle
+/// not fitting in its window is played back in a later window.
+///
+/// The arrival times computed for the two-hop relay passes (the uplink to the relay, then the
+/// playback contact) therefore account for the recorder of the relay.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PlaybackManager {
+    /// The data transmission rate of the playback.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The paired windows, sorted by the end of their receive window at initialization.
+    windows: Vec<PlaybackWindow>,
+    /// The volume booked in each playback window.
+    booked: Vec<Volume>,
+    /// The volume of each playback window (within the contact).
+    capacities: Vec<Volume>,
+}
+
+impl PlaybackManager {
+    /// Creates a new `PlaybackManager`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The data rate of the playback.
+    /// * `delay` - The link delay for this contact.
+    /// * `windows` - The receive windows paired with their playback windows.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `PlaybackManager`.
+    pub fn new(rate: DataRate, delay: Duration, windows: Vec<PlaybackWindow>) -> Self {
+        Self {
+            rate,
+            delay,
+            windows,
+            booked: Vec::new(),
+            capacities: Vec::new(),
+        }
+    }
+
+    /// Retrieves the paired windows.
+    pub fn get_windows(&self) -> &[PlaybackWindow] {
+        &self.windows
+    }
+
+    /// Bounds a playback window by the contact.
+    fn bounds(contact_data: &ContactInfo, window: &PlaybackWindow) -> (Date, Date) {
+        (
+            Date::max(window.playback_start, contact_data.start),
+            Date::min(window.playback_end, contact_data.end),
+        )
+    }
+
+    /// Finds the playback window of a bundle ready at `at_time`, and its transmission.
+    ///
+    /// # Returns
+    ///
+    /// The index of the window and the transmission, `None` if no window can play the bundle back.
+    fn find_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<(usize, ContactManagerTxData)> {
+        let first = self
+            .windows
+            .iter()
+            .position(|window| at_time < window.receive_end)?;
+        for idx in first..self.windows.len() {
+            if !contact_data.semantics.accepts(
+                bundle.size,
+                self.capacities[idx] - self.booked[idx],
+                self.capacities[idx],
+            ) {
+                continue;
+            }
+            let (start, end) = Self::bounds(contact_data, &self.windows[idx]);
+            let queue_end = start + duration_from_f64(self.booked[idx] / self.rate);
+            let tx_start = Date::max(queue_end, at_time);
+            let tx_end = tx_start + duration_from_f64(bundle.size / self.rate);
+            if !ends_by(tx_end, end) {
+                continue;
+            }
+            return Some((
+                idx,
+                ContactManagerTxData {
+                    tx_start,
+                    tx_end,
+                    delay: self.delay,
+                    expiration: end,
+                    arrival: tx_end + self.delay,
+                },
+            ));
+        }
+        None
+    }
+
+    /// Finds the playback window of a transmission.
+    fn window_of(
+        &self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+    ) -> Option<usize> {
+        self.windows.iter().position(|window| {
+            let (start, end) = Self::bounds(contact_data, window);
+            start <= tx_data.tx_start && ends_by(tx_data.tx_end, end)
+        })
+    }
+}
+
+impl ContactManager for PlaybackManager {
+    /// Simulates the playback of a bundle, in the window paired with its reception.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The time the bundle is ready at the relay.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.find_tx(contact_data, at_time, bundle)
+            .map(|(_, tx_data)| tx_data)
+    }
+
+    /// Schedules the playback of a bundle, the volume is booked in its playback window.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The time the bundle is ready at the relay.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let (idx, tx_data) = self.find_tx(contact_data, at_time, bundle)?;
+        self.booked[idx] += bundle.size;
+        Some(tx_data)
+    }
+
+    /// Releases a playback booked by `schedule_tx`, from the window of the transmission.
+    fn release_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        let Some(idx) = self.window_of(contact_data, tx_data) else {
+            return false;
+        };
+        self.booked[idx] = settle_volume(self.booked[idx] - bundle.size);
+        true
+    }
+
+    /// Imports a committed playback, the volume is booked in the window of the transmission.
+    fn import_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        bundle: &Bundle,
+    ) -> bool {
+        let Some(idx) = self.window_of(contact_data, tx_data) else {
+            return false;
+        };
+        self.booked[idx] += bundle.size;
+        true
+    }
+
+    /// Creates a manager with the paired windows shifted by `offset`.
+    fn repeat(&self, offset: Duration) -> Option<Self> {
+        let windows = self
+            .windows
+            .iter()
+            .map(|window| PlaybackWindow {
+                receive_start: window.receive_start + offset,
+                receive_end: window.receive_end + offset,
+                playback_start: window.playback_start + offset,
+                playback_end: window.playback_end + offset,
+            })
+            .collect();
+        Some(Self::new(self.rate, self.delay, windows))
+    }
+
+    /// Returns the volume remaining in the playback windows.
+    fn get_residual_volume(&self, _contact_data: &ContactInfo, _bundle: &Bundle) -> Option<Volume> {
+        Some(
+            self.capacities
+                .iter()
+                .zip(&self.booked)
+                .map(|(capacity, booked)| *capacity - *booked)
+                .sum(),
+        )
+    }
+
+    /// Returns the volume booked in the playback windows.
+    fn get_booked_volume(&self, _contact_data: &ContactInfo) -> Option<Volume> {
+        Some(self.booked.iter().sum())
+    }
+
+    /// Sorts the windows and initializes their volumes within the contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the rate is not positive, if a window is malformed (a playback window
+    /// must not start before its receive window), or if no playback window overlaps the contact.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        if self.rate <= RATE_ZERO {
+            return false;
+        }
+        for window in &self.windows {
+            if window.receive_start > window.receive_end
+                || window.playback_start >= window.playback_end
+                || window.playback_start < window.receive_start
+            {
+                return false;
+            }
+        }
+        self.windows.sort_by(|a, b| {
+            a.receive_end
+                .partial_cmp(&b.receive_end)
+                .unwrap_or(core::cmp::Ordering::Equal)
+        });
+        self.capacities = self
+            .windows
+            .iter()
+            .map(|window| {
+                let (start, end) = Self::bounds(contact_data, window);
+                if start < end {
+                    contact_data
+                        .semantics
+                        .round_volume(time_to_f64(end - start) * self.rate)
+                } else {
+                    VOLUME_ZERO
+                }
+            })
+            .collect();
+        self.booked = alloc::vec![VOLUME_ZERO; self.windows.len()];
+        self.capacities
+            .iter()
+            .any(|capacity| *capacity > VOLUME_ZERO)
+    }
+
+    /// Returns the volume of the playback windows at initialization.
+    #[cfg(feature = "first_depleted")]
+    fn get_original_volume(&self) -> Volume {
+        self.capacities.iter().sum()
+    }
+}
+
+impl DispatchParser<PlaybackManager> for PlaybackManager {}
+
+impl Parser<PlaybackManager> for PlaybackManager {
+    /// Parses a `PlaybackManager` from the lexer: the rate, the delay, then the paired windows.
+    ///
+    /// ```text
+    /// contact <from> <to> <start> <end> [marker] <rate> <delay>
+    /// window <receive_start> <receive_end> <playback_start> <playback_end>
+    /// window <receive_start> <receive_end> <playback_start> <playback_end>
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `lexer` - The lexer used for parsing tokens.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ParsingState` indicating whether parsing was successful (`Finished`) or encountered an error (`Error`).
+    fn parse(lexer: &mut dyn Lexer) -> ParsingState<PlaybackManager> {
+        let rate = match parse_required::<DataRate>(lexer) {
+            Ok(rate) => rate,
+            Err(msg) => return ParsingState::Error(msg),
+        };
+        let delay = match parse_required::<Duration>(lexer) {
+            Ok(delay) => delay,
+            Err(msg) => return ParsingState::Error(msg),
+        };
+
+        let mut windows = Vec::new();
+        loop {
+            match lexer.lookup() {
+                ParsingState::Finished(token) if token == "window" => {
+                    lexer.consume_next_token();
+                }
+                ParsingState::Error(msg) => return ParsingState::Error(msg),
+                _ => break,
+            }
+            let mut dates = [TIME_ZERO; 4];
+            for date in &mut dates {
+                *date = match parse_date(lexer) {
+                    ParsingState::Finished(value) => value,
+                    ParsingState::Error(msg) => return ParsingState::Error(msg),
+                    ParsingState::EOF => {
+                        return ParsingState::Error(format!(
+                            "Parsing failed ({})",
+                            lexer.get_current_position()
+                        ))
+                    }
+                };
+            }
+            windows.push(PlaybackWindow {
+                receive_start: dates[0],
+                receive_end: dates[1],
+                playback_start: dates[2],
+                playback_end: dates[3],
+            });
+        }
+        ParsingState::Finished(PlaybackManager::new(rate, delay, windows))
+    }
+}
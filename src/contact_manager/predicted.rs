@@ -0,0 +1,142 @@
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    types::{duration_from_f64, ends_by, DataRate, Date, Duration, RATE_ZERO},
+};
+
+/// A contact manager for the predicted opportunistic encounters, mixed with the scheduled
+/// contacts in a plan (CGR-BP hybrid).
+///
+/// The contact is the expected window of the encounter, occurring with a probability (returned
+/// by `get_confidence`, see `Probabilistic` for the distance penalizing the predicted segments).
+/// As the encounter may not occur, the manager never commits volume: the scheduling only
+/// computes the transmission times, the bundles fitting in the window being always accepted.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PredictedManager {
+    /// The expected data transmission rate.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The probability that the encounter occurs.
+    probability: f32,
+}
+
+impl PredictedManager {
+    /// Creates a new `PredictedManager` with specified rate, delay and probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The expected data rate of the encounter.
+    /// * `delay` - The link delay for this contact.
+    /// * `probability` - The probability that the encounter occurs, within ]0, 1].
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `PredictedManager`.
+    pub fn new(rate: DataRate, delay: Duration, probability: f32) -> Self {
+        Self {
+            rate,
+            delay,
+            probability,
+        }
+    }
+}
+
+impl ContactManager for PredictedManager {
+    /// Simulates the transmission of a bundle, as soon as possible within the expected window.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let tx_start = Date::max(contact_data.start, at_time);
+        let tx_end = tx_start + duration_from_f64(bundle.size / self.rate);
+        if !ends_by(tx_end, contact_data.end) {
+            return None;
+        }
+        Some(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            delay: self.delay,
+            expiration: contact_data.end,
+            arrival: self.delay + tx_end,
+        })
+    }
+
+    /// Computes the transmission like `dry_run_tx`, no volume is committed.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx(contact_data, at_time, bundle)
+    }
+
+    /// Nothing was committed, the release always succeeds.
+    fn release_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        _bundle: &Bundle,
+    ) -> bool {
+        true
+    }
+
+    /// Nothing is committed, the import always succeeds.
+    fn import_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+        _bundle: &Bundle,
+    ) -> bool {
+        true
+    }
+
+    /// Creates a manager with the same rate, delay and probability.
+    fn repeat(&self, _offset: Duration) -> Option<Self> {
+        Some(Self::new(self.rate, self.delay, self.probability))
+    }
+
+    /// Creates a manager if both managers have the same rate, delay and probability.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        (self.rate == next.rate && self.delay == next.delay && self.probability == next.probability)
+            .then(|| Self::new(self.rate, self.delay, self.probability))
+    }
+
+    /// Returns the probability that the encounter occurs.
+    fn get_confidence(&self) -> f32 {
+        self.probability
+    }
+
+    /// Checks the parameters of the prediction.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information (unused in this implementation).
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the rate is not positive or if the probability is not within ]0, 1].
+    fn try_init(&mut self, _contact_data: &ContactInfo) -> bool {
+        self.rate > RATE_ZERO && self.probability > 0.0 && self.probability <= 1.0
+    }
+}
+
+// contact <from> <to> <start> <end> [marker] <rate> <delay> <probability>
+crate::contact_manager_parser!(
+    PredictedManager,
+    new(rate: DataRate, delay: Duration, probability: f32)
+);
@@ -1,10 +1,17 @@
 // The Segmented contacts are composites, construct the contact by adding intervals
 // Use is_wellformed for sanity check
 
+use alloc::{format, vec::Vec};
+
 use crate::bundle::Bundle;
 use crate::contact::ContactInfo;
+use crate::contact_plan::semantics::Headroom;
+use crate::epoch::parse_date;
 use crate::parsing::{DispatchParser, Lexer, Parser, ParsingState};
-use crate::types::{DataRate, Date, Duration, Token, Volume};
+use crate::types::{
+    duration_from_f64, ends_by, settle_volume, time_to_f64, times_match, DataRate, Date, Duration,
+    Token, Volume, TIME_ZERO, VOLUME_EPSILON, VOLUME_ZERO,
+};
 
 use super::{ContactManager, ContactManagerTxData};
 
@@ -58,7 +65,7 @@ impl SegmentationManager {
             rate_intervals,
             delay_intervals,
             #[cfg(feature = "first_depleted")]
-            original_volume: 0.0,
+            original_volume: VOLUME_ZERO,
         }
     }
 
@@ -71,16 +78,16 @@ impl SegmentationManager {
     ///
     /// # Returns
     ///
-    /// The delay value for the corresponding interval, or `Duration::MAX` if no interval applies.
+    /// The delay value for the corresponding interval, or `None` if no interval applies.
     #[inline(always)]
-    fn get_delay(tx_end: Date, delay_intervals: &Vec<Segment<Duration>>) -> Duration {
+    fn get_delay(tx_end: Date, delay_intervals: &Vec<Segment<Duration>>) -> Option<Duration> {
         for delay_seg in delay_intervals {
             if tx_end > delay_seg.end {
                 continue;
             }
-            return delay_seg.val;
+            return Some(delay_seg.val);
         }
-        Duration::MAX
+        None
     }
 
     /// Calculates the transmission end time based on the current time, the volume to be transmitted, and the deadline.
@@ -103,22 +110,68 @@ impl SegmentationManager {
                 continue;
             }
 
-            tx_end = at_time + volume / rate_seg.val;
+            tx_end = at_time + duration_from_f64(volume / rate_seg.val);
 
             if tx_end > rate_seg.end {
-                volume -= rate_seg.val * (tx_end - at_time);
+                volume -= rate_seg.val * time_to_f64(tx_end - at_time);
                 at_time = rate_seg.end;
                 continue;
             }
-            volume = 0.0;
+            volume = VOLUME_ZERO;
             break;
         }
 
-        if volume > 0.0 || tx_end > deadline {
+        if volume > VOLUME_EPSILON || !ends_by(tx_end, deadline) {
             return None;
         }
         Some(tx_end)
     }
+
+    /// Calculates the volume transmittable between two times, from the rate intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the period.
+    /// * `to` - The end of the period.
+    ///
+    /// # Returns
+    ///
+    /// The volume of the period.
+    #[inline(always)]
+    fn get_volume_between(&self, from: Date, to: Date) -> Volume {
+        let mut volume = VOLUME_ZERO;
+        for rate_seg in &self.rate_intervals {
+            let start = Date::max(from, rate_seg.start);
+            let end = Date::min(to, rate_seg.end);
+            if start < end {
+                volume += time_to_f64(end - start) * rate_seg.val;
+            }
+        }
+        volume
+    }
+
+    /// Tells whether a bundle fits in the free volume of the contact minus its headroom (see
+    /// `ContactSemantics::headroom`), the free intervals being the only limit otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the headroom is left free after the transmission.
+    fn respects_headroom(&self, contact_data: &ContactInfo, bundle: &Bundle) -> bool {
+        if contact_data.semantics.headroom == Headroom::None {
+            return true;
+        }
+        let mut free = VOLUME_ZERO;
+        for free_seg in &self.free_intervals {
+            free += self.get_volume_between(free_seg.start, free_seg.end);
+        }
+        let capacity = self.get_volume_between(contact_data.start, contact_data.end);
+        contact_data.semantics.accepts(bundle.size, free, capacity)
+    }
 }
 
 /// Implements the `ContactManager` trait for `SegmentationManager`, providing methods for simulating and scheduling transmissions.
@@ -127,7 +180,7 @@ impl ContactManager for SegmentationManager {
     ///
     /// # Arguments
     ///
-    /// * `_contact_data` - Reference to the contact information (unused in this implementation).
+    /// * `contact_data` - Reference to the contact information, for the headroom.
     /// * `at_time` - The current time for scheduling purposes.
     /// * `bundle` - The bundle to be transmitted.
     ///
@@ -136,10 +189,13 @@ impl ContactManager for SegmentationManager {
     /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
     fn dry_run_tx(
         &self,
-        _contact_data: &ContactInfo,
+        contact_data: &ContactInfo,
         at_time: Date,
         bundle: &Bundle,
     ) -> Option<ContactManagerTxData> {
+        if !self.respects_headroom(contact_data, bundle) {
+            return None;
+        }
         let mut tx_start: Date;
 
         for free_seg in &self.free_intervals {
@@ -148,7 +204,7 @@ impl ContactManager for SegmentationManager {
             }
             tx_start = Date::max(free_seg.start, at_time);
             if let Some(tx_end) = self.get_tx_end(tx_start, bundle.size, free_seg.end) {
-                let delay = Self::get_delay(tx_end, &self.delay_intervals);
+                let delay = Self::get_delay(tx_end, &self.delay_intervals)?;
                 return Some(ContactManagerTxData {
                     tx_start,
                     tx_end,
@@ -166,7 +222,7 @@ impl ContactManager for SegmentationManager {
     ///
     /// # Arguments
     ///
-    /// * `_contact_data` - Reference to the contact information (unused in this implementation).
+    /// * `contact_data` - Reference to the contact information, for the headroom.
     /// * `at_time` - The current time for scheduling purposes.
     /// * `bundle` - The bundle to be transmitted.
     ///
@@ -175,13 +231,16 @@ impl ContactManager for SegmentationManager {
     /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
     fn schedule_tx(
         &mut self,
-        _contact_data: &ContactInfo,
+        contact_data: &ContactInfo,
         at_time: Date,
         bundle: &Bundle,
     ) -> Option<ContactManagerTxData> {
-        let mut tx_start = 0.0;
+        if !self.respects_headroom(contact_data, bundle) {
+            return None;
+        }
+        let mut tx_start = TIME_ZERO;
         let mut index = 0;
-        let mut tx_end = 0.0;
+        let mut tx_end = TIME_ZERO;
 
         for free_seg in &self.free_intervals {
             if free_seg.end < at_time {
@@ -195,11 +254,11 @@ impl ContactManager for SegmentationManager {
             index += 1;
         }
 
+        let delay = Self::get_delay(tx_end, &self.delay_intervals)?;
         let interval = &mut self.free_intervals[index];
         let expiration = interval.end;
-        let delay = Self::get_delay(tx_end, &self.delay_intervals);
 
-        if interval.start != tx_start {
+        if !times_match(interval.start, tx_start) {
             interval.end = tx_start;
             self.free_intervals.insert(
                 index + 1,
@@ -235,19 +294,19 @@ impl ContactManager for SegmentationManager {
         // we check that we have no holes for rate segments
         let mut time = contact_data.start;
         for inter in &self.rate_intervals {
-            if inter.start != time {
+            if !times_match(inter.start, time) {
                 return false;
             }
             time = inter.end;
             #[cfg(feature = "first_depleted")]
             {
-                self.original_volume += (inter.end - inter.start) * inter.val;
+                self.original_volume += time_to_f64(inter.end - inter.start) * inter.val;
             }
         }
         let opt_rate_end = self.rate_intervals.last();
         match opt_rate_end {
             Some(last_rate_seg) => {
-                if last_rate_seg.end != contact_data.end {
+                if !times_match(last_rate_seg.end, contact_data.end) {
                     return false;
                 }
             }
@@ -257,7 +316,7 @@ impl ContactManager for SegmentationManager {
         // we check that we have no holes for delay segments
         time = contact_data.start;
         for inter in &self.delay_intervals {
-            if inter.start != time {
+            if !times_match(inter.start, time) {
                 return false;
             }
             time = inter.end;
@@ -266,7 +325,7 @@ impl ContactManager for SegmentationManager {
         let opt_delay_end = self.delay_intervals.last();
         match opt_delay_end {
             Some(last_delay_seg) => {
-                if last_delay_seg.end != contact_data.end {
+                if !times_match(last_delay_seg.end, contact_data.end) {
                     return false;
                 }
             }
@@ -284,6 +343,188 @@ impl ContactManager for SegmentationManager {
         true
     }
 
+    /// Returns the volume of the free intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `_contact_data` - Reference to the contact information (unused in this implementation).
+    /// * `_bundle` - The bundle considered (unused in this implementation).
+    ///
+    /// # Returns
+    ///
+    /// The volume that can still be transmitted during the contact.
+    fn get_residual_volume(&self, _contact_data: &ContactInfo, _bundle: &Bundle) -> Option<Volume> {
+        let mut volume = VOLUME_ZERO;
+        for free_seg in &self.free_intervals {
+            volume += self.get_volume_between(free_seg.start, free_seg.end);
+        }
+        Some(volume)
+    }
+
+    /// Returns the volume of the booked intervals, i.e. the volume of the contact minus the
+    /// volume of the free intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// The volume booked by the scheduled transmissions.
+    fn get_booked_volume(&self, contact_data: &ContactInfo) -> Option<Volume> {
+        let mut free = VOLUME_ZERO;
+        for free_seg in &self.free_intervals {
+            free += self.get_volume_between(free_seg.start, free_seg.end);
+        }
+        let total = self.get_volume_between(contact_data.start, contact_data.end);
+        Some(settle_volume(total - free))
+    }
+
+    /// Releases a transmission booked by `schedule_tx`, its interval is free again.
+    ///
+    /// # Arguments
+    ///
+    /// * `_contact_data` - Reference to the contact information (unused in this implementation).
+    /// * `tx_data` - The transmission returned by `schedule_tx`.
+    /// * `_bundle` - The bundle scheduled (unused in this implementation).
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the interval of the transmission is (partly) free, i.e. not booked.
+    fn release_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        _bundle: &Bundle,
+    ) -> bool {
+        let (start, end) = (tx_data.tx_start, tx_data.tx_end);
+        let index = self
+            .free_intervals
+            .iter()
+            .position(|free_seg| ends_by(end, free_seg.start))
+            .unwrap_or(self.free_intervals.len());
+        if index > 0 && !ends_by(self.free_intervals[index - 1].end, start) {
+            return false;
+        }
+        let merge_prev = index > 0 && times_match(self.free_intervals[index - 1].end, start);
+        let merge_next =
+            index < self.free_intervals.len() && times_match(self.free_intervals[index].start, end);
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                let next_end = self.free_intervals.remove(index).end;
+                self.free_intervals[index - 1].end = next_end;
+            }
+            (true, false) => self.free_intervals[index - 1].end = end,
+            (false, true) => self.free_intervals[index].start = start,
+            (false, false) => self.free_intervals.insert(
+                index,
+                Segment {
+                    start,
+                    end,
+                    val: (),
+                },
+            ),
+        }
+        true
+    }
+
+    /// Imports a committed transmission, its interval is removed from the free intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `_contact_data` - Reference to the contact information (unused in this implementation).
+    /// * `tx_data` - The committed transmission.
+    /// * `_bundle` - The bundle transmitted (unused in this implementation).
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the interval of the transmission is (partly) booked, i.e. not free.
+    fn import_tx(
+        &mut self,
+        _contact_data: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+        _bundle: &Bundle,
+    ) -> bool {
+        let (start, end) = (tx_data.tx_start, tx_data.tx_end);
+        let Some(index) = self
+            .free_intervals
+            .iter()
+            .position(|free_seg| free_seg.start <= start && ends_by(end, free_seg.end))
+        else {
+            return false;
+        };
+        let free_seg = &mut self.free_intervals[index];
+        let free_end = free_seg.end;
+        match (
+            times_match(free_seg.start, start),
+            times_match(free_end, end),
+        ) {
+            (true, true) => {
+                self.free_intervals.remove(index);
+            }
+            (true, false) => free_seg.start = end,
+            (false, true) => free_seg.end = start,
+            (false, false) => {
+                free_seg.end = start;
+                self.free_intervals.insert(
+                    index + 1,
+                    Segment {
+                        start: end,
+                        end: free_end,
+                        val: (),
+                    },
+                );
+            }
+        }
+        true
+    }
+
+    /// Creates a manager with the rate and delay intervals shifted by `offset`.
+    fn repeat(&self, offset: Duration) -> Option<Self> {
+        fn shift<T: Copy>(segments: &[Segment<T>], offset: Duration) -> Vec<Segment<T>> {
+            segments
+                .iter()
+                .map(|seg| Segment {
+                    start: seg.start + offset,
+                    end: seg.end + offset,
+                    val: seg.val,
+                })
+                .collect()
+        }
+        Some(Self::new(
+            shift(&self.rate_intervals, offset),
+            shift(&self.delay_intervals, offset),
+        ))
+    }
+
+    /// Creates a manager with the rate and delay intervals of both managers, the contiguous
+    /// intervals with the same value being merged: the rates and delays may differ.
+    fn merge(&self, next: &Self) -> Option<Self> {
+        fn concat<T: Copy + PartialEq>(
+            first: &[Segment<T>],
+            next: &[Segment<T>],
+        ) -> Vec<Segment<T>> {
+            let mut segments: Vec<Segment<T>> = Vec::with_capacity(first.len() + next.len());
+            for seg in first.iter().chain(next) {
+                match segments.last_mut() {
+                    Some(last) if last.val == seg.val && times_match(last.end, seg.start) => {
+                        last.end = seg.end;
+                    }
+                    _ => segments.push(Segment {
+                        start: seg.start,
+                        end: seg.end,
+                        val: seg.val,
+                    }),
+                }
+            }
+            segments
+        }
+        Some(Self::new(
+            concat(&self.rate_intervals, &next.rate_intervals),
+            concat(&self.delay_intervals, &next.delay_intervals),
+        ))
+    }
+
     /// For first depleted compatibility
     ///
     /// # Returns
@@ -316,12 +557,12 @@ impl ContactManager for SegmentationManager {
 /// - `Finished((start, end, val))` if the interval is successfully parsed.
 /// - `Error(msg)` if there is an error during parsing.
 /// - `EOF` if an unexpected end-of-file is encountered during parsing.
-fn parse_interval<T: std::str::FromStr>(lexer: &mut dyn Lexer) -> ParsingState<(Date, Date, T)> {
+fn parse_interval<T: core::str::FromStr>(lexer: &mut dyn Lexer) -> ParsingState<(Date, Date, T)> {
     let start: Date;
     let end: Date;
     let val: T;
 
-    let start_state = Date::parse(lexer);
+    let start_state = parse_date(lexer);
     match start_state {
         ParsingState::Finished(value) => start = value,
         ParsingState::Error(msg) => return ParsingState::Error(msg),
@@ -333,7 +574,7 @@ fn parse_interval<T: std::str::FromStr>(lexer: &mut dyn Lexer) -> ParsingState<(
         }
     }
 
-    let end_state = Date::parse(lexer);
+    let end_state = parse_date(lexer);
     match end_state {
         ParsingState::Finished(value) => end = value,
         ParsingState::Error(msg) => return ParsingState::Error(msg),
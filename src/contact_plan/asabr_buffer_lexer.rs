@@ -0,0 +1,233 @@
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+};
+
+use crate::epoch::Epoch;
+use crate::parsing::{Lexer, ParsingState, Span};
+
+/// The location of a token in the input of a `BufferLexer`.
+#[derive(Clone, Copy)]
+struct TokenLocation<'a> {
+    /// The line number of the token.
+    line: u32,
+    /// The byte offset of the line in the input.
+    line_offset: usize,
+    /// The text of the line.
+    line_text: &'a str,
+    /// The byte offset of the token in the line.
+    column: usize,
+    /// The text of the token.
+    text: &'a str,
+}
+
+/// A lexer tokenizing a contact plan held in memory without copying it.
+///
+/// The `BufferLexer` accepts the inputs of the `StrLexer` and the `FileLexer` (lines starting with
+/// `#` are comments), but the tokens are borrowed from the input (see
+/// `Lexer::consume_next_token_str`) and the spans are only built on request, so that no allocation
+/// is made per token. The input is typically a whole contact plan read at once (see
+/// `BufferLexer::read_file`), which makes it the fastest way to load very large plans.
+pub struct BufferLexer<'a> {
+    /// The whole input.
+    input: &'a str,
+    /// The byte offset of the next line to read.
+    next_line_offset: usize,
+    /// The number of lines read.
+    line: u32,
+    /// The byte offset of the line being tokenized.
+    line_offset: usize,
+    /// The text of the line being tokenized (empty for the comments).
+    line_text: &'a str,
+    /// The byte offset in `line_text` after the last token found.
+    column: usize,
+    /// The token found by a lookup, not consumed yet.
+    next: Option<TokenLocation<'a>>,
+    /// The last consumed token.
+    current: Option<TokenLocation<'a>>,
+    /// Tracks the token's position in the line of the last consumed token.
+    token_position: u32,
+    /// The epoch declared in the input, if any.
+    epoch: Option<Epoch>,
+}
+
+impl<'a> BufferLexer<'a> {
+    /// Creates a new `BufferLexer` for the provided contact plan content.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `BufferLexer`.
+    pub fn new(content: &'a str) -> Self {
+        Self {
+            input: content,
+            next_line_offset: 0,
+            line: 0,
+            line_offset: 0,
+            line_text: "",
+            column: 0,
+            next: None,
+            current: None,
+            token_position: 0,
+            epoch: None,
+        }
+    }
+
+    /// Reads a whole contact plan file, to be tokenized with `BufferLexer::new`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The path to the contact plan file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the content of the file, or an `std::io::Error` if the file cannot be read (or is
+    /// not valid UTF-8).
+    #[cfg(feature = "std")]
+    pub fn read_file(filename: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(filename)
+    }
+
+    /// Finds the next token without consuming it, reading the next lines if needed.
+    fn find_next(&mut self) -> Option<TokenLocation<'a>> {
+        if self.next.is_some() {
+            return self.next;
+        }
+        loop {
+            let rest = &self.line_text[self.column..];
+            let trimmed = rest.trim_start();
+            if !trimmed.is_empty() {
+                let column = self.column + rest.len() - trimmed.len();
+                let len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+                self.column = column + len;
+                self.next = Some(TokenLocation {
+                    line: self.line,
+                    line_offset: self.line_offset,
+                    line_text: self.line_text,
+                    column,
+                    text: &trimmed[..len],
+                });
+                return self.next;
+            }
+
+            // the lines are split like `str::lines`
+            if self.next_line_offset >= self.input.len() {
+                return None;
+            }
+            let remaining = &self.input[self.next_line_offset..];
+            let (line, read) = match remaining.find('\n') {
+                Some(end) => (&remaining[..end], end + 1),
+                None => (remaining, remaining.len()),
+            };
+            self.line += 1;
+            self.line_offset = self.next_line_offset;
+            self.next_line_offset += read;
+            self.column = 0;
+            // Skip lines starting with '#'
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            self.line_text = if line.trim_start().starts_with('#') {
+                ""
+            } else {
+                line
+            };
+        }
+    }
+
+    /// Consumes the next token, borrowed from the input.
+    fn consume(&mut self) -> Option<&'a str> {
+        let token = self.find_next()?;
+        self.next = None;
+        if self
+            .current
+            .is_none_or(|current| current.line != token.line)
+        {
+            self.token_position = 0;
+        }
+        self.token_position += 1;
+        self.current = Some(token);
+        Some(token.text)
+    }
+}
+
+impl Lexer for BufferLexer<'_> {
+    /// Consumes and returns the next token (word) from the input.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ParsingState::Finished(String)` if a token is successfully consumed,
+    /// or `ParsingState::EOF` if the end of the input is reached.
+    fn consume_next_token(&mut self) -> ParsingState<String> {
+        match self.consume() {
+            Some(token) => ParsingState::Finished(token.to_string()),
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Returns the current position in the input in terms of line number and token position.
+    ///
+    /// # Returns
+    ///
+    /// A string in the format `"line {current_line}, token {token_position}"`.
+    fn get_current_position(&self) -> String {
+        let line = self.current.map_or(0, |current| current.line);
+        format!("line {}, token {}", line, self.token_position)
+    }
+
+    /// Consumes and returns the next token, borrowed from the input.
+    fn consume_next_token_str(&mut self) -> ParsingState<Cow<'_, str>> {
+        match self.consume() {
+            Some(token) => ParsingState::Finished(Cow::Borrowed(token)),
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Looks at the next token without consuming it.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ParsingState::Finished(String)` if a token is available,
+    /// or `ParsingState::EOF` if the end of the input is reached.
+    fn lookup(&mut self) -> ParsingState<String> {
+        match self.find_next() {
+            Some(token) => ParsingState::Finished(token.text.to_string()),
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Looks at the next token without consuming it, borrowed from the input.
+    fn lookup_str(&mut self) -> ParsingState<Cow<'_, str>> {
+        match self.find_next() {
+            Some(token) => ParsingState::Finished(Cow::Borrowed(token.text)),
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Returns the span of the last consumed token.
+    fn get_current_span(&self) -> Option<Span> {
+        self.current.map(|current| {
+            Span::new(
+                current.line,
+                self.token_position,
+                current.line_offset,
+                current.column,
+                current.text,
+                current.line_text,
+            )
+        })
+    }
+
+    /// Returns the epoch declared in the input, if any.
+    fn get_epoch(&self) -> Option<Epoch> {
+        self.epoch
+    }
+
+    /// Declares the epoch of the input.
+    fn set_epoch(&mut self, epoch: Epoch) -> bool {
+        self.epoch = Some(epoch);
+        true
+    }
+}
@@ -3,23 +3,21 @@ use std::{
     io::{self, BufRead, BufReader},
 };
 
-use crate::parsing::{Lexer, ParsingState};
+use crate::contact_plan::line_tokens::LineTokens;
+use crate::epoch::Epoch;
+use crate::parsing::{Lexer, ParsingState, Span};
 
 /// A lexer for tokenizing text from a file.
 ///
 /// The `FileLexer` reads a file line by line, processes tokens (words), and provides them one at a time for parsing.
 /// It skips lines starting with `#`, allowing them to be used as comments in the input file.
 pub struct FileLexer {
-    /// Tracks the current line number during lookup operations.
-    lookup_current_line: u32,
-    /// Tracks the line number from which the current token was consumed.
-    current_line: u32,
-    /// Tracks the token's position in the current line.
-    token_position: u32,
     /// A buffered reader for the input file.
     reader: BufReader<File>,
-    /// A stack that stores tokens (words) from the file, in reverse order, for easy consumption.
-    buffer_stack: Vec<String>,
+    /// The byte offset of the next line to read.
+    offset: usize,
+    /// The tokens of the current line, with the positions and the epoch.
+    tokens: LineTokens,
 }
 
 impl FileLexer {
@@ -40,18 +38,13 @@ impl FileLexer {
         let file = File::open(filename)?;
         let reader = BufReader::new(file);
         Ok(Self {
-            lookup_current_line: 0,
-            current_line: 0,
-            token_position: 0,
             reader,
-            buffer_stack: Vec::new(),
+            offset: 0,
+            tokens: LineTokens::new(),
         })
     }
 
-    /// Reads the next line of the file and splits it into words, storing them in the buffer stack.
-    ///
-    /// This function continues reading until it finds a non-empty line that doesn't start with `#`.
-    /// The words are stored in reverse order in the `buffer_stack` to facilitate easy pop operations.
+    /// Reads the lines of the file until one holds tokens (see `LineTokens::push_line`).
     ///
     /// # Returns
     ///
@@ -65,21 +58,14 @@ impl FileLexer {
                 return Ok(());
             }
 
-            self.lookup_current_line += 1;
-
-            // Skip lines starting with '#'
-            if line.trim_start().starts_with('#') {
-                continue;
-            }
-
-            // Split the line into words and collect them into a vector in reverse order
-            let words: Vec<String> = line.split_whitespace().rev().map(String::from).collect();
-            if words.is_empty() {
-                continue;
+            let line_offset = self.offset;
+            self.offset += bytes_read;
+            if self
+                .tokens
+                .push_line(line.trim_end_matches(['\r', '\n']), line_offset)
+            {
+                return Ok(());
             }
-
-            self.buffer_stack.extend(words);
-            return Ok(());
         }
     }
 }
@@ -94,7 +80,7 @@ impl Lexer for FileLexer {
     /// Returns `ParsingState::Finished(String)` if a token is successfully consumed,
     /// `ParsingState::EOF` if the end of the file is reached, or `ParsingState::Error` if an error occurs.
     fn consume_next_token(&mut self) -> ParsingState<String> {
-        if self.buffer_stack.is_empty() {
+        if self.tokens.is_empty() {
             let res = self.read_next_words();
             match res {
                 Ok(_) => {}
@@ -102,18 +88,7 @@ impl Lexer for FileLexer {
             }
         }
 
-        let next_word = self.buffer_stack.pop();
-        match next_word {
-            Some(word) => {
-                if self.current_line != self.lookup_current_line {
-                    self.token_position = 0;
-                    self.current_line = self.lookup_current_line;
-                }
-                self.token_position += 1;
-                ParsingState::Finished(word)
-            }
-            None => ParsingState::EOF,
-        }
+        self.tokens.consume()
     }
 
     /// Returns the current position in the file in terms of line number and token position.
@@ -124,7 +99,7 @@ impl Lexer for FileLexer {
     ///
     /// A string in the format `"line {current_line}, token {token_position}"`.
     fn get_current_position(&self) -> String {
-        format!("line {}, token {}", self.current_line, self.token_position)
+        self.tokens.position()
     }
 
     /// Looks at the next token without consuming it.
@@ -136,7 +111,7 @@ impl Lexer for FileLexer {
     /// Returns `ParsingState::Finished(String)` if a token is available,
     /// `ParsingState::EOF` if the end of the file is reached, or `ParsingState::Error` if an error occurs.
     fn lookup(&mut self) -> ParsingState<String> {
-        if self.buffer_stack.is_empty() {
+        if self.tokens.is_empty() {
             let res = self.read_next_words();
             match res {
                 Ok(_) => {}
@@ -144,10 +119,22 @@ impl Lexer for FileLexer {
             }
         }
 
-        let next_word = self.buffer_stack.last();
-        match next_word {
-            Some(word) => ParsingState::Finished(word.to_string()),
-            None => ParsingState::EOF,
-        }
+        self.tokens.lookup()
+    }
+
+    /// Returns the span of the last consumed token.
+    fn get_current_span(&self) -> Option<Span> {
+        self.tokens.span()
+    }
+
+    /// Returns the epoch declared in the file, if any.
+    fn get_epoch(&self) -> Option<Epoch> {
+        self.tokens.epoch()
+    }
+
+    /// Declares the epoch of the file.
+    fn set_epoch(&mut self, epoch: Epoch) -> bool {
+        self.tokens.set_epoch(epoch);
+        true
     }
 }
@@ -0,0 +1,102 @@
+use alloc::string::String;
+use core::str::Lines;
+
+use crate::contact_plan::line_tokens::LineTokens;
+use crate::epoch::Epoch;
+use crate::parsing::{Lexer, ParsingState, Span};
+
+/// A lexer for tokenizing text from an in-memory string.
+///
+/// The `StrLexer` behaves like the `FileLexer` but does not rely on the filesystem, it is then
+/// usable without the "std" feature (e.g. for WASM targets or embedded contact plans).
+/// It skips lines starting with `#`, allowing them to be used as comments in the input.
+pub struct StrLexer<'a> {
+    /// The whole input.
+    input: &'a str,
+    /// The remaining lines of the input.
+    lines: Lines<'a>,
+    /// The tokens of the current line, with the positions and the epoch.
+    tokens: LineTokens,
+}
+
+impl<'a> StrLexer<'a> {
+    /// Creates a new `StrLexer` for the provided contact plan content.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `StrLexer`.
+    pub fn new(content: &'a str) -> Self {
+        Self {
+            input: content,
+            lines: content.lines(),
+            tokens: LineTokens::new(),
+        }
+    }
+
+    /// Reads the lines of the input until one holds tokens (see `LineTokens::push_line`).
+    fn read_next_words(&mut self) {
+        for line in self.lines.by_ref() {
+            let line_offset = line.as_ptr() as usize - self.input.as_ptr() as usize;
+            if self.tokens.push_line(line, line_offset) {
+                return;
+            }
+        }
+    }
+}
+
+impl Lexer for StrLexer<'_> {
+    /// Consumes and returns the next token (word) from the input.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ParsingState::Finished(String)` if a token is successfully consumed,
+    /// or `ParsingState::EOF` if the end of the input is reached.
+    fn consume_next_token(&mut self) -> ParsingState<String> {
+        if self.tokens.is_empty() {
+            self.read_next_words();
+        }
+        self.tokens.consume()
+    }
+
+    /// Returns the current position in the input in terms of line number and token position.
+    ///
+    /// # Returns
+    ///
+    /// A string in the format `"line {current_line}, token {token_position}"`.
+    fn get_current_position(&self) -> String {
+        self.tokens.position()
+    }
+
+    /// Looks at the next token without consuming it.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ParsingState::Finished(String)` if a token is available,
+    /// or `ParsingState::EOF` if the end of the input is reached.
+    fn lookup(&mut self) -> ParsingState<String> {
+        if self.tokens.is_empty() {
+            self.read_next_words();
+        }
+        self.tokens.lookup()
+    }
+
+    /// Returns the span of the last consumed token.
+    fn get_current_span(&self) -> Option<Span> {
+        self.tokens.span()
+    }
+
+    /// Returns the epoch declared in the input, if any.
+    fn get_epoch(&self) -> Option<Epoch> {
+        self.tokens.epoch()
+    }
+
+    /// Declares the epoch of the input.
+    fn set_epoch(&mut self, epoch: Epoch) -> bool {
+        self.tokens.set_epoch(epoch);
+        true
+    }
+}
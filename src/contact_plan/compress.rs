@@ -0,0 +1,79 @@
+use alloc::{rc::Rc, vec::Vec};
+
+use crate::{
+    contact::{Contact, ContactInfo},
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    types::times_match,
+};
+
+/// Merges a contact with the next contact of its link, if they are back-to-back, with the same
+/// semantics and metadata, and if their managers can be merged (see `ContactManager::merge`).
+fn try_merge<NM: NodeManager, CM: ContactManager>(
+    contact: &Contact<NM, CM>,
+    next: &Contact<NM, CM>,
+) -> Option<Contact<NM, CM>> {
+    let (info, next_info) = (&contact.info, &next.info);
+    if info.tx_node != next_info.tx_node
+        || info.rx_node != next_info.rx_node
+        || !times_match(info.end, next_info.start)
+        || info.semantics != next_info.semantics
+    {
+        return None;
+    }
+    match (&contact.metadata, &next.metadata) {
+        (None, None) => {}
+        (Some(metadata), Some(next_metadata)) if Rc::ptr_eq(metadata, next_metadata) => {}
+        _ => return None,
+    }
+    #[cfg(feature = "contact_suppression")]
+    if contact.suppressed != next.suppressed {
+        return None;
+    }
+
+    let manager = contact.manager.merge(&next.manager)?;
+    let merged_info = ContactInfo {
+        end: next_info.end,
+        ..*info
+    };
+    let mut merged = Contact::try_new(merged_info, manager)?;
+    merged.metadata = contact.metadata.clone();
+    #[cfg(feature = "contact_suppression")]
+    {
+        merged.suppressed = contact.suppressed;
+    }
+    Some(merged)
+}
+
+/// Compresses a contact plan by merging the back-to-back contacts of each link into longer
+/// contacts, e.g. for the plans generated with a fixed time step (per-minute visibility exports).
+///
+/// Two consecutive contacts of a link (the second starting at the end of the first) are merged if
+/// their managers accept it (see `ContactManager::merge`): the legacy, hybrid and disciplined
+/// managers with identical parameters, or the segmented managers whatever their rates and delays,
+/// the merged contact carrying the intervals of both. The merged managers are new, the plan is to
+/// be compressed before the routing (the bookings are not merged).
+///
+/// # Parameters
+///
+/// * `contacts` - The contacts of the plan.
+///
+/// # Returns
+///
+/// * `Vec<Contact<NM, CM>>` - The compressed contacts, sorted by link and start time.
+pub fn compress_contacts<NM: NodeManager, CM: ContactManager>(
+    mut contacts: Vec<Contact<NM, CM>>,
+) -> Vec<Contact<NM, CM>> {
+    contacts.sort_unstable();
+    let mut compressed: Vec<Contact<NM, CM>> = Vec::with_capacity(contacts.len());
+    for contact in contacts {
+        if let Some(last) = compressed.last_mut() {
+            if let Some(merged) = try_merge(last, &contact) {
+                *last = merged;
+                continue;
+            }
+        }
+        compressed.push(contact);
+    }
+    compressed
+}
@@ -0,0 +1,454 @@
+use std::f64::consts::PI;
+
+use crate::{
+    contact::{Contact, ContactInfo},
+    contact_manager::ContactManager,
+    contact_plan::semantics::ContactSemantics,
+    epoch::{days_from_civil, Epoch, DTN_EPOCH_UNIX_OFFSET},
+    node_manager::NodeManager,
+    types::{time_from_seconds, time_to_seconds, Date, Duration, NodeID},
+};
+
+/// The gravitational parameter of the Earth, in km^3/s^2.
+const EARTH_MU: f64 = 398_600.441_8;
+/// The equatorial radius of the Earth (WGS 84), in km.
+const EARTH_RADIUS: f64 = 6_378.137;
+/// The flattening of the Earth (WGS 84).
+const EARTH_FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// The second zonal harmonic of the Earth.
+const EARTH_J2: f64 = 1.082_626_68e-3;
+/// The speed of light, in km/s.
+pub const LIGHT_SPEED: f64 = 299_792.458;
+
+/// A position in the Earth-fixed frame (ECEF), in km.
+pub type Position = [f64; 3];
+
+/// The trajectory of a spacecraft.
+pub trait Ephemeris {
+    /// Computes the position of the spacecraft.
+    ///
+    /// # Parameters
+    ///
+    /// * `dtn_seconds` - The time, in seconds since the DTN epoch (see `Epoch`).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Position>` - The position in the Earth-fixed frame, or `None` if the ephemeris
+    ///   does not cover the time.
+    fn position(&self, dtn_seconds: f64) -> Option<Position>;
+}
+
+/// The Greenwich mean sidereal time, in radians, UT1 being approximated by the time.
+fn gmst(dtn_seconds: f64) -> f64 {
+    // the DTN epoch is 12 hours before J2000.0
+    let days = (dtn_seconds - 43_200.0) / 86_400.0;
+    (280.460_618_37 + 360.985_647_366_29 * days)
+        .to_radians()
+        .rem_euclid(2.0 * PI)
+}
+
+/// A Keplerian orbit with the secular drifts caused by the oblateness of the Earth (J2), e.g.
+/// from the mean elements of a TLE.
+///
+/// The propagation is a coarse alternative to SGP4, suitable for the contact plans (the visibility
+/// windows drift by seconds per day for the low orbits), without atmospheric drag.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct KeplerianOrbit {
+    /// The time of the elements, in seconds since the DTN epoch.
+    pub epoch: f64,
+    /// The semi-major axis, in km.
+    pub semi_major_axis: f64,
+    /// The eccentricity.
+    pub eccentricity: f64,
+    /// The inclination, in radians.
+    pub inclination: f64,
+    /// The right ascension of the ascending node, in radians.
+    pub raan: f64,
+    /// The argument of perigee, in radians.
+    pub arg_perigee: f64,
+    /// The mean anomaly at the epoch, in radians.
+    pub mean_anomaly: f64,
+}
+
+impl KeplerianOrbit {
+    /// Creates an orbit from the two lines of a TLE (the title line excluded).
+    ///
+    /// # Parameters
+    ///
+    /// * `line1` - The first line of the TLE, for the epoch.
+    /// * `line2` - The second line of the TLE, for the mean elements.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, String>` - The orbit, or an error message if a line is malformed.
+    pub fn from_tle(line1: &str, line2: &str) -> Result<Self, String> {
+        let field = |line: &str, start: usize, end: usize, name: &str| -> Result<f64, String> {
+            line.get(start..end)
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .ok_or_else(|| format!("Malformed TLE {} ({})", name, line))
+        };
+        if !line1.starts_with('1') || !line2.starts_with('2') {
+            return Err(format!("Malformed TLE lines ({} / {})", line1, line2));
+        }
+        let year = field(line1, 18, 20, "epoch year")? as i64;
+        let year = if year < 57 { 2000 + year } else { 1900 + year };
+        let day_of_year = field(line1, 20, 32, "epoch day")?;
+        let epoch = (days_from_civil(year, 1, 1) as f64 + day_of_year - 1.0) * 86_400.0
+            - DTN_EPOCH_UNIX_OFFSET;
+
+        // the eccentricity has an implied leading decimal point
+        let eccentricity = field(line2, 26, 33, "eccentricity")? * 1e-7;
+        let mean_motion = field(line2, 52, 63, "mean motion")? * 2.0 * PI / 86_400.0;
+        if mean_motion <= 0.0 || !(0.0..1.0).contains(&eccentricity) {
+            return Err(format!("Malformed TLE elements ({})", line2));
+        }
+        Ok(Self {
+            epoch,
+            semi_major_axis: (EARTH_MU / (mean_motion * mean_motion)).cbrt(),
+            eccentricity,
+            inclination: field(line2, 8, 16, "inclination")?.to_radians(),
+            raan: field(line2, 17, 25, "RAAN")?.to_radians(),
+            arg_perigee: field(line2, 34, 42, "argument of perigee")?.to_radians(),
+            mean_anomaly: field(line2, 43, 51, "mean anomaly")?.to_radians(),
+        })
+    }
+
+    /// The mean motion, in radians per second.
+    fn mean_motion(&self) -> f64 {
+        (EARTH_MU / self.semi_major_axis.powi(3)).sqrt()
+    }
+}
+
+impl Ephemeris for KeplerianOrbit {
+    fn position(&self, dtn_seconds: f64) -> Option<Position> {
+        let elapsed = dtn_seconds - self.epoch;
+        let n = self.mean_motion();
+        let e = self.eccentricity;
+        let p = self.semi_major_axis * (1.0 - e * e);
+        let j2_factor = 1.5 * n * EARTH_J2 * (EARTH_RADIUS / p).powi(2);
+        let cos_i = self.inclination.cos();
+        let raan = self.raan - j2_factor * cos_i * elapsed;
+        let arg_perigee =
+            self.arg_perigee + 0.5 * j2_factor * (5.0 * cos_i * cos_i - 1.0) * elapsed;
+        let mean_anomaly = (self.mean_anomaly + n * elapsed).rem_euclid(2.0 * PI);
+
+        // Kepler's equation, by Newton's method
+        let mut eccentric_anomaly = if e < 0.8 { mean_anomaly } else { PI };
+        for _ in 0..20 {
+            let delta = (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+                / (1.0 - e * eccentric_anomaly.cos());
+            eccentric_anomaly -= delta;
+            if delta.abs() < 1e-12 {
+                break;
+            }
+        }
+        let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+        // the position in the perifocal frame
+        let x = self.semi_major_axis * (cos_e - e);
+        let y = self.semi_major_axis * (1.0 - e * e).sqrt() * sin_e;
+
+        let (sin_w, cos_w) = arg_perigee.sin_cos();
+        let (sin_o, cos_o) = raan.sin_cos();
+        let sin_i = self.inclination.sin();
+        let inertial = [
+            x * (cos_o * cos_w - sin_o * sin_w * cos_i)
+                - y * (cos_o * sin_w + sin_o * cos_w * cos_i),
+            x * (sin_o * cos_w + cos_o * sin_w * cos_i)
+                - y * (sin_o * sin_w - cos_o * cos_w * cos_i),
+            x * sin_w * sin_i + y * cos_w * sin_i,
+        ];
+
+        let (sin_t, cos_t) = gmst(dtn_seconds).sin_cos();
+        Some([
+            cos_t * inertial[0] + sin_t * inertial[1],
+            -sin_t * inertial[0] + cos_t * inertial[1],
+            inertial[2],
+        ])
+    }
+}
+
+/// An ephemeris sampled by an external tool (e.g. Orekit, GMAT), linearly interpolated between
+/// the samples.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct TabulatedEphemeris {
+    /// The samples, sorted by time (seconds since the DTN epoch).
+    samples: Vec<(f64, Position)>,
+}
+
+impl TabulatedEphemeris {
+    /// Creates a tabulated ephemeris.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples` - The times (seconds since the DTN epoch) and the positions in the
+    ///   Earth-fixed frame, in km.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `TabulatedEphemeris`, covering the span of its samples.
+    pub fn new(mut samples: Vec<(f64, Position)>) -> Self {
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { samples }
+    }
+}
+
+impl Ephemeris for TabulatedEphemeris {
+    fn position(&self, dtn_seconds: f64) -> Option<Position> {
+        let idx = self
+            .samples
+            .partition_point(|(time, _)| *time <= dtn_seconds);
+        if idx == 0 {
+            return None;
+        }
+        let (t0, p0) = self.samples[idx - 1];
+        if t0 == dtn_seconds {
+            return Some(p0);
+        }
+        let (t1, p1) = self.samples.get(idx)?;
+        let ratio = (dtn_seconds - t0) / (t1 - t0);
+        Some([
+            p0[0] + ratio * (p1[0] - p0[0]),
+            p0[1] + ratio * (p1[1] - p0[1]),
+            p0[2] + ratio * (p1[2] - p0[2]),
+        ])
+    }
+}
+
+/// A ground station, on the WGS 84 ellipsoid.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct GroundStation {
+    /// The node of the station.
+    pub node: NodeID,
+    /// The geodetic latitude, in degrees.
+    pub latitude: f64,
+    /// The longitude, in degrees.
+    pub longitude: f64,
+    /// The altitude above the ellipsoid, in km.
+    pub altitude: f64,
+    /// The elevation mask, in degrees: the spacecraft are visible above it.
+    pub min_elevation: f64,
+}
+
+impl GroundStation {
+    /// The position of the station in the Earth-fixed frame, and its local vertical.
+    fn frame(&self) -> (Position, Position) {
+        let (sin_lat, cos_lat) = self.latitude.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.to_radians().sin_cos();
+        let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+        let normal = EARTH_RADIUS / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let position = [
+            (normal + self.altitude) * cos_lat * cos_lon,
+            (normal + self.altitude) * cos_lat * sin_lon,
+            (normal * (1.0 - e2) + self.altitude) * sin_lat,
+        ];
+        (position, [cos_lat * cos_lon, cos_lat * sin_lon, sin_lat])
+    }
+
+    /// Computes the elevation and the range of a spacecraft.
+    ///
+    /// # Parameters
+    ///
+    /// * `position` - The position of the spacecraft in the Earth-fixed frame, in km.
+    ///
+    /// # Returns
+    ///
+    /// * `(f64, f64)` - The elevation in degrees and the range in km.
+    pub fn look_at(&self, position: &Position) -> (f64, f64) {
+        let (station, up) = self.frame();
+        let line = [
+            position[0] - station[0],
+            position[1] - station[1],
+            position[2] - station[2],
+        ];
+        let range = (line[0] * line[0] + line[1] * line[1] + line[2] * line[2]).sqrt();
+        if range == 0.0 {
+            return (90.0, 0.0);
+        }
+        let sin_elevation = (line[0] * up[0] + line[1] * up[1] + line[2] * up[2]) / range;
+        (sin_elevation.clamp(-1.0, 1.0).asin().to_degrees(), range)
+    }
+}
+
+/// A visibility window between a spacecraft and a ground station, given to the link budget of
+/// `build_contacts` for each direction.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Pass {
+    /// The transmitting node.
+    pub tx_node: NodeID,
+    /// The receiving node.
+    pub rx_node: NodeID,
+    /// The start of the window.
+    pub start: Date,
+    /// The end of the window.
+    pub end: Date,
+    /// The highest elevation of the spacecraft during the window, in degrees.
+    pub max_elevation: f64,
+    /// The shortest range during the window, in km.
+    pub min_range: f64,
+    /// The longest range during the window, in km (e.g. for a conservative light-time delay,
+    /// `max_range / LIGHT_SPEED`).
+    pub max_range: f64,
+}
+
+/// Computes the visibility windows of a spacecraft from a ground station.
+///
+/// The elevation is sampled with the step, the crossings of the elevation mask being refined by
+/// bisection: the windows shorter than the step may be missed.
+///
+/// # Parameters
+///
+/// * `ephemeris` - The trajectory of the spacecraft.
+/// * `station` - The ground station.
+/// * `epoch` - The epoch of the plan dates.
+/// * `start` - The start of the search.
+/// * `end` - The end of the search.
+/// * `step` - The sampling step.
+///
+/// # Returns
+///
+/// * `Vec<(Date, Date)>` - The visibility windows, in chronological order.
+pub fn visibility_windows(
+    ephemeris: &dyn Ephemeris,
+    station: &GroundStation,
+    epoch: &Epoch,
+    start: Date,
+    end: Date,
+    step: Duration,
+) -> Vec<(Date, Date)> {
+    let visible = |time: f64| {
+        ephemeris
+            .position(epoch.dtn_seconds() + time)
+            .is_some_and(|position| station.look_at(&position).0 >= station.min_elevation)
+    };
+    // the time of the change of visibility between two samples
+    let crossing = |mut before: f64, mut after: f64, was_visible: bool| {
+        while after - before > 1e-3 {
+            let middle = 0.5 * (before + after);
+            if visible(middle) == was_visible {
+                before = middle;
+            } else {
+                after = middle;
+            }
+        }
+        after
+    };
+
+    let (start, end, step) = (
+        time_to_seconds(start),
+        time_to_seconds(end),
+        time_to_seconds(step),
+    );
+    if step <= 0.0 || end <= start {
+        return Vec::new();
+    }
+    let mut windows: Vec<(f64, f64)> = Vec::new();
+    let mut opened = visible(start).then_some(start);
+    let mut time = start;
+    while time < end {
+        let next = f64::min(time + step, end);
+        let next_visible = visible(next);
+        match opened {
+            None if next_visible => opened = Some(crossing(time, next, false)),
+            Some(open) if !next_visible => {
+                windows.push((open, crossing(time, next, true)));
+                opened = None;
+            }
+            _ => {}
+        }
+        time = next;
+    }
+    if let Some(open) = opened {
+        windows.push((open, end));
+    }
+    windows
+        .into_iter()
+        .map(|(start, end)| (time_from_seconds(start), time_from_seconds(end)))
+        .filter(|(start, end)| start < end)
+        .collect()
+}
+
+/// Builds the contacts between spacecraft and ground stations from their visibility windows
+/// (see `visibility_windows`), in both directions, the managers being created by a link budget.
+///
+/// # Parameters
+///
+/// * `spacecraft` - The nodes of the spacecraft with their trajectories.
+/// * `stations` - The ground stations.
+/// * `epoch` - The epoch of the plan dates.
+/// * `start` - The start of the plan.
+/// * `end` - The end of the plan.
+/// * `step` - The sampling step of the visibility.
+/// * `link_budget` - Creates the manager of a contact from its pass (e.g. an `EVLManager` with a
+///   rate depending on the range), or `None` to drop the contact (e.g. no uplink).
+/// * `semantics` - The semantics of the contacts.
+///
+/// # Returns
+///
+/// * `Vec<Contact<NM, CM>>` - The contacts, the spacecraft being the transmitters of the downlinks
+///   and the stations of the uplinks.
+#[allow(clippy::too_many_arguments)]
+pub fn build_contacts<NM: NodeManager, CM: ContactManager, F: FnMut(&Pass) -> Option<CM>>(
+    spacecraft: &[(NodeID, &dyn Ephemeris)],
+    stations: &[GroundStation],
+    epoch: &Epoch,
+    start: Date,
+    end: Date,
+    step: Duration,
+    mut link_budget: F,
+    semantics: ContactSemantics,
+) -> Vec<Contact<NM, CM>> {
+    let mut contacts = Vec::new();
+    for (node, ephemeris) in spacecraft {
+        for station in stations {
+            for (window_start, window_end) in
+                visibility_windows(*ephemeris, station, epoch, start, end, step)
+            {
+                // the geometry of the pass, sampled with the step (and at the window bounds)
+                let (from, to) = (time_to_seconds(window_start), time_to_seconds(window_end));
+                let mut pass = Pass {
+                    tx_node: *node,
+                    rx_node: station.node,
+                    start: window_start,
+                    end: window_end,
+                    max_elevation: f64::MIN,
+                    min_range: f64::MAX,
+                    max_range: 0.0,
+                };
+                let mut time = from;
+                loop {
+                    if let Some(position) = ephemeris.position(epoch.dtn_seconds() + time) {
+                        let (elevation, range) = station.look_at(&position);
+                        pass.max_elevation = f64::max(pass.max_elevation, elevation);
+                        pass.min_range = f64::min(pass.min_range, range);
+                        pass.max_range = f64::max(pass.max_range, range);
+                    }
+                    if time >= to {
+                        break;
+                    }
+                    time = f64::min(time + time_to_seconds(step), to);
+                }
+
+                for (tx_node, rx_node) in [(*node, station.node), (station.node, *node)] {
+                    let pass = Pass {
+                        tx_node,
+                        rx_node,
+                        ..pass
+                    };
+                    let Some(manager) = link_budget(&pass) else {
+                        continue;
+                    };
+                    let info = ContactInfo::new(tx_node, rx_node, pass.start, pass.end)
+                        .with_semantics(semantics);
+                    if let Some(contact) = Contact::try_new(info, manager) {
+                        contacts.push(contact);
+                    }
+                }
+            }
+        }
+    }
+    contacts
+}
@@ -0,0 +1,137 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::parsing::{ParseError, Span};
+
+/// The errors of the contact plan parsers (A-SABR, ION and tvg-util formats).
+///
+/// The messages are the ones of the former `String` errors, the errors tied to a token carry its
+/// span if the input tracks spans (see `Lexer::get_current_span`).
+#[derive(Debug)]
+pub enum ContactPlanError {
+    /// The contact plan could not be read.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// A token or an element does not follow the format.
+    Syntax {
+        /// The description of the error.
+        message: String,
+        /// The span of the offending token.
+        span: Option<Span>,
+    },
+    /// Two nodes share an id or a name.
+    DuplicateNode {
+        /// The description of the error.
+        message: String,
+        /// The span of the last token of the node declaration.
+        span: Option<Span>,
+    },
+    /// Nodes are referenced (e.g. by contacts) but not declared, or no node is declared.
+    MissingNode {
+        /// The description of the error.
+        message: String,
+    },
+    /// A node declaration is well formed but invalid.
+    MalformedNode {
+        /// The description of the error.
+        message: String,
+        /// The span of the last token of the node declaration.
+        span: Option<Span>,
+    },
+    /// A contact declaration is well formed but invalid (e.g. ending before its start), or
+    /// cannot be completed (e.g. an ION contact without range).
+    MalformedContact {
+        /// The description of the error.
+        message: String,
+        /// The span of the last token of the contact declaration.
+        span: Option<Span>,
+    },
+    /// An alias conflicts with a node name, another alias or a node id, or refers to an
+    /// unknown node.
+    InvalidAlias {
+        /// The description of the error.
+        message: String,
+    },
+}
+
+impl ContactPlanError {
+    /// Creates a `Syntax` error.
+    ///
+    /// # Parameters
+    ///
+    /// * `message` - The description of the error.
+    /// * `span` - The span of the offending token, if known.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The error.
+    pub fn syntax(message: String, span: Option<Span>) -> Self {
+        Self::Syntax { message, span }
+    }
+
+    /// Returns the description of the error, without the snippet of the span.
+    pub fn message(&self) -> String {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => error.to_string(),
+            Self::Syntax { message, .. }
+            | Self::DuplicateNode { message, .. }
+            | Self::MissingNode { message }
+            | Self::MalformedNode { message, .. }
+            | Self::MalformedContact { message, .. }
+            | Self::InvalidAlias { message } => message.clone(),
+        }
+    }
+
+    /// Returns the span of the offending token, if known.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Syntax { span, .. }
+            | Self::DuplicateNode { span, .. }
+            | Self::MalformedNode { span, .. }
+            | Self::MalformedContact { span, .. } => span.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ContactPlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())?;
+        if let Some(span) = self.span() {
+            write!(f, "\n{}", span.snippet())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ContactPlanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ContactPlanError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Keeps the `?` operator working in the functions returning `Result<_, String>`.
+impl From<ContactPlanError> for String {
+    fn from(error: ContactPlanError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<ContactPlanError> for ParseError {
+    fn from(error: ContactPlanError) -> Self {
+        let span = error.span().cloned();
+        ParseError::new(error.message(), span)
+    }
+}
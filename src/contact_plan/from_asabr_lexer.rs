@@ -1,15 +1,93 @@
+use crate::types::{HashMap, HashSet};
 use crate::{
     contact::{Contact, ContactInfo},
     contact_manager::ContactManager,
-    node::{Node, NodeInfo},
+    contact_plan::{
+        extract_loopback_contacts,
+        periodic::{parse_repeat, PeriodicContact},
+        semantics::{parse_headroom, resolve_overlaps, ContactSemantics},
+        ContactPlanError,
+    },
+    epoch::parse_epoch_declaration,
+    node::{ExclusionScope, Node, NodeInfo},
+    node_manager::none::NoManagement,
     parsing::{Dispatcher, Parser},
-    types::{NodeID, NodeName},
+    types::{Date, NodeID, NodeName, Token},
 };
 use crate::{
     node_manager::NodeManager,
-    parsing::{parse_components, DispatchParser, Lexer, ParsingState},
+    parsing::{parse_components, DispatchParser, Lexer, ParseError, ParsingState},
 };
-use std::{cmp::max, collections::HashSet};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::cmp::max;
+
+/// The behavior of `ASABRContactPlan::parse_with_options` when nodes appear in the contacts but
+/// are not declared.
+pub enum MissingNodePolicy<NM: NodeManager> {
+    /// The parsing fails, the behavior of `ASABRContactPlan::parse`.
+    Strict,
+    /// The missing nodes are created with the manager returned by the function, named after
+    /// their id, and reported as warnings.
+    Lenient(fn() -> NM),
+}
+
+/// The options of `ASABRContactPlan::parse_with_options`, strict by default.
+pub struct ParseOptions<NM: NodeManager> {
+    /// The behavior when nodes appear in the contacts but are not declared.
+    pub missing_nodes: MissingNodePolicy<NM>,
+    /// The interpretation of the plan assumptions, set on every contact (see `ContactSemantics`).
+    pub semantics: ContactSemantics,
+}
+
+impl<NM: NodeManager> Default for ParseOptions<NM> {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl<NM: NodeManager> ParseOptions<NM> {
+    /// Creates options preserving the behavior of `ASABRContactPlan::parse`.
+    pub fn strict() -> Self {
+        Self {
+            missing_nodes: MissingNodePolicy::Strict,
+            semantics: ContactSemantics::default(),
+        }
+    }
+
+    /// Creates options auto-creating the missing nodes with the managers returned by `manager`.
+    pub fn lenient_with(manager: fn() -> NM) -> Self {
+        Self {
+            missing_nodes: MissingNodePolicy::Lenient(manager),
+            semantics: ContactSemantics::default(),
+        }
+    }
+
+    /// Sets the interpretation of the plan assumptions, e.g. `ContactSemantics::ION` for the
+    /// plans written for ION.
+    ///
+    /// # Parameters
+    ///
+    /// * `semantics` - The semantics of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The options with the semantics.
+    pub fn with_semantics(mut self, semantics: ContactSemantics) -> Self {
+        self.semantics = semantics;
+        self
+    }
+}
+
+impl ParseOptions<NoManagement> {
+    /// Creates options auto-creating the missing nodes with `NoManagement`.
+    pub fn lenient() -> Self {
+        Self::lenient_with(|| NoManagement {})
+    }
+}
 
 /// `ContactPlan` is responsible for managing and validating the parsing of contacts and nodes
 /// in a network configuration. It tracks known node IDs and names to ensure uniqueness,
@@ -47,8 +125,8 @@ impl ASABRContactPlan {
     ///
     /// # Returns
     ///
-    /// * `Result<(), String>` - Returns `Ok(())` if the node was successfully added, or an error message
-    ///   if there is a conflict with an existing node ID or name.
+    /// * `Result<(), String>` - Returns `Ok(())` if the node was successfully added, or the description
+    ///   of the conflict with an existing node ID or name.
     ///
     /// # Type Parameters
     ///
@@ -67,7 +145,7 @@ impl ASABRContactPlan {
             return Err(format!("Two nodes have the same id ({})", node_id));
         }
         if known_node_names.contains(&node_name) {
-            return Err(format!("Two nodes have the same id ({})", node_name));
+            return Err(format!("Two nodes have the same name ({})", node_name));
         }
         let value = max(node.get_node_id(), node.get_node_id());
         *max_node_in_in_nodes = max(*max_node_in_in_nodes, value.into());
@@ -77,11 +155,88 @@ impl ASABRContactPlan {
         Ok(())
     }
 
+    /// Attaches the aliases declared with `alias` elements to their nodes.
+    ///
+    /// An alias must be unique among the node names and aliases, and a numeric alias (an endpoint
+    /// ID, usable as a bundle destination) must not be the id of a declared node.
+    ///
+    /// # Parameters
+    ///
+    /// * `aliases` - The aliases, with the id of their node and the position of their declaration.
+    /// * `nodes` - The nodes of the plan.
+    /// * `known_node_ids` - The ids of the nodes.
+    /// * `known_node_names` - The names of the nodes, updated with the aliases.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ContactPlanError>` - Returns `Ok(())` if all the aliases were attached, or an
+    ///   `InvalidAlias` error for the first invalid alias.
+    fn add_aliases<NM: NodeManager>(
+        aliases: Vec<(NodeID, NodeName, String)>,
+        nodes: &mut [Node<NM>],
+        known_node_ids: &HashSet<NodeID>,
+        known_node_names: &mut HashSet<NodeName>,
+    ) -> Result<(), ContactPlanError> {
+        for (node_id, alias, position) in aliases {
+            if known_node_names.contains(&alias) {
+                return Err(ContactPlanError::InvalidAlias {
+                    message: format!("Alias \"{}\" already in use ({})", alias, position),
+                });
+            }
+            if let Ok(alias_id) = alias.parse::<NodeID>() {
+                if known_node_ids.contains(&alias_id) {
+                    return Err(ContactPlanError::InvalidAlias {
+                        message: format!("Alias \"{}\" is the id of a node ({})", alias, position),
+                    });
+                }
+            }
+            let Some(node) = nodes.iter_mut().find(|node| node.info.id == node_id) else {
+                return Err(ContactPlanError::InvalidAlias {
+                    message: format!("Alias of unknown node {} ({})", node_id, position),
+                });
+            };
+            known_node_names.insert(alias.clone());
+            node.info.aliases.push(alias);
+        }
+        Ok(())
+    }
+
+    /// Parses nodes and contacts from a lexer, see `parse`.
+    ///
+    /// On failure, the error carries the span of the offending token or the last token of the
+    /// offending element, if the lexer tracks spans (see `ContactPlanError::span`).
+    ///
+    /// # Parameters
+    ///
+    /// * `lexer` - A mutable reference to a `Lexer` instance, which provides tokens from the input text.
+    /// * `node_marker_map` - An optional hash map that associates node markers with parsing functions.
+    /// * `contact_marker_map` - An optional hash map that associates contact markers with parsing functions.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), ParseError>` - Returns a tuple containing vectors of parsed
+    ///   nodes and contacts, or a `ParseError` if there is an issue during parsing.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_detailed<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM>,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM>,
+    >(
+        lexer: &mut dyn Lexer,
+        node_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<NM>>>,
+        contact_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<CM>>>,
+    ) -> Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), ParseError> {
+        Self::parse(lexer, node_marker_map, contact_marker_map).map_err(ParseError::from)
+    }
+
     /// Parses nodes and contacts from a lexer, while ensuring node ID and name uniqueness
     /// and consistency between node definitions and contacts.
     ///
     /// The lexer processes tokens from input text, and this method associates each parsed element
-    /// with a node or a contact. It uses marker maps to recognize elements based on predefined markers.
+    /// with a node or a contact. An `epoch <time>` element (ISO 8601 or `dtn:<seconds>`) allows the
+    /// following contacts to use absolute times, converted to dates relative to this epoch. It uses marker maps to recognize elements based on predefined markers.
+    /// An `alias <node id> <alias>` element declares another endpoint name or ID of a node.
+    /// A contact followed by a `repeat` clause is expanded into its occurrences (see `parse_periodic`).
+    /// The loopback contacts (from a node to itself) become delivery windows of their node.
     /// Do not provide the associated marker map if you plan to use a dyn NodeManager or dyn ContactManager.
     ///
     /// # Parameters
@@ -92,8 +247,8 @@ impl ASABRContactPlan {
     ///
     /// # Returns
     ///
-    /// * `Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), String>` - Returns a tuple containing vectors of parsed
-    ///   nodes and contacts, or an error message if there is an issue during parsing.
+    /// * `Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), ContactPlanError>` - Returns a tuple containing vectors of parsed
+    ///   nodes and contacts, or the error if there is an issue during parsing.
     ///
     /// # Type Parameters
     ///
@@ -108,14 +263,123 @@ impl ASABRContactPlan {
         lexer: &mut dyn Lexer,
         node_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<NM>>>,
         contact_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<CM>>>,
-    ) -> Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), String> {
+    ) -> Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>), ContactPlanError> {
+        Self::parse_with_options(
+            lexer,
+            node_marker_map,
+            contact_marker_map,
+            &ParseOptions::strict(),
+        )
+        .map(|(nodes, contacts, _)| (nodes, contacts))
+    }
+
+    /// Parses nodes and contacts from a lexer like `parse`, with the behavior for undeclared nodes
+    /// and the semantics of the contacts selected by `options`. The overlapping contacts are
+    /// handled by the overlap rule of the semantics (see `resolve_overlaps`).
+    ///
+    /// In lenient mode, the nodes referenced by the contacts (or skipped in the node ids) but not
+    /// declared are created, named after their id (suffixed with `_` on name conflicts). A warning
+    /// is returned for each of them.
+    ///
+    /// # Parameters
+    ///
+    /// * `lexer` - A mutable reference to a `Lexer` instance, which provides tokens from the input text.
+    /// * `node_marker_map` - An optional hash map that associates node markers with parsing functions.
+    /// * `contact_marker_map` - An optional hash map that associates contact markers with parsing functions.
+    /// * `options` - The parser options.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError>` - Returns a tuple containing vectors of parsed
+    ///   nodes, contacts and warnings, or the error if there is an issue during parsing.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_with_options<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM>,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM>,
+    >(
+        lexer: &mut dyn Lexer,
+        node_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<NM>>>,
+        contact_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<CM>>>,
+        options: &ParseOptions<NM>,
+    ) -> Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError> {
+        Self::parse_elements(lexer, node_marker_map, contact_marker_map, options, false)
+            .map(|(nodes, contacts, _, warnings)| (nodes, contacts, warnings))
+    }
+
+    /// Parses nodes and contacts from a lexer like `parse_with_options`, the periodic contacts
+    /// being returned as templates rather than expanded, for the multigraph to expand them lazily
+    /// (see `Multigraph::add_periodic`).
+    ///
+    /// A contact followed by `repeat <period> <count>` or `repeat <period> until <date>` is
+    /// repeated with the period, `count` times or while the occurrences start before `date`.
+    /// A `headroom <percentage>%` or `headroom <volume>` declared before `repeat` overrides the
+    /// headroom of the options for the contact (see `Headroom`).
+    /// The periodic delivery windows (loopback contacts) are expanded upfront. The occurrences are
+    /// not checked for overlaps with the other contacts.
+    ///
+    /// # Parameters
+    ///
+    /// * `lexer` - A mutable reference to a `Lexer` instance, which provides tokens from the input text.
+    /// * `node_marker_map` - An optional hash map that associates node markers with parsing functions.
+    /// * `contact_marker_map` - An optional hash map that associates contact markers with parsing functions.
+    /// * `options` - The parser options.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Node<NM>>, Vec<Contact<NM, CM>>, Vec<PeriodicContact<CM>>, Vec<String>), ContactPlanError>` -
+    ///   Returns a tuple containing vectors of parsed nodes, contacts, periodic contacts and
+    ///   warnings, or the error if there is an issue during parsing.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_periodic<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM>,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM>,
+    >(
+        lexer: &mut dyn Lexer,
+        node_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<NM>>>,
+        contact_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<CM>>>,
+        options: &ParseOptions<NM>,
+    ) -> Result<
+        (
+            Vec<Node<NM>>,
+            Vec<Contact<NM, CM>>,
+            Vec<PeriodicContact<CM>>,
+            Vec<String>,
+        ),
+        ContactPlanError,
+    > {
+        Self::parse_elements(lexer, node_marker_map, contact_marker_map, options, true)
+    }
+
+    /// Parses the elements of a plan, see `parse_with_options` and `parse_periodic`. The periodic
+    /// contacts are expanded upfront unless `lazy` is set.
+    #[allow(clippy::type_complexity)]
+    fn parse_elements<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM>,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM>,
+    >(
+        lexer: &mut dyn Lexer,
+        node_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<NM>>>,
+        contact_marker_map: Option<&Dispatcher<fn(&mut dyn Lexer) -> ParsingState<CM>>>,
+        options: &ParseOptions<NM>,
+        lazy: bool,
+    ) -> Result<
+        (
+            Vec<Node<NM>>,
+            Vec<Contact<NM, CM>>,
+            Vec<PeriodicContact<CM>>,
+            Vec<String>,
+        ),
+        ContactPlanError,
+    > {
         let mut contacts: Vec<Contact<NM, CM>> = Vec::new();
+        let mut periodic: Vec<PeriodicContact<CM>> = Vec::new();
         let mut nodes: Vec<Node<NM>> = Vec::new();
 
         let mut known_node_ids: HashSet<NodeID> = HashSet::new();
         let mut known_node_names: HashSet<NodeName> = HashSet::new();
         let mut max_node_id_in_contacts: usize = 0;
         let mut max_node_in_in_nodes: usize = 0;
+        let mut aliases: Vec<(NodeID, NodeName, String)> = Vec::new();
 
         loop {
             let res = lexer.consume_next_token();
@@ -125,7 +389,7 @@ impl ASABRContactPlan {
                     break;
                 }
                 ParsingState::Error(msg) => {
-                    return Err(msg);
+                    return Err(ContactPlanError::syntax(msg, lexer.get_current_span()));
                 }
                 ParsingState::Finished(element_type) => match element_type.as_str() {
                     "contact" => {
@@ -136,20 +400,72 @@ impl ASABRContactPlan {
                                 break;
                             }
                             ParsingState::Error(msg) => {
-                                return Err(msg);
+                                return Err(ContactPlanError::syntax(
+                                    msg,
+                                    lexer.get_current_span(),
+                                ));
                             }
                             ParsingState::Finished((info, manager)) => {
-                                if let Some(contact) = Contact::try_new(info, manager) {
+                                let mut info = info.with_semantics(options.semantics);
+                                if matches!(lexer.lookup(), ParsingState::Finished(token) if token == "headroom")
+                                {
+                                    lexer.consume_next_token();
+                                    info.semantics.headroom =
+                                        parse_headroom(lexer).map_err(|msg| {
+                                            ContactPlanError::syntax(msg, lexer.get_current_span())
+                                        })?;
+                                }
+                                if matches!(lexer.lookup(), ParsingState::Finished(token) if token == "repeat")
+                                {
+                                    lexer.consume_next_token();
+                                    let malformed = |message: String, lexer: &dyn Lexer| {
+                                        ContactPlanError::MalformedContact {
+                                            message: format!(
+                                                "{} ({})",
+                                                message,
+                                                lexer.get_current_position()
+                                            ),
+                                            span: lexer.get_current_span(),
+                                        }
+                                    };
+                                    let (period, limit) = parse_repeat(lexer).map_err(|msg| {
+                                        ContactPlanError::syntax(msg, lexer.get_current_span())
+                                    })?;
+                                    let mut template =
+                                        PeriodicContact::try_new(info, manager, period, limit)
+                                            .map_err(|message| malformed(message, lexer))?;
+                                    let value = max(info.tx_node, info.rx_node);
+                                    max_node_id_in_contacts =
+                                        max(max_node_id_in_contacts, value.into());
+                                    // the delivery windows are not edges, they are expanded upfront
+                                    if lazy && info.tx_node != info.rx_node {
+                                        periodic.push(template);
+                                    } else {
+                                        for contact in template
+                                            .expand_until(Date::MAX)
+                                            .map_err(|message| malformed(message, lexer))?
+                                        {
+                                            Self::add_contact(
+                                                contact,
+                                                &mut contacts,
+                                                &mut max_node_id_in_contacts,
+                                            );
+                                        }
+                                    }
+                                } else if let Some(contact) = Contact::try_new(info, manager) {
                                     Self::add_contact(
                                         contact,
                                         &mut contacts,
                                         &mut max_node_id_in_contacts,
                                     );
                                 } else {
-                                    return Err(format!(
-                                        "Malformed contact ({})",
-                                        lexer.get_current_position()
-                                    ));
+                                    return Err(ContactPlanError::MalformedContact {
+                                        message: format!(
+                                            "Malformed contact ({})",
+                                            lexer.get_current_position()
+                                        ),
+                                        span: lexer.get_current_span(),
+                                    });
                                 }
                             }
                         }
@@ -161,7 +477,10 @@ impl ASABRContactPlan {
                                 break;
                             }
                             ParsingState::Error(msg) => {
-                                return Err(msg);
+                                return Err(ContactPlanError::syntax(
+                                    msg,
+                                    lexer.get_current_span(),
+                                ));
                             }
                             ParsingState::Finished((info, manager)) => {
                                 if let Some(node) = Node::try_new(info, manager) {
@@ -173,40 +492,127 @@ impl ASABRContactPlan {
                                         &mut known_node_names,
                                     ) {
                                         Ok(_) => {}
-                                        Err(msg) => {
-                                            return Err(msg);
+                                        Err(message) => {
+                                            return Err(ContactPlanError::DuplicateNode {
+                                                message,
+                                                span: lexer.get_current_span(),
+                                            });
                                         }
                                     }
                                 } else {
-                                    return Err(format!(
-                                        "Malformed node ({})",
-                                        lexer.get_current_position()
-                                    ));
+                                    return Err(ContactPlanError::MalformedNode {
+                                        message: format!(
+                                            "Malformed node ({})",
+                                            lexer.get_current_position()
+                                        ),
+                                        span: lexer.get_current_span(),
+                                    });
                                 }
                             }
                         }
                     }
+                    "epoch" => parse_epoch_declaration(lexer).map_err(|message| {
+                        ContactPlanError::syntax(message, lexer.get_current_span())
+                    })?,
+                    "alias" => {
+                        let node_id = match <NodeID as Token<NodeID>>::parse(lexer) {
+                            ParsingState::Finished(value) => value,
+                            ParsingState::Error(msg) => {
+                                return Err(ContactPlanError::syntax(msg, lexer.get_current_span()))
+                            }
+                            ParsingState::EOF => break,
+                        };
+                        match lexer.consume_next_token() {
+                            ParsingState::Finished(alias) => {
+                                aliases.push((node_id, alias, lexer.get_current_position()))
+                            }
+                            ParsingState::Error(msg) => {
+                                return Err(ContactPlanError::syntax(msg, lexer.get_current_span()))
+                            }
+                            ParsingState::EOF => {
+                                return Err(ContactPlanError::syntax(
+                                    format!("Missing alias ({})", lexer.get_current_position()),
+                                    lexer.get_current_span(),
+                                ))
+                            }
+                        }
+                    }
                     _ => {
-                        return Err(format!(
-                            "Unrecognized CP element ({})",
-                            lexer.get_current_position()
+                        return Err(ContactPlanError::syntax(
+                            format!("Unrecognized CP element ({})", lexer.get_current_position()),
+                            lexer.get_current_span(),
                         ))
                     }
                 },
             }
         }
+        let mut warnings = Vec::new();
+        // an empty plan is not completed
+        let is_empty = nodes.is_empty() && contacts.is_empty();
+        if let (MissingNodePolicy::Lenient(manager), false) = (&options.missing_nodes, is_empty) {
+            let max_node_id = max(max_node_id_in_contacts, max_node_in_in_nodes);
+            for id in 0..=max_node_id {
+                let node_id = id as NodeID;
+                if known_node_ids.contains(&node_id) {
+                    continue;
+                }
+                let mut name = node_id.to_string();
+                while known_node_names.contains(&name) {
+                    name.push('_');
+                }
+                warnings.push(format!(
+                    "Node {} is not declared, created as \"{}\"",
+                    node_id, name
+                ));
+                let info = NodeInfo {
+                    id: node_id,
+                    name,
+                    excluded: false,
+                    exclusion_scope: ExclusionScope::Node,
+                    delivery_windows: Vec::new(),
+                    attributes: HashMap::new(),
+                    aliases: Vec::new(),
+                };
+                if let Some(node) = Node::try_new(info, manager()) {
+                    Self::add_node(
+                        node,
+                        &mut nodes,
+                        &mut max_node_in_in_nodes,
+                        &mut known_node_ids,
+                        &mut known_node_names,
+                    )
+                    .map_err(|message| ContactPlanError::DuplicateNode {
+                        message,
+                        span: None,
+                    })?;
+                }
+            }
+        }
         if max_node_id_in_contacts != max_node_in_in_nodes {
-            return Err(
-                "The max node numbers for the contact and node definitions do not match"
+            return Err(ContactPlanError::MissingNode {
+                message: "The max node numbers for the contact and node definitions do not match"
                     .to_string(),
-            );
+            });
         }
         if nodes.is_empty() {
-            return Err("Nodes must be declared".to_string());
+            return Err(ContactPlanError::MissingNode {
+                message: "Nodes must be declared".to_string(),
+            });
         }
         if nodes.len() - 1 != max_node_id_in_contacts {
-            return Err("Some node declarations are missing".to_string());
+            return Err(ContactPlanError::MissingNode {
+                message: "Some node declarations are missing".to_string(),
+            });
         }
-        Ok((nodes, contacts))
+        Self::add_aliases(aliases, &mut nodes, &known_node_ids, &mut known_node_names)?;
+        extract_loopback_contacts(&mut nodes, &mut contacts);
+        let overlaps = resolve_overlaps(&mut contacts).map_err(|message| {
+            ContactPlanError::MalformedContact {
+                message,
+                span: None,
+            }
+        })?;
+        warnings.extend(overlaps);
+        Ok((nodes, contacts, periodic, warnings))
     }
 }
@@ -9,25 +9,34 @@ use crate::{
         seg::{Segment, SegmentationManager},
         ContactManager,
     },
-    node::{Node, NodeInfo},
+    contact_plan::{
+        semantics::{resolve_overlaps, ContactSemantics},
+        ContactPlanError,
+    },
+    node::{DeliveryWindow, ExclusionScope, Node, NodeInfo},
     node_manager::{none::NoManagement, NodeManager},
-    types::{DataRate, Date, Duration, NodeID},
+    parsing::{split_words_with_columns, Span},
+    types::{rate_from_per_second, time_from_seconds, DataRate, Date, Duration, NodeID, TIME_ZERO},
 };
 
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, str::FromStr};
 use std::{
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{BufRead, BufReader},
 };
 
 pub struct IONContactData {
-    tx_start: Date,
-    tx_end: Date,
-    tx_node: NodeID,
-    rx_node: NodeID,
+    pub(super) tx_start: Date,
+    pub(super) tx_end: Date,
+    pub(super) tx_node: NodeID,
+    pub(super) rx_node: NodeID,
     data_rate: DataRate,
-    delay: Duration,
+    pub(super) delay: Duration,
     _confidence: f32,
+    /// The line declaring the contact, for error reporting.
+    pub(super) line: u32,
+    /// The semantics of the contact plan.
+    pub(super) semantics: ContactSemantics,
 }
 
 // Implement `Ord` and `PartialOrd` for sorting
@@ -57,6 +66,33 @@ impl PartialEq for IONContactData {
 
 impl Eq for IONContactData {}
 
+impl IONContactData {
+    /// Gathers the data of a contact from its `a contact` and `a range` commands.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        tx_start: Date,
+        tx_end: Date,
+        tx_node: NodeID,
+        rx_node: NodeID,
+        data_rate: DataRate,
+        delay: Duration,
+        confidence: f32,
+        line: u32,
+    ) -> Self {
+        Self {
+            tx_start,
+            tx_end,
+            tx_node,
+            rx_node,
+            data_rate,
+            delay,
+            _confidence: confidence,
+            line,
+            semantics: ContactSemantics::default(),
+        }
+    }
+}
+
 struct IONRangeData {
     tx_start: Date,
     tx_end: Date,
@@ -66,7 +102,8 @@ struct IONRangeData {
 }
 
 fn contact_info_from_tvg_data(data: &IONContactData) -> ContactInfo {
-    return ContactInfo::new(data.tx_node, data.rx_node, data.tx_start, data.tx_end);
+    return ContactInfo::new(data.tx_node, data.rx_node, data.tx_start, data.tx_end)
+        .with_semantics(data.semantics);
 }
 
 pub trait FromIONContactData<NM: NodeManager, CM: ContactManager> {
@@ -129,6 +166,10 @@ fn manage_aliases(
                     id: next as NodeID,
                     name: candidate_name.to_string(),
                     excluded: false,
+                    exclusion_scope: ExclusionScope::Node,
+                    delivery_windows: Vec::new(),
+                    attributes: HashMap::new(),
+                    aliases: Vec::new(),
                 },
                 NoManagement {},
             )
@@ -157,18 +198,103 @@ fn manage_contacts(
     }
 }
 
-fn get_confidence(vec: &Vec<String>) -> f32 {
-    if vec.len() >= 8 {
-        vec[7].parse::<f32>().unwrap()
-    } else {
-        1.0
+/// A line of an ION contact plan, split into words, for error reporting.
+pub(super) struct IONLine<'a> {
+    /// The line number (starting at 1).
+    pub(super) number: u32,
+    /// The byte offset of the line in the file.
+    pub(super) offset: usize,
+    /// The text of the line.
+    pub(super) text: &'a str,
+    /// The words of the line, with their offset in the line.
+    pub(super) words: Vec<(String, usize)>,
+}
+
+impl IONLine<'_> {
+    /// Builds a `Syntax` error pointing to the word at `index`, or to the whole line if the word
+    /// is missing.
+    pub(super) fn error(&self, message: String, index: usize) -> ContactPlanError {
+        let span = match self.words.get(index) {
+            Some((word, column)) => Span::new(
+                self.number,
+                index as u32 + 1,
+                self.offset,
+                *column,
+                word,
+                self.text,
+            ),
+            None => Span::new(self.number, 0, self.offset, 0, self.text, self.text),
+        };
+        ContactPlanError::syntax(format!("{} (line {})", message, self.number), Some(span))
+    }
+
+    /// Returns the word at `index`.
+    pub(super) fn word(&self, index: usize, name: &str) -> Result<&String, ContactPlanError> {
+        match self.words.get(index) {
+            Some((word, _)) => Ok(word),
+            None => Err(self.error(format!("Missing {}", name), index)),
+        }
+    }
+
+    /// Parses the word at `index`.
+    pub(super) fn parse<T: FromStr>(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> Result<T, ContactPlanError> {
+        let word = self.word(index, name)?;
+        word.parse::<T>()
+            .map_err(|_| self.error(format!("Unable to parse {} \"{}\"", name, word), index))
+    }
+
+    /// Parses the optional confidence (8th word), 1.0 if absent.
+    pub(super) fn confidence(&self) -> Result<f32, ContactPlanError> {
+        if self.words.len() >= 8 {
+            self.parse(7, "confidence")
+        } else {
+            Ok(1.0)
+        }
+    }
+}
+
+/// Builds a `MalformedContact` error for a contact, referring to the line declaring it.
+fn contact_error(message: &str, contact: &IONContactData) -> ContactPlanError {
+    ContactPlanError::MalformedContact {
+        message: format!("{} (line {})", message, contact.line),
+        span: None,
     }
 }
 
 impl IONContactPlan {
     pub fn parse<NM: NodeManager, CM: FromIONContactData<NM, CM> + ContactManager>(
         filename: &str,
-    ) -> io::Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>)> {
+    ) -> Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>), ContactPlanError> {
+        Self::parse_with_semantics(filename, ContactSemantics::default())
+            .map(|(nodes, contacts, _)| (nodes, contacts))
+    }
+
+    /// Parses an ION contact plan like `parse`, the contacts taking the semantics of the plan
+    /// (e.g. `ContactSemantics::ION`). The overlapping contacts are handled by the overlap rule of
+    /// the semantics (see `resolve_overlaps`).
+    ///
+    /// # Parameters
+    ///
+    /// * `filename` - The path of the contact plan.
+    /// * `semantics` - The semantics of the contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError>` -
+    ///   The nodes, the contacts and a warning for each contact dropped, or the first error.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_with_semantics<
+        NM: NodeManager,
+        CM: FromIONContactData<NM, CM> + ContactManager,
+    >(
+        filename: &str,
+        semantics: ContactSemantics,
+    ) -> Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError>
+    {
         let file = File::open(filename)?;
         let mut reader = BufReader::new(file);
         let mut map_id_map: HashMap<String, NodeID> = HashMap::new();
@@ -181,6 +307,9 @@ impl IONContactPlan {
         let mut contacts = vec![];
         let mut nodes = vec![];
 
+        let mut line_number: u32 = 0;
+        let mut offset: usize = 0;
+
         loop {
             let mut line = String::new();
             let bytes_read = reader.read_line(&mut line)?;
@@ -188,26 +317,50 @@ impl IONContactPlan {
             if bytes_read == 0 {
                 break;
             }
+            line_number += 1;
+            let line_offset = offset;
+            offset += bytes_read;
+
             // Skip lines starting with '#'
             if line.trim_start().starts_with('#') {
                 continue;
             }
-            let words: Vec<String> = line.split_whitespace().map(String::from).collect();
+            let ion_line = IONLine {
+                number: line_number,
+                offset: line_offset,
+                text: line.trim_end_matches(['\r', '\n']),
+                words: split_words_with_columns(&line),
+            };
 
-            if words.is_empty() {
+            if ion_line.words.is_empty() {
                 continue;
             }
 
-            if words[0].as_str() != "a" {
+            if ion_line.words[0].0.as_str() != "a" {
                 continue;
             }
-            if words[1].as_str() == "contact" {
-                let tx_start: Date = words[2].parse().unwrap();
-                let tx_end: Date = words[3].parse().unwrap();
-                let tx_node = manage_aliases(&mut map_id_map, &words[4], &mut nodes);
-                let rx_node = manage_aliases(&mut map_id_map, &words[5], &mut nodes);
-                let data_rate: DataRate = words[6].parse().unwrap();
-                let confidence = get_confidence(&words);
+            let command = ion_line.word(1, "command")?.as_str();
+            if command == "contact" {
+                let tx_start = time_from_seconds(ion_line.parse(2, "start time")?);
+                let tx_end = time_from_seconds(ion_line.parse(3, "end time")?);
+                let tx_name = ion_line.word(4, "transmitter")?;
+                let rx_name = ion_line.word(5, "receiver")?;
+                let data_rate = rate_from_per_second(ion_line.parse(6, "data rate")?);
+                let confidence = ion_line.confidence()?;
+                let tx_node = manage_aliases(&mut map_id_map, tx_name, &mut nodes);
+                let rx_node = manage_aliases(&mut map_id_map, rx_name, &mut nodes);
+
+                // registration contact, a delivery window rather than an edge
+                if tx_node == rx_node {
+                    nodes[tx_node as usize]
+                        .info
+                        .delivery_windows
+                        .push(DeliveryWindow {
+                            start: tx_start,
+                            end: tx_end,
+                        });
+                    continue;
+                }
                 contact_count += 1;
 
                 manage_contacts(
@@ -218,17 +371,25 @@ impl IONContactPlan {
                         tx_node,
                         rx_node,
                         data_rate,
-                        delay: 0.0,
+                        delay: TIME_ZERO,
                         _confidence: confidence,
+                        line: line_number,
+                        semantics,
                     },
                 );
             }
-            if words[1].as_str() == "range" {
-                let tx_start: Date = words[2].parse().unwrap();
-                let tx_end: Date = words[3].parse().unwrap();
-                let tx_node = manage_aliases(&mut map_id_map, &words[4], &mut nodes);
-                let rx_node = manage_aliases(&mut map_id_map, &words[5], &mut nodes);
-                let delay: Duration = words[6].parse().unwrap();
+            if command == "range" {
+                let tx_start = time_from_seconds(ion_line.parse(2, "start time")?);
+                let tx_end = time_from_seconds(ion_line.parse(3, "end time")?);
+                let tx_name = ion_line.word(4, "transmitter")?;
+                let rx_name = ion_line.word(5, "receiver")?;
+                let delay = time_from_seconds(ion_line.parse(6, "delay")?);
+                let tx_node = manage_aliases(&mut map_id_map, tx_name, &mut nodes);
+                let rx_node = manage_aliases(&mut map_id_map, rx_name, &mut nodes);
+                // the range of a registration contact is meaningless
+                if tx_node == rx_node {
+                    continue;
+                }
                 ranges.push(IONRangeData {
                     tx_start,
                     tx_end,
@@ -252,9 +413,15 @@ impl IONContactPlan {
                     for contact in contact_vec.iter_mut() {
                         if range.tx_start <= contact.tx_start && contact.tx_end <= range.tx_end {
                             contact.delay = range.delay;
-                            contacts.push(CM::ion_convert(contact).unwrap());
+                            match CM::ion_convert(contact) {
+                                Some(converted) => contacts.push(converted),
+                                None => return Err(contact_error("Invalid contact", contact)),
+                            }
                         } else {
-                            panic!("This parser only supports one range per contact");
+                            return Err(contact_error(
+                                "This parser only supports one range per contact",
+                                contact,
+                            ));
                         }
                     }
                 }
@@ -262,9 +429,18 @@ impl IONContactPlan {
         }
 
         if contacts.len() != contact_count {
-            panic!("At least one contact has no range");
+            return Err(ContactPlanError::MalformedContact {
+                message: String::from("At least one contact has no range"),
+                span: None,
+            });
         }
 
-        Ok((nodes, contacts))
+        let warnings = resolve_overlaps(&mut contacts).map_err(|message| {
+            ContactPlanError::MalformedContact {
+                message,
+                span: None,
+            }
+        })?;
+        Ok((nodes, contacts, warnings))
     }
 }
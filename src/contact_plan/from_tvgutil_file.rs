@@ -9,12 +9,16 @@ use crate::{
         seg::{Segment, SegmentationManager},
         ContactManager,
     },
-    node::{Node, NodeInfo},
+    contact_plan::{
+        semantics::{resolve_overlaps, ContactSemantics},
+        ContactPlanError,
+    },
+    node::{DeliveryWindow, ExclusionScope, Node, NodeInfo},
     node_manager::{none::NoManagement, NodeManager},
-    types::{DataRate, Date, Duration, NodeID},
+    types::{rate_from_per_second, time_from_seconds, DataRate, Date, Duration, NodeID},
 };
 
-use std::{collections::HashMap, io};
+use std::collections::HashMap;
 
 use serde_json::Value;
 use std::fs;
@@ -28,10 +32,13 @@ pub struct TVGUtilContactData {
     delay: Duration,
     data_rate: DataRate,
     _confidence: f32,
+    /// The semantics of the contact plan.
+    semantics: ContactSemantics,
 }
 
 fn contact_info_from_tvg_data(data: &TVGUtilContactData) -> ContactInfo {
-    return ContactInfo::new(data.tx_node, data.rx_node, data.tx_start, data.tx_end);
+    return ContactInfo::new(data.tx_node, data.rx_node, data.tx_start, data.tx_end)
+        .with_semantics(data.semantics);
 }
 
 pub trait FromTVGUtilContactData<NM: NodeManager, CM: ContactManager> {
@@ -78,18 +85,84 @@ impl FromTVGUtilContactData<NoManagement, SegmentationManager> for SegmentationM
 
 pub struct TVGUtilContactPlan {}
 
+/// Builds a `Syntax` error, the JSON values having no span.
+fn invalid(message: String) -> ContactPlanError {
+    ContactPlanError::syntax(message, None)
+}
+
+/// Converts the JSON array of a contact, returns `None` if the contact is malformed or invalid.
+fn parse_contact<NM: NodeManager, CM: FromTVGUtilContactData<NM, CM> + ContactManager>(
+    contact_data: &Value,
+    tx_node: NodeID,
+    rx_node: NodeID,
+    semantics: ContactSemantics,
+) -> Option<Contact<NM, CM>> {
+    let contact_array = contact_data.as_array()?;
+    let start = time_from_seconds(contact_array.get(2)?.as_f64()?);
+    let end = time_from_seconds(contact_array.get(3)?.as_f64()?);
+    let first_level_array = contact_array.get(4)?.as_array()?;
+    let second_level_array = first_level_array.first()?.as_array()?;
+    let confidence = second_level_array.get(1)?.as_f64()? as f32;
+    let third_level_array = second_level_array.get(2)?.as_array()?;
+    let fourth_level_array = third_level_array.first()?.as_array()?;
+    let data_rate = rate_from_per_second(fourth_level_array.get(1)?.as_f64()?);
+    let delay = time_from_seconds(fourth_level_array.get(2)?.as_f64()?);
+
+    let tvgcontact = TVGUtilContactData {
+        tx_start: start,
+        tx_end: end,
+        tx_node,
+        rx_node,
+        delay,
+        data_rate,
+        _confidence: confidence,
+        semantics,
+    };
+
+    CM::tvg_convert(tvgcontact)
+}
+
 impl TVGUtilContactPlan {
     pub fn parse<NM: NodeManager, CM: FromTVGUtilContactData<NM, CM> + ContactManager>(
         filename: &str,
-    ) -> io::Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>)> {
+    ) -> Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>), ContactPlanError> {
+        Self::parse_with_semantics(filename, ContactSemantics::default())
+            .map(|(nodes, contacts, _)| (nodes, contacts))
+    }
+
+    /// Parses a dtn-tvg-util contact plan like `parse`, the contacts taking the semantics of the
+    /// plan. The overlapping contacts are handled by the overlap rule of the semantics (see
+    /// `resolve_overlaps`).
+    ///
+    /// # Parameters
+    ///
+    /// * `filename` - The path of the contact plan.
+    /// * `semantics` - The semantics of the contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError>` -
+    ///   The nodes, the contacts and a warning for each contact dropped, or the first error.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_with_semantics<
+        NM: NodeManager,
+        CM: FromTVGUtilContactData<NM, CM> + ContactManager,
+    >(
+        filename: &str,
+        semantics: ContactSemantics,
+    ) -> Result<(Vec<Node<NoManagement>>, Vec<Contact<NM, CM>>, Vec<String>), ContactPlanError>
+    {
         let mut nodes: Vec<Node<NoManagement>> = Vec::new();
         let mut contacts: Vec<Contact<NM, CM>> = Vec::new();
 
         let mut map_id_map: HashMap<&str, NodeID> = HashMap::new();
 
         let json_data = fs::read_to_string(filename)?;
-        let parsed: Value = serde_json::from_str(&json_data).unwrap();
-        let json_nodes = parsed["vertices"].as_object().unwrap();
+        let parsed: Value = serde_json::from_str(&json_data)
+            .map_err(|e| invalid(format!("Malformed JSON ({})", e)))?;
+        let json_nodes = parsed["vertices"]
+            .as_object()
+            .ok_or_else(|| invalid(String::from("Missing \"vertices\" object")))?;
 
         for (node_id, (node_name, _node_data)) in json_nodes.iter().enumerate() {
             map_id_map.insert(&node_name, node_id as NodeID);
@@ -99,6 +172,10 @@ impl TVGUtilContactPlan {
                         id: node_id as NodeID,
                         name: node_name.to_string(),
                         excluded: false,
+                        exclusion_scope: ExclusionScope::Node,
+                        delivery_windows: Vec::new(),
+                        attributes: HashMap::new(),
+                        aliases: Vec::new(),
                     },
                     NoManagement {},
                 )
@@ -106,40 +183,63 @@ impl TVGUtilContactPlan {
             );
         }
 
-        let json_contacts = parsed["edges"].as_array().unwrap();
-        for nodes_pair in json_contacts {
-            let data = nodes_pair.as_object().unwrap();
-            let pair = data["vertices"].as_array().unwrap();
-            let tx_node = map_id_map.get(pair[0].as_str().unwrap()).unwrap();
-            let rx_node = map_id_map.get(pair[1].as_str().unwrap()).unwrap();
-
-            for contact_data in data["contacts"].as_array().unwrap() {
-                let contact_array = contact_data.as_array().unwrap();
-                let start = contact_array[2].as_f64().unwrap() as Date;
-                let end = contact_array[3].as_f64().unwrap() as Date;
-                let first_level_array = contact_array[4].as_array().unwrap();
-                let second_level_array = first_level_array[0].as_array().unwrap();
-                let confidence = second_level_array[1].as_f64().unwrap() as f32;
-                let third_level_array = second_level_array[2].as_array().unwrap();
-                let fourth_level_array = third_level_array[0].as_array().unwrap();
-                let data_rate = fourth_level_array[1].as_f64().unwrap() as DataRate;
-                let delay = fourth_level_array[2].as_f64().unwrap() as Duration;
-
-                let tvgcontact = TVGUtilContactData {
-                    tx_start: start,
-                    tx_end: end,
-                    tx_node: *tx_node,
-                    rx_node: *rx_node,
-                    delay,
-                    data_rate,
-                    _confidence: confidence,
-                };
-
-                let contact = CM::tvg_convert(tvgcontact).unwrap();
-
+        let json_contacts = parsed["edges"]
+            .as_array()
+            .ok_or_else(|| invalid(String::from("Missing \"edges\" array")))?;
+        for (edge_idx, nodes_pair) in json_contacts.iter().enumerate() {
+            let edge_error = |what: &str| invalid(format!("{} (edge {})", what, edge_idx));
+            let data = nodes_pair
+                .as_object()
+                .ok_or_else(|| edge_error("Malformed edge"))?;
+            let pair = data["vertices"]
+                .as_array()
+                .filter(|pair| pair.len() == 2)
+                .ok_or_else(|| edge_error("Malformed vertex pair"))?;
+            let mut node_ids = [0 as NodeID; 2];
+            for (node_id, vertex) in node_ids.iter_mut().zip(pair) {
+                let name = vertex
+                    .as_str()
+                    .ok_or_else(|| edge_error("Malformed vertex name"))?;
+                *node_id = *map_id_map
+                    .get(name)
+                    .ok_or_else(|| ContactPlanError::MissingNode {
+                        message: format!("Unknown vertex \"{}\" (edge {})", name, edge_idx),
+                    })?;
+            }
+            let [tx_node, rx_node] = node_ids;
+
+            let json_contact_list = data["contacts"]
+                .as_array()
+                .ok_or_else(|| edge_error("Missing \"contacts\" array"))?;
+            for (contact_idx, contact_data) in json_contact_list.iter().enumerate() {
+                let contact = parse_contact::<NM, CM>(contact_data, tx_node, rx_node, semantics)
+                    .ok_or_else(|| ContactPlanError::MalformedContact {
+                        message: format!(
+                            "Malformed contact {} (edge {}: {})",
+                            contact_idx, edge_idx, contact_data
+                        ),
+                        span: None,
+                    })?;
+                // registration contact, a delivery window rather than an edge
+                if tx_node == rx_node {
+                    nodes[tx_node as usize]
+                        .info
+                        .delivery_windows
+                        .push(DeliveryWindow {
+                            start: contact.info.start,
+                            end: contact.info.end,
+                        });
+                    continue;
+                }
                 contacts.push(contact);
             }
         }
-        Ok((nodes, contacts))
+        let warnings = resolve_overlaps(&mut contacts).map_err(|message| {
+            ContactPlanError::MalformedContact {
+                message,
+                span: None,
+            }
+        })?;
+        Ok((nodes, contacts, warnings))
     }
 }
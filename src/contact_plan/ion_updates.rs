@@ -0,0 +1,526 @@
+use crate::{
+    contact::ContactInfo,
+    contact_manager::ContactManager,
+    contact_plan::{
+        from_ion_file::{FromIONContactData, IONContactData, IONLine},
+        semantics::ContactSemantics,
+        ContactPlanError,
+    },
+    multigraph::Multigraph,
+    node::DeliveryWindow,
+    node_manager::NodeManager,
+    parsing::split_words_with_columns,
+    route_storage::StoredRouteInfo,
+    types::{ContactID, DataRate, Date, Duration, NodeID, Volume, TIME_ZERO, VOLUME_ZERO},
+};
+
+/// A command of an ION contact plan update stream (e.g. an uplinked `ionadmin` script).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+pub enum IONUpdate {
+    /// `a contact <start> <end> <from> <to> <rate> [confidence]`
+    AddContact {
+        /// The start time of the contact.
+        start: Date,
+        /// The end time of the contact.
+        end: Date,
+        /// The transmitting node.
+        tx_node: NodeID,
+        /// The receiving node.
+        rx_node: NodeID,
+        /// The data rate of the contact.
+        data_rate: DataRate,
+        /// The confidence of the contact.
+        confidence: f32,
+        /// The line of the command, for error reporting.
+        line: u32,
+    },
+    /// `d contact <start|*> <from> <to>`
+    DeleteContact {
+        /// The start time of the contact, all the contacts of the link if `None` (`*`).
+        start: Option<Date>,
+        /// The transmitting node.
+        tx_node: NodeID,
+        /// The receiving node.
+        rx_node: NodeID,
+    },
+    /// `a range <start> <end> <from> <to> <owlt>`
+    AddRange {
+        /// The start time of the range.
+        start: Date,
+        /// The end time of the range.
+        end: Date,
+        /// The transmitting node.
+        tx_node: NodeID,
+        /// The receiving node.
+        rx_node: NodeID,
+        /// The one-way light time.
+        delay: Duration,
+    },
+    /// `d range <start|*> <from> <to>`
+    DeleteRange {
+        /// The start time of the range, all the ranges of the link if `None` (`*`).
+        start: Option<Date>,
+        /// The transmitting node.
+        tx_node: NodeID,
+        /// The receiving node.
+        rx_node: NodeID,
+    },
+}
+
+/// A range known by an `IONPlanUpdater`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+struct IONRange {
+    start: Date,
+    end: Date,
+    tx_node: NodeID,
+    rx_node: NodeID,
+    delay: Duration,
+}
+
+/// A contact deletion conflicting with the transmissions already scheduled on the contact.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+pub struct IONConflict {
+    /// The ID of the contact.
+    pub id: ContactID,
+    /// The contact to delete.
+    pub info: ContactInfo,
+    /// The volume booked on the contact.
+    pub booked_volume: Volume,
+    /// Whether the contact was removed anyway (see `IONPlanUpdater::with_forced_deletes`).
+    pub removed: bool,
+}
+
+/// The changes made to a multigraph by `IONPlanUpdater::apply`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Default)]
+pub struct IONUpdateReport {
+    /// The IDs of the contacts inserted.
+    pub added: Vec<ContactID>,
+    /// The IDs of the contacts removed.
+    pub removed: Vec<ContactID>,
+    /// The deletions of contacts with scheduled volume.
+    pub conflicts: Vec<IONConflict>,
+    /// The commands that could not be applied (e.g. a contact without range).
+    pub rejected: Vec<ContactPlanError>,
+}
+
+impl IONUpdateReport {
+    /// Checks if a stored route uses a removed contact, i.e. if it must be dropped from the
+    /// route storage.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The summary of the stored route (e.g. from `TreeCache::trees`).
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if a contact of the route was removed.
+    pub fn affects(&self, route: &StoredRouteInfo) -> bool {
+        route
+            .signature
+            .contacts()
+            .iter()
+            .any(|id| self.removed.contains(id))
+    }
+
+    /// Selects the stored routes using a removed contact.
+    ///
+    /// The routes using none of the removed contacts stay valid, but they may no longer be the
+    /// best ones if contacts were added.
+    ///
+    /// # Parameters
+    ///
+    /// * `routes` - The summaries of the stored routes.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<StoredRouteInfo>` - The routes affected by the update.
+    pub fn affected_routes<I: IntoIterator<Item = StoredRouteInfo>>(
+        &self,
+        routes: I,
+    ) -> Vec<StoredRouteInfo> {
+        routes
+            .into_iter()
+            .filter(|route| self.affects(route))
+            .collect()
+    }
+}
+
+/// Applies ION contact plan update streams (add/delete contact, add/delete range) to a live
+/// multigraph, e.g. the plan updates uplinked to a spacecraft.
+///
+/// The updater keeps the ranges declared by the streams, as a contact takes its delay from the
+/// range covering it. The ranges of the initial plan can be declared by applying the initial plan
+/// itself: its contacts are already in the multigraph and are rejected as duplicates.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Default)]
+pub struct IONPlanUpdater {
+    /// The known ranges.
+    ranges: Vec<IONRange>,
+    /// Whether the contacts with scheduled volume are removed by the deletions.
+    force_deletes: bool,
+    /// The semantics of the contacts added.
+    semantics: ContactSemantics,
+}
+
+impl IONPlanUpdater {
+    /// Creates an updater without range, the deletions of the contacts with scheduled volume are
+    /// refused.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `IONPlanUpdater`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the contacts with scheduled volume are removed by the deletions. The
+    /// conflicts are reported in both cases.
+    ///
+    /// # Parameters
+    ///
+    /// * `force` - `true` to remove the contacts anyway.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updater with the deletion policy.
+    pub fn with_forced_deletes(mut self, force: bool) -> Self {
+        self.force_deletes = force;
+        self
+    }
+
+    /// Sets the semantics of the contacts added by the streams (the default semantics if not
+    /// set), e.g. the semantics the initial plan was parsed with.
+    ///
+    /// # Parameters
+    ///
+    /// * `semantics` - The semantics of the contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updater with the semantics.
+    pub fn with_semantics(mut self, semantics: ContactSemantics) -> Self {
+        self.semantics = semantics;
+        self
+    }
+
+    /// Parses an ION update stream. The `a`/`d` commands of contacts and ranges are kept, the
+    /// other lines are ignored like by `IONContactPlan::parse`.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - The multigraph to update, resolving the node names.
+    /// * `content` - The text of the update stream.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<IONUpdate>, ContactPlanError>` - The commands, or the first syntax error
+    ///   (including the unknown nodes, the nodes cannot be added to a live multigraph).
+    pub fn parse<NM: NodeManager, CM: ContactManager>(
+        multigraph: &Multigraph<NM, CM>,
+        content: &str,
+    ) -> Result<Vec<IONUpdate>, ContactPlanError> {
+        let mut updates = Vec::new();
+        let mut offset = 0;
+        for (idx, line) in content.split_inclusive('\n').enumerate() {
+            let line_offset = offset;
+            offset += line.len();
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let ion_line = IONLine {
+                number: idx as u32 + 1,
+                offset: line_offset,
+                text: line.trim_end_matches(['\r', '\n']),
+                words: split_words_with_columns(line),
+            };
+            let Some((action, _)) = ion_line.words.first() else {
+                continue;
+            };
+            let add = match action.as_str() {
+                "a" => true,
+                "d" => false,
+                _ => continue,
+            };
+            let command = ion_line.word(1, "command")?.as_str();
+            if command != "contact" && command != "range" {
+                continue;
+            }
+
+            let node = |index: usize, name: &str| -> Result<NodeID, ContactPlanError> {
+                let word = ion_line.word(index, name)?;
+                multigraph
+                    .resolve(word)
+                    .ok_or_else(|| ion_line.error(format!("Unknown node \"{}\"", word), index))
+            };
+            let update = if add {
+                let start: Date = ion_line.parse(2, "start time")?;
+                let end: Date = ion_line.parse(3, "end time")?;
+                let tx_node = node(4, "transmitter")?;
+                let rx_node = node(5, "receiver")?;
+                if command == "contact" {
+                    IONUpdate::AddContact {
+                        start,
+                        end,
+                        tx_node,
+                        rx_node,
+                        data_rate: ion_line.parse(6, "data rate")?,
+                        confidence: ion_line.confidence()?,
+                        line: ion_line.number,
+                    }
+                } else {
+                    IONUpdate::AddRange {
+                        start,
+                        end,
+                        tx_node,
+                        rx_node,
+                        delay: ion_line.parse(6, "delay")?,
+                    }
+                }
+            } else {
+                let start = match ion_line.word(2, "start time")?.as_str() {
+                    "*" => None,
+                    _ => Some(ion_line.parse(2, "start time")?),
+                };
+                let tx_node = node(3, "transmitter")?;
+                let rx_node = node(4, "receiver")?;
+                if command == "contact" {
+                    IONUpdate::DeleteContact {
+                        start,
+                        tx_node,
+                        rx_node,
+                    }
+                } else {
+                    IONUpdate::DeleteRange {
+                        start,
+                        tx_node,
+                        rx_node,
+                    }
+                }
+            };
+            updates.push(update);
+        }
+        Ok(updates)
+    }
+
+    /// Parses and applies an ION update stream, see `parse` and `apply`.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - The multigraph to update (e.g. from `Router::multigraph`).
+    /// * `content` - The text of the update stream.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<IONUpdateReport, ContactPlanError>` - The changes made, or the first syntax
+    ///   error (nothing is applied).
+    pub fn apply_str<NM: NodeManager, CM: FromIONContactData<NM, CM> + ContactManager>(
+        &mut self,
+        multigraph: &mut Multigraph<NM, CM>,
+        content: &str,
+    ) -> Result<IONUpdateReport, ContactPlanError> {
+        let updates = Self::parse(multigraph, content)?;
+        Ok(self.apply(multigraph, &updates))
+    }
+
+    /// Applies update commands to a live multigraph.
+    ///
+    /// The range commands are applied first, as the ION plans usually declare the ranges after
+    /// the contacts, then the contact commands in order:
+    /// * an added contact takes the delay of the range covering it, the contacts without range
+    ///   and the duplicates (same link and start time) are rejected,
+    /// * a deleted contact with scheduled volume (see `ContactManager::get_booked_volume`) is a
+    ///   conflict, and is only removed if the deletions are forced,
+    /// * the registration contacts (from a node to itself) are the delivery windows of their node.
+    ///
+    /// The ranges only apply to the contacts added afterwards, the managers of the contacts in the
+    /// multigraph are kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - The multigraph to update (e.g. from `Router::multigraph`).
+    /// * `updates` - The commands, see `parse`.
+    ///
+    /// # Returns
+    ///
+    /// * `IONUpdateReport` - The changes made, to select the stored routes to recompute (see
+    ///   `IONUpdateReport::affected_routes`).
+    pub fn apply<NM: NodeManager, CM: FromIONContactData<NM, CM> + ContactManager>(
+        &mut self,
+        multigraph: &mut Multigraph<NM, CM>,
+        updates: &[IONUpdate],
+    ) -> IONUpdateReport {
+        let mut report = IONUpdateReport::default();
+
+        for update in updates {
+            match *update {
+                IONUpdate::AddRange {
+                    start,
+                    end,
+                    tx_node,
+                    rx_node,
+                    delay,
+                } => {
+                    // a range redeclared replaces the previous one
+                    self.ranges.retain(|range| {
+                        !(range.tx_node == tx_node
+                            && range.rx_node == rx_node
+                            && range.start == start)
+                    });
+                    self.ranges.push(IONRange {
+                        start,
+                        end,
+                        tx_node,
+                        rx_node,
+                        delay,
+                    });
+                }
+                IONUpdate::DeleteRange {
+                    start,
+                    tx_node,
+                    rx_node,
+                } => {
+                    self.ranges.retain(|range| {
+                        !(range.tx_node == tx_node
+                            && range.rx_node == rx_node
+                            && start.is_none_or(|start| range.start == start))
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        for update in updates {
+            match *update {
+                IONUpdate::AddContact {
+                    start,
+                    end,
+                    tx_node,
+                    rx_node,
+                    data_rate,
+                    confidence,
+                    line,
+                } => {
+                    if tx_node == rx_node {
+                        multigraph.nodes[tx_node as usize]
+                            .borrow_mut()
+                            .info
+                            .delivery_windows
+                            .push(DeliveryWindow { start, end });
+                        continue;
+                    }
+                    if let Err(error) = self.add_contact(
+                        multigraph,
+                        IONContactData::new(
+                            start, end, tx_node, rx_node, data_rate, TIME_ZERO, confidence, line,
+                        ),
+                        &mut report,
+                    ) {
+                        report.rejected.push(error);
+                    }
+                }
+                IONUpdate::DeleteContact {
+                    start,
+                    tx_node,
+                    rx_node,
+                } => {
+                    if tx_node == rx_node {
+                        multigraph.nodes[tx_node as usize]
+                            .borrow_mut()
+                            .info
+                            .delivery_windows
+                            .retain(|window| start.is_some_and(|start| window.start != start));
+                        continue;
+                    }
+                    self.delete_contacts(multigraph, start, tx_node, rx_node, &mut report);
+                }
+                _ => {}
+            }
+        }
+
+        report
+    }
+
+    /// Inserts a contact with the delay of the range covering it.
+    fn add_contact<NM: NodeManager, CM: FromIONContactData<NM, CM> + ContactManager>(
+        &self,
+        multigraph: &mut Multigraph<NM, CM>,
+        mut data: IONContactData,
+        report: &mut IONUpdateReport,
+    ) -> Result<(), ContactPlanError> {
+        let error = |message: &str, data: &IONContactData| ContactPlanError::MalformedContact {
+            message: format!("{} (line {})", message, data.line),
+            span: None,
+        };
+
+        let duplicate = multigraph.senders[data.tx_node as usize]
+            .receivers
+            .iter()
+            .find(|receiver| receiver.node.borrow().info.id == data.rx_node)
+            .is_some_and(|receiver| {
+                receiver
+                    .contacts_to_receiver
+                    .iter()
+                    .any(|contact| contact.borrow().info.start == data.tx_start)
+            });
+        if duplicate {
+            return Err(error("Duplicate contact", &data));
+        }
+
+        let Some(range) = self.ranges.iter().find(|range| {
+            range.tx_node == data.tx_node
+                && range.rx_node == data.rx_node
+                && range.start <= data.tx_start
+                && data.tx_end <= range.end
+        }) else {
+            return Err(error("The contact has no range", &data));
+        };
+        data.delay = range.delay;
+        data.semantics = self.semantics;
+
+        let contact = CM::ion_convert(&data).ok_or_else(|| error("Invalid contact", &data))?;
+        let id = multigraph
+            .insert_contact(contact)
+            .map_err(|message| error(&message, &data))?;
+        report.added.push(id);
+        Ok(())
+    }
+
+    /// Removes the contacts of a link, unless they have scheduled volume and the deletions are
+    /// not forced.
+    fn delete_contacts<NM: NodeManager, CM: ContactManager>(
+        &self,
+        multigraph: &mut Multigraph<NM, CM>,
+        start: Option<Date>,
+        tx_node: NodeID,
+        rx_node: NodeID,
+        report: &mut IONUpdateReport,
+    ) {
+        let force = self.force_deletes;
+        let mut conflicts = Vec::new();
+        let removed = multigraph.remove_contacts(tx_node, rx_node, |contact| {
+            if start.is_some_and(|start| contact.info.start != start) {
+                return false;
+            }
+            match contact.manager.get_booked_volume(&contact.info) {
+                Some(booked_volume) if booked_volume > VOLUME_ZERO => {
+                    conflicts.push(IONConflict {
+                        id: contact.id,
+                        info: contact.info,
+                        booked_volume,
+                        removed: force,
+                    });
+                    force
+                }
+                _ => true,
+            }
+        });
+        report.conflicts.extend(conflicts);
+        report
+            .removed
+            .extend(removed.iter().map(|contact| contact.borrow().id));
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::epoch::Epoch;
+use crate::parsing::{split_words_with_columns, ParsingState, Span};
+
+/// The tokens of the line being read by a line-based lexer (`StrLexer`, `FileLexer`), with the
+/// tracking of the positions, the spans and the epoch they share.
+///
+/// The lexers only provide the lines: the comments (lines starting with `#`) and the empty lines
+/// are skipped here.
+pub(crate) struct LineTokens {
+    /// Tracks the current line number during lookup operations.
+    lookup_current_line: u32,
+    /// Tracks the line number from which the current token was consumed.
+    current_line: u32,
+    /// Tracks the token's position in the current line.
+    token_position: u32,
+    /// A stack that stores tokens (words) from the input, with their offset in the line, in reverse
+    /// order, for easy consumption.
+    buffer_stack: Vec<(String, usize)>,
+    /// The byte offset of the line of the buffered tokens.
+    line_offset: usize,
+    /// The text of the line of the buffered tokens.
+    line_text: String,
+    /// The span of the last consumed token.
+    span: Option<Span>,
+    /// The epoch declared in the input, if any.
+    epoch: Option<Epoch>,
+}
+
+impl LineTokens {
+    pub(crate) fn new() -> Self {
+        Self {
+            lookup_current_line: 0,
+            current_line: 0,
+            token_position: 0,
+            buffer_stack: Vec::new(),
+            line_offset: 0,
+            line_text: String::new(),
+            span: None,
+            epoch: None,
+        }
+    }
+
+    /// Tells whether all the tokens of the line were consumed, the next line must be pushed.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buffer_stack.is_empty()
+    }
+
+    /// Splits the next line of the input into words, storing them in reverse order in the buffer
+    /// stack to facilitate easy pop operations.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - The text of the line, without its line ending.
+    /// * `line_offset` - The byte offset of the line in the input.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the line is a comment or is empty, the next line must then be pushed.
+    pub(crate) fn push_line(&mut self, line: &str, line_offset: usize) -> bool {
+        self.lookup_current_line += 1;
+
+        // Skip lines starting with '#'
+        if line.trim_start().starts_with('#') {
+            return false;
+        }
+
+        let words = split_words_with_columns(line);
+        if words.is_empty() {
+            return false;
+        }
+
+        self.buffer_stack.extend(words.into_iter().rev());
+        self.line_offset = line_offset;
+        self.line_text = String::from(line);
+        true
+    }
+
+    /// Consumes and returns the next token of the line, see `Lexer::consume_next_token`.
+    pub(crate) fn consume(&mut self) -> ParsingState<String> {
+        match self.buffer_stack.pop() {
+            Some((word, column)) => {
+                if self.current_line != self.lookup_current_line {
+                    self.token_position = 0;
+                    self.current_line = self.lookup_current_line;
+                }
+                self.token_position += 1;
+                self.span = Some(Span::new(
+                    self.current_line,
+                    self.token_position,
+                    self.line_offset,
+                    column,
+                    &word,
+                    &self.line_text,
+                ));
+                ParsingState::Finished(word)
+            }
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Returns the next token of the line without consuming it, see `Lexer::lookup`.
+    pub(crate) fn lookup(&self) -> ParsingState<String> {
+        match self.buffer_stack.last() {
+            Some((word, _)) => ParsingState::Finished(word.to_string()),
+            None => ParsingState::EOF,
+        }
+    }
+
+    /// Returns the position of the last consumed token, see `Lexer::get_current_position`.
+    pub(crate) fn position(&self) -> String {
+        format!("line {}, token {}", self.current_line, self.token_position)
+    }
+
+    /// Returns the span of the last consumed token.
+    pub(crate) fn span(&self) -> Option<Span> {
+        self.span.clone()
+    }
+
+    /// Returns the epoch declared in the input, if any.
+    pub(crate) fn epoch(&self) -> Option<Epoch> {
+        self.epoch
+    }
+
+    /// Declares the epoch of the input.
+    pub(crate) fn set_epoch(&mut self, epoch: Epoch) {
+        self.epoch = Some(epoch);
+    }
+}
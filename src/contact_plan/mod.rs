@@ -1,4 +1,65 @@
+pub mod asabr_buffer_lexer;
+#[cfg(feature = "std")]
 pub mod asabr_file_lexer;
+pub mod asabr_str_lexer;
+pub mod compress;
+#[cfg(feature = "ephemeris")]
+pub mod ephemeris;
+pub mod error;
 pub mod from_asabr_lexer;
+#[cfg(feature = "std")]
 pub mod from_ion_file;
+#[cfg(feature = "std")]
 pub mod from_tvgutil_file;
+#[cfg(feature = "std")]
+pub mod ion_updates;
+mod line_tokens;
+pub mod periodic;
+pub mod sanitize;
+pub mod semantics;
+pub mod splice;
+pub mod template;
+
+pub use compress::compress_contacts;
+pub use error::ContactPlanError;
+pub use splice::{splice, SpliceChange, SplicedPlan};
+
+use alloc::vec::Vec;
+
+use crate::{
+    contact::Contact,
+    contact_manager::ContactManager,
+    node::{DeliveryWindow, Node},
+    node_manager::NodeManager,
+};
+
+/// Moves the registration (loopback) contacts, i.e. the contacts from a node to itself, to the
+/// delivery windows of their node.
+///
+/// The loopback contacts do not carry bundles between nodes, they only declare when a node can
+/// deliver to its local applications. The loopback contacts of unknown nodes are dropped.
+///
+/// # Parameters
+///
+/// * `nodes` - The nodes of the plan, updated with the delivery windows.
+/// * `contacts` - The contacts of the plan, the loopback contacts are removed.
+pub fn extract_loopback_contacts<NM: NodeManager, CM: ContactManager>(
+    nodes: &mut [Node<NM>],
+    contacts: &mut Vec<Contact<NM, CM>>,
+) {
+    contacts.retain(|contact| {
+        if contact.info.tx_node != contact.info.rx_node {
+            return true;
+        }
+        if let Some(node) = nodes
+            .iter_mut()
+            .find(|node| node.info.id == contact.info.tx_node)
+        {
+            node.info.delivery_windows.push(DeliveryWindow {
+                start: contact.info.start,
+                end: contact.info.end,
+            });
+        }
+        false
+    });
+}
@@ -0,0 +1,193 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    contact::{Contact, ContactInfo},
+    contact_manager::ContactManager,
+    epoch::parse_date,
+    node_manager::NodeManager,
+    parsing::{Lexer, ParsingState},
+    types::{parse_required, Date, Duration, TIME_ZERO},
+};
+
+/// The end of the repetitions of a periodic contact.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum RepeatLimit {
+    /// The count of occurrences, the first one included.
+    Count(usize),
+    /// The occurrences start before this date.
+    Until(Date),
+}
+
+/// A contact repeated with a fixed period (e.g. a daily ground station pass), the occurrences
+/// being expanded into the multigraph lazily (see `Multigraph::expand_periodic`) rather than
+/// materialized upfront.
+///
+/// The occurrence `k` is the contact shifted by `k * period`, its manager being created with
+/// `ContactManager::repeat`. An occurrence may cross the boundary of its period (e.g. a pass from
+/// 23:00 to 01:00 repeated daily), but the occurrences do not overlap: the contact lasts at most
+/// one period.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PeriodicContact<CM: ContactManager> {
+    /// The first occurrence.
+    info: ContactInfo,
+    /// The manager of the first occurrence, not initialized.
+    manager: CM,
+    /// The period of the repetitions.
+    period: Duration,
+    /// The end of the repetitions.
+    limit: RepeatLimit,
+    /// The index of the next occurrence to expand.
+    next: usize,
+}
+
+impl<CM: ContactManager> PeriodicContact<CM> {
+    /// Creates a new `PeriodicContact`.
+    ///
+    /// # Parameters
+    ///
+    /// * `info` - The contact information of the first occurrence.
+    /// * `manager` - The manager of the first occurrence, not initialized (see `Contact::try_new`).
+    /// * `period` - The period of the repetitions.
+    /// * `limit` - The end of the repetitions.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, String>` - The periodic contact, or an error message if the period is not
+    ///   positive or shorter than the contact, or if the manager cannot be repeated.
+    pub fn try_new(
+        info: ContactInfo,
+        manager: CM,
+        period: Duration,
+        limit: RepeatLimit,
+    ) -> Result<Self, String> {
+        if period <= TIME_ZERO {
+            return Err(format!("The period must be positive ({})", period));
+        }
+        if info.end - info.start > period {
+            return Err(format!(
+                "Contact {}->{} [{}, {}] is longer than its period ({})",
+                info.tx_node, info.rx_node, info.start, info.end, period
+            ));
+        }
+        if manager.repeat(TIME_ZERO).is_none() {
+            return Err(format!(
+                "The manager of contact {}->{} [{}, {}] cannot be repeated",
+                info.tx_node, info.rx_node, info.start, info.end
+            ));
+        }
+        Ok(Self {
+            info,
+            manager,
+            period,
+            limit,
+            next: 0,
+        })
+    }
+
+    /// Retrieves the contact information of the first occurrence.
+    pub fn get_info(&self) -> &ContactInfo {
+        &self.info
+    }
+
+    /// Retrieves the period of the repetitions.
+    pub fn get_period(&self) -> Duration {
+        self.period
+    }
+
+    /// Retrieves the end of the repetitions.
+    pub fn get_limit(&self) -> RepeatLimit {
+        self.limit
+    }
+
+    /// Retrieves the start of the next occurrence to expand.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Date>` - The start time, or `None` if all the occurrences were expanded.
+    pub fn next_start(&self) -> Option<Date> {
+        let start = self.info.start + self.period * self.next as Duration;
+        match self.limit {
+            RepeatLimit::Count(count) if self.next >= count => None,
+            RepeatLimit::Until(until) if start >= until => None,
+            _ => Some(start),
+        }
+    }
+
+    /// Tells whether all the occurrences were expanded.
+    pub fn is_exhausted(&self) -> bool {
+        self.next_start().is_none()
+    }
+
+    /// Expands the occurrences starting before `horizon` that were not expanded yet.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon` - The end of the planning horizon, `Date::MAX` to expand all the occurrences.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Contact<NM, CM>>, String>` - The contacts of the occurrences, in order, or an
+    ///   error message if an occurrence cannot be created.
+    pub fn expand_until<NM: NodeManager>(
+        &mut self,
+        horizon: Date,
+    ) -> Result<Vec<Contact<NM, CM>>, String> {
+        let mut contacts = Vec::new();
+        while let Some(start) = self.next_start() {
+            if start >= horizon {
+                break;
+            }
+            let offset = start - self.info.start;
+            let mut info = self.info;
+            info.start = start;
+            info.end += offset;
+            let contact = self
+                .manager
+                .repeat(offset)
+                .and_then(|manager| Contact::try_new(info, manager))
+                .ok_or_else(|| {
+                    format!(
+                        "Malformed occurrence {}->{} [{}, {}]",
+                        info.tx_node, info.rx_node, info.start, info.end
+                    )
+                })?;
+            contacts.push(contact);
+            self.next += 1;
+        }
+        Ok(contacts)
+    }
+}
+
+/// Parses the repetitions of a contact, after the `repeat` keyword.
+///
+/// ```text
+/// repeat <period> <count>
+/// repeat <period> until <date>
+/// ```
+///
+/// # Parameters
+///
+/// * `lexer` - The lexer used for parsing tokens.
+///
+/// # Returns
+///
+/// * `Result<(Duration, RepeatLimit), String>` - The period and the end of the repetitions, or an
+///   error message.
+pub fn parse_repeat(lexer: &mut dyn Lexer) -> Result<(Duration, RepeatLimit), String> {
+    let period = parse_required::<Duration>(lexer)?;
+    match lexer.lookup() {
+        ParsingState::Finished(token) if token == "until" => {
+            lexer.consume_next_token();
+            match parse_date(lexer) {
+                ParsingState::Finished(until) => Ok((period, RepeatLimit::Until(until))),
+                ParsingState::Error(msg) => Err(msg),
+                ParsingState::EOF => Err(format!(
+                    "Missing repetition end ({})",
+                    lexer.get_current_position()
+                )),
+            }
+        }
+        _ => Ok((period, RepeatLimit::Count(parse_required::<usize>(lexer)?))),
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
+
+use crate::{
+    epoch::{parse_absolute_time, Epoch},
+    types::{
+        duration_from_f64, rate_from_f64, rate_to_f64, time_to_f64, DataRate, Date, Duration,
+        NodeID,
+    },
+    workload::WorkloadTrace,
+};
+
+/// A small deterministic pseudo-random generator (SplitMix64).
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in [0, 1).
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Rewrites operational A-SABR contact plans (and their workload traces) into shareable ones, e.g.
+/// for bug reports, without leaking mission data.
+///
+/// The comments are dropped. The node names are replaced by `node<id>`, and the node attributes
+/// (`key=value` tokens) and aliases are dropped. The contact rates (including the `rate` intervals of the
+/// segmented contacts) and the bundle sizes can be scaled, and the contacts can be shifted in time
+/// by a random offset (the same for all the times of a contact, keeping the durations, including
+/// the `until` bound of the repetitions). The `epoch` declaration is dropped and the absolute
+/// times (ISO 8601 or `dtn:<seconds>`) are rewritten relative to it, the mission epoch not
+/// appearing in the output. The output only depends on the input and on the seed.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct PlanSanitizer {
+    /// The seed of the time jitters.
+    seed: u64,
+    /// Whether the node names and attributes are replaced.
+    anonymize_names: bool,
+    /// The factor applied to the contact rates.
+    rate_scale: f64,
+    /// The factor applied to the bundle sizes of the traces.
+    size_scale: f64,
+    /// The maximum absolute offset of the contacts and of the trace submissions.
+    time_jitter: Duration,
+}
+
+impl PlanSanitizer {
+    /// Creates a sanitizer anonymizing the node names, without scaling nor jitter.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the time jitters.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `PlanSanitizer`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            anonymize_names: true,
+            rate_scale: 1.0,
+            size_scale: 1.0,
+            time_jitter: duration_from_f64(0.0),
+        }
+    }
+
+    /// Enables or disables the replacement of the node names and attributes.
+    pub fn anonymize_names(mut self, anonymize_names: bool) -> Self {
+        self.anonymize_names = anonymize_names;
+        self
+    }
+
+    /// Sets the factor applied to the contact rates.
+    pub fn scale_rates(mut self, factor: f64) -> Self {
+        self.rate_scale = factor;
+        self
+    }
+
+    /// Sets the factor applied to the bundle sizes of the traces.
+    pub fn scale_sizes(mut self, factor: f64) -> Self {
+        self.size_scale = factor;
+        self
+    }
+
+    /// Sets the maximum absolute offset of the contacts and of the trace submissions.
+    pub fn jitter_times(mut self, max_offset: Duration) -> Self {
+        self.time_jitter = max_offset;
+        self
+    }
+
+    /// Draws an offset in [-time_jitter, time_jitter], not moving `earliest` before 0.
+    fn draw_offset(&self, rng: &mut SplitMix64, earliest: Date) -> Duration {
+        let jitter = time_to_f64(self.time_jitter);
+        let offset = duration_from_f64((rng.next_f64() * 2.0 - 1.0) * jitter);
+        let zero = duration_from_f64(0.0);
+        if earliest + offset < zero {
+            zero - earliest
+        } else {
+            offset
+        }
+    }
+
+    /// Sanitizes an A-SABR contact plan.
+    ///
+    /// # Parameters
+    ///
+    /// * `plan` - The text of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, String>` - The sanitized plan, or an error message if a contact or the
+    ///   epoch is malformed.
+    pub fn sanitize(&self, plan: &str) -> Result<String, String> {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut epoch = None;
+        let mut output = String::new();
+
+        for (line_idx, line) in plan.lines().enumerate() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some(element_type) = tokens.first() else {
+                continue;
+            };
+            if element_type.starts_with('#') {
+                continue;
+            }
+            let sanitized = match *element_type {
+                "epoch" => {
+                    epoch = Some(
+                        tokens
+                            .get(1)
+                            .and_then(|token| Epoch::from_token(token))
+                            .ok_or_else(|| format!("Malformed epoch (line {})", line_idx + 1))?,
+                    );
+                    continue;
+                }
+                "node" if self.anonymize_names => self.sanitize_node(&tokens),
+                "alias" if self.anonymize_names => continue,
+                "contact" => self
+                    .sanitize_contact(&tokens, epoch.as_ref(), &mut rng)
+                    .ok_or_else(|| format!("Malformed contact (line {})", line_idx + 1))?,
+                _ => tokens.iter().map(|token| token.to_string()).collect(),
+            };
+            output.push_str(&sanitized.join(" "));
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn sanitize_node(&self, tokens: &[&str]) -> Vec<String> {
+        let mut sanitized = Vec::with_capacity(tokens.len());
+        for (i, token) in tokens.iter().enumerate() {
+            match i {
+                2 if NodeID::from_str(tokens[1]).is_ok() => {
+                    sanitized.push(format!("node{}", tokens[1]))
+                }
+                _ if i > 2 && token.contains('=') => {}
+                _ => sanitized.push(token.to_string()),
+            }
+        }
+        sanitized
+    }
+
+    fn sanitize_contact(
+        &self,
+        tokens: &[&str],
+        epoch: Option<&Epoch>,
+        rng: &mut SplitMix64,
+    ) -> Option<Vec<String>> {
+        if tokens.len() < 5 {
+            return None;
+        }
+        // the absolute times are resolved with the epoch, the output only has relative times
+        let resolve = |token: &str| match parse_absolute_time(token) {
+            Some(dtn_seconds) => epoch.map(|epoch| epoch.to_relative(dtn_seconds)),
+            None => Date::from_str(token).ok(),
+        };
+        let start = resolve(tokens[3])?;
+        let end = resolve(tokens[4])?;
+        let offset = self.draw_offset(rng, start);
+        let mut sanitized: Vec<String> =
+            tokens[..3].iter().map(|token| token.to_string()).collect();
+        sanitized.push((start + offset).to_string());
+        sanitized.push((end + offset).to_string());
+
+        let rest = &tokens[5..];
+        // segmented contact: "rate <start> <end> <value>" and "delay <start> <end> <value>",
+        // otherwise "[marker] <rate> <delay>", the rate being the first number
+        let segmented = rest
+            .iter()
+            .any(|token| *token == "rate" || *token == "delay");
+        let mut rate_done = segmented;
+        let mut i = 0;
+        while i < rest.len() {
+            match rest[i] {
+                "rate" | "delay" if segmented && i + 3 < rest.len() => {
+                    sanitized.push(rest[i].to_string());
+                    sanitized.push((resolve(rest[i + 1])? + offset).to_string());
+                    sanitized.push((resolve(rest[i + 2])? + offset).to_string());
+                    if rest[i] == "rate" {
+                        let rate = DataRate::from_str(rest[i + 3]).ok()?;
+                        sanitized
+                            .push(rate_from_f64(rate_to_f64(rate) * self.rate_scale).to_string());
+                    } else {
+                        sanitized.push(rest[i + 3].to_string());
+                    }
+                    i += 4;
+                }
+                // "repeat <period> until <date>", the repetitions keep their count
+                "until" if i + 1 < rest.len() => {
+                    sanitized.push(rest[i].to_string());
+                    sanitized.push((resolve(rest[i + 1])? + offset).to_string());
+                    i += 2;
+                }
+                token => {
+                    if parse_absolute_time(token).is_some() {
+                        sanitized.push((resolve(token)? + offset).to_string());
+                    } else {
+                        match DataRate::from_str(token) {
+                            Ok(rate) if !rate_done => {
+                                sanitized.push(
+                                    rate_from_f64(rate_to_f64(rate) * self.rate_scale).to_string(),
+                                );
+                                rate_done = true;
+                            }
+                            _ => sanitized.push(token.to_string()),
+                        }
+                    }
+                    i += 1;
+                }
+            }
+        }
+        Some(sanitized)
+    }
+
+    /// Sanitizes a workload trace: scales the bundle sizes and jitters the submission times (the
+    /// expirations keep their delay to the submission).
+    ///
+    /// # Parameters
+    ///
+    /// * `trace` - The workload trace.
+    ///
+    /// # Returns
+    ///
+    /// * `WorkloadTrace` - The sanitized trace.
+    pub fn sanitize_trace(&self, trace: &WorkloadTrace) -> WorkloadTrace {
+        // independent from the contact jitters
+        let mut rng = SplitMix64::new(!self.seed);
+        let mut sanitized = trace.clone();
+        for entry in &mut sanitized.entries {
+            let offset = self.draw_offset(&mut rng, entry.time);
+            entry.time += offset;
+            entry.bundle.expiration += offset;
+            entry.bundle.size *= self.size_scale;
+        }
+        sanitized
+    }
+}
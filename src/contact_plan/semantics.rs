@@ -0,0 +1,297 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    contact::Contact,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    parsing::{Lexer, ParsingState},
+    types::{
+        volume_fits, volume_from_f64, volume_to_f64, Date, NodeID, Volume, VOLUME_EPSILON,
+        VOLUME_ZERO,
+    },
+};
+
+/// The handling of the overlapping contacts of a link (same transmitting and receiving nodes).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapRule {
+    /// The overlapping contacts are kept as parallel contacts (e.g. two radios on the link).
+    #[default]
+    Keep,
+    /// A plan with overlapping contacts is rejected.
+    Reject,
+    /// A contact overlapping an earlier contact of its link is dropped with a warning, like ION
+    /// refusing an overlapping contact insertion.
+    DropLater,
+}
+
+/// The rounding of the volume of a contact, computed from its rate and duration.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum VolumeRounding {
+    /// The volume is the exact product of the rate and the duration.
+    #[default]
+    Exact,
+    /// The volume is rounded down to a whole count of bytes, like the ION capacities.
+    FloorBytes,
+}
+
+/// The accounting of the bundles in the queue of a contact.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueAccounting {
+    /// A bundle is accepted if it fits in the residual volume of the contact.
+    #[default]
+    MustFit,
+    /// A bundle is accepted while the residual volume is positive, the last bundle may overbook
+    /// the contact.
+    Overbook,
+}
+
+/// The volume of a contact kept free by the bookings, e.g. the margin of an operator for the
+/// retransmissions and the control traffic.
+///
+/// The values are stored as `f32` for the contact information to stay compact (see
+/// `ContactInfo`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Headroom {
+    /// The whole volume can be booked.
+    #[default]
+    None,
+    /// The fraction of the volume kept free (e.g. `0.1` for the last 10%).
+    Fraction(f32),
+    /// The absolute volume kept free.
+    Reserve(f32),
+}
+
+impl Headroom {
+    /// Computes the volume kept free on a contact.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The volume of the contact (or the budget of the priority).
+    ///
+    /// # Returns
+    ///
+    /// * `Volume` - The volume that cannot be booked.
+    pub fn reserve(&self, capacity: Volume) -> Volume {
+        match self {
+            Self::None => VOLUME_ZERO,
+            Self::Fraction(fraction) => capacity * *fraction as f64,
+            Self::Reserve(reserve) => volume_from_f64(*reserve as f64),
+        }
+    }
+}
+
+/// Parses the headroom declared after a contact, `headroom <percentage>%` keeping a fraction of
+/// the volume free, `headroom <volume>` an absolute volume. The `headroom` token is consumed by
+/// the caller.
+///
+/// # Parameters
+///
+/// * `lexer` - The lexer, positioned after the `headroom` token.
+///
+/// # Returns
+///
+/// * `Result<Headroom, String>` - The headroom, or the description of the malformed value.
+pub fn parse_headroom(lexer: &mut dyn Lexer) -> Result<Headroom, String> {
+    let token = match lexer.consume_next_token() {
+        ParsingState::Finished(token) => token,
+        ParsingState::Error(msg) => return Err(msg),
+        ParsingState::EOF => {
+            return Err(format!(
+                "Missing headroom value ({})",
+                lexer.get_current_position()
+            ))
+        }
+    };
+    let (value, fraction) = match token.strip_suffix('%') {
+        Some(percentage) => (percentage, true),
+        None => (token.as_str(), false),
+    };
+    match value.parse::<f32>() {
+        Ok(value) if fraction && (0.0..=100.0).contains(&value) => {
+            Ok(Headroom::Fraction(value / 100.0))
+        }
+        Ok(value) if !fraction && value >= 0.0 => Ok(Headroom::Reserve(value)),
+        _ => Err(format!(
+            "Invalid headroom \"{}\" ({})",
+            token,
+            lexer.get_current_position()
+        )),
+    }
+}
+
+/// The interpretation of the contact plan assumptions, shared by the parser and the contact
+/// managers so that a plan means the same on every node of a deployment.
+///
+/// The presets match the semantics of some implementations (`STRICT_SABR`, `ION`, `RELAXED`),
+/// the default is the historical behavior of the library (overlapping contacts kept, exact
+/// volumes, the bundles must fit). The semantics are selected with
+/// `ParseOptions::with_semantics` (or the `parse_with_semantics` functions of the ION and
+/// dtn-tvg-util importers), or set on the `ContactInfo` before `Contact::try_new` for the plans
+/// built programmatically (see `resolve_overlaps` before the router construction). A
+/// headroom set with the options is the default of the plan, a contact declaring its own headroom
+/// overrides it.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct ContactSemantics {
+    /// The handling of the overlapping contacts of a link.
+    pub overlaps: OverlapRule,
+    /// The rounding of the contact volumes.
+    pub volume_rounding: VolumeRounding,
+    /// The accounting of the queued bundles.
+    pub queue_accounting: QueueAccounting,
+    /// The volume of the contacts kept free by the bookings.
+    pub headroom: Headroom,
+}
+
+impl ContactSemantics {
+    /// The SABR semantics (CCSDS 734.3-B): a link has a single contact at a time, the volumes are
+    /// exact and the bundles must fit.
+    pub const STRICT_SABR: Self = Self {
+        overlaps: OverlapRule::Reject,
+        volume_rounding: VolumeRounding::Exact,
+        queue_accounting: QueueAccounting::MustFit,
+        headroom: Headroom::None,
+    };
+
+    /// The ION semantics: the overlapping contacts are dropped, the volumes are whole bytes and
+    /// the bundles must fit.
+    pub const ION: Self = Self {
+        overlaps: OverlapRule::DropLater,
+        volume_rounding: VolumeRounding::FloorBytes,
+        queue_accounting: QueueAccounting::MustFit,
+        headroom: Headroom::None,
+    };
+
+    /// The relaxed semantics: the overlapping contacts are parallel contacts, and the contacts can
+    /// be overbooked by their last bundle.
+    pub const RELAXED: Self = Self {
+        overlaps: OverlapRule::Keep,
+        volume_rounding: VolumeRounding::Exact,
+        queue_accounting: QueueAccounting::Overbook,
+        headroom: Headroom::None,
+    };
+
+    /// Sets the volume of the contacts kept free by the bookings.
+    pub fn with_headroom(mut self, headroom: Headroom) -> Self {
+        self.headroom = headroom;
+        self
+    }
+
+    /// Rounds the volume of a contact, used by the managers at initialization.
+    ///
+    /// # Parameters
+    ///
+    /// * `volume` - The volume computed from the rate and the duration.
+    ///
+    /// # Returns
+    ///
+    /// * `Volume` - The volume of the contact.
+    pub fn round_volume(&self, volume: Volume) -> Volume {
+        match self.volume_rounding {
+            VolumeRounding::Exact => volume,
+            // the drift must not lose a byte
+            VolumeRounding::FloorBytes => {
+                volume_from_f64((volume_to_f64(volume) + 1e-6) as u64 as f64)
+            }
+        }
+    }
+
+    /// Tells whether a bundle can be queued on a contact, used by the managers. The headroom is
+    /// not available to the bundle.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size of the bundle.
+    /// * `residual` - The residual volume of the contact (or of the budget of the priority).
+    /// * `capacity` - The volume of the contact (or the budget of the priority), for the headroom.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the bundle is accepted.
+    pub fn accepts(&self, size: Volume, residual: Volume, capacity: Volume) -> bool {
+        let residual = residual - self.headroom.reserve(capacity);
+        match self.queue_accounting {
+            QueueAccounting::MustFit => volume_fits(size, residual),
+            QueueAccounting::Overbook => residual > VOLUME_EPSILON || volume_fits(size, residual),
+        }
+    }
+}
+
+/// Applies the overlap rule of the semantics of the contacts to a contact plan, e.g. before the
+/// construction of a router from contacts built programmatically.
+///
+/// The contacts are compared link by link, in the order of their start times. The rule of the
+/// later contact of an overlapping pair applies.
+///
+/// # Parameters
+///
+/// * `contacts` - The contacts of the plan, the dropped contacts are removed.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, String>` - A warning for each contact dropped, or an error message for
+///   the first overlap rejected.
+pub fn resolve_overlaps<NM: NodeManager, CM: ContactManager>(
+    contacts: &mut Vec<Contact<NM, CM>>,
+) -> Result<Vec<String>, String> {
+    let mut order: Vec<usize> = (0..contacts.len()).collect();
+    order.sort_by(|a, b| {
+        let (a, b) = (&contacts[*a].info, &contacts[*b].info);
+        (a.tx_node, a.rx_node).cmp(&(b.tx_node, b.rx_node)).then(
+            a.start
+                .partial_cmp(&b.start)
+                .unwrap_or(core::cmp::Ordering::Equal),
+        )
+    });
+
+    let mut warnings = Vec::new();
+    let mut dropped = Vec::new();
+    // the link of the previous contact and the latest end of the contacts kept on it
+    let mut current: Option<(NodeID, NodeID, Date)> = None;
+    for idx in order {
+        let info = &contacts[idx].info;
+        let end = match current {
+            Some((tx_node, rx_node, end)) if (tx_node, rx_node) == (info.tx_node, info.rx_node) => {
+                end
+            }
+            _ => {
+                current = Some((info.tx_node, info.rx_node, info.end));
+                continue;
+            }
+        };
+        if info.start >= end {
+            current = Some((info.tx_node, info.rx_node, info.end));
+            continue;
+        }
+        match info.semantics.overlaps {
+            OverlapRule::Keep => {
+                if info.end > end {
+                    current = Some((info.tx_node, info.rx_node, info.end));
+                }
+            }
+            OverlapRule::Reject => {
+                return Err(format!(
+                    "Contact {}->{} [{}, {}] overlaps an earlier contact of its link",
+                    info.tx_node, info.rx_node, info.start, info.end
+                ));
+            }
+            OverlapRule::DropLater => {
+                warnings.push(format!(
+                    "Contact {}->{} [{}, {}] overlaps an earlier contact of its link, dropped",
+                    info.tx_node, info.rx_node, info.start, info.end
+                ));
+                dropped.push(idx);
+            }
+        }
+    }
+
+    dropped.sort_unstable();
+    for idx in dropped.into_iter().rev() {
+        contacts.remove(idx);
+    }
+    Ok(warnings)
+}
@@ -0,0 +1,199 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    contact::{Contact, ContactInfo},
+    contact_manager::ContactManager,
+    node::Node,
+    node_manager::NodeManager,
+    types::{Date, TIME_ZERO},
+};
+
+/// A difference between the current plan and a spliced plan, for the selective invalidation of
+/// the caches (e.g. the routes using a removed or resized contact).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+pub enum SpliceChange {
+    /// A contact of the new plan absent from the current plan.
+    Added(ContactInfo),
+    /// A contact of the current plan absent from the spliced plan.
+    Removed(ContactInfo),
+    /// A contact of the current plan truncated at the switch time, or extended by the contact of
+    /// the new plan continuing it.
+    Resized {
+        /// The contact in the current plan.
+        before: ContactInfo,
+        /// The contact in the spliced plan.
+        after: ContactInfo,
+    },
+}
+
+/// The output of `splice`.
+pub struct SplicedPlan<NM: NodeManager, CM: ContactManager> {
+    /// The nodes of both plans, the new plan prevailing for the nodes declared twice.
+    pub nodes: Vec<Node<NM>>,
+    /// The contacts of the current plan before the switch time, and of the new plan after.
+    pub contacts: Vec<Contact<NM, CM>>,
+    /// The contacts changed by the splice, relative to the current plan.
+    pub changes: Vec<SpliceChange>,
+}
+
+fn same_link(a: &ContactInfo, b: &ContactInfo) -> bool {
+    a.tx_node == b.tx_node && a.rx_node == b.rx_node
+}
+
+fn same_contact(a: &ContactInfo, b: &ContactInfo) -> bool {
+    same_link(a, b) && a.start == b.start && a.end == b.end
+}
+
+/// Rebuilds a contact for new contact times, with a fresh manager (see `ContactManager::repeat`),
+/// the bookings of the contact being dropped.
+fn resize<NM: NodeManager, CM: ContactManager>(
+    contact: Contact<NM, CM>,
+    start: Date,
+    end: Date,
+) -> Result<Contact<NM, CM>, String> {
+    let before = contact.info;
+    let refused = || {
+        format!(
+            "Contact {}->{} [{}, {}] cannot be resized to [{}, {}] by its manager",
+            before.tx_node, before.rx_node, before.start, before.end, start, end
+        )
+    };
+    let manager = contact.manager.repeat(TIME_ZERO).ok_or_else(refused)?;
+    let info = ContactInfo {
+        start,
+        end,
+        ..before
+    };
+    let mut resized = Contact::try_new(info, manager).ok_or_else(refused)?;
+    resized.metadata = contact.metadata;
+    #[cfg(feature = "contact_suppression")]
+    {
+        resized.suppressed = contact.suppressed;
+    }
+    Ok(resized)
+}
+
+/// Merges two contact plans at a boundary time, for the rolling plan updates (plans uploaded in
+/// overlapping batches).
+///
+/// The current plan is kept before `switch_time` and replaced by the new plan after:
+/// * the contacts of the current plan starting after the switch are dropped, and the ones
+///   spanning it are truncated at the switch time,
+/// * the contacts of the new plan ending before the switch are dropped, and the ones spanning it
+///   start at the switch time,
+/// * a contact spanning the switch in both plans (same link, overlapping) is kept as a single
+///   contact, from the start of the current one to the end of the new one, with the manager of the
+///   new one.
+///
+/// The contacts with the same link and times in both plans are reported as unchanged, and take
+/// the manager of the new plan (the managers are not compared). The resized contacts take a fresh
+/// manager with the same parameters (their bookings are dropped), which may refuse the new times
+/// (e.g. the segmented managers, whose segments must cover the contact exactly); the managers that
+/// cannot be repeated (see `ContactManager::repeat`) cannot be resized.
+///
+/// # Parameters
+///
+/// * `current_plan` - The nodes and contacts of the plan in use.
+/// * `new_plan` - The nodes and contacts of the uploaded plan.
+/// * `switch_time` - The time from which the new plan prevails.
+///
+/// # Returns
+///
+/// * `Result<SplicedPlan<NM, CM>, String>` - The spliced plan with the changes, or an error
+///   message if a manager refuses a resize.
+pub fn splice<NM: NodeManager, CM: ContactManager>(
+    current_plan: (Vec<Node<NM>>, Vec<Contact<NM, CM>>),
+    new_plan: (Vec<Node<NM>>, Vec<Contact<NM, CM>>),
+    switch_time: Date,
+) -> Result<SplicedPlan<NM, CM>, String> {
+    let (current_nodes, current_contacts) = current_plan;
+    let (mut nodes, new_contacts) = new_plan;
+
+    for node in current_nodes {
+        if !nodes.iter().any(|known| known.info.id == node.info.id) {
+            nodes.push(node);
+        }
+    }
+
+    let mut contacts = Vec::with_capacity(current_contacts.len() + new_contacts.len());
+    let mut changes = Vec::new();
+    // the contacts of the current plan starting after the switch, and the ones spanning it
+    let mut replaced: Vec<ContactInfo> = Vec::new();
+    let mut spanning: Vec<Contact<NM, CM>> = Vec::new();
+
+    for contact in current_contacts {
+        if contact.info.end <= switch_time {
+            contacts.push(contact);
+        } else if contact.info.start >= switch_time {
+            replaced.push(contact.info);
+        } else {
+            spanning.push(contact);
+        }
+    }
+
+    for contact in new_contacts {
+        let info = contact.info;
+        if info.end <= switch_time {
+            continue;
+        }
+        if info.start >= switch_time {
+            match replaced.iter().position(|known| same_contact(known, &info)) {
+                Some(idx) => {
+                    replaced.swap_remove(idx);
+                }
+                None => changes.push(SpliceChange::Added(info)),
+            }
+            contacts.push(contact);
+            continue;
+        }
+        // spanning the switch, continuing a contact of the current plan if any
+        let continued = spanning.iter().position(|known| {
+            same_link(&known.info, &info)
+                && known.info.start < info.end
+                && info.start < known.info.end
+        });
+        match continued {
+            Some(idx) => {
+                let before = spanning.swap_remove(idx).info;
+                let merged = if info.start == before.start {
+                    contact
+                } else {
+                    resize(contact, before.start, info.end)?
+                };
+                if merged.info.end != before.end {
+                    changes.push(SpliceChange::Resized {
+                        before,
+                        after: merged.info,
+                    });
+                }
+                contacts.push(merged);
+            }
+            None => {
+                let truncated = resize(contact, switch_time, info.end)?;
+                changes.push(SpliceChange::Added(truncated.info));
+                contacts.push(truncated);
+            }
+        }
+    }
+
+    // the spanning contacts not continued by the new plan end at the switch
+    for contact in spanning {
+        let before = contact.info;
+        let truncated = resize(contact, before.start, switch_time)?;
+        changes.push(SpliceChange::Resized {
+            before,
+            after: truncated.info,
+        });
+        contacts.push(truncated);
+    }
+    for before in replaced {
+        changes.push(SpliceChange::Removed(before));
+    }
+
+    Ok(SplicedPlan {
+        nodes,
+        contacts,
+        changes,
+    })
+}
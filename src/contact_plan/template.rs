@@ -0,0 +1,150 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::{
+    epoch::Epoch,
+    parsing::{Lexer, ParsingState, Span},
+    types::HashMap,
+};
+
+/// A lexer substituting the variables of a contact plan template, wrapping the lexer of the
+/// template (e.g. a `StrLexer` or a `FileLexer`).
+///
+/// A variable is written `$NAME` in a token, `NAME` being made of ASCII letters, digits and
+/// underscores (e.g. `contact 0 1 $START $END $RATE_HI`, or `rate=$RATE` in the attributes), and
+/// is replaced by its value before the token is parsed, so that one template can be instantiated
+/// across parameter sweeps. A `$` not followed by a name is kept as is. Using a variable without
+/// value is a parsing error.
+pub struct TemplateLexer<L: Lexer> {
+    /// The lexer of the template.
+    inner: L,
+    /// The values of the variables, by name (without the `$`).
+    variables: HashMap<String, String>,
+}
+
+impl<L: Lexer> TemplateLexer<L> {
+    /// Creates a `TemplateLexer` without variable values.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The lexer of the template.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `TemplateLexer`.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Sets the value of a variable.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the variable, without the `$`.
+    /// * `value` - The value, e.g. a rate or a time.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The lexer with the value.
+    pub fn with_variable<V: ToString>(mut self, name: &str, value: V) -> Self {
+        self.variables.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the values of several variables, replacing the values already set for the same names.
+    ///
+    /// # Parameters
+    ///
+    /// * `variables` - The values, by name (without the `$`).
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The lexer with the values.
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables.extend(variables);
+        self
+    }
+
+    /// Returns the wrapped lexer.
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+
+    /// Replaces the variables of a token.
+    fn substitute(&self, token: String) -> ParsingState<String> {
+        if !token.contains('$') {
+            return ParsingState::Finished(token);
+        }
+        let mut output = String::with_capacity(token.len());
+        let mut rest = token.as_str();
+        while let Some(dollar) = rest.find('$') {
+            output.push_str(&rest[..dollar]);
+            let after = &rest[dollar + 1..];
+            let name_len = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            if name_len == 0 {
+                output.push('$');
+                rest = after;
+                continue;
+            }
+            let name = &after[..name_len];
+            match self.variables.get(name) {
+                Some(value) => output.push_str(value),
+                None => {
+                    return ParsingState::Error(format!(
+                        "Undefined variable \"${}\" ({})",
+                        name,
+                        self.inner.get_current_position()
+                    ))
+                }
+            }
+            rest = &after[name_len..];
+        }
+        output.push_str(rest);
+        ParsingState::Finished(output)
+    }
+}
+
+impl<L: Lexer> Lexer for TemplateLexer<L> {
+    /// Looks up the next token, with its variables replaced.
+    fn lookup(&mut self) -> ParsingState<String> {
+        match self.inner.lookup() {
+            ParsingState::Finished(token) => self.substitute(token),
+            other => other,
+        }
+    }
+
+    /// Consumes the next token, with its variables replaced.
+    fn consume_next_token(&mut self) -> ParsingState<String> {
+        match self.inner.consume_next_token() {
+            ParsingState::Finished(token) => self.substitute(token),
+            other => other,
+        }
+    }
+
+    /// Returns the current position in the template.
+    fn get_current_position(&self) -> String {
+        self.inner.get_current_position()
+    }
+
+    /// Returns the span of the last consumed token, in the template.
+    fn get_current_span(&self) -> Option<Span> {
+        self.inner.get_current_span()
+    }
+
+    /// Returns the epoch declared in the template, if any.
+    fn get_epoch(&self) -> Option<Epoch> {
+        self.inner.get_epoch()
+    }
+
+    /// Declares the epoch of the template.
+    fn set_epoch(&mut self, epoch: Epoch) -> bool {
+        self.inner.set_epoch(epoch)
+    }
+}
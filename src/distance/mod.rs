@@ -1,11 +1,14 @@
-use std::cmp::Ordering;
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use alloc::rc::Rc;
+use core::cmp::Ordering;
+use core::{cell::RefCell, marker::PhantomData};
 
 use crate::node_manager::NodeManager;
 use crate::{contact_manager::ContactManager, route_stage::RouteStage};
 
 pub mod hop;
+pub mod probabilistic;
 pub mod sabr;
+pub mod soft_deadline;
 
 /// A trait that allows RouteStages to define custom distance comparison strategies.
 ///
@@ -63,7 +66,7 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> DistanceWrapper<N
 }
 
 impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Ord for DistanceWrapper<NM, CM, D> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         D::cmp(&self.0.borrow(), &other.0.borrow())
     }
 }
@@ -71,7 +74,7 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Ord for DistanceW
 impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> PartialOrd
     for DistanceWrapper<NM, CM, D>
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -0,0 +1,131 @@
+use core::{cmp::Ordering, marker::PhantomData};
+
+use crate::{
+    contact::Contact,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    node_manager::NodeManager,
+    pathfinding::hybrid_parenting::HybridParentingOrd,
+    route_stage::{RouteStage, StageAnnotator},
+    types::{duration_from_f64, Date},
+};
+
+use super::Distance;
+
+/// The probability that a route stage is reached, the product of the confidences of the contacts
+/// of the route (see `ContactManager::get_confidence`), annotated by `ProbabilityAnnotator`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct DeliveryProbability(pub f32);
+
+/// Retrieves the probability that a stage is reached, 1.0 if not annotated (e.g. the source).
+#[inline(always)]
+pub fn delivery_probability<NM: NodeManager, CM: ContactManager>(
+    stage: &RouteStage<NM, CM>,
+) -> f32 {
+    stage
+        .annotations
+        .get::<DeliveryProbability>()
+        .map_or(1.0, |probability| probability.0)
+}
+
+/// The `StageAnnotator` of the `Probabilistic` distance, accumulating the `DeliveryProbability`
+/// of the stages.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ProbabilityAnnotator {}
+
+impl<NM: NodeManager, CM: ContactManager> StageAnnotator<NM, CM> for ProbabilityAnnotator {
+    #[inline(always)]
+    fn annotate(
+        proposition: &mut RouteStage<NM, CM>,
+        parent: &RouteStage<NM, CM>,
+        contact: &Contact<NM, CM>,
+        _tx_data: &ContactManagerTxData,
+    ) -> bool {
+        let probability = delivery_probability(parent) * contact.manager.get_confidence();
+        proposition
+            .annotations
+            .insert(DeliveryProbability(probability));
+        true
+    }
+}
+
+/// A distance wrapper for the plans mixing scheduled contacts with predicted encounters (e.g.
+/// `PredictedManager`), penalizing the arrival time of the stages by their improbability.
+///
+/// The stages are compared by `at_time + (1 - p) * PENALTY`, `p` being the `DeliveryProbability`
+/// of the stage and `PENALTY` a duration in time units (an hour by default): a predicted segment
+/// is taken if it saves more than its penalty over the scheduled routes. The stages with the
+/// same penalized time are ordered by `D`. Use it with the `ProbabilityAnnotator`, e.g.
+/// `NodeParentingTreeExcl<NM, CM, Probabilistic<SABR>, ProbabilityAnnotator>`, the routes being
+/// considered certain otherwise.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Probabilistic<D, const PENALTY: u32 = 3600> {
+    #[doc(hidden)]
+    _phantom: PhantomData<D>,
+}
+
+/// The arrival time of a stage penalized by its improbability.
+#[inline(always)]
+fn penalized_time<NM: NodeManager, CM: ContactManager, const PENALTY: u32>(
+    stage: &RouteStage<NM, CM>,
+) -> Date {
+    let improbability = 1.0 - delivery_probability(stage) as f64;
+    stage.at_time + duration_from_f64(improbability * PENALTY as f64)
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>, const PENALTY: u32> Distance<NM, CM>
+    for Probabilistic<D, PENALTY>
+{
+    /// Compares two `RouteStage` instances by their penalized arrival times.
+    ///
+    /// The comparison follows these rules, in descending order of priority:
+    /// 1. Penalized time: The `RouteStage` with a later penalized arrival time is considered greater.
+    /// 2. The ordering of `D`.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Greater` if `first` is considered greater than `second` based on the criteria.
+    /// - `Ordering::Less` if `second` is considered greater than `first`.
+    /// - `Ordering::Equal` if both stages are equal by all criteria.
+    #[inline(always)]
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        let first_time = penalized_time::<NM, CM, PENALTY>(first);
+        let second_time = penalized_time::<NM, CM, PENALTY>(second);
+        match first_time.partial_cmp(&second_time) {
+            Some(Ordering::Equal) | None => D::cmp(first, second),
+            Some(ordering) => ordering,
+        }
+    }
+
+    /// Checks if two `RouteStage` instances have the same penalized arrival time and are equal
+    /// for `D`.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to check for equality.
+    /// - `second`: The second route stage to check for equality.
+    ///
+    /// # Returns
+    /// - `true` if `first` and `second` are equal by all the criteria of `cmp`.
+    /// - `false` otherwise.
+    #[inline(always)]
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        penalized_time::<NM, CM, PENALTY>(first) == penalized_time::<NM, CM, PENALTY>(second)
+            && D::eq(first, second)
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: HybridParentingOrd<NM, CM>, const PENALTY: u32>
+    HybridParentingOrd<NM, CM> for Probabilistic<D, PENALTY>
+{
+    // A more probable proposition is retained, it may be the only one to occur.
+    fn can_retain(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        delivery_probability(prop) > delivery_probability(known) || D::can_retain(prop, known)
+    }
+    // A less probable proposition never prunes a stage.
+    fn must_prune(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        delivery_probability(prop) >= delivery_probability(known) && D::must_prune(prop, known)
+    }
+}
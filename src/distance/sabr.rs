@@ -1,8 +1,8 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 
 use crate::{
     contact_manager::ContactManager, node_manager::NodeManager,
-    pathfinding::hybrid_parenting::HybridParentingOrd, route_stage::RouteStage,
+    pathfinding::hybrid_parenting::HybridParentingOrd, route_stage::RouteStage, types::NodeID,
 };
 
 use super::Distance;
@@ -88,3 +88,73 @@ impl<NM: NodeManager, CM: ContactManager> HybridParentingOrd<NM, CM> for SABR {
         return prop.at_time <= known.at_time && prop.hop_count <= known.hop_count;
     }
 }
+
+/// Returns the entry node of the route reaching a stage, i.e. the receiving node of its first hop.
+pub(crate) fn entry_node<NM: NodeManager, CM: ContactManager>(
+    stage: &RouteStage<NM, CM>,
+) -> Option<NodeID> {
+    stage.via.as_ref()?;
+    let entry = stage
+        .ancestors()
+        .take_while(|parent| parent.borrow().via.is_some())
+        .last()
+        .map_or(stage.to_node, |entry| entry.borrow().to_node);
+    Some(entry)
+}
+
+/// A struct allowing to use the route selection order of the CCSDS SABR specification strictly.
+///
+/// `SABRCompliant` extends `SABR` with the last tie-break of the specification, the entry node
+/// (the receiving node of the first hop) with the lowest number is preferred. The preset
+/// `CgrSabrCompliant` router uses it for both the pathfinding and the route selection.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SABRCompliant {}
+
+impl<NM: NodeManager, CM: ContactManager> Distance<NM, CM> for SABRCompliant {
+    /// Compares two `RouteStage` instances following the SABR route selection order.
+    ///
+    /// The comparison follows these rules, in descending order of priority:
+    /// 1. `at_time`: The `RouteStage` with a later `at_time` (delivery time) is considered greater.
+    /// 2. `hop_count`: If `at_time` is equal, the one with a higher `hop_count` is greater.
+    /// 3. `expiration`: If `at_time` and `hop_count` are equal, the one with an earlier `expiration`
+    ///    (termination time) is greater.
+    /// 4. Entry node: If all the above are equal, the one entering the network through the node with
+    ///    the higher number is greater.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Greater` if `first` is considered greater than `second` based on the criteria.
+    /// - `Ordering::Less` if `second` is considered greater than `first`.
+    /// - `Ordering::Equal` if both stages are equal by all criteria.
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        match <SABR as Distance<NM, CM>>::cmp(first, second) {
+            Ordering::Equal => entry_node(first).cmp(&entry_node(second)),
+            ordering => ordering,
+        }
+    }
+
+    /// Checks if two `RouteStage` instances are equal following the SABR route selection order.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to check for equality.
+    /// - `second`: The second route stage to check for equality.
+    ///
+    /// # Returns
+    /// - `true` if `first` and `second` are equal by all the criteria of `cmp`.
+    /// - `false` otherwise.
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        <SABR as Distance<NM, CM>>::eq(first, second) && entry_node(first) == entry_node(second)
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> HybridParentingOrd<NM, CM> for SABRCompliant {
+    fn can_retain(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        <SABR as HybridParentingOrd<NM, CM>>::can_retain(prop, known)
+    }
+    fn must_prune(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        <SABR as HybridParentingOrd<NM, CM>>::must_prune(prop, known)
+    }
+}
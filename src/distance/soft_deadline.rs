@@ -0,0 +1,83 @@
+use core::{cmp::Ordering, marker::PhantomData};
+
+use crate::{
+    contact_manager::ContactManager, node_manager::NodeManager,
+    pathfinding::hybrid_parenting::HybridParentingOrd, route_stage::RouteStage,
+};
+
+use super::Distance;
+
+/// A distance wrapper preferring the route stages reached by the soft deadline of the bundle
+/// (see `Bundle::soft_deadline`), the stages being ordered by `D` otherwise.
+///
+/// A stage reached after the deadline is greater than any stage reached by the deadline: the
+/// penalty is lexicographic, e.g. `SoftDeadline<Hop>` takes a longer route meeting the deadline
+/// over a shorter one missing it. Without soft deadline, the ordering of `D` is left unchanged.
+/// The expiration of the bundle still prunes the routes.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SoftDeadline<D> {
+    #[doc(hidden)]
+    _phantom: PhantomData<D>,
+}
+
+/// Tells whether a stage is reached after the soft deadline of its bundle.
+#[inline(always)]
+fn is_late<NM: NodeManager, CM: ContactManager>(stage: &RouteStage<NM, CM>) -> bool {
+    stage
+        .soft_deadline
+        .is_some_and(|deadline| stage.at_time > deadline)
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Distance<NM, CM>
+    for SoftDeadline<D>
+{
+    /// Compares two `RouteStage` instances, the stage reached by the soft deadline being lesser.
+    ///
+    /// The comparison follows these rules, in descending order of priority:
+    /// 1. Lateness: The `RouteStage` reached after the soft deadline is considered greater.
+    /// 2. The ordering of `D`.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Greater` if `first` is considered greater than `second` based on the criteria.
+    /// - `Ordering::Less` if `second` is considered greater than `first`.
+    /// - `Ordering::Equal` if both stages are equal by all criteria.
+    #[inline(always)]
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        match is_late(first).cmp(&is_late(second)) {
+            Ordering::Equal => D::cmp(first, second),
+            ordering => ordering,
+        }
+    }
+
+    /// Checks if two `RouteStage` instances are equal, i.e. both late or both on time, and equal
+    /// for `D`.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to check for equality.
+    /// - `second`: The second route stage to check for equality.
+    ///
+    /// # Returns
+    /// - `true` if `first` and `second` are equal by all the criteria of `cmp`.
+    /// - `false` otherwise.
+    #[inline(always)]
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        is_late(first) == is_late(second) && D::eq(first, second)
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: HybridParentingOrd<NM, CM>> HybridParentingOrd<NM, CM>
+    for SoftDeadline<D>
+{
+    // A proposition on time is retained against a late known stage.
+    fn can_retain(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        (!is_late(prop) && is_late(known)) || D::can_retain(prop, known)
+    }
+    // A late proposition never prunes a stage on time.
+    fn must_prune(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        (is_late(known) || !is_late(prop)) && D::must_prune(prop, known)
+    }
+}
@@ -0,0 +1,246 @@
+use alloc::{format, string::String};
+use core::str::FromStr;
+
+use crate::{
+    parsing::{Lexer, ParsingState},
+    types::{time_from_seconds, time_to_seconds, Date},
+};
+
+/// The offset between the UNIX epoch and the DTN epoch (2000-01-01T00:00:00Z), in seconds.
+pub const DTN_EPOCH_UNIX_OFFSET: f64 = 946_684_800.0;
+
+/// Computes the number of days since 1970-01-01 of a civil date (proleptic Gregorian calendar).
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Computes the number of days of a month (proleptic Gregorian calendar, with leap years).
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_digits(s: &str, len: usize) -> Option<i64> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parses an ISO 8601 timestamp (`YYYY-MM-DDTHH:MM:SS[.fff](Z|±HH:MM)`).
+///
+/// # Parameters
+///
+/// * `timestamp` - The timestamp.
+///
+/// # Returns
+///
+/// The number of seconds since the DTN epoch, or `None` if the timestamp is malformed (e.g. a day
+/// beyond the end of its month).
+pub fn parse_iso8601(timestamp: &str) -> Option<f64> {
+    let (date, time) = timestamp.split_once(['T', 't'])?;
+
+    let mut date_parts = date.split('-');
+    let year = parse_digits(date_parts.next()?, 4)?;
+    let month = parse_digits(date_parts.next()?, 2)?;
+    let day = parse_digits(date_parts.next()?, 2)?;
+    if date_parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+    if !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let (time, offset_seconds) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let sign_idx = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(sign_idx);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (offset_h, offset_m) = offset[1..].split_once(':')?;
+        let offset_h = parse_digits(offset_h, 2)?;
+        let offset_m = parse_digits(offset_m, 2)?;
+        (time, sign * (offset_h * 3600 + offset_m * 60))
+    };
+
+    let mut time_parts = time.split(':');
+    let hours = parse_digits(time_parts.next()?, 2)?;
+    let minutes = parse_digits(time_parts.next()?, 2)?;
+    let seconds_str = time_parts.next()?;
+    if time_parts.next().is_some() || hours > 23 || minutes > 59 {
+        return None;
+    }
+    let (whole_seconds, fraction) = match seconds_str.split_once('.') {
+        Some((whole, fraction)) => {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            (whole, f64::from_str(&format!("0.{}", fraction)).ok()?)
+        }
+        None => (seconds_str, 0.0),
+    };
+    let whole_seconds = parse_digits(whole_seconds, 2)?;
+    if whole_seconds > 60 {
+        return None;
+    }
+
+    let unix_seconds =
+        days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + whole_seconds
+            - offset_seconds;
+    Some(unix_seconds as f64 + fraction - DTN_EPOCH_UNIX_OFFSET)
+}
+
+/// Parses an absolute time, either an ISO 8601 timestamp or DTN epoch seconds prefixed by `dtn:`.
+///
+/// # Parameters
+///
+/// * `token` - The time token.
+///
+/// # Returns
+///
+/// The number of seconds since the DTN epoch, or `None` if the token is not an absolute time.
+pub fn parse_absolute_time(token: &str) -> Option<f64> {
+    if let Some(seconds) = token.strip_prefix("dtn:") {
+        return f64::from_str(seconds).ok();
+    }
+    parse_iso8601(token)
+}
+
+/// The reference of the relative `Date`s, expressed in seconds since the DTN epoch.
+///
+/// The internal dates are relative to the epoch, in seconds (or in the unit of the plan).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Epoch {
+    /// The epoch, in seconds since the DTN epoch.
+    dtn_seconds: f64,
+}
+
+impl Epoch {
+    /// Creates an epoch from a number of seconds since the DTN epoch.
+    pub fn from_dtn_seconds(dtn_seconds: f64) -> Self {
+        Self { dtn_seconds }
+    }
+
+    /// Creates an epoch from an ISO 8601 timestamp, or `None` if the timestamp is malformed.
+    pub fn from_iso8601(timestamp: &str) -> Option<Self> {
+        parse_iso8601(timestamp).map(Self::from_dtn_seconds)
+    }
+
+    /// Creates an epoch from an absolute time token (ISO 8601 or `dtn:<seconds>`).
+    pub fn from_token(token: &str) -> Option<Self> {
+        parse_absolute_time(token).map(Self::from_dtn_seconds)
+    }
+
+    /// Returns the epoch in seconds since the DTN epoch.
+    pub fn dtn_seconds(&self) -> f64 {
+        self.dtn_seconds
+    }
+
+    /// Converts an absolute time (seconds since the DTN epoch) to a relative `Date`, counted in
+    /// the time units of the plans (see `TICKS_PER_SECOND`).
+    pub fn to_relative(&self, dtn_seconds: f64) -> Date {
+        time_from_seconds(dtn_seconds - self.dtn_seconds)
+    }
+
+    /// Converts a relative `Date` to an absolute time (seconds since the DTN epoch).
+    pub fn to_dtn_seconds(&self, date: Date) -> f64 {
+        self.dtn_seconds + time_to_seconds(date)
+    }
+
+    /// Converts an ISO 8601 timestamp to a relative `Date`, or `None` if the timestamp is malformed.
+    pub fn relative_from_iso8601(&self, timestamp: &str) -> Option<Date> {
+        parse_iso8601(timestamp).map(|dtn_seconds| self.to_relative(dtn_seconds))
+    }
+
+    /// Converts a time token to a relative `Date`.
+    ///
+    /// The token can be a relative date (e.g. `60`), an ISO 8601 timestamp or DTN epoch seconds
+    /// prefixed by `dtn:`.
+    pub fn parse_time(&self, token: &str) -> Option<Date> {
+        match parse_absolute_time(token) {
+            Some(dtn_seconds) => Some(self.to_relative(dtn_seconds)),
+            None => Date::from_str(token).ok(),
+        }
+    }
+}
+
+/// Parses a date from the lexer.
+///
+/// Relative dates are parsed as is. Absolute dates (ISO 8601 timestamps or `dtn:<seconds>`) are
+/// converted with the epoch declared to the lexer, if any.
+///
+/// # Parameters
+///
+/// * `lexer` - The lexer used to read the date.
+///
+/// # Returns
+///
+/// * `ParsingState<Date>` - The parsing state.
+pub fn parse_date(lexer: &mut dyn Lexer) -> ParsingState<Date> {
+    let token = match lexer.consume_next_token() {
+        ParsingState::Finished(token) => token,
+        ParsingState::Error(msg) => return ParsingState::Error(msg),
+        ParsingState::EOF => return ParsingState::EOF,
+    };
+
+    if let Some(dtn_seconds) = parse_absolute_time(&token) {
+        return match lexer.get_epoch() {
+            Some(epoch) => ParsingState::Finished(epoch.to_relative(dtn_seconds)),
+            None => ParsingState::Error(format!(
+                "Absolute date without epoch declaration ({})",
+                lexer.get_current_position()
+            )),
+        };
+    }
+
+    match Date::from_str(&token) {
+        Ok(date) => ParsingState::Finished(date),
+        Err(_) => ParsingState::Error(format!(
+            "Unable to parse date ({})",
+            lexer.get_current_position()
+        )),
+    }
+}
+
+/// Declares the epoch to the lexer, the time tokens that follow can be absolute.
+///
+/// # Parameters
+///
+/// * `lexer` - The lexer, positioned on the epoch token.
+///
+/// # Returns
+///
+/// * `Result<(), String>` - An error if the token is not an absolute time or if the lexer
+///   does not support epochs.
+pub(crate) fn parse_epoch_declaration(lexer: &mut dyn Lexer) -> Result<(), String> {
+    let token = match lexer.consume_next_token() {
+        ParsingState::Finished(token) => token,
+        ParsingState::Error(msg) => return Err(msg),
+        ParsingState::EOF => {
+            return Err(format!("Parsing failed ({})", lexer.get_current_position()))
+        }
+    };
+    let Some(epoch) = Epoch::from_token(&token) else {
+        return Err(format!(
+            "Malformed epoch ({})",
+            lexer.get_current_position()
+        ));
+    };
+    if !lexer.set_epoch(epoch) {
+        return Err(String::from(
+            "The lexer does not support epoch declarations",
+        ));
+    }
+    Ok(())
+}
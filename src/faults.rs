@@ -0,0 +1,321 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+use serde::Serialize;
+
+use crate::{
+    contact_manager::ContactManager,
+    contact_plan::sanitize::SplitMix64,
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    routing::Router,
+    types::{duration_from_f64, time_to_f64, ContactID, Date, Duration},
+    workload::{
+        resolve_destinations, submission_order, MetricsAccumulator, TraceMetrics, WorkloadTrace,
+    },
+};
+
+/// The deviation of a contact from the plan, drawn by a `FaultModel`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct ContactFault {
+    /// Whether the contact does not occur at all.
+    pub failed: bool,
+    /// The delay of the start of the contact.
+    pub late_start: Duration,
+    /// The advance of the end of the contact.
+    pub early_end: Duration,
+    /// The factor applied to the rate of the contact (1 for the planned rate).
+    pub rate_factor: f64,
+}
+
+impl ContactFault {
+    /// Tells whether the contact occurs as planned.
+    pub fn is_nominal(&self) -> bool {
+        !self.failed
+            && self.late_start == duration_from_f64(0.0)
+            && self.early_end == duration_from_f64(0.0)
+            && self.rate_factor == 1.0
+    }
+}
+
+/// The contact failures and plan deviations injected by `play_trace_with_faults`.
+///
+/// Each contact is independently subject to each fault with its own probability: a total failure,
+/// a late start (uniformly drawn up to a maximum delay), an early end (uniformly drawn up to a
+/// maximum advance), and a reduced rate (a fixed factor). The faults of a contact only depend on
+/// the seed and on the identifier of the contact, the routers built from the same plan facing the
+/// same deviations.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct FaultModel {
+    /// The seed of the draws.
+    seed: u64,
+    /// The probability that a contact fails entirely.
+    failure: f64,
+    /// The probability that a contact starts late.
+    late_start: f64,
+    /// The maximum delay of a late start.
+    max_late_start: Duration,
+    /// The probability that a contact ends early.
+    early_end: f64,
+    /// The maximum advance of an early end.
+    max_early_end: Duration,
+    /// The probability that a contact delivers at a reduced rate.
+    reduced_rate: f64,
+    /// The factor applied to the rate of the contacts delivering at a reduced rate.
+    rate_factor: f64,
+}
+
+impl FaultModel {
+    /// Creates a model without fault, the contacts occurring as planned.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the draws.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `FaultModel`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            failure: 0.0,
+            late_start: 0.0,
+            max_late_start: duration_from_f64(0.0),
+            early_end: 0.0,
+            max_early_end: duration_from_f64(0.0),
+            reduced_rate: 0.0,
+            rate_factor: 1.0,
+        }
+    }
+
+    /// Sets the probability that a contact fails entirely.
+    pub fn failures(mut self, probability: f64) -> Self {
+        self.failure = probability;
+        self
+    }
+
+    /// Sets the probability that a contact starts late, and the maximum delay of its start.
+    pub fn late_starts(mut self, probability: f64, max_delay: Duration) -> Self {
+        self.late_start = probability;
+        self.max_late_start = max_delay;
+        self
+    }
+
+    /// Sets the probability that a contact ends early, and the maximum advance of its end.
+    pub fn early_ends(mut self, probability: f64, max_advance: Duration) -> Self {
+        self.early_end = probability;
+        self.max_early_end = max_advance;
+        self
+    }
+
+    /// Sets the probability that a contact delivers at a reduced rate, and the factor applied to
+    /// its rate (e.g. 0.5 for half the planned rate).
+    pub fn reduced_rates(mut self, probability: f64, factor: f64) -> Self {
+        self.reduced_rate = probability;
+        self.rate_factor = factor;
+        self
+    }
+
+    /// Draws the deviation of a contact.
+    ///
+    /// # Parameters
+    ///
+    /// * `contact` - The identifier of the contact.
+    ///
+    /// # Returns
+    ///
+    /// * `ContactFault` - The deviation of the contact, the same for each call.
+    pub fn draw(&self, contact: ContactID) -> ContactFault {
+        let mut rng =
+            SplitMix64::new(self.seed ^ (contact as u64).wrapping_mul(0x2545_F491_4F6C_DD1D));
+        // the four draws are always made, a probability not shifting the draws of the others
+        let failed = rng.next_f64() < self.failure;
+        let late = rng.next_f64();
+        let early = rng.next_f64();
+        let reduced = rng.next_f64() < self.reduced_rate;
+        let zero = duration_from_f64(0.0);
+        ContactFault {
+            failed,
+            late_start: if late < self.late_start {
+                duration_from_f64(late / self.late_start * time_to_f64(self.max_late_start))
+            } else {
+                zero
+            },
+            early_end: if early < self.early_end {
+                duration_from_f64(early / self.early_end * time_to_f64(self.max_early_end))
+            } else {
+                zero
+            },
+            rate_factor: if reduced { self.rate_factor } else { 1.0 },
+        }
+    }
+}
+
+/// The results of `play_trace_with_faults`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct FaultReplay {
+    /// The results predicted by the router, from the plan.
+    pub planned: TraceMetrics,
+    /// The results of the routes facing the faults.
+    pub actual: TraceMetrics,
+    /// The count of destinations lost because a contact of their route failed.
+    pub lost_to_failures: usize,
+    /// The count of destinations lost because a transmission of their route did not fit in the
+    /// actual contact (late start, early end, reduced rate, or a late arrival at the transmitting
+    /// node).
+    pub lost_to_truncations: usize,
+}
+
+impl FaultReplay {
+    /// Computes the fraction of the planned deliveries lost to the faults.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The fraction of the destinations reached in the plan and not actually reached,
+    ///   0 if the plan reaches no destination.
+    pub fn delivery_loss(&self) -> f64 {
+        if self.planned.reached == 0 {
+            return 0.0;
+        }
+        (self.planned.reached - self.actual.reached) as f64 / self.planned.reached as f64
+    }
+
+    /// Computes the increase of the mean latency of the deliveries due to the faults.
+    pub fn latency_increase(&self) -> f64 {
+        self.actual.mean_latency - self.planned.mean_latency
+    }
+}
+
+/// The outcome of a route facing the faults.
+enum Delivery {
+    /// The destination is reached, at the given time.
+    Reached(Date),
+    /// A contact of the route failed.
+    Failed,
+    /// A transmission did not fit in its actual contact.
+    Truncated,
+}
+
+/// Follows a route through the actual contacts, each transmission starting at its planned start
+/// at the earliest.
+fn deliver<NM: NodeManager, CM: ContactManager>(
+    route: &Rc<RefCell<RouteStage<NM, CM>>>,
+    faults: &FaultModel,
+    submission: Date,
+) -> Delivery {
+    let mut stages = Vec::new();
+    let mut current = route.clone();
+    loop {
+        let parent = match &current.borrow().via {
+            Some(via) => via.parent_route.clone(),
+            None => break,
+        };
+        stages.push(current);
+        current = parent;
+    }
+
+    let mut time = submission;
+    for stage in stages.iter().rev() {
+        let stage = stage.borrow();
+        let (Some(via), Some(tx_data)) = (&stage.via, &stage.tx_data) else {
+            continue;
+        };
+        let contact = via.contact.borrow();
+        let fault = faults.draw(contact.id);
+        if fault.failed {
+            return Delivery::Failed;
+        }
+        let start = contact.info.start + fault.late_start;
+        let end = contact.info.end - fault.early_end;
+
+        let mut tx_start = tx_data.tx_start;
+        if tx_start < start {
+            tx_start = start;
+        }
+        if tx_start < time {
+            tx_start = time;
+        }
+        let tx_duration =
+            duration_from_f64(time_to_f64(tx_data.tx_end - tx_data.tx_start) / fault.rate_factor);
+        let tx_end = tx_start + tx_duration;
+        if tx_end > end {
+            return Delivery::Truncated;
+        }
+        time = tx_end + tx_data.delay;
+    }
+    Delivery::Reached(time)
+}
+
+/// Replays a workload trace against a router like `play_trace`, and follows the routes through
+/// contacts deviating from the plan (see `FaultModel`), to evaluate the robustness of a router.
+///
+/// The router computes its routes from the plan, unaware of the faults. Each route is then
+/// followed hop by hop: a transmission starts at its planned start, or later if the contact starts
+/// late or the bundle arrives late at the transmitting node, and lasts longer if the contact
+/// delivers at a reduced rate. The destination is lost if a contact of the route fails or if a
+/// transmission ends after its actual contact, the bundles not being rerouted. The transmissions
+/// of the other bundles are not moved by the deviations.
+///
+/// # Parameters
+///
+/// * `router` - The router.
+/// * `trace` - The workload trace.
+/// * `faults` - The deviations of the contacts.
+///
+/// # Returns
+///
+/// * `FaultReplay` - The planned and actual results.
+pub fn play_trace_with_faults<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    trace: &WorkloadTrace,
+    faults: &FaultModel,
+) -> FaultReplay {
+    let mut planned = MetricsAccumulator::default();
+    let mut actual = MetricsAccumulator::default();
+    let mut replay = FaultReplay::default();
+
+    for entry in submission_order(trace) {
+        let bundle = &entry.bundle;
+        planned.submit(bundle);
+        actual.submit(bundle);
+
+        let Some(output) = router.route(bundle.source, bundle, entry.time, &Vec::new()) else {
+            planned.conclude(bundle, 0);
+            actual.conclude(bundle, 0);
+            continue;
+        };
+
+        let destinations = resolve_destinations(router, bundle);
+        let mut planned_reached = 0;
+        let mut actual_reached = 0;
+        for (_contact, routes) in output.first_hops.values() {
+            for route in routes {
+                let at_time = {
+                    let route = route.borrow();
+                    if !destinations.contains(&route.to_node) {
+                        continue;
+                    }
+                    route.at_time
+                };
+                planned_reached += 1;
+                planned.reach(time_to_f64(at_time - entry.time), route);
+                match deliver(route, faults, entry.time) {
+                    Delivery::Reached(arrival) => {
+                        actual_reached += 1;
+                        actual.reach(time_to_f64(arrival - entry.time), route);
+                    }
+                    Delivery::Failed => replay.lost_to_failures += 1,
+                    Delivery::Truncated => replay.lost_to_truncations += 1,
+                }
+            }
+        }
+        planned.conclude(bundle, planned_reached);
+        actual.conclude(bundle, actual_reached);
+    }
+
+    replay.planned = planned.finish();
+    replay.actual = actual.finish();
+    replay
+}
@@ -1,3 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("a_sabr requires either the \"std\" (default) or the \"alloc\" feature");
+
+extern crate alloc;
+
 /// Module containing the adaptive contact definition.
 pub mod contact;
 /// Module containing the variable component of a contact for resource management.
@@ -8,9 +15,14 @@ pub mod node;
 pub mod node_manager;
 /// Module containing the library primitive types.
 pub mod types;
+/// Module containing the unit-safe volumes and data rates, with the "strict-units" feature.
+#[cfg(feature = "strict-units")]
+pub mod units;
 
 /// Module containing the bundle definition.
 pub mod bundle;
+/// Module containing the QoS profiles, the routing parameters of the classes of bundles.
+pub mod qos;
 
 /// Module containing the data structure storing the nodes and contacts.
 /// The structure does not influence the pathfinding implementations.
@@ -21,16 +33,52 @@ pub mod pathfinding;
 /// A RouteStage is an abstraction of Dijkstra's algorithm progress, a route hop, or work areas.
 pub mod route_stage;
 
+/// Module containing the admission control of flows, based on the route estimations.
+pub mod admission;
+/// Module containing the injection of contact failures and plan deviations into the workload
+/// replays.
+pub mod faults;
+/// Module containing the contact plan optimizer (simulated annealing over the plan parameters),
+/// requires the "std" feature.
+#[cfg(feature = "std")]
+pub mod optimizer;
+/// Module containing the volume reservations (routing confirmed in two phases).
+pub mod reservation;
 ///  Module containing the storage capabilities for the routes.
 pub mod route_storage;
 ///  Module containing the routing algorithms.
 pub mod routing;
+/// Module containing the end-to-end scenarios (a contact plan, a workload and the expected metrics).
+pub mod scenario;
+/// Module containing the temporal distance matrices (earliest arrivals over a grid of start times).
+pub mod temporal_distance;
+/// Module containing the bundle workload traces and their replay against a router.
+pub mod workload;
 
 /// Module containing the logic to read a contact plan.
+/// File based importers require the "std" feature.
 pub mod contact_plan;
 /// Module containing the logic to enable different distance comparison strategy between two paths.
 pub mod distance;
+/// Module containing the conversions between absolute times (ISO 8601, DTN epoch) and dates.
+pub mod epoch;
 /// Module containing the logic to enable parsing abilities for the components.
 pub mod parsing;
+/// Module containing the OpenTelemetry spans of the routing calls, emitted with the "otel" feature.
+mod telemetry;
 
+/// Helpers for quick experiments (contact plan loading, route printing), requires the "std" feature.
+#[cfg(feature = "std")]
 pub mod utils;
+
+/// JavaScript bindings (contact plan parsing from strings and routing), requires the "wasm" feature.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Router service running in a dedicated thread and queried over tokio channels, requires the "service" feature.
+#[cfg(feature = "service")]
+pub mod service;
+
+/// Protobuf encoding of contact plans and routing outputs, requires the "interop-proto" feature.
+#[cfg(feature = "interop-proto")]
+pub mod proto;
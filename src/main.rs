@@ -3,28 +3,39 @@ use std::{cell::RefCell, env, rc::Rc};
 use a_sabr::{
     bundle::Bundle,
     contact_manager::{
+        discipline::DisciplinedETOManager,
+        hybrid::HybridManager,
         legacy::{eto::ETOManager, evl::EVLManager, qd::QDManager},
+        partition::coerce_partitioned,
+        playback::PlaybackManager,
+        predicted::PredictedManager,
         seg::SegmentationManager,
         ContactManager,
     },
-    contact_plan::{asabr_file_lexer::FileLexer, from_asabr_lexer::ASABRContactPlan},
+    contact_plan::{
+        asabr_file_lexer::FileLexer, from_asabr_lexer::ASABRContactPlan, ContactPlanError,
+    },
     node_manager::none::NoManagement,
     parsing::{coerce_cm, ContactMarkerMap},
     route_storage::cache::TreeCache,
     routing::{aliases::SpsnHybridParenting, Router},
-    utils::pretty_print,
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+    utils::{RouteDisplay, Verbosity},
 };
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <cp_file>", args[0]);
+        eprintln!("Usage: {} <cp_file> [text|table|json]", args[0]);
         std::process::exit(1);
     }
     println!("Working with cp {}.", args[1]);
 
     // We create a lexer to retrieve tokens from a file
-    let mut mylexer = FileLexer::new(&args[1]).unwrap();
+    let mut mylexer = FileLexer::new(&args[1]).unwrap_or_else(|e| {
+        eprintln!("{}", ContactPlanError::from(e));
+        std::process::exit(1);
+    });
 
     // All nodes will have the same management approach (NoManagement) but the contacts may be of various types
     // We provide a map with markers that will allow the parser to create the correct contacts types thanks to
@@ -34,6 +45,11 @@ fn main() {
     contact_dispatch.add("qd", coerce_cm::<QDManager>);
     contact_dispatch.add("evl", coerce_cm::<ETOManager>);
     contact_dispatch.add("seg", coerce_cm::<SegmentationManager>);
+    contact_dispatch.add("hybrid", coerce_cm::<HybridManager>);
+    contact_dispatch.add("part", coerce_partitioned);
+    contact_dispatch.add("deto", coerce_cm::<DisciplinedETOManager>);
+    contact_dispatch.add("playback", coerce_cm::<PlaybackManager>);
+    contact_dispatch.add("predicted", coerce_cm::<PredictedManager>);
 
     // We parse the contact plan (A-SABR format thanks to ASABRContactPlan) and the lexer
     let (nodes, contacts) = ASABRContactPlan::parse::<NoManagement, Box<dyn ContactManager>>(
@@ -41,7 +57,10 @@ fn main() {
         None,
         Some(&contact_dispatch),
     )
-    .unwrap();
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
 
     // We create a storage for the Paths
     let table = Rc::new(RefCell::new(TreeCache::new(true, false, 10)));
@@ -55,17 +74,29 @@ fn main() {
         source: 0,
         destinations: vec![4],
         priority: 0,
-        size: 1.0,
-        expiration: 10000.0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
     };
 
     // We schedule the bundle (resource updates were conducted)
-    let out = spsn.route(0, &b, 0.0, &Vec::new());
+    let out = spsn.route(0, &b, TIME_ZERO, &Vec::new());
 
     if let Some(out) = out {
         for (_contact_ptr, (_contact, dest_routes)) in &out.first_hops {
             for route_rc in dest_routes {
-                pretty_print(route_rc.clone());
+                let display = RouteDisplay::for_bundle(route_rc, &b);
+                match args.get(2).map(String::as_str) {
+                    Some("table") => {
+                        println!("{}", display.with_verbosity(Verbosity::Full).to_table())
+                    }
+                    Some("json") => {
+                        println!("{}", display.with_verbosity(Verbosity::Full).to_json())
+                    }
+                    _ => println!("{}", display),
+                }
             }
         }
     }
@@ -1,10 +1,16 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::{cell::RefCell, mem::size_of};
+use serde::Serialize;
 
-use super::node::Node;
-use crate::contact::Contact;
+use super::node::{DeliveryWindow, ExclusionScope, Node};
+use crate::contact::{Contact, ContactInfo};
 use crate::contact_manager::ContactManager;
+use crate::contact_plan::{extract_loopback_contacts, periodic::PeriodicContact};
 use crate::node_manager::NodeManager;
+use crate::route_stage::RouteStage;
 use crate::types::*;
 
 /// Represents a sender node in a routing system, with associated receivers.
@@ -27,6 +33,8 @@ pub struct Sender<NM: NodeManager, CM: ContactManager> {
 ///
 /// The `Receiver` struct holds references to contacts that provide paths to this receiver,
 /// and it also includes a mechanism for lazy pruning of outdated contacts based on a time threshold.
+/// The contacts are sorted by start time then end time (see `check_ordering`), and indexed by the running maximum of their end times,
+/// allowing the first valid contact to be found by binary search for any query time.
 ///
 /// # Generic Parameters
 /// - `NM`: A type implementing the `NodeManager` trait, managing node-level operations.
@@ -37,16 +45,76 @@ pub struct Receiver<NM: NodeManager, CM: ContactManager> {
     pub node: Rc<RefCell<Node<NM>>>,
     /// A list of contacts providing paths to this receiver.
     pub contacts_to_receiver: Vec<Rc<RefCell<Contact<NM, CM>>>>,
-    /// The index of the next contact to be checked for relevance.
+    /// The index of the first valid contact found by the last lookup.
     pub next: usize,
+    /// The running maximum of the contact end times: `max_ends[i]` is the latest end time of the
+    /// contacts `0..=i`. Non-decreasing, it allows binary searches whatever the contact durations.
+    max_ends: Vec<Date>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> Receiver<NM, CM> {
+    /// Creates a new `Receiver` and builds the index of its contacts.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The receiving node.
+    /// * `contacts_to_receiver` - The contacts to the node, sorted by start time then end time.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `Receiver`.
+    pub fn new(
+        node: Rc<RefCell<Node<NM>>>,
+        contacts_to_receiver: Vec<Rc<RefCell<Contact<NM, CM>>>>,
+    ) -> Self {
+        let mut receiver = Self {
+            node,
+            contacts_to_receiver,
+            next: 0,
+            max_ends: Vec::new(),
+        };
+        receiver.rebuild_index();
+        receiver
+    }
+
+    /// Rebuilds the index of the contacts, to be called after a change of `contacts_to_receiver`
+    /// or of the end times of the contacts.
+    pub fn rebuild_index(&mut self) {
+        self.max_ends.clear();
+        self.max_ends.reserve(self.contacts_to_receiver.len());
+        let mut max_end = Date::MIN;
+        for contact in &self.contacts_to_receiver {
+            let end = contact.borrow().info.end;
+            if end > max_end {
+                max_end = end;
+            }
+            self.max_ends.push(max_end);
+        }
+        self.next = 0;
+    }
+
+    /// Finds the index of the first contact ending after a given time, in O(log n).
+    ///
+    /// # Parameters
+    /// - `time`: The query time, in the past or in the future of the previous queries.
+    ///
+    /// # Returns
+    /// - `Some(usize)`: The index of the first contact with an end time after `time`.
+    /// - `None`: If all the contacts end before `time`.
+    pub fn first_idx_at(&self, time: Date) -> Option<usize> {
+        let idx = self.max_ends.partition_point(|end| *end <= time);
+        if idx < self.max_ends.len() {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
     /// Lazily prunes outdated contacts and returns the index of the first valid contact.
     ///
-    /// This method iterates over `contacts_to_receiver`, starting from the index stored in `self.next`.
-    /// It checks if each contact is still valid based on its expiration time. Once a valid contact
-    /// is found, it updates `self.next` and returns the index of this contact.
+    /// The index found by the last lookup is checked first, in O(1), as the successive queries
+    /// usually share the same current time. Otherwise, the index is found by binary search, so that
+    /// the queries can go back and forth in time.
     ///
     /// # Parameters
     /// - `current_time`: The current time against which contact expiration is checked.
@@ -55,29 +123,141 @@ impl<NM: NodeManager, CM: ContactManager> Receiver<NM, CM> {
     /// - `Some(usize)`: The index of the first valid contact if found.
     /// - `None`: If no valid contact is found.
     pub fn lazy_prune_and_get_first_idx(&mut self, current_time: Date) -> Option<usize> {
-        for (idx, contact) in self.contacts_to_receiver.iter().enumerate().skip(self.next) {
-            if contact.borrow().info.end > current_time {
-                self.next = idx;
-                return Some(idx);
+        let next = self.next;
+        if next < self.max_ends.len()
+            && self.max_ends[next] > current_time
+            && (next == 0 || self.max_ends[next - 1] <= current_time)
+        {
+            return Some(next);
+        }
+        let idx = self.first_idx_at(current_time)?;
+        self.next = idx;
+        Some(idx)
+    }
+
+    /// Checks that the contacts are well-formed: toward the node of the receiver, from a single
+    /// transmitting node, and sorted by start time then end time (see the `Ord` of `Contact`).
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the contacts are well-formed.
+    /// - `Err(String)`: A message describing the first issue found.
+    pub fn check_ordering(&self) -> Result<(), String> {
+        let rx_node = self.node.borrow().info.id;
+        for contact in &self.contacts_to_receiver {
+            let contact = contact.borrow();
+            let info = &contact.info;
+            if info.rx_node != rx_node {
+                return Err(format!(
+                    "Contact {} {}->{} [{}, {}] in the receiver of node {}",
+                    contact.id, info.tx_node, info.rx_node, info.start, info.end, rx_node
+                ));
+            }
+        }
+        for pair in self.contacts_to_receiver.windows(2) {
+            let (previous, contact) = (pair[0].borrow(), pair[1].borrow());
+            let info = &contact.info;
+            if info.tx_node != previous.info.tx_node {
+                return Err(format!(
+                    "Contacts from nodes {} and {} in the same receiver of node {}",
+                    previous.info.tx_node, info.tx_node, rx_node
+                ));
+            }
+            if *previous > *contact {
+                return Err(format!(
+                    "Contact {} {}->{} [{}, {}] after contact {} [{}, {}]",
+                    contact.id,
+                    info.tx_node,
+                    info.rx_node,
+                    info.start,
+                    info.end,
+                    previous.id,
+                    previous.info.start,
+                    previous.info.end
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Repairs the ordering of the contacts with a stable sort by start time then end time, and
+    /// rebuilds the index (e.g. after a direct change of `contacts_to_receiver` or of the contact
+    /// windows).
+    ///
+    /// The contacts toward another node are not moved, see `check_ordering`.
+    ///
+    /// # Returns
+    /// - `Vec<String>`: A warning for each contact out of order, empty if the contacts were
+    ///   already sorted.
+    pub fn repair_ordering(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for pair in self.contacts_to_receiver.windows(2) {
+            let (previous, contact) = (pair[0].borrow(), pair[1].borrow());
+            if *previous > *contact {
+                let info = &contact.info;
+                warnings.push(format!(
+                    "Contact {} {}->{} [{}, {}] was after contact {} [{}, {}], reordered",
+                    contact.id,
+                    info.tx_node,
+                    info.rx_node,
+                    info.start,
+                    info.end,
+                    previous.id,
+                    previous.info.start,
+                    previous.info.end
+                ));
             }
         }
-        return None;
+        if !warnings.is_empty() {
+            self.contacts_to_receiver
+                .sort_by(|a, b| a.borrow().cmp(&b.borrow()));
+            self.rebuild_index();
+        }
+        warnings
     }
 
     /// Checks if the receiver's node is excluded from routing or pathfinding.
     ///
     /// This method provides a quick check on whether the receiver node is excluded
     /// from any routing operations. This is useful for selectively excluding nodes
-    /// without removing them from the network entirely.
+    /// without removing them from the network entirely. A node excluded as a relay or as a
+    /// destination only (see `ExclusionScope`) still receives the bundles.
     ///
     /// # Returns
-    /// - `true`: If the receiver node is excluded.
+    /// - `true`: If the receiver node is excluded entirely.
     /// - `false`: If the receiver node is included.
     pub fn is_excluded(&self) -> bool {
-        return self.node.borrow().info.excluded;
+        let node = self.node.borrow();
+        return node.info.excluded && node.info.exclusion_scope == ExclusionScope::Node;
     }
 }
 
+/// The memory footprint of a multigraph, see `Multigraph::memory_report`.
+///
+/// The sizes are approximate: the allocations are counted at their capacity, the heap data of the
+/// managers (e.g. the segments of a `SegmentationManager`) and of the contact metadata are not
+/// counted.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct MemoryReport {
+    /// The count of nodes.
+    pub nodes: usize,
+    /// The count of contacts in the receivers (the removed contacts excluded).
+    pub contacts: usize,
+    /// The count of receivers, i.e. of links.
+    pub receivers: usize,
+    /// The bytes of the nodes, their names, aliases, attributes and delivery windows included.
+    pub node_bytes: usize,
+    /// The bytes of the contacts, their managers included.
+    pub contact_bytes: usize,
+    /// The bytes of the senders and receivers, the contact lists and indexes included.
+    pub receiver_bytes: usize,
+    /// The bytes of the work area of a pathfinding with contact parenting (a slot per contact
+    /// ID and a route stage per contact), allocated during each route computation.
+    pub work_area_bytes: usize,
+    /// The bytes of the multigraph, the work area excluded.
+    pub total_bytes: usize,
+}
+
 /// Represents a multigraph structure, where each node can have multiple connections.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct Multigraph<NM: NodeManager, CM: ContactManager> {
@@ -87,6 +267,14 @@ pub struct Multigraph<NM: NodeManager, CM: ContactManager> {
     pub nodes: Vec<Rc<RefCell<Node<NM>>>>,
     /// * `node_count` - The total number of nodes in the multigraph.
     node_count: usize,
+    /// * `contact_count` - The total number of contacts in the multigraph.
+    contact_count: usize,
+    /// * `names` - The node IDs by node name and alias.
+    names: HashMap<NodeName, NodeID>,
+    /// * `down_nodes` - The nodes marked down as neighbors (see `Node::down_until`).
+    down_nodes: Vec<NodeID>,
+    /// * `periodic` - The periodic contacts with occurrences not expanded yet.
+    periodic: Vec<PeriodicContact<CM>>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
@@ -97,6 +285,7 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
     /// overhead if some nodes are not transmitters in the contact plan. Regarding the
     /// receivers, only fast iteration is required. The indices of the senders[tx_id].receivers
     /// Vec do not match the receivers NodeID, and no entry exists if a node never receives.
+    /// The loopback contacts are not edges, they become delivery windows of their node.
     ///
     /// # Parameters
     ///
@@ -109,12 +298,30 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
     pub fn new(mut nodes: Vec<Node<NM>>, mut contact_plan: Vec<Contact<NM, CM>>) -> Self {
         // the contact plan might not be sorted
         // having a sorted list of contacts allow easy multigraph creation
+        // the loopback contacts are delivery windows, not edges
+        extract_loopback_contacts(&mut nodes, &mut contact_plan);
+
         let node_count = nodes.len();
+        let contact_count = contact_plan.len();
         let mut senders: Vec<Sender<NM, CM>> = Vec::with_capacity(node_count);
 
-        contact_plan.sort_unstable();
+        // stable: the identical contacts keep the order of the plan, and thus of their ids
+        contact_plan.sort();
         nodes.sort_unstable();
 
+        // the contact ids are the indices in the sorted contact plan
+        for (id, contact) in contact_plan.iter_mut().enumerate() {
+            contact.id = id;
+        }
+
+        let mut names = HashMap::new();
+        for node in &nodes {
+            names.insert(node.info.name.clone(), node.info.id);
+            for alias in &node.info.aliases {
+                names.insert(alias.clone(), node.info.id);
+            }
+        }
+
         let mut all_refs = Vec::with_capacity(node_count);
 
         for node in nodes {
@@ -151,11 +358,10 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
                 contacts_to_receiver.push(Rc::new(RefCell::new(contact)));
             }
 
-            senders[tx_id as usize].receivers.push(Receiver {
-                node: all_refs[rx_id as usize].clone(),
-                contacts_to_receiver: contacts_to_receiver,
-                next: 0,
-            });
+            senders[tx_id as usize].receivers.push(Receiver::new(
+                all_refs[rx_id as usize].clone(),
+                contacts_to_receiver,
+            ));
         }
 
         for sender in &mut senders {
@@ -166,12 +372,17 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
             senders,
             nodes: all_refs,
             node_count,
+            contact_count,
+            names,
+            down_nodes: Vec::new(),
+            periodic: Vec::new(),
         }
     }
 
     /// Applies exclusions to the nodes based on the provided sorted exclusions.
     ///
     /// Marks nodes as excluded if their index is in the `exclusions` list, otherwise unmarks them.
+    /// A node marked is barred from the roles of its exclusion scope (see `set_exclusion_scope`).
     ///
     /// # Parameters
     ///
@@ -190,6 +401,154 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
         }
     }
 
+    /// Sets the roles a node is barred from when it is in the exclusion list of a pathfinding
+    /// (see `prepare_for_exclusions_sorted`), e.g. a node not to be used as a relay but still
+    /// reachable as a destination. The scope persists across the pathfinding calls, the stored
+    /// routes computed with another scope not being recomputed.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The ID of the node.
+    /// * `scope` - The roles the node is barred from, `ExclusionScope::Node` by default.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if the node is unknown.
+    pub fn set_exclusion_scope(&mut self, node: NodeID, scope: ExclusionScope) -> bool {
+        let Some(node_ref) = self.nodes.get(node as usize) else {
+            return false;
+        };
+        node_ref.borrow_mut().info.exclusion_scope = scope;
+        true
+    }
+
+    /// Marks a node as down until a given time: the transmissions toward the node are deferred
+    /// to this time. Unlike the exclusions, the state persists across the pathfinding calls.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The ID of the node.
+    /// * `until` - The end of the down state, `Date::MAX` for a node down until marked up.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if the node is unknown.
+    pub fn set_node_down(&mut self, node: NodeID, until: Date) -> bool {
+        let Some(node_ref) = self.nodes.get(node as usize) else {
+            return false;
+        };
+        node_ref.borrow_mut().down_until = Some(until);
+        if !self.down_nodes.contains(&node) {
+            self.down_nodes.push(node);
+        }
+        true
+    }
+
+    /// Marks a node as up, ending its down state early.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The ID of the node.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if the node is unknown.
+    pub fn set_node_up(&mut self, node: NodeID) -> bool {
+        let Some(node_ref) = self.nodes.get(node as usize) else {
+            return false;
+        };
+        node_ref.borrow_mut().down_until = None;
+        self.down_nodes.retain(|down| *down != node);
+        true
+    }
+
+    /// Ends the down states reaching their end at a given time.
+    ///
+    /// # Parameters
+    ///
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if at least one node went back up.
+    pub fn expire_down_nodes(&mut self, curr_time: Date) -> bool {
+        let nodes = &self.nodes;
+        let count = self.down_nodes.len();
+        self.down_nodes.retain(|down| {
+            let mut node = nodes[*down as usize].borrow_mut();
+            match node.down_until {
+                Some(until) if until > curr_time => true,
+                _ => {
+                    node.down_until = None;
+                    false
+                }
+            }
+        });
+        self.down_nodes.len() != count
+    }
+
+    /// Advances the notion of "now" of all the contact managers, see
+    /// `ContactManager::advance_time`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The current time.
+    pub fn advance_time(&self, now: Date) {
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    let mut contact = contact.borrow_mut();
+                    let contact = &mut *contact;
+                    contact.manager.advance_time(&contact.info, now);
+                }
+            }
+        }
+    }
+
+    /// Retrieves the nodes currently marked down, see `set_node_down`.
+    ///
+    /// # Returns
+    ///
+    /// * `&[NodeID]` - The IDs of the nodes.
+    pub fn get_down_nodes(&self) -> &[NodeID] {
+        &self.down_nodes
+    }
+
+    /// Resolves a node name, alias or ID into the ID of the routing node.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name or an alias of the node, or a node ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<NodeID>` - The ID of the node, or `None` if no node matches.
+    pub fn resolve(&self, name: &str) -> Option<NodeID> {
+        if let Some(id) = self.names.get(name) {
+            return Some(*id);
+        }
+        match name.parse::<NodeID>() {
+            Ok(id) if (id as usize) < self.node_count => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Resolves an endpoint ID (a node ID or a numeric alias) into the ID of the routing node.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The endpoint ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<NodeID>` - The ID of the node, or `None` if no node matches.
+    pub fn resolve_id(&self, id: NodeID) -> Option<NodeID> {
+        if (id as usize) < self.node_count {
+            return Some(id);
+        }
+        self.names.get(&id.to_string()).copied()
+    }
+
     /// Retrieves the total number of nodes in the multigraph.
     ///
     /// # Returns
@@ -198,4 +557,501 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
     pub fn get_node_count(&self) -> usize {
         self.node_count
     }
+
+    /// Retrieves the bound of the contact IDs of the multigraph.
+    ///
+    /// The `ContactID`s of the contacts range from 0 to this count (excluded). This is the number
+    /// of contacts, unless contacts were removed by `remove_contacts` (the IDs are not reused).
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The bound of the contact IDs.
+    pub fn get_contact_count(&self) -> usize {
+        self.contact_count
+    }
+
+    /// Inserts a contact in the live multigraph (hot update), e.g. for the contact plan updates
+    /// received during the operations.
+    ///
+    /// The contact takes the next free `ContactID`. The routes already computed are not updated,
+    /// the caller decides which stored routes must be recomputed.
+    ///
+    /// # Parameters
+    ///
+    /// * `contact` - The contact to insert, between two distinct nodes of the multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ContactID, String>` - The ID of the contact, or an error message if a node is
+    ///   unknown or the contact is a loopback contact (a delivery window of its node).
+    pub fn insert_contact(&mut self, mut contact: Contact<NM, CM>) -> Result<ContactID, String> {
+        let tx_node = contact.get_tx_node();
+        let rx_node = contact.get_rx_node();
+        for node in [tx_node, rx_node] {
+            if node as usize >= self.node_count {
+                return Err(format!(
+                    "Contact {}->{} [{}, {}] with unknown node {}",
+                    tx_node, rx_node, contact.info.start, contact.info.end, node
+                ));
+            }
+        }
+        if tx_node == rx_node {
+            return Err(format!(
+                "Contact {}->{} [{}, {}] is a delivery window",
+                tx_node, rx_node, contact.info.start, contact.info.end
+            ));
+        }
+
+        let id = self.contact_count;
+        contact.id = id;
+        self.contact_count += 1;
+
+        let receivers = &mut self.senders[tx_node as usize].receivers;
+        let receiver = match receivers
+            .iter()
+            .position(|receiver| receiver.node.borrow().info.id == rx_node)
+        {
+            Some(idx) => &mut receivers[idx],
+            None => {
+                receivers.push(Receiver::new(
+                    self.nodes[rx_node as usize].clone(),
+                    Vec::new(),
+                ));
+                receivers.last_mut().unwrap()
+            }
+        };
+        let idx = receiver
+            .contacts_to_receiver
+            .partition_point(|other| *other.borrow() <= contact);
+        receiver
+            .contacts_to_receiver
+            .insert(idx, Rc::new(RefCell::new(contact)));
+        receiver.rebuild_index();
+        Ok(id)
+    }
+
+    /// Adds a periodic contact, its occurrences being inserted lazily by `expand_periodic`.
+    ///
+    /// # Parameters
+    ///
+    /// * `periodic` - The periodic contact, between two distinct nodes of the multigraph.
+    pub fn add_periodic(&mut self, periodic: PeriodicContact<CM>) {
+        self.periodic.push(periodic);
+    }
+
+    /// Retrieves the periodic contacts with occurrences not expanded yet.
+    pub fn get_periodic(&self) -> &[PeriodicContact<CM>] {
+        &self.periodic
+    }
+
+    /// Inserts the occurrences of the periodic contacts starting before `horizon` (e.g. the
+    /// current time plus the planning lookahead), see `insert_contact`. The periodic contacts
+    /// whose occurrences are all expanded are dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon` - The end of the planning horizon.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, String>` - The count of contacts inserted, or an error message if an
+    ///   occurrence cannot be created or inserted.
+    pub fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        let mut periodic = core::mem::take(&mut self.periodic);
+        let mut inserted = 0;
+        let mut result = Ok(());
+        for template in &mut periodic {
+            result = template.expand_until::<NM>(horizon).and_then(|contacts| {
+                for contact in contacts {
+                    self.insert_contact(contact)?;
+                    inserted += 1;
+                }
+                Ok(())
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        periodic.retain(|template| !template.is_exhausted());
+        self.periodic = periodic;
+        result.map(|_| inserted)
+    }
+
+    /// Checks that the contacts of every receiver are well-formed, see `Receiver::check_ordering`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - An error message describing the first issue found.
+    pub fn check_ordering(&self) -> Result<(), String> {
+        for (tx_node, sender) in self.senders.iter().enumerate() {
+            for receiver in &sender.receivers {
+                receiver.check_ordering()?;
+                if let Some(contact) = receiver.contacts_to_receiver.first() {
+                    let contact = contact.borrow();
+                    if contact.info.tx_node as usize != tx_node {
+                        return Err(format!(
+                            "Contacts from node {} in the receivers of node {}",
+                            contact.info.tx_node, tx_node
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Repairs the ordering of the contacts of every receiver, see `Receiver::repair_ordering`.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - A warning for each contact out of order.
+    pub fn repair_ordering(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for sender in &mut self.senders {
+            for receiver in &mut sender.receivers {
+                warnings.extend(receiver.repair_ordering());
+            }
+        }
+        warnings
+    }
+
+    /// Removes contacts from the live multigraph (hot update).
+    ///
+    /// The IDs of the removed contacts are not reused. The routes already computed may still
+    /// refer to the removed contacts, the caller decides which stored routes must be dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx_node` - The transmitting node of the contacts.
+    /// * `rx_node` - The receiving node of the contacts.
+    /// * `predicate` - Selects the contacts of the link to remove.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Rc<RefCell<Contact<NM, CM>>>>` - The removed contacts, sorted by start time.
+    pub fn remove_contacts<F: FnMut(&Contact<NM, CM>) -> bool>(
+        &mut self,
+        tx_node: NodeID,
+        rx_node: NodeID,
+        mut predicate: F,
+    ) -> Vec<Rc<RefCell<Contact<NM, CM>>>> {
+        let mut removed = Vec::new();
+        let Some(sender) = self.senders.get_mut(tx_node as usize) else {
+            return removed;
+        };
+        let Some(receiver) = sender
+            .receivers
+            .iter_mut()
+            .find(|receiver| receiver.node.borrow().info.id == rx_node)
+        else {
+            return removed;
+        };
+        receiver.contacts_to_receiver.retain(|contact| {
+            if predicate(&contact.borrow()) {
+                removed.push(contact.clone());
+                return false;
+            }
+            true
+        });
+        if !removed.is_empty() {
+            receiver.rebuild_index();
+        }
+        removed
+    }
+
+    /// Feeds the queue of a link observed outside of the router (e.g. by telemetry) to the
+    /// contact of the link active at the time of the observation, see
+    /// `ContactManager::set_observed_queue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx_node` - The transmitting node of the link.
+    /// * `rx_node` - The receiving node of the link.
+    /// * `volume_per_priority` - The volume queued for each priority level, from the lowest.
+    /// * `observed_at` - The time of the observation.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if no contact of the link is active at the time of the observation, or
+    ///   if its manager does not model a queue.
+    pub fn set_observed_queue(
+        &mut self,
+        tx_node: NodeID,
+        rx_node: NodeID,
+        volume_per_priority: &[Volume],
+        observed_at: Date,
+    ) -> bool {
+        let Some(receiver) = self.senders.get(tx_node as usize).and_then(|sender| {
+            sender
+                .receivers
+                .iter()
+                .find(|receiver| receiver.node.borrow().info.id == rx_node)
+        }) else {
+            return false;
+        };
+        let Some(first_idx) = receiver.first_idx_at(observed_at) else {
+            return false;
+        };
+        for contact in &receiver.contacts_to_receiver[first_idx..] {
+            let mut contact = contact.borrow_mut();
+            if contact.info.start > observed_at {
+                break;
+            }
+            if contact.info.end <= observed_at {
+                continue;
+            }
+            let info = contact.info;
+            return contact
+                .manager
+                .set_observed_queue(&info, volume_per_priority, observed_at);
+        }
+        false
+    }
+
+    /// Reports the memory footprint of the multigraph, e.g. to size a deployment on a constrained
+    /// gateway.
+    ///
+    /// # Returns
+    ///
+    /// * `MemoryReport` - The counts and the approximate bytes.
+    pub fn memory_report(&self) -> MemoryReport {
+        // the strong and weak counts of an `Rc` allocation
+        let rc_overhead = 2 * size_of::<usize>();
+        let string_bytes = |string: &String| size_of::<String>() + string.capacity();
+
+        let mut node_bytes = self.nodes.capacity() * size_of::<Rc<RefCell<Node<NM>>>>();
+        for node in &self.nodes {
+            let node = node.borrow();
+            node_bytes += rc_overhead + size_of::<RefCell<Node<NM>>>() + node.info.name.capacity();
+            node_bytes += node.info.delivery_windows.capacity() * size_of::<DeliveryWindow>();
+            node_bytes += node.info.aliases.iter().map(string_bytes).sum::<usize>();
+            node_bytes += node
+                .info
+                .attributes
+                .iter()
+                .map(|(key, value)| string_bytes(key) + string_bytes(value))
+                .sum::<usize>();
+        }
+        node_bytes += self
+            .names
+            .keys()
+            .map(|name| string_bytes(name) + size_of::<NodeID>())
+            .sum::<usize>();
+        node_bytes += self.down_nodes.capacity() * size_of::<NodeID>();
+
+        let mut contacts = 0;
+        let mut receivers = 0;
+        let mut receiver_bytes = self.senders.capacity() * size_of::<Sender<NM, CM>>();
+        for sender in &self.senders {
+            receivers += sender.receivers.len();
+            receiver_bytes += sender.receivers.capacity() * size_of::<Receiver<NM, CM>>();
+            for receiver in &sender.receivers {
+                contacts += receiver.contacts_to_receiver.len();
+                receiver_bytes += receiver.contacts_to_receiver.capacity()
+                    * size_of::<Rc<RefCell<Contact<NM, CM>>>>();
+                receiver_bytes += receiver.max_ends.capacity() * size_of::<Date>();
+            }
+        }
+        let contact_bytes = contacts * (rc_overhead + size_of::<RefCell<Contact<NM, CM>>>());
+        let work_area_bytes = self.contact_count
+            * size_of::<Option<Rc<RefCell<RouteStage<NM, CM>>>>>()
+            + contacts * (rc_overhead + size_of::<RefCell<RouteStage<NM, CM>>>());
+
+        MemoryReport {
+            nodes: self.nodes.len(),
+            contacts,
+            receivers,
+            node_bytes,
+            contact_bytes,
+            receiver_bytes,
+            work_area_bytes,
+            total_bytes: size_of::<Self>() + node_bytes + contact_bytes + receiver_bytes,
+        }
+    }
+
+    /// Compacts the multigraph: the contacts and delivery windows ended at the current time are
+    /// dropped, then the vectors are shrunk to their length.
+    ///
+    /// Like with `remove_contacts`, the IDs of the dropped contacts are not reused and the routes
+    /// already computed may still refer to them (the routes through ended contacts are expired).
+    ///
+    /// # Parameters
+    ///
+    /// * `curr_time` - The current time, the data ended at this time is dropped.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The count of contacts dropped.
+    pub fn compact(&mut self, curr_time: Date) -> usize {
+        let mut dropped = 0;
+        for sender in &mut self.senders {
+            for receiver in &mut sender.receivers {
+                let before = receiver.contacts_to_receiver.len();
+                receiver
+                    .contacts_to_receiver
+                    .retain(|contact| contact.borrow().info.end > curr_time);
+                dropped += before - receiver.contacts_to_receiver.len();
+                receiver.contacts_to_receiver.shrink_to_fit();
+                receiver.rebuild_index();
+                receiver.max_ends.shrink_to_fit();
+            }
+            // the receivers left without contacts are kept, their indices may be cached (e.g. by
+            // `BackwardPathfinding`)
+            sender.receivers.shrink_to_fit();
+        }
+        for node in &self.nodes {
+            let mut node = node.borrow_mut();
+            node.info
+                .delivery_windows
+                .retain(|window| window.end > curr_time);
+            node.info.delivery_windows.shrink_to_fit();
+            node.info.aliases.shrink_to_fit();
+        }
+        self.expire_down_nodes(curr_time);
+        self.down_nodes.shrink_to_fit();
+        self.names.shrink_to_fit();
+        dropped
+    }
+}
+
+/// Incremental construction of a `Multigraph`, for the topologies built programmatically
+/// (generators, FFI) rather than parsed from a contact plan.
+///
+/// The nodes and contacts can be added in any order, `build` validates the topology before
+/// handing it to `Multigraph::new`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct MultigraphBuilder<NM: NodeManager, CM: ContactManager> {
+    /// The nodes added so far.
+    nodes: Vec<Node<NM>>,
+    /// The contacts added so far.
+    contacts: Vec<Contact<NM, CM>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Default for MultigraphBuilder<NM, CM> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> MultigraphBuilder<NM, CM> {
+    /// Creates an empty builder.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `MultigraphBuilder`.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            contacts: Vec::new(),
+        }
+    }
+
+    /// Creates an empty builder with preallocated storage.
+    ///
+    /// # Parameters
+    ///
+    /// * `node_count` - The expected count of nodes.
+    /// * `contact_count` - The expected count of contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `MultigraphBuilder`.
+    pub fn with_capacity(node_count: usize, contact_count: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(node_count),
+            contacts: Vec::with_capacity(contact_count),
+        }
+    }
+
+    /// Reserves storage for additional nodes and contacts.
+    pub fn reserve(&mut self, additional_nodes: usize, additional_contacts: usize) -> &mut Self {
+        self.nodes.reserve(additional_nodes);
+        self.contacts.reserve(additional_contacts);
+        self
+    }
+
+    /// Adds a node.
+    pub fn add_node(&mut self, node: Node<NM>) -> &mut Self {
+        self.nodes.push(node);
+        self
+    }
+
+    /// Adds a contact.
+    pub fn add_contact(&mut self, contact: Contact<NM, CM>) -> &mut Self {
+        self.contacts.push(contact);
+        self
+    }
+
+    /// Creates and adds a contact.
+    ///
+    /// # Parameters
+    ///
+    /// * `info` - The contact information.
+    /// * `manager` - The contact manager.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&mut Self, String>` - The builder, or an error message if the contact times or
+    ///   the manager are invalid (see `Contact::try_new`).
+    pub fn try_add_contact(&mut self, info: ContactInfo, manager: CM) -> Result<&mut Self, String> {
+        match Contact::try_new(info, manager) {
+            Some(contact) => Ok(self.add_contact(contact)),
+            None => Err(format!(
+                "Invalid contact {}->{} [{}, {}]",
+                info.tx_node, info.rx_node, info.start, info.end
+            )),
+        }
+    }
+
+    /// Adds the nodes of an iterator.
+    pub fn extend_nodes<I: IntoIterator<Item = Node<NM>>>(&mut self, nodes: I) -> &mut Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    /// Adds the contacts of an iterator.
+    pub fn extend_contacts<I: IntoIterator<Item = Contact<NM, CM>>>(
+        &mut self,
+        contacts: I,
+    ) -> &mut Self {
+        self.contacts.extend(contacts);
+        self
+    }
+
+    /// Validates the topology and builds the `Multigraph`.
+    ///
+    /// The node IDs must range from 0 to the node count (excluded), without duplicates, and the
+    /// contacts must be between known nodes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Multigraph<NM, CM>, String>` - The multigraph, or an error message describing
+    ///   the first inconsistency found.
+    pub fn build(mut self) -> Result<Multigraph<NM, CM>, String> {
+        self.nodes.sort_unstable();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if node.info.id as usize != idx {
+                return Err(if idx > 0 && node.info.id == self.nodes[idx - 1].info.id {
+                    format!("Duplicate node {}", node.info.id)
+                } else {
+                    format!("Missing node {}", idx)
+                });
+            }
+        }
+
+        let node_count = self.nodes.len();
+        for contact in &self.contacts {
+            let info = &contact.info;
+            for node in [info.tx_node, info.rx_node] {
+                if node as usize >= node_count {
+                    return Err(format!(
+                        "Contact {}->{} [{}, {}] with unknown node {}",
+                        info.tx_node, info.rx_node, info.start, info.end, node
+                    ));
+                }
+            }
+        }
+
+        Ok(Multigraph::new(self.nodes, self.contacts))
+    }
 }
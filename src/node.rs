@@ -1,22 +1,102 @@
-use std::cmp::Ordering;
+use alloc::{format, string::String, vec::Vec};
+use core::cmp::Ordering;
 
 use crate::{
     node_manager::NodeManager,
     parsing::{Lexer, Parser, ParsingState},
-    types::{NodeID, NodeName, Token},
+    types::{Date, HashMap, NodeID, NodeName, Token},
 };
 
+/// A time window during which a node can deliver bundles to its local applications, declared by
+/// a registration (loopback) contact of the contact plan, i.e. a contact from the node to itself.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct DeliveryWindow {
+    /// The start time of the window.
+    pub start: Date,
+    /// The end time of the window.
+    pub end: Date,
+}
+
+/// The roles an excluded node is barred from (see `Multigraph::set_exclusion_scope`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum ExclusionScope {
+    /// The node is skipped entirely, neither relaying nor receiving the bundles.
+    #[default]
+    Node,
+    /// The node does not relay the bundles, but remains a valid final destination.
+    Relay,
+    /// The node relays the bundles, but is not a valid final destination.
+    Destination,
+}
+
 /// Represents information about a node in the network.
 ///
 /// # Fields
 ///
 /// * `id` - The unique identifier for the node.
 /// * `name` - The name associated with the node.
+/// * `excluded` - Whether the node is excluded from the current pathfinding.
+/// * `exclusion_scope` - The roles the node is barred from when excluded.
+/// * `delivery_windows` - The delivery windows declared by the loopback contacts of the plan.
+/// * `attributes` - Arbitrary key/value attributes (e.g. region, custody capability, energy class),
+///   declared with `key=value` tokens after the node name.
+/// * `aliases` - The other endpoint names or IDs of the node, declared with `alias` elements.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct NodeInfo {
     pub id: NodeID,
     pub name: NodeName,
     pub excluded: bool,
+    pub exclusion_scope: ExclusionScope,
+    pub delivery_windows: Vec<DeliveryWindow>,
+    pub attributes: HashMap<String, String>,
+    pub aliases: Vec<NodeName>,
+}
+
+impl NodeInfo {
+    /// Retrieves the value of an attribute of the node.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the attribute.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The value of the attribute, or `None` if the node does not declare it.
+    pub fn get_attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    /// Checks whether the node is excluded from relaying the bundles.
+    pub fn excluded_as_relay(&self) -> bool {
+        self.excluded && self.exclusion_scope != ExclusionScope::Destination
+    }
+
+    /// Checks whether the node is excluded from being the final destination of the bundles.
+    pub fn excluded_as_destination(&self) -> bool {
+        self.excluded && self.exclusion_scope != ExclusionScope::Relay
+    }
+
+    /// Checks whether the node can deliver bundles locally at a given time.
+    ///
+    /// A node without delivery windows (no loopback contact in the plan) is always considered
+    /// able to deliver.
+    ///
+    /// # Parameters
+    ///
+    /// * `time` - The time of the delivery.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the time falls in a delivery window or if the node has no window.
+    pub fn can_deliver_at(&self, time: Date) -> bool {
+        self.delivery_windows.is_empty()
+            || self
+                .delivery_windows
+                .iter()
+                .any(|window| window.start <= time && time <= window.end)
+    }
 }
 
 /// Represents a node in the network, including its information and associated manager.
@@ -30,6 +110,9 @@ pub struct Node<NM: NodeManager> {
     pub info: NodeInfo,
     /// The manager responsible for handling the node's operations.
     pub manager: NM,
+    /// The time until which the node is considered down as a neighbor (e.g. after a missed
+    /// beacon): no transmission toward the node starts before this time.
+    pub down_until: Option<Date>,
 }
 
 impl<NM: NodeManager> Node<NM> {
@@ -43,8 +126,15 @@ impl<NM: NodeManager> Node<NM> {
     /// # Returns
     ///
     /// * `Option<Self>` - An `Option` containing the new node if successful, or `None`.
-    pub fn try_new(info: NodeInfo, manager: NM) -> Option<Self> {
-        Some(Node { info, manager })
+    pub fn try_new(info: NodeInfo, mut manager: NM) -> Option<Self> {
+        if manager.try_init(&info) {
+            return Some(Node {
+                info,
+                manager,
+                down_until: None,
+            });
+        }
+        None
     }
 
     /// Retrieves the ID of the node.
@@ -64,6 +154,23 @@ impl<NM: NodeManager> Node<NM> {
     pub fn get_node_name(&self) -> NodeName {
         self.info.name.clone()
     }
+
+    /// Computes the earliest time from a given time at which a transmission toward the node can
+    /// start, considering its liveness (see `down_until`).
+    ///
+    /// # Parameters
+    ///
+    /// * `time` - The time from which a transmission is requested.
+    ///
+    /// # Returns
+    ///
+    /// * `Date` - `time`, or the end of the down state if the node is down at `time`.
+    pub fn up_from(&self, time: Date) -> Date {
+        match self.down_until {
+            Some(until) if until > time => until,
+            _ => time,
+        }
+    }
 }
 
 impl<NM: NodeManager> Ord for Node<NM> {
@@ -129,10 +236,31 @@ impl Parser<NodeInfo> for NodeInfo {
                 ))
             }
         }
+
+        // the optional key=value attributes, the manager tokens follow
+        let mut attributes = HashMap::new();
+        while let ParsingState::Finished(token) = lexer.lookup() {
+            let Some((key, value)) = token.split_once('=') else {
+                break;
+            };
+            if key.is_empty() {
+                return ParsingState::Error(format!(
+                    "Empty attribute key ({})",
+                    lexer.get_current_position()
+                ));
+            }
+            attributes.insert(String::from(key), String::from(value));
+            lexer.consume_next_token();
+        }
+
         ParsingState::Finished(NodeInfo {
             id,
             name,
             excluded: false,
+            exclusion_scope: ExclusionScope::Node,
+            delivery_windows: Vec::new(),
+            attributes,
+            aliases: Vec::new(),
         })
     }
 }
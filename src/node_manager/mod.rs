@@ -1,3 +1,7 @@
+use alloc::boxed::Box;
+
+use crate::node::NodeInfo;
+
 #[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 use crate::{bundle::Bundle, types::Date};
 
@@ -102,6 +106,19 @@ macro_rules! define_node_manager {
             /// - `true` if the reception is successfully scheduled within the window, `false` otherwise.
             #[cfg(feature = "node_rx")]
             fn schedule_rx(&mut self, start: Date, end: Date, bundle: &Bundle) -> bool;
+
+            /// Finalize the initialization of the node and notify if the initialization is consistent.
+            ///
+            /// The node information is provided once, e.g. to read the node attributes.
+            ///
+            /// # Parameters
+            /// - `node_info`: Reference to the node information.
+            ///
+            /// # Returns
+            /// - `true` if the initialization is consistent (the default).
+            fn try_init(&mut self, _node_info: &NodeInfo) -> bool {
+                true
+            }
         }
 
         /// Implementation of `NodeManager` for boxed types that implement `NodeManager`.
@@ -135,12 +152,16 @@ macro_rules! define_node_manager {
                 end: Date,
                 bundle: &Bundle,
             ) -> bool {
-                (**self).dry_run_tx(waiting_since, start, end, bundle)
+                (**self).schedule_tx(waiting_since, start, end, bundle)
             }
             /// Delegates the schedule method to the boxed object.
             #[cfg(feature = "node_rx")]
             fn schedule_rx(&mut self, start: Date, end: Date, bundle: &Bundle) -> bool {
-                (**self).dry_run_rx(start, end, bundle)
+                (**self).schedule_rx(start, end, bundle)
+            }
+            /// Delegates the try_init method to the boxed object.
+            fn try_init(&mut self, node_info: &NodeInfo) -> bool {
+                (**self).try_init(node_info)
             }
         }
 
@@ -175,19 +196,23 @@ macro_rules! define_node_manager {
                 end: Date,
                 bundle: &Bundle,
             ) -> bool {
-                (**self).dry_run_tx(waiting_since, start, end, bundle)
+                (**self).schedule_tx(waiting_since, start, end, bundle)
             }
             /// Delegates the schedule method to the boxed object.
             #[cfg(feature = "node_rx")]
             fn schedule_rx(&mut self, start: Date, end: Date, bundle: &Bundle) -> bool {
-                (**self).dry_run_rx(start, end, bundle)
+                (**self).schedule_rx(start, end, bundle)
+            }
+            /// Delegates the try_init method to the boxed object.
+            fn try_init(&mut self, node_info: &NodeInfo) -> bool {
+                (**self).try_init(node_info)
             }
         }
     }
 }
 
 #[cfg(feature = "debug")]
-define_node_manager!(std::fmt::Debug);
+define_node_manager!(core::fmt::Debug);
 
 #[cfg(not(feature = "debug"))]
 define_node_manager!();
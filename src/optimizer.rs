@@ -0,0 +1,228 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+
+use crate::{
+    contact_manager::ContactManager,
+    contact_plan::sanitize::SplitMix64,
+    node_manager::NodeManager,
+    routing::Router,
+    workload::{play_trace, TraceMetrics, WorkloadTrace},
+};
+
+/// An adjustable property of a contact plan (e.g. the duration of a downlink allocation), as a
+/// bounded real value.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct PlanParameter {
+    /// The name of the parameter, for the reports.
+    pub name: String,
+    /// The lowest value allowed.
+    pub min: f64,
+    /// The highest value allowed.
+    pub max: f64,
+    /// The largest change of the value in a single move of the search.
+    pub step: f64,
+}
+
+/// The outcome of `AnnealingOptimizer::optimize`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct OptimizationResult {
+    /// The best assignment found, one value per parameter.
+    pub parameters: Vec<f64>,
+    /// The cost of the best assignment.
+    pub cost: f64,
+    /// The replay results of the best assignment.
+    pub metrics: TraceMetrics,
+    /// The count of assignments evaluated (including the initial one).
+    pub evaluations: usize,
+    /// The count of moves accepted by the search.
+    pub accepted: usize,
+}
+
+/// Computes the cost of a replay: the mean delivery latency over the requested destinations, each
+/// destination not reached counting as `unreached_penalty`.
+///
+/// # Parameters
+///
+/// * `metrics` - The replay results.
+/// * `unreached_penalty` - The latency charged for each destination not reached.
+///
+/// # Returns
+///
+/// * `f64` - The cost, 0 for an empty workload.
+pub fn trace_cost(metrics: &TraceMetrics, unreached_penalty: f64) -> f64 {
+    if metrics.destinations == 0 {
+        return 0.0;
+    }
+    let unreached = (metrics.destinations - metrics.reached) as f64;
+    (metrics.mean_latency * metrics.reached as f64 + unreached_penalty * unreached)
+        / metrics.destinations as f64
+}
+
+/// A planning aid searching for the contact plan parameters minimizing the mean delivery latency
+/// of a workload, the routing engine being the evaluator.
+///
+/// The search is a simulated annealing: each move changes a single parameter by a random amount
+/// (at most its `step`), the moves increasing the cost by `delta` being accepted with a probability
+/// `exp(-delta / temperature)`. The temperature decreases geometrically at each move, a null
+/// initial temperature giving a plain local search (only the improving moves are accepted). The
+/// search only depends on the inputs and on the seed.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct AnnealingOptimizer {
+    /// The seed of the moves.
+    seed: u64,
+    /// The count of moves.
+    iterations: usize,
+    /// The temperature of the first move.
+    initial_temperature: f64,
+    /// The factor applied to the temperature after each move.
+    cooling_rate: f64,
+    /// The latency charged for each destination not reached.
+    unreached_penalty: f64,
+}
+
+impl AnnealingOptimizer {
+    /// Creates an optimizer with 1000 moves, an initial temperature of 1, a cooling rate of 0.99
+    /// and a penalty of 1e6 per destination not reached.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the moves.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `AnnealingOptimizer`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            iterations: 1000,
+            initial_temperature: 1.0,
+            cooling_rate: 0.99,
+            unreached_penalty: 1e6,
+        }
+    }
+
+    /// Sets the count of moves.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the temperature of the first move and the factor applied after each move.
+    pub fn temperature(mut self, initial_temperature: f64, cooling_rate: f64) -> Self {
+        self.initial_temperature = initial_temperature;
+        self.cooling_rate = cooling_rate;
+        self
+    }
+
+    /// Sets the latency charged for each destination not reached.
+    pub fn unreached_penalty(mut self, unreached_penalty: f64) -> Self {
+        self.unreached_penalty = unreached_penalty;
+        self
+    }
+
+    /// Searches for the parameter values minimizing the cost of a workload.
+    ///
+    /// `build` creates a fresh router for an assignment (typically by writing the values into the
+    /// adjustable contacts of the plan), or returns `None` if the assignment gives an invalid plan
+    /// (e.g. overlapping allocations), such assignments being rejected. A fresh router is required
+    /// for each evaluation since the replays consume the contact resources.
+    ///
+    /// # Parameters
+    ///
+    /// * `trace` - The workload to replay for each assignment.
+    /// * `parameters` - The adjustable parameters.
+    /// * `initial` - The initial assignment, one value per parameter.
+    /// * `build` - The router factory.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OptimizationResult, String>` - The best assignment found, or an error message if
+    ///   the inputs are inconsistent or if the initial assignment is invalid.
+    pub fn optimize<NM: NodeManager, CM: ContactManager, F>(
+        &self,
+        trace: &WorkloadTrace,
+        parameters: &[PlanParameter],
+        initial: &[f64],
+        mut build: F,
+    ) -> Result<OptimizationResult, String>
+    where
+        F: FnMut(&[f64]) -> Option<Box<dyn Router<NM, CM>>>,
+    {
+        if parameters.len() != initial.len() {
+            return Err(format!(
+                "{} initial values provided for {} parameters",
+                initial.len(),
+                parameters.len()
+            ));
+        }
+        for (parameter, value) in parameters.iter().zip(initial) {
+            if parameter.min > parameter.max || *value < parameter.min || *value > parameter.max {
+                return Err(format!(
+                    "Initial value {} out of the bounds of parameter \"{}\"",
+                    value, parameter.name
+                ));
+            }
+        }
+
+        let mut evaluate = |values: &[f64]| -> Option<(f64, TraceMetrics)> {
+            let mut router = build(values)?;
+            let metrics = play_trace(&mut *router, trace);
+            Some((trace_cost(&metrics, self.unreached_penalty), metrics))
+        };
+
+        let Some((initial_cost, initial_metrics)) = evaluate(initial) else {
+            return Err("The initial assignment gives an invalid plan".into());
+        };
+        let mut best = OptimizationResult {
+            parameters: initial.to_vec(),
+            cost: initial_cost,
+            metrics: initial_metrics,
+            evaluations: 1,
+            accepted: 0,
+        };
+        if parameters.is_empty() {
+            return Ok(best);
+        }
+
+        let mut rng = SplitMix64::new(self.seed);
+        let mut current = initial.to_vec();
+        let mut current_cost = initial_cost;
+        let mut temperature = self.initial_temperature;
+
+        for _ in 0..self.iterations {
+            let idx = (rng.next_u64() % parameters.len() as u64) as usize;
+            let parameter = &parameters[idx];
+            let mut candidate = current.clone();
+            candidate[idx] = (candidate[idx] + (rng.next_f64() * 2.0 - 1.0) * parameter.step)
+                .clamp(parameter.min, parameter.max);
+            // drawn for every move, to keep the sequence independent from the outcomes
+            let draw = rng.next_f64();
+            let move_temperature = temperature;
+            temperature *= self.cooling_rate;
+
+            let Some((cost, metrics)) = evaluate(&candidate) else {
+                continue;
+            };
+            best.evaluations += 1;
+
+            let delta = cost - current_cost;
+            let accept = delta <= 0.0
+                || (move_temperature > 0.0 && draw < (-delta / move_temperature).exp());
+            if !accept {
+                continue;
+            }
+            best.accepted += 1;
+            current_cost = cost;
+            if cost < best.cost {
+                best.parameters = candidate.clone();
+                best.cost = cost;
+                best.metrics = metrics;
+            }
+            current = candidate;
+        }
+
+        Ok(best)
+    }
+}
@@ -1,6 +1,15 @@
-use std::collections::HashMap;
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
 
-use crate::{contact_manager::ContactManager, node_manager::NodeManager};
+use crate::{
+    contact_manager::ContactManager, epoch::Epoch, node_manager::NodeManager, types::HashMap,
+};
 
 pub type ContactMarkerMap<'a> = Dispatcher<'a, ContactDispatcher>;
 pub type NodeMarkerMap<'a> = Dispatcher<'a, NodeDispatcher>;
@@ -44,7 +53,7 @@ impl<'a, T> Dispatcher<'a, T> {
     ///
     /// An `Option` containing a reference to the value of type `T` if it exists, or `None` if
     /// the `marker` is not found.
-    pub fn get(&self, marker: &'a str) -> Option<&T> {
+    pub fn get(&self, marker: &str) -> Option<&T> {
         return self.map.get(marker);
     }
 }
@@ -59,6 +68,128 @@ pub enum ParsingState<T> {
     Finished(T),
 }
 
+/// The location of a token in the input of a lexer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    /// The line of the token (starting at 1).
+    pub line: u32,
+    /// The position of the token in its line (starting at 1).
+    pub token: u32,
+    /// The byte offset of the token start in the input.
+    pub start: usize,
+    /// The byte offset of the token end (excluded) in the input.
+    pub end: usize,
+    /// The byte offset of the token start in its line.
+    pub column: usize,
+    /// The text of the token.
+    pub text: String,
+    /// The text of the line containing the token.
+    pub line_text: String,
+}
+
+impl Span {
+    /// Builds the span of a token of a line.
+    ///
+    /// # Parameters
+    ///
+    /// * `line` - The line number.
+    /// * `token` - The position of the token in the line.
+    /// * `line_offset` - The byte offset of the line in the input.
+    /// * `column` - The byte offset of the token in the line.
+    /// * `text` - The text of the token.
+    /// * `line_text` - The text of the line.
+    pub fn new(
+        line: u32,
+        token: u32,
+        line_offset: usize,
+        column: usize,
+        text: &str,
+        line_text: &str,
+    ) -> Self {
+        Self {
+            line,
+            token,
+            start: line_offset + column,
+            end: line_offset + column + text.len(),
+            column,
+            text: text.to_string(),
+            line_text: line_text.to_string(),
+        }
+    }
+
+    /// Returns the line of the token with the token underlined, e.g.:
+    ///
+    /// ```text
+    ///   4 | contact 0 1 6O 7260 eto 10000 10
+    ///     |             ^^
+    /// ```
+    pub fn snippet(&self) -> String {
+        let prefix = format!("{:>3} | ", self.line);
+        let padding: String = self.line_text[..self.column]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!(
+            "{}{}\n{:>width$}{}{}",
+            prefix,
+            self.line_text,
+            "| ",
+            padding,
+            "^".repeat(self.text.chars().count().max(1)),
+            width = prefix.len()
+        )
+    }
+}
+
+/// A parsing error, with the span of the last token consumed when the error occurred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The description of the error.
+    pub message: String,
+    /// The span of the offending token, if the lexer tracks spans.
+    pub span: Option<Span>,
+}
+
+impl ParseError {
+    /// Creates a new `ParseError`.
+    pub fn new(message: String, span: Option<Span>) -> Self {
+        Self { message, span }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, "\n{}", span.snippet())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Splits a line into words, with the byte offset of each word in the line.
+///
+/// # Parameters
+///
+/// * `line` - The line to split.
+///
+/// # Returns
+///
+/// * `Vec<(String, usize)>` - The words and their offsets, in order.
+pub fn split_words_with_columns(line: &str) -> Vec<(String, usize)> {
+    line.split_whitespace()
+        .map(|word| {
+            (
+                String::from(word),
+                word.as_ptr() as usize - line.as_ptr() as usize,
+            )
+        })
+        .collect()
+}
+
 /// Trait for a lexer that reads input and returns parsed tokens.
 pub trait Lexer {
     /// Looks up the next token in the input stream.
@@ -67,6 +198,35 @@ pub trait Lexer {
     fn consume_next_token(&mut self) -> ParsingState<String>;
     /// Returns the current position in the input stream.
     fn get_current_position(&self) -> String;
+    /// Consumes and returns the next token like `consume_next_token`, borrowed from the input if
+    /// the lexer holds it (see `BufferLexer`), saving an allocation per token.
+    fn consume_next_token_str(&mut self) -> ParsingState<Cow<'_, str>> {
+        match self.consume_next_token() {
+            ParsingState::Finished(token) => ParsingState::Finished(Cow::Owned(token)),
+            ParsingState::Error(msg) => ParsingState::Error(msg),
+            ParsingState::EOF => ParsingState::EOF,
+        }
+    }
+    /// Looks up the next token like `lookup`, borrowed from the input if the lexer holds it.
+    fn lookup_str(&mut self) -> ParsingState<Cow<'_, str>> {
+        match self.lookup() {
+            ParsingState::Finished(token) => ParsingState::Finished(Cow::Owned(token)),
+            ParsingState::Error(msg) => ParsingState::Error(msg),
+            ParsingState::EOF => ParsingState::EOF,
+        }
+    }
+    /// Returns the span of the last consumed token, if the lexer tracks spans.
+    fn get_current_span(&self) -> Option<Span> {
+        None
+    }
+    /// Returns the epoch declared for the input stream, if any.
+    fn get_epoch(&self) -> Option<Epoch> {
+        None
+    }
+    /// Declares the epoch of the input stream, returns `false` if the lexer does not support epochs.
+    fn set_epoch(&mut self, _epoch: Epoch) -> bool {
+        false
+    }
 }
 
 /// Trait for parsing a generic type `T` from a lexer.
@@ -219,27 +379,23 @@ macro_rules! implement_manager {
                     &Dispatcher<fn(&mut dyn Lexer) -> ParsingState<Box<dyn $manager_type>>>,
                 >,
             ) -> ParsingState<Box<dyn $manager_type>> {
-                let res = lexer.consume_next_token();
-                match res {
-                    ParsingState::EOF => ParsingState::EOF,
-                    ParsingState::Error(msg) => ParsingState::Error(msg),
+                let parse_fn = match lexer.consume_next_token_str() {
+                    ParsingState::EOF => return ParsingState::EOF,
+                    ParsingState::Error(msg) => return ParsingState::Error(msg),
                     ParsingState::Finished(marker) => {
-                        if let Some(marker_map) = marker_map_opt {
-                            if let Some(parse_fn) = marker_map.get(marker.as_str()) {
-                                parse_fn(lexer)
-                            } else {
-                                ParsingState::Error(format!(
-                                    "Unrecognized marker ({})",
-                                    lexer.get_current_position()
-                                ))
-                            }
-                        } else {
-                            ParsingState::Error(format!(
-                                "Dynamic parsing requires a map ({})",
-                                lexer.get_current_position()
-                            ))
-                        }
+                        marker_map_opt.map(|marker_map| marker_map.get(&marker).copied())
                     }
+                };
+                match parse_fn {
+                    None => ParsingState::Error(format!(
+                        "Dynamic parsing requires a map ({})",
+                        lexer.get_current_position()
+                    )),
+                    Some(None) => ParsingState::Error(format!(
+                        "Unrecognized marker ({})",
+                        lexer.get_current_position()
+                    )),
+                    Some(Some(parse_fn)) => parse_fn(lexer),
                 }
             }
         }
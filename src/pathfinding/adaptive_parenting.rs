@@ -0,0 +1,328 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+#[cfg(feature = "contact_work_area")]
+use super::contact_parenting::ContactParentingTreeExcl;
+use super::{
+    hybrid_parenting::{HybridParentingOrd, HybridParentingTreeExcl},
+    node_parenting::NodeParentingTreeExcl,
+    PathFindingOutput, Pathfinding, PathfindingLimits, PathfindingStats,
+};
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    distance::Distance,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    route_stage::{NoAnnotation, StageAnnotator},
+    types::{Date, NodeID},
+};
+
+/// The parenting strategies `AdaptiveParenting` picks from.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ParentingStrategy {
+    /// `NodeParentingTreeExcl`.
+    Node,
+    /// `HybridParentingTreeExcl`.
+    Hybrid,
+    /// `ContactParentingTreeExcl`.
+    #[cfg(feature = "contact_work_area")]
+    Contact,
+}
+
+/// The strategies, in the order they are first tried.
+const STRATEGIES: &[ParentingStrategy] = &[
+    ParentingStrategy::Node,
+    ParentingStrategy::Hybrid,
+    #[cfg(feature = "contact_work_area")]
+    ParentingStrategy::Contact,
+];
+
+/// The cost of a run counted in operations on the priority queue: the pushes, the pops and the
+/// pruned propositions. The default cost of `AdaptiveParenting`, the choices (thus the routes)
+/// being repeatable.
+///
+/// # Parameters
+///
+/// * `stats` - The statistics of the run.
+///
+/// # Returns
+///
+/// * `f64` - The cost of the run.
+pub fn operation_cost(stats: &PathfindingStats) -> f64 {
+    (stats.pushes + stats.pops + stats.pruned) as f64
+}
+
+/// The cost of a run counted in seconds, closer to the actual cost of the strategies but making
+/// the choices depend on the load of the host.
+///
+/// # Parameters
+///
+/// * `stats` - The statistics of the run.
+///
+/// # Returns
+///
+/// * `f64` - The cost of the run.
+#[cfg(feature = "std")]
+pub fn wall_time_cost(stats: &PathfindingStats) -> f64 {
+    stats.wall_time.as_secs_f64()
+}
+
+/// A pathfinding building shortest-path trees (with exclusions) with the parenting strategy that
+/// has been the cheapest on the recent calls, the cheapest strategy depending on the density of
+/// the graph (e.g. the count of contacts per pair of nodes).
+///
+/// The choice is made per call as with a multi-armed bandit: each strategy is first tried once,
+/// then the strategy with the lowest cost estimate is used, the estimate of a strategy being the
+/// exponential moving average of the costs of its runs (see `operation_cost`). To follow the
+/// changes of the graph, the strategy used the least recently is run instead periodically (see
+/// `with_exploration_period`).
+///
+/// The strategies find the same arrival times with the earliest arrival distances, but may break
+/// the ties differently, and HybridParenting may find routes that the others miss when the
+/// resources are constraining.
+///
+/// # Type Parameters
+///
+/// * `NM` - A type that implements the `NodeManager` trait.
+/// * `CM` - A type that implements the `ContactManager` trait.
+/// * `D` - A type that implements the `Distance<NM, CM>` and `HybridParentingOrd<NM, CM>` traits.
+/// * `A` - The annotator of the route stages, see `StageAnnotator`.
+pub struct AdaptiveParenting<
+    NM: NodeManager,
+    CM: ContactManager,
+    D: Distance<NM, CM> + HybridParentingOrd<NM, CM>,
+    A: StageAnnotator<NM, CM> = NoAnnotation,
+> {
+    /// The NodeParenting strategy.
+    node: NodeParentingTreeExcl<NM, CM, D, A>,
+    /// The HybridParenting strategy.
+    hybrid: HybridParentingTreeExcl<NM, CM, D, A>,
+    /// The ContactParenting strategy.
+    #[cfg(feature = "contact_work_area")]
+    contact: ContactParentingTreeExcl<NM, CM, D, A>,
+    /// The cost estimates, by strategy (in the order of `STRATEGIES`), `None` if never run.
+    estimates: Vec<Option<f64>>,
+    /// The index of the last call using each strategy.
+    last_calls: Vec<usize>,
+    /// The count of calls.
+    calls: usize,
+    /// The weight of the last run in the cost estimates.
+    smoothing: f64,
+    /// The period of the exploration calls, never exploring if 0.
+    exploration_period: usize,
+    /// The cost of a run.
+    cost: fn(&PathfindingStats) -> f64,
+    /// The strategy of the last call.
+    last_strategy: Option<ParentingStrategy>,
+    /// The statistics accumulated over the runs.
+    stats: PathfindingStats,
+}
+
+impl<
+        NM: NodeManager,
+        CM: ContactManager,
+        D: Distance<NM, CM> + HybridParentingOrd<NM, CM>,
+        A: StageAnnotator<NM, CM>,
+    > AdaptiveParenting<NM, CM, D, A>
+{
+    /// Sets the weight of the last run in the cost estimates (0.2 by default), a higher weight
+    /// following the changes of the graph faster.
+    ///
+    /// # Parameters
+    ///
+    /// * `smoothing` - The weight, between 0 (exclusive) and 1 (the last run only).
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The pathfinding with the weight.
+    pub fn with_smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Sets the period of the exploration calls (16 by default): every `period` calls, the
+    /// strategy used the least recently is run instead of the cheapest one, to update its
+    /// estimate.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The count of calls between two explorations, 0 to never explore.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The pathfinding with the period.
+    pub fn with_exploration_period(mut self, period: usize) -> Self {
+        self.exploration_period = period;
+        self
+    }
+
+    /// Sets the cost of a run (`operation_cost` by default), e.g. `wall_time_cost`.
+    ///
+    /// # Parameters
+    ///
+    /// * `cost` - Computes the cost of a run from its statistics.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The pathfinding with the cost.
+    pub fn with_cost(mut self, cost: fn(&PathfindingStats) -> f64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Returns the cost estimate of a strategy.
+    ///
+    /// # Parameters
+    ///
+    /// * `strategy` - The strategy.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f64>` - The estimate, `None` if the strategy was never run.
+    pub fn estimate(&self, strategy: ParentingStrategy) -> Option<f64> {
+        let index = STRATEGIES.iter().position(|s| *s == strategy)?;
+        self.estimates[index]
+    }
+
+    /// Returns the strategy of the last call, `None` before the first call.
+    pub fn last_strategy(&self) -> Option<ParentingStrategy> {
+        self.last_strategy
+    }
+
+    /// Picks the strategy of the next call.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The index of the strategy in `STRATEGIES`.
+    fn select(&self) -> usize {
+        // each strategy is first tried once
+        if let Some(index) = self.estimates.iter().position(Option::is_none) {
+            return index;
+        }
+        if self.exploration_period > 0 && self.calls.is_multiple_of(self.exploration_period) {
+            return (0..STRATEGIES.len())
+                .min_by_key(|index| self.last_calls[*index])
+                .unwrap_or(0);
+        }
+        let mut best = 0;
+        for index in 1..STRATEGIES.len() {
+            if self.estimates[index] < self.estimates[best] {
+                best = index;
+            }
+        }
+        best
+    }
+}
+
+impl<
+        NM: NodeManager,
+        CM: ContactManager,
+        D: Distance<NM, CM> + HybridParentingOrd<NM, CM>,
+        A: StageAnnotator<NM, CM>,
+    > Pathfinding<NM, CM> for AdaptiveParenting<NM, CM, D, A>
+{
+    /// Constructs a new `AdaptiveParenting` instance, the strategies sharing the multigraph.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - A shared pointer to a multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `AdaptiveParenting`.
+    fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
+        Self {
+            node: NodeParentingTreeExcl::new(multigraph.clone()),
+            hybrid: HybridParentingTreeExcl::new(multigraph.clone()),
+            #[cfg(feature = "contact_work_area")]
+            contact: ContactParentingTreeExcl::new(multigraph),
+            estimates: alloc::vec![None; STRATEGIES.len()],
+            last_calls: alloc::vec![0; STRATEGIES.len()],
+            calls: 0,
+            smoothing: 0.2,
+            exploration_period: 16,
+            cost: operation_cost,
+            last_strategy: None,
+            stats: PathfindingStats::default(),
+        }
+    }
+
+    /// Builds a shortest-path tree with the strategy picked for the call, and updates the cost
+    /// estimate of the strategy.
+    ///
+    /// # Parameters
+    ///
+    /// * `current_time` - The current time used for evaluating routes.
+    /// * `source` - The `NodeID` of the source node from which to begin pathfinding.
+    /// * `bundle` - The `Bundle` associated with the pathfinding operation.
+    /// * `excluded_nodes_sorted` - A sorted list of `NodeID`s to be excluded from the pathfinding.
+    ///
+    /// # Returns
+    ///
+    /// * `PathfindingOutput<NM, CM>` - The resulting pathfinding output, including the routes found.
+    fn get_next(
+        &mut self,
+        current_time: Date,
+        source: NodeID,
+        bundle: &Bundle,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> PathFindingOutput<NM, CM> {
+        let index = self.select();
+        let strategy = STRATEGIES[index];
+        let output = match strategy {
+            ParentingStrategy::Node => {
+                self.node
+                    .get_next(current_time, source, bundle, excluded_nodes_sorted)
+            }
+            ParentingStrategy::Hybrid => {
+                self.hybrid
+                    .get_next(current_time, source, bundle, excluded_nodes_sorted)
+            }
+            #[cfg(feature = "contact_work_area")]
+            ParentingStrategy::Contact => {
+                self.contact
+                    .get_next(current_time, source, bundle, excluded_nodes_sorted)
+            }
+        };
+
+        let cost = (self.cost)(output.stats());
+        self.estimates[index] = Some(match self.estimates[index] {
+            Some(estimate) => estimate + self.smoothing * (cost - estimate),
+            None => cost,
+        });
+        self.last_calls[index] = self.calls;
+        self.calls += 1;
+        self.last_strategy = Some(strategy);
+        self.stats.merge(output.stats());
+        output
+    }
+
+    /// Get a shared pointer to the multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * A shared pointer to the multigraph.
+    fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
+        self.node.get_multigraph()
+    }
+
+    /// Sets the limits of the next runs of all the strategies, see `PathfindingLimits`.
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The limits of each run.
+    fn set_limits(&mut self, limits: PathfindingLimits) {
+        self.node.set_limits(limits);
+        self.hybrid.set_limits(limits);
+        #[cfg(feature = "contact_work_area")]
+        self.contact.set_limits(limits);
+    }
+
+    /// Returns the statistics accumulated over the runs of all the strategies, see
+    /// `PathfindingStats`.
+    fn cumulative_stats(&self) -> Option<PathfindingStats> {
+        Some(self.stats)
+    }
+}
@@ -0,0 +1,299 @@
+use alloc::{collections::BinaryHeap, rc::Rc, vec, vec::Vec};
+use core::{cell::RefCell, cmp::Ordering};
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    route_stage::{RouteStage, ViaHop},
+    types::{time_from_f64, time_to_f64, Date, NodeID},
+};
+
+/// The maximum number of bisection steps to find the latest transmission time on a contact.
+const MAX_BISECTION_STEPS: usize = 64;
+
+/// The result of a backward pathfinding, see `BackwardPathfinding::latest_departure`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct LatestDeparture<NM: NodeManager, CM: ContactManager> {
+    /// The latest time at which the bundle can leave the source.
+    pub departure: Date,
+    /// The route stage reaching the destination. The route is initialized, the stages hold the
+    /// transmissions of the latest departure.
+    pub route: Rc<RefCell<RouteStage<NM, CM>>>,
+}
+
+/// A node label of the backward pathfinding, the latest time at which the bundle must be at the node.
+struct LatestLabel(Date, NodeID);
+
+impl Ord for LatestLabel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for LatestLabel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for LatestLabel {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LatestLabel {}
+
+/// The hop selected toward the destination for a node.
+type NextHop<NM, CM> = (Rc<RefCell<Contact<NM, CM>>>, ContactManagerTxData);
+
+/// A reverse-time Dijkstra, computing the latest departure from a source to meet a delivery
+/// deadline at a destination, e.g. to schedule data collection as late as possible.
+///
+/// The labels are the latest times at which the bundle must be at the nodes, starting from the
+/// deadline at the destination. The contacts are explored backward with dry runs of the contact
+/// managers, the node managers are not considered.
+///
+/// # Type Parameters
+///
+/// * `NM` - A type that implements the `NodeManager` trait.
+/// * `CM` - A type that implements the `ContactManager` trait.
+pub struct BackwardPathfinding<NM: NodeManager, CM: ContactManager> {
+    /// The node multigraph for contact access.
+    graph: Rc<RefCell<Multigraph<NM, CM>>>,
+    /// For each receiving node, the (sender index, receiver index) pairs of the multigraph leading to it.
+    incoming: Vec<Vec<(usize, usize)>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> BackwardPathfinding<NM, CM> {
+    /// Creates a backward pathfinding over a multigraph.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - A shared pointer to a multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `BackwardPathfinding`.
+    pub fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
+        let incoming = {
+            let graph = multigraph.borrow();
+            let mut incoming = vec![Vec::new(); graph.get_node_count()];
+            for (sender_idx, sender) in graph.senders.iter().enumerate() {
+                for (receiver_idx, receiver) in sender.receivers.iter().enumerate() {
+                    let rx_node = receiver.node.borrow().info.id;
+                    incoming[rx_node as usize].push((sender_idx, receiver_idx));
+                }
+            }
+            incoming
+        };
+        Self {
+            graph: multigraph,
+            incoming,
+        }
+    }
+
+    /// Get a shared pointer to the multigraph.
+    pub fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
+        self.graph.clone()
+    }
+
+    /// Finds the latest transmission on a contact for an arrival before `deadline`.
+    ///
+    /// The transmission at `not_before` must be feasible, the latest time is then searched
+    /// by bisection (after a guess assuming a constant transmission duration and delay).
+    ///
+    /// # Returns
+    ///
+    /// The latest time at the transmitting node and the corresponding transmission, `None` if the
+    /// contact cannot be used.
+    fn latest_tx(
+        contact: &Contact<NM, CM>,
+        bundle: &Bundle,
+        not_before: Date,
+        deadline: Date,
+    ) -> Option<(Date, ContactManagerTxData)> {
+        let check = |at_time: Date| {
+            contact
+                .manager
+                .dry_run_tx(&contact.info, at_time, bundle)
+                .filter(|tx_data| tx_data.arrival <= deadline)
+        };
+
+        let mut lo = Date::max(contact.info.start, not_before);
+        let mut hi = Date::min(contact.info.end, deadline);
+        if lo > hi {
+            return None;
+        }
+        let mut best = check(lo)?;
+        if let Some(tx_data) = check(hi) {
+            return Some((hi, tx_data));
+        }
+
+        let guess =
+            Date::min(contact.info.end, deadline - best.delay) - (best.tx_end - best.tx_start);
+        if lo < guess && guess < hi {
+            match check(guess) {
+                Some(tx_data) => {
+                    lo = guess;
+                    best = tx_data;
+                }
+                None => hi = guess,
+            }
+        }
+
+        for _ in 0..MAX_BISECTION_STEPS {
+            let mid = time_from_f64((time_to_f64(lo) + time_to_f64(hi)) / 2.0);
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            match check(mid) {
+                Some(tx_data) => {
+                    lo = mid;
+                    best = tx_data;
+                }
+                None => hi = mid,
+            }
+        }
+        Some((lo, best))
+    }
+
+    /// Computes the latest departure from `source` for a delivery at the destination of the bundle
+    /// before `deadline`.
+    ///
+    /// # Parameters
+    ///
+    /// * `current_time` - The earliest departure allowed.
+    /// * `source` - The `NodeID` of the source node.
+    /// * `bundle` - The unicast `Bundle` (its expiration also bounds the delivery).
+    /// * `deadline` - The delivery deadline at the destination.
+    /// * `excluded_nodes_sorted` - A vector of `NodeID`s that should be excluded from the pathfinding.
+    ///
+    /// # Returns
+    ///
+    /// The latest departure and its route, or `None` if the deadline cannot be met.
+    pub fn latest_departure(
+        &mut self,
+        current_time: Date,
+        source: NodeID,
+        bundle: &Bundle,
+        deadline: Date,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> Option<LatestDeparture<NM, CM>> {
+        if bundle.destinations.len() != 1 || bundle.destinations[0] == source {
+            return None;
+        }
+        let destination = bundle.destinations[0];
+        let deadline = Date::min(deadline, bundle.expiration);
+
+        let mut graph = self.graph.borrow_mut();
+        graph.prepare_for_exclusions_sorted(excluded_nodes_sorted);
+        if graph.senders[destination as usize]
+            .node
+            .borrow()
+            .info
+            .excluded_as_destination()
+        {
+            return None;
+        }
+
+        let node_count = graph.get_node_count();
+        let mut labels: Vec<Option<Date>> = vec![None; node_count];
+        let mut next_hops: Vec<Option<NextHop<NM, CM>>> = vec![None; node_count];
+        let mut visited = vec![false; node_count];
+        let mut priority_queue = BinaryHeap::new();
+
+        labels[destination as usize] = Some(deadline);
+        priority_queue.push(LatestLabel(deadline, destination));
+
+        while let Some(LatestLabel(label, rx_node)) = priority_queue.pop() {
+            if visited[rx_node as usize] {
+                continue;
+            }
+            visited[rx_node as usize] = true;
+            if rx_node == source {
+                break;
+            }
+
+            for &(sender_idx, receiver_idx) in &self.incoming[rx_node as usize] {
+                if visited[sender_idx] {
+                    continue;
+                }
+                let sender = &graph.senders[sender_idx];
+                if sender_idx != source as usize && sender.node.borrow().info.excluded_as_relay() {
+                    continue;
+                }
+                let receiver = &sender.receivers[receiver_idx];
+                // no transmission toward a neighbor marked down
+                let not_before = receiver.node.borrow().up_from(current_time);
+                for contact in &receiver.contacts_to_receiver {
+                    let contact_borrowed = contact.borrow();
+                    if contact_borrowed.info.start > label {
+                        break;
+                    }
+                    #[cfg(feature = "contact_suppression")]
+                    if contact_borrowed.suppressed {
+                        continue;
+                    }
+                    if let Some((at_time, tx_data)) =
+                        Self::latest_tx(&contact_borrowed, bundle, not_before, label)
+                    {
+                        if labels[sender_idx].is_none_or(|known| at_time > known) {
+                            labels[sender_idx] = Some(at_time);
+                            next_hops[sender_idx] = Some((contact.clone(), tx_data));
+                            priority_queue.push(LatestLabel(at_time, sender_idx as NodeID));
+                        }
+                    }
+                }
+            }
+        }
+
+        let departure = labels[source as usize].filter(|_| visited[source as usize])?;
+
+        let source_route = Rc::new(RefCell::new(RouteStage::new(
+            departure,
+            source,
+            None,
+            #[cfg(feature = "node_proc")]
+            bundle.clone(),
+        )));
+        source_route.borrow_mut().soft_deadline = bundle.soft_deadline;
+        let mut route = source_route;
+        let mut tx_node_id = source;
+        while tx_node_id != destination {
+            let (contact, tx_data) = next_hops[tx_node_id as usize].clone()?;
+            let rx_node_id = contact.borrow().get_rx_node();
+            let mut stage = RouteStage::new(
+                tx_data.arrival,
+                rx_node_id,
+                Some(ViaHop {
+                    contact,
+                    parent_route: route.clone(),
+                    tx_node: graph.nodes[tx_node_id as usize].clone(),
+                    rx_node: graph.nodes[rx_node_id as usize].clone(),
+                }),
+                #[cfg(feature = "node_proc")]
+                bundle.clone(),
+            );
+            {
+                let parent = route.borrow();
+                stage.hop_count = parent.hop_count + 1;
+                stage.cumulative_delay = parent.cumulative_delay + tx_data.delay;
+                stage.expiration = Date::min(
+                    tx_data.expiration - parent.cumulative_delay,
+                    parent.expiration,
+                );
+                stage.soft_deadline = parent.soft_deadline;
+            }
+            stage.tx_data = Some(tx_data);
+            route = Rc::new(RefCell::new(stage));
+            tx_node_id = rx_node_id;
+        }
+        RouteStage::init_route(route.clone());
+
+        Some(LatestDeparture { departure, route })
+    }
+}
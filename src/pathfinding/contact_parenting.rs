@@ -1,23 +1,24 @@
-use std::{
+use alloc::{collections::BinaryHeap, rc::Rc, vec, vec::Vec};
+use core::{
     cell::RefCell,
     cmp::{Ordering, Reverse},
-    collections::BinaryHeap,
     marker::PhantomData,
-    rc::Rc,
 };
 
 use crate::{
     bundle::Bundle,
-    contact::Contact,
     contact_manager::ContactManager,
     distance::{Distance, DistanceWrapper},
     multigraph::Multigraph,
     node_manager::NodeManager,
-    route_stage::RouteStage,
+    route_stage::{NoAnnotation, RouteStage, StageAnnotator},
     types::{Date, NodeID},
 };
 
-use super::{try_make_hop, PathFindingOutput, Pathfinding};
+use super::{
+    pathfinding_span, try_make_hop, Expansions, PathFindingOutput, Pathfinding, PathfindingLimits,
+    PathfindingStats,
+};
 
 macro_rules! define_contact_graph {
     ($name:ident, $is_tree_output:tt, $with_exclusions:tt) => {
@@ -29,9 +30,20 @@ macro_rules! define_contact_graph {
         ///
         /// * `NM` - A type that implements the `NodeManager` trait.
         /// * `CM` - A type that implements the `ContactManager` trait.
-        pub struct $name<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> {
+        /// * `D` - A type that implements the `Distance<NM, CM>` trait.
+        /// * `A` - A type that implements the `StageAnnotator<NM, CM>` trait (`NoAnnotation` by default).
+        pub struct $name<
+            NM: NodeManager,
+            CM: ContactManager,
+            D: Distance<NM, CM>,
+            A: StageAnnotator<NM, CM> = NoAnnotation,
+        > {
             /// The node multigraph for contact access.
             graph: Rc<RefCell<Multigraph<NM, CM>>>,
+            /// The limits of each run.
+            limits: PathfindingLimits,
+            /// The statistics accumulated over the runs.
+            stats: PathfindingStats,
             /// For tree construction, tracks the nodes visited as transmitters.
             visited_as_tx_ids: Vec<bool>,
             /// For tree construction, tracks the nodes visited as receivers.
@@ -43,10 +55,16 @@ macro_rules! define_contact_graph {
 
             #[doc(hidden)]
             _phantom_distance: PhantomData<D>,
+            #[doc(hidden)]
+            _phantom_annotator: PhantomData<A>,
         }
 
-        impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Pathfinding<NM, CM>
-            for $name<NM, CM, D>
+        impl<
+                NM: NodeManager,
+                CM: ContactManager,
+                D: Distance<NM, CM>,
+                A: StageAnnotator<NM, CM>,
+            > Pathfinding<NM, CM> for $name<NM, CM, D, A>
         {
             /// Constructs a new `ContactParenting` instance with the provided nodes and contacts.
             ///
@@ -65,11 +83,14 @@ macro_rules! define_contact_graph {
 
                 Self {
                     graph: multigraph,
+                    limits: PathfindingLimits::default(),
+                    stats: PathfindingStats::default(),
                     visited_as_tx_ids: vec![false; node_count],
                     visited_as_rx_ids: vec![false; node_count],
                     visited_as_tx_count: 1,
                     visited_as_rx_count: 1,
                     _phantom_distance: PhantomData,
+                    _phantom_annotator: PhantomData,
                 }
             }
 
@@ -95,6 +116,7 @@ macro_rules! define_contact_graph {
                 bundle: &Bundle,
                 excluded_nodes_sorted: &Vec<NodeID>,
             ) -> PathFindingOutput<NM, CM> {
+                let span = pathfinding_span(source, bundle);
                 let mut graph = self.graph.borrow_mut();
                 if $with_exclusions {
                     graph.prepare_for_exclusions_sorted(excluded_nodes_sorted);
@@ -107,6 +129,7 @@ macro_rules! define_contact_graph {
                         #[cfg(feature = "node_proc")]
                         bundle.clone(),
                     )));
+                source_route.borrow_mut().soft_deadline = bundle.soft_deadline;
 
                 let mut tree: PathFindingOutput<NM, CM> = PathFindingOutput::new(
                     &bundle,
@@ -116,7 +139,9 @@ macro_rules! define_contact_graph {
                 );
                 let mut priority_queue: BinaryHeap<Reverse<DistanceWrapper<NM, CM, D>>> =
                     BinaryHeap::new();
-                let mut altered_contacts: Vec<Rc<RefCell<Contact<NM, CM>>>> = Vec::new();
+                // The work areas are owned by this call, indexed by ContactID.
+                let mut work_areas: Vec<Option<Rc<RefCell<RouteStage<NM, CM>>>>> =
+                    vec![None; graph.get_contact_count()];
 
                 if $is_tree_output {
                     self.visited_as_tx_ids.fill(false);
@@ -128,9 +153,12 @@ macro_rules! define_contact_graph {
                 }
 
                 tree.by_destination[source as usize] = Some(source_route.clone());
+                let mut expansions = Expansions::new(self.limits);
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
+                expansions.push();
 
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
+                    expansions.pop();
                     if from_route.borrow().is_disabled {
                         continue;
                     }
@@ -141,6 +169,10 @@ macro_rules! define_contact_graph {
                             break;
                         }
                     }
+                    if !expansions.try_expand() {
+                        tree.truncated = true;
+                        break;
+                    }
 
                     if $is_tree_output {
                         if !(self.visited_as_tx_ids[tx_node_id as usize]) {
@@ -150,6 +182,13 @@ macro_rules! define_contact_graph {
                     }
 
                     let sender = &mut graph.senders[tx_node_id as usize];
+                    // a node excluded as a relay is reached, but does not forward the bundle
+                    if $with_exclusions
+                        && tx_node_id != source
+                        && sender.node.borrow().info.excluded_as_relay()
+                    {
+                        continue;
+                    }
 
                     for receiver in &mut sender.receivers {
                         if $with_exclusions {
@@ -161,7 +200,7 @@ macro_rules! define_contact_graph {
                         if let Some(first_contact_index) =
                             receiver.lazy_prune_and_get_first_idx(current_time)
                         {
-                            if let Some(route_proposition) = try_make_hop(
+                            if let Some(route_proposition) = try_make_hop::<NM, CM, A>(
                                 first_contact_index,
                                 &from_route,
                                 &bundle,
@@ -171,8 +210,8 @@ macro_rules! define_contact_graph {
                             ) {
                                 let mut push = false;
                                 if let Some(hop) = &route_proposition.via {
-                                    // todo : improve CF..
-                                    if let Some(know_route_ref) = &hop.contact.borrow().work_area {
+                                    let contact_id = hop.contact.borrow().id;
+                                    if let Some(know_route_ref) = &work_areas[contact_id] {
                                         let mut know_route = know_route_ref.borrow_mut();
                                         if D::cmp(&route_proposition, &know_route) == Ordering::Less
                                         {
@@ -182,10 +221,12 @@ macro_rules! define_contact_graph {
                                         }
                                     } else {
                                         // if "None"
-                                        altered_contacts.push(hop.contact.clone());
                                         push = true;
                                     }
                                 }
+                                if !push {
+                                    expansions.prune();
+                                }
                                 if push {
                                     let rx_node_id = receiver.node.borrow().info.id;
 
@@ -195,8 +236,8 @@ macro_rules! define_contact_graph {
                                         priority_queue.push(Reverse(DistanceWrapper::new(
                                             route_proposition_ref.clone(),
                                         )));
-                                        let contact = &hop.contact;
-                                        contact.borrow_mut().work_area =
+                                        expansions.push();
+                                        work_areas[hop.contact.borrow().id] =
                                             Some(route_proposition_ref.clone());
 
                                         // We can do this directly only in the if "Test" without the else
@@ -234,11 +275,12 @@ macro_rules! define_contact_graph {
                     }
                 }
 
-                // We replace rather than clear because some work areas became part of the output.
-                for contact in altered_contacts {
-                    contact.borrow_mut().work_area = None;
+                if $with_exclusions {
+                    tree.withdraw_excluded_destinations(&graph);
                 }
-
+                expansions.trace(&span, tree.truncated);
+                tree.stats = expansions.stats();
+                self.stats.merge(&tree.stats);
                 return tree;
             }
 
@@ -250,6 +292,20 @@ macro_rules! define_contact_graph {
             fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
                 return self.graph.clone();
             }
+
+            /// Sets the limits of the next runs, see `PathfindingLimits`.
+            ///
+            /// # Parameters
+            ///
+            /// * `limits` - The limits of each run.
+            fn set_limits(&mut self, limits: PathfindingLimits) {
+                self.limits = limits;
+            }
+
+            /// Returns the statistics accumulated over the runs, see `PathfindingStats`.
+            fn cumulative_stats(&self) -> Option<PathfindingStats> {
+                Some(self.stats)
+            }
         }
     };
 }
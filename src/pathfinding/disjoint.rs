@@ -0,0 +1,140 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, iter};
+
+#[cfg(feature = "contact_suppression")]
+use crate::contact::Contact;
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    route_storage::Route,
+    types::{Date, NodeID},
+};
+
+use super::Pathfinding;
+
+/// The independence required between the routes computed by `disjoint_routes`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Disjointness {
+    /// The routes share no intermediate node (and no contact). The pathfinding must apply the
+    /// exclusions (e.g. the `Excl` variants).
+    Node,
+    /// The routes share no contact, requires the "contact_suppression" feature.
+    #[cfg(feature = "contact_suppression")]
+    Contact,
+}
+
+/// Collects the stages of a route, from the destination to the first hop (the source stage excluded).
+fn route_stages<NM: NodeManager, CM: ContactManager>(
+    destination_stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+) -> Vec<Rc<RefCell<RouteStage<NM, CM>>>> {
+    let ancestors = destination_stage.borrow().ancestors();
+    iter::once(destination_stage.clone())
+        .chain(ancestors)
+        .filter(|stage| stage.borrow().via.is_some())
+        .collect()
+}
+
+/// Computes up to `count` pairwise disjoint routes from `source` to the destination of the bundle,
+/// e.g. for reliability-critical traffic needing independent backups.
+///
+/// The routes are computed iteratively, from the best to the worst: after each route, its
+/// intermediate nodes are excluded (`Disjointness::Node`) or its contacts are suppressed
+/// (`Disjointness::Contact`) for the next iterations. With `Disjointness::Node`, a direct route
+/// (without intermediate node) ends the computation unless the "contact_suppression" feature allows
+/// to suppress its contact. The suppressions are restored before returning, no resource is consumed.
+///
+/// # Parameters
+///
+/// * `pathfinding` - The pathfinding, a plain one (the alternative variants suppress contacts on their own).
+/// * `current_time` - The current time.
+/// * `source` - The source node.
+/// * `bundle` - The unicast bundle.
+/// * `excluded_nodes_sorted` - The nodes to exclude from all the routes.
+/// * `count` - The maximum number of routes.
+/// * `disjointness` - The independence required between the routes.
+///
+/// # Returns
+///
+/// The initialized routes, the first one being the best.
+pub fn disjoint_routes<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    current_time: Date,
+    source: NodeID,
+    bundle: &Bundle,
+    excluded_nodes_sorted: &[NodeID],
+    count: usize,
+    disjointness: Disjointness,
+) -> Vec<Route<NM, CM>> {
+    let mut routes = Vec::new();
+    if bundle.destinations.len() != 1 || bundle.destinations[0] == source {
+        return routes;
+    }
+    let dest = bundle.destinations[0];
+    let mut exclusions = excluded_nodes_sorted.to_vec();
+    #[cfg(feature = "contact_suppression")]
+    let mut suppressed: Vec<Rc<RefCell<Contact<NM, CM>>>> = Vec::new();
+
+    while routes.len() < count {
+        let tree = Rc::new(RefCell::new(pathfinding.get_next(
+            current_time,
+            source,
+            bundle,
+            &exclusions,
+        )));
+        let Some(route) = Route::from_tree(tree, dest) else {
+            break;
+        };
+        let stages = route_stages(&route.destination_stage);
+
+        let intermediate_nodes: Vec<NodeID> = stages
+            .iter()
+            .map(|stage| stage.borrow().to_node)
+            .filter(|node| *node != dest)
+            .collect();
+        // the pathfinding might not apply the exclusions
+        if intermediate_nodes
+            .iter()
+            .any(|node| exclusions.binary_search(node).is_ok())
+        {
+            break;
+        }
+
+        let mut restricted = false;
+        if disjointness == Disjointness::Node && !intermediate_nodes.is_empty() {
+            for node in intermediate_nodes {
+                if let Err(pos) = exclusions.binary_search(&node) {
+                    exclusions.insert(pos, node);
+                }
+            }
+            restricted = true;
+        }
+        #[cfg(feature = "contact_suppression")]
+        if !restricted {
+            for stage in &stages {
+                if let Some(contact) = stage.borrow().get_via_contact() {
+                    if !contact.borrow().suppressed {
+                        contact.borrow_mut().suppressed = true;
+                        suppressed.push(contact);
+                    }
+                }
+            }
+            restricted = true;
+        }
+
+        RouteStage::init_route(route.destination_stage.clone());
+        routes.push(route);
+        if !restricted {
+            break;
+        }
+    }
+
+    #[cfg(feature = "contact_suppression")]
+    for contact in suppressed {
+        contact.borrow_mut().suppressed = false;
+    }
+
+    routes
+}
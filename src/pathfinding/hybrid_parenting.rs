@@ -1,9 +1,8 @@
-use std::{
+use alloc::{collections::BinaryHeap, rc::Rc, vec, vec::Vec};
+use core::{
     cell::RefCell,
     cmp::{Ordering, Reverse},
-    collections::BinaryHeap,
     marker::PhantomData,
-    rc::Rc,
 };
 
 use crate::{
@@ -12,7 +11,7 @@ use crate::{
     distance::{Distance, DistanceWrapper},
     multigraph::Multigraph,
     node_manager::NodeManager,
-    route_stage::RouteStage,
+    route_stage::{NoAnnotation, RouteStage, StageAnnotator},
     types::{Date, NodeID},
 };
 
@@ -127,11 +126,16 @@ impl<NM: NodeManager, CM: ContactManager> HybridParentingWorkArea<NM, CM> {
             source: self.source,
             excluded_nodes_sorted: self.excluded_nodes_sorted.clone(),
             by_destination: options,
+            truncated: false,
+            stats: PathfindingStats::default(),
         };
     }
 }
 
-use super::{try_make_hop, PathFindingOutput, Pathfinding};
+use super::{
+    pathfinding_span, try_make_hop, Expansions, PathFindingOutput, Pathfinding, PathfindingLimits,
+    PathfindingStats,
+};
 
 /// Attempts to insert a new route proposal into the pathfinding output tree.
 ///
@@ -231,22 +235,31 @@ macro_rules! define_mpt {
         /// * `NM` - A type that implements the `NodeManager` trait.
         /// * `CM` - A type that implements the `ContactManager` trait.
         /// * `D` - A type that implements the `Distance<NM, CM>` trait.
+        /// * `A` - A type that implements the `StageAnnotator<NM, CM>` trait (`NoAnnotation` by default).
         pub struct $name<
             NM: NodeManager,
             CM: ContactManager,
             D: Distance<NM, CM> + HybridParentingOrd<NM, CM>,
+            A: StageAnnotator<NM, CM> = NoAnnotation,
         > {
             /// The node multigraph for contact access.
             graph: Rc<RefCell<Multigraph<NM, CM>>>,
+            /// The limits of each run.
+            limits: PathfindingLimits,
+            /// The statistics accumulated over the runs.
+            stats: PathfindingStats,
             #[doc(hidden)]
             _phantom_distance: PhantomData<D>,
+            #[doc(hidden)]
+            _phantom_annotator: PhantomData<A>,
         }
 
         impl<
                 NM: NodeManager,
                 CM: ContactManager,
                 D: Distance<NM, CM> + HybridParentingOrd<NM, CM>,
-            > Pathfinding<NM, CM> for $name<NM, CM, D>
+                A: StageAnnotator<NM, CM>,
+            > Pathfinding<NM, CM> for $name<NM, CM, D, A>
         {
             /// Constructs a new `HybridParenting` instance with the provided nodes and contacts.
             ///
@@ -260,7 +273,10 @@ macro_rules! define_mpt {
             fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
                 Self {
                     graph: multigraph,
+                    limits: PathfindingLimits::default(),
+                    stats: PathfindingStats::default(),
                     _phantom_distance: PhantomData,
+                    _phantom_annotator: PhantomData,
                 }
             }
 
@@ -286,6 +302,7 @@ macro_rules! define_mpt {
                 bundle: &Bundle,
                 excluded_nodes_sorted: &Vec<NodeID>,
             ) -> PathFindingOutput<NM, CM> {
+                let span = pathfinding_span(source, bundle);
                 let mut graph = self.graph.borrow_mut();
                 if $with_exclusions {
                     graph.prepare_for_exclusions_sorted(excluded_nodes_sorted);
@@ -298,6 +315,7 @@ macro_rules! define_mpt {
                         #[cfg(feature = "node_proc")]
                         bundle.clone(),
                     )));
+                source_route.borrow_mut().soft_deadline = bundle.soft_deadline;
                 let mut tree: HybridParentingWorkArea<NM, CM> = HybridParentingWorkArea::new(
                     bundle,
                     source_route.clone(),
@@ -308,9 +326,13 @@ macro_rules! define_mpt {
                     BinaryHeap::new();
 
                 tree.by_destination[source as usize].push(source_route.clone());
+                let mut expansions = Expansions::new(self.limits);
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
+                expansions.push();
 
+                let mut truncated = false;
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
+                    expansions.pop();
                     if from_route.borrow().is_disabled {
                         continue;
                     }
@@ -322,8 +344,19 @@ macro_rules! define_mpt {
                             break;
                         }
                     }
+                    if !expansions.try_expand() {
+                        truncated = true;
+                        break;
+                    }
 
                     let sender = &mut graph.senders[tx_node_id as usize];
+                    // a node excluded as a relay is reached, but does not forward the bundle
+                    if $with_exclusions
+                        && tx_node_id != source
+                        && sender.node.borrow().info.excluded_as_relay()
+                    {
+                        continue;
+                    }
 
                     for receiver in &mut sender.receivers {
                         if $with_exclusions {
@@ -335,7 +368,7 @@ macro_rules! define_mpt {
                         if let Some(first_contact_index) =
                             receiver.lazy_prune_and_get_first_idx(current_time)
                         {
-                            if let Some(route_proposition) = try_make_hop(
+                            if let Some(route_proposition) = try_make_hop::<NM, CM, A>(
                                 first_contact_index,
                                 &from_route,
                                 bundle,
@@ -349,6 +382,9 @@ macro_rules! define_mpt {
                                 {
                                     priority_queue
                                         .push(Reverse(DistanceWrapper::new(new_route.clone())));
+                                    expansions.push();
+                                } else {
+                                    expansions.prune();
                                 }
                             }
                         }
@@ -360,7 +396,15 @@ macro_rules! define_mpt {
                     v.truncate(1);
                 }
 
-                return tree.to_pathfinding_output();
+                expansions.trace(&span, truncated);
+                let mut output = tree.to_pathfinding_output();
+                output.truncated = truncated;
+                output.stats = expansions.stats();
+                self.stats.merge(&output.stats);
+                if $with_exclusions {
+                    output.withdraw_excluded_destinations(&graph);
+                }
+                output
             }
 
             /// Get a shared pointer to the multigraph.
@@ -371,6 +415,20 @@ macro_rules! define_mpt {
             fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
                 return self.graph.clone();
             }
+
+            /// Sets the limits of the next runs, see `PathfindingLimits`.
+            ///
+            /// # Parameters
+            ///
+            /// * `limits` - The limits of each run.
+            fn set_limits(&mut self, limits: PathfindingLimits) {
+                self.limits = limits;
+            }
+
+            /// Returns the statistics accumulated over the runs, see `PathfindingStats`.
+            fn cumulative_stats(&self) -> Option<PathfindingStats> {
+                Some(self.stats)
+            }
         }
     };
 }
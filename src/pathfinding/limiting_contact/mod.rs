@@ -2,8 +2,8 @@ use crate::contact::Contact;
 use crate::contact_manager::ContactManager;
 use crate::node_manager::NodeManager;
 use crate::route_stage::RouteStage;
-use std::cell::RefCell;
-use std::rc::Rc;
+use alloc::rc::Rc;
+use core::cell::RefCell;
 
 #[cfg(feature = "first_depleted")]
 pub mod first_depleted;
@@ -103,12 +103,12 @@ macro_rules! create_new_alternative_path_variant {
         > {
             /// The underlying pathfinding algorithm used to find individual paths.
             pathfinding: P,
-            suppression_map: Vec<Vec<std::rc::Rc<std::cell::RefCell<Contact<NM, CM>>>>>,
+            suppression_map: alloc::vec::Vec<alloc::vec::Vec<alloc::rc::Rc<core::cell::RefCell<Contact<NM, CM>>>>>,
 
             #[doc(hidden)]
-            _phantom_nm: std::marker::PhantomData<NM>,
+            _phantom_nm: core::marker::PhantomData<NM>,
             #[doc(hidden)]
-            _phantom_cm: std::marker::PhantomData<CM>,
+            _phantom_cm: core::marker::PhantomData<CM>,
         }
 
         impl<
@@ -129,15 +129,15 @@ macro_rules! create_new_alternative_path_variant {
             ///
             #[doc = concat!("* `Self` - A new instance of `", stringify!($struct_name), "`.")]
             fn new(
-                multigraph: std::rc::Rc<std::cell::RefCell<crate::multigraph::Multigraph<NM, CM>>>
+                multigraph: alloc::rc::Rc<core::cell::RefCell<crate::multigraph::Multigraph<NM, CM>>>
             ) -> Self {
                 let node_count = multigraph.borrow().get_node_count();
                 Self {
 
                     pathfinding: P::new(multigraph),
-                    suppression_map: vec![Vec::new(); node_count],
-                    _phantom_nm: std::marker::PhantomData,
-                    _phantom_cm: std::marker::PhantomData,
+                    suppression_map: alloc::vec![alloc::vec::Vec::new(); node_count],
+                    _phantom_nm: core::marker::PhantomData,
+                    _phantom_cm: core::marker::PhantomData,
                 }
             }
             /// Finds the next route based on the current state and available contacts.
@@ -157,7 +157,7 @@ macro_rules! create_new_alternative_path_variant {
                 current_time: crate::types::Date,
                 source: crate::types::NodeID,
                 bundle: &crate::bundle::Bundle,
-                excluded_nodes_sorted: &Vec<crate::types::NodeID>,
+                excluded_nodes_sorted: &alloc::vec::Vec<crate::types::NodeID>,
             ) -> crate::pathfinding::PathFindingOutput<NM, CM> {
 
                 self.suppression_map[bundle.destinations[0] as usize].retain(|contact| {
@@ -185,14 +185,63 @@ macro_rules! create_new_alternative_path_variant {
                 return tree;
             }
 
+            /// Finds the best route without the suppressions, and without preparing a new suppression.
+            ///
+            /// # Parameters
+            ///
+            /// * `current_time` - The current time used for evaluating routes.
+            /// * `source` - The `NodeID` of the source node from which to begin pathfinding.
+            /// * `bundle` - The `Bundle` associated with the pathfinding operation.
+            /// * `excluded_nodes_sorted` - A list of `NodeID`s to be excluded from the pathfinding.
+            ///
+            /// # Returns
+            ///
+            /// * `PathfindingOutput<CM>` - The resulting pathfinding output, including the routes found.
+            fn peek(
+                &mut self,
+                current_time: crate::types::Date,
+                source: crate::types::NodeID,
+                bundle: &crate::bundle::Bundle,
+                excluded_nodes_sorted: &alloc::vec::Vec<crate::types::NodeID>,
+            ) -> crate::pathfinding::PathFindingOutput<NM, CM> {
+                self.pathfinding
+                    .peek(current_time, source, bundle, excluded_nodes_sorted)
+            }
+
+            /// Drops the suppressions prepared for a destination, the next route computed toward
+            /// it being the best one.
+            ///
+            /// # Parameters
+            ///
+            /// * `dest` - The destination node.
+            fn reset_destination(&mut self, dest: crate::types::NodeID) {
+                if let Some(suppressions) = self.suppression_map.get_mut(dest as usize) {
+                    suppressions.clear();
+                }
+            }
+
             /// Get a shared pointer to the multigraph.
             ///
             /// # Returns
             ///
             /// * A shared pointer to the multigraph.
-            fn get_multigraph(&self) -> std::rc::Rc<std::cell::RefCell<crate::multigraph::Multigraph<NM, CM>>> {
+            fn get_multigraph(&self) -> alloc::rc::Rc<core::cell::RefCell<crate::multigraph::Multigraph<NM, CM>>> {
                 return self.pathfinding.get_multigraph();
             }
+
+            /// Sets the limits of the next runs of the underlying pathfinding.
+            ///
+            /// # Parameters
+            ///
+            /// * `limits` - The limits of each run.
+            fn set_limits(&mut self, limits: crate::pathfinding::PathfindingLimits) {
+                self.pathfinding.set_limits(limits);
+            }
+
+            /// Returns the statistics accumulated over the runs of the underlying pathfinding.
+            fn cumulative_stats(&self) -> Option<crate::pathfinding::PathfindingStats> {
+                self.pathfinding.cumulative_stats()
+            }
         }
     };
 }
@@ -3,18 +3,24 @@ use crate::contact_manager::{ContactManager, ContactManagerTxData};
 use crate::multigraph::Multigraph;
 use crate::node::Node;
 use crate::node_manager::NodeManager;
-use crate::route_stage::ViaHop;
-use crate::types::{Date, NodeID};
+use crate::route_stage::{StageAnnotator, Successors, ViaHop};
+use crate::telemetry::Span;
+use crate::types::{Date, NodeID, TIME_ZERO};
 use crate::{bundle::Bundle, route_stage::RouteStage};
-use std::cell::RefCell;
-use std::rc::Rc;
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::cell::RefCell;
+use serde::Serialize;
 
+pub mod adaptive_parenting;
+pub mod backward;
 #[cfg(feature = "contact_work_area")]
 pub mod contact_parenting;
+pub mod disjoint;
 pub mod hybrid_parenting;
 #[cfg(feature = "contact_suppression")]
 pub mod limiting_contact;
 pub mod node_parenting;
+pub mod testing;
 
 /// Data structure that holds the results of a pathfinding operation.
 ///
@@ -34,6 +40,11 @@ pub struct PathFindingOutput<NM: NodeManager, CM: ContactManager> {
     pub excluded_nodes_sorted: Vec<NodeID>,
     /// A vector that contains a `RouteStage`s for a specific destination node ID as the index.
     pub by_destination: Vec<Option<Rc<RefCell<RouteStage<NM, CM>>>>>,
+    /// Whether the search was stopped by the `PathfindingLimits`, the routes being the ones found
+    /// so far (possibly not the best ones, some destinations being possibly not reached).
+    pub truncated: bool,
+    /// The statistics of the run.
+    pub(crate) stats: PathfindingStats,
 }
 
 impl<NM: NodeManager, CM: ContactManager> PathFindingOutput<NM, CM> {
@@ -62,6 +73,29 @@ impl<NM: NodeManager, CM: ContactManager> PathFindingOutput<NM, CM> {
             source,
             excluded_nodes_sorted: exclusions,
             by_destination: vec![None; node_count],
+            truncated: false,
+            stats: PathfindingStats::default(),
+        }
+    }
+
+    /// Returns the statistics gathered during the construction of the output, the default
+    /// (empty) statistics if the output was not built by a run (e.g. a cached or merged output).
+    pub fn stats(&self) -> &PathfindingStats {
+        &self.stats
+    }
+
+    /// Withdraws the routes toward the nodes excluded as destinations (see `ExclusionScope`), the
+    /// stages reaching these nodes remaining the parents of the routes they relay.
+    ///
+    /// # Parameters
+    ///
+    /// * `graph` - The multigraph, with the exclusions of the pathfinding applied.
+    pub(crate) fn withdraw_excluded_destinations(&mut self, graph: &Multigraph<NM, CM>) {
+        let source = self.source.borrow().to_node;
+        for (node_id, sender) in graph.senders.iter().enumerate() {
+            if node_id != source as usize && sender.node.borrow().info.excluded_as_destination() {
+                self.by_destination[node_id] = None;
+            }
         }
     }
 
@@ -81,6 +115,229 @@ impl<NM: NodeManager, CM: ContactManager> PathFindingOutput<NM, CM> {
             RouteStage::init_route(route);
         }
     }
+
+    /// Iterates over the stages of the route to a destination, from the first hop down to the
+    /// stage reaching the destination. The route is initialized if needed (see
+    /// `init_for_destination`).
+    ///
+    /// # Parameters
+    ///
+    /// * `destination` - The target node ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Successors<NM, CM>` - The iterator, empty if no route reaches the destination.
+    pub fn path_to(&self, destination: NodeID) -> Successors<NM, CM> {
+        self.init_for_destination(destination);
+        self.source.borrow().successors(destination)
+    }
+}
+
+/// The limits of a pathfinding run, protecting the real-time agents from pathological plans.
+///
+/// When a limit is reached, the search stops and the routes found so far are returned, the output
+/// being flagged as truncated (see `PathFindingOutput::truncated`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default)]
+pub struct PathfindingLimits {
+    /// The maximum count of route stages expanded per run (unbounded if `None`).
+    pub max_expansions: Option<usize>,
+    /// The maximum time spent per run (unbounded if `None`).
+    #[cfg(feature = "std")]
+    pub max_wall_time: Option<std::time::Duration>,
+}
+
+impl PathfindingLimits {
+    /// Creates limits bounding the count of route stages expanded per run.
+    pub fn expansions(max_expansions: usize) -> Self {
+        Self {
+            max_expansions: Some(max_expansions),
+            ..Default::default()
+        }
+    }
+
+    /// Creates limits bounding the time spent per run.
+    #[cfg(feature = "std")]
+    pub fn wall_time(max_wall_time: std::time::Duration) -> Self {
+        Self {
+            max_wall_time: Some(max_wall_time),
+            ..Default::default()
+        }
+    }
+}
+
+/// The statistics of pathfinding runs, gathered during the construction of the trees.
+///
+/// The statistics of a run are attached to its output (see `PathFindingOutput::stats`), and the
+/// pathfindings and routers accumulate the ones of their runs (see `Pathfinding::cumulative_stats`
+/// and `Router::pathfinding_stats`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct PathfindingStats {
+    /// The count of runs.
+    pub runs: usize,
+    /// The count of route stages expanded, i.e. whose transmitting node was explored.
+    pub expanded: usize,
+    /// The count of route stages pushed to the priority queue.
+    pub pushes: usize,
+    /// The count of route stages popped from the priority queue, the disabled ones included.
+    pub pops: usize,
+    /// The count of route propositions pruned, i.e. not better than the routes already known.
+    pub pruned: usize,
+    /// The time spent in the runs.
+    #[cfg(feature = "std")]
+    pub wall_time: std::time::Duration,
+}
+
+impl PathfindingStats {
+    /// Adds the statistics of other runs.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The statistics to add.
+    pub fn merge(&mut self, other: &PathfindingStats) {
+        self.runs += other.runs;
+        self.expanded += other.expanded;
+        self.pushes += other.pushes;
+        self.pops += other.pops;
+        self.pruned += other.pruned;
+        #[cfg(feature = "std")]
+        {
+            self.wall_time += other.wall_time;
+        }
+    }
+
+    /// Computes the statistics of the runs made since an earlier snapshot of cumulative
+    /// statistics.
+    ///
+    /// # Parameters
+    ///
+    /// * `earlier` - The cumulative statistics at the snapshot.
+    ///
+    /// # Returns
+    ///
+    /// * `PathfindingStats` - The statistics of the runs made since the snapshot.
+    pub fn since(&self, earlier: &PathfindingStats) -> PathfindingStats {
+        PathfindingStats {
+            runs: self.runs.saturating_sub(earlier.runs),
+            expanded: self.expanded.saturating_sub(earlier.expanded),
+            pushes: self.pushes.saturating_sub(earlier.pushes),
+            pops: self.pops.saturating_sub(earlier.pops),
+            pruned: self.pruned.saturating_sub(earlier.pruned),
+            #[cfg(feature = "std")]
+            wall_time: self.wall_time.saturating_sub(earlier.wall_time),
+        }
+    }
+}
+
+/// The consumption of the `PathfindingLimits` during a run, and its statistics.
+pub(crate) struct Expansions {
+    /// The limits of the run.
+    limits: PathfindingLimits,
+    /// The statistics of the run so far.
+    stats: PathfindingStats,
+    /// The start of the run.
+    #[cfg(feature = "std")]
+    start: std::time::Instant,
+}
+
+impl Expansions {
+    /// Starts a run.
+    pub(crate) fn new(limits: PathfindingLimits) -> Self {
+        Self {
+            limits,
+            stats: PathfindingStats {
+                runs: 1,
+                ..Default::default()
+            },
+            #[cfg(feature = "std")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Counts the expansion of a route stage.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if the limits are reached, the search being to stop.
+    pub(crate) fn try_expand(&mut self) -> bool {
+        if self
+            .limits
+            .max_expansions
+            .is_some_and(|max_expansions| self.stats.expanded >= max_expansions)
+        {
+            return false;
+        }
+        #[cfg(feature = "std")]
+        if let Some(max_wall_time) = self.limits.max_wall_time {
+            if self.start.elapsed() >= max_wall_time {
+                return false;
+            }
+        }
+        self.stats.expanded += 1;
+        true
+    }
+
+    /// Counts a route stage pushed to the priority queue.
+    pub(crate) fn push(&mut self) {
+        self.stats.pushes += 1;
+    }
+
+    /// Counts a route stage popped from the priority queue.
+    pub(crate) fn pop(&mut self) {
+        self.stats.pops += 1;
+    }
+
+    /// Counts a route proposition pruned.
+    pub(crate) fn prune(&mut self) {
+        self.stats.pruned += 1;
+    }
+
+    /// Ends the run.
+    ///
+    /// # Returns
+    ///
+    /// * `PathfindingStats` - The statistics of the run.
+    pub(crate) fn stats(&self) -> PathfindingStats {
+        PathfindingStats {
+            #[cfg(feature = "std")]
+            wall_time: self.start.elapsed(),
+            ..self.stats
+        }
+    }
+
+    /// Sets the attributes of the span of the run (see `pathfinding_span`) once it is over.
+    ///
+    /// # Parameters
+    ///
+    /// * `span` - The span of the run.
+    /// * `truncated` - Whether the limits stopped the run.
+    pub(crate) fn trace(&self, span: &Span, truncated: bool) {
+        span.int("expansions", self.stats.expanded as i64);
+        span.int("pushes", self.stats.pushes as i64);
+        span.int("pops", self.stats.pops as i64);
+        span.int("pruned", self.stats.pruned as i64);
+        span.flag("truncated", truncated);
+    }
+}
+
+/// Starts the span of a pathfinding run (see `telemetry`).
+///
+/// # Parameters
+///
+/// * `source` - The source node of the run.
+/// * `bundle` - The bundle of the run.
+///
+/// # Returns
+///
+/// * `Span` - The span, ended when dropped.
+pub(crate) fn pathfinding_span(source: NodeID, bundle: &Bundle) -> Span {
+    let span = Span::start("a_sabr.pathfinding");
+    span.int("source", source.into());
+    if let Some(dest) = bundle.destinations.first() {
+        span.int("destination", (*dest).into());
+    }
+    span
 }
 
 /// The `Pathfinding` trait provides the interface for implementing a pathfinding algorithm.
@@ -123,12 +380,68 @@ pub trait Pathfinding<NM: NodeManager, CM: ContactManager> {
         excluded_nodes_sorted: &Vec<NodeID>,
     ) -> PathFindingOutput<NM, CM>;
 
+    /// Determines the routes like `get_next`, without altering the state of the pathfinding for
+    /// the next calls (e.g. the contact suppressions of the alternative pathfinding variants).
+    ///
+    /// Used for estimations, the default implementation calls `get_next`.
+    ///
+    /// # Parameters
+    ///
+    /// * `current_time` - The current time for the pathfinding operation.
+    /// * `source` - The `NodeID` of the source node.
+    /// * `bundle` - A reference to the `Bundle` being routed.
+    /// * `excluded_nodes_sorted` - A vector of `NodeID`s that should be excluded from the pathfinding.
+    ///
+    /// # Returns
+    ///
+    /// A `PathfindingOutput` containing the results of the pathfinding operation.
+    fn peek(
+        &mut self,
+        current_time: Date,
+        source: NodeID,
+        bundle: &Bundle,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> PathFindingOutput<NM, CM> {
+        self.get_next(current_time, source, bundle, excluded_nodes_sorted)
+    }
+
+    /// Forgets the state kept between the calls for a destination (e.g. the contact suppressions
+    /// of the alternative pathfinding variants), the next `get_next` call finding the best route
+    /// toward it again.
+    ///
+    /// The default implementation does nothing, the pathfinding keeping no such state.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    fn reset_destination(&mut self, _dest: NodeID) {}
+
     /// Get a shared pointer to the multigraph.
     ///
     /// # Returns
     ///
     /// * A shared pointer to the multigraph.
     fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>>;
+
+    /// Sets the limits of the next runs (unbounded by default), see `PathfindingLimits`.
+    ///
+    /// The default implementation ignores the limits.
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The limits of each run.
+    fn set_limits(&mut self, _limits: PathfindingLimits) {}
+
+    /// Returns the statistics accumulated over the runs of the pathfinding (see
+    /// `PathfindingStats`).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<PathfindingStats>` - The statistics, `None` if the pathfinding does not gather
+    ///   them (the default implementation).
+    fn cumulative_stats(&self) -> Option<PathfindingStats> {
+        None
+    }
 }
 
 /// Attempts to make a hop (i.e., a transmission between nodes) for the given route stage and bundle,
@@ -145,8 +458,9 @@ pub trait Pathfinding<NM: NodeManager, CM: ContactManager> {
 ///
 /// # Returns
 ///
-/// An `Option` containing a `RouteStage` if a suitable hop is found, or `None` if no valid hop is available.
-fn try_make_hop<NM: NodeManager, CM: ContactManager>(
+/// An `Option` containing a `RouteStage` if a suitable hop is found, or `None` if no valid hop is
+/// available or if the annotator `A` discards the proposition.
+fn try_make_hop<NM: NodeManager, CM: ContactManager, A: StageAnnotator<NM, CM>>(
     first_contact_index: usize,
     sndr_route: &Rc<RefCell<RouteStage<NM, CM>>>,
     _bundle: &Bundle,
@@ -156,10 +470,10 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
 ) -> Option<RouteStage<NM, CM>> {
     let mut index = 0;
     let mut final_data = ContactManagerTxData {
-        tx_start: 0.0,
-        tx_end: 0.0,
-        delay: 0.0,
-        expiration: 0.0,
+        tx_start: TIME_ZERO,
+        tx_end: TIME_ZERO,
+        delay: TIME_ZERO,
+        expiration: TIME_ZERO,
         arrival: Date::MAX,
     };
 
@@ -190,6 +504,12 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
             .dry_run_process(sndr_route_borrowed.at_time, &mut bundle_to_consider);
         #[cfg(not(feature = "node_proc"))]
         let sending_time = sndr_route_borrowed.at_time;
+        // no transmission toward a neighbor marked down
+        let sending_time = rx_node.borrow().up_from(sending_time);
+        // down beyond the contact (e.g. until `Date::MAX`, which would overflow the integer dates)
+        if sending_time > contact_borrowed.info.end {
+            continue;
+        }
 
         if let Some(hop) = contact_borrowed.manager.dry_run_tx(
             &contact_borrowed.info,
@@ -244,6 +564,18 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
             final_data.expiration - sndr_route_borrowed.cumulative_delay,
             sndr_route_borrowed.expiration,
         );
+        route_proposition.soft_deadline = sndr_route_borrowed.soft_deadline;
+        route_proposition.annotations = sndr_route_borrowed.annotations.clone();
+        route_proposition.tx_data = Some(final_data);
+
+        if !A::annotate(
+            &mut route_proposition,
+            &sndr_route_borrowed,
+            &seleted_contact.borrow(),
+            &final_data,
+        ) {
+            return None;
+        }
 
         return Some(route_proposition);
     }
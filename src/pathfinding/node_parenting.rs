@@ -1,7 +1,5 @@
-use std::{
-    cell::RefCell, cmp::Ordering, cmp::Reverse, collections::BinaryHeap, marker::PhantomData,
-    rc::Rc,
-};
+use alloc::{collections::BinaryHeap, rc::Rc, vec::Vec};
+use core::{cell::RefCell, cmp::Ordering, cmp::Reverse, marker::PhantomData};
 
 use crate::{
     bundle::Bundle,
@@ -9,11 +7,14 @@ use crate::{
     distance::{Distance, DistanceWrapper},
     multigraph::Multigraph,
     node_manager::NodeManager,
-    route_stage::RouteStage,
+    route_stage::{NoAnnotation, RouteStage, StageAnnotator},
     types::{Date, NodeID},
 };
 
-use super::{try_make_hop, PathFindingOutput, Pathfinding};
+use super::{
+    pathfinding_span, try_make_hop, Expansions, PathFindingOutput, Pathfinding, PathfindingLimits,
+    PathfindingStats,
+};
 
 macro_rules! define_node_graph {
     ($name:ident, $is_tree_output:tt, $with_exclusions:tt) => {
@@ -26,15 +27,31 @@ macro_rules! define_node_graph {
         /// * `NM` - A type that implements the `NodeManager` trait.
         /// * `CM` - A type that implements the `ContactManager` trait.
         /// * `D` - A type that implements the `Distance<NM, CM>` trait.
-        pub struct $name<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> {
+        /// * `A` - A type that implements the `StageAnnotator<NM, CM>` trait (`NoAnnotation` by default).
+        pub struct $name<
+            NM: NodeManager,
+            CM: ContactManager,
+            D: Distance<NM, CM>,
+            A: StageAnnotator<NM, CM> = NoAnnotation,
+        > {
             /// The node multigraph for contact access.
             graph: Rc<RefCell<Multigraph<NM, CM>>>,
+            /// The limits of each run.
+            limits: PathfindingLimits,
+            /// The statistics accumulated over the runs.
+            stats: PathfindingStats,
             #[doc(hidden)]
             _phantom_distance: PhantomData<D>,
+            #[doc(hidden)]
+            _phantom_annotator: PhantomData<A>,
         }
 
-        impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Pathfinding<NM, CM>
-            for $name<NM, CM, D>
+        impl<
+                NM: NodeManager,
+                CM: ContactManager,
+                D: Distance<NM, CM>,
+                A: StageAnnotator<NM, CM>,
+            > Pathfinding<NM, CM> for $name<NM, CM, D, A>
         {
             /// Constructs a new `NodeParenting` instance with the provided nodes and contacts.
             ///
@@ -48,7 +65,10 @@ macro_rules! define_node_graph {
             fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
                 Self {
                     graph: multigraph,
+                    limits: PathfindingLimits::default(),
+                    stats: PathfindingStats::default(),
                     _phantom_distance: PhantomData,
+                    _phantom_annotator: PhantomData,
                 }
             }
 
@@ -74,6 +94,7 @@ macro_rules! define_node_graph {
                 bundle: &Bundle,
                 excluded_nodes_sorted: &Vec<NodeID>,
             ) -> PathFindingOutput<NM, CM> {
+                let span = pathfinding_span(source, bundle);
                 let mut graph = self.graph.borrow_mut();
 
                 if $with_exclusions {
@@ -87,6 +108,7 @@ macro_rules! define_node_graph {
                         #[cfg(feature = "node_proc")]
                         bundle.clone(),
                     )));
+                source_route.borrow_mut().soft_deadline = bundle.soft_deadline;
                 let mut tree: PathFindingOutput<NM, CM> = PathFindingOutput::new(
                     bundle,
                     source_route.clone(),
@@ -105,9 +127,12 @@ macro_rules! define_node_graph {
                     }
                 }
 
+                let mut expansions = Expansions::new(self.limits);
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
+                expansions.push();
 
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
+                    expansions.pop();
                     if from_route.borrow().is_disabled {
                         continue;
                     }
@@ -117,7 +142,18 @@ macro_rules! define_node_graph {
                             break;
                         }
                     }
+                    if !expansions.try_expand() {
+                        tree.truncated = true;
+                        break;
+                    }
                     let sender = &mut graph.senders[tx_node_id as usize];
+                    // a node excluded as a relay is reached, but does not forward the bundle
+                    if $with_exclusions
+                        && tx_node_id != source
+                        && sender.node.borrow().info.excluded_as_relay()
+                    {
+                        continue;
+                    }
 
                     for receiver in &mut sender.receivers {
                         if $with_exclusions {
@@ -129,7 +165,7 @@ macro_rules! define_node_graph {
                         if let Some(first_contact_index) =
                             receiver.lazy_prune_and_get_first_idx(current_time)
                         {
-                            if let Some(route_proposition) = try_make_hop(
+                            if let Some(route_proposition) = try_make_hop::<NM, CM, A>(
                                 first_contact_index,
                                 &from_route,
                                 bundle,
@@ -155,12 +191,21 @@ macro_rules! define_node_graph {
                                     tree.by_destination[receiver.node.borrow().info.id as usize] =
                                         Some(route_ref.clone());
                                     priority_queue.push(Reverse(DistanceWrapper::new(route_ref)));
+                                    expansions.push();
+                                } else {
+                                    expansions.prune();
                                 }
                             }
                         }
                     }
                 }
 
+                if $with_exclusions {
+                    tree.withdraw_excluded_destinations(&graph);
+                }
+                expansions.trace(&span, tree.truncated);
+                tree.stats = expansions.stats();
+                self.stats.merge(&tree.stats);
                 tree
             }
 
@@ -172,6 +217,20 @@ macro_rules! define_node_graph {
             fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
                 return self.graph.clone();
             }
+
+            /// Sets the limits of the next runs, see `PathfindingLimits`.
+            ///
+            /// # Parameters
+            ///
+            /// * `limits` - The limits of each run.
+            fn set_limits(&mut self, limits: PathfindingLimits) {
+                self.limits = limits;
+            }
+
+            /// Returns the statistics accumulated over the runs, see `PathfindingStats`.
+            fn cumulative_stats(&self) -> Option<PathfindingStats> {
+                Some(self.stats)
+            }
         }
     };
 }
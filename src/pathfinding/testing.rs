@@ -0,0 +1,234 @@
+use alloc::{format, rc::Rc, string::String, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    types::{Date, NodeID},
+};
+
+use super::{PathFindingOutput, Pathfinding, PathfindingLimits, PathfindingStats};
+
+/// Verifies the invariants of a pathfinding output.
+///
+/// For each route stage registered in `by_destination`, the path back to the source is checked:
+/// - the stage is registered at the index of its `to_node`,
+/// - the path is acyclic and ends at the source stage, which starts at `current_time`,
+/// - the via contacts connect the parent node to the child node,
+/// - the arrival times are non-decreasing along the path,
+/// - the expirations are non-increasing along the path,
+/// - the hop counts increase by one at each hop,
+/// - no excluded node is reached (if `check_exclusions` is set).
+///
+/// # Parameters
+///
+/// * `output` - The output to check.
+/// * `current_time` - The time provided to `get_next`.
+/// * `source` - The source provided to `get_next`.
+/// * `check_exclusions` - Whether the output must comply with its `excluded_nodes_sorted`.
+///
+/// # Returns
+///
+/// * `Result<(), String>` - A description of the first violation found.
+pub fn check_output<NM: NodeManager, CM: ContactManager>(
+    output: &PathFindingOutput<NM, CM>,
+    current_time: Date,
+    source: NodeID,
+    check_exclusions: bool,
+) -> Result<(), String> {
+    {
+        let source_stage = output.source.borrow();
+        if source_stage.to_node != source {
+            return Err(format!(
+                "The source stage is at node {} instead of {}",
+                source_stage.to_node, source
+            ));
+        }
+        if source_stage.at_time != current_time {
+            return Err(format!(
+                "The source stage is at t={} instead of t={}",
+                source_stage.at_time, current_time
+            ));
+        }
+    }
+
+    for (dest, stage_opt) in output.by_destination.iter().enumerate() {
+        let Some(stage) = stage_opt else {
+            continue;
+        };
+        if stage.borrow().to_node as usize != dest {
+            return Err(format!(
+                "The stage registered for node {} reaches node {}",
+                dest,
+                stage.borrow().to_node
+            ));
+        }
+        check_path(output, stage, check_exclusions)
+            .map_err(|msg| format!("Invalid path to node {}: {}", dest, msg))?;
+    }
+    Ok(())
+}
+
+fn check_path<NM: NodeManager, CM: ContactManager>(
+    output: &PathFindingOutput<NM, CM>,
+    stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+    check_exclusions: bool,
+) -> Result<(), String> {
+    let mut visited: Vec<*const RefCell<RouteStage<NM, CM>>> = Vec::new();
+    let mut current = stage.clone();
+
+    loop {
+        let ptr = Rc::as_ptr(&current);
+        if visited.contains(&ptr) {
+            return Err(format!(
+                "cycle detected at node {}",
+                current.borrow().to_node
+            ));
+        }
+        visited.push(ptr);
+
+        let parent = {
+            let child = current.borrow();
+            let Some(via) = &child.via else {
+                if !Rc::ptr_eq(&current, &output.source) {
+                    return Err(format!(
+                        "the path starts at node {} which is not the source stage",
+                        child.to_node
+                    ));
+                }
+                return Ok(());
+            };
+
+            if check_exclusions
+                && output
+                    .excluded_nodes_sorted
+                    .binary_search(&child.to_node)
+                    .is_ok()
+            {
+                return Err(format!("excluded node {} is reached", child.to_node));
+            }
+
+            let parent = via.parent_route.borrow();
+            let contact = via.contact.borrow();
+            if contact.get_tx_node() != parent.to_node || contact.get_rx_node() != child.to_node {
+                return Err(format!(
+                    "the contact {} -> {} does not connect node {} to node {}",
+                    contact.get_tx_node(),
+                    contact.get_rx_node(),
+                    parent.to_node,
+                    child.to_node
+                ));
+            }
+            if child.at_time < parent.at_time {
+                return Err(format!(
+                    "the arrival time decreases from t={} (node {}) to t={} (node {})",
+                    parent.at_time, parent.to_node, child.at_time, child.to_node
+                ));
+            }
+            if child.expiration > parent.expiration {
+                return Err(format!(
+                    "the expiration increases from t={} (node {}) to t={} (node {})",
+                    parent.expiration, parent.to_node, child.expiration, child.to_node
+                ));
+            }
+            if child.hop_count != parent.hop_count + 1 {
+                return Err(format!(
+                    "the hop count goes from {} (node {}) to {} (node {})",
+                    parent.hop_count, parent.to_node, child.hop_count, child.to_node
+                ));
+            }
+            via.parent_route.clone()
+        };
+        current = parent;
+    }
+}
+
+/// A `Pathfinding` wrapper checking the invariants of each output with `check_output`.
+///
+/// The checks are only conducted in debug builds (`debug_assertions`), a violation panics.
+/// Set `WITH_EXCLUSIONS` to `false` for the variants that ignore the excluded nodes.
+///
+/// # Type Parameters
+///
+/// * `NM` - A type that implements the `NodeManager` trait.
+/// * `CM` - A type that implements the `ContactManager` trait.
+/// * `P` - The wrapped `Pathfinding` implementation.
+pub struct CheckedPathfinding<
+    NM: NodeManager,
+    CM: ContactManager,
+    P: Pathfinding<NM, CM>,
+    const WITH_EXCLUSIONS: bool = true,
+> {
+    /// The wrapped pathfinding.
+    inner: P,
+    #[doc(hidden)]
+    _phantom_nm: core::marker::PhantomData<NM>,
+    #[doc(hidden)]
+    _phantom_cm: core::marker::PhantomData<CM>,
+}
+
+impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, const WITH_EXCLUSIONS: bool>
+    Pathfinding<NM, CM> for CheckedPathfinding<NM, CM, P, WITH_EXCLUSIONS>
+{
+    fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
+        Self {
+            inner: P::new(multigraph),
+            // for compilation
+            _phantom_nm: core::marker::PhantomData,
+            _phantom_cm: core::marker::PhantomData,
+        }
+    }
+
+    fn get_next(
+        &mut self,
+        current_time: Date,
+        source: NodeID,
+        bundle: &Bundle,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> PathFindingOutput<NM, CM> {
+        let output = self
+            .inner
+            .get_next(current_time, source, bundle, excluded_nodes_sorted);
+        #[cfg(debug_assertions)]
+        if let Err(msg) = check_output(&output, current_time, source, WITH_EXCLUSIONS) {
+            panic!("Pathfinding invariant violated: {}", msg);
+        }
+        output
+    }
+
+    fn peek(
+        &mut self,
+        current_time: Date,
+        source: NodeID,
+        bundle: &Bundle,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> PathFindingOutput<NM, CM> {
+        let output = self
+            .inner
+            .peek(current_time, source, bundle, excluded_nodes_sorted);
+        #[cfg(debug_assertions)]
+        if let Err(msg) = check_output(&output, current_time, source, WITH_EXCLUSIONS) {
+            panic!("Pathfinding invariant violated: {}", msg);
+        }
+        output
+    }
+
+    fn reset_destination(&mut self, dest: NodeID) {
+        self.inner.reset_destination(dest)
+    }
+
+    fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
+        self.inner.get_multigraph()
+    }
+
+    fn set_limits(&mut self, limits: PathfindingLimits) {
+        self.inner.set_limits(limits)
+    }
+
+    fn cumulative_stats(&self) -> Option<PathfindingStats> {
+        self.inner.cumulative_stats()
+    }
+}
@@ -0,0 +1,311 @@
+//! Protobuf messages (see `proto/asabr.proto`) to exchange contact plans and routing outputs
+//! with other tools. The times of the messages are in seconds and the data rates per second (see
+//! `TICKS_PER_SECOND`).
+
+use prost::Message;
+
+use crate::{
+    contact::ContactInfo,
+    contact_manager::{
+        legacy::{
+            eto::{ETOManager, PETOManager},
+            evl::{EVLManager, PEVLManager},
+            qd::{PQDManager, QDManager},
+        },
+        seg::{Segment, SegmentationManager},
+        ContactManager,
+    },
+    contact_plan::semantics::{resolve_overlaps, ContactSemantics},
+    node::{ExclusionScope, NodeInfo},
+    node_manager::none::NoManagement,
+    routing::{FirstHopSummary, RouteSummary, RoutingSummary},
+    types::{
+        rate_from_per_second, time_from_seconds, time_to_seconds, DataRate, Duration, HashMap,
+        HopCount, NodeID,
+    },
+};
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Node {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(bool, tag = "3")]
+    pub excluded: bool,
+}
+
+/// A contact with a constant data rate and delay.
+#[derive(Clone, PartialEq, Message)]
+pub struct Contact {
+    #[prost(uint32, tag = "1")]
+    pub tx_node: u32,
+    #[prost(uint32, tag = "2")]
+    pub rx_node: u32,
+    #[prost(double, tag = "3")]
+    pub start: f64,
+    #[prost(double, tag = "4")]
+    pub end: f64,
+    #[prost(double, tag = "5")]
+    pub data_rate: f64,
+    #[prost(double, tag = "6")]
+    pub delay: f64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ContactPlan {
+    #[prost(message, repeated, tag = "1")]
+    pub nodes: Vec<Node>,
+    #[prost(message, repeated, tag = "2")]
+    pub contacts: Vec<Contact>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Route {
+    #[prost(uint32, tag = "1")]
+    pub to_node: u32,
+    #[prost(double, tag = "2")]
+    pub at_time: f64,
+    #[prost(uint32, tag = "3")]
+    pub hop_count: u32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct FirstHop {
+    #[prost(uint32, tag = "1")]
+    pub tx_node: u32,
+    #[prost(uint32, tag = "2")]
+    pub rx_node: u32,
+    #[prost(double, tag = "3")]
+    pub start: f64,
+    #[prost(double, tag = "4")]
+    pub end: f64,
+    #[prost(message, repeated, tag = "5")]
+    pub routes: Vec<Route>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RoutingOutput {
+    #[prost(message, repeated, tag = "1")]
+    pub first_hops: Vec<FirstHop>,
+}
+
+/// Builds a contact manager from the rate and delay of a protobuf contact.
+pub trait FromProtoContact: ContactManager + Sized {
+    fn proto_convert(contact: &Contact) -> Self;
+}
+
+macro_rules! generate_for_evl_variants {
+    ($cm_name:ident) => {
+        impl FromProtoContact for $cm_name {
+            fn proto_convert(contact: &Contact) -> Self {
+                $cm_name::new(
+                    rate_from_per_second(contact.data_rate),
+                    time_from_seconds(contact.delay),
+                )
+            }
+        }
+    };
+}
+
+generate_for_evl_variants!(EVLManager);
+generate_for_evl_variants!(ETOManager);
+generate_for_evl_variants!(QDManager);
+generate_for_evl_variants!(PEVLManager);
+generate_for_evl_variants!(PETOManager);
+generate_for_evl_variants!(PQDManager);
+
+impl FromProtoContact for SegmentationManager {
+    fn proto_convert(contact: &Contact) -> Self {
+        SegmentationManager::new(
+            vec![Segment::<DataRate> {
+                start: time_from_seconds(contact.start),
+                end: time_from_seconds(contact.end),
+                val: rate_from_per_second(contact.data_rate),
+            }],
+            vec![Segment::<Duration> {
+                start: time_from_seconds(contact.start),
+                end: time_from_seconds(contact.end),
+                val: time_from_seconds(contact.delay),
+            }],
+        )
+    }
+}
+
+fn to_node_id(id: u32) -> Result<NodeID, String> {
+    NodeID::try_from(id).map_err(|_| format!("Node id {} is out of range", id))
+}
+
+/// Converts a protobuf contact plan to A-SABR nodes and contacts, the contacts taking the
+/// semantics of the plan. The overlapping contacts are handled by the overlap rule of the
+/// semantics (see `resolve_overlaps`).
+///
+/// # Arguments
+///
+/// * `plan` - The decoded contact plan.
+/// * `semantics` - The semantics of the contacts.
+///
+/// # Returns
+///
+/// The nodes, the contacts and a warning for each contact dropped, or an error message if an id
+/// is out of range, the node ids are not contiguous from 0, a contact references an undeclared
+/// node, a component is invalid or an overlap is rejected.
+#[allow(clippy::type_complexity)]
+pub fn convert_contact_plan<CM: FromProtoContact>(
+    plan: &ContactPlan,
+    semantics: ContactSemantics,
+) -> Result<
+    (
+        Vec<crate::node::Node<NoManagement>>,
+        Vec<crate::contact::Contact<NoManagement, CM>>,
+        Vec<String>,
+    ),
+    String,
+> {
+    let mut nodes = Vec::with_capacity(plan.nodes.len());
+    for node in &plan.nodes {
+        let info = NodeInfo {
+            id: to_node_id(node.id)?,
+            name: node.name.clone(),
+            excluded: node.excluded,
+            exclusion_scope: ExclusionScope::Node,
+            delivery_windows: Vec::new(),
+            attributes: HashMap::new(),
+            aliases: Vec::new(),
+        };
+        match crate::node::Node::try_new(info, NoManagement {}) {
+            Some(node) => nodes.push(node),
+            None => return Err(format!("Node {} is invalid", node.id)),
+        }
+    }
+    // the multigraph indexes the nodes by id
+    let mut ids: Vec<u32> = plan.nodes.iter().map(|node| node.id).collect();
+    ids.sort_unstable();
+    for (index, id) in ids.iter().enumerate() {
+        if *id as usize != index {
+            return Err(format!(
+                "Node ids are not contiguous (node {} is missing or duplicated)",
+                index
+            ));
+        }
+    }
+
+    let mut contacts = Vec::with_capacity(plan.contacts.len());
+    for contact in &plan.contacts {
+        if contact.tx_node as usize >= nodes.len() || contact.rx_node as usize >= nodes.len() {
+            return Err(format!(
+                "Contact {} -> {} references an undeclared node",
+                contact.tx_node, contact.rx_node
+            ));
+        }
+        let info = ContactInfo::new(
+            to_node_id(contact.tx_node)?,
+            to_node_id(contact.rx_node)?,
+            time_from_seconds(contact.start),
+            time_from_seconds(contact.end),
+        )
+        .with_semantics(semantics);
+        match crate::contact::Contact::try_new(info, CM::proto_convert(contact)) {
+            Some(contact) => contacts.push(contact),
+            None => {
+                return Err(format!(
+                    "Contact {} -> {} ({}, {}) is invalid",
+                    contact.tx_node, contact.rx_node, contact.start, contact.end
+                ))
+            }
+        }
+    }
+    let warnings = resolve_overlaps(&mut contacts)?;
+    Ok((nodes, contacts, warnings))
+}
+
+/// Decodes a protobuf contact plan and converts it to A-SABR nodes and contacts (see
+/// `convert_contact_plan`).
+///
+/// # Arguments
+///
+/// * `bytes` - The encoded `ContactPlan` message.
+/// * `semantics` - The semantics of the contacts.
+///
+/// # Returns
+///
+/// The nodes, the contacts and the warnings, or an error message.
+#[allow(clippy::type_complexity)]
+pub fn decode_contact_plan<CM: FromProtoContact>(
+    bytes: &[u8],
+    semantics: ContactSemantics,
+) -> Result<
+    (
+        Vec<crate::node::Node<NoManagement>>,
+        Vec<crate::contact::Contact<NoManagement, CM>>,
+        Vec<String>,
+    ),
+    String,
+> {
+    let plan = ContactPlan::decode(bytes).map_err(|e| e.to_string())?;
+    convert_contact_plan(&plan, semantics)
+}
+
+impl From<&RoutingSummary> for RoutingOutput {
+    fn from(summary: &RoutingSummary) -> Self {
+        RoutingOutput {
+            first_hops: summary
+                .first_hops
+                .iter()
+                .map(|hop| FirstHop {
+                    tx_node: hop.tx_node as u32,
+                    rx_node: hop.rx_node as u32,
+                    start: time_to_seconds(hop.start),
+                    end: time_to_seconds(hop.end),
+                    routes: hop
+                        .routes
+                        .iter()
+                        .map(|route| Route {
+                            to_node: route.to_node as u32,
+                            at_time: time_to_seconds(route.at_time),
+                            hop_count: route.hop_count as u32,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&RoutingOutput> for RoutingSummary {
+    type Error = String;
+
+    fn try_from(output: &RoutingOutput) -> Result<Self, Self::Error> {
+        let mut first_hops = Vec::with_capacity(output.first_hops.len());
+        for hop in &output.first_hops {
+            let mut routes = Vec::with_capacity(hop.routes.len());
+            for route in &hop.routes {
+                routes.push(RouteSummary {
+                    to_node: to_node_id(route.to_node)?,
+                    at_time: time_from_seconds(route.at_time),
+                    hop_count: HopCount::try_from(route.hop_count)
+                        .map_err(|_| format!("Hop count {} is out of range", route.hop_count))?,
+                });
+            }
+            first_hops.push(FirstHopSummary {
+                tx_node: to_node_id(hop.tx_node)?,
+                rx_node: to_node_id(hop.rx_node)?,
+                start: time_from_seconds(hop.start),
+                end: time_from_seconds(hop.end),
+                routes,
+            });
+        }
+        Ok(RoutingSummary { first_hops })
+    }
+}
+
+/// Encodes a routing summary as a protobuf `RoutingOutput` message.
+pub fn encode_routing_summary(summary: &RoutingSummary) -> Vec<u8> {
+    RoutingOutput::from(summary).encode_to_vec()
+}
+
+/// Decodes a protobuf `RoutingOutput` message to a routing summary.
+pub fn decode_routing_summary(bytes: &[u8]) -> Result<RoutingSummary, String> {
+    let output = RoutingOutput::decode(bytes).map_err(|e| e.to_string())?;
+    RoutingSummary::try_from(&output)
+}
@@ -0,0 +1,119 @@
+use alloc::string::String;
+
+use crate::types::{duration_from_f64, Duration, HashMap, Priority};
+
+/// The routing parameters of a class of bundles (e.g. telemetry, commands), see
+/// `Bundle::from_profile`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct QosProfile {
+    /// The priority of the bundles.
+    pub priority: Priority,
+    /// The lifetime of the bundles: a bundle created at `now` expires at `now + expiration_offset`.
+    pub expiration_offset: Duration,
+    /// Whether the bundles are critical (see `Bundle::critical`).
+    pub critical: bool,
+}
+
+/// A registry of the `QosProfile`s by class name.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default)]
+pub struct QosRegistry {
+    /// The profiles by class name.
+    profiles: HashMap<String, QosProfile>,
+}
+
+impl QosRegistry {
+    /// Creates a registry without profile.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `QosRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the default profiles, the lifetimes being expressed in seconds:
+    ///
+    /// * "command" - priority 2, expires after 10 minutes, critical.
+    /// * "telemetry" - priority 1, expires after 1 hour.
+    /// * "science-bulk" - priority 0, expires after 7 days.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `QosRegistry`.
+    pub fn with_defaults() -> Self {
+        Self::new()
+            .with_profile(
+                "command",
+                QosProfile {
+                    priority: 2,
+                    expiration_offset: duration_from_f64(600.0),
+                    critical: true,
+                },
+            )
+            .with_profile(
+                "telemetry",
+                QosProfile {
+                    priority: 1,
+                    expiration_offset: duration_from_f64(3600.0),
+                    critical: false,
+                },
+            )
+            .with_profile(
+                "science-bulk",
+                QosProfile {
+                    priority: 0,
+                    expiration_offset: duration_from_f64(7.0 * 86400.0),
+                    critical: false,
+                },
+            )
+    }
+
+    /// Registers a profile, replacing the profile of the class if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - The class name.
+    /// * `profile` - The profile of the class.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The registry with the profile.
+    pub fn with_profile(mut self, class: &str, profile: QosProfile) -> Self {
+        self.register(class, profile);
+        self
+    }
+
+    /// Registers a profile, replacing the profile of the class if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - The class name.
+    /// * `profile` - The profile of the class.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<QosProfile>` - The replaced profile, if any.
+    pub fn register(&mut self, class: &str, profile: QosProfile) -> Option<QosProfile> {
+        self.profiles.insert(class.into(), profile)
+    }
+
+    /// Retrieves the profile of a class.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - The class name.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&QosProfile>` - The profile, or `None` if the class is unknown.
+    pub fn get(&self, class: &str) -> Option<&QosProfile> {
+        self.profiles.get(class)
+    }
+
+    /// Lists the class names, in no particular order.
+    pub fn classes(&self) -> impl Iterator<Item = &str> + '_ {
+        self.profiles.keys().map(|class| class.as_str())
+    }
+}
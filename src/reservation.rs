@@ -0,0 +1,286 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    routing::{Router, RoutingOutput},
+    types::{Date, Duration, HashMap, NodeID},
+};
+
+/// Identifies a reservation of a `ReservationBook`.
+pub type ReservationToken = u64;
+
+/// The reason why a reservation cannot be committed.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReservationError {
+    /// The token is unknown, or its reservation was already committed or cancelled.
+    Unknown,
+    /// The reservation expired at `expired_at`, its volume was released.
+    Expired { expired_at: Date },
+}
+
+/// A transmission booked for a reservation.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct Booking<NM: NodeManager, CM: ContactManager> {
+    /// The contact of the transmission.
+    contact: Rc<RefCell<Contact<NM, CM>>>,
+    /// The transmission, as returned by the contact manager.
+    tx_data: ContactManagerTxData,
+    /// The bundle as scheduled on the contact (e.g. with the priority of its multicast branch).
+    bundle: Bundle,
+}
+
+/// The volume held for a bundle until its commitment or its expiry.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Reservation<NM: NodeManager, CM: ContactManager> {
+    /// The bundle of the reservation.
+    pub bundle: Bundle,
+    /// The time at which the reservation expires if not committed.
+    pub expires_at: Date,
+    /// The transmissions booked.
+    bookings: Vec<Booking<NM, CM>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Reservation<NM, CM> {
+    /// Returns the count of transmissions (i.e. contacts) booked by the reservation.
+    pub fn booking_count(&self) -> usize {
+        self.bookings.len()
+    }
+
+    /// Releases the transmissions booked.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if all the bookings were released, `false` if a contact manager does not
+    ///   support the release (see `ContactManager::release_tx`), its volume staying booked.
+    fn release(self) -> bool {
+        let mut released = true;
+        for booking in self.bookings {
+            let mut contact = booking.contact.borrow_mut();
+            let contact = &mut *contact;
+            released &=
+                contact
+                    .manager
+                    .release_tx(&contact.info, &booking.tx_data, &booking.bundle);
+        }
+        released
+    }
+}
+
+/// Collects the transmissions scheduled by a routing output, once per route stage, the stages
+/// left unbooked (not listed in the hop timings, see `RoutingOutput::hop_timings`) excepted.
+#[allow(clippy::type_complexity)]
+fn collect_bookings<NM: NodeManager, CM: ContactManager>(
+    _bundle: &Bundle,
+    output: &RoutingOutput<NM, CM>,
+) -> Vec<Booking<NM, CM>> {
+    // the destinations served by each stage, for the priorities of the multicast branches
+    let mut stages: HashMap<usize, (Rc<RefCell<RouteStage<NM, CM>>>, Vec<NodeID>)> = HashMap::new();
+    for (_contact, routes) in output.first_hops.values() {
+        for route in routes {
+            let dest = route.borrow().to_node;
+            let mut curr = route.clone();
+            loop {
+                let parent = match &curr.borrow().via {
+                    Some(via) => via.parent_route.clone(),
+                    None => break,
+                };
+                let entry = stages
+                    .entry(Rc::as_ptr(&curr) as usize)
+                    .or_insert_with(|| (curr.clone(), Vec::new()));
+                if !entry.1.contains(&dest) {
+                    entry.1.push(dest);
+                }
+                curr = parent;
+            }
+        }
+    }
+
+    let mut bookings = Vec::with_capacity(stages.len());
+    for (_ptr, (stage, _dests)) in stages {
+        let stage = stage.borrow();
+        let (Some(via), Some(tx_data)) = (&stage.via, stage.tx_data) else {
+            continue;
+        };
+        let contact_id = via.contact.borrow().id;
+        if !output
+            .hop_timings
+            .iter()
+            .any(|hop| hop.contact == contact_id && hop.tx_start == tx_data.tx_start)
+        {
+            continue;
+        }
+        #[cfg(feature = "node_proc")]
+        let stage_bundle = stage.bundle.clone();
+        #[cfg(not(feature = "node_proc"))]
+        let stage_bundle = _bundle.for_destinations(&_dests).into_owned();
+        bookings.push(Booking {
+            contact: via.contact.clone(),
+            tx_data,
+            bundle: stage_bundle,
+        });
+    }
+    bookings
+}
+
+/// Holds the volume of bundles routed but not confirmed yet (two-phase commit), e.g. for agents
+/// confirming the transmissions with the convergence layers before the final commitment.
+///
+/// A reservation routes the bundle (the contact volumes are booked as for any routing) and
+/// records the transmissions booked. A committed reservation keeps its bookings, a cancelled or
+/// expired one releases them through `ContactManager::release_tx`, preventing phantom bookings.
+/// The expired reservations are released by the calls to `reserve`, `commit` and `expire`.
+///
+/// The node managers (see the "node_tx" and "node_rx" features) are not rolled back, and the
+/// route storages of the router may keep routes computed while the volume was held.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ReservationBook<NM: NodeManager, CM: ContactManager> {
+    /// The pending reservations.
+    reservations: HashMap<ReservationToken, Reservation<NM, CM>>,
+    /// The token of the next reservation.
+    next_token: ReservationToken,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Default for ReservationBook<NM, CM> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> ReservationBook<NM, CM> {
+    /// Creates a book without reservation.
+    pub fn new() -> Self {
+        Self {
+            reservations: HashMap::new(),
+            next_token: 0,
+        }
+    }
+
+    /// Routes a bundle and holds the volume booked until `commit`, `cancel` or the expiry.
+    ///
+    /// # Parameters
+    ///
+    /// * `router` - The router booking the volume.
+    /// * `source` - The source node ID.
+    /// * `bundle` - The bundle to route.
+    /// * `curr_time` - The current time.
+    /// * `excluded_nodes` - A sorted list of nodes to exclude from the routing paths.
+    /// * `ttl` - The lifetime of the reservation.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(ReservationToken, RoutingOutput<NM, CM>)>` - The token of the reservation and
+    ///   the routing output, or `None` if no route is found.
+    pub fn reserve<R: Router<NM, CM> + ?Sized>(
+        &mut self,
+        router: &mut R,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+        ttl: Duration,
+    ) -> Option<(ReservationToken, RoutingOutput<NM, CM>)> {
+        self.expire(curr_time);
+        let output = router.route(source, bundle, curr_time, excluded_nodes)?;
+        let token = self.next_token;
+        self.next_token += 1;
+        self.reservations.insert(
+            token,
+            Reservation {
+                bundle: bundle.clone(),
+                expires_at: curr_time + ttl,
+                bookings: collect_bookings(bundle, &output),
+            },
+        );
+        Some((token, output))
+    }
+
+    /// Finalizes a reservation, its bookings are kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `token` - The token of the reservation.
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Bundle, ReservationError>` - The bundle of the reservation, or the reason why the
+    ///   reservation cannot be committed.
+    pub fn commit(
+        &mut self,
+        token: ReservationToken,
+        curr_time: Date,
+    ) -> Result<Bundle, ReservationError> {
+        let reservation = self
+            .reservations
+            .remove(&token)
+            .ok_or(ReservationError::Unknown)?;
+        if reservation.expires_at <= curr_time {
+            let expired_at = reservation.expires_at;
+            reservation.release();
+            self.expire(curr_time);
+            return Err(ReservationError::Expired { expired_at });
+        }
+        self.expire(curr_time);
+        Ok(reservation.bundle)
+    }
+
+    /// Cancels a reservation, its bookings are released.
+    ///
+    /// # Parameters
+    ///
+    /// * `token` - The token of the reservation.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<bool>` - `None` if the token is unknown, otherwise whether all the bookings were
+    ///   released (see `ContactManager::release_tx`).
+    pub fn cancel(&mut self, token: ReservationToken) -> Option<bool> {
+        Some(self.reservations.remove(&token)?.release())
+    }
+
+    /// Releases the reservations expired at `curr_time`.
+    ///
+    /// # Parameters
+    ///
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ReservationToken>` - The tokens of the expired reservations, sorted.
+    pub fn expire(&mut self, curr_time: Date) -> Vec<ReservationToken> {
+        let mut expired: Vec<ReservationToken> = self
+            .reservations
+            .iter()
+            .filter(|(_, reservation)| reservation.expires_at <= curr_time)
+            .map(|(token, _)| *token)
+            .collect();
+        expired.sort_unstable();
+        for token in &expired {
+            if let Some(reservation) = self.reservations.remove(token) {
+                reservation.release();
+            }
+        }
+        expired
+    }
+
+    /// Retrieves a pending reservation.
+    pub fn get(&self, token: ReservationToken) -> Option<&Reservation<NM, CM>> {
+        self.reservations.get(&token)
+    }
+
+    /// Returns the count of pending reservations.
+    pub fn len(&self) -> usize {
+        self.reservations.len()
+    }
+
+    /// Checks if no reservation is pending.
+    pub fn is_empty(&self) -> bool {
+        self.reservations.is_empty()
+    }
+}
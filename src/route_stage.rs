@@ -1,12 +1,34 @@
 use crate::bundle::Bundle;
 use crate::contact::Contact;
-use crate::contact_manager::ContactManager;
-use crate::node::Node;
+use crate::contact_manager::{ContactManager, ContactManagerTxData};
+use crate::node::{ExclusionScope, Node};
 use crate::node_manager::NodeManager;
-use crate::types::{Date, Duration, HopCount, NodeID};
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+use crate::types::{ContactID, Date, Duration, HashMap, HopCount, NodeID, TIME_ZERO};
+use alloc::{rc::Rc, vec::Vec};
+use core::any::{Any, TypeId};
+use core::cell::RefCell;
+
+/// The reason why the hop of a route stage cannot be dry run or scheduled.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum HopFailure {
+    /// The stage is a source stage, it has no hop.
+    NoHop,
+    /// The route does not continue toward the destination from `node` (e.g. a route of a tree
+    /// not reaching the destination).
+    MissingHop { node: NodeID },
+    /// The receiving node is excluded.
+    ExcludedNode { node: NodeID },
+    /// The contact manager refused the transmission: insufficient volume or budget, or no
+    /// transmission ending within the contact.
+    ContactRefused { contact: ContactID },
+    /// The transmitting node refused the transmission window ("node_tx" feature).
+    NodeTxRefused { node: NodeID },
+    /// The receiving node refused the reception ("node_rx" feature).
+    NodeRxRefused { node: NodeID },
+    /// The bundle would arrive at `arrival`, after its expiration.
+    Expired { arrival: Date },
+}
 
 /// Represents an intermediate hop in a route, typically used for multi-hop communication or routing.
 ///
@@ -33,6 +55,111 @@ impl<NM: NodeManager, CM: ContactManager> Clone for ViaHop<NM, CM> {
     }
 }
 
+/// A type map holding the third-party data attached to a `RouteStage`.
+///
+/// Each annotation is identified by its type, a custom `Distance` typically defines its own
+/// type (e.g. an accumulated energy or risk) and reads it back with `get`. The values are shared
+/// between the clones of a stage.
+#[derive(Clone, Default)]
+pub struct Annotations {
+    entries: Vec<(TypeId, Rc<dyn Any>)>,
+}
+
+impl Annotations {
+    /// Creates an empty annotation map.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the annotation of type `T`, if any.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.entries
+            .iter()
+            .find(|(id, _)| *id == TypeId::of::<T>())
+            .and_then(|(_, value)| value.downcast_ref::<T>())
+    }
+
+    /// Sets the annotation of type `T`, replacing the previous value.
+    pub fn insert<T: 'static>(&mut self, value: T) {
+        let value: Rc<dyn Any> = Rc::new(value);
+        match self
+            .entries
+            .iter_mut()
+            .find(|(id, _)| *id == TypeId::of::<T>())
+        {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((TypeId::of::<T>(), value)),
+        }
+    }
+
+    /// Removes the annotation of type `T`, returns `true` if it was present.
+    pub fn remove<T: 'static>(&mut self) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(id, _)| *id != TypeId::of::<T>());
+        len != self.entries.len()
+    }
+
+    /// Returns `true` if no annotation is set.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(feature = "debug")]
+impl core::fmt::Debug for Annotations {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Annotations({})", self.entries.len())
+    }
+}
+
+/// A trait allowing to populate the annotations of the route stages during pathfinding.
+///
+/// The annotator is called by the pathfinding for each hop proposition, before the proposition
+/// is compared with the known routes. Pair it with a `Distance` reading the annotations.
+///
+/// # Type Parameters
+///
+/// * `NM` - A type that implements the `NodeManager` trait.
+/// * `CM` - A type that implements the `ContactManager` trait.
+pub trait StageAnnotator<NM: NodeManager, CM: ContactManager> {
+    /// Annotates a hop proposition.
+    ///
+    /// # Parameters
+    ///
+    /// * `proposition` - The new route stage, its timing fields are already set.
+    /// * `parent` - The route stage of the sender.
+    /// * `contact` - The contact selected for the hop.
+    /// * `tx_data` - The transmission data of the hop.
+    ///
+    /// # Returns
+    ///
+    /// * `false` to discard the proposition (e.g. a budget is exceeded), `true` otherwise.
+    fn annotate(
+        proposition: &mut RouteStage<NM, CM>,
+        parent: &RouteStage<NM, CM>,
+        contact: &Contact<NM, CM>,
+        tx_data: &ContactManagerTxData,
+    ) -> bool;
+}
+
+/// The default `StageAnnotator`, leaves the annotations empty.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct NoAnnotation {}
+
+impl<NM: NodeManager, CM: ContactManager> StageAnnotator<NM, CM> for NoAnnotation {
+    #[inline(always)]
+    fn annotate(
+        _proposition: &mut RouteStage<NM, CM>,
+        _parent: &RouteStage<NM, CM>,
+        _contact: &Contact<NM, CM>,
+        _tx_data: &ContactManagerTxData,
+    ) -> bool {
+        true
+    }
+}
+
 /// Represents a stage in the routing process to a destination node.
 ///
 ///  # Type Parameters
@@ -55,12 +182,19 @@ pub struct RouteStage<NM: NodeManager, CM: ContactManager> {
     pub cumulative_delay: Duration,
     /// The time at which this route stage expires, indicating when it is no longer valid.
     pub expiration: Date,
+    /// The soft deadline of the bundle the route was computed for, see `Bundle::soft_deadline`.
+    pub soft_deadline: Option<Date>,
     /// A flag indicating whether the route has been fully initialized and is ready for routing.
     pub route_initialized: bool,
     /// A hashmap that maps destination node IDs to their respective next route stages.
     #[cfg_attr(feature = "debug", derivative(Debug = "ignore"))]
     // avoid cyclic print with debug formatting
     pub next_for_destination: HashMap<NodeID, Rc<RefCell<RouteStage<NM, CM>>>>,
+    /// The third-party data attached to this stage, populated by a `StageAnnotator`.
+    pub annotations: Annotations,
+    /// The transmission toward `to_node` computed by the last pathfinding, dry run or scheduling
+    /// of this stage, `None` for a source stage.
+    pub tx_data: Option<ContactManagerTxData>,
 
     #[cfg(feature = "node_proc")]
     /// The stage of the bundle that arrives at to_node
@@ -92,10 +226,13 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
             is_disabled: false,
             via: via_hop,
             hop_count: 0,
-            cumulative_delay: 0.0,
+            cumulative_delay: TIME_ZERO,
             expiration: Date::MAX,
+            soft_deadline: None,
             route_initialized: false,
             next_for_destination: HashMap::new(),
+            annotations: Annotations::new(),
+            tx_data: None,
             #[cfg(feature = "node_proc")]
             bundle: bundle,
         }
@@ -114,6 +251,9 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         route.hop_count = self.hop_count;
         route.cumulative_delay = self.cumulative_delay;
         route.expiration = self.expiration;
+        route.soft_deadline = self.soft_deadline;
+        route.annotations = self.annotations.clone();
+        route.tx_data = self.tx_data;
 
         return route;
     }
@@ -161,67 +301,97 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     /// * `true` if the scheduling process was successful and the bundle is properly scheduled.
     /// * `false` if the scheduling process failed for any reason, such as a node being excluded, timing constraints, or invalid transmission conditions.
     pub fn schedule(&mut self, at_time: Date, bundle: &Bundle) -> bool {
-        if let Some(via) = &self.via {
-            let mut contact_borrowed = via.contact.borrow_mut();
-            let info = contact_borrowed.info;
+        self.try_schedule(at_time, bundle).is_ok()
+    }
 
-            // If bundle processing is enabled, a mutable bundle copy is required to be attached to the RouteStage.
-            #[cfg(feature = "node_proc")]
-            let mut bundle_to_consider = bundle.clone();
-            #[cfg(not(feature = "node_proc"))]
-            let bundle_to_consider = bundle;
+    /// Schedules the transmission of a `bundle` like `schedule`, and tells why it failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `at_time` - current time at the tx node.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), HopFailure>` - The cause of the failure, if any.
+    pub fn try_schedule(&mut self, at_time: Date, bundle: &Bundle) -> Result<(), HopFailure> {
+        let Some(via) = &self.via else {
+            return Err(HopFailure::NoHop);
+        };
+        let mut contact_borrowed = via.contact.borrow_mut();
+        // disjoint borrows, the info is not copied
+        let contact = &mut *contact_borrowed;
+        let info = &contact.info;
 
-            #[allow(unused_mut)]
-            #[cfg(any(feature = "node_tx", feature = "node_proc"))]
-            let mut tx_node = via.tx_node.borrow_mut();
-            #[cfg(feature = "node_rx")]
-            let mut rx_node = via.rx_node.borrow_mut();
+        // If bundle processing is enabled, a mutable bundle copy is required to be attached to the RouteStage.
+        #[cfg(feature = "node_proc")]
+        let mut bundle_to_consider = bundle.clone();
+        #[cfg(not(feature = "node_proc"))]
+        let bundle_to_consider = bundle;
 
-            #[cfg(feature = "node_proc")]
-            let sending_time = tx_node
-                .manager
-                .schedule_process(at_time, &mut bundle_to_consider);
-            #[cfg(not(feature = "node_proc"))]
-            let sending_time = at_time;
-
-            if let Some(res) =
-                contact_borrowed
-                    .manager
-                    .schedule_tx(&info, sending_time, &bundle_to_consider)
-            {
-                #[cfg(feature = "node_tx")]
-                if !tx_node.manager.schedule_tx(
-                    sending_time,
-                    res.tx_start,
-                    res.tx_end,
-                    &bundle_to_consider,
-                ) {
-                    return false;
-                }
-
-                let arrival_time = res.tx_end + res.delay;
-
-                if arrival_time > bundle_to_consider.expiration {
-                    return false;
-                }
-                #[cfg(feature = "node_rx")]
-                if !rx_node.manager.schedule_rx(
-                    res.tx_start + res.delay,
-                    res.tx_end + res.delay,
-                    &bundle_to_consider,
-                ) {
-                    return false;
-                }
-
-                self.at_time = arrival_time;
-                #[cfg(feature = "node_proc")]
-                {
-                    self.bundle = bundle_to_consider;
-                }
-                return true;
-            }
+        #[allow(unused_mut)]
+        #[cfg(any(feature = "node_tx", feature = "node_proc"))]
+        let mut tx_node = via.tx_node.borrow_mut();
+        #[cfg(feature = "node_rx")]
+        let mut rx_node = via.rx_node.borrow_mut();
+
+        #[cfg(feature = "node_proc")]
+        let sending_time = tx_node
+            .manager
+            .schedule_process(at_time, &mut bundle_to_consider);
+        #[cfg(not(feature = "node_proc"))]
+        let sending_time = at_time;
+        // no transmission toward a neighbor marked down
+        #[cfg(feature = "node_rx")]
+        let sending_time = rx_node.up_from(sending_time);
+        #[cfg(not(feature = "node_rx"))]
+        let sending_time = via.rx_node.borrow().up_from(sending_time);
+        // down beyond the contact (e.g. until `Date::MAX`, which would overflow the integer dates)
+        if sending_time > info.end {
+            return Err(HopFailure::ContactRefused {
+                contact: contact.id,
+            });
         }
-        return false;
+
+        let Some(res) = contact
+            .manager
+            .schedule_tx(info, sending_time, &bundle_to_consider)
+        else {
+            return Err(HopFailure::ContactRefused {
+                contact: contact.id,
+            });
+        };
+        #[cfg(feature = "node_tx")]
+        if !tx_node
+            .manager
+            .schedule_tx(sending_time, res.tx_start, res.tx_end, &bundle_to_consider)
+        {
+            return Err(HopFailure::NodeTxRefused { node: info.tx_node });
+        }
+
+        let arrival_time = res.tx_end + res.delay;
+
+        if arrival_time > bundle_to_consider.expiration {
+            return Err(HopFailure::Expired {
+                arrival: arrival_time,
+            });
+        }
+        #[cfg(feature = "node_rx")]
+        if !rx_node.manager.schedule_rx(
+            res.tx_start + res.delay,
+            res.tx_end + res.delay,
+            &bundle_to_consider,
+        ) {
+            return Err(HopFailure::NodeRxRefused { node: info.rx_node });
+        }
+
+        self.at_time = arrival_time;
+        self.tx_data = Some(res);
+        #[cfg(feature = "node_proc")]
+        {
+            self.bundle = bundle_to_consider;
+        }
+        Ok(())
     }
 
     /// Performs a dry run to simulate the transmission of a `bundle` through a network without actually
@@ -244,75 +414,121 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     /// * `true` if the dry run was successful and the bundle can be transmitted according to the simulation.
     /// * `false` if the dry run fails, such as due to an excluded node, invalid timing, or any other condition preventing transmission.
     pub fn dry_run(&mut self, at_time: Date, bundle: &Bundle, with_exclusions: bool) -> bool {
-        if let Some(via) = &self.via {
-            let contact_borrowed = via.contact.borrow_mut();
-            let info = contact_borrowed.info;
-
-            if with_exclusions {
-                {
-                    let node = via.rx_node.borrow();
-                    if node.info.excluded {
-                        return false;
-                    }
-                }
+        self.try_dry_run(at_time, bundle, with_exclusions).is_ok()
+    }
+
+    /// Performs a dry run like `dry_run`, and tells why it failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `at_time` - current time at the tx node.
+    /// * `bundle` - The bundle to simulate transmission for.
+    /// * `with_exclusions` - If `true`, checks whether the receiving node is excluded from the transmission.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), HopFailure>` - The cause of the failure, if any.
+    pub fn try_dry_run(
+        &mut self,
+        at_time: Date,
+        bundle: &Bundle,
+        with_exclusions: bool,
+    ) -> Result<(), HopFailure> {
+        let Some(via) = &self.via else {
+            return Err(HopFailure::NoHop);
+        };
+        let contact_borrowed = via.contact.borrow_mut();
+        let info = &contact_borrowed.info;
+
+        if with_exclusions {
+            let node = via.rx_node.borrow();
+            // a node excluded as a destination only still relays the bundles of others
+            let barred = match node.info.exclusion_scope {
+                ExclusionScope::Node => true,
+                ExclusionScope::Relay => false,
+                ExclusionScope::Destination => bundle.destinations.contains(&node.info.id),
+            };
+            if node.info.excluded && barred {
+                return Err(HopFailure::ExcludedNode { node: node.info.id });
+            }
+            // the source transmits even if excluded as a relay
+            let tx_node = via.tx_node.borrow();
+            if tx_node.info.excluded_as_relay() && via.parent_route.borrow().via.is_some() {
+                return Err(HopFailure::ExcludedNode {
+                    node: tx_node.info.id,
+                });
             }
+        }
 
-            // If bundle processing is enabled, a mutable bundle copy is required to be attached to the RouteStage.
-            #[cfg(feature = "node_proc")]
-            let mut bundle_to_consider = bundle.clone();
-            #[cfg(not(feature = "node_proc"))]
-            let bundle_to_consider = bundle;
-
-            #[cfg(any(feature = "node_tx", feature = "node_proc"))]
-            let tx_node = via.tx_node.borrow_mut();
-            #[cfg(feature = "node_rx")]
-            let rx_node = via.rx_node.borrow_mut();
-            #[cfg(feature = "node_proc")]
-            let sending_time = tx_node
+        // If bundle processing is enabled, a mutable bundle copy is required to be attached to the RouteStage.
+        #[cfg(feature = "node_proc")]
+        let mut bundle_to_consider = bundle.clone();
+        #[cfg(not(feature = "node_proc"))]
+        let bundle_to_consider = bundle;
+
+        #[cfg(any(feature = "node_tx", feature = "node_proc"))]
+        let tx_node = via.tx_node.borrow_mut();
+        #[cfg(feature = "node_rx")]
+        let rx_node = via.rx_node.borrow_mut();
+        #[cfg(feature = "node_proc")]
+        let sending_time = tx_node
+            .manager
+            .dry_run_process(at_time, &mut bundle_to_consider);
+
+        #[cfg(not(feature = "node_proc"))]
+        let sending_time = at_time;
+        // no transmission toward a neighbor marked down
+        #[cfg(feature = "node_rx")]
+        let sending_time = rx_node.up_from(sending_time);
+        #[cfg(not(feature = "node_rx"))]
+        let sending_time = via.rx_node.borrow().up_from(sending_time);
+        // down beyond the contact (e.g. until `Date::MAX`, which would overflow the integer dates)
+        if sending_time > info.end {
+            return Err(HopFailure::ContactRefused {
+                contact: contact_borrowed.id,
+            });
+        }
+
+        let Some(res) =
+            contact_borrowed
                 .manager
-                .dry_run_process(at_time, &mut bundle_to_consider);
-
-            #[cfg(not(feature = "node_proc"))]
-            let sending_time = at_time;
-
-            if let Some(res) =
-                contact_borrowed
-                    .manager
-                    .dry_run_tx(&info, sending_time, &bundle_to_consider)
-            {
-                #[cfg(feature = "node_tx")]
-                if !tx_node.manager.dry_run_tx(
-                    sending_time,
-                    res.tx_start,
-                    res.tx_end,
-                    &bundle_to_consider,
-                ) {
-                    return false;
-                }
-
-                let arrival_time = res.tx_end + res.delay;
-
-                if arrival_time > bundle_to_consider.expiration {
-                    return false;
-                }
-                #[cfg(feature = "node_rx")]
-                if !rx_node.manager.dry_run_rx(
-                    res.tx_start + res.delay,
-                    res.tx_end + res.delay,
-                    &bundle_to_consider,
-                ) {
-                    return false;
-                }
-
-                self.at_time = arrival_time;
-                #[cfg(feature = "node_proc")]
-                {
-                    self.bundle = bundle_to_consider;
-                }
-                return true;
-            }
+                .dry_run_tx(info, sending_time, &bundle_to_consider)
+        else {
+            return Err(HopFailure::ContactRefused {
+                contact: contact_borrowed.id,
+            });
+        };
+        #[cfg(feature = "node_tx")]
+        if !tx_node
+            .manager
+            .dry_run_tx(sending_time, res.tx_start, res.tx_end, &bundle_to_consider)
+        {
+            return Err(HopFailure::NodeTxRefused { node: info.tx_node });
+        }
+
+        let arrival_time = res.tx_end + res.delay;
+
+        if arrival_time > bundle_to_consider.expiration {
+            return Err(HopFailure::Expired {
+                arrival: arrival_time,
+            });
+        }
+        #[cfg(feature = "node_rx")]
+        if !rx_node.manager.dry_run_rx(
+            res.tx_start + res.delay,
+            res.tx_end + res.delay,
+            &bundle_to_consider,
+        ) {
+            return Err(HopFailure::NodeRxRefused { node: info.rx_node });
+        }
+
+        self.at_time = arrival_time;
+        self.tx_data = Some(res);
+        #[cfg(feature = "node_proc")]
+        {
+            self.bundle = bundle_to_consider;
         }
-        return false;
+        Ok(())
     }
 
     pub fn get_via_contact(&self) -> Option<Rc<RefCell<Contact<NM, CM>>>> {
@@ -321,4 +537,80 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         }
         None
     }
+
+    /// Iterates over the parent stages of this stage, from its parent up to the source stage.
+    ///
+    /// # Returns
+    ///
+    /// * `Ancestors<NM, CM>` - The iterator, empty for a source stage.
+    pub fn ancestors(&self) -> Ancestors<NM, CM> {
+        Ancestors {
+            next: self.via.as_ref().map(|via| via.parent_route.clone()),
+        }
+    }
+
+    /// Iterates over the next stages toward a destination, from the child of this stage down to
+    /// the stage reaching the destination.
+    ///
+    /// The stages are linked with `next_for_destination`, the route must have been initialized
+    /// (see `init_route`).
+    ///
+    /// # Parameters
+    ///
+    /// * `destination` - The destination node ID.
+    ///
+    /// # Returns
+    ///
+    /// * `Successors<NM, CM>` - The iterator, empty if no initialized route goes from this stage to
+    ///   the destination.
+    pub fn successors(&self, destination: NodeID) -> Successors<NM, CM> {
+        Successors {
+            destination,
+            next: self.next_for_destination.get(&destination).cloned(),
+        }
+    }
+}
+
+/// An iterator over the parent stages of a `RouteStage`, see `RouteStage::ancestors`.
+///
+/// The stages are yielded as shared handles, each one being borrowed as needed: the iterator
+/// does not hold any borrow between two calls.
+pub struct Ancestors<NM: NodeManager, CM: ContactManager> {
+    next: Option<Rc<RefCell<RouteStage<NM, CM>>>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Iterator for Ancestors<NM, CM> {
+    type Item = Rc<RefCell<RouteStage<NM, CM>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let curr = self.next.take()?;
+        self.next = curr
+            .borrow()
+            .via
+            .as_ref()
+            .map(|via| via.parent_route.clone());
+        Some(curr)
+    }
+}
+
+/// An iterator over the next stages toward a destination, see `RouteStage::successors`.
+///
+/// Like `Ancestors`, the iterator does not hold any borrow between two calls.
+pub struct Successors<NM: NodeManager, CM: ContactManager> {
+    destination: NodeID,
+    next: Option<Rc<RefCell<RouteStage<NM, CM>>>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Iterator for Successors<NM, CM> {
+    type Item = Rc<RefCell<RouteStage<NM, CM>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let curr = self.next.take()?;
+        self.next = curr
+            .borrow()
+            .next_for_destination
+            .get(&self.destination)
+            .cloned();
+        Some(curr)
+    }
 }
@@ -1,4 +1,5 @@
-use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+use alloc::{collections::VecDeque, rc::Rc, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData};
 
 use crate::{
     bundle::Bundle,
@@ -6,10 +7,116 @@ use crate::{
     node_manager::NodeManager,
     pathfinding::PathFindingOutput,
     routing::{dry_run_multicast, dry_run_unicast_tree},
-    types::{Date, NodeID},
+    telemetry::Span,
+    types::{Date, HashMap, NodeID},
 };
+#[cfg(feature = "node_proc")]
+use crate::{route_stage::RouteStage, types::Volume};
 
-use super::TreeStorage;
+use super::{route_goes_through, StoredTreeInfo, TreeStorage};
+
+/// A tree stored by a `TreeCache`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct CachedTree<NM: NodeManager, CM: ContactManager> {
+    /// The pathfinding output.
+    tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
+    /// The sizes of the processed bundle on the hops of the route toward each destination, from
+    /// the first hop (see `RouteStage::bundle`).
+    #[cfg(feature = "node_proc")]
+    processed_sizes: HashMap<NodeID, Vec<Volume>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> CachedTree<NM, CM> {
+    /// Wraps a tree, collecting the processed sizes of its routes.
+    fn new(tree: Rc<RefCell<PathFindingOutput<NM, CM>>>) -> Self {
+        #[cfg(feature = "node_proc")]
+        let processed_sizes = {
+            let mut processed_sizes = HashMap::new();
+            for (dest, stage) in tree.borrow().by_destination.iter().enumerate() {
+                if let Some(stage) = stage {
+                    let sizes = route_stages(stage)
+                        .iter()
+                        .map(|stage| stage.borrow().bundle.size)
+                        .collect();
+                    processed_sizes.insert(dest as NodeID, sizes);
+                }
+            }
+            processed_sizes
+        };
+        Self {
+            tree,
+            #[cfg(feature = "node_proc")]
+            processed_sizes,
+        }
+    }
+
+    /// Checks if the tree was computed for a bundle shadowing `bundle`, see `Bundle::shadows`.
+    #[cfg(not(feature = "node_proc"))]
+    fn shadows(&self, bundle: &Bundle, check_size: bool, check_priority: bool) -> bool {
+        self.tree
+            .borrow()
+            .bundle
+            .shadows(bundle, check_size, check_priority)
+    }
+
+    /// Checks if the tree was computed for a bundle shadowing `bundle`, see `Bundle::shadows`.
+    ///
+    /// The sizes are compared hop by hop toward each destination: `bundle` is processed along the
+    /// route like during the pathfinding, and the tree shadows it if a hop carried a larger
+    /// processed bundle, as the processing can change the sizes of both bundles differently.
+    #[cfg(feature = "node_proc")]
+    fn shadows(&self, bundle: &Bundle, check_size: bool, check_priority: bool) -> bool {
+        let tree = self.tree.borrow();
+        if tree.bundle.shadows(bundle, false, check_priority) {
+            return true;
+        }
+        if !check_size {
+            return false;
+        }
+        bundle.destinations.iter().any(|dest| {
+            let (Some(sizes), Some(Some(stage))) = (
+                self.processed_sizes.get(dest),
+                tree.by_destination.get(*dest as usize),
+            ) else {
+                return false;
+            };
+            let mut processed = bundle.clone();
+            route_stages(stage)
+                .iter()
+                .zip(sizes)
+                .any(|(stage, tree_size)| {
+                    let stage = stage.borrow();
+                    if let Some(via) = &stage.via {
+                        let departure = via.parent_route.borrow().at_time;
+                        via.tx_node
+                            .borrow()
+                            .manager
+                            .dry_run_process(departure, &mut processed);
+                    }
+                    *tree_size > processed.size
+                })
+        })
+    }
+}
+
+/// Lists the stages of the route leading to a stage, from the first hop.
+#[cfg(feature = "node_proc")]
+fn route_stages<NM: NodeManager, CM: ContactManager>(
+    destination_stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+) -> Vec<Rc<RefCell<RouteStage<NM, CM>>>> {
+    let mut stages = Vec::new();
+    let mut curr = destination_stage.clone();
+    loop {
+        let parent = match &curr.borrow().via {
+            Some(via) => via.parent_route.clone(),
+            None => break,
+        };
+        stages.push(curr);
+        curr = parent;
+    }
+    stages.reverse();
+    stages
+}
 
 /// A cache for storing pathfinding output entries, enabling efficient retrieval and management.
 ///
@@ -23,8 +130,10 @@ pub struct TreeCache<NM: NodeManager, CM: ContactManager> {
     check_priority: bool,
     /// The maximum number of entries allowed in the cache.
     max_entries: usize,
-    /// A deque of reference-counted mutable references to `PathfindingOutput` instances stored in the cache.
-    trees: VecDeque<Rc<RefCell<PathFindingOutput<NM, CM>>>>,
+    /// A deque of the `PathfindingOutput` instances stored in the cache.
+    trees: VecDeque<CachedTree<NM, CM>>,
+    /// The moving average of the dry run failures of the selections, per destination.
+    dry_run_failures: RefCell<HashMap<NodeID, f32>>,
 
     // for compilation
     #[doc(hidden)]
@@ -49,10 +158,33 @@ impl<NM: NodeManager, CM: ContactManager> TreeCache<NM, CM> {
             check_priority,
             max_entries,
             trees: VecDeque::new(),
+            dry_run_failures: RefCell::new(HashMap::new()),
             // for compilation
             _phantom_nm: PhantomData,
         }
     }
+
+    /// Lists the cached trees, from the oldest to the newest.
+    ///
+    /// # Returns
+    ///
+    /// * `impl Iterator<Item = StoredTreeInfo>` - An iterator over the summaries of the trees.
+    pub fn trees(&self) -> impl Iterator<Item = StoredTreeInfo> + '_ {
+        self.trees
+            .iter()
+            .map(|cached| StoredTreeInfo::from_tree(&cached.tree.borrow()))
+    }
+
+    /// Records the outcome of the dry runs of a selection toward a destination, in a moving
+    /// average weighting the last outcome by 1/8.
+    fn record_dry_run(&self, dest: NodeID, failed: bool) {
+        let outcome = if failed { 1.0 } else { 0.0 };
+        self.dry_run_failures
+            .borrow_mut()
+            .entry(dest)
+            .and_modify(|rate| *rate += (outcome - *rate) / 8.0)
+            .or_insert(outcome);
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM, CM> {
@@ -78,31 +210,42 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
         Option<Rc<RefCell<PathFindingOutput<NM, CM>>>>,
         Option<Vec<NodeID>>,
     ) {
+        let span = Span::start("a_sabr.storage.select");
+        span.int("destinations", bundle.destinations.len() as i64);
         let multicast = bundle.destinations.len() > 1;
-        for tree in &self.trees {
-            if tree
-                .borrow()
-                .bundle
-                .shadows(bundle, self.check_size, self.check_priority)
-            {
+        let mut tried = false;
+        for cached in &self.trees {
+            if cached.shadows(bundle, self.check_size, self.check_priority) {
                 continue;
             }
+            let tree = &cached.tree;
             if &tree.borrow().excluded_nodes_sorted != excluded_nodes_sorted {
                 continue;
             }
+            tried = true;
             match multicast {
                 false => {
                     if let Some(_res) = dry_run_unicast_tree(bundle, curr_time, tree.clone(), false)
                     {
+                        self.record_dry_run(bundle.destinations[0], false);
+                        span.flag("hit", true);
                         return (Some(tree.clone()), None);
                     }
                 }
                 true => {
                     let reachable_nodes = dry_run_multicast(bundle, curr_time, tree.clone());
+                    for dest in &bundle.destinations {
+                        self.record_dry_run(*dest, !reachable_nodes.contains(dest));
+                    }
+                    span.flag("hit", true);
                     return (Some(tree.clone()), Some(reachable_nodes));
                 }
             }
         }
+        if tried {
+            self.record_dry_run(bundle.destinations[0], true);
+        }
+        span.flag("hit", false);
         (None, None)
     }
 
@@ -114,14 +257,17 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
     ///
     /// * `new_tree` - A reference-counted mutable reference to the `PathfindingOutput` to store.
     fn store(&mut self, _bundle: &Bundle, new_tree: Rc<RefCell<PathFindingOutput<NM, CM>>>) {
+        let _span = Span::start("a_sabr.storage.store");
         let mut replace_index = None;
-        for (i, tree) in self.trees.iter().enumerate() {
-            if tree.borrow().excluded_nodes_sorted == new_tree.borrow().excluded_nodes_sorted {
+        for (i, cached) in self.trees.iter().enumerate() {
+            if cached.tree.borrow().excluded_nodes_sorted == new_tree.borrow().excluded_nodes_sorted
+            {
                 replace_index = Some(i);
                 break;
             }
         }
 
+        let new_tree = CachedTree::new(new_tree);
         if let Some(i) = replace_index {
             self.trees[i] = new_tree;
         } else {
@@ -132,4 +278,31 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
             self.trees.pop_front();
         }
     }
+
+    /// Returns the moving average of the dry run failures toward a destination, the failures
+    /// being the selections for which no cached tree could carry the bundle.
+    fn dry_run_failure_rate(&self, dest: NodeID) -> Option<f32> {
+        self.dry_run_failures.borrow().get(&dest).copied()
+    }
+
+    /// Drops the trees with a route going through a node, or all the trees.
+    ///
+    /// # Parameters
+    ///
+    /// * `via_node` - The node, all the trees are dropped if `None`.
+    fn invalidate(&mut self, via_node: Option<NodeID>) {
+        let Some(node) = via_node else {
+            self.trees.clear();
+            return;
+        };
+        self.trees.retain(|cached| {
+            !cached
+                .tree
+                .borrow()
+                .by_destination
+                .iter()
+                .flatten()
+                .any(|stage| route_goes_through(stage, node))
+        });
+    }
 }
@@ -1,4 +1,6 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use alloc::{boxed::Box, collections::BTreeSet, rc::Rc, vec::Vec};
+use core::{cell::RefCell, iter};
+use serde::Serialize;
 
 pub mod cache;
 pub mod table;
@@ -10,7 +12,7 @@ use crate::{
     node_manager::NodeManager,
     pathfinding::PathFindingOutput,
     route_stage::RouteStage,
-    types::{Date, NodeID, Priority, Volume},
+    types::{ContactID, Date, HashMap, NodeID, Priority, Volume},
 };
 
 /// A trait for managing tree storage and retrieval.
@@ -48,6 +50,135 @@ pub trait TreeStorage<NM: NodeManager, CM: ContactManager> {
     /// * `bundle` - A bundle copy for which the tree was created.
     /// * `tree` - A reference-counted mutable reference to the `PathfindingOutput` to store.
     fn store(&mut self, bundle: &Bundle, tree: Rc<RefCell<PathFindingOutput<NM, CM>>>);
+
+    /// Drops the stored trees made stale by a change of the network state, e.g. a neighbor marked
+    /// down or up (see `Router::set_neighbor_state`). The default implementation keeps the trees.
+    ///
+    /// # Parameters
+    ///
+    /// * `via_node` - Drops the trees with a route going through this node, all the trees if
+    ///   `None`.
+    fn invalidate(&mut self, _via_node: Option<NodeID>) {}
+
+    /// Returns the recent rate of the dry runs failing toward a destination during the selections,
+    /// i.e. the stored trees no longer able to carry the bundles (e.g. congested contacts).
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f32>` - The failure rate, from 0 to 1, or `None` if the storage does not track the
+    ///   dry runs or no dry run was performed toward `dest`.
+    fn dry_run_failure_rate(&self, _dest: NodeID) -> Option<f32> {
+        None
+    }
+}
+
+/// A read-only summary of a stored route, e.g. to display the routing state of a node.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct StoredRouteInfo {
+    /// The source node.
+    pub source: NodeID,
+    /// The destination node.
+    pub destination: NodeID,
+    /// The receiving nodes of the hops, from the first hop to the destination.
+    pub hops: Vec<NodeID>,
+    /// The canonical signature of the route.
+    pub signature: RouteSignature,
+    /// The arrival time at the destination computed by the last pathfinding, dry run or scheduling.
+    pub arrival: Date,
+    /// The expiration time of the route (its first ending contact).
+    pub expiration: Date,
+}
+
+impl StoredRouteInfo {
+    /// Summarizes the route leading to a route stage.
+    ///
+    /// # Parameters
+    ///
+    /// * `destination_stage` - The route stage reaching the destination.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The summary, without hops for a source stage.
+    pub fn from_stage<NM: NodeManager, CM: ContactManager>(
+        destination_stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+    ) -> Self {
+        let (destination, arrival, expiration) = {
+            let stage = destination_stage.borrow();
+            (stage.to_node, stage.at_time, stage.expiration)
+        };
+        let mut hops = Vec::new();
+        let mut curr = destination_stage.clone();
+        loop {
+            let parent = match &curr.borrow().via {
+                Some(via) => via.parent_route.clone(),
+                None => break,
+            };
+            hops.push(curr.borrow().to_node);
+            curr = parent;
+        }
+        hops.reverse();
+        let source = curr.borrow().to_node;
+
+        Self {
+            source,
+            destination,
+            hops,
+            signature: RouteSignature::from_stage(destination_stage),
+            arrival,
+            expiration,
+        }
+    }
+}
+
+/// A read-only summary of a stored pathfinding tree, e.g. to display the routing state of a node.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct StoredTreeInfo {
+    /// The source node.
+    pub source: NodeID,
+    /// The size of the bundle the tree was computed for.
+    pub size: Volume,
+    /// The priority of the bundle the tree was computed for.
+    pub priority: Priority,
+    /// The nodes excluded from the tree.
+    pub excluded_nodes: Vec<NodeID>,
+    /// The routes of the tree, one per reached destination.
+    pub routes: Vec<StoredRouteInfo>,
+}
+
+impl StoredTreeInfo {
+    /// Summarizes a pathfinding tree.
+    ///
+    /// # Parameters
+    ///
+    /// * `tree` - The pathfinding output.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The summary.
+    pub fn from_tree<NM: NodeManager, CM: ContactManager>(
+        tree: &PathFindingOutput<NM, CM>,
+    ) -> Self {
+        let source = tree.source.borrow().to_node;
+        Self {
+            source,
+            size: tree.bundle.size,
+            priority: tree.bundle.priority,
+            excluded_nodes: tree.excluded_nodes_sorted.clone(),
+            routes: tree
+                .by_destination
+                .iter()
+                .flatten()
+                .filter(|stage| stage.borrow().to_node != source)
+                .map(StoredRouteInfo::from_stage)
+                .collect(),
+        }
+    }
 }
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -71,6 +202,113 @@ impl<NM: NodeManager, CM: ContactManager> Route<NM, CM> {
         }
         return None;
     }
+
+    /// Computes the canonical signature of the route.
+    pub fn signature(&self) -> RouteSignature {
+        RouteSignature::from_stage(&self.destination_stage)
+    }
+}
+
+/// Checks whether the route leading to a route stage goes through a node, i.e. if the node
+/// receives one of its hops.
+///
+/// # Parameters
+///
+/// * `destination_stage` - The route stage reaching the destination.
+/// * `node` - The ID of the node.
+///
+/// # Returns
+///
+/// * `bool` - `true` if one of the hops reaches `node`.
+pub fn route_goes_through<NM: NodeManager, CM: ContactManager>(
+    destination_stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+    node: NodeID,
+) -> bool {
+    let ancestors = destination_stage.borrow().ancestors();
+    iter::once(destination_stage.clone())
+        .chain(ancestors)
+        .any(|stage| {
+            let stage = stage.borrow();
+            stage.via.is_some() && stage.to_node == node
+        })
+}
+
+/// The canonical signature of a route: the identifiers of its contacts, from the first hop to the
+/// last one.
+///
+/// Two routes with the same signature go through the same contacts (the contact identifiers being
+/// assigned by the `Multigraph`), whatever the times computed for their stages. The signatures
+/// allow to deduplicate, compare and count routes.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct RouteSignature(Vec<ContactID>);
+
+impl RouteSignature {
+    /// Computes the signature of the route leading to a route stage.
+    ///
+    /// # Parameters
+    ///
+    /// * `destination_stage` - The route stage reaching the destination.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The signature, empty for a source stage.
+    pub fn from_stage<NM: NodeManager, CM: ContactManager>(
+        destination_stage: &Rc<RefCell<RouteStage<NM, CM>>>,
+    ) -> Self {
+        let ancestors = destination_stage.borrow().ancestors();
+        let mut contacts: Vec<ContactID> = iter::once(destination_stage.clone())
+            .chain(ancestors)
+            .filter_map(|curr| Some(curr.borrow().via.as_ref()?.contact.borrow().id))
+            .collect();
+        contacts.reverse();
+        Self(contacts)
+    }
+
+    /// Returns the identifiers of the contacts, from the first hop to the last one.
+    pub fn contacts(&self) -> &[ContactID] {
+        &self.0
+    }
+}
+
+/// The differences between two sets of routes, see `diff_routes`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, Serialize)]
+pub struct RouteSetDiff {
+    /// The routes of the second set only, sorted by signature.
+    pub added: Vec<RouteSignature>,
+    /// The routes of the first set only, sorted by signature.
+    pub removed: Vec<RouteSignature>,
+}
+
+impl RouteSetDiff {
+    /// Checks if both sets hold the same routes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compares two sets of routes (e.g. two listings of a storage, or the routes selected by two
+/// routers), the duplicates being ignored.
+///
+/// # Parameters
+///
+/// * `before` - The signatures of the first set.
+/// * `after` - The signatures of the second set.
+///
+/// # Returns
+///
+/// * `RouteSetDiff` - The routes added and removed from `before` to `after`.
+pub fn diff_routes<'a>(
+    before: impl IntoIterator<Item = &'a RouteSignature>,
+    after: impl IntoIterator<Item = &'a RouteSignature>,
+) -> RouteSetDiff {
+    let before: BTreeSet<&RouteSignature> = before.into_iter().collect();
+    let after: BTreeSet<&RouteSignature> = after.into_iter().collect();
+    RouteSetDiff {
+        added: after.difference(&before).map(|&sig| sig.clone()).collect(),
+        removed: before.difference(&after).map(|&sig| sig.clone()).collect(),
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager> Clone for Route<NM, CM> {
@@ -110,6 +348,82 @@ pub trait RouteStorage<NM: NodeManager, CM: ContactManager> {
     ) -> Option<Route<NM, CM>>;
 
     fn store(&mut self, bundle: &Bundle, route: Route<NM, CM>);
+
+    /// Drops the stored routes made stale by a change of the network state, e.g. a neighbor marked
+    /// down or up (see `Router::set_neighbor_state`). The default implementation keeps the routes.
+    ///
+    /// # Parameters
+    ///
+    /// * `via_node` - Drops the routes going through this node, all the routes if `None`.
+    fn invalidate(&mut self, _via_node: Option<NodeID>) {}
+
+    /// Takes the refresh requests raised since the last call, e.g. by the aging of the routes.
+    ///
+    /// # Parameters
+    ///
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(NodeID, usize)>` - The destinations whose routes must be recomputed, with the count
+    ///   of routes to recompute for each.
+    fn take_refresh_requests(&mut self, _curr_time: Date) -> Vec<(NodeID, usize)> {
+        Vec::new()
+    }
+}
+
+/// Delegates to the boxed storage, e.g. for the storages selected at runtime (see
+/// `build_generic_router_with_storages`).
+impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for Box<dyn TreeStorage<NM, CM>> {
+    fn select(
+        &self,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> (
+        Option<Rc<RefCell<PathFindingOutput<NM, CM>>>>,
+        Option<Vec<NodeID>>,
+    ) {
+        (**self).select(bundle, curr_time, excluded_nodes_sorted)
+    }
+
+    fn store(&mut self, bundle: &Bundle, tree: Rc<RefCell<PathFindingOutput<NM, CM>>>) {
+        (**self).store(bundle, tree)
+    }
+
+    fn invalidate(&mut self, via_node: Option<NodeID>) {
+        (**self).invalidate(via_node)
+    }
+
+    fn dry_run_failure_rate(&self, dest: NodeID) -> Option<f32> {
+        (**self).dry_run_failure_rate(dest)
+    }
+}
+
+/// Delegates to the boxed storage, e.g. for the storages selected at runtime (see
+/// `build_generic_router_with_storages`).
+impl<NM: NodeManager, CM: ContactManager> RouteStorage<NM, CM> for Box<dyn RouteStorage<NM, CM>> {
+    fn select(
+        &mut self,
+        bundle: &Bundle,
+        curr_time: Date,
+        multigraph: Rc<RefCell<Multigraph<NM, CM>>>,
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> Option<Route<NM, CM>> {
+        (**self).select(bundle, curr_time, multigraph, excluded_nodes_sorted)
+    }
+
+    fn store(&mut self, bundle: &Bundle, route: Route<NM, CM>) {
+        (**self).store(bundle, route)
+    }
+
+    fn invalidate(&mut self, via_node: Option<NodeID>) {
+        (**self).invalidate(via_node)
+    }
+
+    fn take_refresh_requests(&mut self, curr_time: Date) -> Vec<(NodeID, usize)> {
+        (**self).take_refresh_requests(curr_time)
+    }
 }
 
 /// A struct that manages limits and conditions for scheduling based on bundle characteristics.
@@ -117,6 +431,7 @@ pub trait RouteStorage<NM: NodeManager, CM: ContactManager> {
 /// The `Guard` struct keeps track of known routing limits and determines if a scheduling
 /// should be aborted based on its properties and the properties of the associated `Bundle`.
 pub struct Guard {
+    enabled: bool,
     with_priorities: bool,
     known_limits: HashMap<(NodeID, Priority), Volume>,
 }
@@ -133,11 +448,25 @@ impl Guard {
     /// * `Self` - A new instance of `Guard`.
     pub fn new(with_priorities: bool) -> Self {
         Self {
+            enabled: true,
             with_priorities,
             known_limits: HashMap::new(),
         }
     }
 
+    /// Creates a `Guard` that never aborts and learns no limit, every bundle being routed.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new, disabled, instance of `Guard`.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            with_priorities: false,
+            known_limits: HashMap::new(),
+        }
+    }
+
     /// Determines whether the processing must be aborted based on the known limits and bundle.
     ///
     /// This method checks if the current `Bundle` cannot reach any destinations due to size limits.
@@ -150,6 +479,9 @@ impl Guard {
     ///
     /// * `bool` - Returns `true` if processing must be aborted; otherwise, returns `false`.
     pub fn must_abort(&self, bundle: &Bundle) -> bool {
+        if !self.enabled {
+            return false;
+        }
         let priority = if self.with_priorities {
             bundle.priority
         } else {
@@ -167,6 +499,65 @@ impl Guard {
         unreachable_count == bundle.destinations.len()
     }
 
+    /// Returns the known size limit toward a destination: the bundles of this size or larger
+    /// are known to be unroutable.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    /// * `priority` - The priority of the bundles (ignored if the guard ignores priorities).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Volume>` - The limit, or `None` if no limit is known.
+    pub fn limit(&self, dest: NodeID, priority: Priority) -> Option<Volume> {
+        let priority = if self.with_priorities { priority } else { 0 };
+        self.known_limits.get(&(dest, priority)).copied()
+    }
+
+    /// Lists the known limits, e.g. to share them with other nodes (see `StateSummary`).
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(NodeID, Priority, Volume)>` - The destination, the priority (0 if the guard ignores
+    ///   priorities) and the limit of each known limit.
+    pub fn known_limits(&self) -> Vec<(NodeID, Priority, Volume)> {
+        self.known_limits
+            .iter()
+            .map(|((dest, priority), limit)| (*dest, *priority, *limit))
+            .collect()
+    }
+
+    /// Merges a limit learned elsewhere, the lowest limit being kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    /// * `priority` - The priority of the bundles (ignored if the guard ignores priorities).
+    /// * `limit` - The size of the bundles known to be unroutable.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the limit was lowered or added.
+    pub fn merge_limit(&mut self, dest: NodeID, priority: Priority, limit: Volume) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let priority = if self.with_priorities { priority } else { 0 };
+        if let Some(val) = self.known_limits.get(&(dest, priority)) {
+            if *val <= limit {
+                return false;
+            }
+        }
+        self.known_limits.insert((dest, priority), limit);
+        true
+    }
+
+    /// Forgets the known limits, e.g. after a change of the network state.
+    pub fn clear(&mut self) {
+        self.known_limits.clear();
+    }
+
     /// Adds a new size limit for a specific destination based on the given bundle.
     ///
     /// If the new size limit is larger than the current limit for the destination and priority,
@@ -177,6 +568,9 @@ impl Guard {
     /// * `bundle` - A reference to the `Bundle` containing the size to be added.
     /// * `dest` - The destination `NodeID` for which the limit is being added.
     pub fn add_limit(&mut self, bundle: &Bundle, dest: NodeID) {
+        if !self.enabled {
+            return;
+        }
         let priority = if self.with_priorities {
             bundle.priority
         } else {
@@ -1,11 +1,101 @@
-use std::{cell::RefCell, cmp::Ordering, marker::PhantomData, rc::Rc};
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, cmp::Ordering, marker::PhantomData};
 
 use crate::{
-    bundle::Bundle, contact_manager::ContactManager, distance::Distance, multigraph::Multigraph,
-    node_manager::NodeManager, routing::dry_run_unicast_path, types::NodeID,
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    distance::{sabr::entry_node, Distance},
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    route_stage::HopFailure,
+    routing::try_dry_run_unicast_path,
+    telemetry::Span,
+    types::{Date, Duration, HopCount, NodeID},
 };
 
-use super::{Route, RouteStorage};
+/// The outcome of a candidate route during a selection, see `RoutingTable::with_audit`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum CandidateVerdict {
+    /// The termination time of the route is passed, the route was removed from the table.
+    Terminated,
+    /// The route reached a limit of the aging policy, the route was removed from the table.
+    Aged,
+    /// The dry run failed: excluded node, insufficient capacity, backlog or late delivery (see
+    /// `CandidateAudit::failure`).
+    Infeasible,
+    /// The route was feasible but not the best one.
+    Outranked,
+    /// The route was selected.
+    Selected,
+}
+
+/// The record of a candidate route during a selection.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct CandidateAudit {
+    /// The entry node of the route (the receiving node of the first hop).
+    pub entry_node: Option<NodeID>,
+    /// The number of hops of the route.
+    pub hop_count: HopCount,
+    /// The termination time of the route.
+    pub termination_time: Date,
+    /// The delivery time computed by the dry run, `None` if the route was not dry run successfully.
+    pub delivery_time: Option<Date>,
+    /// The outcome of the candidate.
+    pub verdict: CandidateVerdict,
+    /// The cause of the failure of the dry run, for the infeasible candidates.
+    pub failure: Option<HopFailure>,
+}
+
+use super::{route_goes_through, Route, RouteSignature, RouteStorage, StoredRouteInfo};
+
+/// The aging policy of a `RoutingTable`, the routes are otherwise retained until their termination.
+///
+/// The stored routes reflect the state of the contact plan at their computation, they drift from
+/// it as the bundles are scheduled. An aged route is removed from the table at the next selection
+/// for its destination, and the refresh of the destination is requested to the router (see
+/// `RouteStorage::take_refresh_requests`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default)]
+pub struct AgingPolicy {
+    /// The maximum count of selections of a route (unbounded if `None`).
+    pub max_uses: Option<usize>,
+    /// The maximum age of a route since its computation (unbounded if `None`).
+    pub max_age: Option<Duration>,
+    /// The count of routes recomputed for a destination after the aging of some of its routes,
+    /// no refresh is requested if 0.
+    pub refresh_top_k: usize,
+}
+
+/// A route stored in a `RoutingTable`, with its aging data.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct TableEntry<NM: NodeManager, CM: ContactManager> {
+    /// The route.
+    route: Route<NM, CM>,
+    /// The signature of the route, for the duplicate detection.
+    signature: RouteSignature,
+    /// The count of selections of the route.
+    uses: usize,
+}
+
+impl<NM: NodeManager, CM: ContactManager> TableEntry<NM, CM> {
+    /// Checks if the route reached a limit of the aging policy.
+    fn is_aged(&self, policy: &AgingPolicy, curr_time: Date) -> bool {
+        if let Some(max_uses) = policy.max_uses {
+            if self.uses >= max_uses {
+                return true;
+            }
+        }
+        if let Some(max_age) = policy.max_age {
+            // the source stage holds the time of the computation
+            if curr_time - self.route.source_stage.borrow().at_time > max_age {
+                return true;
+            }
+        }
+        false
+    }
+}
 
 /// A routing table that stores the routes for each destinations.
 ///
@@ -18,13 +108,21 @@ use super::{Route, RouteStorage};
 /// - `D`: A type implementing `Distance<NM, CM>`, providing a distance metric for route comparison.
 ///
 /// # Fields
-/// - `tables`: A vector of vectors of `Route<NM, CM>`, where each inner vector represents
+/// - `tables`: A vector of vectors of routes, where each inner vector represents
 ///   routes to a specific destination node.
 /// - `_phantom_nm`: A phantom marker to associate the routing table with a `NodeManager` type.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct RoutingTable<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> {
     /// Routes are stored in a two-dimensional vector, grouped by destination node.
-    tables: Vec<Vec<Route<NM, CM>>>,
+    tables: Vec<Vec<TableEntry<NM, CM>>>,
+    /// The maximum count of routes per destination (unbounded if `None`).
+    max_routes: Option<usize>,
+    /// The aging policy of the routes.
+    aging: AgingPolicy,
+    /// The destinations to refresh, with the count of routes to recompute.
+    refresh_requests: Vec<(NodeID, usize)>,
+    /// The candidates of the last selection, if the audit is enabled.
+    audit: Option<Vec<CandidateAudit>>,
     #[doc(hidden)]
     _phantom_nm: PhantomData<NM>,
     #[doc(hidden)]
@@ -40,11 +138,105 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RoutingTable<NM,
     pub fn new() -> Self {
         Self {
             tables: Vec::new(),
+            max_routes: None,
+            aging: AgingPolicy::default(),
+            refresh_requests: Vec::new(),
+            audit: None,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_distance: PhantomData,
         }
     }
+
+    /// Creates a new, empty `RoutingTable` recording the candidates of each selection.
+    ///
+    /// The audit allows to check the route selection step by step, e.g. against the SABR
+    /// specification with the `SABRCompliant` distance.
+    ///
+    /// # Returns
+    /// A new instance of `RoutingTable` with the audit enabled.
+    pub fn with_audit() -> Self {
+        let mut table = Self::new();
+        table.audit = Some(Vec::new());
+        table
+    }
+
+    /// Creates a new, empty `RoutingTable` with an aging policy.
+    ///
+    /// # Parameters
+    /// - `aging`: The limits of the routes and the refresh to request when they are reached.
+    ///
+    /// # Returns
+    /// A new instance of `RoutingTable` with the aging policy.
+    pub fn with_aging(aging: AgingPolicy) -> Self {
+        let mut table = Self::new();
+        table.aging = aging;
+        table
+    }
+
+    /// Sets the aging policy, applied to the stored routes from the next selection.
+    pub fn set_aging(&mut self, aging: AgingPolicy) {
+        self.aging = aging;
+    }
+
+    /// Creates a new, empty `RoutingTable` storing a limited count of routes per destination.
+    ///
+    /// # Parameters
+    /// - `max_routes`: The maximum count of routes per destination, the best routes according to
+    ///   the distance are kept.
+    ///
+    /// # Returns
+    /// A new instance of `RoutingTable` with the limit.
+    pub fn with_max_routes(max_routes: usize) -> Self {
+        let mut table = Self::new();
+        table.max_routes = Some(max_routes);
+        table
+    }
+
+    /// Sets the maximum count of routes per destination (unbounded if `None`), applied from the
+    /// next storage.
+    pub fn set_max_routes(&mut self, max_routes: Option<usize>) {
+        self.max_routes = max_routes;
+    }
+
+    /// Lists the routes stored for a destination, in the order of the table.
+    ///
+    /// # Parameters
+    /// - `dest`: The destination node.
+    ///
+    /// # Returns
+    /// An iterator over the summaries of the routes.
+    pub fn routes_to(&self, dest: NodeID) -> impl Iterator<Item = StoredRouteInfo> + '_ {
+        self.tables
+            .get(dest as usize)
+            .into_iter()
+            .flatten()
+            .map(|entry| StoredRouteInfo::from_stage(&entry.route.destination_stage))
+    }
+
+    /// Retrieves the count of routes stored for a destination.
+    pub fn route_count(&self, dest: NodeID) -> usize {
+        self.tables
+            .get(dest as usize)
+            .map_or(0, |routes| routes.len())
+    }
+
+    /// Requests the refresh of a destination, if the aging policy refreshes the routes.
+    fn request_refresh(&mut self, dest: NodeID) {
+        if self.aging.refresh_top_k > 0
+            && !self.refresh_requests.iter().any(|(node, _)| *node == dest)
+        {
+            self.refresh_requests.push((dest, self.aging.refresh_top_k));
+        }
+    }
+
+    /// Retrieves the candidates of the last selection, in the order of the table.
+    ///
+    /// # Returns
+    /// The candidate records, `None` if the audit is disabled.
+    pub fn last_audit(&self) -> Option<&[CandidateAudit]> {
+        self.audit.as_deref()
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM, CM>
@@ -56,15 +248,64 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
     /// destination index exceeds the current size of `tables`, the vector is resized to
     /// accommodate the new destination.
     ///
+    /// A stored route with the same contact sequence is replaced by the new one. If the
+    /// destination reached the maximum count of routes, the new route replaces the worst
+    /// stored route if it is better according to the distance, and is dropped otherwise.
+    ///
     /// # Parameters
     /// - `bundle`: The bundle whose destination will determine the storage index.
     /// - `route`: The `Route<NM, CM>` to be stored.
     fn store(&mut self, bundle: &Bundle, route: Route<NM, CM>) {
         let dest = bundle.destinations[0];
+        let span = Span::start("a_sabr.storage.store");
+        span.int("destination", dest.into());
         if self.tables.len() < 1 + dest as usize {
-            self.tables.resize((dest + 1) as usize, vec![])
+            self.tables.resize_with((dest + 1) as usize, Vec::new)
+        }
+        let routes = &mut self.tables[dest as usize];
+
+        // a route with the same contact sequence is replaced by the fresher one
+        let signature = route.signature();
+        if let Some(entry) = routes.iter_mut().find(|entry| entry.signature == signature) {
+            *entry = TableEntry {
+                route,
+                signature,
+                uses: 0,
+            };
+            return;
         }
-        self.tables[dest as usize].push(route);
+
+        if let Some(max_routes) = self.max_routes {
+            if routes.len() >= max_routes {
+                // the worst route is replaced if the new one is better
+                let worst_idx = routes
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| {
+                        D::cmp(
+                            &a.route.destination_stage.borrow(),
+                            &b.route.destination_stage.borrow(),
+                        )
+                    })
+                    .map(|(idx, _)| idx);
+                match worst_idx {
+                    Some(idx)
+                        if D::cmp(
+                            &route.destination_stage.borrow(),
+                            &routes[idx].route.destination_stage.borrow(),
+                        ) == Ordering::Less =>
+                    {
+                        routes.remove(idx);
+                    }
+                    _ => return,
+                }
+            }
+        }
+        routes.push(TableEntry {
+            route,
+            signature,
+            uses: 0,
+        });
     }
 
     /// Selects the best route for a bundle, based on current network conditions and
@@ -90,21 +331,51 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
     fn select(
         &mut self,
         bundle: &Bundle,
-        curr_time: crate::types::Date,
+        curr_time: Date,
         multigraph: Rc<RefCell<Multigraph<NM, CM>>>,
         excluded_nodes_sorted: &Vec<NodeID>,
     ) -> Option<Route<NM, CM>> {
         let dest = bundle.destinations[0];
+        let span = Span::start("a_sabr.storage.select");
+        span.int("destination", dest.into());
 
         if self.tables.len() < 1 + dest as usize {
-            self.tables.resize((dest + 1) as usize, vec![])
+            self.tables.resize_with((dest + 1) as usize, Vec::new)
         }
 
+        let aging = self.aging;
+        let mut aged_count = 0;
         let routes = &mut self.tables[dest as usize];
         let mut best_route_option: Option<Route<NM, CM>> = None;
+        let mut audit = self.audit.take().map(|mut audit| {
+            audit.clear();
+            audit
+        });
+        let mut selected_idx = None;
+        // the index of the best route among the retained ones
+        let mut best_idx = None;
+        let mut retained_count = 0;
 
-        routes.retain(|route| {
-            if curr_time > route.destination_stage.borrow().expiration {
+        routes.retain(|entry| {
+            let route = &entry.route;
+            let mut record = audit.as_ref().map(|_| {
+                let stage = route.destination_stage.borrow();
+                CandidateAudit {
+                    entry_node: entry_node(&stage),
+                    hop_count: stage.hop_count,
+                    termination_time: stage.expiration,
+                    delivery_time: None,
+                    verdict: CandidateVerdict::Terminated,
+                    failure: None,
+                }
+            });
+            let retained = if curr_time > route.destination_stage.borrow().expiration {
+                false
+            } else if entry.is_aged(&aging, curr_time) {
+                aged_count += 1;
+                if let Some(record) = record.as_mut() {
+                    record.verdict = CandidateVerdict::Aged;
+                }
                 false
             } else {
                 // apply exclusions
@@ -112,28 +383,99 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
                     .borrow_mut()
                     .prepare_for_exclusions_sorted(excluded_nodes_sorted);
                 // dry run with exclusions
-                if let Some(new_candidate) =
-                    dry_run_unicast_path(bundle, curr_time, route.source_stage.clone(), true)
+                match try_dry_run_unicast_path(bundle, curr_time, route.source_stage.clone(), true)
                 {
-                    match best_route_option {
-                        Some(ref best_route) => {
-                            if D::cmp(
-                                &new_candidate.borrow(),
-                                &best_route.destination_stage.borrow(),
-                            ) == Ordering::Less
-                            {
+                    Ok(new_candidate) => {
+                        if let Some(record) = record.as_mut() {
+                            record.delivery_time = Some(new_candidate.borrow().at_time);
+                            record.verdict = CandidateVerdict::Outranked;
+                        }
+                        match best_route_option {
+                            Some(ref best_route) => {
+                                if D::cmp(
+                                    &new_candidate.borrow(),
+                                    &best_route.destination_stage.borrow(),
+                                ) == Ordering::Less
+                                {
+                                    best_route_option = Some(route.clone());
+                                    best_idx = Some(retained_count);
+                                    selected_idx = audit.as_ref().map(|audit| audit.len());
+                                }
+                            }
+                            None => {
                                 best_route_option = Some(route.clone());
+                                best_idx = Some(retained_count);
+                                selected_idx = audit.as_ref().map(|audit| audit.len());
                             }
                         }
-                        None => {
-                            best_route_option = Some(route.clone());
+                    }
+                    Err(failure) => {
+                        if let Some(record) = record.as_mut() {
+                            record.verdict = CandidateVerdict::Infeasible;
+                            record.failure = Some(failure);
                         }
                     }
-                };
+                }
+                retained_count += 1;
                 true
+            };
+            if let (Some(audit), Some(record)) = (audit.as_mut(), record) {
+                audit.push(record);
             }
+            retained
         });
 
+        if let Some(idx) = best_idx {
+            routes[idx].uses += 1;
+        }
+        if aged_count > 0 {
+            self.request_refresh(dest);
+        }
+
+        if let Some(mut audit) = audit {
+            if let Some(idx) = selected_idx {
+                audit[idx].verdict = CandidateVerdict::Selected;
+            }
+            self.audit = Some(audit);
+        }
+
+        span.int("candidates", retained_count as i64);
+        span.flag("hit", best_route_option.is_some());
         return best_route_option;
     }
+
+    /// Removes the aged routes of all the destinations, and takes the destinations whose routes
+    /// aged since the last call.
+    ///
+    /// # Parameters
+    /// - `curr_time`: The current time, used for the age of the routes.
+    ///
+    /// # Returns
+    /// The destinations to refresh, with the `refresh_top_k` count of the aging policy.
+    fn take_refresh_requests(&mut self, curr_time: Date) -> Vec<(NodeID, usize)> {
+        let aging = self.aging;
+        for dest in 0..self.tables.len() {
+            let routes = &mut self.tables[dest];
+            let count = routes.len();
+            routes.retain(|entry| !entry.is_aged(&aging, curr_time));
+            if routes.len() < count {
+                self.request_refresh(dest as NodeID);
+            }
+        }
+        core::mem::take(&mut self.refresh_requests)
+    }
+
+    /// Drops the routes going through a node, or all the routes.
+    ///
+    /// # Parameters
+    /// - `via_node`: The node, all the routes are dropped if `None`.
+    fn invalidate(&mut self, via_node: Option<NodeID>) {
+        let Some(node) = via_node else {
+            self.tables.iter_mut().for_each(Vec::clear);
+            return;
+        };
+        for routes in &mut self.tables {
+            routes.retain(|entry| !route_goes_through(&entry.route.destination_stage, node));
+        }
+    }
 }
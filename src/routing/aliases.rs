@@ -5,13 +5,22 @@ use crate::{
     node::Node,
     node_manager::NodeManager,
     pathfinding::{
+        adaptive_parenting::AdaptiveParenting,
         hybrid_parenting::{HybridParentingPathExcl, HybridParentingTreeExcl},
         node_parenting::{NodeParentingPathExcl, NodeParentingTreeExcl},
     },
-    route_storage::{cache::TreeCache, table::RoutingTable},
+    route_storage::{cache::TreeCache, table::RoutingTable, Guard, RouteStorage, TreeStorage},
     routing::volcgr::VolCgr,
+    types::Duration,
 };
-use std::{cell::RefCell, rc::Rc};
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cell::RefCell, fmt};
+use serde::Serialize;
 
 #[cfg(feature = "contact_suppression")]
 use super::cgr::Cgr;
@@ -20,6 +29,8 @@ use crate::pathfinding::contact_parenting::ContactParentingPath;
 #[cfg(feature = "contact_work_area")]
 use crate::pathfinding::contact_parenting::{ContactParentingPathExcl, ContactParentingTreeExcl};
 
+#[cfg(feature = "contact_suppression")]
+use crate::distance::sabr::SABRCompliant;
 #[cfg(feature = "contact_suppression")]
 use crate::pathfinding::hybrid_parenting::HybridParentingPath;
 #[cfg(feature = "first_depleted")]
@@ -29,140 +40,165 @@ use crate::pathfinding::limiting_contact::first_ending::FirstEnding;
 #[cfg(feature = "contact_suppression")]
 use crate::pathfinding::node_parenting::NodeParentingPath;
 
-use super::{spsn::Spsn, Router};
+use super::{
+    config::{feature_enabled, FEATURES},
+    spsn::Spsn,
+    Router,
+};
+
+pub type SpsnHybridParenting<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, HybridParentingTreeExcl<NM, CM, SABR>, S>;
 
-pub type SpsnHybridParenting<NM, CM> =
-    Spsn<NM, CM, HybridParentingTreeExcl<NM, CM, SABR>, TreeCache<NM, CM>>;
+pub type SpsnNodeParenting<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, NodeParentingTreeExcl<NM, CM, SABR>, S>;
 
-pub type SpsnNodeParenting<NM, CM> =
-    Spsn<NM, CM, NodeParentingTreeExcl<NM, CM, SABR>, TreeCache<NM, CM>>;
+pub type SpsnAdaptiveParenting<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, AdaptiveParenting<NM, CM, SABR>, S>;
 
 #[cfg(feature = "contact_work_area")]
-pub type SpsnContactParenting<NM, CM> =
-    Spsn<NM, CM, ContactParentingTreeExcl<NM, CM, SABR>, TreeCache<NM, CM>>;
+pub type SpsnContactParenting<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, ContactParentingTreeExcl<NM, CM, SABR>, S>;
 
-pub type VolCgrHybridParenting<NM, CM> =
-    VolCgr<NM, CM, HybridParentingPathExcl<NM, CM, SABR>, RoutingTable<NM, CM, SABR>>;
+pub type VolCgrHybridParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    VolCgr<NM, CM, HybridParentingPathExcl<NM, CM, SABR>, S>;
 
-pub type VolCgrNodeParenting<NM, CM> =
-    VolCgr<NM, CM, NodeParentingPathExcl<NM, CM, SABR>, RoutingTable<NM, CM, SABR>>;
+pub type VolCgrNodeParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    VolCgr<NM, CM, NodeParentingPathExcl<NM, CM, SABR>, S>;
 
 #[cfg(feature = "contact_work_area")]
-pub type VolCgrContactParenting<NM, CM> =
-    VolCgr<NM, CM, ContactParentingPathExcl<NM, CM, SABR>, RoutingTable<NM, CM, SABR>>;
+pub type VolCgrContactParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    VolCgr<NM, CM, ContactParentingPathExcl<NM, CM, SABR>, S>;
 
 #[cfg(feature = "contact_suppression")]
-pub type CgrFirstEndingHybridParenting<NM, CM> =
-    Cgr<NM, CM, FirstEnding<NM, CM, HybridParentingPath<NM, CM, SABR>>, RoutingTable<NM, CM, SABR>>;
+pub type CgrFirstEndingHybridParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, HybridParentingPath<NM, CM, SABR>>, S>;
 
 #[cfg(feature = "first_depleted")]
-pub type CgrFirstDepletedHybridParenting<NM, CM> = Cgr<
-    NM,
-    CM,
-    FirstDepleted<NM, CM, HybridParentingPath<NM, CM, SABR>>,
-    RoutingTable<NM, CM, SABR>,
->;
+pub type CgrFirstDepletedHybridParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, HybridParentingPath<NM, CM, SABR>>, S>;
 
 #[cfg(feature = "contact_suppression")]
-pub type CgrFirstEndingNodeParenting<NM, CM> =
-    Cgr<NM, CM, FirstEnding<NM, CM, NodeParentingPath<NM, CM, SABR>>, RoutingTable<NM, CM, SABR>>;
+pub type CgrFirstEndingNodeParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, NodeParentingPath<NM, CM, SABR>>, S>;
 
 #[cfg(feature = "first_depleted")]
-pub type CgrFirstDepletedNodeParenting<NM, CM> =
-    Cgr<NM, CM, FirstDepleted<NM, CM, NodeParentingPath<NM, CM, SABR>>, RoutingTable<NM, CM, SABR>>;
+pub type CgrFirstDepletedNodeParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, NodeParentingPath<NM, CM, SABR>>, S>;
 
 #[cfg(all(feature = "contact_work_area", feature = "contact_suppression"))]
-pub type CgrFirstEndingContactParenting<NM, CM> = Cgr<
-    NM,
-    CM,
-    FirstEnding<NM, CM, ContactParentingPath<NM, CM, SABR>>,
-    RoutingTable<NM, CM, SABR>,
->;
+pub type CgrFirstEndingContactParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, ContactParentingPath<NM, CM, SABR>>, S>;
 
 #[cfg(all(feature = "contact_work_area", feature = "first_depleted"))]
-pub type CgrFirstDepletedContactParenting<NM, CM> = Cgr<
-    NM,
-    CM,
-    FirstDepleted<NM, CM, ContactParentingPath<NM, CM, SABR>>,
-    RoutingTable<NM, CM, SABR>,
->;
+pub type CgrFirstDepletedContactParenting<NM, CM, S = RoutingTable<NM, CM, SABR>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, ContactParentingPath<NM, CM, SABR>>, S>;
+
+/// The SABR compliance preset, to be created with `Cgr::sabr_compliant` (the
+/// "CgrSabrCompliant" router type of `build_generic_router`).
+#[cfg(feature = "contact_suppression")]
+pub type CgrSabrCompliant<NM, CM, S = RoutingTable<NM, CM, SABRCompliant>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, NodeParentingPath<NM, CM, SABRCompliant>>, S>;
 
-pub type SpsnHybridParentingHop<NM, CM> =
-    Spsn<NM, CM, HybridParentingTreeExcl<NM, CM, Hop>, TreeCache<NM, CM>>;
+pub type SpsnHybridParentingHop<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, HybridParentingTreeExcl<NM, CM, Hop>, S>;
 
-pub type SpsnNodeParentingHop<NM, CM> =
-    Spsn<NM, CM, NodeParentingTreeExcl<NM, CM, Hop>, TreeCache<NM, CM>>;
+pub type SpsnNodeParentingHop<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, NodeParentingTreeExcl<NM, CM, Hop>, S>;
 
 #[cfg(feature = "contact_work_area")]
-pub type SpsnContactParentingHop<NM, CM> =
-    Spsn<NM, CM, ContactParentingTreeExcl<NM, CM, Hop>, TreeCache<NM, CM>>;
+pub type SpsnContactParentingHop<NM, CM, S = TreeCache<NM, CM>> =
+    Spsn<NM, CM, ContactParentingTreeExcl<NM, CM, Hop>, S>;
 
-pub type VolCgrHybridParentingHop<NM, CM> =
-    VolCgr<NM, CM, HybridParentingPathExcl<NM, CM, Hop>, RoutingTable<NM, CM, Hop>>;
+pub type VolCgrHybridParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    VolCgr<NM, CM, HybridParentingPathExcl<NM, CM, Hop>, S>;
 
-pub type VolCgrNodeParentingHop<NM, CM> =
-    VolCgr<NM, CM, NodeParentingPathExcl<NM, CM, Hop>, RoutingTable<NM, CM, Hop>>;
+pub type VolCgrNodeParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    VolCgr<NM, CM, NodeParentingPathExcl<NM, CM, Hop>, S>;
 
 #[cfg(feature = "contact_work_area")]
-pub type VolCgrContactParentingHop<NM, CM> =
-    VolCgr<NM, CM, ContactParentingPathExcl<NM, CM, Hop>, RoutingTable<NM, CM, Hop>>;
+pub type VolCgrContactParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    VolCgr<NM, CM, ContactParentingPathExcl<NM, CM, Hop>, S>;
 
 #[cfg(feature = "contact_suppression")]
-pub type CgrFirstEndingHybridParentingHop<NM, CM> =
-    Cgr<NM, CM, FirstEnding<NM, CM, HybridParentingPath<NM, CM, Hop>>, RoutingTable<NM, CM, Hop>>;
+pub type CgrFirstEndingHybridParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, HybridParentingPath<NM, CM, Hop>>, S>;
 
 #[cfg(feature = "first_depleted")]
-pub type CgrFirstDepletedHybridParentingHop<NM, CM> =
-    Cgr<NM, CM, FirstDepleted<NM, CM, HybridParentingPath<NM, CM, Hop>>, RoutingTable<NM, CM, Hop>>;
+pub type CgrFirstDepletedHybridParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, HybridParentingPath<NM, CM, Hop>>, S>;
 
 #[cfg(feature = "contact_suppression")]
-pub type CgrFirstEndingNodeParentingHop<NM, CM> =
-    Cgr<NM, CM, FirstEnding<NM, CM, NodeParentingPath<NM, CM, Hop>>, RoutingTable<NM, CM, Hop>>;
+pub type CgrFirstEndingNodeParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, NodeParentingPath<NM, CM, Hop>>, S>;
 
 #[cfg(feature = "first_depleted")]
-pub type CgrFirstDepletedNodeParentingHop<NM, CM> =
-    Cgr<NM, CM, FirstDepleted<NM, CM, NodeParentingPath<NM, CM, Hop>>, RoutingTable<NM, CM, Hop>>;
+pub type CgrFirstDepletedNodeParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, NodeParentingPath<NM, CM, Hop>>, S>;
 
 #[cfg(all(feature = "contact_work_area", feature = "contact_suppression"))]
-pub type CgrFirstEndingContactParentingHop<NM, CM> =
-    Cgr<NM, CM, FirstEnding<NM, CM, ContactParentingPath<NM, CM, Hop>>, RoutingTable<NM, CM, Hop>>;
+pub type CgrFirstEndingContactParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstEnding<NM, CM, ContactParentingPath<NM, CM, Hop>>, S>;
 
 #[cfg(all(feature = "contact_work_area", feature = "first_depleted"))]
-pub type CgrFirstDepletedContactParentingHop<NM, CM> = Cgr<
-    NM,
-    CM,
-    FirstDepleted<NM, CM, ContactParentingPath<NM, CM, Hop>>,
-    RoutingTable<NM, CM, Hop>,
->;
+pub type CgrFirstDepletedContactParentingHop<NM, CM, S = RoutingTable<NM, CM, Hop>> =
+    Cgr<NM, CM, FirstDepleted<NM, CM, ContactParentingPath<NM, CM, Hop>>, S>;
 
 macro_rules! register_cgr_router {
-    ($router:ident, $router_name:literal, $test_name_variable:ident, $nodes:ident, $contacts:ident) => {
+    ($router:ident, $router_name:literal, $test_name_variable:ident, $nodes:ident, $contacts:ident, $storages:ident, $options:ident) => {
         if $test_name_variable == $router_name {
+            if let Some(factory) = &$storages.route_storage {
+                let storage = Rc::new(RefCell::new(factory()));
+                let router =
+                    $router::<NM, CM, BoxedRouteStorage<NM, CM>>::new($nodes, $contacts, storage);
+                return Ok(Box::new(match $options.booking_horizon {
+                    Some(horizon) => router.with_booking_horizon(horizon),
+                    None => router,
+                }));
+            }
             let routing_table = Rc::new(RefCell::new(RoutingTable::new()));
 
-            return Box::new($router::<NM, CM>::new($nodes, $contacts, routing_table));
+            let router = $router::<NM, CM>::new($nodes, $contacts, routing_table);
+            return Ok(Box::new(match $options.booking_horizon {
+                Some(horizon) => router.with_booking_horizon(horizon),
+                None => router,
+            }));
         }
     };
 }
 
 macro_rules! register_spsn_router {
-    ($router:ident, $router_name:literal, $test_name_variable:ident, $nodes:ident, $contacts:ident, $check_size:ident, $check_priority:ident, $max_entries:ident) => {
+    ($router:ident, $router_name:literal, $test_name_variable:ident, $nodes:ident, $contacts:ident, $check_size:ident, $check_priority:ident, $max_entries:ident, $storages:ident, $options:ident) => {
         if $test_name_variable == $router_name {
+            if let Some(factory) = &$storages.tree_storage {
+                let storage = Rc::new(RefCell::new(factory()));
+                let router = $router::<NM, CM, BoxedTreeStorage<NM, CM>>::new(
+                    $nodes,
+                    $contacts,
+                    storage,
+                    $check_priority,
+                )
+                .with_guard($options.guard($check_priority));
+                return Ok(Box::new(match $options.booking_horizon {
+                    Some(horizon) => router.with_booking_horizon(horizon),
+                    None => router,
+                }));
+            }
             let cache = Rc::new(RefCell::new(TreeCache::new(
                 $check_size,
                 $check_priority,
                 $max_entries,
             )));
 
-            return Box::new($router::<NM, CM>::new(
-                $nodes,
-                $contacts,
-                cache,
-                $check_priority,
-            ));
+            let router = $router::<NM, CM>::new($nodes, $contacts, cache, $check_priority)
+                .with_guard($options.guard($check_priority));
+            return Ok(Box::new(match $options.booking_horizon {
+                Some(horizon) => router.with_booking_horizon(horizon),
+                None => router,
+            }));
         }
     };
 }
+#[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Clone)]
 pub struct SpsnOptions {
     pub check_size: bool,
@@ -170,16 +206,395 @@ pub struct SpsnOptions {
     pub max_entries: usize,
 }
 
+/// The settings of the routers built by `build_generic_router_with_options` that are not part
+/// of their type.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+pub struct RouterOptions {
+    /// Whether the Spsn routers abort the routing of the bundles toward the destinations known to
+    /// be unreachable for their size (see `Guard`).
+    pub guard: bool,
+    /// Whether the guard tracks the limits per priority, `SpsnOptions::check_priority` if `None`.
+    pub guard_priorities: Option<bool>,
+    /// How far ahead of the routing time the transmissions are booked, without limit if `None`
+    /// (see `Spsn::with_booking_horizon`).
+    pub booking_horizon: Option<Duration>,
+}
+
+impl Default for RouterOptions {
+    fn default() -> Self {
+        Self {
+            guard: true,
+            guard_priorities: None,
+            booking_horizon: None,
+        }
+    }
+}
+
+impl RouterOptions {
+    /// Creates the guard of a Spsn router.
+    ///
+    /// # Parameters
+    ///
+    /// * `check_priority` - The `SpsnOptions::check_priority` of the router.
+    ///
+    /// # Returns
+    ///
+    /// * `Guard` - The guard, disabled if `guard` is not set.
+    fn guard(&self, check_priority: bool) -> Guard {
+        if !self.guard {
+            return Guard::disabled();
+        }
+        Guard::new(self.guard_priorities.unwrap_or(check_priority))
+    }
+}
+
+/// A tree storage selected at runtime, see `StorageFactories`.
+pub type BoxedTreeStorage<NM, CM> = Box<dyn TreeStorage<NM, CM>>;
+
+/// A route storage selected at runtime, see `StorageFactories`.
+pub type BoxedRouteStorage<NM, CM> = Box<dyn RouteStorage<NM, CM>>;
+
+/// The storages of the routers built by `build_generic_router_with_storages`, in place of the
+/// `TreeCache` of the Spsn routers and of the `RoutingTable` of the Cgr and VolCgr routers (e.g.
+/// persistent or indexed storages).
+///
+/// A factory is called once per router built. Without factory, the default storage is used.
+pub struct StorageFactories<NM: NodeManager, CM: ContactManager> {
+    /// Creates the tree storage of the Spsn routers.
+    tree_storage: Option<Box<dyn Fn() -> BoxedTreeStorage<NM, CM>>>,
+    /// Creates the route storage of the Cgr and VolCgr routers.
+    route_storage: Option<Box<dyn Fn() -> BoxedRouteStorage<NM, CM>>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Default for StorageFactories<NM, CM> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> StorageFactories<NM, CM> {
+    /// Creates the factories of the default storages.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `StorageFactories`.
+    pub fn new() -> Self {
+        Self {
+            tree_storage: None,
+            route_storage: None,
+        }
+    }
+
+    /// Sets the factory of the tree storage of the Spsn routers (the `SpsnOptions` then only
+    /// configure the guard of the router).
+    ///
+    /// # Parameters
+    ///
+    /// * `factory` - Creates a tree storage.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The factories with the tree storage one.
+    pub fn with_tree_storage<F: Fn() -> BoxedTreeStorage<NM, CM> + 'static>(
+        mut self,
+        factory: F,
+    ) -> Self {
+        self.tree_storage = Some(Box::new(factory));
+        self
+    }
+
+    /// Sets the factory of the route storage of the Cgr and VolCgr routers.
+    ///
+    /// # Parameters
+    ///
+    /// * `factory` - Creates a route storage.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The factories with the route storage one.
+    pub fn with_route_storage<F: Fn() -> BoxedRouteStorage<NM, CM> + 'static>(
+        mut self,
+        factory: F,
+    ) -> Self {
+        self.route_storage = Some(Box::new(factory));
+        self
+    }
+}
+
+/// The router types of `build_generic_router`, with the features they require.
+const ROUTER_TYPES: &[(&str, &[&str])] = &[
+    ("SpsnNodeParenting", &[]),
+    ("SpsnNodeParentingHop", &[]),
+    ("SpsnHybridParenting", &[]),
+    ("SpsnHybridParentingHop", &[]),
+    ("SpsnAdaptiveParenting", &[]),
+    ("SpsnContactParenting", &["contact_work_area"]),
+    ("SpsnContactParentingHop", &["contact_work_area"]),
+    ("VolCgrNodeParenting", &[]),
+    ("VolCgrNodeParentingHop", &[]),
+    ("VolCgrHybridParenting", &[]),
+    ("VolCgrHybridParentingHop", &[]),
+    ("VolCgrContactParenting", &["contact_work_area"]),
+    ("VolCgrContactParentingHop", &["contact_work_area"]),
+    ("CgrFirstEndingNodeParenting", &["contact_suppression"]),
+    ("CgrFirstEndingNodeParentingHop", &["contact_suppression"]),
+    ("CgrFirstEndingHybridParenting", &["contact_suppression"]),
+    ("CgrFirstEndingHybridParentingHop", &["contact_suppression"]),
+    (
+        "CgrFirstEndingContactParenting",
+        &["contact_work_area", "contact_suppression"],
+    ),
+    (
+        "CgrFirstEndingContactParentingHop",
+        &["contact_work_area", "contact_suppression"],
+    ),
+    (
+        "CgrFirstDepletedNodeParenting",
+        &["contact_suppression", "first_depleted"],
+    ),
+    (
+        "CgrFirstDepletedNodeParentingHop",
+        &["contact_suppression", "first_depleted"],
+    ),
+    (
+        "CgrFirstDepletedHybridParenting",
+        &["contact_suppression", "first_depleted"],
+    ),
+    (
+        "CgrFirstDepletedHybridParentingHop",
+        &["contact_suppression", "first_depleted"],
+    ),
+    (
+        "CgrFirstDepletedContactParenting",
+        &["contact_work_area", "contact_suppression", "first_depleted"],
+    ),
+    (
+        "CgrFirstDepletedContactParentingHop",
+        &["contact_work_area", "contact_suppression", "first_depleted"],
+    ),
+    ("CgrSabrCompliant", &["contact_suppression"]),
+];
+
+/// An error of `build_generic_router`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouterBuildError {
+    /// No router has this name.
+    UnknownRouter(String),
+    /// The router requires features that were not compiled.
+    NotCompiled {
+        /// The name of the router.
+        router_type: String,
+        /// The features missing.
+        missing_features: Vec<&'static str>,
+    },
+    /// The Spsn routers require `SpsnOptions`.
+    MissingSpsnOptions(String),
+}
+
+impl fmt::Display for RouterBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRouter(router_type) => {
+                write!(f, "Router type \"{}\" is unknown", router_type)
+            }
+            Self::NotCompiled {
+                router_type,
+                missing_features,
+            } => write!(
+                f,
+                "Router type \"{}\" is not compiled (missing features: {})",
+                router_type,
+                missing_features.join(", ")
+            ),
+            Self::MissingSpsnOptions(router_type) => {
+                write!(f, "Router type \"{}\" requires Spsn options", router_type)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RouterBuildError {}
+
+/// Keeps the `?` operator working in the functions returning `Result<_, String>`.
+impl From<RouterBuildError> for String {
+    fn from(error: RouterBuildError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Checks that a router type of `build_generic_router` is known and compiled.
+///
+/// # Parameters
+///
+/// * `router_type` - The name of the router.
+/// * `with_spsn_options` - Whether `SpsnOptions` are provided.
+///
+/// # Returns
+///
+/// * `Result<(), RouterBuildError>` - The reason why the router cannot be built, if any.
+pub fn check_router_type(
+    router_type: &str,
+    with_spsn_options: bool,
+) -> Result<(), RouterBuildError> {
+    let Some((_, required)) = ROUTER_TYPES.iter().find(|(name, _)| *name == router_type) else {
+        return Err(RouterBuildError::UnknownRouter(router_type.into()));
+    };
+    let missing_features: Vec<&'static str> = required
+        .iter()
+        .copied()
+        .filter(|feature| feature_enabled(feature) != Some(true))
+        .collect();
+    if !missing_features.is_empty() {
+        return Err(RouterBuildError::NotCompiled {
+            router_type: router_type.into(),
+            missing_features,
+        });
+    }
+    if router_type.starts_with("Spsn") && !with_spsn_options {
+        return Err(RouterBuildError::MissingSpsnOptions(router_type.into()));
+    }
+    Ok(())
+}
+
+/// The compiled features and the router types available, see `capabilities`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct Capabilities {
+    /// The features compiled.
+    pub features: Vec<&'static str>,
+    /// The router types of `build_generic_router` compiled.
+    pub router_types: Vec<&'static str>,
+    /// The router types of `build_generic_router` not compiled, with the features missing.
+    pub unavailable_router_types: Vec<(&'static str, Vec<&'static str>)>,
+}
+
+/// Reports the features compiled and the router types available at runtime, e.g. for the
+/// applications selecting the router by name.
+///
+/// # Returns
+///
+/// * `Capabilities` - The report.
+pub fn capabilities() -> Capabilities {
+    let features = FEATURES
+        .iter()
+        .copied()
+        .filter(|feature| feature_enabled(feature) == Some(true))
+        .collect();
+    let mut router_types = Vec::new();
+    let mut unavailable_router_types = Vec::new();
+    for (router_type, _) in ROUTER_TYPES {
+        match check_router_type(router_type, true) {
+            Err(RouterBuildError::NotCompiled {
+                missing_features, ..
+            }) => unavailable_router_types.push((*router_type, missing_features)),
+            _ => router_types.push(*router_type),
+        }
+    }
+    Capabilities {
+        features,
+        router_types,
+        unavailable_router_types,
+    }
+}
+
+/// Builds a router by name (e.g. "SpsnNodeParenting", "CgrFirstEndingHybridParentingHop"), see
+/// `capabilities` for the names available.
+///
+/// # Parameters
+///
+/// * `router_type` - The name of the router.
+/// * `nodes` - The nodes of the contact plan.
+/// * `contacts` - The contacts of the contact plan.
+/// * `spsn_options` - The options of the Spsn routers, required for them.
+///
+/// # Returns
+///
+/// * `Result<Box<dyn Router<NM, CM>>, RouterBuildError>` - The router, or the reason why it cannot
+///   be built (e.g. a feature not compiled).
 pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'static>(
     router_type: &str,
     nodes: Vec<Node<NM>>,
     contacts: Vec<Contact<NM, CM>>,
     spsn_options: Option<SpsnOptions>,
-) -> Box<dyn Router<NM, CM>> {
-    if let Some(options) = spsn_options {
-        let check_size = options.check_size;
-        let check_priority = options.check_priority;
-        let max_entries = options.max_entries;
+) -> Result<Box<dyn Router<NM, CM>>, RouterBuildError> {
+    build_generic_router_with_storages(
+        router_type,
+        nodes,
+        contacts,
+        spsn_options,
+        &StorageFactories::new(),
+    )
+}
+
+/// Builds a router like `build_generic_router`, with the storages created by the factories.
+///
+/// # Parameters
+///
+/// * `router_type` - The name of the router, see `build_generic_router`.
+/// * `nodes` - The nodes of the contact plan.
+/// * `contacts` - The contacts of the contact plan.
+/// * `spsn_options` - The options of the Spsn routers.
+/// * `storages` - The factories of the storages.
+///
+/// # Returns
+///
+/// * `Result<Box<dyn Router<NM, CM>>, RouterBuildError>` - The router, or the reason why it cannot
+///   be built.
+pub fn build_generic_router_with_storages<
+    NM: NodeManager + 'static,
+    CM: ContactManager + 'static,
+>(
+    router_type: &str,
+    nodes: Vec<Node<NM>>,
+    contacts: Vec<Contact<NM, CM>>,
+    spsn_options: Option<SpsnOptions>,
+    storages: &StorageFactories<NM, CM>,
+) -> Result<Box<dyn Router<NM, CM>>, RouterBuildError> {
+    build_generic_router_with_options(
+        router_type,
+        nodes,
+        contacts,
+        spsn_options,
+        storages,
+        &RouterOptions::default(),
+    )
+}
+
+/// Builds a router like `build_generic_router_with_storages`, with the guard and the booking
+/// horizon of the options.
+///
+/// # Parameters
+///
+/// * `router_type` - The name of the router, see `build_generic_router`.
+/// * `nodes` - The nodes of the contact plan.
+/// * `contacts` - The contacts of the contact plan.
+/// * `spsn_options` - The options of the Spsn routers.
+/// * `storages` - The factories of the storages.
+/// * `options` - The settings of the router.
+///
+/// # Returns
+///
+/// * `Result<Box<dyn Router<NM, CM>>, RouterBuildError>` - The router, or the reason why it cannot
+///   be built.
+pub fn build_generic_router_with_options<
+    NM: NodeManager + 'static,
+    CM: ContactManager + 'static,
+>(
+    router_type: &str,
+    nodes: Vec<Node<NM>>,
+    contacts: Vec<Contact<NM, CM>>,
+    spsn_options: Option<SpsnOptions>,
+    storages: &StorageFactories<NM, CM>,
+    options: &RouterOptions,
+) -> Result<Box<dyn Router<NM, CM>>, RouterBuildError> {
+    check_router_type(router_type, spsn_options.is_some())?;
+
+    if let Some(spsn) = spsn_options {
+        let check_size = spsn.check_size;
+        let check_priority = spsn.check_priority;
+        let max_entries = spsn.max_entries;
 
         register_spsn_router!(
             SpsnNodeParenting,
@@ -189,7 +604,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
         );
 
         register_spsn_router!(
@@ -200,7 +617,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
         );
 
         register_spsn_router!(
@@ -211,7 +630,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
         );
 
         register_spsn_router!(
@@ -222,7 +643,22 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
+        );
+
+        register_spsn_router!(
+            SpsnAdaptiveParenting,
+            "SpsnAdaptiveParenting",
+            router_type,
+            nodes,
+            contacts,
+            check_size,
+            check_priority,
+            max_entries,
+            storages,
+            options
         );
 
         #[cfg(feature = "contact_work_area")]
@@ -234,7 +670,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
         );
 
         #[cfg(feature = "contact_work_area")]
@@ -246,7 +684,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             contacts,
             check_size,
             check_priority,
-            max_entries
+            max_entries,
+            storages,
+            options
         );
     }
 
@@ -255,7 +695,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrNodeParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     register_cgr_router!(
@@ -263,7 +705,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrHybridParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     register_cgr_router!(
@@ -271,7 +715,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrHybridParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     register_cgr_router!(
@@ -279,7 +725,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrNodeParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_work_area")]
@@ -288,7 +736,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrContactParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_work_area")]
@@ -297,7 +747,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "VolCgrContactParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_suppression")]
@@ -306,7 +758,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingHybridParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_suppression")]
@@ -315,7 +769,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingHybridParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_suppression")]
@@ -324,7 +780,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingNodeParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(feature = "contact_suppression")]
@@ -333,7 +791,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingNodeParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_work_area", feature = "contact_suppression"))]
@@ -342,7 +802,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingContactParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_work_area", feature = "contact_suppression"))]
@@ -351,7 +813,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstEndingContactParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_suppression", feature = "first_depleted"))]
@@ -360,7 +824,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedHybridParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_suppression", feature = "first_depleted"))]
@@ -369,7 +835,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedHybridParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_suppression", feature = "first_depleted"))]
@@ -378,7 +846,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedNodeParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(feature = "contact_suppression", feature = "first_depleted"))]
@@ -387,7 +857,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedNodeParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(
@@ -400,7 +872,9 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedContactParentingHop",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
     #[cfg(all(
@@ -413,11 +887,31 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         "CgrFirstDepletedContactParenting",
         router_type,
         nodes,
-        contacts
+        contacts,
+        storages,
+        options
     );
 
-    panic!(
-        "Router type \"{}\" is invalid! (check for typo, disabled feature, or missing options for Spsn algos)",
-        &router_type
-    );
+    #[cfg(feature = "contact_suppression")]
+    if router_type == "CgrSabrCompliant" {
+        if let Some(factory) = &storages.route_storage {
+            let storage = Rc::new(RefCell::new(factory()));
+            let router = CgrSabrCompliant::<NM, CM, BoxedRouteStorage<NM, CM>>::sabr_compliant(
+                nodes, contacts, storage,
+            );
+            return Ok(Box::new(match options.booking_horizon {
+                Some(horizon) => router.with_booking_horizon(horizon),
+                None => router,
+            }));
+        }
+        let routing_table = Rc::new(RefCell::new(RoutingTable::new()));
+        let router = CgrSabrCompliant::<NM, CM>::sabr_compliant(nodes, contacts, routing_table);
+        return Ok(Box::new(match options.booking_horizon {
+            Some(horizon) => router.with_booking_horizon(horizon),
+            None => router,
+        }));
+    }
+
+    // unreachable: the router type was validated
+    Err(RouterBuildError::UnknownRouter(router_type.into()))
 }
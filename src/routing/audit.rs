@@ -0,0 +1,475 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    time::Instant,
+};
+
+use alloc::{
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::cell::RefCell;
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    pathfinding::PathfindingStats,
+    route_storage::RouteSignature,
+    types::{time_to_f64, volume_to_f64, Date, NodeID, Priority, Volume},
+};
+
+use super::{
+    committed::CommittedTx, congestion::CongestionEstimate, gossip::StateSummary,
+    warm_up::WarmUpBudget, NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
+
+/// The FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv_write(hash: &mut u64, bytes: &[u8]) {
+    for byte in bytes {
+        *hash ^= *byte as u64;
+        *hash = hash.wrapping_mul(FNV_PRIME);
+    }
+}
+
+/// Computes a stable hash (FNV-1a) of the inputs of a routing call, to match the records of the
+/// same request across runs and hosts.
+///
+/// # Parameters
+///
+/// * `source` - The source node ID.
+/// * `bundle` - The bundle.
+/// * `curr_time` - The current time.
+/// * `excluded_nodes` - The excluded nodes.
+///
+/// # Returns
+///
+/// * `u64` - The hash of the inputs.
+pub fn inputs_hash(
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+    excluded_nodes: &[NodeID],
+) -> u64 {
+    let mut hash = FNV_OFFSET;
+    fnv_write(&mut hash, &source.to_le_bytes());
+    fnv_write(&mut hash, &time_to_f64(curr_time).to_bits().to_le_bytes());
+    fnv_write(&mut hash, &(bundle.destinations.len() as u64).to_le_bytes());
+    for dest in &bundle.destinations {
+        fnv_write(&mut hash, &dest.to_le_bytes());
+    }
+    fnv_write(&mut hash, &bundle.priority.to_le_bytes());
+    fnv_write(
+        &mut hash,
+        &volume_to_f64(bundle.size).to_bits().to_le_bytes(),
+    );
+    fnv_write(
+        &mut hash,
+        &time_to_f64(bundle.expiration).to_bits().to_le_bytes(),
+    );
+    fnv_write(&mut hash, &[bundle.critical as u8]);
+    // the hash of the bundles without soft deadline is unchanged
+    if let Some(deadline) = bundle.soft_deadline {
+        fnv_write(&mut hash, &time_to_f64(deadline).to_bits().to_le_bytes());
+    }
+    for (dest, priority) in &bundle.destination_priorities {
+        fnv_write(&mut hash, &dest.to_le_bytes());
+        fnv_write(&mut hash, &priority.to_le_bytes());
+    }
+    for node in excluded_nodes {
+        fnv_write(&mut hash, &node.to_le_bytes());
+    }
+    hash
+}
+
+/// The record of a routing call, see `AuditedRouter`.
+///
+/// The record holds the inputs of the call, so that the decisions can be replayed (see
+/// `AuditLog::replay`), and the routes chosen as signatures (see `RouteSignature`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RoutingAuditRecord {
+    /// The rank of the call since the creation of the log, the oldest records being dropped.
+    pub sequence: u64,
+    /// The hash of the inputs, see `inputs_hash`.
+    pub inputs_hash: u64,
+    /// The current time of the call.
+    pub curr_time: Date,
+    /// The source node.
+    pub source: NodeID,
+    /// The destinations of the bundle.
+    pub destinations: Vec<NodeID>,
+    /// The priority of the bundle.
+    pub priority: Priority,
+    /// The size of the bundle.
+    pub size: Volume,
+    /// The expiration of the bundle.
+    pub expiration: Date,
+    /// The soft deadline of the bundle.
+    pub soft_deadline: Option<Date>,
+    /// Whether the bundle is critical.
+    pub critical: bool,
+    /// The per-destination priorities of the bundle.
+    pub destination_priorities: Vec<(NodeID, Priority)>,
+    /// The nodes excluded by the call.
+    pub excluded_nodes: Vec<NodeID>,
+    /// The routes chosen, as (destination, signature) pairs sorted by destination, empty if the
+    /// routing failed.
+    pub routes: Vec<(NodeID, RouteSignature)>,
+    /// The count of first hops of the output, several first hops being alternatives for the
+    /// critical bundles (or branches for the multicast bundles).
+    pub alternatives: usize,
+    /// The duration of the call, in nanoseconds.
+    pub compute_ns: u64,
+}
+
+impl RoutingAuditRecord {
+    /// Rebuilds the bundle of the call.
+    pub fn bundle(&self) -> Bundle {
+        Bundle {
+            source: self.source,
+            destinations: self.destinations.clone(),
+            priority: self.priority,
+            size: self.size,
+            expiration: self.expiration,
+            soft_deadline: self.soft_deadline,
+            critical: self.critical,
+            destination_priorities: self.destination_priorities.clone(),
+        }
+    }
+}
+
+/// Extracts the routes chosen by a routing call, sorted by destination.
+fn chosen_routes<NM: NodeManager, CM: ContactManager>(
+    output: Option<&RoutingOutput<NM, CM>>,
+) -> (Vec<(NodeID, RouteSignature)>, usize) {
+    let Some(output) = output else {
+        return (Vec::new(), 0);
+    };
+    let mut routes: Vec<(NodeID, RouteSignature)> = output
+        .first_hops
+        .values()
+        .flat_map(|(_contact, routes)| routes)
+        .map(|route| (route.borrow().to_node, RouteSignature::from_stage(route)))
+        .collect();
+    routes.sort();
+    (routes, output.first_hops.len())
+}
+
+/// A bounded log of routing calls (a ring buffer), for the post-incident analysis of the routing
+/// decisions.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct AuditLog {
+    /// The maximum count of records, the oldest ones being dropped first.
+    capacity: usize,
+    /// The records, from the oldest to the newest.
+    records: VecDeque<RoutingAuditRecord>,
+    /// The sequence of the next record.
+    next_sequence: u64,
+}
+
+impl AuditLog {
+    /// Creates an empty log.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The maximum count of records kept.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `AuditLog`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+            next_sequence: 0,
+        }
+    }
+
+    /// Appends a record, its sequence being assigned by the log.
+    pub fn push(&mut self, mut record: RoutingAuditRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        record.sequence = self.next_sequence;
+        self.next_sequence += 1;
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Lists the records, from the oldest to the newest.
+    pub fn records(&self) -> impl Iterator<Item = &RoutingAuditRecord> + '_ {
+        self.records.iter()
+    }
+
+    /// Retrieves the count of records kept.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Checks whether the log holds no record.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Retrieves the count of records dropped, by the capacity or by `clear`.
+    pub fn dropped(&self) -> u64 {
+        self.next_sequence - self.records.len() as u64
+    }
+
+    /// Removes all the records, the sequences are not reset.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Writes the records as JSON lines (one record per line), from the oldest to the newest.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` - The destination of the records.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - An error message if the writing fails.
+    pub fn write_to(&self, writer: &mut dyn Write) -> Result<(), String> {
+        for record in &self.records {
+            let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+            writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())
+    }
+
+    /// Dumps the records to a file, as JSON lines (see `write_to`).
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the file, replaced if it exists.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - An error message if the file cannot be written.
+    pub fn dump(&self, path: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Unable to create {}: {}", path, e))?;
+        self.write_to(&mut BufWriter::new(file))
+    }
+
+    /// Replays the recorded calls against a router, in the order of the log, and compares the
+    /// routes chosen.
+    ///
+    /// The decisions depend on the resources consumed by the previous calls: use a router in the
+    /// state of the first record (e.g. a fresh router if no record was dropped).
+    ///
+    /// # Parameters
+    ///
+    /// * `router` - The router replaying the calls.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u64>` - The sequences of the records whose routes differ in the replay.
+    pub fn replay<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+        &self,
+        router: &mut R,
+    ) -> Vec<u64> {
+        let mut diverging = Vec::new();
+        for record in &self.records {
+            let output = router.route(
+                record.source,
+                &record.bundle(),
+                record.curr_time,
+                &record.excluded_nodes,
+            );
+            let (routes, _) = chosen_routes(output.as_ref());
+            if routes != record.routes {
+                diverging.push(record.sequence);
+            }
+        }
+        diverging
+    }
+}
+
+/// A router recording each routing call into an `AuditLog`.
+///
+/// The `route` and `route_with_backup` calls are recorded (the primary routing only for the
+/// latter), the estimations are not.
+pub struct AuditedRouter<NM: NodeManager, CM: ContactManager> {
+    /// The audited router.
+    inner: Box<dyn Router<NM, CM>>,
+    /// The records of the calls.
+    log: AuditLog,
+}
+
+impl<NM: NodeManager, CM: ContactManager> AuditedRouter<NM, CM> {
+    /// Wraps a router.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The router to audit.
+    /// * `capacity` - The maximum count of records kept.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `AuditedRouter`.
+    pub fn new(inner: Box<dyn Router<NM, CM>>, capacity: usize) -> Self {
+        Self {
+            inner,
+            log: AuditLog::new(capacity),
+        }
+    }
+
+    /// Retrieves the log of the calls.
+    pub fn log(&self) -> &AuditLog {
+        &self.log
+    }
+
+    /// Retrieves the log of the calls, e.g. to clear it.
+    pub fn log_mut(&mut self) -> &mut AuditLog {
+        &mut self.log
+    }
+
+    /// Returns the underlying router.
+    pub fn into_inner(self) -> Box<dyn Router<NM, CM>> {
+        self.inner
+    }
+
+    fn record(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        output: Option<&RoutingOutput<NM, CM>>,
+        start: Instant,
+    ) {
+        let compute_ns = start.elapsed().as_nanos() as u64;
+        let (routes, alternatives) = chosen_routes(output);
+        self.log.push(RoutingAuditRecord {
+            sequence: 0,
+            inputs_hash: inputs_hash(source, bundle, curr_time, excluded_nodes),
+            curr_time,
+            source,
+            destinations: bundle.destinations.clone(),
+            priority: bundle.priority,
+            size: bundle.size,
+            expiration: bundle.expiration,
+            soft_deadline: bundle.soft_deadline,
+            critical: bundle.critical,
+            destination_priorities: bundle.destination_priorities.clone(),
+            excluded_nodes: excluded_nodes.to_vec(),
+            routes,
+            alternatives,
+            compute_ns,
+        });
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> Router<NM, CM> for AuditedRouter<NM, CM> {
+    fn route(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        let start = Instant::now();
+        let output = self.inner.route(source, bundle, curr_time, excluded_nodes);
+        self.record(
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+            output.as_ref(),
+            start,
+        );
+        output
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        self.inner
+            .estimate(source, bundle, curr_time, excluded_nodes)
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        let start = Instant::now();
+        let output = self
+            .inner
+            .route_with_backup(source, bundle, curr_time, excluded_nodes);
+        self.record(
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+            output.as_ref().map(|output| &output.primary),
+            start,
+        );
+        output
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.inner.resolve(name)
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        self.inner.set_neighbor_state(node, state, until)
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.inner.advance_time(now)
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        self.inner.multigraph()
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.inner.pathfinding_stats()
+    }
+    fn export_state_summary(&self, curr_time: Date) -> Option<StateSummary> {
+        self.inner.export_state_summary(curr_time)
+    }
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        self.inner.merge_state_summary(summary, curr_time)
+    }
+    fn import_committed(&mut self, schedules: &[CommittedTx]) -> usize {
+        self.inner.import_committed(schedules)
+    }
+    fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        self.inner.expand_periodic(horizon)
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        self.inner.warm_up(sources, destinations, times, budget)
+    }
+    fn congestion(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        self.inner
+            .congestion(source, bundle, curr_time, excluded_nodes)
+    }
+}
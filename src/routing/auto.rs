@@ -0,0 +1,246 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::ContactManager,
+    node::Node,
+    node_manager::NodeManager,
+    types::{Date, HashMap, HashSet, NodeID},
+};
+
+use super::dyn_router::{
+    DistanceKind, DynRouter, DynRouterBuilder, ParentingKind, RouterKind, StorageConfig,
+    SuppressionKind,
+};
+
+/// The characteristics of a contact plan driving the choice of the pathfinding variant.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default)]
+pub struct PlanProfile {
+    /// The count of nodes.
+    pub node_count: usize,
+    /// The count of contacts (loopback contacts excluded).
+    pub contact_count: usize,
+    /// The count of links (pairs of transmitting and receiving nodes with at least one contact).
+    pub link_count: usize,
+    /// The mean count of contacts per link, i.e. the parallelism of the multigraph edges.
+    pub contacts_per_link: f64,
+    /// The mean count of links per transmitting node, i.e. the branching of the multigraph.
+    pub branching: f64,
+}
+
+impl PlanProfile {
+    /// Computes the profile of a contact plan.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the plan.
+    /// * `contacts` - The contacts of the plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The profile.
+    pub fn from_plan<NM: NodeManager, CM: ContactManager>(
+        nodes: &[Node<NM>],
+        contacts: &[Contact<NM, CM>],
+    ) -> Self {
+        let mut links: HashSet<(NodeID, NodeID)> = HashSet::new();
+        let mut senders: HashMap<NodeID, usize> = HashMap::new();
+        let mut contact_count = 0;
+        for contact in contacts {
+            let (tx, rx) = (contact.get_tx_node(), contact.get_rx_node());
+            if tx == rx {
+                continue;
+            }
+            contact_count += 1;
+            if links.insert((tx, rx)) {
+                *senders.entry(tx).or_insert(0) += 1;
+            }
+        }
+        let link_count = links.len();
+        Self {
+            node_count: nodes.len(),
+            contact_count,
+            link_count,
+            contacts_per_link: if link_count > 0 {
+                contact_count as f64 / link_count as f64
+            } else {
+                0.0
+            },
+            branching: if senders.is_empty() {
+                0.0
+            } else {
+                link_count as f64 / senders.len() as f64
+            },
+        }
+    }
+
+    /// Recommends the pathfinding variant likely to be the fastest for the plan.
+    ///
+    /// The node parenting explores the receivers of each node once, the other variants pay for
+    /// the parallel contacts of the links: node parenting is recommended for nearly simple graphs
+    /// (few contacts per link). The contact parenting keeps a work area per contact, which pays
+    /// off for the plans with both many parallel contacts and a wide branching, if the
+    /// "contact_work_area" feature is compiled. The hybrid parenting is recommended otherwise.
+    /// The recommendation is a heuristic, `benchmark_parenting` measures the actual timings.
+    ///
+    /// # Returns
+    ///
+    /// * `ParentingKind` - The recommended variant.
+    pub fn recommend_parenting(&self) -> ParentingKind {
+        if self.contacts_per_link <= 2.0 {
+            return ParentingKind::NodeParenting;
+        }
+        if cfg!(feature = "contact_work_area")
+            && self.contacts_per_link >= 32.0
+            && self.branching >= 8.0
+        {
+            return ParentingKind::ContactParenting;
+        }
+        ParentingKind::HybridParenting
+    }
+}
+
+/// The options of `build_auto_router`, the pathfinding variant being selected automatically.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct AutoRouterOptions {
+    /// The routing mainframe, Spsn by default.
+    pub kind: RouterKind,
+    /// The suppression strategy, mandatory for Cgr, forbidden otherwise.
+    pub suppression: Option<SuppressionKind>,
+    /// The distance, SABR by default.
+    pub distance: DistanceKind,
+    /// The storage parameters, only used by Spsn.
+    pub storage: StorageConfig,
+}
+
+impl Default for AutoRouterOptions {
+    fn default() -> Self {
+        Self {
+            kind: RouterKind::Spsn,
+            suppression: None,
+            distance: DistanceKind::default(),
+            storage: StorageConfig::default(),
+        }
+    }
+}
+
+impl AutoRouterOptions {
+    /// Creates the builder of a `DynRouter` with these options and a pathfinding variant.
+    fn builder<NM: NodeManager, CM: ContactManager>(
+        &self,
+        parenting: ParentingKind,
+    ) -> DynRouterBuilder {
+        let mut builder = DynRouter::<NM, CM>::builder(self.kind)
+            .parenting(parenting)
+            .distance(self.distance)
+            .storage(self.storage.clone());
+        if let Some(suppression) = self.suppression {
+            builder = builder.suppression(suppression);
+        }
+        builder
+    }
+}
+
+/// Builds a router with the pathfinding variant recommended for the plan (see
+/// `PlanProfile::recommend_parenting`).
+///
+/// # Parameters
+///
+/// * `nodes` - The nodes of the contact plan.
+/// * `contacts` - The contacts of the contact plan.
+/// * `options` - The other choices of the router.
+///
+/// # Returns
+///
+/// * `Result<DynRouter<NM, CM>, String>` - The router (its `parenting()` tells the variant
+///   selected), or an error message if the options are inconsistent.
+pub fn build_auto_router<NM: NodeManager + 'static, CM: ContactManager + 'static>(
+    nodes: Vec<Node<NM>>,
+    contacts: Vec<Contact<NM, CM>>,
+    options: &AutoRouterOptions,
+) -> Result<DynRouter<NM, CM>, String> {
+    let parenting = PlanProfile::from_plan(&nodes, &contacts).recommend_parenting();
+    options.builder::<NM, CM>(parenting).build(nodes, contacts)
+}
+
+/// A routing request timed by `benchmark_parenting`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct BenchmarkProbe {
+    /// The source node.
+    pub source: NodeID,
+    /// The bundle to route.
+    pub bundle: Bundle,
+    /// The current time of the routing.
+    pub curr_time: Date,
+}
+
+/// Times the pathfinding variants on a plan, for the cases where the heuristic of
+/// `build_auto_router` is not trusted.
+///
+/// For each variant available, a fresh router is built from `plan` and the probes are routed in
+/// sequence, the construction of the router being excluded from the timing. The variants whose
+/// feature is not compiled are skipped.
+///
+/// # Parameters
+///
+/// * `plan` - Creates the nodes and contacts of the plan (called once per variant).
+/// * `options` - The other choices of the router.
+/// * `probes` - The routing requests to time.
+///
+/// # Returns
+///
+/// * `Result<Vec<(ParentingKind, std::time::Duration)>, String>` - The variants with their
+///   timings, from the fastest to the slowest, or an error message if the options are
+///   inconsistent.
+#[cfg(feature = "std")]
+pub fn benchmark_parenting<NM, CM, F>(
+    mut plan: F,
+    options: &AutoRouterOptions,
+    probes: &[BenchmarkProbe],
+) -> Result<Vec<(ParentingKind, std::time::Duration)>, String>
+where
+    NM: NodeManager + 'static,
+    CM: ContactManager + 'static,
+    F: FnMut() -> (Vec<Node<NM>>, Vec<Contact<NM, CM>>),
+{
+    use super::Router;
+
+    let mut variants = Vec::from([ParentingKind::NodeParenting, ParentingKind::HybridParenting]);
+    if cfg!(feature = "contact_work_area") {
+        variants.push(ParentingKind::ContactParenting);
+    }
+
+    let mut timings = Vec::with_capacity(variants.len());
+    for parenting in variants {
+        let (nodes, contacts) = plan();
+        let mut router = options
+            .builder::<NM, CM>(parenting)
+            .build(nodes, contacts)?;
+        let start = std::time::Instant::now();
+        for probe in probes {
+            router.route(probe.source, &probe.bundle, probe.curr_time, &Vec::new());
+        }
+        timings.push((parenting, start.elapsed()));
+    }
+    timings.sort_by_key(|(_, duration)| *duration);
+    Ok(timings)
+}
+
+/// Formats a profile on a single line, e.g. for logs.
+impl core::fmt::Display for PlanProfile {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} contacts over {} links ({:.1} contacts per link, branching {:.1})",
+            self.node_count,
+            self.contact_count,
+            self.link_count,
+            self.contacts_per_link,
+            self.branching
+        )
+    }
+}
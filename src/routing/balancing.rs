@@ -0,0 +1,262 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    pathfinding::{PathFindingOutput, Pathfinding},
+    types::{times_match, volume_to_f64, Date, Duration, NodeID},
+};
+
+#[cfg(feature = "contact_suppression")]
+use crate::contact::Contact;
+
+use super::{dry_run_unicast_tree, schedule_unicast, RoutingOutput, StableHash};
+
+/// The spreading of the unicast bundles over the first hops of equal cost, by a deterministic
+/// hash of the bundles.
+///
+/// The routes of equal cost are the routes reaching the destination at the same time with the
+/// same hop count through different neighbors. Instead of the first route found, each bundle
+/// takes the route of a neighbor selected by a hash of the bundle: the nodes configured alike
+/// spread the same bundles identically, without randomness nor shared state, and a run can be
+/// replayed.
+///
+/// The bundles being identified by their source and their creation time in BP, the hash covers
+/// the source, the destination, the expiration (creation time plus lifetime), the size and the
+/// priority of the bundle.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HashBalancing {
+    /// The seed of the hash, to change the spreading of a deployment.
+    seed: u64,
+    /// The weights of some neighbors, the other neighbors having a weight of 1.
+    weights: Vec<(NodeID, u32)>,
+}
+
+impl HashBalancing {
+    /// Creates a spreading without weights.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the hash, shared by the nodes to spread the bundles alike.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `HashBalancing`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            weights: Vec::new(),
+        }
+    }
+
+    /// Sets the weight of a neighbor, the share of the bundles it receives among the neighbors of
+    /// equal cost being proportional to its weight (a weight of 0 avoids the neighbor unless all
+    /// the neighbors of equal cost have a weight of 0).
+    ///
+    /// # Parameters
+    ///
+    /// * `neighbor` - The neighbor node.
+    /// * `weight` - The weight of the neighbor, 1 by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The spreading with the weight.
+    pub fn with_weight(mut self, neighbor: NodeID, weight: u32) -> Self {
+        match self.weights.iter_mut().find(|(node, _)| *node == neighbor) {
+            Some(entry) => entry.1 = weight,
+            None => self.weights.push((neighbor, weight)),
+        }
+        self
+    }
+
+    /// Retrieves the weight of a neighbor.
+    pub fn weight(&self, neighbor: NodeID) -> u32 {
+        self.weights
+            .iter()
+            .find(|(node, _)| *node == neighbor)
+            .map_or(1, |(_, weight)| *weight)
+    }
+
+    /// Hashes a unicast bundle (FNV-1a with a final mix), the same on every platform.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The unicast bundle.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The hash of the bundle.
+    pub fn hash(&self, bundle: &Bundle) -> u64 {
+        let mut hash = StableHash::new();
+        hash.write(&self.seed.to_le_bytes());
+        hash.write(&bundle.source.to_le_bytes());
+        for dest in &bundle.destinations {
+            hash.write(&dest.to_le_bytes());
+        }
+        hash.write_time(bundle.expiration);
+        hash.write(&volume_to_f64(bundle.size).to_bits().to_le_bytes());
+        hash.write(&[bundle.priority]);
+        hash.finish()
+    }
+
+    /// Selects a neighbor among the neighbors of equal cost for a bundle.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The unicast bundle.
+    /// * `neighbors` - The neighbors of equal cost, in any order.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<NodeID>` - The neighbor selected, `None` if there is no neighbor.
+    pub fn select(&self, bundle: &Bundle, neighbors: &[NodeID]) -> Option<NodeID> {
+        // the order of the discovery must not matter
+        let mut sorted = neighbors.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let total: u64 = sorted
+            .iter()
+            .map(|neighbor| self.weight(*neighbor) as u64)
+            .sum();
+        if total == 0 {
+            return sorted.first().copied();
+        }
+        let mut pick = self.hash(bundle) % total;
+        for neighbor in sorted {
+            let weight = self.weight(neighbor) as u64;
+            if pick < weight {
+                return Some(neighbor);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+/// Routes a unicast bundle over a route of equal cost selected by a `HashBalancing`.
+///
+/// The best route is found, then its first hop neighbor is discarded and the pathfinding is
+/// repeated while the routes found have the same cost (the same arrival time and hop count), like
+/// `route_critical_unicast`. The route through the neighbor selected by the hash is scheduled.
+/// The route storage of the router is not used.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router (`Pathfinding::peek` is used).
+/// - `balancing`: The spreading of the bundles.
+/// - `source`: The source node ID.
+/// - `bundle`: The unicast `Bundle`.
+/// - `curr_time`: The current time.
+/// - `excluded_nodes`: A sorted list of nodes to exclude from the routing paths.
+/// - `booking_horizon`: How far ahead of `curr_time` the transmissions are booked, without limit
+///   if `None`.
+///
+/// # Returns
+/// The routing output of the route selected, `None` if no route was found.
+pub fn route_balanced_unicast<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    balancing: &HashBalancing,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+    excluded_nodes: &[NodeID],
+    booking_horizon: Option<Duration>,
+) -> Option<RoutingOutput<NM, CM>> {
+    if bundle.destinations.len() != 1 || bundle.destinations[0] == source {
+        return None;
+    }
+    let dest = bundle.destinations[0];
+    #[cfg(not(feature = "contact_suppression"))]
+    let mut exclusions = excluded_nodes.to_vec();
+    #[cfg(feature = "contact_suppression")]
+    let exclusions = excluded_nodes.to_vec();
+    #[cfg(feature = "contact_suppression")]
+    let mut suppressed: Vec<Rc<RefCell<Contact<NM, CM>>>> = Vec::new();
+    // the neighbors of equal cost and their trees, in the order of the discovery
+    let mut neighbors: Vec<NodeID> = Vec::new();
+    let mut trees: Vec<Rc<RefCell<PathFindingOutput<NM, CM>>>> = Vec::new();
+    let mut best = None;
+
+    loop {
+        let tree = Rc::new(RefCell::new(pathfinding.peek(
+            curr_time,
+            source,
+            bundle,
+            &exclusions,
+        )));
+        let Some(route) = dry_run_unicast_tree(bundle, curr_time, tree.clone(), true) else {
+            break;
+        };
+        let cost = (route.borrow().at_time, route.borrow().hop_count);
+        match best {
+            None => best = Some(cost),
+            Some((arrival, hop_count)) => {
+                if !times_match(cost.0, arrival) || cost.1 != hop_count {
+                    break;
+                }
+            }
+        }
+        let Some(neighbor) = tree
+            .borrow()
+            .get_source_route()
+            .borrow()
+            .next_for_destination
+            .get(&dest)
+            .and_then(|next| next.borrow().get_via_contact())
+            .map(|contact| contact.borrow().get_rx_node())
+        else {
+            break;
+        };
+        neighbors.push(neighbor);
+        trees.push(tree);
+
+        #[cfg(feature = "contact_suppression")]
+        {
+            let multigraph = pathfinding.get_multigraph();
+            let graph = multigraph.borrow();
+            let Some(sender) = graph.senders.get(source as usize) else {
+                break;
+            };
+            for receiver in &sender.receivers {
+                if receiver.node.borrow().info.id != neighbor {
+                    continue;
+                }
+                for contact in &receiver.contacts_to_receiver {
+                    if !contact.borrow().suppressed {
+                        contact.borrow_mut().suppressed = true;
+                        suppressed.push(contact.clone());
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "contact_suppression"))]
+        {
+            if neighbor == dest {
+                break;
+            }
+            if let Err(pos) = exclusions.binary_search(&neighbor) {
+                exclusions.insert(pos, neighbor);
+            }
+        }
+    }
+
+    #[cfg(feature = "contact_suppression")]
+    for contact in suppressed {
+        contact.borrow_mut().suppressed = false;
+    }
+
+    let selected = balancing.select(bundle, &neighbors)?;
+    let idx = neighbors
+        .iter()
+        .position(|neighbor| *neighbor == selected)?;
+    Some(schedule_unicast(
+        bundle,
+        curr_time,
+        trees.swap_remove(idx),
+        true,
+        booking_horizon,
+    ))
+}
@@ -5,20 +5,72 @@ use crate::{
     multigraph::Multigraph,
     node::Node,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{Pathfinding, PathfindingLimits, PathfindingStats},
     route_stage::RouteStage,
     route_storage::{Route, RouteStorage},
-    types::{Date, NodeID},
+    types::{Date, Duration, HopCount, NodeID},
 };
 
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData};
 
-use super::{dry_run_unicast_path, schedule_unicast_path, Router, RoutingOutput};
+use super::{
+    backup_unicast,
+    balancing::{route_balanced_unicast, HashBalancing},
+    dry_run_unicast_path, estimate_unicast, refresh_routes, resolve_destinations,
+    route_critical_unicast, route_span, schedule_unicast_path, update_neighbor_state,
+    warm_up::{warm_up_routes, WarmUpBudget, WarmUpClock},
+    NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
+
+/// The compute budget of the anytime mode of `Cgr`.
+///
+/// The budget bounds the number of alternative routes enumerated (via contact suppression) per
+/// routing call. When it expires, the best route found so far is scheduled.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default)]
+pub struct CgrBudget {
+    /// The maximum count of pathfinding runs per routing call (unbounded if `None`), at least one
+    /// run is performed.
+    pub max_routes: Option<usize>,
+    /// The maximum time spent in pathfinding per routing call (unbounded if `None`).
+    #[cfg(feature = "std")]
+    pub max_time: Option<std::time::Duration>,
+}
+
+impl CgrBudget {
+    /// Creates a budget bounding the count of pathfinding runs per routing call, at least one run
+    /// (a budget of 0 is raised to 1, the first route being always computed).
+    pub fn routes(max_routes: usize) -> Self {
+        Self {
+            max_routes: Some(max_routes.max(1)),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a budget bounding the time spent in pathfinding per routing call.
+    #[cfg(feature = "std")]
+    pub fn time(max_time: std::time::Duration) -> Self {
+        Self {
+            max_time: Some(max_time),
+            ..Default::default()
+        }
+    }
+}
 
 pub struct Cgr<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorage<NM, CM>>
 {
     route_storage: Rc<RefCell<S>>,
     pathfinding: P,
+    /// The budget of the anytime mode, the first feasible route is selected if `None`.
+    budget: Option<CgrBudget>,
+    /// Whether all the candidate routes are computed before the selection (SABR compliance mode).
+    exhaustive: bool,
+    /// The spreading of the unicast bundles over the routes of equal cost, the first route found
+    /// is selected if `None`.
+    load_balancing: Option<HashBalancing>,
+    /// How far ahead of the routing time the transmissions are booked, without limit if `None`.
+    booking_horizon: Option<Duration>,
 
     // for compilation
     #[doc(hidden)]
@@ -37,15 +89,133 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
         curr_time: Date,
         excluded_nodes: &Vec<NodeID>,
     ) -> Option<RoutingOutput<NM, CM>> {
+        let _span = route_span("cgr", source, bundle, curr_time);
+        // the routes computed while a neighbor was down may no longer be the best
+        let multigraph = self.pathfinding.get_multigraph();
+        if multigraph.borrow_mut().expire_down_nodes(curr_time) {
+            self.route_storage.borrow_mut().invalidate(None);
+        }
+        let resolved = resolve_destinations(&multigraph, bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
         if bundle.expiration < curr_time {
             return None;
         }
 
         if bundle.destinations.len() == 1 {
+            if bundle.critical {
+                return route_critical_unicast(
+                    &mut self.pathfinding,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
+            if let Some(balancing) = &self.load_balancing {
+                return route_balanced_unicast(
+                    &mut self.pathfinding,
+                    balancing,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
+            // the aged routes are recomputed before the selection
+            self.refresh(source, curr_time);
+            if self.exhaustive {
+                return self.route_unicast_exhaustive(source, bundle, curr_time, excluded_nodes);
+            }
+            if let Some(budget) = self.budget {
+                return self.route_unicast_anytime(
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    budget,
+                );
+            }
             return self.route_unicast(source, bundle, curr_time, excluded_nodes);
         }
 
-        todo!();
+        // multicast is not supported by the path-based routers
+        None
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        estimate_unicast(
+            &mut self.pathfinding,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        )
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        let primary = self.route(source, bundle, curr_time, excluded_nodes)?;
+        let backup = backup_unicast(
+            &mut self.pathfinding,
+            &primary,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        );
+        Some(RoutingWithBackup { primary, backup })
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.pathfinding.get_multigraph().borrow().resolve(name)
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        let multigraph = self.pathfinding.get_multigraph();
+        let Some(stale_via) = update_neighbor_state(&multigraph, node, state, until) else {
+            return false;
+        };
+        self.route_storage.borrow_mut().invalidate(stale_via);
+        true
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.pathfinding.get_multigraph().borrow().advance_time(now);
+        true
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.pathfinding.cumulative_stats()
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        warm_up_routes(
+            &mut self.pathfinding,
+            &self.route_storage,
+            sources,
+            destinations,
+            times,
+            &mut WarmUpClock::new(budget),
+        )
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        Some(self.pathfinding.get_multigraph())
     }
 }
 
@@ -56,16 +226,144 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         nodes: Vec<Node<NM>>,
         contacts: Vec<Contact<NM, CM>>,
         route_storage: Rc<RefCell<S>>,
+    ) -> Self {
+        Self::with_budget(nodes, contacts, route_storage, None)
+    }
+
+    /// Creates a new `Cgr` router, in anytime mode if a budget is provided.
+    ///
+    /// In anytime mode, the alternative routes are enumerated until the budget expires or no
+    /// route remains, and the best feasible route (earliest arrival, then fewest hops) is
+    /// scheduled. The route selected from the storage, if any, is the first candidate.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the contact plan.
+    /// * `contacts` - The contacts of the contact plan.
+    /// * `route_storage` - The storage for the routes found.
+    /// * `budget` - The budget of the anytime mode, `None` to select the first feasible route.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `Cgr`.
+    pub fn with_budget(
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+        route_storage: Rc<RefCell<S>>,
+        budget: Option<CgrBudget>,
     ) -> Self {
         Self {
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(nodes, contacts)))),
             route_storage: route_storage.clone(),
+            budget,
+            exhaustive: false,
+            load_balancing: None,
+            booking_horizon: None,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         }
     }
 
+    /// Creates a new `Cgr` router in SABR compliance mode.
+    ///
+    /// In compliance mode, the candidate route list of a destination is completed (the alternative
+    /// routes are enumerated until no route remains) before each selection, then the route storage
+    /// filters the candidates (termination time, capacity, backlog, delivery deadline) and selects
+    /// the best one. Use it with the `SABRCompliant` distance for the SABR selection order, as the
+    /// `CgrSabrCompliant` preset does.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the contact plan.
+    /// * `contacts` - The contacts of the contact plan.
+    /// * `route_storage` - The storage for the candidate routes.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `Cgr`.
+    pub fn sabr_compliant(
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+        route_storage: Rc<RefCell<S>>,
+    ) -> Self {
+        let mut router = Self::new(nodes, contacts, route_storage);
+        router.exhaustive = true;
+        router
+    }
+
+    /// Spreads the unicast bundles over the routes of equal cost by a deterministic hash of the
+    /// bundles (see `HashBalancing`), instead of selecting the first route found.
+    ///
+    /// # Parameters
+    ///
+    /// * `balancing` - The spreading of the bundles.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the load balancing.
+    pub fn with_load_balancing(mut self, balancing: HashBalancing) -> Self {
+        self.load_balancing = Some(balancing);
+        self
+    }
+
+    /// Bounds each pathfinding run of the router, the routes found so far being used when a
+    /// limit is reached (see `PathfindingLimits`).
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The limits of each pathfinding run.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the pathfinding limits.
+    pub fn with_pathfinding_limits(mut self, limits: PathfindingLimits) -> Self {
+        self.pathfinding.set_limits(limits);
+        self
+    }
+
+    /// Limits how far ahead of the routing time the transmissions are booked, leaving the volume
+    /// of the later contacts to later, better informed, routing decisions.
+    ///
+    /// The hops whose transmission starts after `curr_time + horizon` are dry run like the
+    /// others (the route is selected and returned as usual), but are not booked nor listed in
+    /// the hop timings of the routing output.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon` - The booking horizon.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the booking horizon.
+    pub fn with_booking_horizon(mut self, horizon: Duration) -> Self {
+        self.booking_horizon = Some(horizon);
+        self
+    }
+
+    /// Recomputes the routes of the destinations whose refresh was requested by the route
+    /// storage, e.g. after the aging of their routes (see `AgingPolicy`).
+    ///
+    /// Called automatically before each unicast routing, the application can also call it ahead
+    /// of time (e.g. while idle).
+    ///
+    /// # Parameters
+    ///
+    /// * `source` - The local node.
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The count of routes recomputed.
+    pub fn refresh(&mut self, source: NodeID, curr_time: Date) -> usize {
+        refresh_routes(
+            &mut self.pathfinding,
+            &self.route_storage,
+            source,
+            curr_time,
+        )
+    }
+
     fn route_unicast(
         &mut self,
         source: NodeID,
@@ -75,10 +373,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
     ) -> Option<RoutingOutput<NM, CM>> {
         let dest = bundle.destinations[0];
 
-        let mut bundle_to_consider = bundle.clone();
-        // if we are not volume aware, we drop the constraints
-        bundle_to_consider.priority = 1;
-        bundle_to_consider.size = 0.0;
+        let bundle_to_consider = pathfinding_bundle(bundle);
 
         let route_option = self.route_storage.borrow_mut().select(
             bundle,
@@ -92,6 +387,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
                 bundle,
                 curr_time,
                 route.source_stage.clone(),
+                self.booking_horizon,
             ));
         }
 
@@ -103,9 +399,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
 
             if let Some(route) = Route::from_tree(tree, dest) {
                 RouteStage::init_route(route.destination_stage.clone());
-                self.route_storage
-                    .borrow_mut()
-                    .store(&bundle, route.clone());
+                self.route_storage.borrow_mut().store(bundle, route.clone());
                 let dry_run =
                     dry_run_unicast_path(bundle, curr_time, route.source_stage.clone(), true);
                 if let Some(_) = dry_run {
@@ -113,6 +407,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
                         bundle,
                         curr_time,
                         route.source_stage.clone(),
+                        self.booking_horizon,
                     ));
                 }
             } else {
@@ -121,4 +416,149 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         }
         None
     }
+
+    /// Completes the candidate route list of the destination, then selects from the storage.
+    fn route_unicast_exhaustive(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        let dest = bundle.destinations[0];
+        if dest == source {
+            return None;
+        }
+
+        // each alternative route suppresses a contact, bounds the enumeration for other pathfindings
+        let max_routes = self
+            .pathfinding
+            .get_multigraph()
+            .borrow()
+            .get_contact_count()
+            + 1;
+        for _ in 0..max_routes {
+            // the candidates are computed for the bundle (the stages carry it with node_proc)
+            let new_tree = self
+                .pathfinding
+                .get_next(curr_time, source, bundle, excluded_nodes);
+            let tree = Rc::new(RefCell::new(new_tree));
+            let Some(route) = Route::from_tree(tree, dest) else {
+                break;
+            };
+            RouteStage::init_route(route.destination_stage.clone());
+            self.route_storage.borrow_mut().store(bundle, route);
+        }
+
+        let route = self.route_storage.borrow_mut().select(
+            bundle,
+            curr_time,
+            self.pathfinding.get_multigraph().clone(),
+            excluded_nodes,
+        )?;
+        Some(schedule_unicast_path(
+            bundle,
+            curr_time,
+            route.source_stage.clone(),
+            self.booking_horizon,
+        ))
+    }
+
+    fn route_unicast_anytime(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+        budget: CgrBudget,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        let dest = bundle.destinations[0];
+
+        let bundle_to_consider = pathfinding_bundle(bundle);
+
+        // the best source stage found so far, and its (arrival time, hop count) at destination
+        let mut best_stage: Option<Rc<RefCell<RouteStage<NM, CM>>>> = None;
+        let mut best_key = (Date::MAX, HopCount::MAX);
+        let mut consider = |route: &Route<NM, CM>| {
+            if let Some(dest_stage) =
+                dry_run_unicast_path(bundle, curr_time, route.source_stage.clone(), true)
+            {
+                let key = {
+                    let dest_stage = dest_stage.borrow();
+                    (dest_stage.at_time, dest_stage.hop_count)
+                };
+                if best_stage.is_none() || key < best_key {
+                    best_stage = Some(route.source_stage.clone());
+                    best_key = key;
+                }
+            }
+        };
+
+        if let Some(route) = self.route_storage.borrow_mut().select(
+            bundle,
+            curr_time,
+            self.pathfinding.get_multigraph().clone(),
+            excluded_nodes,
+        ) {
+            consider(&route);
+        }
+
+        #[cfg(feature = "std")]
+        let start = std::time::Instant::now();
+        let mut runs = 0;
+
+        loop {
+            if let Some(max_routes) = budget.max_routes {
+                if runs >= max_routes.max(1) {
+                    break;
+                }
+            }
+            #[cfg(feature = "std")]
+            if let Some(max_time) = budget.max_time {
+                if start.elapsed() >= max_time {
+                    break;
+                }
+            }
+            runs += 1;
+
+            let new_tree =
+                self.pathfinding
+                    .get_next(curr_time, source, &bundle_to_consider, excluded_nodes);
+            let tree = Rc::new(RefCell::new(new_tree));
+
+            match Route::from_tree(tree, dest) {
+                Some(route) => {
+                    RouteStage::init_route(route.destination_stage.clone());
+                    self.route_storage.borrow_mut().store(bundle, route.clone());
+                    consider(&route);
+                }
+                None => break,
+            }
+        }
+
+        best_stage.map(|source_stage| {
+            schedule_unicast_path(bundle, curr_time, source_stage, self.booking_horizon)
+        })
+    }
+}
+
+/// Returns the bundle the routes are computed for: if we are not volume aware, we drop the
+/// constraints, but the stages carry the bundle scheduled with node_proc.
+///
+/// # Parameters
+///
+/// * `bundle` - The bundle to route.
+///
+/// # Returns
+///
+/// * `Bundle` - The bundle given to the pathfinding.
+fn pathfinding_bundle(bundle: &Bundle) -> Bundle {
+    #[allow(unused_mut)]
+    let mut bundle_to_consider = bundle.clone();
+    #[cfg(not(feature = "node_proc"))]
+    {
+        bundle_to_consider.priority = 1;
+        bundle_to_consider.size = crate::types::VOLUME_ZERO;
+    }
+    bundle_to_consider
 }
@@ -0,0 +1,90 @@
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::cell::RefCell;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    types::{ContactID, Date, HashMap, Priority, Volume, TIME_ZERO, VOLUME_ZERO},
+};
+
+/// A transmission committed before the start of a router (e.g. by a previous instance of the
+/// router, before a restart), see `Router::import_committed`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CommittedTx {
+    /// The contact of the transmission, identified by its `ContactID` in the multigraph.
+    pub contact: ContactID,
+    /// The size of the bundle transmitted.
+    pub size: Volume,
+    /// The priority of the bundle transmitted.
+    pub priority: Priority,
+    /// The start of the transmission.
+    pub tx_start: Date,
+    /// The end of the transmission.
+    pub tx_end: Date,
+}
+
+/// Imports committed transmissions into the contacts of a multigraph, with
+/// `ContactManager::import_tx`: the volumes and the free intervals of the contacts are consumed
+/// as if the transmissions had been scheduled by the router.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `schedules` - The committed transmissions.
+///
+/// # Returns
+///
+/// * `usize` - The count of transmissions imported. A transmission is not imported if its contact
+///   is unknown, if its window is malformed, or if its manager refuses it (see `import_tx`).
+pub fn import_multigraph_committed<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    schedules: &[CommittedTx],
+) -> usize {
+    let graph = multigraph.borrow();
+    let mut contacts: HashMap<ContactID, &Rc<RefCell<Contact<NM, CM>>>> = HashMap::new();
+    for sender in &graph.senders {
+        for receiver in &sender.receivers {
+            for contact in &receiver.contacts_to_receiver {
+                contacts.insert(contact.borrow().id, contact);
+            }
+        }
+    }
+
+    let mut imported = 0;
+    for committed in schedules {
+        if committed.tx_end < committed.tx_start || committed.size < VOLUME_ZERO {
+            continue;
+        }
+        let Some(contact) = contacts.get(&committed.contact) else {
+            continue;
+        };
+        let mut contact = contact.borrow_mut();
+        let info = contact.info;
+        let bundle = Bundle {
+            source: info.tx_node,
+            destinations: vec![info.rx_node],
+            priority: committed.priority,
+            size: committed.size,
+            expiration: Date::MAX,
+            soft_deadline: None,
+            critical: false,
+            destination_priorities: Vec::new(),
+        };
+        let tx_data = ContactManagerTxData {
+            tx_start: committed.tx_start,
+            tx_end: committed.tx_end,
+            delay: TIME_ZERO,
+            expiration: info.end,
+            arrival: committed.tx_end,
+        };
+        if contact.manager.import_tx(&info, &tx_data, &bundle) {
+            imported += 1;
+        }
+    }
+    imported
+}
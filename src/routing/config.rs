@@ -0,0 +1,361 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::Deserialize;
+
+use crate::{
+    contact::Contact, contact_manager::ContactManager, node::Node, node_manager::NodeManager,
+    types::duration_from_f64,
+};
+
+#[cfg(feature = "std")]
+use crate::{
+    contact_plan::{asabr_file_lexer::FileLexer, from_asabr_lexer::ASABRContactPlan},
+    parsing::{DispatchParser, Dispatcher, Lexer, Parser, ParsingState},
+};
+
+use super::{
+    aliases::{build_generic_router_with_options, RouterOptions, SpsnOptions, StorageFactories},
+    Router,
+};
+
+/// The routing mainframe.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Mainframe {
+    /// Shortest-path trees with caching (SPSN).
+    Spsn,
+    /// Contact Graph Routing with alternative pathfinding.
+    Cgr,
+    /// Volume aware CGR (routes are recomputed when the stored ones are depleted).
+    VolCgr,
+}
+
+/// The Dijkstra variant used for pathfinding.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PathfindingVariant {
+    NodeParenting,
+    HybridParenting,
+    /// Requires the "contact_work_area" feature.
+    ContactParenting,
+}
+
+/// The alternative pathfinding strategy (Cgr only).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AlternativePathfinding {
+    /// Requires the "contact_suppression" feature.
+    FirstEnding,
+    /// Requires the "first_depleted" feature.
+    FirstDepleted,
+}
+
+/// The distance used to compare the paths.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum DistanceKind {
+    #[default]
+    Sabr,
+    Hop,
+}
+
+/// The route storage parameters (Spsn only).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Whether the cached trees are selected according to the bundle size.
+    pub check_size: bool,
+    /// Whether the cached trees are selected according to the bundle priority.
+    /// The guard of the router also tracks the limits per priority if set.
+    pub check_priority: bool,
+    /// The maximum number of trees in the cache.
+    pub max_entries: usize,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            check_size: false,
+            check_priority: false,
+            max_entries: 10,
+        }
+    }
+}
+
+/// The guard settings (Spsn only), see `Guard`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct GuardConfig {
+    /// Whether the routing of the bundles toward the destinations known to be unreachable for
+    /// their size is aborted.
+    pub enabled: bool,
+    /// Whether the limits are tracked per priority, `storage.check_priority` if unset.
+    pub with_priorities: Option<bool>,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            with_priorities: None,
+        }
+    }
+}
+
+/// When the transmissions of a route are committed (booked on the contacts).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum CommitPolicy {
+    /// All the transmissions of the route are booked at routing time.
+    #[default]
+    Full,
+    /// Only the transmissions starting within the horizon (in seconds after the routing time)
+    /// are booked, see `Spsn::with_booking_horizon`.
+    Horizon(f64),
+}
+
+/// A description of a router, deserializable with serde (e.g. from JSON, TOML or YAML).
+///
+/// Example (JSON):
+/// ```json
+/// {
+///     "mainframe": "Cgr",
+///     "pathfinding": "HybridParenting",
+///     "alternative": "FirstEnding",
+///     "distance": "Sabr",
+///     "commit": { "Horizon": 3600.0 },
+///     "required_features": ["contact_suppression"]
+/// }
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Deserialize)]
+pub struct RouterConfig {
+    /// The routing mainframe.
+    pub mainframe: Mainframe,
+    /// The Dijkstra variant.
+    pub pathfinding: PathfindingVariant,
+    /// The alternative pathfinding strategy, mandatory for Cgr, forbidden otherwise.
+    #[serde(default)]
+    pub alternative: Option<AlternativePathfinding>,
+    /// The distance, SABR by default.
+    #[serde(default)]
+    pub distance: DistanceKind,
+    /// The storage parameters, only used by Spsn.
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// The guard settings, only used by Spsn.
+    #[serde(default)]
+    pub guard: GuardConfig,
+    /// The commit policy, full by default.
+    #[serde(default)]
+    pub commit: CommitPolicy,
+    /// The crate features the configuration relies on, checked at construction.
+    #[serde(default)]
+    pub required_features: Vec<String>,
+}
+
+/// The crate features, see `feature_enabled`.
+pub(super) const FEATURES: &[&str] = &[
+    "std",
+    "alloc",
+    "debug",
+    "contact_work_area",
+    "contact_suppression",
+    "first_depleted",
+    "node_proc",
+    "node_tx",
+    "node_rx",
+    "manual_queueing",
+    "integer_time",
+    "integer_time_ms",
+    "integer_time_us",
+    "strict-units",
+    "parallel",
+    "ephemeris",
+    "service",
+    "daemon",
+    "wasm",
+    "interop-proto",
+    "otel",
+];
+
+/// Returns whether a crate feature was compiled in, `None` if the feature is unknown.
+pub(super) fn feature_enabled(feature: &str) -> Option<bool> {
+    match feature {
+        "std" => Some(cfg!(feature = "std")),
+        "alloc" => Some(cfg!(feature = "alloc")),
+        "integer_time" => Some(cfg!(feature = "integer_time")),
+        "integer_time_ms" => Some(cfg!(feature = "integer_time_ms")),
+        "integer_time_us" => Some(cfg!(feature = "integer_time_us")),
+        "strict-units" => Some(cfg!(feature = "strict-units")),
+        "parallel" => Some(cfg!(feature = "parallel")),
+        "ephemeris" => Some(cfg!(feature = "ephemeris")),
+        "service" => Some(cfg!(feature = "service")),
+        "daemon" => Some(cfg!(feature = "daemon")),
+        "wasm" => Some(cfg!(feature = "wasm")),
+        "interop-proto" => Some(cfg!(feature = "interop-proto")),
+        "otel" => Some(cfg!(feature = "otel")),
+        "debug" => Some(cfg!(feature = "debug")),
+        "contact_work_area" => Some(cfg!(feature = "contact_work_area")),
+        "contact_suppression" => Some(cfg!(feature = "contact_suppression")),
+        "first_depleted" => Some(cfg!(feature = "first_depleted")),
+        "node_proc" => Some(cfg!(feature = "node_proc")),
+        "node_tx" => Some(cfg!(feature = "node_tx")),
+        "node_rx" => Some(cfg!(feature = "node_rx")),
+        "manual_queueing" => Some(cfg!(feature = "manual_queueing")),
+        _ => None,
+    }
+}
+
+impl RouterConfig {
+    /// Parses a configuration from a JSON string.
+    #[cfg(feature = "std")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// Checks that the configuration is consistent and that the required features are compiled.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - An error message describing the first issue found.
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.mainframe, self.alternative) {
+            (Mainframe::Cgr, None) => {
+                return Err("Cgr requires an alternative pathfinding strategy".to_string())
+            }
+            (Mainframe::Spsn | Mainframe::VolCgr, Some(_)) => {
+                return Err("Only Cgr supports alternative pathfinding".to_string())
+            }
+            _ => {}
+        }
+        if let CommitPolicy::Horizon(horizon) = self.commit {
+            if !horizon.is_finite() || horizon < 0.0 {
+                return Err(format!("Invalid booking horizon {}", horizon));
+            }
+        }
+
+        let mut required: Vec<&str> = self.required_features.iter().map(|f| f.as_str()).collect();
+        if self.pathfinding == PathfindingVariant::ContactParenting {
+            required.push("contact_work_area");
+        }
+        match self.alternative {
+            Some(AlternativePathfinding::FirstEnding) => required.push("contact_suppression"),
+            Some(AlternativePathfinding::FirstDepleted) => required.push("first_depleted"),
+            None => {}
+        }
+        for feature in required {
+            match feature_enabled(feature) {
+                Some(true) => {}
+                Some(false) => return Err(format!("Feature \"{}\" is not compiled", feature)),
+                None => return Err(format!("Unknown feature \"{}\"", feature)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the router as registered in `build_generic_router`.
+    pub fn router_type(&self) -> String {
+        let mainframe = match self.mainframe {
+            Mainframe::Spsn => "Spsn",
+            Mainframe::Cgr => "Cgr",
+            Mainframe::VolCgr => "VolCgr",
+        };
+        let alternative = match self.alternative {
+            Some(AlternativePathfinding::FirstEnding) => "FirstEnding",
+            Some(AlternativePathfinding::FirstDepleted) => "FirstDepleted",
+            None => "",
+        };
+        let pathfinding = match self.pathfinding {
+            PathfindingVariant::NodeParenting => "NodeParenting",
+            PathfindingVariant::HybridParenting => "HybridParenting",
+            PathfindingVariant::ContactParenting => "ContactParenting",
+        };
+        let distance = match self.distance {
+            DistanceKind::Sabr => "",
+            DistanceKind::Hop => "Hop",
+        };
+        format!("{}{}{}{}", mainframe, alternative, pathfinding, distance)
+    }
+
+    /// Validates the configuration and builds the router.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the contact plan.
+    /// * `contacts` - The contacts of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Box<dyn Router<NM, CM>>, String>` - The router or the validation error.
+    pub fn build<NM: NodeManager + 'static, CM: ContactManager + 'static>(
+        &self,
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+    ) -> Result<Box<dyn Router<NM, CM>>, String> {
+        self.validate()?;
+        let spsn_options = match self.mainframe {
+            Mainframe::Spsn => Some(SpsnOptions {
+                check_size: self.storage.check_size,
+                check_priority: self.storage.check_priority,
+                max_entries: self.storage.max_entries,
+            }),
+            _ => None,
+        };
+        let options = RouterOptions {
+            guard: self.guard.enabled,
+            guard_priorities: self.guard.with_priorities,
+            booking_horizon: match self.commit {
+                CommitPolicy::Full => None,
+                CommitPolicy::Horizon(horizon) => Some(duration_from_f64(horizon)),
+            },
+        };
+        Ok(build_generic_router_with_options(
+            &self.router_type(),
+            nodes,
+            contacts,
+            spsn_options,
+            &StorageFactories::new(),
+            &options,
+        )?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM> + 'static,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM> + 'static,
+    > dyn Router<NM, CM>
+{
+    /// Builds a router from a configuration and an A-SABR contact plan file.
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - The router configuration.
+    /// * `plan_path` - The path to the contact plan.
+    /// * `node_marker_map` - The markers for the node managers, if dynamic parsing is used.
+    /// * `contact_marker_map` - The markers for the contact managers, if dynamic parsing is used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Box<dyn Router<NM, CM>>, String>` - The router or an error message (file, parsing, or configuration).
+    #[allow(clippy::type_complexity)]
+    pub fn from_config(
+        config: &RouterConfig,
+        plan_path: &str,
+        node_marker_map: Option<&Dispatcher<'_, fn(&mut dyn Lexer) -> ParsingState<NM>>>,
+        contact_marker_map: Option<&Dispatcher<'_, fn(&mut dyn Lexer) -> ParsingState<CM>>>,
+    ) -> Result<Box<dyn Router<NM, CM>>, String> {
+        config.validate()?;
+        let mut lexer = FileLexer::new(plan_path).map_err(|e| e.to_string())?;
+        let (nodes, contacts) =
+            ASABRContactPlan::parse::<NM, CM>(&mut lexer, node_marker_map, contact_marker_map)?;
+        config.build(nodes, contacts)
+    }
+}
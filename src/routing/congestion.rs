@@ -0,0 +1,86 @@
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    types::{NodeID, Volume, VOLUME_ZERO},
+};
+
+use super::RouteEstimate;
+
+/// The congestion toward a destination, see `Router::congestion`.
+///
+/// The score gathers the signals available to the router, each normalized from 0 (no pressure)
+/// to 1 (the routing fails), so that the applications can throttle the generation of low-priority
+/// bundles before the routing fails outright.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct CongestionEstimate {
+    /// The destination node.
+    pub destination: NodeID,
+    /// The normalized congestion, the highest of the pressures below, 1 if no route is found.
+    pub score: f32,
+    /// The known size limit toward the destination (see `Guard`), the bundles of this size or
+    /// larger being unroutable.
+    pub guard_limit: Option<Volume>,
+    /// The bundle size relative to the guard limit (1 if the bundle reaches the limit).
+    pub guard_pressure: Option<f32>,
+    /// The recent rate of the dry runs of the stored routes failing toward the destination.
+    pub dry_run_failure_rate: Option<f32>,
+    /// The share of the residual volume of the bottleneck contact of the estimated route used by
+    /// the bundle, `None` if no route is found or no contact manager tracks volumes.
+    pub volume_pressure: Option<f32>,
+}
+
+impl CongestionEstimate {
+    /// Combines the congestion signals toward the destination of a unicast bundle.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The probe bundle (destination, priority and size of the traffic considered).
+    /// * `guard_limit` - The known size limit toward the destination, if any.
+    /// * `dry_run_failure_rate` - The dry run failure rate of the route storage, if tracked.
+    /// * `estimate` - The estimated route of the bundle, `None` if no route is found.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The congestion estimate.
+    pub fn new(
+        bundle: &Bundle,
+        guard_limit: Option<Volume>,
+        dry_run_failure_rate: Option<f32>,
+        estimate: Option<&RouteEstimate>,
+    ) -> Self {
+        let guard_pressure = guard_limit.map(|limit| {
+            if bundle.size >= limit || limit <= VOLUME_ZERO {
+                1.0
+            } else {
+                (bundle.size / limit) as f32
+            }
+        });
+        let volume_pressure = estimate
+            .and_then(|estimate| estimate.min_volume_margin())
+            .map(|margin| {
+                let residual = margin + bundle.size;
+                if residual <= VOLUME_ZERO {
+                    1.0
+                } else {
+                    (bundle.size / residual).clamp(0.0, 1.0) as f32
+                }
+            });
+        let score = match estimate {
+            None => 1.0,
+            Some(_) => [guard_pressure, dry_run_failure_rate, volume_pressure]
+                .into_iter()
+                .flatten()
+                .fold(0.0, f32::max),
+        };
+        Self {
+            destination: bundle.destinations[0],
+            score,
+            guard_limit,
+            guard_pressure,
+            dry_run_failure_rate,
+            volume_pressure,
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::{boxed::Box, rc::Rc, string::String, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    pathfinding::PathfindingStats,
+    types::{Date, HashMap, NodeID},
+};
+
+use super::{
+    committed::CommittedTx, congestion::CongestionEstimate, gossip::StateSummary,
+    warm_up::WarmUpBudget, NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
+
+/// The static neighbor preferences of the nodes, used by a `ContingencyRouter` when no scheduled
+/// route is found (e.g. a lost or outdated contact plan).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default)]
+pub struct ContingencyProfile {
+    /// The neighbors of each node, in the order of preference.
+    preferences: HashMap<NodeID, Vec<NodeID>>,
+}
+
+impl ContingencyProfile {
+    /// Creates a profile without preferences, i.e. without fallback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the neighbors of a node, in the order of preference.
+    pub fn with_preferences(mut self, node: NodeID, neighbors: Vec<NodeID>) -> Self {
+        self.preferences.insert(node, neighbors);
+        self
+    }
+
+    /// Retrieves the neighbors of a node, in the order of preference (empty if not configured).
+    pub fn preferences(&self, node: NodeID) -> &[NodeID] {
+        self.preferences
+            .get(&node)
+            .map(|neighbors| neighbors.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// An unscheduled forwarding decision of a `ContingencyRouter`: the bundle is to be forwarded
+/// toward a neighbor, at the next opportunity, without any resource booked nor delivery time
+/// predicted.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct ForwardToward {
+    /// The node forwarding the bundle.
+    pub source: NodeID,
+    /// The neighbor chosen from the preferences of the source.
+    pub neighbor: NodeID,
+    /// The destinations of the bundle.
+    pub destinations: Vec<NodeID>,
+}
+
+/// The decision of `ContingencyRouter::route_or_fallback`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ContingencyDecision<NM: NodeManager, CM: ContactManager> {
+    /// A route was found and scheduled by the wrapped router.
+    Scheduled(RoutingOutput<NM, CM>),
+    /// No route was found, the bundle is forwarded toward a preferred neighbor.
+    Unscheduled(ForwardToward),
+}
+
+impl<NM: NodeManager, CM: ContactManager> ContingencyDecision<NM, CM> {
+    /// Tells whether the decision comes from a scheduled route.
+    pub fn is_scheduled(&self) -> bool {
+        matches!(self, Self::Scheduled(_))
+    }
+}
+
+/// A router degrading gracefully under the loss of the contact plan: if the wrapped router finds
+/// no scheduled route, `route_or_fallback` emits a "forward toward" decision from the static
+/// neighbor preferences of the source (see `ContingencyProfile`), like the operational fallbacks
+/// of the hybrid scheduled/opportunistic networks.
+///
+/// The first preferred neighbor is chosen, skipping the excluded nodes and the neighbors marked
+/// down (see `Router::set_neighbor_state`), a destination of the bundle being preferred if it is
+/// among the neighbors. The other calls, `route` included, are forwarded to the wrapped router.
+pub struct ContingencyRouter<NM: NodeManager, CM: ContactManager> {
+    /// The wrapped router.
+    inner: Box<dyn Router<NM, CM>>,
+    /// The neighbor preferences.
+    profile: ContingencyProfile,
+    /// The end of the down state of the neighbors marked down.
+    down_until: HashMap<NodeID, Date>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> ContingencyRouter<NM, CM> {
+    /// Wraps a router.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The router computing the scheduled routes.
+    /// * `profile` - The neighbor preferences of the fallback.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `ContingencyRouter`.
+    pub fn new(inner: Box<dyn Router<NM, CM>>, profile: ContingencyProfile) -> Self {
+        Self {
+            inner,
+            profile,
+            down_until: HashMap::new(),
+        }
+    }
+
+    /// Retrieves the neighbor preferences.
+    pub fn profile(&self) -> &ContingencyProfile {
+        &self.profile
+    }
+
+    /// Retrieves the neighbor preferences, e.g. to reconfigure a node.
+    pub fn profile_mut(&mut self) -> &mut ContingencyProfile {
+        &mut self.profile
+    }
+
+    /// Returns the underlying router.
+    pub fn into_inner(self) -> Box<dyn Router<NM, CM>> {
+        self.inner
+    }
+
+    /// Routes a bundle with the wrapped router, falling back to the neighbor preferences of the
+    /// source if no route is found.
+    ///
+    /// # Parameters
+    ///
+    /// * `source` - The source node ID.
+    /// * `bundle` - The bundle to route.
+    /// * `curr_time` - The current time.
+    /// * `excluded_nodes` - A list of nodes to exclude, from the routes and from the fallback.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ContingencyDecision<NM, CM>>` - The scheduled routing output, or the unscheduled
+    ///   decision, `None` if the bundle expired or if no preferred neighbor is available.
+    pub fn route_or_fallback(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<ContingencyDecision<NM, CM>> {
+        if let Some(output) = self.inner.route(source, bundle, curr_time, excluded_nodes) {
+            return Some(ContingencyDecision::Scheduled(output));
+        }
+        if bundle.expiration < curr_time {
+            return None;
+        }
+        self.fallback(source, bundle, curr_time, excluded_nodes)
+            .map(|neighbor| {
+                ContingencyDecision::Unscheduled(ForwardToward {
+                    source,
+                    neighbor,
+                    destinations: bundle.destinations.clone(),
+                })
+            })
+    }
+
+    /// Chooses the preferred neighbor of the source, see `ContingencyRouter`.
+    fn fallback(
+        &self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+    ) -> Option<NodeID> {
+        let available = |neighbor: &&NodeID| {
+            **neighbor != source
+                && !excluded_nodes.contains(*neighbor)
+                && self
+                    .down_until
+                    .get(*neighbor)
+                    .is_none_or(|until| *until <= curr_time)
+        };
+        let neighbors = self.profile.preferences(source);
+        neighbors
+            .iter()
+            .filter(available)
+            .find(|neighbor| bundle.destinations.contains(neighbor))
+            .or_else(|| neighbors.iter().find(available))
+            .copied()
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> Router<NM, CM> for ContingencyRouter<NM, CM> {
+    fn route(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        self.inner.route(source, bundle, curr_time, excluded_nodes)
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        self.inner
+            .estimate(source, bundle, curr_time, excluded_nodes)
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        self.inner
+            .route_with_backup(source, bundle, curr_time, excluded_nodes)
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.inner.resolve(name)
+    }
+    // the states are also kept for the fallback, whether the wrapped router supports them or not
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        match state {
+            NeighborState::Down => self.down_until.insert(node, until),
+            NeighborState::Up => self.down_until.remove(&node),
+        };
+        self.inner.set_neighbor_state(node, state, until)
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.down_until.retain(|_, until| *until > now);
+        self.inner.advance_time(now)
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        self.inner.multigraph()
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.inner.pathfinding_stats()
+    }
+    fn export_state_summary(&self, curr_time: Date) -> Option<StateSummary> {
+        self.inner.export_state_summary(curr_time)
+    }
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        self.inner.merge_state_summary(summary, curr_time)
+    }
+    fn import_committed(&mut self, schedules: &[CommittedTx]) -> usize {
+        self.inner.import_committed(schedules)
+    }
+    fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        self.inner.expand_periodic(horizon)
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        self.inner.warm_up(sources, destinations, times, budget)
+    }
+    fn congestion(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        self.inner
+            .congestion(source, bundle, curr_time, excluded_nodes)
+    }
+}
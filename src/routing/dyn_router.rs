@@ -0,0 +1,464 @@
+use alloc::{boxed::Box, rc::Rc, string::String, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::ContactManager,
+    distance::{hop::Hop, sabr::SABR, Distance},
+    multigraph::Multigraph,
+    node::Node,
+    node_manager::NodeManager,
+    pathfinding::{
+        hybrid_parenting::{HybridParentingOrd, HybridParentingPathExcl, HybridParentingTreeExcl},
+        node_parenting::{NodeParentingPathExcl, NodeParentingTreeExcl},
+        PathfindingStats,
+    },
+    route_storage::{cache::TreeCache, table::RoutingTable},
+    types::{Date, NodeID},
+};
+
+#[cfg(feature = "contact_suppression")]
+use super::cgr::Cgr;
+#[cfg(all(feature = "contact_work_area", feature = "contact_suppression"))]
+use crate::pathfinding::contact_parenting::ContactParentingPath;
+#[cfg(feature = "contact_work_area")]
+use crate::pathfinding::contact_parenting::{ContactParentingPathExcl, ContactParentingTreeExcl};
+#[cfg(feature = "first_depleted")]
+use crate::pathfinding::limiting_contact::first_depleted::FirstDepleted;
+#[cfg(feature = "contact_suppression")]
+use crate::pathfinding::{
+    hybrid_parenting::HybridParentingPath, limiting_contact::first_ending::FirstEnding,
+    node_parenting::NodeParentingPath,
+};
+
+use super::{
+    committed::CommittedTx,
+    config::{
+        AlternativePathfinding, CommitPolicy, GuardConfig, Mainframe, PathfindingVariant,
+        RouterConfig,
+    },
+    congestion::CongestionEstimate,
+    gossip::StateSummary,
+    spsn::Spsn,
+    volcgr::VolCgr,
+    warm_up::WarmUpBudget,
+    MulticastSplit, NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
+
+pub use super::config::{DistanceKind, StorageConfig};
+
+/// The routing mainframe of a `DynRouter`.
+pub type RouterKind = Mainframe;
+/// The Dijkstra variant of a `DynRouter`.
+pub type ParentingKind = PathfindingVariant;
+/// The alternative pathfinding (contact suppression) strategy of a `DynRouter`, Cgr only.
+pub type SuppressionKind = AlternativePathfinding;
+
+/// The configuration of a `DynRouter`, created with `DynRouter::builder`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct DynRouterBuilder {
+    /// The routing mainframe.
+    kind: RouterKind,
+    /// The Dijkstra variant, node parenting by default.
+    parenting: ParentingKind,
+    /// The distance, SABR by default.
+    distance: DistanceKind,
+    /// The suppression strategy, mandatory for Cgr, forbidden otherwise.
+    suppression: Option<SuppressionKind>,
+    /// The storage parameters, only used by Spsn.
+    storage: StorageConfig,
+    /// The split of the multicast branches, only used by Spsn.
+    multicast_split: MulticastSplit,
+}
+
+impl DynRouterBuilder {
+    /// Sets the Dijkstra variant.
+    pub fn parenting(mut self, parenting: ParentingKind) -> Self {
+        self.parenting = parenting;
+        self
+    }
+
+    /// Sets the distance.
+    pub fn distance(mut self, distance: DistanceKind) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Sets the suppression strategy (Cgr only).
+    pub fn suppression(mut self, suppression: SuppressionKind) -> Self {
+        self.suppression = Some(suppression);
+        self
+    }
+
+    /// Sets the storage parameters (Spsn only).
+    pub fn storage(mut self, storage: StorageConfig) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets where the branches of the multicast trees split (Spsn only).
+    pub fn multicast_split(mut self, split: MulticastSplit) -> Self {
+        self.multicast_split = split;
+        self
+    }
+
+    /// Checks the combination and instantiates the router.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the contact plan.
+    /// * `contacts` - The contacts of the contact plan.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DynRouter<NM, CM>, String>` - The router, or an error message if the combination
+    ///   is inconsistent or relies on features not compiled.
+    pub fn build<NM: NodeManager + 'static, CM: ContactManager + 'static>(
+        self,
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+    ) -> Result<DynRouter<NM, CM>, String> {
+        RouterConfig {
+            mainframe: self.kind,
+            pathfinding: self.parenting,
+            alternative: self.suppression,
+            distance: self.distance,
+            storage: self.storage.clone(),
+            guard: GuardConfig::default(),
+            commit: CommitPolicy::default(),
+            required_features: Vec::new(),
+        }
+        .validate()?;
+
+        let inner = match self.distance {
+            DistanceKind::Sabr => self.instantiate::<NM, CM, SABR>(nodes, contacts)?,
+            DistanceKind::Hop => self.instantiate::<NM, CM, Hop>(nodes, contacts)?,
+        };
+        Ok(DynRouter {
+            kind: self.kind,
+            parenting: self.parenting,
+            distance: self.distance,
+            suppression: self.suppression,
+            inner,
+        })
+    }
+
+    /// Instantiates the generic combination for a distance.
+    fn instantiate<
+        NM: NodeManager + 'static,
+        CM: ContactManager + 'static,
+        D: Distance<NM, CM> + HybridParentingOrd<NM, CM> + 'static,
+    >(
+        &self,
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+    ) -> Result<Box<dyn Router<NM, CM>>, String> {
+        match self.kind {
+            RouterKind::Spsn => {
+                let check_priority = self.storage.check_priority;
+                let cache = Rc::new(RefCell::new(TreeCache::new(
+                    self.storage.check_size,
+                    check_priority,
+                    self.storage.max_entries,
+                )));
+                match self.parenting {
+                    ParentingKind::NodeParenting => Ok(Box::new(
+                        Spsn::<NM, CM, NodeParentingTreeExcl<NM, CM, D>, _>::new(
+                            nodes,
+                            contacts,
+                            cache,
+                            check_priority,
+                        )
+                        .with_multicast_split(self.multicast_split),
+                    )),
+                    ParentingKind::HybridParenting => Ok(Box::new(
+                        Spsn::<NM, CM, HybridParentingTreeExcl<NM, CM, D>, _>::new(
+                            nodes,
+                            contacts,
+                            cache,
+                            check_priority,
+                        )
+                        .with_multicast_split(self.multicast_split),
+                    )),
+                    #[cfg(feature = "contact_work_area")]
+                    ParentingKind::ContactParenting => Ok(Box::new(
+                        Spsn::<NM, CM, ContactParentingTreeExcl<NM, CM, D>, _>::new(
+                            nodes,
+                            contacts,
+                            cache,
+                            check_priority,
+                        )
+                        .with_multicast_split(self.multicast_split),
+                    )),
+                    #[cfg(not(feature = "contact_work_area"))]
+                    ParentingKind::ContactParenting => Err(not_compiled("contact_work_area")),
+                }
+            }
+            RouterKind::VolCgr => {
+                let table = Rc::new(RefCell::new(RoutingTable::<NM, CM, D>::new()));
+                match self.parenting {
+                    ParentingKind::NodeParenting => Ok(Box::new(VolCgr::<
+                        NM,
+                        CM,
+                        NodeParentingPathExcl<NM, CM, D>,
+                        _,
+                    >::new(
+                        nodes, contacts, table
+                    ))),
+                    ParentingKind::HybridParenting => Ok(Box::new(VolCgr::<
+                        NM,
+                        CM,
+                        HybridParentingPathExcl<NM, CM, D>,
+                        _,
+                    >::new(
+                        nodes, contacts, table
+                    ))),
+                    #[cfg(feature = "contact_work_area")]
+                    ParentingKind::ContactParenting => Ok(Box::new(VolCgr::<
+                        NM,
+                        CM,
+                        ContactParentingPathExcl<NM, CM, D>,
+                        _,
+                    >::new(
+                        nodes, contacts, table
+                    ))),
+                    #[cfg(not(feature = "contact_work_area"))]
+                    ParentingKind::ContactParenting => Err(not_compiled("contact_work_area")),
+                }
+            }
+            #[cfg(feature = "contact_suppression")]
+            RouterKind::Cgr => self.instantiate_cgr::<NM, CM, D>(nodes, contacts),
+            #[cfg(not(feature = "contact_suppression"))]
+            RouterKind::Cgr => Err(not_compiled("contact_suppression")),
+        }
+    }
+
+    /// Instantiates the Cgr combination for a distance.
+    #[cfg(feature = "contact_suppression")]
+    fn instantiate_cgr<
+        NM: NodeManager + 'static,
+        CM: ContactManager + 'static,
+        D: Distance<NM, CM> + HybridParentingOrd<NM, CM> + 'static,
+    >(
+        &self,
+        nodes: Vec<Node<NM>>,
+        contacts: Vec<Contact<NM, CM>>,
+    ) -> Result<Box<dyn Router<NM, CM>>, String> {
+        let table = Rc::new(RefCell::new(RoutingTable::<NM, CM, D>::new()));
+        match (self.suppression, self.parenting) {
+            (Some(SuppressionKind::FirstEnding), ParentingKind::NodeParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstEnding<NM, CM, NodeParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            (Some(SuppressionKind::FirstEnding), ParentingKind::HybridParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstEnding<NM, CM, HybridParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            #[cfg(feature = "contact_work_area")]
+            (Some(SuppressionKind::FirstEnding), ParentingKind::ContactParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstEnding<NM, CM, ContactParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            #[cfg(feature = "first_depleted")]
+            (Some(SuppressionKind::FirstDepleted), ParentingKind::NodeParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstDepleted<NM, CM, NodeParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            #[cfg(feature = "first_depleted")]
+            (Some(SuppressionKind::FirstDepleted), ParentingKind::HybridParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstDepleted<NM, CM, HybridParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            #[cfg(all(feature = "contact_work_area", feature = "first_depleted"))]
+            (Some(SuppressionKind::FirstDepleted), ParentingKind::ContactParenting) => {
+                Ok(Box::new(Cgr::<
+                    NM,
+                    CM,
+                    FirstDepleted<NM, CM, ContactParentingPath<NM, CM, D>>,
+                    _,
+                >::new(nodes, contacts, table)))
+            }
+            // rejected by the validation
+            _ => Err("Unsupported Cgr combination".into()),
+        }
+    }
+}
+
+#[cfg(not(all(feature = "contact_work_area", feature = "contact_suppression")))]
+fn not_compiled(feature: &str) -> String {
+    alloc::format!("Feature \"{}\" is not compiled", feature)
+}
+
+/// A router selected at runtime with typed options, the alternative to the names of
+/// `build_generic_router` and to the type aliases of `routing::aliases`.
+///
+/// ```ignore
+/// let router = DynRouter::builder(RouterKind::Cgr)
+///     .parenting(ParentingKind::HybridParenting)
+///     .suppression(SuppressionKind::FirstEnding)
+///     .distance(DistanceKind::Hop)
+///     .build(nodes, contacts)?;
+/// ```
+///
+/// The router internally instantiates the matching generic combination, and implements `Router`
+/// by delegation.
+pub struct DynRouter<NM: NodeManager, CM: ContactManager> {
+    /// The routing mainframe.
+    kind: RouterKind,
+    /// The Dijkstra variant.
+    parenting: ParentingKind,
+    /// The distance.
+    distance: DistanceKind,
+    /// The suppression strategy (Cgr only).
+    suppression: Option<SuppressionKind>,
+    /// The generic combination.
+    inner: Box<dyn Router<NM, CM>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> DynRouter<NM, CM> {
+    /// Starts the configuration of a router, with node parenting, the SABR distance and the
+    /// default storage parameters.
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` - The routing mainframe.
+    ///
+    /// # Returns
+    ///
+    /// * `DynRouterBuilder` - The configuration, to be completed and built.
+    pub fn builder(kind: RouterKind) -> DynRouterBuilder {
+        DynRouterBuilder {
+            kind,
+            parenting: ParentingKind::NodeParenting,
+            distance: DistanceKind::default(),
+            suppression: None,
+            storage: StorageConfig::default(),
+            multicast_split: MulticastSplit::Late,
+        }
+    }
+
+    /// Returns the routing mainframe.
+    pub fn kind(&self) -> RouterKind {
+        self.kind
+    }
+
+    /// Returns the Dijkstra variant.
+    pub fn parenting(&self) -> ParentingKind {
+        self.parenting
+    }
+
+    /// Returns the distance.
+    pub fn distance(&self) -> DistanceKind {
+        self.distance
+    }
+
+    /// Returns the suppression strategy, `None` for Spsn and VolCgr.
+    pub fn suppression(&self) -> Option<SuppressionKind> {
+        self.suppression
+    }
+
+    /// Returns the underlying router.
+    pub fn into_inner(self) -> Box<dyn Router<NM, CM>> {
+        self.inner
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> Router<NM, CM> for DynRouter<NM, CM> {
+    fn route(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        self.inner.route(source, bundle, curr_time, excluded_nodes)
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        self.inner
+            .estimate(source, bundle, curr_time, excluded_nodes)
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        self.inner
+            .route_with_backup(source, bundle, curr_time, excluded_nodes)
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.inner.resolve(name)
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        self.inner.set_neighbor_state(node, state, until)
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.inner.advance_time(now)
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        self.inner.multigraph()
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.inner.pathfinding_stats()
+    }
+    fn export_state_summary(&self, curr_time: Date) -> Option<StateSummary> {
+        self.inner.export_state_summary(curr_time)
+    }
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        self.inner.merge_state_summary(summary, curr_time)
+    }
+    fn import_committed(&mut self, schedules: &[CommittedTx]) -> usize {
+        self.inner.import_committed(schedules)
+    }
+    fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        self.inner.expand_periodic(horizon)
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        self.inner.warm_up(sources, destinations, times, budget)
+    }
+    fn congestion(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        self.inner
+            .congestion(source, bundle, curr_time, excluded_nodes)
+    }
+}
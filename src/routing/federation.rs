@@ -0,0 +1,332 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::cmp::Ordering;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    pathfinding::PathfindingStats,
+    types::{Date, NodeID},
+};
+
+use super::{
+    committed::CommittedTx,
+    congestion::CongestionEstimate,
+    gossip::StateSummary,
+    warm_up::{WarmUpBudget, WarmUpClock},
+    NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
+
+/// The selection among the members of a `FederatedRouter`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FederationPolicy {
+    /// The member with the earliest estimated delivery is selected (see `Router::estimate`), the
+    /// order of the members breaking the ties. The members are tried in the order of the
+    /// preference for the bundles without estimation (e.g. multicast).
+    BestArrival,
+    /// The first member (in the order of the members) finding a route is selected, e.g. to prefer
+    /// a convergence layer and fall back to the others.
+    Preference,
+}
+
+/// A member of a `FederatedRouter`: a router over the contact plan slice of a convergence layer.
+struct Member<NM: NodeManager, CM: ContactManager> {
+    /// The name of the member (e.g. the convergence layer).
+    name: String,
+    /// The router over the slice.
+    router: Box<dyn Router<NM, CM>>,
+}
+
+/// A router combining the routers of several contact plan slices, for the nodes with several
+/// convergence layers (e.g. an RF plan and an optical plan).
+///
+/// Each routing call selects a single member by the policy, and only this member schedules the
+/// bundle: the resources of the other slices are not consumed. The members share the node IDs.
+pub struct FederatedRouter<NM: NodeManager, CM: ContactManager> {
+    /// The members, in the order of preference.
+    members: Vec<Member<NM, CM>>,
+    /// The selection among the members.
+    policy: FederationPolicy,
+}
+
+impl<NM: NodeManager, CM: ContactManager> FederatedRouter<NM, CM> {
+    /// Creates a federation without member.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The selection among the members.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `FederatedRouter`.
+    pub fn new(policy: FederationPolicy) -> Self {
+        Self {
+            members: Vec::new(),
+            policy,
+        }
+    }
+
+    /// Adds a member, after the previous ones in the order of preference.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the member (e.g. the convergence layer).
+    /// * `router` - The router over the contact plan slice of the member.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The federation with the new member.
+    pub fn with_member(mut self, name: &str, router: Box<dyn Router<NM, CM>>) -> Self {
+        self.members.push(Member {
+            name: name.into(),
+            router,
+        });
+        self
+    }
+
+    /// Lists the names of the members, in the order of preference.
+    pub fn members(&self) -> impl Iterator<Item = &str> + '_ {
+        self.members.iter().map(|member| member.name.as_str())
+    }
+
+    /// Retrieves the router of a member.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the member.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&mut dyn Router<NM, CM>>` - The router, or `None` if no member has this name.
+    pub fn member_mut(&mut self, name: &str) -> Option<&mut (dyn Router<NM, CM> + 'static)> {
+        self.members
+            .iter_mut()
+            .find(|member| member.name == name)
+            .map(|member| member.router.as_mut())
+    }
+
+    /// Orders the members to try for a bundle, according to the policy.
+    fn candidates(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.members.len()).collect();
+        if self.policy == FederationPolicy::BestArrival && bundle.destinations.len() == 1 {
+            let estimates: Vec<Option<Date>> = self
+                .members
+                .iter_mut()
+                .map(|member| {
+                    member
+                        .router
+                        .estimate(source, bundle, curr_time, excluded_nodes)
+                        .map(|estimate| estimate.delivery_time)
+                })
+                .collect();
+            // stable: the members without estimation keep their rank, after the others
+            order.sort_by(|a, b| match (estimates[*a], estimates[*b]) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        }
+        order
+    }
+
+    /// Routes a bundle like `Router::route`, and tells the member selected.
+    ///
+    /// The members are tried in the order given by the policy, until one finds a route.
+    ///
+    /// # Parameters
+    ///
+    /// * `source` - The source node ID.
+    /// * `bundle` - The bundle to route.
+    /// * `curr_time` - The current time.
+    /// * `excluded_nodes` - A sorted list of nodes to exclude from the routing paths.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(&str, RoutingOutput<NM, CM>)>` - The name of the member selected and its routing
+    ///   output, or `None` if no member finds a route.
+    pub fn route_federated(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<(&str, RoutingOutput<NM, CM>)> {
+        for idx in self.candidates(source, bundle, curr_time, excluded_nodes) {
+            if let Some(output) =
+                self.members[idx]
+                    .router
+                    .route(source, bundle, curr_time, excluded_nodes)
+            {
+                return Some((self.members[idx].name.as_str(), output));
+            }
+        }
+        None
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> Router<NM, CM> for FederatedRouter<NM, CM> {
+    fn route(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        self.route_federated(source, bundle, curr_time, excluded_nodes)
+            .map(|(_name, output)| output)
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        let mut best: Option<RouteEstimate> = None;
+        for member in &mut self.members {
+            let Some(estimate) = member
+                .router
+                .estimate(source, bundle, curr_time, excluded_nodes)
+            else {
+                continue;
+            };
+            if self.policy == FederationPolicy::Preference {
+                return Some(estimate);
+            }
+            if best
+                .as_ref()
+                .is_none_or(|known| estimate.delivery_time < known.delivery_time)
+            {
+                best = Some(estimate);
+            }
+        }
+        best
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        for idx in self.candidates(source, bundle, curr_time, excluded_nodes) {
+            if let Some(output) = self.members[idx].router.route_with_backup(
+                source,
+                bundle,
+                curr_time,
+                excluded_nodes,
+            ) {
+                return Some(output);
+            }
+        }
+        None
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.members
+            .iter()
+            .find_map(|member| member.router.resolve(name))
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        // the neighbor is down for all the convergence layers
+        let mut applied = false;
+        for member in &mut self.members {
+            applied |= member.router.set_neighbor_state(node, state, until);
+        }
+        applied
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        let mut applied = false;
+        for member in &mut self.members {
+            applied |= member.router.advance_time(now);
+        }
+        applied
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        // the runs of all the convergence layers
+        let mut total: Option<PathfindingStats> = None;
+        for member in &self.members {
+            if let Some(stats) = member.router.pathfinding_stats() {
+                total
+                    .get_or_insert_with(PathfindingStats::default)
+                    .merge(&stats);
+            }
+        }
+        total
+    }
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        self.members
+            .iter_mut()
+            .map(|member| member.router.merge_state_summary(summary, curr_time))
+            .sum()
+    }
+    fn import_committed(&mut self, schedules: &[CommittedTx]) -> usize {
+        self.members
+            .iter_mut()
+            .map(|member| member.router.import_committed(schedules))
+            .sum()
+    }
+    fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        self.members
+            .iter_mut()
+            .map(|member| member.router.expand_periodic(horizon))
+            .sum()
+    }
+    // the members share the budget in their order, a member being charged for the runs that
+    // stored a tree or a route
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        let mut clock = WarmUpClock::new(budget);
+        let mut stored = 0;
+        for member in &mut self.members {
+            if clock.expired() {
+                break;
+            }
+            let member_stored =
+                member
+                    .router
+                    .warm_up(sources, destinations, times, clock.remaining());
+            clock.consume(member_stored);
+            stored += member_stored;
+        }
+        stored
+    }
+    fn congestion(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        // the bundles can go through the least congested slice
+        let mut best: Option<CongestionEstimate> = None;
+        for member in &mut self.members {
+            let Some(congestion) =
+                member
+                    .router
+                    .congestion(source, bundle, curr_time, excluded_nodes)
+            else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|known| congestion.score < known.score)
+            {
+                best = Some(congestion);
+            }
+        }
+        best
+    }
+}
@@ -0,0 +1,233 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    types::{times_match, volume_fits, Date, NodeID, Priority, Volume, VOLUME_ZERO},
+};
+
+use super::{NeighborState, Router};
+
+/// A size limit known by a router toward a destination (see `Guard`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LimitSummary {
+    /// The destination node.
+    pub destination: NodeID,
+    /// The priority of the bundles (0 if the guard ignores priorities).
+    pub priority: Priority,
+    /// The bundles of this size or larger are unroutable toward the destination.
+    pub size: Volume,
+}
+
+/// The volume booked on a contact, the contact being identified by its link and its start time.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ContactUtilization {
+    /// The transmitting node of the contact.
+    pub tx_node: NodeID,
+    /// The receiving node of the contact.
+    pub rx_node: NodeID,
+    /// The start time of the contact.
+    pub start: Date,
+    /// The volume booked on the contact, all priorities included.
+    pub booked: Volume,
+}
+
+/// A node marked down, the contacts toward the node being suppressed until `until` (see
+/// `Router::set_neighbor_state`).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DownNode {
+    /// The node marked down.
+    pub node: NodeID,
+    /// The end of the down state.
+    pub until: Date,
+}
+
+/// The congestion knowledge of a router, exchanged opportunistically between the nodes over their
+/// contacts (see `Router::export_state_summary` and `Router::merge_state_summary`).
+///
+/// The summary is compact: only the contacts with a booking that have not ended are listed, and
+/// the nodes are identified by their IDs (the nodes must share the node declarations of the plan).
+/// It is serializable with serde, the encoding being left to the application.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateSummary {
+    /// The time of the export.
+    pub generated_at: Date,
+    /// The size limits learned by the guard of the router.
+    pub limits: Vec<LimitSummary>,
+    /// The volumes booked on the contacts.
+    pub utilization: Vec<ContactUtilization>,
+    /// The nodes marked down, i.e. the suppressed contacts.
+    pub down_nodes: Vec<DownNode>,
+}
+
+impl StateSummary {
+    /// Tells whether the summary carries no knowledge.
+    pub fn is_empty(&self) -> bool {
+        self.limits.is_empty() && self.utilization.is_empty() && self.down_nodes.is_empty()
+    }
+}
+
+/// Exports the contact utilization and the down nodes of a multigraph into a summary, without
+/// guard limits.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `curr_time` - The current time, the contacts ended are not exported.
+///
+/// # Returns
+///
+/// * `StateSummary` - The summary.
+pub fn export_multigraph_state<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    curr_time: Date,
+) -> StateSummary {
+    let graph = multigraph.borrow();
+    let mut utilization = Vec::new();
+    for sender in &graph.senders {
+        for receiver in &sender.receivers {
+            for contact in &receiver.contacts_to_receiver {
+                let contact = contact.borrow();
+                if contact.info.end <= curr_time {
+                    continue;
+                }
+                let Some(booked) = contact.manager.get_booked_volume(&contact.info) else {
+                    continue;
+                };
+                if booked > VOLUME_ZERO {
+                    utilization.push(ContactUtilization {
+                        tx_node: contact.info.tx_node,
+                        rx_node: contact.info.rx_node,
+                        start: contact.info.start,
+                        booked,
+                    });
+                }
+            }
+        }
+    }
+    let down_nodes = graph
+        .get_down_nodes()
+        .iter()
+        .filter_map(|node| {
+            let until = graph.nodes[*node as usize].borrow().down_until?;
+            (until > curr_time).then_some(DownNode { node: *node, until })
+        })
+        .collect();
+    StateSummary {
+        generated_at: curr_time,
+        limits: Vec::new(),
+        utilization,
+        down_nodes,
+    }
+}
+
+/// Merges the contact utilization of a summary into a multigraph.
+///
+/// The merge is conservative and idempotent: a contact takes the booked volume of the summary if
+/// it exceeds its own (see `ContactManager::set_observed_queue`, the volume being accounted at the
+/// lowest priority), the contacts whose managers do not model a queue are left untouched. The down
+/// nodes are merged by `Router::merge_state_summary`, for the routers to invalidate their routes.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `summary` - The summary received.
+/// * `curr_time` - The current time, the contacts ended are ignored.
+///
+/// # Returns
+///
+/// * `usize` - The count of contacts updated.
+pub fn merge_multigraph_utilization<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    summary: &StateSummary,
+    curr_time: Date,
+) -> usize {
+    let graph = multigraph.borrow();
+    let mut updated = 0;
+    for entry in &summary.utilization {
+        let Some(receiver) = graph
+            .senders
+            .get(entry.tx_node as usize)
+            .and_then(|sender| {
+                sender
+                    .receivers
+                    .iter()
+                    .find(|receiver| receiver.node.borrow().info.id == entry.rx_node)
+            })
+        else {
+            continue;
+        };
+        let Some(contact) = receiver
+            .contacts_to_receiver
+            .iter()
+            .find(|contact| times_match(contact.borrow().info.start, entry.start))
+        else {
+            continue;
+        };
+        let mut contact = contact.borrow_mut();
+        if contact.info.end <= curr_time {
+            continue;
+        }
+        let info = contact.info;
+        let known = contact
+            .manager
+            .get_booked_volume(&info)
+            .unwrap_or(VOLUME_ZERO);
+        if volume_fits(entry.booked, known) {
+            continue;
+        }
+        if contact
+            .manager
+            .set_observed_queue(&info, &[entry.booked], summary.generated_at)
+        {
+            updated += 1;
+        }
+    }
+    updated
+}
+
+/// Merges the down nodes of a summary into a router, with `Router::set_neighbor_state` for the
+/// router to invalidate its routes. A node is marked down if it is not, or until a later time.
+///
+/// # Parameters
+///
+/// * `router` - The router.
+/// * `summary` - The summary received.
+/// * `curr_time` - The current time, the down states ended are ignored.
+///
+/// # Returns
+///
+/// * `usize` - The count of nodes updated.
+pub fn merge_down_nodes<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    summary: &StateSummary,
+    curr_time: Date,
+) -> usize {
+    let Some(multigraph) = router.multigraph() else {
+        return 0;
+    };
+    let mut updated = 0;
+    for down in &summary.down_nodes {
+        if down.until <= curr_time {
+            continue;
+        }
+        let known = match multigraph.borrow().nodes.get(down.node as usize) {
+            Some(node) => node.borrow().down_until,
+            None => continue,
+        };
+        if known.is_some_and(|until| until >= down.until) {
+            continue;
+        }
+        if router.set_neighbor_state(down.node, NeighborState::Down, down.until) {
+            updated += 1;
+        }
+    }
+    updated
+}
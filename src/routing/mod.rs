@@ -1,19 +1,53 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use alloc::{borrow::Cow, rc::Rc, string::String, vec, vec::Vec};
+use core::cell::RefCell;
+use serde::Serialize;
 
+use self::source_route::{schedule_route_header, RouteHeader};
+use self::warm_up::WarmUpBudget;
 use crate::{
     bundle::Bundle,
     contact::Contact,
     contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node::Node,
     node_manager::NodeManager,
-    pathfinding::PathFindingOutput,
-    route_stage::RouteStage,
-    types::{Date, NodeID},
+    pathfinding::{PathFindingOutput, Pathfinding, PathfindingStats},
+    route_stage::{HopFailure, RouteStage, ViaHop},
+    route_storage::{Route, RouteStorage},
+    telemetry::Span,
+    types::{
+        time_to_f64, volume_to_f64, ContactID, Date, Duration, HashMap, HopCount, NodeID, Volume,
+        VOLUME_ZERO,
+    },
 };
 
 pub mod aliases;
+#[cfg(feature = "std")]
+pub mod audit;
+pub mod auto;
+pub mod balancing;
 pub mod cgr;
+pub mod committed;
+pub mod config;
+pub mod congestion;
+pub mod contingency;
+pub mod dyn_router;
+pub mod federation;
+pub mod gossip;
+#[cfg(feature = "contact_suppression")]
+pub mod multicopy;
+pub mod robustness;
+pub mod scheduler;
+pub mod source_route;
 pub mod spsn;
 pub mod volcgr;
+pub mod warm_up;
+
+use committed::{import_multigraph_committed, CommittedTx};
+use congestion::CongestionEstimate;
+use gossip::{
+    export_multigraph_state, merge_down_nodes, merge_multigraph_utilization, StateSummary,
+};
 
 /// A trait to allow generic initialization of routers.
 pub trait Router<NM: NodeManager, CM: ContactManager> {
@@ -39,6 +73,961 @@ pub trait Router<NM: NodeManager, CM: ContactManager> {
         curr_time: Date,
         excluded_nodes: &Vec<NodeID>,
     ) -> Option<RoutingOutput<NM, CM>>;
+
+    /// Estimates the route of a unicast bundle without scheduling it.
+    ///
+    /// No resource is consumed and the route storage is neither read nor updated, the bundle
+    /// does not even need to exist (e.g. for admission control). The pathfinding is conducted
+    /// from scratch, so the estimation may differ from the route actually selected by `route`.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID.
+    /// - `bundle`: The `Bundle` to estimate (only unicast bundles are supported).
+    /// - `curr_time`: The current time.
+    /// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+    ///
+    /// # Returns
+    /// A `RouteEstimate` if a feasible route exists, `None` otherwise or if the router does not
+    /// support estimations.
+    fn estimate(
+        &mut self,
+        _source: NodeID,
+        _bundle: &Bundle,
+        _curr_time: Date,
+        _excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        None
+    }
+
+    /// Estimates the round-trip time between two nodes from the contact plan (e.g. to set the
+    /// retransmission or custody timers), with `estimate` in both directions.
+    ///
+    /// The reply leaves `b` at the forward delivery time, after its processing at `b` with the
+    /// "node_proc" feature. Both bundles have the size `bundle_size`, the lowest priority and
+    /// never expire.
+    ///
+    /// # Parameters
+    /// - `a`: The node sending the bundle and receiving the reply.
+    /// - `b`: The node receiving the bundle and sending the reply.
+    /// - `bundle_size`: The size of the bundle and of the reply.
+    /// - `curr_time`: The current time, when the bundle leaves `a`.
+    ///
+    /// # Returns
+    /// A `RttEstimate` if both directions have a feasible route, `None` otherwise or if the router
+    /// does not support estimations.
+    fn estimate_rtt(
+        &mut self,
+        a: NodeID,
+        b: NodeID,
+        bundle_size: Volume,
+        curr_time: Date,
+    ) -> Option<RttEstimate> {
+        let bundle = Bundle {
+            source: a,
+            destinations: vec![b],
+            priority: 0,
+            size: bundle_size,
+            expiration: Date::MAX,
+            soft_deadline: None,
+            critical: false,
+            destination_priorities: Vec::new(),
+        };
+        let forward = self.estimate(a, &bundle, curr_time, &Vec::new())?;
+
+        #[allow(unused_mut)]
+        let mut reply = Bundle {
+            source: b,
+            destinations: vec![a],
+            ..bundle
+        };
+        #[allow(unused_mut)]
+        let mut turnaround = forward.delivery_time;
+        #[cfg(feature = "node_proc")]
+        if let Some(multigraph) = self.multigraph() {
+            let multigraph = multigraph.borrow();
+            let node = multigraph.nodes.get(b as usize)?.borrow();
+            turnaround = node.manager.dry_run_process(turnaround, &mut reply);
+        }
+
+        let reverse = self.estimate(b, &reply, turnaround, &Vec::new())?;
+        Some(RttEstimate {
+            rtt: reverse.delivery_time - curr_time,
+            forward,
+            turnaround,
+            reverse,
+        })
+    }
+
+    /// Computes a stable hash of the route from `source` to `dest`, for the nodes computing their
+    /// routes from the same contact plan to detect a divergence cheaply (e.g. a ground station
+    /// and an onboard router exchanging the digests of some routes).
+    ///
+    /// The route is estimated with `estimate` for an empty probe bundle (lowest priority, never
+    /// expiring), the digest covering its contacts (nodes and windows) and its delivery time: the
+    /// digests differ if the plans, the contact managers or the routing configurations lead to
+    /// different routes or arrival times. The nodes are identified by their IDs, the plans must
+    /// declare the nodes in the same order.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID.
+    /// - `dest`: The destination node ID.
+    /// - `curr_time`: The time the probe leaves the source.
+    ///
+    /// # Returns
+    /// The digest, `None` if no route is found or if the router does not support estimations.
+    fn route_digest(&mut self, source: NodeID, dest: NodeID, curr_time: Date) -> Option<u64> {
+        let bundle = Bundle {
+            source,
+            destinations: vec![dest],
+            priority: 0,
+            size: VOLUME_ZERO,
+            expiration: Date::MAX,
+            soft_deadline: None,
+            critical: false,
+            destination_priorities: Vec::new(),
+        };
+        self.estimate(source, &bundle, curr_time, &Vec::new())
+            .map(|estimate| estimate.digest)
+    }
+
+    /// Routes a bundle like `route`, and precomputes a backup route avoiding the first hop
+    /// contact of the primary route, to switch instantly on a transmission failure.
+    ///
+    /// The backup route is validated with a dry run but not scheduled: its resources are not
+    /// reserved, schedule it (e.g. with `route`) if the primary transmission fails.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing operation.
+    /// - `bundle`: The `Bundle` containing destination information and other relevant routing data.
+    /// - `curr_time`: The current time, which affects scheduling and time-sensitive routing calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+    ///
+    /// # Returns
+    /// The primary routing output and the backup, `None` if the primary routing fails. The default
+    /// implementation provides no backup.
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        let primary = self.route(source, bundle, curr_time, excluded_nodes)?;
+        Some(RoutingWithBackup {
+            primary,
+            backup: None,
+        })
+    }
+
+    /// Resolves a node name, alias or ID into the ID of the routing node.
+    ///
+    /// # Parameters
+    /// - `name`: The name or an alias of the node (see the `alias` plan element), or a node ID.
+    ///
+    /// # Returns
+    /// The ID of the node, `None` if no node matches or if the router does not support the
+    /// resolution.
+    fn resolve(&self, _name: &str) -> Option<NodeID> {
+        None
+    }
+
+    /// Marks a neighbor as temporarily down (e.g. after a missed beacon), or back up.
+    ///
+    /// While a node is down, no transmission toward it starts before `until`: the contacts toward
+    /// the node are suppressed until this time, and remain usable after it. Unlike the
+    /// `excluded_nodes` of the routing calls, the state persists across the calls, until `until`
+    /// is reached or the node is marked up. The stored routes made stale by the change are dropped:
+    /// the routes through the node when it goes down, all the routes when it goes back up (the
+    /// routes avoiding it may no longer be the best).
+    ///
+    /// # Parameters
+    /// - `node`: The ID of the neighbor.
+    /// - `state`: The new state of the neighbor.
+    /// - `until`: The end of the down state, `Date::MAX` for a node down until marked up (ignored
+    ///   for `NeighborState::Up`).
+    ///
+    /// # Returns
+    /// `true` if the state was applied, `false` if the node is unknown or if the router does not
+    /// support the liveness states.
+    fn set_neighbor_state(&mut self, _node: NodeID, _state: NeighborState, _until: Date) -> bool {
+        false
+    }
+
+    /// Advances the notion of "now" of the contact managers, so that the modeled queues (e.g.
+    /// ETO and QD managers) drain as time passes during long-running operation.
+    ///
+    /// # Parameters
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// `true` if the time was applied, `false` if the router does not support it.
+    fn advance_time(&mut self, _now: Date) -> bool {
+        false
+    }
+
+    /// Gives access to the multigraph of the router, e.g. for the analyses of the routing outputs
+    /// (see `RoutingOutput::robustness`).
+    ///
+    /// # Returns
+    /// The multigraph, or `None` if the router does not route over a single multigraph.
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        None
+    }
+
+    /// Returns the statistics accumulated over the pathfinding runs of the router, e.g. to
+    /// profile a replay (see `profile_trace`).
+    ///
+    /// # Returns
+    /// The statistics, or `None` if the router does not gather them.
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        None
+    }
+
+    /// Estimates the congestion toward the destination of a unicast probe bundle (backpressure),
+    /// see `CongestionEstimate`.
+    ///
+    /// Like `estimate`, no resource is consumed, but a pathfinding is conducted.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID.
+    /// - `bundle`: The probe bundle, for the destination, the priority and the size of the traffic.
+    /// - `curr_time`: The current time.
+    /// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+    ///
+    /// # Returns
+    /// The `CongestionEstimate`, or `None` for multicast bundles or if the router does not support
+    /// it.
+    fn congestion(
+        &mut self,
+        _source: NodeID,
+        _bundle: &Bundle,
+        _curr_time: Date,
+        _excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        None
+    }
+
+    /// Exports the congestion knowledge of the router (learned limits, contact utilization and
+    /// down nodes), to be shared with other nodes opportunistically, see `StateSummary`.
+    ///
+    /// # Parameters
+    /// - `curr_time`: The current time, the knowledge about the past is not exported.
+    ///
+    /// # Returns
+    /// The summary, `None` if the router does not support it. The default implementation exports
+    /// the state of the multigraph, without limits.
+    fn export_state_summary(&self, curr_time: Date) -> Option<StateSummary> {
+        self.multigraph()
+            .map(|multigraph| export_multigraph_state(&multigraph, curr_time))
+    }
+
+    /// Merges the congestion knowledge of another node, see `export_state_summary`.
+    ///
+    /// The merge is conservative and idempotent: the contacts take the highest booked volume, the
+    /// nodes stay down until the latest time, and the lowest limits are kept. The limits of a
+    /// router are learned from its own source node, merge them between the routers of a same node
+    /// (e.g. onboard and on the ground) or of nodes sharing their upstream routes.
+    ///
+    /// # Parameters
+    /// - `summary`: The summary received.
+    /// - `curr_time`: The current time.
+    ///
+    /// # Returns
+    /// The count of items merged (contacts, nodes and limits updated). The default implementation
+    /// merges the state of the multigraph, without limits.
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        let mut merged = merge_down_nodes(self, summary, curr_time);
+        if let Some(multigraph) = self.multigraph() {
+            merged += merge_multigraph_utilization(&multigraph, summary, curr_time);
+        }
+        merged
+    }
+
+    /// Imports the transmissions committed before the start of the router (e.g. after a restart
+    /// mid-mission), so that the contacts do not appear pristine: the volumes and the free
+    /// intervals of the contacts are consumed whatever their managers (see
+    /// `ContactManager::import_tx`), reconstructing the current resource state.
+    ///
+    /// # Parameters
+    /// - `schedules`: The committed transmissions.
+    ///
+    /// # Returns
+    /// The count of transmissions imported. The default implementation imports the transmissions
+    /// into the multigraph of the router, if any.
+    fn import_committed(&mut self, schedules: &[CommittedTx]) -> usize {
+        self.multigraph().map_or(0, |multigraph| {
+            import_multigraph_committed(&multigraph, schedules)
+        })
+    }
+
+    /// Expands the occurrences of the periodic contacts starting before `horizon` into the
+    /// multigraph (see `Multigraph::expand_periodic`), e.g. with the current time plus the
+    /// planning lookahead along with `advance_time`. Like the contact plan updates, the routes
+    /// already stored are kept, the new contacts being considered by the next pathfindings.
+    ///
+    /// # Parameters
+    /// - `horizon`: The end of the planning horizon.
+    ///
+    /// # Returns
+    /// The count of contacts inserted, or an error message if an occurrence cannot be inserted.
+    /// The default implementation expands the periodic contacts of the multigraph of the
+    /// router, if any.
+    fn expand_periodic(&mut self, horizon: Date) -> Result<usize, String> {
+        self.multigraph().map_or(Ok(0), |multigraph| {
+            multigraph.borrow_mut().expand_periodic(horizon)
+        })
+    }
+
+    /// Precomputes and stores the routes of the expected traffic ahead of time (e.g. while idle
+    /// after the startup), so that the first routing calls find them in the route storage
+    /// instead of paying the full pathfinding latency.
+    ///
+    /// The routes are computed for each time, source and destination (in this order, the most
+    /// expected first), without constraints (no size, lowest priority, no exclusion), unless the
+    /// storage already provides one. The route storages do not distinguish the sources, a router
+    /// serving a single local node: warm up the sources the router routes for. Likewise, the
+    /// storages drop the routes expired at a later time of `times`, and a `TreeCache` keeps a
+    /// single tree per exclusion list: the times are best taken within the lifetime of the
+    /// routes, e.g. the next routing time only.
+    ///
+    /// # Parameters
+    /// - `sources`: The source nodes of the expected traffic.
+    /// - `destinations`: The destinations of the expected traffic.
+    /// - `times`: The expected routing times.
+    /// - `budget`: The compute budget, the warm-up stops when it expires.
+    ///
+    /// # Returns
+    /// The count of trees or routes stored. The default implementation stores nothing, e.g. for
+    /// the routers without route storage.
+    fn warm_up(
+        &mut self,
+        _sources: &[NodeID],
+        _destinations: &[NodeID],
+        _times: &[Date],
+        _budget: WarmUpBudget,
+    ) -> usize {
+        0
+    }
+
+    /// Routes a reply (e.g. a status report or a custody signal) along the reverse of the route
+    /// of the original bundle, falling back to `route` if the reverse path cannot carry it.
+    ///
+    /// The reverse path goes through the nodes of the original route in the reverse order, each
+    /// hop taking the earliest contact of the multigraph able to carry the reply (see
+    /// `reverse_unicast`), which is common with the symmetric contact plans.
+    ///
+    /// # Parameters
+    /// - `original_route`: The route stage of the original bundle at its destination, the source
+    ///   of the reply.
+    /// - `reply_bundle`: The reply, its destination being the source of the original route.
+    /// - `curr_time`: The current time.
+    ///
+    /// # Returns
+    /// The routing output of the reply, or `None` if no route is found.
+    fn route_reverse(
+        &mut self,
+        original_route: &Rc<RefCell<RouteStage<NM, CM>>>,
+        reply_bundle: &Bundle,
+        curr_time: Date,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        if let Some(multigraph) = self.multigraph() {
+            if let Some(output) =
+                reverse_unicast(&multigraph, original_route, reply_bundle, curr_time)
+            {
+                return Some(output);
+            }
+        }
+        let source = original_route.borrow().to_node;
+        self.route(source, reply_bundle, curr_time, &Vec::new())
+    }
+
+    /// Routes a pre-routed bundle, i.e. a bundle carrying its route (source routing), falling
+    /// back to `route` if the route cannot be followed, for the networks mixing source-routed
+    /// and hop-by-hop traffic.
+    ///
+    /// The route is validated and scheduled against the current resources of the multigraph (see
+    /// `schedule_route_header`). It is not stored, and is not checked against the liveness and
+    /// exclusion scopes of the router beyond what the contacts and nodes enforce.
+    ///
+    /// # Parameters
+    /// - `source`: The source node of the bundle.
+    /// - `bundle`: The bundle, a unicast bundle for the route to be followed.
+    /// - `header`: The route carried by the bundle.
+    /// - `curr_time`: The current time.
+    /// - `excluded_nodes`: The nodes to exclude, from the carried route as well.
+    ///
+    /// # Returns
+    /// The routing output, or `None` if the route cannot be followed and no route is found.
+    fn route_pre_routed(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        header: &RouteHeader,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        if let Some(multigraph) = self.multigraph() {
+            if let Ok(output) = schedule_route_header(
+                &multigraph,
+                source,
+                bundle,
+                header,
+                curr_time,
+                excluded_nodes,
+            ) {
+                return Some(output);
+            }
+        }
+        self.route(source, bundle, curr_time, excluded_nodes)
+    }
+}
+
+/// The liveness of a neighbor, see `Router::set_neighbor_state`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NeighborState {
+    /// The neighbor is reachable according to the contact plan.
+    Up,
+    /// The neighbor is unreachable (soft state, e.g. missed beacons).
+    Down,
+}
+
+/// Where the branches of a multicast tree split, i.e. where the bundle is duplicated.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum MulticastSplit {
+    /// The branches split as late as possible: a single copy goes through the hops shared by the
+    /// routes of several destinations, for fewer transmissions upstream.
+    #[default]
+    Late,
+    /// The branches split as early as possible: the source sends a copy per destination along
+    /// the route of the destination, for a lower buffer usage downstream (no duplication at the
+    /// intermediate nodes).
+    Early,
+}
+
+/// Applies a liveness state to a node of the multigraph, see `Router::set_neighbor_state`.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `node` - The ID of the node.
+/// * `state` - The new state of the node.
+/// * `until` - The end of the down state (ignored for `NeighborState::Up`).
+///
+/// # Returns
+///
+/// * `Option<Option<NodeID>>` - `None` if the node is unknown, otherwise the node whose routes
+///   are stale (see `TreeStorage::invalidate`), `None` for all the routes.
+pub fn update_neighbor_state<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    node: NodeID,
+    state: NeighborState,
+    until: Date,
+) -> Option<Option<NodeID>> {
+    let mut graph = multigraph.borrow_mut();
+    match state {
+        NeighborState::Down if graph.set_node_down(node, until) => Some(Some(node)),
+        NeighborState::Up if graph.set_node_up(node) => Some(None),
+        _ => None,
+    }
+}
+
+/// Rewrites the destinations of a bundle given as numeric aliases into the IDs of their nodes.
+///
+/// The routes computed for the rewritten bundle reach the node IDs, not the aliases.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph holding the aliases.
+/// * `bundle` - The bundle to route.
+///
+/// # Returns
+///
+/// * `Option<Bundle>` - The rewritten bundle, or `None` if no destination is an alias (the bundle
+///   can be routed as is).
+pub fn resolve_destinations<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    bundle: &Bundle,
+) -> Option<Bundle> {
+    let multigraph = multigraph.borrow();
+    let node_count = multigraph.get_node_count();
+    if bundle
+        .destinations
+        .iter()
+        .all(|dest| (*dest as usize) < node_count)
+    {
+        return None;
+    }
+    let mut resolved = bundle.clone();
+    for dest in &mut resolved.destinations {
+        if let Some(id) = multigraph.resolve_id(*dest) {
+            *dest = id;
+        }
+    }
+    Some(resolved)
+}
+
+/// Starts the span of a routing call (see `telemetry`), the parent of the spans of its
+/// pathfinding, storage and scheduling calls.
+///
+/// # Parameters
+///
+/// * `router` - The kind of router, e.g. "spsn".
+/// * `source` - The source node ID.
+/// * `bundle` - The bundle to route.
+/// * `curr_time` - The current time.
+///
+/// # Returns
+///
+/// * `Span` - The span, ended when dropped.
+pub(crate) fn route_span(
+    router: &'static str,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+) -> Span {
+    let span = Span::start("a_sabr.route");
+    span.text("router", router);
+    span.int("source", source.into());
+    if let Some(dest) = bundle.destinations.first() {
+        span.int("bundle.destination", (*dest).into());
+    }
+    span.int("bundle.destinations", bundle.destinations.len() as i64);
+    span.float("bundle.size", volume_to_f64(bundle.size));
+    span.int("bundle.priority", bundle.priority.into());
+    span.float("time", time_to_f64(curr_time));
+    span
+}
+
+/// The output of `Router::route_with_backup`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RoutingWithBackup<NM: NodeManager, CM: ContactManager> {
+    /// The scheduled routing output.
+    pub primary: RoutingOutput<NM, CM>,
+    /// The backup routing output (unicast only), with a different first hop contact, not scheduled.
+    pub backup: Option<RoutingOutput<NM, CM>>,
+}
+
+/// Computes the backup route of a unicast bundle, avoiding the first hop contact of the primary.
+///
+/// With the "contact_suppression" feature, the primary first hop contact is suppressed during the
+/// pathfinding. Otherwise, the primary first hop node is excluded (unless it is the destination),
+/// and the backup is discarded if it uses the same contact.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router (`Pathfinding::peek` is used).
+/// - `primary`: The routing output of the primary route.
+/// - `source`: The source node ID.
+/// - `bundle`: The unicast `Bundle`.
+/// - `curr_time`: The current time.
+/// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+///
+/// # Returns
+/// The backup routing output, validated with a dry run, or `None` if no backup exists.
+pub fn backup_unicast<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    primary: &RoutingOutput<NM, CM>,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+    excluded_nodes: &[NodeID],
+) -> Option<RoutingOutput<NM, CM>> {
+    if bundle.destinations.len() != 1 {
+        return None;
+    }
+    let dest = bundle.destinations[0];
+    let (primary_contact, _) = primary.lazy_get_for_unicast(dest)?;
+
+    #[cfg(feature = "contact_suppression")]
+    let exclusions = excluded_nodes.to_vec();
+    #[cfg(not(feature = "contact_suppression"))]
+    let exclusions = {
+        let mut exclusions = excluded_nodes.to_vec();
+        let first_hop_node = primary_contact.borrow().get_rx_node();
+        if first_hop_node != dest {
+            if let Err(pos) = exclusions.binary_search(&first_hop_node) {
+                exclusions.insert(pos, first_hop_node);
+            }
+        }
+        exclusions
+    };
+
+    #[cfg(feature = "contact_suppression")]
+    let was_suppressed = core::mem::replace(&mut primary_contact.borrow_mut().suppressed, true);
+    let tree = Rc::new(RefCell::new(pathfinding.peek(
+        curr_time,
+        source,
+        bundle,
+        &exclusions,
+    )));
+    #[cfg(feature = "contact_suppression")]
+    {
+        primary_contact.borrow_mut().suppressed = was_suppressed;
+    }
+
+    let route = dry_run_unicast_tree(bundle, curr_time, tree.clone(), true)?;
+    let first_hop = tree
+        .borrow()
+        .get_source_route()
+        .borrow()
+        .next_for_destination
+        .get(&dest)?
+        .borrow()
+        .get_via_contact()?;
+    if Rc::ptr_eq(&first_hop, &primary_contact) {
+        return None;
+    }
+
+    let mut hop_timings = Vec::new();
+    let mut curr_opt = Some(route.clone());
+    while let Some(curr_route) = curr_opt {
+        let stage = curr_route.borrow();
+        curr_opt = stage.via.as_ref().map(|via| {
+            hop_timings.extend(hop_timing(&stage, via.parent_route.borrow().at_time));
+            via.parent_route.clone()
+        });
+    }
+    hop_timings.reverse();
+
+    let mut first_hops = HashMap::new();
+    first_hops.insert(first_hop.as_ptr() as usize, (first_hop, vec![route]));
+    Some(RoutingOutput {
+        first_hops,
+        hop_timings,
+        uncovered: Vec::new(),
+    })
+}
+
+/// Routes a critical unicast bundle over all the feasible first hops (SABR critical bundles).
+///
+/// The best route is scheduled, then the neighbor of its first hop is discarded and the
+/// pathfinding is repeated until no feasible route remains. A neighbor is discarded by suppressing
+/// all the contacts from the source to it with the "contact_suppression" feature (restored before
+/// returning), by excluding it otherwise (except if it is the destination, which ends the
+/// computation).
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router.
+/// - `source`: The source node ID.
+/// - `bundle`: The critical unicast `Bundle`.
+/// - `curr_time`: The current time.
+/// - `excluded_nodes`: A sorted list of nodes to exclude from the routing paths.
+/// - `booking_horizon`: How far ahead of `curr_time` the transmissions are booked, without limit
+///   if `None`.
+///
+/// # Returns
+/// The routing output with one first hop per neighbor, `None` if no route was found.
+pub fn route_critical_unicast<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+    excluded_nodes: &[NodeID],
+    booking_horizon: Option<Duration>,
+) -> Option<RoutingOutput<NM, CM>> {
+    if bundle.destinations.len() != 1 || bundle.destinations[0] == source {
+        return None;
+    }
+    #[cfg(not(feature = "contact_suppression"))]
+    let (dest, mut exclusions) = (bundle.destinations[0], excluded_nodes.to_vec());
+    #[cfg(feature = "contact_suppression")]
+    let exclusions = excluded_nodes.to_vec();
+    #[cfg(feature = "contact_suppression")]
+    let mut suppressed: Vec<Rc<RefCell<Contact<NM, CM>>>> = Vec::new();
+    let mut first_hops = HashMap::new();
+    let mut hop_timings = Vec::new();
+
+    loop {
+        // the neighbors are discarded here, the state of the pathfinding (e.g. the suppressions of
+        // the alternative variants) is left untouched for the next bundles
+        let tree = Rc::new(RefCell::new(pathfinding.peek(
+            curr_time,
+            source,
+            bundle,
+            &exclusions,
+        )));
+        if dry_run_unicast_tree(bundle, curr_time, tree.clone(), true).is_none() {
+            break;
+        }
+        let output = schedule_unicast(bundle, curr_time, tree, false, booking_horizon);
+        hop_timings.extend(output.hop_timings);
+        let mut neighbor = None;
+        for (key, (contact, routes)) in output.first_hops {
+            neighbor = Some(contact.borrow().get_rx_node());
+            first_hops.insert(key, (contact, routes));
+        }
+        let Some(neighbor) = neighbor else {
+            break;
+        };
+
+        #[cfg(feature = "contact_suppression")]
+        {
+            let multigraph = pathfinding.get_multigraph();
+            let graph = multigraph.borrow();
+            let Some(sender) = graph.senders.get(source as usize) else {
+                break;
+            };
+            for receiver in &sender.receivers {
+                if receiver.node.borrow().info.id != neighbor {
+                    continue;
+                }
+                for contact in &receiver.contacts_to_receiver {
+                    if !contact.borrow().suppressed {
+                        contact.borrow_mut().suppressed = true;
+                        suppressed.push(contact.clone());
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "contact_suppression"))]
+        {
+            if neighbor == dest {
+                break;
+            }
+            if let Err(pos) = exclusions.binary_search(&neighbor) {
+                exclusions.insert(pos, neighbor);
+            }
+        }
+    }
+
+    #[cfg(feature = "contact_suppression")]
+    for contact in suppressed {
+        contact.borrow_mut().suppressed = false;
+    }
+
+    if first_hops.is_empty() {
+        return None;
+    }
+    Some(RoutingOutput {
+        first_hops,
+        hop_timings,
+        uncovered: Vec::new(),
+    })
+}
+
+/// A hash independent of the platform and of the build (FNV-1a with a final mix), for the values
+/// compared across nodes.
+pub(crate) struct StableHash(u64);
+
+impl StableHash {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn write_time(&mut self, time: Date) {
+        self.write(&time_to_f64(time).to_bits().to_le_bytes());
+    }
+
+    // the low bits of FNV-1a are weak, a modulo of the hash relies on them
+    pub(crate) fn finish(&self) -> u64 {
+        let mut hash = self.0;
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^ (hash >> 33)
+    }
+}
+
+/// The predicted quality of a route, see `Router::estimate`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RouteEstimate {
+    /// The destination node.
+    pub destination: NodeID,
+    /// The predicted delivery time at the destination.
+    pub delivery_time: Date,
+    /// The number of hops to reach the destination.
+    pub hop_count: HopCount,
+    /// For each hop, the volume that would remain on the contact after the bundle transmission,
+    /// `None` if the contact manager does not track volumes.
+    pub volume_margins: Vec<Option<Volume>>,
+    /// The probability that all the contacts of the route occur, as the product of the contacts
+    /// confidences.
+    pub confidence: f32,
+    /// A stable hash of the route (its contacts and the delivery time), see `Router::route_digest`.
+    pub digest: u64,
+}
+
+impl RouteEstimate {
+    /// Returns the lowest volume margin of the route, `None` if no hop tracks volumes.
+    pub fn min_volume_margin(&self) -> Option<Volume> {
+        self.volume_margins
+            .iter()
+            .flatten()
+            .copied()
+            .reduce(Volume::min)
+    }
+}
+
+/// The predicted round trip between two nodes, see `Router::estimate_rtt`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RttEstimate {
+    /// The estimation of the bundle route.
+    pub forward: RouteEstimate,
+    /// The departure time of the reply, after the processing of the bundle.
+    pub turnaround: Date,
+    /// The estimation of the reply route.
+    pub reverse: RouteEstimate,
+    /// The duration from the departure of the bundle to the delivery of the reply.
+    pub rtt: Duration,
+}
+
+/// Estimates the route of a unicast bundle with `Pathfinding::peek` and a dry run of the route.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router.
+/// - `source`: The source node ID.
+/// - `bundle`: The `Bundle` to estimate.
+/// - `curr_time`: The current time.
+/// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+///
+/// # Returns
+/// A `RouteEstimate` if a feasible route exists, `None` otherwise or for multicast bundles.
+pub fn estimate_unicast<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+    excluded_nodes: &Vec<NodeID>,
+) -> Option<RouteEstimate> {
+    if bundle.expiration < curr_time || bundle.destinations.len() != 1 {
+        return None;
+    }
+    let dest = bundle.destinations[0];
+    if dest == source {
+        return None;
+    }
+
+    let tree = pathfinding.peek(curr_time, source, bundle, excluded_nodes);
+    let mut path: Vec<Rc<RefCell<RouteStage<NM, CM>>>> = Vec::new();
+    // an unknown destination has no route
+    let mut curr_opt = tree.by_destination.get(dest as usize).cloned().flatten();
+    while let Some(curr_route) = curr_opt {
+        curr_opt = curr_route
+            .borrow()
+            .via
+            .as_ref()
+            .map(|via| via.parent_route.clone());
+        if curr_opt.is_some() {
+            path.push(curr_route);
+        }
+    }
+    if path.is_empty() {
+        return None;
+    }
+    path.reverse();
+
+    let mut at_time = curr_time;
+    let mut volume_margins = Vec::with_capacity(path.len());
+    let mut confidence = 1.0;
+    let mut digest = StableHash::new();
+    #[cfg(not(feature = "node_proc"))]
+    let bundle_to_consider = bundle;
+    for stage in &path {
+        let mut stage_borrowed = stage.borrow_mut();
+
+        #[cfg(feature = "node_proc")]
+        let bundle_to_consider = stage_borrowed.bundle.clone();
+
+        if !stage_borrowed.dry_run(at_time, &bundle_to_consider, true) {
+            return None;
+        }
+        at_time = stage_borrowed.at_time;
+
+        let contact = stage_borrowed.get_via_contact()?;
+        let contact_borrowed = contact.borrow();
+        volume_margins.push(
+            contact_borrowed
+                .manager
+                .get_residual_volume(&contact_borrowed.info, bundle)
+                .map(|volume| volume - bundle.size),
+        );
+        confidence *= contact_borrowed.manager.get_confidence();
+
+        let info = &contact_borrowed.info;
+        digest.write(&info.tx_node.to_le_bytes());
+        digest.write(&info.rx_node.to_le_bytes());
+        digest.write_time(info.start);
+        digest.write_time(info.end);
+    }
+    digest.write_time(at_time);
+
+    Some(RouteEstimate {
+        destination: dest,
+        delivery_time: at_time,
+        hop_count: path.len() as HopCount,
+        volume_margins,
+        confidence,
+        digest: digest.finish(),
+    })
+}
+
+/// Recomputes the routes of the destinations whose refresh was requested by the route storage
+/// (see `RouteStorage::take_refresh_requests`), e.g. after the aging of their routes.
+///
+/// The state of the pathfinding for the destination is reset (see
+/// `Pathfinding::reset_destination`), then the routes are computed with `Pathfinding::get_next`,
+/// from the best one. The routes are computed without constraints (no size, lowest priority, no
+/// exclusion) and stored.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router.
+/// - `route_storage`: The route storage of the router.
+/// - `source`: The source node ID.
+/// - `curr_time`: The current time.
+///
+/// # Returns
+/// The count of routes stored.
+pub fn refresh_routes<
+    NM: NodeManager,
+    CM: ContactManager,
+    P: Pathfinding<NM, CM>,
+    S: RouteStorage<NM, CM>,
+>(
+    pathfinding: &mut P,
+    route_storage: &Rc<RefCell<S>>,
+    source: NodeID,
+    curr_time: Date,
+) -> usize {
+    let requests = route_storage.borrow_mut().take_refresh_requests(curr_time);
+    let no_exclusions = Vec::new();
+    let mut stored = 0;
+
+    for (dest, count) in requests {
+        if dest == source {
+            continue;
+        }
+        let template = Bundle {
+            source,
+            destinations: vec![dest],
+            priority: 1,
+            size: VOLUME_ZERO,
+            expiration: Date::MAX,
+            soft_deadline: None,
+            critical: false,
+            destination_priorities: Vec::new(),
+        };
+        // the alternatives are enumerated from the best route, regardless of the earlier routings
+        pathfinding.reset_destination(dest);
+        for _ in 0..count {
+            let tree = pathfinding.get_next(curr_time, source, &template, &no_exclusions);
+            let Some(route) = Route::from_tree(Rc::new(RefCell::new(tree)), dest) else {
+                break;
+            };
+            RouteStage::init_route(route.destination_stage.clone());
+            route_storage.borrow_mut().store(&template, route);
+            stored += 1;
+        }
+    }
+    stored
 }
 
 /// A struct that represents the output of a routing operation.
@@ -54,6 +1043,8 @@ pub trait Router<NM: NodeManager, CM: ContactManager> {
 ///       that represents the first hop for the respective route.
 ///     - `Vec<NodeID>`: A vector of `NodeID`s representing the nodes that can be reached from
 ///       the first hop.
+/// * `hop_timings` - The timing of each hop of the routes, see `HopTiming`.
+/// * `uncovered` - The destinations of a multicast bundle not reached, see `UncoveredDestination`.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct RoutingOutput<NM: NodeManager, CM: ContactManager> {
     pub first_hops: HashMap<
@@ -63,6 +1054,15 @@ pub struct RoutingOutput<NM: NodeManager, CM: ContactManager> {
             Vec<Rc<RefCell<RouteStage<NM, CM>>>>,
         ),
     >,
+    /// The hops in the order of their scheduling (from the source along each route, a hop shared
+    /// by several destinations of a multicast bundle being listed once). Only the booked hops are
+    /// listed, e.g. the first hop only if the router schedules the first hop only, or the hops
+    /// within the booking horizon of the router (see `Spsn::with_booking_horizon`).
+    pub hop_timings: Vec<HopTiming>,
+    /// The destinations of a multicast bundle not reached by the routes, with the reason, for the
+    /// caller to arrange another delivery (e.g. a later retry, or another source). Empty for the
+    /// unicast bundles, no output being returned if the destination is not reached.
+    pub uncovered: Vec<UncoveredDestination>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> RoutingOutput<NM, CM> {
@@ -82,6 +1082,183 @@ impl<NM: NodeManager, CM: ContactManager> RoutingOutput<NM, CM> {
         }
         None
     }
+
+    /// Builds an owned summary of the output, with no reference to the routing structures.
+    ///
+    /// # Returns
+    /// A `RoutingSummary` that can be moved across threads or serialized.
+    pub fn summarize(&self) -> RoutingSummary {
+        let mut first_hops = Vec::with_capacity(self.first_hops.len());
+        for (contact, dest_routes) in self.first_hops.values() {
+            let info = contact.borrow().info;
+            let routes = dest_routes
+                .iter()
+                .map(|route_rc| {
+                    let route = route_rc.borrow();
+                    RouteSummary {
+                        to_node: route.to_node,
+                        at_time: route.at_time,
+                        hop_count: route.hop_count,
+                    }
+                })
+                .collect();
+            first_hops.push(FirstHopSummary {
+                tx_node: info.tx_node,
+                rx_node: info.rx_node,
+                start: info.start,
+                end: info.end,
+                routes,
+            });
+        }
+        RoutingSummary { first_hops }
+    }
+}
+
+/// An owned view of a `RoutingOutput`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RoutingSummary {
+    /// One entry per first hop contact.
+    pub first_hops: Vec<FirstHopSummary>,
+}
+
+/// The first hop contact of a `RoutingSummary` and the routes using it.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct FirstHopSummary {
+    /// The transmitting node of the first hop contact.
+    pub tx_node: NodeID,
+    /// The receiving node of the first hop contact.
+    pub rx_node: NodeID,
+    /// The start time of the first hop contact.
+    pub start: Date,
+    /// The end time of the first hop contact.
+    pub end: Date,
+    /// The routes (one per reached destination) going through this contact.
+    pub routes: Vec<RouteSummary>,
+}
+
+/// A route of a `RoutingSummary`, toward a single destination.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RouteSummary {
+    /// The destination node.
+    pub to_node: NodeID,
+    /// The expected arrival time at the destination.
+    pub at_time: Date,
+    /// The number of hops to reach the destination.
+    pub hop_count: HopCount,
+}
+
+/// The reason why a destination of a multicast bundle is not reached by a routing output.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum UncoveredReason {
+    /// The pathfinding found no route toward the destination.
+    NoRoute,
+    /// A hop of the route toward the destination cannot be dry run or booked for the bundle.
+    HopRefused(HopFailure),
+}
+
+/// A destination of a multicast bundle not reached by a routing output, see
+/// `RoutingOutput::uncovered`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct UncoveredDestination {
+    /// The destination.
+    pub destination: NodeID,
+    /// Why the destination is not reached.
+    pub reason: UncoveredReason,
+}
+
+/// The timing of a hop of a routing output, derived when the hop is scheduled (or dry run).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub struct HopTiming {
+    /// The contact of the hop.
+    pub contact: ContactID,
+    /// The transmitting node of the hop.
+    pub tx_node: NodeID,
+    /// The receiving node of the hop.
+    pub rx_node: NodeID,
+    /// The time the bundle is at the transmitting node (its arrival, or the routing time at the
+    /// source).
+    pub ready: Date,
+    /// The time the bundle waits at the transmitting node before its transmission (processing and
+    /// queueing included).
+    pub wait: Duration,
+    /// The start of the transmission.
+    pub tx_start: Date,
+    /// The end of the transmission.
+    pub tx_end: Date,
+    /// The propagation delay of the last bit.
+    pub delay: Duration,
+    /// The arrival time of the last bit at the receiving node.
+    pub arrival: Date,
+}
+
+/// Derives the timing of the hop reaching a stage, from its transmission.
+///
+/// # Parameters
+///
+/// * `stage` - The stage, scheduled or dry run.
+/// * `ready` - The time the bundle is at the transmitting node.
+///
+/// # Returns
+///
+/// * `Option<HopTiming>` - The timing, `None` for a source stage.
+pub(crate) fn hop_timing<NM: NodeManager, CM: ContactManager>(
+    stage: &RouteStage<NM, CM>,
+    ready: Date,
+) -> Option<HopTiming> {
+    let tx_data = stage.tx_data?;
+    let contact = stage.get_via_contact()?;
+    let contact = contact.borrow();
+    Some(HopTiming {
+        contact: contact.id,
+        tx_node: contact.info.tx_node,
+        rx_node: contact.info.rx_node,
+        ready,
+        wait: tx_data.tx_start - ready,
+        tx_start: tx_data.tx_start,
+        tx_end: tx_data.tx_end,
+        delay: tx_data.delay,
+        arrival: tx_data.arrival,
+    })
+}
+
+/// Returns the bundle to consider for a stage of a multicast tree, with the priority inherited
+/// from the destinations served downstream (see `Bundle::destination_priorities`).
+///
+/// # Parameters
+///
+/// * `bundle` - The multicast bundle.
+/// * `stage` - The stage, its bundle is the processed one with the "node_proc" feature.
+/// * `downstream_dests` - The destinations served through the stage.
+///
+/// # Returns
+///
+/// * `Cow<'a, Bundle>` - The bundle to schedule the stage with.
+#[cfg(not(feature = "node_proc"))]
+fn branch_bundle<'a, NM: NodeManager, CM: ContactManager>(
+    bundle: &'a Bundle,
+    _stage: &RouteStage<NM, CM>,
+    downstream_dests: &[NodeID],
+) -> Cow<'a, Bundle> {
+    bundle.for_destinations(downstream_dests)
+}
+
+#[cfg(feature = "node_proc")]
+fn branch_bundle<'a, NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    stage: &RouteStage<NM, CM>,
+    downstream_dests: &[NodeID],
+) -> Cow<'a, Bundle> {
+    let mut stage_bundle = stage.bundle.clone();
+    if !bundle.destination_priorities.is_empty() {
+        stage_bundle.priority = bundle.inherited_priority(downstream_dests);
+    }
+    Cow::Owned(stage_bundle)
 }
 
 pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
@@ -89,29 +1266,78 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
     at_time: Date,
     tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
 ) -> Vec<NodeID> {
+    dry_run_multicast_tree(bundle, at_time, tree).0
+}
+
+/// Dry runs a multicast tree like `dry_run_multicast`, and tells why the other destinations are
+/// not reachable.
+///
+/// # Parameters
+///
+/// * `bundle` - The multicast bundle.
+/// * `at_time` - The current time.
+/// * `tree` - The pathfinding output.
+///
+/// # Returns
+///
+/// * `(Vec<NodeID>, Vec<UncoveredDestination>)` - The destinations still reachable, and the
+///   others.
+fn dry_run_multicast_tree<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    at_time: Date,
+    tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
+) -> (Vec<NodeID>, Vec<UncoveredDestination>) {
     let tree_ref = tree.borrow();
     let mut dests_in_tree = Vec::new();
-    let mut reached_destinations = Vec::new();
+    let mut uncovered = Vec::new();
     for dest in &bundle.destinations {
         if let Some(_route_for_dest) = &tree_ref.by_destination[*dest as usize] {
             tree_ref.init_for_destination(*dest);
             dests_in_tree.push(*dest);
+        } else {
+            uncovered.push(UncoveredDestination {
+                destination: *dest,
+                reason: UncoveredReason::NoRoute,
+            });
         }
     }
+    let (reached, mut refused) =
+        dry_run_multicast_from(bundle, at_time, tree_ref.get_source_route(), dests_in_tree);
+    uncovered.append(&mut refused);
+    (reached, uncovered)
+}
 
-    let source_route = tree_ref.get_source_route();
+/// Dry runs the branches of a multicast tree from its source stage, see `dry_run_multicast`.
+///
+/// # Parameters
+///
+/// * `bundle` - The multicast bundle.
+/// * `at_time` - The current time.
+/// * `source_route` - The source stage, with the routes of the destinations initialized.
+/// * `dests_in_tree` - The destinations reached by the tree.
+///
+/// # Returns
+///
+/// * `(Vec<NodeID>, Vec<UncoveredDestination>)` - The destinations still reachable, and the
+///   destinations of the branches refused by the dry run.
+fn dry_run_multicast_from<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    at_time: Date,
+    source_route: Rc<RefCell<RouteStage<NM, CM>>>,
+    dests_in_tree: Vec<NodeID>,
+) -> (Vec<NodeID>, Vec<UncoveredDestination>) {
+    let mut reached_destinations = Vec::new();
+    let mut uncovered = Vec::new();
     let mut accumulator = vec![(source_route, true, at_time, dests_in_tree)];
-    #[cfg(not(feature = "node_proc"))]
-    let bundle_to_consider = bundle;
 
     while let Some((current_route, is_source, mut time, downstream_dests)) = accumulator.pop() {
         let mut route_borrowed = current_route.borrow_mut();
 
-        #[cfg(feature = "node_proc")]
-        let bundle_to_consider = route_borrowed.bundle.clone();
+        let bundle_to_consider = branch_bundle(bundle, &route_borrowed, &downstream_dests);
 
         if !is_source {
-            if !route_borrowed.dry_run(time, &bundle_to_consider, false) {
+            if let Err(failure) = route_borrowed.try_dry_run(time, &bundle_to_consider, false) {
+                refuse_branch(&mut uncovered, &downstream_dests, failure);
                 continue;
             }
             time = route_borrowed.at_time;
@@ -131,6 +1357,12 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
                 } else {
                     next_routes.insert(ptr, (next_route.clone(), vec![dest]));
                 }
+            } else {
+                refuse_branch(
+                    &mut uncovered,
+                    &[dest],
+                    HopFailure::MissingHop { node: reached_node },
+                );
             }
         }
         for (_ptr, (next_route, next_downstream_dests)) in next_routes {
@@ -138,15 +1370,53 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
         }
     }
 
-    return reached_destinations;
+    return (reached_destinations, uncovered);
+}
+
+/// Reports the destinations served through a hop refused by a dry run or a booking.
+fn refuse_branch(
+    uncovered: &mut Vec<UncoveredDestination>,
+    downstream_dests: &[NodeID],
+    failure: HopFailure,
+) {
+    uncovered.extend(
+        downstream_dests
+            .iter()
+            .map(|destination| UncoveredDestination {
+                destination: *destination,
+                reason: UncoveredReason::HopRefused(failure),
+            }),
+    );
+}
+
+/// Tells whether the transmission of a stage starts after the end of the booking horizon, the
+/// stage being left unbooked (see `Spsn::with_booking_horizon`).
+///
+/// # Parameters
+///
+/// * `stage` - The stage, its transmission being the one of its last dry run.
+/// * `booking_end` - The end of the booking horizon, `None` if the bookings are not limited.
+fn beyond_booking_horizon<NM: NodeManager, CM: ContactManager>(
+    stage: &RouteStage<NM, CM>,
+    booking_end: Option<Date>,
+) -> bool {
+    match (booking_end, &stage.tx_data) {
+        (Some(end), Some(tx_data)) => tx_data.tx_start > end,
+        _ => false,
+    }
 }
 
 fn update_multicast<NM: NodeManager, CM: ContactManager>(
-    _bundle: &Bundle,
+    bundle: &Bundle,
     at_time: Date,
     reachable_after_dry_run: Vec<NodeID>,
+    mut uncovered: Vec<UncoveredDestination>,
     source_route: Rc<RefCell<RouteStage<NM, CM>>>,
+    booking_horizon: Option<Duration>,
 ) -> RoutingOutput<NM, CM> {
+    let span = Span::start("a_sabr.schedule");
+    span.int("destinations", reachable_after_dry_run.len() as i64);
+    let booking_end = booking_horizon.map(|horizon| at_time + horizon);
     let mut first_hops_map: HashMap<
         usize,
         (
@@ -154,26 +1424,27 @@ fn update_multicast<NM: NodeManager, CM: ContactManager>(
             Vec<Rc<RefCell<RouteStage<NM, CM>>>>,
         ),
     > = HashMap::new();
+    let mut hop_timings = Vec::new();
     let mut accumulator: Vec<(
         Rc<RefCell<RouteStage<NM, CM>>>,
         Option<usize>,
         Date,
         Vec<u16>,
     )> = vec![(source_route, None, at_time, reachable_after_dry_run)];
-    #[cfg(not(feature = "node_proc"))]
-    let bundle_to_consider = _bundle;
 
-    while let Some((current_route, mut first_hop_ptr, mut time, downstream_dests)) =
-        accumulator.pop()
-    {
+    while let Some((current_route, first_hop_ptr, mut time, downstream_dests)) = accumulator.pop() {
         let mut route_borrowed = current_route.borrow_mut();
 
-        #[cfg(feature = "node_proc")]
-        let bundle_to_consider = route_borrowed.bundle.clone();
+        let bundle_to_consider = branch_bundle(bundle, &route_borrowed, &downstream_dests);
 
+        // the hops beyond the booking horizon keep their dry run, the next ones of the branch too
         if !first_hop_ptr.is_none() {
-            if !route_borrowed.schedule(time, &bundle_to_consider) {
-                continue;
+            if !beyond_booking_horizon(&route_borrowed, booking_end) {
+                if let Err(failure) = route_borrowed.try_schedule(time, &bundle_to_consider) {
+                    refuse_branch(&mut uncovered, &downstream_dests, failure);
+                    continue;
+                }
+                hop_timings.extend(hop_timing(&route_borrowed, time));
             }
             time = route_borrowed.at_time;
         }
@@ -198,21 +1469,32 @@ fn update_multicast<NM: NodeManager, CM: ContactManager>(
             }
         }
         for (_ptr, (next_route, next_downstream_dests)) in next_routes {
-            if first_hop_ptr.is_none() {
+            // each branch leaving the source has its own first hop
+            let mut branch_first_hop_ptr = first_hop_ptr;
+            if branch_first_hop_ptr.is_none() {
                 let first_hop_contact = next_route.borrow().get_via_contact();
                 if let Some(first_hop_contact) = first_hop_contact {
                     let ptr = first_hop_contact.as_ptr() as usize;
-                    first_hop_ptr = Some(ptr);
+                    branch_first_hop_ptr = Some(ptr);
                     if first_hops_map.get(&ptr).is_none() {
                         first_hops_map.insert(ptr, (first_hop_contact, Vec::new()));
                     }
                 }
             }
-            accumulator.push((next_route, first_hop_ptr, time, next_downstream_dests));
+            accumulator.push((
+                next_route,
+                branch_first_hop_ptr,
+                time,
+                next_downstream_dests,
+            ));
         }
     }
+    span.int("hops", hop_timings.len() as i64);
+    span.int("uncovered", uncovered.len() as i64);
     return RoutingOutput {
         first_hops: first_hops_map,
+        hop_timings,
+        uncovered,
     };
 }
 
@@ -223,12 +1505,14 @@ fn update_multicast<NM: NodeManager, CM: ContactManager>(
 ///
 /// # Parameters
 ///
-/// * `source` - The ID of the source node initiating the route.
 /// * `bundle` - The current bundle containing routing information.
 /// * `curr_time` - The current date/time for the routing operation.
-/// * `tree_ref` - A reference to the pathfinding output.
-/// * `dry_run_to_fill_targets` - Set this boolean to true if the tree is fresh (i.e. the dry run
-/// from selection did not occur).
+/// * `tree` - The pathfinding output.
+/// * `targets_opt` - The destinations reachable according to the dry run of the selection, the
+///   dry run is performed if `None` (fresh tree).
+/// * `split` - Where the branches of the tree split.
+/// * `booking_horizon` - How far ahead of `curr_time` the transmissions are booked, without
+///   limit if `None`.
 ///
 /// # Returns
 ///
@@ -238,50 +1522,154 @@ fn schedule_multicast<NM: NodeManager, CM: ContactManager>(
     curr_time: Date,
     tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
     targets_opt: Option<Vec<NodeID>>,
+    split: MulticastSplit,
+    booking_horizon: Option<Duration>,
 ) -> RoutingOutput<NM, CM> {
-    let targets = match targets_opt {
-        Some(targets) => targets,
-        None => dry_run_multicast(bundle, curr_time, tree.clone()),
+    if split == MulticastSplit::Early {
+        // the copies book the shared hops once per destination, the dry run must be redone
+        let (source_route, dests_in_tree) = split_early(&tree.borrow(), &bundle.destinations);
+        let mut uncovered: Vec<UncoveredDestination> = bundle
+            .destinations
+            .iter()
+            .filter(|dest| !dests_in_tree.contains(dest))
+            .map(|dest| UncoveredDestination {
+                destination: *dest,
+                reason: UncoveredReason::NoRoute,
+            })
+            .collect();
+        let (targets, mut refused) =
+            dry_run_multicast_from(bundle, curr_time, source_route.clone(), dests_in_tree);
+        uncovered.append(&mut refused);
+        return update_multicast(
+            bundle,
+            curr_time,
+            targets,
+            uncovered,
+            source_route,
+            booking_horizon,
+        );
+    }
+    // the targets of a selection reach all the destinations
+    let (targets, uncovered) = match targets_opt {
+        Some(targets) => (targets, Vec::new()),
+        None => dry_run_multicast_tree(bundle, curr_time, tree.clone()),
     };
     let source_route = tree.borrow().get_source_route();
-    return update_multicast(bundle, curr_time, targets, source_route.clone());
+    return update_multicast(
+        bundle,
+        curr_time,
+        targets,
+        uncovered,
+        source_route.clone(),
+        booking_horizon,
+    );
+}
+
+/// Transforms a multicast tree for `MulticastSplit::Early`: the route of each destination is
+/// copied from the source, so that no stage is shared by several destinations.
+///
+/// The stages of the tree are left untouched (the tree may be stored), the copies are attached to
+/// a copy of the source stage.
+///
+/// # Parameters
+///
+/// * `tree` - The pathfinding output.
+/// * `destinations` - The destinations of the bundle.
+///
+/// # Returns
+///
+/// * `(Rc<RefCell<RouteStage<NM, CM>>>, Vec<NodeID>)` - The copy of the source stage, and the
+///   destinations reached by the tree.
+fn split_early<NM: NodeManager, CM: ContactManager>(
+    tree: &PathFindingOutput<NM, CM>,
+    destinations: &[NodeID],
+) -> (Rc<RefCell<RouteStage<NM, CM>>>, Vec<NodeID>) {
+    let source_route = Rc::new(RefCell::new(tree.source.borrow().clone()));
+    let mut dests_in_tree = Vec::new();
+    for dest in destinations {
+        let Some(dest_route) = &tree.by_destination[*dest as usize] else {
+            continue;
+        };
+        dests_in_tree.push(*dest);
+
+        let mut stages = Vec::new();
+        let mut curr = dest_route.clone();
+        loop {
+            let parent = match &curr.borrow().via {
+                Some(via) => via.parent_route.clone(),
+                None => break,
+            };
+            stages.push(curr);
+            curr = parent;
+        }
+
+        let mut parent = source_route.clone();
+        for stage in stages.iter().rev() {
+            let mut copy = stage.borrow().clone();
+            if let Some(via) = &mut copy.via {
+                via.parent_route = parent.clone();
+            }
+            let copy = Rc::new(RefCell::new(copy));
+            parent
+                .borrow_mut()
+                .next_for_destination
+                .insert(*dest, copy.clone());
+            parent = copy;
+        }
+        parent.borrow_mut().route_initialized = true;
+    }
+    (source_route, dests_in_tree)
 }
 
 pub fn dry_run_unicast_path<NM: NodeManager, CM: ContactManager>(
     bundle: &Bundle,
-    mut at_time: Date,
+    at_time: Date,
     source_route: Rc<RefCell<RouteStage<NM, CM>>>,
     with_exclusions: bool,
 ) -> Option<Rc<RefCell<RouteStage<NM, CM>>>> {
+    try_dry_run_unicast_path(bundle, at_time, source_route, with_exclusions).ok()
+}
+
+/// Dry runs a unicast route like `dry_run_unicast_path`, and tells why it failed.
+///
+/// # Parameters
+/// - `bundle`: The unicast `Bundle`.
+/// - `at_time`: The starting time of the dry run.
+/// - `source_route`: The source stage of the route.
+/// - `with_exclusions`: Whether the excluded nodes make the route fail.
+///
+/// # Returns
+/// The destination stage of the route, or the failure of the first hop failing.
+pub fn try_dry_run_unicast_path<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    mut at_time: Date,
+    source_route: Rc<RefCell<RouteStage<NM, CM>>>,
+    with_exclusions: bool,
+) -> Result<Rc<RefCell<RouteStage<NM, CM>>>, HopFailure> {
     let dest = bundle.destinations[0];
 
-    let mut curr_opt = source_route
-        .borrow()
-        .next_for_destination
-        .get(&dest)
-        .cloned();
+    let mut curr_node = source_route.borrow().to_node;
+    let successors = source_route.borrow().successors(dest);
     #[cfg(not(feature = "node_proc"))]
     let bundle_to_consider = bundle;
-    while let Some(curr_route) = curr_opt {
+    for curr_route in successors {
         let mut curr_route_borrowed = curr_route.borrow_mut();
 
         #[cfg(feature = "node_proc")]
         let bundle_to_consider = curr_route_borrowed.bundle.clone();
 
-        if !curr_route_borrowed.dry_run(at_time, &bundle_to_consider, with_exclusions) {
-            return None;
-        }
+        curr_route_borrowed.try_dry_run(at_time, &bundle_to_consider, with_exclusions)?;
 
         at_time = curr_route_borrowed.at_time;
 
         if curr_route_borrowed.to_node == dest {
-            return Some(curr_route.clone());
+            return Ok(curr_route.clone());
         }
 
-        curr_opt = curr_route_borrowed.next_for_destination.get(&dest).cloned();
+        curr_node = curr_route_borrowed.to_node;
     }
 
-    None
+    Err(HopFailure::MissingHop { node: curr_node })
 }
 
 // ($fn_name:ident, $apply_exclusions:ident, $try_init:ident)
@@ -325,6 +1713,158 @@ pub fn dry_run_unicast_tree<NM: NodeManager, CM: ContactManager>(
     None
 }
 
+/// Schedules a unicast bundle along the reverse of a route, see `Router::route_reverse`.
+///
+/// Each hop of the reverse path takes the earliest contact (by start time) from the next node to
+/// the previous node of the route able to carry the bundle, the whole path is dry run before the
+/// scheduling.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `original_route` - The route stage of the original bundle at its destination.
+/// * `bundle` - The reply, its destination being the source of the original route.
+/// * `curr_time` - The current time.
+///
+/// # Returns
+///
+/// * `Option<RoutingOutput<NM, CM>>` - The routing output, or `None` if the bundle is not a
+///   unicast bundle toward the source of the route or if a hop of the reverse path cannot carry
+///   it.
+pub fn reverse_unicast<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    original_route: &Rc<RefCell<RouteStage<NM, CM>>>,
+    bundle: &Bundle,
+    curr_time: Date,
+) -> Option<RoutingOutput<NM, CM>> {
+    // the nodes of the original route, from its destination to its source
+    let mut nodes = Vec::new();
+    let mut curr = original_route.clone();
+    loop {
+        let parent = {
+            let stage = curr.borrow();
+            nodes.push(stage.to_node);
+            match &stage.via {
+                Some(via) => via.parent_route.clone(),
+                None => break,
+            }
+        };
+        curr = parent;
+    }
+    let (&reply_source, &reply_dest) = (nodes.first()?, nodes.last()?);
+    if nodes.len() < 2 || bundle.destinations.len() != 1 || bundle.destinations[0] != reply_dest {
+        return None;
+    }
+
+    let graph = multigraph.borrow();
+    let source_route = Rc::new(RefCell::new(RouteStage::new(
+        curr_time,
+        reply_source,
+        None,
+        #[cfg(feature = "node_proc")]
+        bundle.clone(),
+    )));
+    let mut parent = source_route.clone();
+    for hop in nodes.windows(2) {
+        let (tx_node, rx_node) = (hop[0], hop[1]);
+        let receiver = graph.senders[tx_node as usize]
+            .receivers
+            .iter()
+            .find(|receiver| receiver.node.borrow().info.id == rx_node)?;
+        let at_time = parent.borrow().at_time;
+        let first_idx = receiver.first_idx_at(at_time)?;
+        parent = receiver.contacts_to_receiver[first_idx..]
+            .iter()
+            .filter(|contact| {
+                #[cfg(feature = "contact_suppression")]
+                if contact.borrow().suppressed {
+                    return false;
+                }
+                contact.borrow().info.end > at_time
+            })
+            .find_map(|contact| {
+                try_extend_route(
+                    &parent,
+                    contact,
+                    &graph.nodes[tx_node as usize],
+                    &receiver.node,
+                    bundle,
+                )
+                .ok()
+            })?;
+    }
+    drop(graph);
+
+    RouteStage::init_route(parent);
+    Some(update_unicast(
+        bundle,
+        reply_dest,
+        curr_time,
+        source_route,
+        None,
+    ))
+}
+
+/// Extends a route with the hop over a contact, the hop being dry run at the arrival time of the
+/// route (without exclusions).
+///
+/// # Parameters
+///
+/// * `parent` - The last stage of the route.
+/// * `contact` - The contact of the hop.
+/// * `tx_node` - The transmitting node, reached by `parent`.
+/// * `rx_node` - The receiving node.
+/// * `bundle` - The bundle, as submitted at the source (processed along the route with the
+///   "node_proc" feature).
+///
+/// # Returns
+///
+/// * `Result<Rc<RefCell<RouteStage<NM, CM>>>, HopFailure>` - The stage of the hop, or the reason
+///   why the contact cannot carry the bundle.
+pub(crate) fn try_extend_route<NM: NodeManager, CM: ContactManager>(
+    parent: &Rc<RefCell<RouteStage<NM, CM>>>,
+    contact: &Rc<RefCell<Contact<NM, CM>>>,
+    tx_node: &Rc<RefCell<Node<NM>>>,
+    rx_node: &Rc<RefCell<Node<NM>>>,
+    bundle: &Bundle,
+) -> Result<Rc<RefCell<RouteStage<NM, CM>>>, HopFailure> {
+    let at_time = parent.borrow().at_time;
+    // the bundle as processed by the previous nodes
+    #[cfg(feature = "node_proc")]
+    let hop_bundle = &parent.borrow().bundle.clone();
+    #[cfg(not(feature = "node_proc"))]
+    let hop_bundle = bundle;
+    #[cfg(feature = "node_proc")]
+    let _ = bundle;
+
+    let rx_node_id = rx_node.borrow().info.id;
+    let mut stage = RouteStage::new(
+        at_time,
+        rx_node_id,
+        Some(ViaHop {
+            contact: contact.clone(),
+            parent_route: parent.clone(),
+            tx_node: tx_node.clone(),
+            rx_node: rx_node.clone(),
+        }),
+        #[cfg(feature = "node_proc")]
+        hop_bundle.clone(),
+    );
+    stage.try_dry_run(at_time, hop_bundle, false)?;
+
+    {
+        let parent_borrowed = parent.borrow();
+        let tx_data = stage.tx_data.ok_or(HopFailure::NoHop)?;
+        stage.hop_count = parent_borrowed.hop_count + 1;
+        stage.cumulative_delay = parent_borrowed.cumulative_delay + tx_data.delay;
+        stage.expiration = Date::min(
+            tx_data.expiration - parent_borrowed.cumulative_delay,
+            parent_borrowed.expiration,
+        );
+    }
+    Ok(Rc::new(RefCell::new(stage)))
+}
+
 /// Iteratively updates routes based on scheduled contacts.
 ///
 /// # Parameters
@@ -333,26 +1873,29 @@ pub fn dry_run_unicast_tree<NM: NodeManager, CM: ContactManager>(
 /// * `dest` - The destination for the bundle.
 /// * `at_time` - The current date/time for the routing operation.
 /// * `source_route` - The source route.
+/// * `booking_horizon` - How far ahead of `at_time` the transmissions are booked, without limit
+///   if `None`: the next hops keep their dry run and are not listed in the hop timings.
 fn update_unicast<NM: NodeManager, CM: ContactManager>(
     _bundle: &Bundle,
     dest: NodeID,
     mut at_time: Date,
     source_route: Rc<RefCell<RouteStage<NM, CM>>>,
+    booking_horizon: Option<Duration>,
 ) -> RoutingOutput<NM, CM> {
     if source_route.borrow().to_node == dest {
         panic!("Bundle's destination is equal to source");
     }
+    let span = Span::start("a_sabr.schedule");
+    span.int("destination", dest.into());
 
-    let mut curr_opt = source_route
-        .borrow()
-        .next_for_destination
-        .get(&dest)
-        .cloned();
+    let successors = source_route.borrow().successors(dest);
 
     let mut first_hop: Option<Rc<RefCell<Contact<NM, CM>>>> = None;
+    let mut hop_timings = Vec::new();
+    let booking_end = booking_horizon.map(|horizon| at_time + horizon);
     #[cfg(not(feature = "node_proc"))]
     let bundle_to_consider = _bundle;
-    while let Some(curr_route) = curr_opt {
+    for curr_route in successors {
         let mut curr_route_borrowed = curr_route.borrow_mut();
 
         if first_hop.is_none() {
@@ -362,8 +1905,12 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
         #[cfg(feature = "node_proc")]
         let bundle_to_consider = curr_route_borrowed.bundle.clone();
 
-        if !curr_route_borrowed.schedule(at_time, &bundle_to_consider) {
-            panic!("Faulty dry run, didn't allow a clean update!");
+        // the hops beyond the booking horizon keep their dry run
+        if !beyond_booking_horizon(&curr_route_borrowed, booking_end) {
+            if !curr_route_borrowed.schedule(at_time, &bundle_to_consider) {
+                panic!("Faulty dry run, didn't allow a clean update!");
+            }
+            hop_timings.extend(hop_timing(&curr_route_borrowed, at_time));
         }
 
         at_time = curr_route_borrowed.at_time;
@@ -378,12 +1925,15 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
                     ),
                 > = HashMap::new();
                 first_hops.insert(first.as_ptr() as usize, (first, vec![curr_route.clone()]));
-                return RoutingOutput { first_hops };
+                span.int("hops", hop_timings.len() as i64);
+                return RoutingOutput {
+                    first_hops,
+                    hop_timings,
+                    uncovered: Vec::new(),
+                };
             }
             panic!("First hop tracking issue");
         }
-
-        curr_opt = curr_route_borrowed.next_for_destination.get(&dest).cloned();
     }
 
     panic!("Faulty dry run, didn't allow a clean update!");
@@ -405,6 +1955,8 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
 /// - `node_list`: A list of nodes (`Node<NM>`) in the network.
 /// - `init_tree`: A boolean flag indicating whether to initialize the tree for routing to the
 ///   destination node.
+/// - `booking_horizon`: How far ahead of `curr_time` the transmissions are booked, without limit
+///   if `None`.
 ///
 /// # Returns
 /// Returns a `RoutingOutput<NM, CM>` containing the scheduled routing details.
@@ -413,13 +1965,20 @@ fn schedule_unicast<NM: NodeManager, CM: ContactManager>(
     curr_time: Date,
     tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
     init_tree: bool,
+    booking_horizon: Option<Duration>,
 ) -> RoutingOutput<NM, CM> {
     if init_tree {
         tree.borrow().init_for_destination(bundle.destinations[0]);
     }
     let dest = bundle.destinations[0];
     let source_route = tree.borrow().get_source_route();
-    return update_unicast(bundle, dest, curr_time, source_route.clone());
+    return update_unicast(
+        bundle,
+        dest,
+        curr_time,
+        source_route.clone(),
+        booking_horizon,
+    );
 }
 
 /// Schedules a unicast pathfinding operation for a given source route without tree initialization.
@@ -434,6 +1993,8 @@ fn schedule_unicast<NM: NodeManager, CM: ContactManager>(
 /// - `curr_time`: The current time, used as the starting time for scheduling.
 /// - `source_route`: The starting `RouteStage` for unicast pathfinding.
 /// - `node_list`: A list of nodes (`Node<NM>`) in the network.
+/// - `booking_horizon`: How far ahead of `curr_time` the transmissions are booked, without limit
+///   if `None`.
 ///
 /// # Returns
 /// Returns a `RoutingOutput<NM, CM>` containing the scheduled routing details.
@@ -441,7 +2002,14 @@ fn schedule_unicast_path<NM: NodeManager, CM: ContactManager>(
     bundle: &Bundle,
     curr_time: Date,
     source_route: Rc<RefCell<RouteStage<NM, CM>>>,
+    booking_horizon: Option<Duration>,
 ) -> RoutingOutput<NM, CM> {
     let dest = bundle.destinations[0];
-    return update_unicast(bundle, dest, curr_time, source_route.clone());
+    return update_unicast(
+        bundle,
+        dest,
+        curr_time,
+        source_route.clone(),
+        booking_horizon,
+    );
 }
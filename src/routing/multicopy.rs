@@ -0,0 +1,205 @@
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node::Node,
+    node_manager::NodeManager,
+    pathfinding::{Pathfinding, PathfindingStats},
+    types::{Date, HashMap, NodeID},
+};
+
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData};
+
+use super::{
+    dry_run_unicast_path, estimate_unicast, hop_timing, update_neighbor_state, NeighborState,
+    RouteEstimate, Router, RoutingOutput,
+};
+
+/// A bounded multi-copy router (spray-and-wait like), requires the "contact_suppression" feature.
+///
+/// Each unicast bundle is forwarded on up to `copies` distinct first hops, over contact-disjoint
+/// routes: after each copy, all the contacts of its route are suppressed for the next pathfinding
+/// iterations, and restored once the bundle is routed. Only the first hop of each copy is
+/// scheduled (its contact volume is reserved), the next nodes route their copy on reception.
+///
+/// # Type Parameters
+///
+/// * `NM` - A type that implements the `NodeManager` trait.
+/// * `CM` - A type that implements the `ContactManager` trait.
+/// * `P` - A type that implements the `Pathfinding<NM, CM>` trait (a path variant is enough, the
+///   alternative variants are only peeked).
+pub struct MultiCopy<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>> {
+    pathfinding: P,
+    /// The maximum number of copies (L) of a bundle.
+    copies: usize,
+
+    // for compilation
+    #[doc(hidden)]
+    _phantom_nm: PhantomData<NM>,
+    #[doc(hidden)]
+    _phantom_cm: PhantomData<CM>,
+}
+
+impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>> Router<NM, CM>
+    for MultiCopy<NM, CM, P>
+{
+    fn route(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        if bundle.expiration < curr_time {
+            return None;
+        }
+
+        if bundle.destinations.len() == 1 {
+            return self.route_unicast(source, bundle, curr_time, excluded_nodes);
+        }
+
+        // multicast is not supported by the path-based routers
+        None
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        estimate_unicast(
+            &mut self.pathfinding,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        )
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        // no route storage to invalidate
+        update_neighbor_state(&self.pathfinding.get_multigraph(), node, state, until).is_some()
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.pathfinding.get_multigraph().borrow().advance_time(now);
+        true
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.pathfinding.cumulative_stats()
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        Some(self.pathfinding.get_multigraph())
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>> MultiCopy<NM, CM, P> {
+    /// Creates a multi-copy router.
+    ///
+    /// # Parameters
+    ///
+    /// * `nodes` - The nodes of the contact plan.
+    /// * `contacts` - The contacts of the contact plan.
+    /// * `copies` - The maximum number of copies (L) of a bundle, at least 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `MultiCopy`.
+    pub fn new(nodes: Vec<Node<NM>>, contacts: Vec<Contact<NM, CM>>, copies: usize) -> Self {
+        Self {
+            pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(nodes, contacts)))),
+            copies: copies.max(1),
+            // for compilation
+            _phantom_nm: PhantomData,
+            _phantom_cm: PhantomData,
+        }
+    }
+
+    /// Routes the copies of a unicast bundle.
+    ///
+    /// # Returns
+    ///
+    /// The routing output with one first hop per copy (up to `copies`), `None` if no copy could
+    /// be routed.
+    fn route_unicast(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingOutput<NM, CM>> {
+        let dest = bundle.destinations[0];
+        let mut first_hops = HashMap::new();
+        let mut hop_timings = Vec::new();
+        let mut suppressed: Vec<Rc<RefCell<Contact<NM, CM>>>> = Vec::new();
+
+        for _ in 0..self.copies {
+            // the disjointness comes from the suppressions below, `peek` leaves the state of the
+            // alternative variants (their own suppressions) untouched for the next bundles
+            let tree = self
+                .pathfinding
+                .peek(curr_time, source, bundle, excluded_nodes);
+            let Some(dest_route) = tree.by_destination.get(dest as usize).cloned().flatten() else {
+                break;
+            };
+            tree.init_for_destination(dest);
+
+            // suppress the contacts of the route for the next copies (disjointness)
+            let mut curr_opt = Some(dest_route.clone());
+            while let Some(curr_route) = curr_opt {
+                curr_opt = curr_route.borrow().via.as_ref().map(|via| {
+                    let mut contact = via.contact.borrow_mut();
+                    if !contact.suppressed {
+                        contact.suppressed = true;
+                        suppressed.push(via.contact.clone());
+                    }
+                    via.parent_route.clone()
+                });
+            }
+
+            let source_route = tree.get_source_route();
+            if dry_run_unicast_path(bundle, curr_time, source_route.clone(), true).is_none() {
+                continue;
+            }
+            let Some(first_hop) = source_route
+                .borrow()
+                .next_for_destination
+                .get(&dest)
+                .cloned()
+            else {
+                continue;
+            };
+
+            #[cfg(feature = "node_proc")]
+            let bundle_to_consider = &first_hop.borrow().bundle.clone();
+            #[cfg(not(feature = "node_proc"))]
+            let bundle_to_consider = bundle;
+
+            if !first_hop
+                .borrow_mut()
+                .schedule(curr_time, bundle_to_consider)
+            {
+                continue;
+            }
+            hop_timings.extend(hop_timing(&first_hop.borrow(), curr_time));
+            let first_hop_contact = first_hop.borrow().get_via_contact();
+            if let Some(contact) = first_hop_contact {
+                first_hops.insert(contact.as_ptr() as usize, (contact, vec![dest_route]));
+            }
+        }
+
+        for contact in suppressed {
+            contact.borrow_mut().suppressed = false;
+        }
+
+        if first_hops.is_empty() {
+            return None;
+        }
+        Some(RoutingOutput {
+            first_hops,
+            hop_timings,
+            uncovered: Vec::new(),
+        })
+    }
+}
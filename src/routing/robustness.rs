@@ -0,0 +1,246 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, cmp::Ordering};
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::{ContactManager, ContactManagerTxData},
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    types::{Date, Duration, NodeID, Volume},
+};
+
+use super::RoutingOutput;
+
+/// The robustness of a hop of a `RouteRobustness`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct HopRobustness {
+    /// The transmitting node.
+    pub tx_node: NodeID,
+    /// The receiving node.
+    pub rx_node: NodeID,
+    /// The time left between the end of the transmission and the end of the contact.
+    pub slack: Duration,
+    /// The residual volume of the contact (after the bundle if it was scheduled), `None` if the
+    /// contact manager does not track volumes.
+    pub residual_volume: Option<Volume>,
+    /// The count of the other contacts toward `rx_node` able to carry the bundle from its arrival
+    /// at `tx_node` without delaying the next hops.
+    pub alternatives: usize,
+}
+
+/// The robustness of a computed route: the margins left on each hop if the contact plan turns out
+/// to be inaccurate (e.g. a contact ending early, less volume than expected, a missed contact).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RouteRobustness {
+    /// The destination node.
+    pub destination: NodeID,
+    /// The expected arrival time at the destination.
+    pub arrival: Date,
+    /// The hops from the source to the destination.
+    pub hops: Vec<HopRobustness>,
+    /// The smallest slack of the hops.
+    pub min_slack: Duration,
+    /// The smallest residual volume of the hops, `None` if no contact manager tracks volumes.
+    pub min_residual_volume: Option<Volume>,
+    /// The smallest count of alternatives of the hops.
+    pub min_alternatives: usize,
+}
+
+/// A hop collected from the route stages, before the analysis.
+struct CollectedHop<NM: NodeManager, CM: ContactManager> {
+    /// The contact of the hop.
+    contact: Rc<RefCell<Contact<NM, CM>>>,
+    /// The transmission computed for the route.
+    tx_data: ContactManagerTxData,
+    /// The arrival time at the transmitting node.
+    departure: Date,
+}
+
+impl RouteRobustness {
+    /// Analyzes the route leading to a route stage, from the transmissions computed by the last
+    /// pathfinding, dry run or scheduling of the stages.
+    ///
+    /// The alternatives of a hop are the contacts of the multigraph toward the same receiver (not
+    /// suppressed) accepting the bundle (dry run) from its arrival at the transmitting node, with an
+    /// arrival before the latest transmission of the next hop (see `SourceRoute::from_stage`), or
+    /// before the bundle expiration for the last hop.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The route stage reaching the destination.
+    /// * `bundle` - The bundle routed.
+    /// * `multigraph` - The multigraph the route was computed on (see `Router::multigraph`).
+    ///
+    /// # Returns
+    ///
+    /// The `RouteRobustness`, or `None` if `route` is a source stage or a stage has no transmission
+    /// data.
+    pub fn from_stage<NM: NodeManager, CM: ContactManager>(
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+        bundle: &Bundle,
+        multigraph: &Multigraph<NM, CM>,
+    ) -> Option<RouteRobustness> {
+        let (destination, arrival) = {
+            let route = route.borrow();
+            (route.to_node, route.at_time)
+        };
+        let bundle = bundle.for_destinations(&[destination]);
+
+        let mut collected: Vec<CollectedHop<NM, CM>> = Vec::new();
+        let mut curr_route = route.clone();
+        loop {
+            let parent = {
+                let curr_borrowed = curr_route.borrow();
+                let Some(via) = &curr_borrowed.via else {
+                    break;
+                };
+                collected.push(CollectedHop {
+                    contact: via.contact.clone(),
+                    tx_data: curr_borrowed.tx_data?,
+                    departure: via.parent_route.borrow().at_time,
+                });
+                via.parent_route.clone()
+            };
+            curr_route = parent;
+        }
+        if collected.is_empty() {
+            return None;
+        }
+        collected.reverse();
+
+        // backward pass: the latest arrival at the receiver of each hop
+        let mut latest_arrivals = Vec::with_capacity(collected.len());
+        let mut deadline = bundle.expiration;
+        for hop in collected.iter().rev() {
+            latest_arrivals.push(deadline);
+            let contact_end = hop.contact.borrow().info.end;
+            let tx_duration = hop.tx_data.tx_end - hop.tx_data.tx_start;
+            deadline = Date::min(contact_end, deadline - hop.tx_data.delay) - tx_duration;
+        }
+        latest_arrivals.reverse();
+
+        let mut hops = Vec::with_capacity(collected.len());
+        for (hop, latest_arrival) in collected.iter().zip(latest_arrivals) {
+            let contact = hop.contact.borrow();
+            let mut alternatives = 0;
+            if let Some(receiver) = multigraph
+                .senders
+                .get(contact.info.tx_node as usize)
+                .and_then(|sender| {
+                    sender
+                        .receivers
+                        .iter()
+                        .find(|receiver| receiver.node.borrow().info.id == contact.info.rx_node)
+                })
+            {
+                for other in &receiver.contacts_to_receiver {
+                    if Rc::ptr_eq(other, &hop.contact) {
+                        continue;
+                    }
+                    let other = other.borrow();
+                    #[cfg(feature = "contact_suppression")]
+                    if other.suppressed {
+                        continue;
+                    }
+                    if other.info.end <= hop.departure || other.info.start >= latest_arrival {
+                        continue;
+                    }
+                    if other
+                        .manager
+                        .dry_run_tx(&other.info, hop.departure, &bundle)
+                        .is_some_and(|tx_data| tx_data.arrival <= latest_arrival)
+                    {
+                        alternatives += 1;
+                    }
+                }
+            }
+            hops.push(HopRobustness {
+                tx_node: contact.info.tx_node,
+                rx_node: contact.info.rx_node,
+                slack: contact.info.end - hop.tx_data.tx_end,
+                residual_volume: contact.manager.get_residual_volume(&contact.info, &bundle),
+                alternatives,
+            });
+        }
+
+        let min_slack = hops
+            .iter()
+            .map(|hop| hop.slack)
+            .reduce(Duration::min)
+            .unwrap_or_default();
+        let min_residual_volume = hops
+            .iter()
+            .filter_map(|hop| hop.residual_volume)
+            .reduce(Volume::min);
+        let min_alternatives = hops
+            .iter()
+            .map(|hop| hop.alternatives)
+            .min()
+            .unwrap_or_default();
+
+        Some(RouteRobustness {
+            destination,
+            arrival,
+            hops,
+            min_slack,
+            min_residual_volume,
+            min_alternatives,
+        })
+    }
+
+    /// Compares the robustness of two routes, e.g. to break the ties between routes with the same
+    /// arrival time.
+    ///
+    /// The weakest hops are compared: the count of alternatives first, then the slack, then the
+    /// residual volume (an unknown volume being the weakest).
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The robustness of the other route.
+    ///
+    /// # Returns
+    ///
+    /// `Ordering::Greater` if `self` is more robust than `other`.
+    pub fn cmp_robustness(&self, other: &RouteRobustness) -> Ordering {
+        self.min_alternatives
+            .cmp(&other.min_alternatives)
+            .then_with(|| {
+                self.min_slack
+                    .partial_cmp(&other.min_slack)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| {
+                self.min_residual_volume
+                    .partial_cmp(&other.min_residual_volume)
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> RoutingOutput<NM, CM> {
+    /// Scores the robustness of the route toward a destination, see `RouteRobustness::from_stage`.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    /// * `bundle` - The bundle routed.
+    /// * `multigraph` - The multigraph the route was computed on (see `Router::multigraph`).
+    ///
+    /// # Returns
+    ///
+    /// The robustness of the route, or `None` if the destination is not reached.
+    pub fn robustness(
+        &self,
+        dest: NodeID,
+        bundle: &Bundle,
+        multigraph: &Multigraph<NM, CM>,
+    ) -> Option<RouteRobustness> {
+        let (_, route) = self.lazy_get_for_unicast(dest)?;
+        RouteRobustness::from_stage(&route, bundle, multigraph)
+    }
+}
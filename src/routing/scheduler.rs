@@ -0,0 +1,251 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    types::{Date, NodeID},
+};
+
+use super::{Router, RoutingOutput};
+
+/// Identifies a bundle parked by a `Scheduler`.
+pub type PendingID = usize;
+
+/// A bundle waiting for a route.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct PendingBundle {
+    /// The identifier of the parked bundle.
+    pub id: PendingID,
+    /// The node holding the bundle.
+    pub source: NodeID,
+    /// The bundle.
+    pub bundle: Bundle,
+    /// The nodes to exclude from the routes.
+    pub excluded_nodes: Vec<NodeID>,
+}
+
+/// The result of `Scheduler::submit`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum Submission<NM: NodeManager, CM: ContactManager> {
+    /// The bundle was routed immediately.
+    Routed(RoutingOutput<NM, CM>),
+    /// The bundle cannot be routed now and was parked.
+    Parked(PendingID),
+}
+
+/// An event produced by the re-evaluation of the parked bundles.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum SchedulerEvent<NM: NodeManager, CM: ContactManager> {
+    /// A parked bundle was routed (and scheduled), it leaves the scheduler.
+    Routed {
+        pending: PendingBundle,
+        output: RoutingOutput<NM, CM>,
+    },
+    /// A parked bundle expired before being routed, it leaves the scheduler.
+    Expired { pending: PendingBundle },
+}
+
+/// A "store and re-route" loop: the bundles that cannot be routed now are parked, and
+/// re-evaluated when the next relevant contact begins.
+///
+/// The event list is derived from the contact plan: a parked bundle is re-evaluated when a contact
+/// transmitting from its source node starts. The parked bundles are re-evaluated by decreasing
+/// priority, then in submission order.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Scheduler {
+    /// The contact start events (start time, transmitting node), sorted by start time.
+    contact_starts: Vec<(Date, NodeID)>,
+    /// The index of the next contact start event to process.
+    next_event: usize,
+    /// The parked bundles.
+    pending: Vec<PendingBundle>,
+    /// The identifier of the next parked bundle.
+    next_id: PendingID,
+}
+
+impl Scheduler {
+    /// Creates a scheduler from contact start events.
+    ///
+    /// # Parameters
+    ///
+    /// * `contact_starts` - The (start time, transmitting node) pairs of the contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of `Scheduler`, without parked bundles.
+    pub fn new(mut contact_starts: Vec<(Date, NodeID)>) -> Self {
+        contact_starts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Self {
+            contact_starts,
+            next_event: 0,
+            pending: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Creates a scheduler from the contacts of a contact plan.
+    pub fn from_contacts<NM: NodeManager, CM: ContactManager>(
+        contacts: &[Contact<NM, CM>],
+    ) -> Self {
+        Self::new(
+            contacts
+                .iter()
+                .map(|contact| (contact.info.start, contact.info.tx_node))
+                .collect(),
+        )
+    }
+
+    /// Creates a scheduler from the contacts of a multigraph.
+    pub fn from_multigraph<NM: NodeManager, CM: ContactManager>(
+        multigraph: &Multigraph<NM, CM>,
+    ) -> Self {
+        let mut contact_starts = Vec::with_capacity(multigraph.get_contact_count());
+        for sender in &multigraph.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    let info = contact.borrow().info;
+                    contact_starts.push((info.start, info.tx_node));
+                }
+            }
+        }
+        Self::new(contact_starts)
+    }
+
+    /// Routes a bundle now, or parks it if it cannot be routed.
+    ///
+    /// # Parameters
+    ///
+    /// * `router` - The router.
+    /// * `source` - The node holding the bundle.
+    /// * `bundle` - The bundle.
+    /// * `curr_time` - The current time.
+    /// * `excluded_nodes` - The nodes to exclude from the routes.
+    ///
+    /// # Returns
+    ///
+    /// The routing output, or the identifier of the parked bundle.
+    pub fn submit<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+        &mut self,
+        router: &mut R,
+        source: NodeID,
+        bundle: Bundle,
+        curr_time: Date,
+        excluded_nodes: Vec<NodeID>,
+    ) -> Submission<NM, CM> {
+        if let Some(output) = router.route(source, &bundle, curr_time, &excluded_nodes) {
+            return Submission::Routed(output);
+        }
+        Submission::Parked(self.park(source, bundle, excluded_nodes))
+    }
+
+    /// Parks a bundle without trying to route it.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the parked bundle.
+    pub fn park(
+        &mut self,
+        source: NodeID,
+        bundle: Bundle,
+        excluded_nodes: Vec<NodeID>,
+    ) -> PendingID {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.push(PendingBundle {
+            id,
+            source,
+            bundle,
+            excluded_nodes,
+        });
+        id
+    }
+
+    /// Removes a parked bundle, returns it if it was parked.
+    pub fn cancel(&mut self, id: PendingID) -> Option<PendingBundle> {
+        let pos = self.pending.iter().position(|pending| pending.id == id)?;
+        Some(self.pending.remove(pos))
+    }
+
+    /// Returns the parked bundles.
+    pub fn get_pending(&self) -> &[PendingBundle] {
+        &self.pending
+    }
+
+    /// Returns the start time of the next contact event relevant to a parked bundle.
+    pub fn next_event_time(&self) -> Option<Date> {
+        self.contact_starts[self.next_event..]
+            .iter()
+            .find(|(_, tx_node)| self.pending.iter().any(|p| p.source == *tx_node))
+            .map(|(start, _)| *start)
+    }
+
+    /// Processes the contact start events up to `curr_time`, re-evaluates the relevant parked
+    /// bundles at `curr_time` and drops the expired ones.
+    ///
+    /// # Parameters
+    ///
+    /// * `router` - The router.
+    /// * `curr_time` - The current time.
+    /// * `on_event` - Called for each bundle leaving the scheduler.
+    pub fn poll_with<NM, CM, R, F>(&mut self, router: &mut R, curr_time: Date, mut on_event: F)
+    where
+        NM: NodeManager,
+        CM: ContactManager,
+        R: Router<NM, CM> + ?Sized,
+        F: FnMut(SchedulerEvent<NM, CM>),
+    {
+        let mut triggered: Vec<NodeID> = Vec::new();
+        while let Some((start, tx_node)) = self.contact_starts.get(self.next_event) {
+            if *start > curr_time {
+                break;
+            }
+            if !triggered.contains(tx_node) {
+                triggered.push(*tx_node);
+            }
+            self.next_event += 1;
+        }
+
+        // by decreasing priority, then in submission order (stable sort)
+        let mut pending = core::mem::take(&mut self.pending);
+        pending.sort_by_key(|pending| core::cmp::Reverse(pending.bundle.priority));
+
+        for parked in pending {
+            if parked.bundle.expiration < curr_time {
+                on_event(SchedulerEvent::Expired { pending: parked });
+                continue;
+            }
+            if triggered.contains(&parked.source) {
+                if let Some(output) = router.route(
+                    parked.source,
+                    &parked.bundle,
+                    curr_time,
+                    &parked.excluded_nodes,
+                ) {
+                    on_event(SchedulerEvent::Routed {
+                        pending: parked,
+                        output,
+                    });
+                    continue;
+                }
+            }
+            self.pending.push(parked);
+        }
+        self.pending.sort_by_key(|pending| pending.id);
+    }
+
+    /// Like `poll_with`, returns the events instead of calling a callback.
+    pub fn poll<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+        &mut self,
+        router: &mut R,
+        curr_time: Date,
+    ) -> Vec<SchedulerEvent<NM, CM>> {
+        let mut events = Vec::new();
+        self.poll_with(router, curr_time, |event| events.push(event));
+        events
+    }
+}
@@ -0,0 +1,442 @@
+use alloc::{
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cell::RefCell, fmt};
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    parsing::{Lexer, ParsingState},
+    route_stage::{HopFailure, RouteStage},
+    types::{ContactID, Date, Duration, NodeID},
+};
+
+use super::{try_extend_route, update_unicast, RoutingOutput};
+
+/// A hop of a `SourceRoute`, with the transmission window of the bundle on the contact.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct SourceRouteHop {
+    /// The transmitting node.
+    pub tx_node: NodeID,
+    /// The receiving node.
+    pub rx_node: NodeID,
+    /// The start time of the contact.
+    pub contact_start: Date,
+    /// The end time of the contact.
+    pub contact_end: Date,
+    /// The earliest transmission start, i.e. the one computed for the route (forward pass).
+    pub earliest_tx: Date,
+    /// The latest transmission start still allowing the next hops and the delivery before the
+    /// bundle expiration (backward pass).
+    pub latest_tx: Date,
+    /// The transmission duration of the bundle on the contact.
+    pub tx_duration: Duration,
+    /// The propagation delay of the contact.
+    pub delay: Duration,
+    /// The arrival time at `rx_node` for a transmission at `earliest_tx`.
+    pub arrival: Date,
+}
+
+/// The complete hop sequence of a route, to install the whole path on the intermediate nodes
+/// (source routing, e.g. schedule uploads).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct SourceRoute {
+    /// The source node.
+    pub source: NodeID,
+    /// The destination node.
+    pub destination: NodeID,
+    /// The hops from the source to the destination.
+    pub hops: Vec<SourceRouteHop>,
+}
+
+impl SourceRoute {
+    /// Builds the source route leading to a route stage, from the transmissions computed by the
+    /// last pathfinding, dry run or scheduling of the stages.
+    ///
+    /// The forward pass gives the earliest transmission of each hop. The backward pass gives the
+    /// latest transmission of each hop, constrained by the end of the contact, the latest
+    /// transmission of the next hop and the bundle expiration. The transmission durations are the
+    /// ones of the forward pass (the queueing and the node processing are not considered).
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The route stage reaching the destination.
+    /// * `bundle` - The bundle routed, for its expiration.
+    ///
+    /// # Returns
+    ///
+    /// The `SourceRoute`, or `None` if `route` is a source stage or a stage has no transmission data.
+    pub fn from_stage<NM: NodeManager, CM: ContactManager>(
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+        bundle: &Bundle,
+    ) -> Option<SourceRoute> {
+        let destination = route.borrow().to_node;
+        let mut source = destination;
+        let mut hops = Vec::new();
+
+        let mut curr_opt = Some(route.clone());
+        while let Some(curr_route) = curr_opt {
+            let curr_borrowed = curr_route.borrow();
+            let Some(via) = &curr_borrowed.via else {
+                source = curr_borrowed.to_node;
+                break;
+            };
+            let tx_data = curr_borrowed.tx_data?;
+            let info = via.contact.borrow().info;
+            let tx_duration = tx_data.tx_end - tx_data.tx_start;
+            hops.push(SourceRouteHop {
+                tx_node: info.tx_node,
+                rx_node: info.rx_node,
+                contact_start: info.start,
+                contact_end: info.end,
+                earliest_tx: tx_data.tx_start,
+                latest_tx: tx_data.tx_start,
+                tx_duration,
+                delay: tx_data.delay,
+                arrival: tx_data.arrival,
+            });
+            curr_opt = Some(via.parent_route.clone());
+        }
+
+        if hops.is_empty() {
+            return None;
+        }
+        hops.reverse();
+
+        let mut deadline = bundle.expiration;
+        for hop in hops.iter_mut().rev() {
+            let latest_end = Date::min(hop.contact_end, deadline - hop.delay);
+            hop.latest_tx = latest_end - hop.tx_duration;
+            deadline = hop.latest_tx;
+        }
+
+        Some(SourceRoute {
+            source,
+            destination,
+            hops,
+        })
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> RoutingOutput<NM, CM> {
+    /// Builds the source routes of the output, one per reached destination, see
+    /// `SourceRoute::from_stage`.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The bundle routed, for its expiration.
+    ///
+    /// # Returns
+    ///
+    /// The source routes.
+    pub fn source_routes(&self, bundle: &Bundle) -> Vec<SourceRoute> {
+        self.first_hops
+            .values()
+            .flat_map(|(_, dest_routes)| dest_routes.iter())
+            .filter_map(|route| SourceRoute::from_stage(route, bundle))
+            .collect()
+    }
+
+    /// Builds the source route toward a destination, see `SourceRoute::from_stage`.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest` - The destination node.
+    /// * `bundle` - The bundle routed, for its expiration.
+    ///
+    /// # Returns
+    ///
+    /// The source route, or `None` if the destination is not reached.
+    pub fn source_route(&self, dest: NodeID, bundle: &Bundle) -> Option<SourceRoute> {
+        let (_, route) = self.lazy_get_for_unicast(dest)?;
+        SourceRoute::from_stage(&route, bundle)
+    }
+}
+
+/// The route carried by a pre-routed bundle (source routing), see `Router::route_pre_routed`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq, Serialize)]
+pub enum RouteHeader {
+    /// The contacts of the hops, by ID, from the source to the destination.
+    Contacts(Vec<ContactID>),
+    /// The nodes of the route, from the source to the destination, each hop taking the earliest
+    /// contact able to carry the bundle.
+    Nodes(Vec<NodeID>),
+}
+
+impl RouteHeader {
+    /// Parses a route header: the kind of hops, "contacts" or "nodes", then the IDs separated by
+    /// commas, e.g. `contacts 3,7,12` or `nodes 0,2,5`.
+    ///
+    /// # Parameters
+    ///
+    /// * `lexer` - The lexer providing the tokens of the header.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, String>` - The header, or an error message if it is malformed.
+    pub fn parse(lexer: &mut dyn Lexer) -> Result<Self, String> {
+        let kind = match lexer.consume_next_token() {
+            ParsingState::Finished(kind) => kind,
+            ParsingState::Error(msg) => return Err(msg),
+            ParsingState::EOF => {
+                return Err(format!(
+                    "Missing route header ({})",
+                    lexer.get_current_position()
+                ))
+            }
+        };
+        let ids = match lexer.consume_next_token() {
+            ParsingState::Finished(ids) => ids,
+            ParsingState::Error(msg) => return Err(msg),
+            ParsingState::EOF => {
+                return Err(format!(
+                    "Missing route hops ({})",
+                    lexer.get_current_position()
+                ))
+            }
+        };
+        match kind.as_str() {
+            "contacts" => Ok(Self::Contacts(parse_ids(&ids, lexer)?)),
+            "nodes" => Ok(Self::Nodes(parse_ids(&ids, lexer)?)),
+            _ => Err(format!(
+                "Unrecognized route header \"{}\" ({})",
+                kind,
+                lexer.get_current_position()
+            )),
+        }
+    }
+}
+
+/// Parses IDs separated by commas.
+fn parse_ids<T: core::str::FromStr>(ids: &str, lexer: &dyn Lexer) -> Result<Vec<T>, String> {
+    ids.split(',')
+        .map(|id| {
+            id.parse::<T>().map_err(|_| {
+                format!(
+                    "Malformed route hop \"{}\" ({})",
+                    id,
+                    lexer.get_current_position()
+                )
+            })
+        })
+        .collect()
+}
+
+impl fmt::Display for RouteHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, ids): (&str, Vec<String>) = match self {
+            Self::Contacts(contacts) => (
+                "contacts",
+                contacts.iter().map(|id| id.to_string()).collect(),
+            ),
+            Self::Nodes(nodes) => ("nodes", nodes.iter().map(|id| id.to_string()).collect()),
+        };
+        write!(f, "{} {}", kind, ids.join(","))
+    }
+}
+
+/// The reason why a `RouteHeader` is not followed, see `schedule_route_header`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum RouteRejection {
+    /// The bundle is not a unicast bundle.
+    NotUnicast,
+    /// The route does not go from the source to the destination of the bundle.
+    WrongEndpoints,
+    /// No contact has this ID.
+    UnknownContact { contact: ContactID },
+    /// No node has this ID.
+    UnknownNode { node: NodeID },
+    /// The hop does not start from the node reached by the previous hop, or no contact links
+    /// its nodes.
+    Discontinuous { hop: usize },
+    /// The hop reaches an excluded node.
+    ExcludedNode { node: NodeID },
+    /// The hop cannot carry the bundle with the current resources.
+    HopRefused { hop: usize, failure: HopFailure },
+}
+
+impl fmt::Display for RouteRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotUnicast => write!(f, "The bundle is not a unicast bundle"),
+            Self::WrongEndpoints => write!(
+                f,
+                "The route does not go from the source to the destination of the bundle"
+            ),
+            Self::UnknownContact { contact } => write!(f, "Contact {} is unknown", contact),
+            Self::UnknownNode { node } => write!(f, "Node {} is unknown", node),
+            Self::Discontinuous { hop } => write!(f, "Hop {} is not continuous", hop),
+            Self::ExcludedNode { node } => write!(f, "Node {} is excluded", node),
+            Self::HopRefused { hop, .. } => write!(f, "Hop {} cannot carry the bundle", hop),
+        }
+    }
+}
+
+/// Finds a contact of the multigraph by ID.
+///
+/// # Returns
+///
+/// * `Option<(NodeID, usize, usize)>` - The transmitting node, the index of the receiver among
+///   its receivers and the index of the contact among the contacts toward the receiver.
+fn find_contact<NM: NodeManager, CM: ContactManager>(
+    graph: &Multigraph<NM, CM>,
+    contact_id: ContactID,
+) -> Option<(NodeID, usize, usize)> {
+    for (tx_node, sender) in graph.senders.iter().enumerate() {
+        for (receiver_idx, receiver) in sender.receivers.iter().enumerate() {
+            if let Some(contact_idx) = receiver
+                .contacts_to_receiver
+                .iter()
+                .position(|contact| contact.borrow().id == contact_id)
+            {
+                return Some((tx_node as NodeID, receiver_idx, contact_idx));
+            }
+        }
+    }
+    None
+}
+
+/// Validates the route carried by a pre-routed bundle against the current resources, and
+/// schedules the bundle along it, see `Router::route_pre_routed`.
+///
+/// Each hop is dry run at the arrival time of the previous hop, the whole route being dry run
+/// before the scheduling. With a `RouteHeader::Nodes` header, each hop takes the earliest contact
+/// (by start time) between its nodes able to carry the bundle.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph of the router.
+/// * `source` - The source node of the bundle.
+/// * `bundle` - The bundle.
+/// * `header` - The route carried by the bundle.
+/// * `curr_time` - The current time.
+/// * `excluded_nodes` - The nodes the route must not reach.
+///
+/// # Returns
+///
+/// * `Result<RoutingOutput<NM, CM>, RouteRejection>` - The routing output, or the reason why
+///   the route cannot be followed, nothing being scheduled.
+pub fn schedule_route_header<NM: NodeManager, CM: ContactManager>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    source: NodeID,
+    bundle: &Bundle,
+    header: &RouteHeader,
+    curr_time: Date,
+    excluded_nodes: &[NodeID],
+) -> Result<RoutingOutput<NM, CM>, RouteRejection> {
+    if bundle.destinations.len() != 1 {
+        return Err(RouteRejection::NotUnicast);
+    }
+    let dest = bundle.destinations[0];
+    if dest == source {
+        return Err(RouteRejection::WrongEndpoints);
+    }
+
+    let graph = multigraph.borrow();
+    if source as usize >= graph.senders.len() {
+        return Err(RouteRejection::UnknownNode { node: source });
+    }
+    let source_route = Rc::new(RefCell::new(RouteStage::new(
+        curr_time,
+        source,
+        None,
+        #[cfg(feature = "node_proc")]
+        bundle.clone(),
+    )));
+    let mut parent = source_route.clone();
+    let mut at_node = source;
+
+    match header {
+        RouteHeader::Contacts(contacts) => {
+            for (hop, &contact_id) in contacts.iter().enumerate() {
+                let (tx_node, receiver_idx, contact_idx) =
+                    find_contact(&graph, contact_id).ok_or(RouteRejection::UnknownContact {
+                        contact: contact_id,
+                    })?;
+                if tx_node != at_node {
+                    return Err(if hop == 0 {
+                        RouteRejection::WrongEndpoints
+                    } else {
+                        RouteRejection::Discontinuous { hop }
+                    });
+                }
+                let receiver = &graph.senders[tx_node as usize].receivers[receiver_idx];
+                at_node = receiver.node.borrow().info.id;
+                if excluded_nodes.contains(&at_node) {
+                    return Err(RouteRejection::ExcludedNode { node: at_node });
+                }
+                parent = try_extend_route(
+                    &parent,
+                    &receiver.contacts_to_receiver[contact_idx],
+                    &graph.nodes[tx_node as usize],
+                    &receiver.node,
+                    bundle,
+                )
+                .map_err(|failure| RouteRejection::HopRefused { hop, failure })?;
+            }
+        }
+        RouteHeader::Nodes(nodes) => {
+            if nodes.first() != Some(&source) {
+                return Err(RouteRejection::WrongEndpoints);
+            }
+            for (hop, pair) in nodes.windows(2).enumerate() {
+                let (tx_node, rx_node) = (pair[0], pair[1]);
+                if rx_node as usize >= graph.senders.len() {
+                    return Err(RouteRejection::UnknownNode { node: rx_node });
+                }
+                if excluded_nodes.contains(&rx_node) {
+                    return Err(RouteRejection::ExcludedNode { node: rx_node });
+                }
+                let receiver = graph.senders[tx_node as usize]
+                    .receivers
+                    .iter()
+                    .find(|receiver| receiver.node.borrow().info.id == rx_node)
+                    .ok_or(RouteRejection::Discontinuous { hop })?;
+                let at_time = parent.borrow().at_time;
+                let mut failure = HopFailure::MissingHop { node: tx_node };
+                let first_idx = receiver
+                    .first_idx_at(at_time)
+                    .unwrap_or(receiver.contacts_to_receiver.len());
+                let mut next = None;
+                for contact in &receiver.contacts_to_receiver[first_idx..] {
+                    #[cfg(feature = "contact_suppression")]
+                    if contact.borrow().suppressed {
+                        continue;
+                    }
+                    match try_extend_route(
+                        &parent,
+                        contact,
+                        &graph.nodes[tx_node as usize],
+                        &receiver.node,
+                        bundle,
+                    ) {
+                        Ok(stage) => {
+                            next = Some(stage);
+                            break;
+                        }
+                        Err(hop_failure) => failure = hop_failure,
+                    }
+                }
+                parent = next.ok_or(RouteRejection::HopRefused { hop, failure })?;
+                at_node = rx_node;
+            }
+        }
+    }
+    if at_node != dest {
+        return Err(RouteRejection::WrongEndpoints);
+    }
+    drop(graph);
+
+    RouteStage::init_route(parent);
+    Ok(update_unicast(bundle, dest, curr_time, source_route, None))
+}
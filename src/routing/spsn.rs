@@ -5,14 +5,28 @@ use crate::{
     multigraph::Multigraph,
     node::Node,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{Pathfinding, PathfindingLimits, PathfindingStats},
     route_storage::{Guard, TreeStorage},
-    types::{Date, NodeID},
+    types::{Date, Duration, NodeID},
 };
 
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData};
 
-use super::{schedule_multicast, schedule_unicast, Router, RoutingOutput};
+use super::{
+    backup_unicast,
+    balancing::{route_balanced_unicast, HashBalancing},
+    congestion::CongestionEstimate,
+    estimate_unicast,
+    gossip::{
+        export_multigraph_state, merge_down_nodes, merge_multigraph_utilization, LimitSummary,
+        StateSummary,
+    },
+    resolve_destinations, route_critical_unicast, route_span, schedule_multicast, schedule_unicast,
+    update_neighbor_state,
+    warm_up::{warm_up_trees, WarmUpBudget, WarmUpClock},
+    MulticastSplit, NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
 
 /// A structure representing the Shortest Path with Safety Nodes (SPSN) algorithm.
 ///
@@ -36,6 +50,13 @@ pub struct Spsn<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S:
     /// The guard structure that enforces safety and priority constraints, checking if the routing
     /// can proceed based on the current bundle and its constraints.
     unicast_guard: Guard,
+    /// Where the branches of the multicast trees split.
+    multicast_split: MulticastSplit,
+    /// The spreading of the unicast bundles over the routes of equal cost, the first route found
+    /// is selected if `None`.
+    load_balancing: Option<HashBalancing>,
+    /// How far ahead of the routing time the transmissions are booked, without limit if `None`.
+    booking_horizon: Option<Duration>,
 
     // for compilation
     #[doc(hidden)]
@@ -54,16 +75,177 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: TreeStorage
         curr_time: Date,
         excluded_nodes: &Vec<NodeID>,
     ) -> Option<RoutingOutput<NM, CM>> {
+        let _span = route_span("spsn", source, bundle, curr_time);
+        // the routes computed while a neighbor was down may no longer be the best
+        let multigraph = self.pathfinding.get_multigraph();
+        if multigraph.borrow_mut().expire_down_nodes(curr_time) {
+            self.route_storage.borrow_mut().invalidate(None);
+            self.unicast_guard.clear();
+        }
+        let resolved = resolve_destinations(&multigraph, bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
         if bundle.expiration < curr_time {
             return None;
         }
 
         if bundle.destinations.len() == 1 {
+            if bundle.critical {
+                return route_critical_unicast(
+                    &mut self.pathfinding,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
+            if let Some(balancing) = &self.load_balancing {
+                return route_balanced_unicast(
+                    &mut self.pathfinding,
+                    balancing,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
             return self.route_unicast(source, bundle, curr_time, excluded_nodes);
         }
 
         return self.route_multicast(source, bundle, curr_time, excluded_nodes);
     }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        estimate_unicast(
+            &mut self.pathfinding,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        )
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        let primary = self.route(source, bundle, curr_time, excluded_nodes)?;
+        let backup = backup_unicast(
+            &mut self.pathfinding,
+            &primary,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        );
+        Some(RoutingWithBackup { primary, backup })
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.pathfinding.get_multigraph().borrow().resolve(name)
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        let multigraph = self.pathfinding.get_multigraph();
+        let Some(stale_via) = update_neighbor_state(&multigraph, node, state, until) else {
+            return false;
+        };
+        self.route_storage.borrow_mut().invalidate(stale_via);
+        self.unicast_guard.clear();
+        true
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.pathfinding.get_multigraph().borrow().advance_time(now);
+        true
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.pathfinding.cumulative_stats()
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        warm_up_trees(
+            &mut self.pathfinding,
+            &self.route_storage,
+            sources,
+            destinations,
+            times,
+            &mut WarmUpClock::new(budget),
+        )
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        Some(self.pathfinding.get_multigraph())
+    }
+    fn export_state_summary(&self, curr_time: Date) -> Option<StateSummary> {
+        let mut summary = export_multigraph_state(&self.pathfinding.get_multigraph(), curr_time);
+        summary.limits = self
+            .unicast_guard
+            .known_limits()
+            .into_iter()
+            .map(|(destination, priority, size)| LimitSummary {
+                destination,
+                priority,
+                size,
+            })
+            .collect();
+        Some(summary)
+    }
+    fn merge_state_summary(&mut self, summary: &StateSummary, curr_time: Date) -> usize {
+        // marking a node down clears the guard, the limits are merged last
+        let mut merged = merge_down_nodes(self, summary, curr_time);
+        merged +=
+            merge_multigraph_utilization(&self.pathfinding.get_multigraph(), summary, curr_time);
+        for limit in &summary.limits {
+            if self
+                .unicast_guard
+                .merge_limit(limit.destination, limit.priority, limit.size)
+            {
+                merged += 1;
+            }
+        }
+        merged
+    }
+    fn congestion(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<CongestionEstimate> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        if bundle.destinations.len() != 1 {
+            return None;
+        }
+        let dest = bundle.destinations[0];
+        let estimate = estimate_unicast(
+            &mut self.pathfinding,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        );
+        Some(CongestionEstimate::new(
+            bundle,
+            self.unicast_guard.limit(dest, bundle.priority),
+            self.route_storage.borrow().dry_run_failure_rate(dest),
+            estimate.as_ref(),
+        ))
+    }
 }
 
 impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>
@@ -91,12 +273,93 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(nodes, contacts)))),
             route_storage: route_storage.clone(),
             unicast_guard: Guard::new(with_priorities),
+            multicast_split: MulticastSplit::Late,
+            load_balancing: None,
+            booking_horizon: None,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         }
     }
 
+    /// Sets where the branches of the multicast trees split, `MulticastSplit::Late` by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `split` - The split of the branches.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the split policy.
+    pub fn with_multicast_split(mut self, split: MulticastSplit) -> Self {
+        self.multicast_split = split;
+        self
+    }
+
+    /// Spreads the unicast bundles over the routes of equal cost by a deterministic hash of the
+    /// bundles (see `HashBalancing`), instead of selecting the first route found.
+    ///
+    /// # Parameters
+    ///
+    /// * `balancing` - The spreading of the bundles.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the load balancing.
+    pub fn with_load_balancing(mut self, balancing: HashBalancing) -> Self {
+        self.load_balancing = Some(balancing);
+        self
+    }
+
+    /// Replaces the guard of the unicast routing, e.g. with `Guard::disabled()` to route every
+    /// bundle even toward the destinations known to be unreachable for its size.
+    ///
+    /// # Parameters
+    ///
+    /// * `guard` - The guard.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the guard.
+    pub fn with_guard(mut self, guard: Guard) -> Self {
+        self.unicast_guard = guard;
+        self
+    }
+
+    /// Bounds each pathfinding run of the router, the routes found so far being used when a
+    /// limit is reached (see `PathfindingLimits`).
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The limits of each pathfinding run.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the pathfinding limits.
+    pub fn with_pathfinding_limits(mut self, limits: PathfindingLimits) -> Self {
+        self.pathfinding.set_limits(limits);
+        self
+    }
+
+    /// Limits how far ahead of the routing time the transmissions are booked, leaving the volume
+    /// of the later contacts to later, better informed, routing decisions.
+    ///
+    /// The hops whose transmission starts after `curr_time + horizon` are dry run like the
+    /// others (the route is selected and returned as usual), but are not booked nor listed in
+    /// the hop timings of the routing output.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon` - The booking horizon.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the booking horizon.
+    pub fn with_booking_horizon(mut self, horizon: Duration) -> Self {
+        self.booking_horizon = Some(horizon);
+        self
+    }
+
     /// Routes a bundle to a single destination node using unicast routing.
     ///
     /// The `route_unicast` function performs a unicast routing operation for bundles with only
@@ -132,7 +395,13 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
                 .select(bundle, curr_time, excluded_nodes);
 
         if let Some(tree) = tree_option {
-            return Some(schedule_unicast(bundle, curr_time, tree, false));
+            return Some(schedule_unicast(
+                bundle,
+                curr_time,
+                tree,
+                false,
+                self.booking_horizon,
+            ));
         }
 
         let new_tree = self
@@ -159,7 +428,13 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
             }
         }
 
-        return Some(schedule_unicast(bundle, curr_time, tree_ref, true));
+        return Some(schedule_unicast(
+            bundle,
+            curr_time,
+            tree_ref,
+            true,
+            self.booking_horizon,
+        ));
     }
 
     /// Routes a bundle to multiple destination nodes using multicast routing.
@@ -196,16 +471,29 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
                     curr_time,
                     tree,
                     Some(reachable_nodes),
+                    self.multicast_split,
+                    self.booking_horizon,
                 ));
             }
         }
 
-        let new_tree = self
-            .pathfinding
-            .get_next(curr_time, source, bundle, excluded_nodes);
+        // the root of the tree serves all the destinations, it inherits the highest priority
+        let new_tree = self.pathfinding.get_next(
+            curr_time,
+            source,
+            &bundle.for_destinations(&bundle.destinations),
+            excluded_nodes,
+        );
         let tree = Rc::new(RefCell::new(new_tree));
         self.route_storage.borrow_mut().store(&bundle, tree.clone());
 
-        return Some(schedule_multicast(bundle, curr_time, tree, None));
+        return Some(schedule_multicast(
+            bundle,
+            curr_time,
+            tree,
+            None,
+            self.multicast_split,
+            self.booking_horizon,
+        ));
     }
 }
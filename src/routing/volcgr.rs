@@ -5,15 +5,23 @@ use crate::{
     multigraph::Multigraph,
     node::Node,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{Pathfinding, PathfindingLimits, PathfindingStats},
     route_stage::RouteStage,
     route_storage::{Route, RouteStorage},
-    types::{Date, NodeID},
+    types::{Date, Duration, NodeID},
 };
 
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use alloc::{rc::Rc, vec::Vec};
+use core::{cell::RefCell, marker::PhantomData};
 
-use super::{dry_run_unicast_path, schedule_unicast_path, Router, RoutingOutput};
+use super::{
+    backup_unicast,
+    balancing::{route_balanced_unicast, HashBalancing},
+    dry_run_unicast_path, estimate_unicast, refresh_routes, resolve_destinations,
+    route_critical_unicast, route_span, schedule_unicast_path, update_neighbor_state,
+    warm_up::{warm_up_routes, WarmUpBudget, WarmUpClock},
+    NeighborState, RouteEstimate, Router, RoutingOutput, RoutingWithBackup,
+};
 
 pub struct VolCgr<
     NM: NodeManager,
@@ -23,6 +31,11 @@ pub struct VolCgr<
 > {
     route_storage: Rc<RefCell<S>>,
     pathfinding: P,
+    /// The spreading of the unicast bundles over the routes of equal cost, the first route found
+    /// is selected if `None`.
+    load_balancing: Option<HashBalancing>,
+    /// How far ahead of the routing time the transmissions are booked, without limit if `None`.
+    booking_horizon: Option<Duration>,
 
     // for compilation
     #[doc(hidden)]
@@ -41,15 +54,121 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
         curr_time: Date,
         excluded_nodes: &Vec<NodeID>,
     ) -> Option<RoutingOutput<NM, CM>> {
+        let _span = route_span("volcgr", source, bundle, curr_time);
+        // the routes computed while a neighbor was down may no longer be the best
+        let multigraph = self.pathfinding.get_multigraph();
+        if multigraph.borrow_mut().expire_down_nodes(curr_time) {
+            self.route_storage.borrow_mut().invalidate(None);
+        }
+        let resolved = resolve_destinations(&multigraph, bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
         if bundle.expiration < curr_time {
             return None;
         }
 
         if bundle.destinations.len() == 1 {
+            if bundle.critical {
+                return route_critical_unicast(
+                    &mut self.pathfinding,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
+            if let Some(balancing) = &self.load_balancing {
+                return route_balanced_unicast(
+                    &mut self.pathfinding,
+                    balancing,
+                    source,
+                    bundle,
+                    curr_time,
+                    excluded_nodes,
+                    self.booking_horizon,
+                );
+            }
+            // the aged routes are recomputed before the selection
+            self.refresh(source, curr_time);
             return self.route_unicast(source, bundle, curr_time, excluded_nodes);
         }
 
-        todo!();
+        // multicast is not supported by the path-based routers
+        None
+    }
+    fn estimate(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RouteEstimate> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        estimate_unicast(
+            &mut self.pathfinding,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        )
+    }
+    fn route_with_backup(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &Vec<NodeID>,
+    ) -> Option<RoutingWithBackup<NM, CM>> {
+        let resolved = resolve_destinations(&self.pathfinding.get_multigraph(), bundle);
+        let bundle = resolved.as_ref().unwrap_or(bundle);
+        let primary = self.route(source, bundle, curr_time, excluded_nodes)?;
+        let backup = backup_unicast(
+            &mut self.pathfinding,
+            &primary,
+            source,
+            bundle,
+            curr_time,
+            excluded_nodes,
+        );
+        Some(RoutingWithBackup { primary, backup })
+    }
+    fn resolve(&self, name: &str) -> Option<NodeID> {
+        self.pathfinding.get_multigraph().borrow().resolve(name)
+    }
+    fn set_neighbor_state(&mut self, node: NodeID, state: NeighborState, until: Date) -> bool {
+        let multigraph = self.pathfinding.get_multigraph();
+        let Some(stale_via) = update_neighbor_state(&multigraph, node, state, until) else {
+            return false;
+        };
+        self.route_storage.borrow_mut().invalidate(stale_via);
+        true
+    }
+    fn advance_time(&mut self, now: Date) -> bool {
+        self.pathfinding.get_multigraph().borrow().advance_time(now);
+        true
+    }
+    fn pathfinding_stats(&self) -> Option<PathfindingStats> {
+        self.pathfinding.cumulative_stats()
+    }
+    fn warm_up(
+        &mut self,
+        sources: &[NodeID],
+        destinations: &[NodeID],
+        times: &[Date],
+        budget: WarmUpBudget,
+    ) -> usize {
+        warm_up_routes(
+            &mut self.pathfinding,
+            &self.route_storage,
+            sources,
+            destinations,
+            times,
+            &mut WarmUpClock::new(budget),
+        )
+    }
+    fn multigraph(&self) -> Option<Rc<RefCell<Multigraph<NM, CM>>>> {
+        Some(self.pathfinding.get_multigraph())
     }
 }
 
@@ -64,12 +183,86 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         Self {
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(nodes, contacts)))),
             route_storage: route_storage.clone(),
+            load_balancing: None,
+            booking_horizon: None,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         }
     }
 
+    /// Spreads the unicast bundles over the routes of equal cost by a deterministic hash of the
+    /// bundles (see `HashBalancing`), instead of selecting the first route found.
+    ///
+    /// # Parameters
+    ///
+    /// * `balancing` - The spreading of the bundles.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the load balancing.
+    pub fn with_load_balancing(mut self, balancing: HashBalancing) -> Self {
+        self.load_balancing = Some(balancing);
+        self
+    }
+
+    /// Bounds each pathfinding run of the router, the routes found so far being used when a
+    /// limit is reached (see `PathfindingLimits`).
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The limits of each pathfinding run.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the pathfinding limits.
+    pub fn with_pathfinding_limits(mut self, limits: PathfindingLimits) -> Self {
+        self.pathfinding.set_limits(limits);
+        self
+    }
+
+    /// Limits how far ahead of the routing time the transmissions are booked, leaving the volume
+    /// of the later contacts to later, better informed, routing decisions.
+    ///
+    /// The hops whose transmission starts after `curr_time + horizon` are dry run like the
+    /// others (the route is selected and returned as usual), but are not booked nor listed in
+    /// the hop timings of the routing output.
+    ///
+    /// # Parameters
+    ///
+    /// * `horizon` - The booking horizon.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The router with the booking horizon.
+    pub fn with_booking_horizon(mut self, horizon: Duration) -> Self {
+        self.booking_horizon = Some(horizon);
+        self
+    }
+
+    /// Recomputes the routes of the destinations whose refresh was requested by the route
+    /// storage, e.g. after the aging of their routes (see `AgingPolicy`).
+    ///
+    /// Called automatically before each unicast routing, the application can also call it ahead
+    /// of time (e.g. while idle).
+    ///
+    /// # Parameters
+    ///
+    /// * `source` - The local node.
+    /// * `curr_time` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The count of routes recomputed.
+    pub fn refresh(&mut self, source: NodeID, curr_time: Date) -> usize {
+        refresh_routes(
+            &mut self.pathfinding,
+            &self.route_storage,
+            source,
+            curr_time,
+        )
+    }
+
     fn route_unicast(
         &mut self,
         source: NodeID,
@@ -91,6 +284,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
                 bundle,
                 curr_time,
                 route.source_stage.clone(),
+                self.booking_horizon,
             ));
         }
 
@@ -110,6 +304,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
                     bundle,
                     curr_time,
                     route.source_stage.clone(),
+                    self.booking_horizon,
                 ));
             }
         }
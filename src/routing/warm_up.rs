@@ -0,0 +1,249 @@
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    pathfinding::Pathfinding,
+    route_stage::RouteStage,
+    route_storage::{Route, RouteStorage, TreeStorage},
+    types::{Date, NodeID, VOLUME_ZERO},
+};
+
+/// The compute budget of `Router::warm_up`.
+///
+/// The budget bounds the count of pathfinding runs of the warm-up. When it expires, the warm-up
+/// stops, the routes already stored being kept.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default)]
+pub struct WarmUpBudget {
+    /// The maximum count of pathfinding runs (unbounded if `None`).
+    pub max_runs: Option<usize>,
+    /// The maximum time spent in pathfinding (unbounded if `None`).
+    #[cfg(feature = "std")]
+    pub max_time: Option<std::time::Duration>,
+}
+
+impl WarmUpBudget {
+    /// Creates a budget bounding the count of pathfinding runs.
+    pub fn runs(max_runs: usize) -> Self {
+        Self {
+            max_runs: Some(max_runs),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a budget bounding the time spent in pathfinding.
+    #[cfg(feature = "std")]
+    pub fn time(max_time: std::time::Duration) -> Self {
+        Self {
+            max_time: Some(max_time),
+            ..Default::default()
+        }
+    }
+}
+
+/// The consumption of a `WarmUpBudget` during a warm-up.
+pub(crate) struct WarmUpClock {
+    /// The budget of the warm-up.
+    budget: WarmUpBudget,
+    /// The count of pathfinding runs so far.
+    runs: usize,
+    /// The start of the warm-up.
+    #[cfg(feature = "std")]
+    start: std::time::Instant,
+}
+
+impl WarmUpClock {
+    /// Starts the consumption of a budget.
+    pub(crate) fn new(budget: WarmUpBudget) -> Self {
+        Self {
+            budget,
+            runs: 0,
+            #[cfg(feature = "std")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Tells whether the budget is expired.
+    pub(crate) fn expired(&self) -> bool {
+        if self
+            .budget
+            .max_runs
+            .is_some_and(|max_runs| self.runs >= max_runs)
+        {
+            return true;
+        }
+        #[cfg(feature = "std")]
+        if self
+            .budget
+            .max_time
+            .is_some_and(|max_time| self.start.elapsed() >= max_time)
+        {
+            return true;
+        }
+        false
+    }
+
+    /// Counts pathfinding runs.
+    pub(crate) fn consume(&mut self, runs: usize) {
+        self.runs += runs;
+    }
+
+    /// Returns the budget left, e.g. for the next router of a federation.
+    pub(crate) fn remaining(&self) -> WarmUpBudget {
+        WarmUpBudget {
+            max_runs: self
+                .budget
+                .max_runs
+                .map(|max_runs| max_runs.saturating_sub(self.runs)),
+            #[cfg(feature = "std")]
+            max_time: self
+                .budget
+                .max_time
+                .map(|max_time| max_time.saturating_sub(self.start.elapsed())),
+        }
+    }
+}
+
+/// The bundle the routes are precomputed for, without constraints (no size, lowest priority, no
+/// expiration) so that the stored routes are not shadowed (see `Bundle::shadows`).
+fn warm_up_template(source: NodeID, dest: NodeID) -> Bundle {
+    Bundle {
+        source,
+        destinations: vec![dest],
+        priority: 0,
+        size: VOLUME_ZERO,
+        expiration: Date::MAX,
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+/// Precomputes and stores the trees of a tree storage (e.g. for `Spsn`), see `Router::warm_up`.
+///
+/// For each time, source and destination (in this order), a tree is computed without exclusion
+/// unless the storage already provides one, a tree serving all the destinations it reaches.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router.
+/// - `tree_storage`: The tree storage of the router.
+/// - `sources`: The source nodes of the expected traffic.
+/// - `destinations`: The destinations of the expected traffic.
+/// - `times`: The expected routing times.
+/// - `clock`: The budget of the warm-up.
+///
+/// # Returns
+/// The count of trees stored.
+pub(crate) fn warm_up_trees<
+    NM: NodeManager,
+    CM: ContactManager,
+    P: Pathfinding<NM, CM>,
+    S: TreeStorage<NM, CM>,
+>(
+    pathfinding: &mut P,
+    tree_storage: &Rc<RefCell<S>>,
+    sources: &[NodeID],
+    destinations: &[NodeID],
+    times: &[Date],
+    clock: &mut WarmUpClock,
+) -> usize {
+    let no_exclusions = Vec::new();
+    let mut stored = 0;
+    for &time in times {
+        for &source in sources {
+            for &dest in destinations {
+                if dest == source {
+                    continue;
+                }
+                let template = warm_up_template(source, dest);
+                if tree_storage
+                    .borrow()
+                    .select(&template, time, &no_exclusions)
+                    .0
+                    .is_some()
+                {
+                    continue;
+                }
+                if clock.expired() {
+                    return stored;
+                }
+                clock.consume(1);
+                let tree = pathfinding.get_next(time, source, &template, &no_exclusions);
+                tree_storage
+                    .borrow_mut()
+                    .store(&template, Rc::new(RefCell::new(tree)));
+                stored += 1;
+            }
+        }
+    }
+    stored
+}
+
+/// Precomputes and stores the routes of a route storage (e.g. for `Cgr`), see `Router::warm_up`.
+///
+/// For each time, source and destination (in this order), the best route is computed without
+/// exclusion with `Pathfinding::peek` unless the storage already provides one.
+///
+/// # Parameters
+/// - `pathfinding`: The pathfinding of the router.
+/// - `route_storage`: The route storage of the router.
+/// - `sources`: The source nodes of the expected traffic.
+/// - `destinations`: The destinations of the expected traffic.
+/// - `times`: The expected routing times.
+/// - `clock`: The budget of the warm-up.
+///
+/// # Returns
+/// The count of routes stored.
+pub(crate) fn warm_up_routes<
+    NM: NodeManager,
+    CM: ContactManager,
+    P: Pathfinding<NM, CM>,
+    S: RouteStorage<NM, CM>,
+>(
+    pathfinding: &mut P,
+    route_storage: &Rc<RefCell<S>>,
+    sources: &[NodeID],
+    destinations: &[NodeID],
+    times: &[Date],
+    clock: &mut WarmUpClock,
+) -> usize {
+    let no_exclusions = Vec::new();
+    let mut stored = 0;
+    for &time in times {
+        for &source in sources {
+            for &dest in destinations {
+                if dest == source {
+                    continue;
+                }
+                let template = warm_up_template(source, dest);
+                if route_storage
+                    .borrow_mut()
+                    .select(
+                        &template,
+                        time,
+                        pathfinding.get_multigraph().clone(),
+                        &no_exclusions,
+                    )
+                    .is_some()
+                {
+                    continue;
+                }
+                if clock.expired() {
+                    return stored;
+                }
+                clock.consume(1);
+                let tree = pathfinding.peek(time, source, &template, &no_exclusions);
+                let Some(route) = Route::from_tree(Rc::new(RefCell::new(tree)), dest) else {
+                    continue;
+                };
+                RouteStage::init_route(route.destination_stage.clone());
+                route_storage.borrow_mut().store(&template, route);
+                stored += 1;
+            }
+        }
+    }
+    stored
+}
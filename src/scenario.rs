@@ -0,0 +1,411 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    contact_manager::ContactManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    faults::{play_trace_with_faults, FaultModel, FaultReplay},
+    node_manager::NodeManager,
+    parsing::{DispatchParser, Lexer, Parser},
+    pathfinding::PathfindingStats,
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        Router,
+    },
+    workload::{profile_trace, TraceMetrics, WorkloadTrace},
+};
+
+/// A high-level metric of a scenario run, derived from the `TraceMetrics` of the replay.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioMetric {
+    /// The ratio of the destinations reached over the destinations requested.
+    DeliveryRatio,
+    /// The mean delay between the submission and the delivery.
+    MeanLatency,
+    /// The maximum delay between the submission and the delivery.
+    MaxLatency,
+    /// The mean hop count of the routes.
+    MeanHopCount,
+    /// The count of distinct routes used.
+    UniqueRoutes,
+}
+
+impl ScenarioMetric {
+    /// Retrieves the value of the metric from the results of a replay.
+    ///
+    /// # Parameters
+    ///
+    /// * `metrics` - The results of the replay.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The value of the metric.
+    pub fn value(&self, metrics: &TraceMetrics) -> f64 {
+        match self {
+            Self::DeliveryRatio => {
+                if metrics.destinations == 0 {
+                    return 1.0;
+                }
+                metrics.reached as f64 / metrics.destinations as f64
+            }
+            Self::MeanLatency => metrics.mean_latency,
+            Self::MaxLatency => metrics.max_latency,
+            Self::MeanHopCount => metrics.mean_hop_count,
+            Self::UniqueRoutes => metrics.unique_routes as f64,
+        }
+    }
+}
+
+impl fmt::Display for ScenarioMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::DeliveryRatio => "delivery ratio",
+            Self::MeanLatency => "mean latency",
+            Self::MaxLatency => "max latency",
+            Self::MeanHopCount => "mean hop count",
+            Self::UniqueRoutes => "unique routes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The options of the Spsn routers of a scenario, unless set with `Scenario::with_router`.
+fn default_spsn_options() -> SpsnOptions {
+    SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    }
+}
+
+/// A bound on a metric of a scenario.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy)]
+struct Expectation {
+    /// The metric bounded.
+    metric: ScenarioMetric,
+    /// The lower bound, if any.
+    min: Option<f64>,
+    /// The upper bound, if any.
+    max: Option<f64>,
+}
+
+/// An end-to-end scenario: a contact plan, a workload and the expected high-level metrics of the
+/// workload replay (see `play_trace`).
+///
+/// The scenarios document the use cases of the library and pin their behavior: a change of the
+/// routing altering the metrics of a scenario beyond its bounds is reported by `run`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct Scenario {
+    /// The name of the scenario.
+    name: String,
+    /// The contact plan, in the A-SABR format.
+    plan: String,
+    /// The workload.
+    trace: WorkloadTrace,
+    /// The router type, see `build_generic_router`.
+    router_type: String,
+    /// The options of the Spsn routers.
+    spsn_options: Option<SpsnOptions>,
+    /// The bounds on the metrics.
+    expectations: Vec<Expectation>,
+}
+
+impl Scenario {
+    /// Creates a scenario routed by a `SpsnNodeParenting` router, without expectation.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the scenario.
+    /// * `plan` - The contact plan, in the A-SABR format.
+    /// * `trace` - The workload, in the format of `WorkloadTrace`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, String>` - The scenario, or an error message if the workload is malformed
+    ///   (the plan is parsed by `run`).
+    pub fn new(name: &str, plan: &str, trace: &str) -> Result<Self, String> {
+        let mut lexer = StrLexer::new(trace);
+        let trace = WorkloadTrace::parse(&mut lexer as &mut dyn Lexer)
+            .map_err(|msg| format!("Scenario {}: {}", name, msg))?;
+        Ok(Self {
+            name: name.to_string(),
+            plan: plan.to_string(),
+            trace,
+            router_type: "SpsnNodeParenting".to_string(),
+            spsn_options: Some(default_spsn_options()),
+            expectations: Vec::new(),
+        })
+    }
+
+    /// Retrieves the name of the scenario.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the router of the scenario.
+    ///
+    /// # Parameters
+    ///
+    /// * `router_type` - The router type, see `build_generic_router`.
+    /// * `spsn_options` - The options of the Spsn routers.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The scenario with the router.
+    pub fn with_router(mut self, router_type: &str, spsn_options: Option<SpsnOptions>) -> Self {
+        self.router_type = router_type.to_string();
+        self.spsn_options = spsn_options;
+        self
+    }
+
+    /// Expects a metric to reach a value at least.
+    ///
+    /// # Parameters
+    ///
+    /// * `metric` - The metric.
+    /// * `min` - The lower bound (included).
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The scenario with the expectation.
+    pub fn expect_at_least(mut self, metric: ScenarioMetric, min: f64) -> Self {
+        self.expectations.push(Expectation {
+            metric,
+            min: Some(min),
+            max: None,
+        });
+        self
+    }
+
+    /// Expects a metric not to exceed a value.
+    ///
+    /// # Parameters
+    ///
+    /// * `metric` - The metric.
+    /// * `max` - The upper bound (included).
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The scenario with the expectation.
+    pub fn expect_at_most(mut self, metric: ScenarioMetric, max: f64) -> Self {
+        self.expectations.push(Expectation {
+            metric,
+            min: None,
+            max: Some(max),
+        });
+        self
+    }
+
+    /// Parses the plan, replays the workload against a fresh router and checks the expectations.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ScenarioReport, String>` - The report, or an error message if the plan cannot
+    ///   be parsed or the router cannot be built.
+    pub fn run<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM> + 'static,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM> + 'static,
+    >(
+        &self,
+    ) -> Result<ScenarioReport, String> {
+        let mut router =
+            self.build_router::<NM, CM>(&self.router_type, self.spsn_options.clone())?;
+        let (metrics, pathfinding) = profile_trace(router.as_mut(), &self.trace);
+
+        let mut failures = Vec::new();
+        for expectation in &self.expectations {
+            let value = expectation.metric.value(&metrics);
+            if let Some(min) = expectation.min {
+                if value < min {
+                    failures.push(format!(
+                        "{} is {}, expected at least {}",
+                        expectation.metric, value, min
+                    ));
+                }
+            }
+            if let Some(max) = expectation.max {
+                if value > max {
+                    failures.push(format!(
+                        "{} is {}, expected at most {}",
+                        expectation.metric, value, max
+                    ));
+                }
+            }
+        }
+        Ok(ScenarioReport {
+            name: self.name.clone(),
+            metrics,
+            pathfinding,
+            failures,
+        })
+    }
+
+    /// Replays the workload against fresh routers of several types, facing the same contact
+    /// failures and plan deviations (see `play_trace_with_faults`), to compare their robustness.
+    /// The expectations are not checked.
+    ///
+    /// # Parameters
+    ///
+    /// * `router_types` - The router types, see `build_generic_router`. The Spsn routers use the
+    ///   options of the scenario, or the default options of `new` if not set.
+    /// * `faults` - The deviations of the contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RobustnessReport, String>` - The report, or an error message if the plan cannot
+    ///   be parsed or a router cannot be built.
+    pub fn compare_robustness<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM> + 'static,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM> + 'static,
+    >(
+        &self,
+        router_types: &[&str],
+        faults: &FaultModel,
+    ) -> Result<RobustnessReport, String> {
+        let mut routers = Vec::with_capacity(router_types.len());
+        for router_type in router_types {
+            let spsn_options = self.spsn_options.clone().or(Some(default_spsn_options()));
+            let mut router = self.build_router::<NM, CM>(router_type, spsn_options)?;
+            routers.push(RouterRobustness {
+                router_type: router_type.to_string(),
+                replay: play_trace_with_faults(router.as_mut(), &self.trace, faults),
+            });
+        }
+        Ok(RobustnessReport {
+            name: self.name.clone(),
+            routers,
+        })
+    }
+
+    /// Parses the plan and builds a fresh router.
+    fn build_router<
+        NM: NodeManager + DispatchParser<NM> + Parser<NM> + 'static,
+        CM: ContactManager + DispatchParser<CM> + Parser<CM> + 'static,
+    >(
+        &self,
+        router_type: &str,
+        spsn_options: Option<SpsnOptions>,
+    ) -> Result<Box<dyn Router<NM, CM>>, String> {
+        let mut lexer = StrLexer::new(&self.plan);
+        let (nodes, contacts) = ASABRContactPlan::parse::<NM, CM>(&mut lexer, None, None)
+            .map_err(|error| format!("Scenario {}: {}", self.name, error))?;
+        build_generic_router(router_type, nodes, contacts, spsn_options)
+            .map_err(|error| format!("Scenario {}: {}", self.name, error))
+    }
+}
+
+/// The results of `Scenario::run`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct ScenarioReport {
+    /// The name of the scenario.
+    pub name: String,
+    /// The results of the replay.
+    pub metrics: TraceMetrics,
+    /// The statistics of the pathfinding runs of the replay, if the router gathers them.
+    pub pathfinding: Option<PathfindingStats>,
+    /// The expectations not met.
+    pub failures: Vec<String>,
+}
+
+impl ScenarioReport {
+    /// Tells whether all the expectations are met.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "passed" } else { "FAILED" };
+        writeln!(f, "{}: {}", self.name, verdict)?;
+        writeln!(
+            f,
+            "  delivered {}/{} destinations ({} bundles unrouted)",
+            self.metrics.reached, self.metrics.destinations, self.metrics.unrouted
+        )?;
+        writeln!(
+            f,
+            "  latency mean {:.1} max {:.1}, hop count mean {:.2}, {} unique routes",
+            self.metrics.mean_latency,
+            self.metrics.max_latency,
+            self.metrics.mean_hop_count,
+            self.metrics.unique_routes
+        )?;
+        if let Some(stats) = &self.pathfinding {
+            writeln!(
+                f,
+                "  pathfinding {} runs, {} expanded, {} pushes, {} pops, {} pruned",
+                stats.runs, stats.expanded, stats.pushes, stats.pops, stats.pruned
+            )?;
+        }
+        for failure in &self.failures {
+            writeln!(f, "  {}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+/// The results of a router in `Scenario::compare_robustness`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct RouterRobustness {
+    /// The router type.
+    pub router_type: String,
+    /// The planned and actual results of the replay.
+    pub replay: FaultReplay,
+}
+
+/// The results of `Scenario::compare_robustness`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct RobustnessReport {
+    /// The name of the scenario.
+    pub name: String,
+    /// The results of each router, in the order of the router types.
+    pub routers: Vec<RouterRobustness>,
+}
+
+impl RobustnessReport {
+    /// Retrieves the results of a router type.
+    pub fn get(&self, router_type: &str) -> Option<&FaultReplay> {
+        self.routers
+            .iter()
+            .find(|router| router.router_type == router_type)
+            .map(|router| &router.replay)
+    }
+}
+
+impl fmt::Display for RobustnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: robustness", self.name)?;
+        for router in &self.routers {
+            let replay = &router.replay;
+            writeln!(
+                f,
+                "  {}: delivered {}/{} planned, {} actual ({:.1}% lost: {} failures, {} truncations)",
+                router.router_type,
+                replay.planned.reached,
+                replay.planned.destinations,
+                replay.actual.reached,
+                replay.delivery_loss() * 100.0,
+                replay.lost_to_failures,
+                replay.lost_to_truncations
+            )?;
+            writeln!(
+                f,
+                "    latency mean {:.1} planned, {:.1} actual",
+                replay.planned.mean_latency, replay.actual.mean_latency
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::thread;
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    routing::{Router, RoutingSummary},
+    types::{Date, NodeID},
+};
+
+/// A routing request sent to a router service.
+pub struct RoutingRequest {
+    /// The source node ID initiating the routing operation.
+    pub source: NodeID,
+    /// The bundle to route.
+    pub bundle: Bundle,
+    /// The current time.
+    pub curr_time: Date,
+    /// The nodes to exclude from the routing paths, sorted.
+    pub excluded_nodes: Vec<NodeID>,
+    /// The channel on which the result is sent back.
+    pub reply: oneshot::Sender<Option<RoutingSummary>>,
+}
+
+/// The error returned when the router service is not running anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceClosed;
+
+impl std::fmt::Display for ServiceClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the router service is closed")
+    }
+}
+
+impl std::error::Error for ServiceClosed {}
+
+/// A cloneable handle to a router service.
+///
+/// The routers rely on `Rc` internals and cannot leave the thread that created them. The service
+/// owns the router in a dedicated thread, the handle only exchanges owned data with it through
+/// channels, so it can be shared between async tasks.
+#[derive(Clone)]
+pub struct RouterHandle {
+    sender: mpsc::Sender<RoutingRequest>,
+}
+
+impl RouterHandle {
+    /// Sends a routing request to the service and awaits the result.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing operation.
+    /// - `bundle`: The bundle to route.
+    /// - `curr_time`: The current time.
+    /// - `excluded_nodes`: The nodes to exclude from the routing paths, sorted.
+    ///
+    /// # Returns
+    /// The summary of the routing output (`None` if no route was found), or `ServiceClosed`.
+    pub async fn route(
+        &self,
+        source: NodeID,
+        bundle: Bundle,
+        curr_time: Date,
+        excluded_nodes: Vec<NodeID>,
+    ) -> Result<Option<RoutingSummary>, ServiceClosed> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send(RoutingRequest {
+                source,
+                bundle,
+                curr_time,
+                excluded_nodes,
+                reply,
+            })
+            .await
+            .map_err(|_| ServiceClosed)?;
+        response.await.map_err(|_| ServiceClosed)
+    }
+
+    /// Sends a prepared request to the service, the result will be sent on the request's `reply` channel.
+    ///
+    /// # Parameters
+    /// - `request`: The routing request.
+    ///
+    /// # Returns
+    /// `ServiceClosed` if the service is not running anymore.
+    pub async fn send(&self, request: RoutingRequest) -> Result<(), ServiceClosed> {
+        self.sender.send(request).await.map_err(|_| ServiceClosed)
+    }
+}
+
+/// Starts a router service in a dedicated thread.
+///
+/// The router is built in the service thread by `make_router`, so neither the router nor its
+/// managers need to be `Send`. The service stops when all the handles are dropped.
+///
+/// # Parameters
+/// - `make_router`: The closure building the router.
+/// - `buffer`: The capacity of the request channel, at least 1 (0 is raised to 1).
+///
+/// # Returns
+/// A handle to send requests to the service.
+pub fn spawn_router_service<NM, CM, R, F>(make_router: F, buffer: usize) -> RouterHandle
+where
+    NM: NodeManager,
+    CM: ContactManager,
+    R: Router<NM, CM> + ?Sized,
+    F: FnOnce() -> Box<R> + Send + 'static,
+{
+    // the tokio channels cannot be unbuffered
+    let (sender, mut receiver) = mpsc::channel::<RoutingRequest>(buffer.max(1));
+    thread::spawn(move || {
+        let mut router = make_router();
+        while let Some(request) = receiver.blocking_recv() {
+            let output = router.route(
+                request.source,
+                &request.bundle,
+                request.curr_time,
+                &request.excluded_nodes,
+            );
+            // The requester may have given up, this is not an error for the service
+            let _ = request.reply.send(output.map(|out| out.summarize()));
+        }
+    });
+    RouterHandle { sender }
+}
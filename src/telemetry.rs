@@ -0,0 +1,75 @@
+//! The OpenTelemetry spans of the routing calls, emitted with the "otel" feature.
+//!
+//! The spans are reported to the global tracer provider (see `opentelemetry::global`) under the
+//! "a_sabr" instrumentation scope, each span being the child of the span active when it starts
+//! (e.g. the spans of the pathfinding and storage calls are the children of the `route` span).
+//! Without the feature, the spans are no-ops.
+
+#[cfg(feature = "otel")]
+use opentelemetry::{
+    global,
+    trace::{TraceContextExt, Tracer},
+    Context, ContextGuard, KeyValue,
+};
+
+/// The name of the instrumentation scope of the spans.
+#[cfg(feature = "otel")]
+const SCOPE: &str = "a_sabr";
+
+/// A span active until dropped.
+pub(crate) struct Span {
+    /// The context holding the span.
+    #[cfg(feature = "otel")]
+    cx: Context,
+    /// The guard keeping the span active, i.e. the parent of the spans started meanwhile.
+    #[cfg(feature = "otel")]
+    _guard: ContextGuard,
+}
+
+impl Span {
+    /// Starts a span as the child of the active span, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the span, e.g. "a_sabr.route".
+    #[inline(always)]
+    pub(crate) fn start(_name: &'static str) -> Self {
+        #[cfg(feature = "otel")]
+        {
+            let span = global::tracer(SCOPE).start(_name);
+            let cx = Context::current_with_span(span);
+            let _guard = cx.clone().attach();
+            Self { cx, _guard }
+        }
+        #[cfg(not(feature = "otel"))]
+        Self {}
+    }
+
+    /// Sets an integer attribute.
+    #[inline(always)]
+    pub(crate) fn int(&self, _key: &'static str, _value: i64) {
+        #[cfg(feature = "otel")]
+        self.cx.span().set_attribute(KeyValue::new(_key, _value));
+    }
+
+    /// Sets a floating point attribute.
+    #[inline(always)]
+    pub(crate) fn float(&self, _key: &'static str, _value: f64) {
+        #[cfg(feature = "otel")]
+        self.cx.span().set_attribute(KeyValue::new(_key, _value));
+    }
+
+    /// Sets a string attribute.
+    #[inline(always)]
+    pub(crate) fn text(&self, _key: &'static str, _value: &'static str) {
+        #[cfg(feature = "otel")]
+        self.cx.span().set_attribute(KeyValue::new(_key, _value));
+    }
+
+    /// Sets a boolean attribute.
+    #[inline(always)]
+    pub(crate) fn flag(&self, _key: &'static str, _value: bool) {
+        #[cfg(feature = "otel")]
+        self.cx.span().set_attribute(KeyValue::new(_key, _value));
+    }
+}
@@ -0,0 +1,200 @@
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::cell::RefCell;
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    pathfinding::Pathfinding,
+    types::{Date, Duration, NodeID},
+};
+
+#[cfg(feature = "parallel")]
+use crate::{contact::Contact, node::Node};
+
+/// The earliest arrivals from a source to all the nodes, for a grid of start times (e.g. to
+/// generate delivery latency heatmaps over the planning horizon).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct TemporalDistanceMatrix {
+    /// The source node.
+    pub source: NodeID,
+    /// The start times, one per row.
+    pub start_times: Vec<Date>,
+    /// For each start time, the earliest arrival at each node (indexed by `NodeID`), `None` if
+    /// the node is unreachable.
+    pub arrivals: Vec<Vec<Option<Date>>>,
+}
+
+impl TemporalDistanceMatrix {
+    /// Computes the matrix with a pathfinding producing trees (e.g. `NodeParentingTreeExcl`).
+    ///
+    /// The start times are processed in increasing order, the lazy pruning of the multigraph
+    /// assumes that time does not go backward. The contacts are not scheduled.
+    ///
+    /// # Parameters
+    ///
+    /// * `multigraph` - A shared pointer to the multigraph.
+    /// * `source` - The source node.
+    /// * `bundle` - The probe bundle (its size, priority and expiration are considered).
+    /// * `start_times` - The start times, in any order.
+    /// * `excluded_nodes_sorted` - The nodes to exclude from the pathfinding.
+    ///
+    /// # Returns
+    ///
+    /// The matrix, with the rows in the order of `start_times`.
+    pub fn compute<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+        multigraph: Rc<RefCell<Multigraph<NM, CM>>>,
+        source: NodeID,
+        bundle: &Bundle,
+        start_times: &[Date],
+        excluded_nodes_sorted: &Vec<NodeID>,
+    ) -> Self {
+        let mut pathfinding = P::new(multigraph);
+        let mut arrivals = vec![Vec::new(); start_times.len()];
+        for row in sorted_rows(start_times) {
+            arrivals[row] = earliest_arrivals(
+                &mut pathfinding,
+                source,
+                bundle,
+                start_times[row],
+                excluded_nodes_sorted,
+            );
+        }
+        Self {
+            source,
+            start_times: start_times.to_vec(),
+            arrivals,
+        }
+    }
+
+    /// Computes the matrix like `compute`, with the start times split between `thread_count`
+    /// threads. Requires the "parallel" feature.
+    ///
+    /// The routing structures are not shareable between threads, each thread builds its own
+    /// multigraph from the contact plan provided by `plan`.
+    ///
+    /// # Parameters
+    ///
+    /// * `plan` - Provides the nodes and contacts of the contact plan, called once per thread.
+    /// * `source` - The source node.
+    /// * `bundle` - The probe bundle (its size, priority and expiration are considered).
+    /// * `start_times` - The start times, in any order.
+    /// * `excluded_nodes_sorted` - The nodes to exclude from the pathfinding.
+    /// * `thread_count` - The number of threads, the available parallelism if 0.
+    ///
+    /// # Returns
+    ///
+    /// The matrix, with the rows in the order of `start_times`.
+    #[cfg(feature = "parallel")]
+    pub fn compute_parallel<NM, CM, P, F>(
+        plan: F,
+        source: NodeID,
+        bundle: &Bundle,
+        start_times: &[Date],
+        excluded_nodes_sorted: &Vec<NodeID>,
+        thread_count: usize,
+    ) -> Self
+    where
+        NM: NodeManager,
+        CM: ContactManager,
+        P: Pathfinding<NM, CM>,
+        F: Fn() -> (Vec<Node<NM>>, Vec<Contact<NM, CM>>) + Sync,
+    {
+        let thread_count = match thread_count {
+            0 => std::thread::available_parallelism().map_or(1, |count| count.get()),
+            count => count,
+        };
+        let rows = sorted_rows(start_times);
+        let chunk_size = rows.len().div_ceil(thread_count).max(1);
+        let mut arrivals = vec![Vec::new(); start_times.len()];
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = rows
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let plan = &plan;
+                    scope.spawn(move || {
+                        let (nodes, contacts) = plan();
+                        let multigraph = Rc::new(RefCell::new(Multigraph::new(nodes, contacts)));
+                        let mut pathfinding = P::new(multigraph);
+                        chunk
+                            .iter()
+                            .map(|&row| {
+                                let row_arrivals = earliest_arrivals(
+                                    &mut pathfinding,
+                                    source,
+                                    bundle,
+                                    start_times[row],
+                                    excluded_nodes_sorted,
+                                );
+                                (row, row_arrivals)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            for handle in handles {
+                for (row, row_arrivals) in handle.join().expect("Temporal distance thread panicked")
+                {
+                    arrivals[row] = row_arrivals;
+                }
+            }
+        });
+
+        Self {
+            source,
+            start_times: start_times.to_vec(),
+            arrivals,
+        }
+    }
+
+    /// Returns the earliest arrival at `destination` for the start time of `row`.
+    pub fn get_arrival(&self, row: usize, destination: NodeID) -> Option<Date> {
+        *self.arrivals.get(row)?.get(destination as usize)?
+    }
+
+    /// Returns the delivery latency (arrival minus start time) at `destination` for the start
+    /// time of `row`.
+    pub fn get_latency(&self, row: usize, destination: NodeID) -> Option<Duration> {
+        Some(self.get_arrival(row, destination)? - self.start_times[row])
+    }
+
+    /// Returns the latencies of all the cells, rows in the order of the start times.
+    pub fn latencies(&self) -> Vec<Vec<Option<Duration>>> {
+        self.arrivals
+            .iter()
+            .zip(&self.start_times)
+            .map(|(row, &start_time)| {
+                row.iter()
+                    .map(|arrival| arrival.map(|arrival| arrival - start_time))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Returns the row indices sorted by increasing start time.
+fn sorted_rows(start_times: &[Date]) -> Vec<usize> {
+    let mut rows: Vec<usize> = (0..start_times.len()).collect();
+    rows.sort_by(|&a, &b| start_times[a].partial_cmp(&start_times[b]).unwrap());
+    rows
+}
+
+/// Computes the earliest arrivals at all the nodes for one start time.
+fn earliest_arrivals<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    source: NodeID,
+    bundle: &Bundle,
+    start_time: Date,
+    excluded_nodes_sorted: &Vec<NodeID>,
+) -> Vec<Option<Date>> {
+    pathfinding
+        .get_next(start_time, source, bundle, excluded_nodes_sorted)
+        .by_destination
+        .iter()
+        .map(|route| route.as_ref().map(|route| route.borrow().at_time))
+        .collect()
+}
@@ -1,7 +1,14 @@
-use std::str::FromStr;
+use alloc::{format, string::String};
+use core::str::FromStr;
 
 use crate::parsing::{Lexer, ParsingState};
 
+// Hash map and set types used by the library, `std` ones or `hashbrown` ones without std.
+#[cfg(not(feature = "std"))]
+pub use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap, HashSet};
+
 // Convenient for vector indexing
 // TODO: add a check like ~ static_assert(sizeof(NodeID) <= sizeof(usize))
 
@@ -12,23 +19,337 @@ pub type NodeID = u16;
 pub type NodeName = String;
 
 /// Represents a duration in units (e.g., seconds).
+#[cfg(not(feature = "integer_time"))]
 pub type Duration = f64;
+/// Represents a duration in integer ticks (seconds, milliseconds or microseconds, see
+/// `TICKS_PER_SECOND`).
+#[cfg(feature = "integer_time")]
+pub type Duration = i64;
 
 /// Represents a date (could represent days since a specific epoch).
+#[cfg(not(feature = "integer_time"))]
 pub type Date = f64;
+/// Represents a date in integer ticks since a specific epoch (see `TICKS_PER_SECOND`).
+#[cfg(feature = "integer_time")]
+pub type Date = i64;
+
+/// The count of time units per second, used to convert the times expressed in seconds by the
+/// epochs (ISO 8601 and DTN times) and the external formats (ION, tvgutil, protobuf,
+/// ephemerides), the data rates of these formats being converted per time unit.
+///
+/// The plan units are seconds, unless the "integer_time" feature is refined by the
+/// "integer_time_ms" or "integer_time_us" feature: the dates and durations are then counted in
+/// milliseconds or microseconds, the transmission times being rounded up to the tick instead of
+/// the second. The contact plans in the A-SABR format are expressed in these units (times,
+/// durations, and data rates per unit).
+#[cfg(not(any(feature = "integer_time_ms", feature = "integer_time_us")))]
+pub const TICKS_PER_SECOND: f64 = 1.0;
+/// The count of time units per second, the dates and durations are counted in milliseconds.
+#[cfg(all(feature = "integer_time_ms", not(feature = "integer_time_us")))]
+pub const TICKS_PER_SECOND: f64 = 1e3;
+/// The count of time units per second, the dates and durations are counted in microseconds
+/// (the finest tick wins if both ticks are enabled).
+#[cfg(feature = "integer_time_us")]
+pub const TICKS_PER_SECOND: f64 = 1e6;
+
+/// The zero value for dates and durations.
+#[cfg(not(feature = "integer_time"))]
+pub const TIME_ZERO: Date = 0.0;
+/// The zero value for dates and durations.
+#[cfg(feature = "integer_time")]
+pub const TIME_ZERO: Date = 0;
+
+/// Converts a duration computed with floating point arithmetic (e.g. a volume over a data rate).
+///
+/// With the "integer_time" feature, the value is rounded up, a transmission never ends before the
+/// computed time.
+///
+/// # Parameters
+///
+/// * `duration` - The duration as a float.
+///
+/// # Returns
+///
+/// The `Duration`.
+#[inline(always)]
+pub fn duration_from_f64(duration: f64) -> Duration {
+    #[cfg(not(feature = "integer_time"))]
+    return duration;
+    #[cfg(feature = "integer_time")]
+    {
+        let truncated = duration as Duration;
+        if (truncated as f64) < duration {
+            truncated + 1
+        } else {
+            truncated
+        }
+    }
+}
+
+/// Converts a date or a duration provided as a float (e.g. by an external format).
+///
+/// With the "integer_time" feature, the value is rounded to the nearest integer.
+///
+/// # Parameters
+///
+/// * `time` - The date or duration as a float.
+///
+/// # Returns
+///
+/// The `Date` (or `Duration`).
+#[inline(always)]
+pub fn time_from_f64(time: f64) -> Date {
+    #[cfg(not(feature = "integer_time"))]
+    return time;
+    #[cfg(feature = "integer_time")]
+    {
+        if time < 0.0 {
+            (time - 0.5) as Date
+        } else {
+            (time + 0.5) as Date
+        }
+    }
+}
+
+/// Converts a date or a duration to a float, e.g. to compute a volume from a data rate.
+///
+/// # Parameters
+///
+/// * `time` - The date or duration.
+///
+/// # Returns
+///
+/// The value as a float.
+#[inline(always)]
+pub fn time_to_f64(time: Duration) -> f64 {
+    #[cfg(not(feature = "integer_time"))]
+    return time;
+    #[cfg(feature = "integer_time")]
+    return time as f64;
+}
+
+/// Converts a date or a duration expressed in seconds (e.g. by an epoch or an external format),
+/// see `TICKS_PER_SECOND`.
+///
+/// # Parameters
+///
+/// * `seconds` - The date or duration in seconds.
+///
+/// # Returns
+///
+/// The `Date` (or `Duration`), rounded to the nearest tick with the "integer_time" feature.
+#[inline(always)]
+pub fn time_from_seconds(seconds: f64) -> Date {
+    time_from_f64(seconds * TICKS_PER_SECOND)
+}
+
+/// Converts a date or a duration to seconds (e.g. for an epoch or an external format), see
+/// `TICKS_PER_SECOND`.
+///
+/// # Parameters
+///
+/// * `time` - The date or duration.
+///
+/// # Returns
+///
+/// The value in seconds.
+#[inline(always)]
+pub fn time_to_seconds(time: Duration) -> f64 {
+    time_to_f64(time) / TICKS_PER_SECOND
+}
+
+/// Converts a data rate expressed per second (e.g. by an external format) to a data rate per
+/// time unit, see `TICKS_PER_SECOND`.
+///
+/// # Parameters
+///
+/// * `rate` - The data rate per second.
+///
+/// # Returns
+///
+/// The `DataRate`.
+#[inline(always)]
+pub fn rate_from_per_second(rate: f64) -> DataRate {
+    rate_from_f64(rate / TICKS_PER_SECOND)
+}
+
+/// The tolerance of the comparisons of dates (e.g. the bounds of the segments of a contact, or the
+/// end of a transmission against the end of a contact), absorbing the drift of the floating point
+/// arithmetic on long runs.
+#[cfg(not(feature = "integer_time"))]
+pub const TIME_EPSILON: Duration = 1e-6;
+/// The tolerance of the comparisons of dates, the integer dates are compared exactly.
+#[cfg(feature = "integer_time")]
+pub const TIME_EPSILON: Duration = 0;
+
+/// The tolerance of the comparisons of volumes (e.g. a bundle size against a residual volume
+/// computed by successive bookings and releases).
+pub const VOLUME_EPSILON: Volume = volume_from_f64(1e-6);
+
+/// The empty volume.
+pub const VOLUME_ZERO: Volume = volume_from_f64(0.0);
+
+/// The null data rate.
+pub const RATE_ZERO: DataRate = rate_from_f64(0.0);
+
+/// Converts a volume provided as a float (e.g. by an external format, or a ratio of volumes).
+///
+/// Without the "strict-units" feature, the conversion is the identity.
+///
+/// # Parameters
+///
+/// * `volume` - The volume as a float.
+///
+/// # Returns
+///
+/// The `Volume`.
+#[inline(always)]
+pub const fn volume_from_f64(volume: f64) -> Volume {
+    #[cfg(not(feature = "strict-units"))]
+    return volume;
+    #[cfg(feature = "strict-units")]
+    return Volume::new(volume);
+}
+
+/// Converts a volume to a float, e.g. for a ratio or an external format.
+///
+/// # Parameters
+///
+/// * `volume` - The volume.
+///
+/// # Returns
+///
+/// The value as a float.
+#[inline(always)]
+pub const fn volume_to_f64(volume: Volume) -> f64 {
+    #[cfg(not(feature = "strict-units"))]
+    return volume;
+    #[cfg(feature = "strict-units")]
+    return volume.value();
+}
+
+/// Converts a data rate provided as a float (e.g. by an external format).
+///
+/// # Parameters
+///
+/// * `rate` - The data rate as a float.
+///
+/// # Returns
+///
+/// The `DataRate`.
+#[inline(always)]
+pub const fn rate_from_f64(rate: f64) -> DataRate {
+    #[cfg(not(feature = "strict-units"))]
+    return rate;
+    #[cfg(feature = "strict-units")]
+    return DataRate::new(rate);
+}
+
+/// Converts a data rate to a float, e.g. for an external format.
+///
+/// # Parameters
+///
+/// * `rate` - The data rate.
+///
+/// # Returns
+///
+/// The value as a float.
+#[inline(always)]
+pub const fn rate_to_f64(rate: DataRate) -> f64 {
+    #[cfg(not(feature = "strict-units"))]
+    return rate;
+    #[cfg(feature = "strict-units")]
+    return rate.value();
+}
+
+/// Tells whether two dates are equal, within `TIME_EPSILON`.
+///
+/// # Parameters
+///
+/// * `a` - The first date.
+/// * `b` - The second date.
+///
+/// # Returns
+///
+/// `true` if the dates match.
+#[inline(always)]
+pub fn times_match(a: Date, b: Date) -> bool {
+    #[cfg(not(feature = "integer_time"))]
+    return (a - b).abs() <= TIME_EPSILON;
+    #[cfg(feature = "integer_time")]
+    return a == b;
+}
+
+/// Tells whether a date is not after a deadline, within `TIME_EPSILON`.
+///
+/// # Parameters
+///
+/// * `date` - The date to check (e.g. the end of a transmission).
+/// * `deadline` - The deadline (e.g. the end of a contact).
+///
+/// # Returns
+///
+/// `true` if the date is before the deadline, or matches it.
+#[inline(always)]
+pub fn ends_by(date: Date, deadline: Date) -> bool {
+    date <= deadline || times_match(date, deadline)
+}
+
+/// Tells whether a volume fits in an available volume, within `VOLUME_EPSILON`.
+///
+/// # Parameters
+///
+/// * `volume` - The volume required (e.g. the size of a bundle).
+/// * `available` - The volume available (e.g. the residual volume of a contact).
+///
+/// # Returns
+///
+/// `true` if the volume fits.
+#[inline(always)]
+pub fn volume_fits(volume: Volume, available: Volume) -> bool {
+    volume <= available + VOLUME_EPSILON
+}
+
+/// Settles a volume computed by subtractions (e.g. a queue size after a release), the drift
+/// below `VOLUME_EPSILON` is rounded to zero and the volume never goes negative.
+///
+/// # Parameters
+///
+/// * `volume` - The volume computed.
+///
+/// # Returns
+///
+/// The volume settled.
+#[inline(always)]
+pub fn settle_volume(volume: Volume) -> Volume {
+    if volume <= VOLUME_EPSILON {
+        VOLUME_ZERO
+    } else {
+        volume
+    }
+}
 
 /// Represents the priority of a task or node.
 pub type Priority = u8;
 
 /// Represents the volume of data (in bytes, for example).
+#[cfg(not(feature = "strict-units"))]
 pub type Volume = f64;
+#[cfg(feature = "strict-units")]
+pub use crate::units::Volume;
 
 /// Represents a data transfer rate (in bits per second).
+#[cfg(not(feature = "strict-units"))]
 pub type DataRate = f64;
+#[cfg(feature = "strict-units")]
+pub use crate::units::DataRate;
 
 /// Represents the count of hops in a routing path.
 pub type HopCount = u16;
 
+/// Represents the identifier of a contact, i.e. its index in the sorted contact plan of a multigraph.
+pub type ContactID = usize;
+
 /// A trait for types that can be parsed from a lexer.
 ///
 /// # Type Parameters
@@ -50,17 +371,33 @@ pub trait Token<T> {
 impl<T: FromStr> Token<T> for T {
     /// Implement the `Token` trait for any type that implements `FromStr`.
     fn parse(lexer: &mut dyn Lexer) -> ParsingState<T> {
-        let res = lexer.consume_next_token();
-        match res {
-            ParsingState::EOF => ParsingState::EOF,
-            ParsingState::Error(e) => ParsingState::Error(e),
-            ParsingState::Finished(token) => match token.parse::<T>() {
-                Ok(value) => ParsingState::Finished(value),
-                Err(_) => ParsingState::Error(format!(
-                    "Parsing failed ({})",
-                    lexer.get_current_position()
-                )),
-            },
+        let parsed = match lexer.consume_next_token_str() {
+            ParsingState::EOF => return ParsingState::EOF,
+            ParsingState::Error(e) => return ParsingState::Error(e),
+            ParsingState::Finished(token) => token.parse::<T>(),
+        };
+        match parsed {
+            Ok(value) => ParsingState::Finished(value),
+            Err(_) => {
+                ParsingState::Error(format!("Parsing failed ({})", lexer.get_current_position()))
+            }
         }
     }
 }
+
+/// Parses a mandatory token, the end of the input being an error.
+///
+/// # Parameters
+///
+/// * `lexer` - A mutable reference to the lexer that provides the token.
+///
+/// # Returns
+///
+/// * `Result<T, String>` - The value, or the error message.
+pub fn parse_required<T: Token<T>>(lexer: &mut dyn Lexer) -> Result<T, String> {
+    match T::parse(lexer) {
+        ParsingState::Finished(value) => Ok(value),
+        ParsingState::Error(msg) => Err(msg),
+        ParsingState::EOF => Err(format!("Parsing failed ({})", lexer.get_current_position())),
+    }
+}
@@ -0,0 +1,223 @@
+//! Unit-safe `Volume` and `DataRate`, with the "strict-units" feature.
+//!
+//! Without the feature, `Volume` and `DataRate` are aliases of `f64`: nothing prevents adding a
+//! volume to a date. With it, they become newtypes whose arithmetic is restricted to the
+//! meaningful combinations:
+//!
+//! * `Volume ± Volume = Volume`, `Volume * f64 = Volume`, `Volume / f64 = Volume`,
+//! * `Volume / Volume = f64` (a ratio),
+//! * `Volume / DataRate = f64`, the transmission time in time units (see `duration_from_f64`),
+//! * `DataRate * f64 = Volume` (and `f64 * DataRate`), the volume transmitted in a time (see
+//!   `time_to_f64`).
+//!
+//! The conversions from and to `f64` (`From`, `new`, `value`) are kept for compatibility, the
+//! parsing and the serialization are unchanged. The code compiled with and without the feature
+//! relies on `volume_from_f64`, `volume_to_f64`, `VOLUME_ZERO` (and their rate counterparts) from
+//! the `types` module.
+//!
+//! `Date` and `Duration` are left as is, they are switched to integers by the "integer_time"
+//! feature.
+
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+use serde::{Deserialize, Serialize};
+
+/// Represents the volume of data (in bytes, for example).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Volume(f64);
+
+/// Represents a data transfer rate (in bits per second).
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DataRate(f64);
+
+impl Volume {
+    /// The empty volume.
+    pub const ZERO: Self = Self(0.0);
+    /// The unbounded volume.
+    pub const INFINITY: Self = Self(f64::INFINITY);
+    /// The largest volume.
+    pub const MAX: Self = Self(f64::MAX);
+
+    /// Creates a volume from a float.
+    pub const fn new(value: f64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the volume as a float.
+    pub const fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the largest of two volumes.
+    pub fn max(self, other: Self) -> Self {
+        Self(f64::max(self.0, other.0))
+    }
+
+    /// Returns the smallest of two volumes.
+    pub fn min(self, other: Self) -> Self {
+        Self(f64::min(self.0, other.0))
+    }
+
+    /// Returns the absolute volume.
+    pub fn abs(self) -> Self {
+        Self(if self.0 < 0.0 { -self.0 } else { self.0 })
+    }
+
+    /// Tells whether the volume is finite.
+    pub fn is_finite(self) -> bool {
+        self.0.is_finite()
+    }
+}
+
+impl DataRate {
+    /// The null rate.
+    pub const ZERO: Self = Self(0.0);
+
+    /// Creates a rate from a float.
+    pub const fn new(value: f64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the rate as a float.
+    pub const fn value(self) -> f64 {
+        self.0
+    }
+}
+
+macro_rules! float_conversions {
+    ($unit:ident) => {
+        impl From<f64> for $unit {
+            fn from(value: f64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$unit> for f64 {
+            fn from(value: $unit) -> Self {
+                value.0
+            }
+        }
+
+        impl FromStr for $unit {
+            type Err = <f64 as FromStr>::Err;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                f64::from_str(s).map(Self)
+            }
+        }
+
+        impl fmt::Display for $unit {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+float_conversions!(Volume);
+float_conversions!(DataRate);
+
+impl Add for Volume {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Volume {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for Volume {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Volume {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Volume {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Mul<f64> for Volume {
+    type Output = Self;
+    fn mul(self, factor: f64) -> Self {
+        Self(self.0 * factor)
+    }
+}
+
+impl Div<f64> for Volume {
+    type Output = Self;
+    fn div(self, divisor: f64) -> Self {
+        Self(self.0 / divisor)
+    }
+}
+
+impl MulAssign<f64> for Volume {
+    fn mul_assign(&mut self, factor: f64) {
+        self.0 *= factor;
+    }
+}
+
+/// The ratio of two volumes.
+impl Div for Volume {
+    type Output = f64;
+    fn div(self, other: Self) -> f64 {
+        self.0 / other.0
+    }
+}
+
+/// The transmission time of a volume at a rate, in time units.
+impl Div<DataRate> for Volume {
+    type Output = f64;
+    fn div(self, rate: DataRate) -> f64 {
+        self.0 / rate.0
+    }
+}
+
+/// The volume transmitted at a rate during a time, in time units.
+impl Mul<f64> for DataRate {
+    type Output = Volume;
+    fn mul(self, time: f64) -> Volume {
+        Volume(self.0 * time)
+    }
+}
+
+/// The volume transmitted during a time at a rate, in time units.
+impl Mul<DataRate> for f64 {
+    type Output = Volume;
+    fn mul(self, rate: DataRate) -> Volume {
+        Volume(self * rate.0)
+    }
+}
+
+impl Sum for Volume {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|volume| volume.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a Volume> for Volume {
+    fn sum<I: Iterator<Item = &'a Volume>>(iter: I) -> Self {
+        Self(iter.map(|volume| volume.0).sum())
+    }
+}
@@ -0,0 +1,298 @@
+use std::{cell::RefCell, fmt, iter, rc::Rc};
+
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    route_stage::RouteStage,
+    types::{Date, HopCount, NodeID, Volume},
+};
+
+/// The level of detail of a `RouteDisplay`, each level includes the previous ones.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// The destination, the arrival time and the hop count.
+    Summary,
+    /// The reached node, the arrival time and the hop count of each stage.
+    #[default]
+    Stages,
+    /// The contact of each hop.
+    Contacts,
+    /// The transmission window and the residual volume of each hop.
+    Full,
+}
+
+/// The contact of a hop of a `RouteDisplay`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct ContactDisplay {
+    /// The transmitting node.
+    pub tx_node: NodeID,
+    /// The receiving node.
+    pub rx_node: NodeID,
+    /// The start time of the contact.
+    pub start: Date,
+    /// The end time of the contact.
+    pub end: Date,
+}
+
+/// A stage of a `RouteDisplay`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct StageDisplay {
+    /// The node reached.
+    pub to_node: NodeID,
+    /// The arrival time at the node.
+    pub at_time: Date,
+    /// The number of hops to reach the node.
+    pub hop_count: HopCount,
+    /// The contact used to reach the node, `None` for the source stage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<ContactDisplay>,
+    /// The transmission start and end computed by the last pathfinding, dry run or scheduling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_window: Option<(Date, Date)>,
+    /// The residual volume of the contact for the bundle, if a bundle was provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub residual_volume: Option<Volume>,
+}
+
+/// A printable snapshot of a route, from the source stage to the destination stage.
+///
+/// `RouteDisplay` implements `Display` (the stages, one per line), and can be rendered as a
+/// table or as JSON. The level of detail is set with `with_verbosity`. As a snapshot, it does not
+/// reflect the later changes of the route.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Serialize)]
+pub struct RouteDisplay {
+    /// The destination node.
+    pub destination: NodeID,
+    /// The arrival time at the destination.
+    pub at_time: Date,
+    /// The number of hops to reach the destination.
+    pub hop_count: HopCount,
+    /// The stages, from the source to the destination.
+    pub stages: Vec<StageDisplay>,
+    #[serde(skip)]
+    verbosity: Verbosity,
+}
+
+impl RouteDisplay {
+    /// Takes a snapshot of the route leading to a route stage, without the residual volumes.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The route stage reaching the destination.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The snapshot, with the `Verbosity::Stages` level of detail.
+    pub fn new<NM: NodeManager, CM: ContactManager>(
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+    ) -> Self {
+        Self::snapshot(route, None)
+    }
+
+    /// Takes a snapshot of the route leading to a route stage, with the residual volumes of the
+    /// contacts for a bundle.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The route stage reaching the destination.
+    /// * `bundle` - The bundle for which the residual volumes are retrieved.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The snapshot, with the `Verbosity::Stages` level of detail.
+    pub fn for_bundle<NM: NodeManager, CM: ContactManager>(
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+        bundle: &Bundle,
+    ) -> Self {
+        Self::snapshot(route, Some(bundle))
+    }
+
+    fn snapshot<NM: NodeManager, CM: ContactManager>(
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+        bundle: Option<&Bundle>,
+    ) -> Self {
+        let mut stages = Vec::new();
+        let ancestors = route.borrow().ancestors();
+        for curr_route in iter::once(route.clone()).chain(ancestors) {
+            let curr = curr_route.borrow();
+            let (contact, residual_volume) = match &curr.via {
+                Some(via) => {
+                    let contact = via.contact.borrow();
+                    let info = contact.info;
+                    (
+                        Some(ContactDisplay {
+                            tx_node: info.tx_node,
+                            rx_node: info.rx_node,
+                            start: info.start,
+                            end: info.end,
+                        }),
+                        bundle
+                            .and_then(|bundle| contact.manager.get_residual_volume(&info, bundle)),
+                    )
+                }
+                None => (None, None),
+            };
+            stages.push(StageDisplay {
+                to_node: curr.to_node,
+                at_time: curr.at_time,
+                hop_count: curr.hop_count,
+                contact,
+                tx_window: curr
+                    .tx_data
+                    .map(|tx_data| (tx_data.tx_start, tx_data.tx_end)),
+                residual_volume,
+            });
+        }
+        stages.reverse();
+
+        let route = route.borrow();
+        Self {
+            destination: route.to_node,
+            at_time: route.at_time,
+            hop_count: route.hop_count,
+            stages,
+            verbosity: Verbosity::default(),
+        }
+    }
+
+    /// Sets the level of detail.
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Writes the route into any formatter sink (e.g. a `String`).
+    pub fn write_to<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        write!(writer, "{}", self)
+    }
+
+    /// Renders the stages as a table, with one column per detail of the verbosity level.
+    pub fn to_table(&self) -> String {
+        let mut header = vec!["node", "arrival", "hops"];
+        if self.verbosity >= Verbosity::Contacts {
+            header.extend(["contact", "start", "end"]);
+        }
+        if self.verbosity >= Verbosity::Full {
+            header.extend(["tx start", "tx end", "residual"]);
+        }
+
+        let mut rows: Vec<Vec<String>> = vec![header.iter().map(|h| h.to_string()).collect()];
+        let stages = match self.verbosity {
+            Verbosity::Summary => &self.stages[self.stages.len().saturating_sub(1)..],
+            _ => &self.stages[..],
+        };
+        for stage in stages {
+            let mut row = vec![
+                stage.to_node.to_string(),
+                stage.at_time.to_string(),
+                stage.hop_count.to_string(),
+            ];
+            if self.verbosity >= Verbosity::Contacts {
+                match &stage.contact {
+                    Some(contact) => row.extend([
+                        format!("{}->{}", contact.tx_node, contact.rx_node),
+                        contact.start.to_string(),
+                        contact.end.to_string(),
+                    ]),
+                    None => row.extend(["-".to_string(), "-".to_string(), "-".to_string()]),
+                }
+            }
+            if self.verbosity >= Verbosity::Full {
+                match stage.tx_window {
+                    Some((tx_start, tx_end)) => {
+                        row.extend([tx_start.to_string(), tx_end.to_string()])
+                    }
+                    None => row.extend(["-".to_string(), "-".to_string()]),
+                }
+                row.push(
+                    stage
+                        .residual_volume
+                        .map_or("-".to_string(), |volume| volume.to_string()),
+                );
+            }
+            rows.push(row);
+        }
+
+        let mut widths = vec![0; rows[0].len()];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = usize::max(*width, cell.len());
+            }
+        }
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the route as JSON, with the details of the verbosity level.
+    pub fn to_json(&self) -> String {
+        let mut filtered = self.clone();
+        if self.verbosity == Verbosity::Summary {
+            filtered.stages.clear();
+        }
+        for stage in &mut filtered.stages {
+            if self.verbosity < Verbosity::Contacts {
+                stage.contact = None;
+            }
+            if self.verbosity < Verbosity::Full {
+                stage.tx_window = None;
+                stage.residual_volume = None;
+            }
+        }
+        serde_json::to_string(&filtered).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for RouteDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Route to node {} at t={} with {} hop(s): ",
+            self.destination, self.at_time, self.hop_count
+        )?;
+        if self.verbosity == Verbosity::Summary {
+            return Ok(());
+        }
+        for stage in &self.stages {
+            write!(
+                f,
+                "\n\t- Reach node {} at t={} with {} hop(s)",
+                stage.to_node, stage.at_time, stage.hop_count
+            )?;
+            if self.verbosity >= Verbosity::Contacts {
+                if let Some(contact) = &stage.contact {
+                    write!(
+                        f,
+                        " via contact {}->{} [{}, {}]",
+                        contact.tx_node, contact.rx_node, contact.start, contact.end
+                    )?;
+                }
+            }
+            if self.verbosity >= Verbosity::Full {
+                if let Some((tx_start, tx_end)) = stage.tx_window {
+                    write!(f, ", tx [{}, {}]", tx_start, tx_end)?;
+                }
+                if let Some(volume) = stage.residual_volume {
+                    write!(f, ", residual volume {}", volume)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
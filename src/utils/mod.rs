@@ -10,6 +10,10 @@ use crate::{
     route_stage::RouteStage,
 };
 
+pub mod display;
+
+pub use display::{RouteDisplay, Verbosity};
+
 pub fn init_pathfinding<
     NM: NodeManager + DispatchParser<NM> + Parser<NM>,
     CM: ContactManager + DispatchParser<CM> + Parser<CM>,
@@ -30,28 +34,7 @@ pub fn init_pathfinding<
     ))));
 }
 
+/// Prints a route to the standard output, see `RouteDisplay` for other formats and sinks.
 pub fn pretty_print<NM: NodeManager, CM: ContactManager>(route: Rc<RefCell<RouteStage<NM, CM>>>) {
-    let mut backtrace: Vec<String> = Vec::new();
-    println!(
-        "Route to node {} at t={} with {} hop(s): ",
-        route.borrow().to_node,
-        route.borrow().at_time,
-        route.borrow().hop_count
-    );
-    let mut curr_route_opt = Some(route);
-    while let Some(curr_route_rc) = curr_route_opt.take() {
-        let curr_route = curr_route_rc.borrow();
-        backtrace.push(format!(
-            "\t- Reach node {} at t={} with {} hop(s)",
-            curr_route.to_node, curr_route.at_time, curr_route.hop_count
-        ));
-        match &curr_route.via {
-            Some(via_val) => curr_route_opt = Some(via_val.parent_route.clone()),
-            None => curr_route_opt = None,
-        }
-    }
-    println!(
-        "{}",
-        backtrace.into_iter().rev().collect::<Vec<_>>().join("\n")
-    );
+    println!("{}", RouteDisplay::new(&route));
 }
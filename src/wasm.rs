@@ -0,0 +1,163 @@
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::{
+        discipline::DisciplinedETOManager,
+        hybrid::HybridManager,
+        legacy::{eto::ETOManager, evl::EVLManager, qd::QDManager},
+        partition::coerce_partitioned,
+        playback::PlaybackManager,
+        predicted::PredictedManager,
+        seg::SegmentationManager,
+        ContactManager,
+    },
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    parsing::{coerce_cm, ContactMarkerMap},
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        Router,
+    },
+    types::{volume_from_f64, Date, NodeID, Priority},
+};
+
+/// A router usable from JavaScript, built from a contact plan provided as a string.
+///
+/// The contacts are parsed with the "evl", "qd", "eto", "seg", "hybrid", "deto", "playback",
+/// "predicted" and "part" markers, and the nodes are not managed.
+#[wasm_bindgen]
+pub struct WasmRouter {
+    router: Box<dyn Router<NoManagement, Box<dyn ContactManager>>>,
+}
+
+#[wasm_bindgen]
+impl WasmRouter {
+    /// Parses an A-SABR contact plan and builds the requested router.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_plan` - The content of the contact plan (A-SABR format).
+    /// * `router_type` - The name of the router (e.g. "SpsnHybridParenting", "CgrFirstEndingNodeParenting").
+    /// * `check_size` - SPSN only, whether the cached trees are checked by bundle size.
+    /// * `check_priority` - SPSN only, whether the cached trees are checked by bundle priority.
+    /// * `max_entries` - SPSN only, the size of the tree cache.
+    ///
+    /// # Returns
+    ///
+    /// The router, or an error message if the contact plan cannot be parsed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        contact_plan: &str,
+        router_type: &str,
+        check_size: bool,
+        check_priority: bool,
+        max_entries: usize,
+    ) -> Result<WasmRouter, JsError> {
+        let mut lexer = StrLexer::new(contact_plan);
+        let mut contact_dispatch: ContactMarkerMap = ContactMarkerMap::new();
+        contact_dispatch.add("evl", coerce_cm::<EVLManager>);
+        contact_dispatch.add("qd", coerce_cm::<QDManager>);
+        contact_dispatch.add("eto", coerce_cm::<ETOManager>);
+        contact_dispatch.add("seg", coerce_cm::<SegmentationManager>);
+        contact_dispatch.add("hybrid", coerce_cm::<HybridManager>);
+        contact_dispatch.add("part", coerce_partitioned);
+        contact_dispatch.add("part", coerce_partitioned);
+        contact_dispatch.add("deto", coerce_cm::<DisciplinedETOManager>);
+        contact_dispatch.add("playback", coerce_cm::<PlaybackManager>);
+        contact_dispatch.add("predicted", coerce_cm::<PredictedManager>);
+        contact_dispatch.add("predicted", coerce_cm::<PredictedManager>);
+
+        let (nodes, contacts) = ASABRContactPlan::parse::<NoManagement, Box<dyn ContactManager>>(
+            &mut lexer,
+            None,
+            Some(&contact_dispatch),
+        )
+        .map_err(|e| JsError::new(&e.to_string()))?;
+
+        let spsn_options = if router_type.starts_with("Spsn") {
+            Some(SpsnOptions {
+                check_size,
+                check_priority,
+                max_entries,
+            })
+        } else {
+            None
+        };
+
+        Ok(WasmRouter {
+            router: build_generic_router(router_type, nodes, contacts, spsn_options)
+                .map_err(|e| JsError::new(&e.to_string()))?,
+        })
+    }
+
+    /// Routes (and schedules) a bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The node initiating the routing operation.
+    /// * `destinations` - The destination(s) of the bundle.
+    /// * `priority` - The priority of the bundle.
+    /// * `size` - The size of the bundle.
+    /// * `expiration` - The expiration date of the bundle.
+    /// * `curr_time` - The current time.
+    /// * `excluded_nodes` - The nodes to exclude, sorted.
+    ///
+    /// # Returns
+    ///
+    /// A JSON string, an array with one entry per first hop (`tx_node`, `rx_node`, `start`, `end`)
+    /// and the routes (`to_node`, `at_time`, `hop_count`) using this first hop. The array is empty
+    /// if no route was found.
+    #[allow(clippy::too_many_arguments)]
+    pub fn route(
+        &mut self,
+        source: NodeID,
+        destinations: Vec<NodeID>,
+        priority: Priority,
+        size: f64,
+        expiration: Date,
+        curr_time: Date,
+        excluded_nodes: Vec<NodeID>,
+    ) -> String {
+        let bundle = Bundle {
+            source,
+            destinations,
+            priority,
+            size: volume_from_f64(size),
+            expiration,
+            soft_deadline: None,
+            critical: false,
+            destination_priorities: Vec::new(),
+        };
+
+        let mut hops = Vec::new();
+        if let Some(out) = self
+            .router
+            .route(source, &bundle, curr_time, &excluded_nodes)
+        {
+            for (contact, dest_routes) in out.first_hops.values() {
+                let info = contact.borrow().info;
+                let routes: Vec<_> = dest_routes
+                    .iter()
+                    .map(|route| {
+                        let route = route.borrow();
+                        json!({
+                            "to_node": route.to_node,
+                            "at_time": route.at_time,
+                            "hop_count": route.hop_count,
+                        })
+                    })
+                    .collect();
+                hops.push(json!({
+                    "tx_node": info.tx_node,
+                    "rx_node": info.rx_node,
+                    "start": info.start,
+                    "end": info.end,
+                    "routes": routes,
+                }));
+            }
+        }
+        serde_json::Value::Array(hops).to_string()
+    }
+}
@@ -0,0 +1,448 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cell::RefCell, cmp::Ordering, fmt};
+use serde::Serialize;
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    epoch::{parse_date, parse_epoch_declaration},
+    node_manager::NodeManager,
+    parsing::{Lexer, ParsingState},
+    pathfinding::PathfindingStats,
+    route_stage::RouteStage,
+    route_storage::RouteSignature,
+    routing::Router,
+    types::{time_to_f64, Date, HashSet, NodeID, Priority, Token, Volume},
+};
+
+/// A bundle submission of a `WorkloadTrace`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct TraceEntry {
+    /// The submission time of the bundle.
+    pub time: Date,
+    /// The bundle, submitted at its source node.
+    pub bundle: Bundle,
+}
+
+/// A bundle workload, to replay the same submissions against several routers.
+///
+/// The text format has one `bundle` entry per line (lines starting with `#` are comments):
+///
+/// ```text
+/// epoch <time>
+/// bundle <time> <source> <destination>[,<destination>...] <size> <priority> <expiration>
+/// ```
+///
+/// The times accept the same formats as the contact plans (relative, or absolute after an
+/// `epoch` declaration). The trace is printed back in the same format with `Display`, with
+/// relative times and without the epoch.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default)]
+pub struct WorkloadTrace {
+    /// The submissions, in the order of the trace.
+    pub entries: Vec<TraceEntry>,
+}
+
+/// Converts a parsing state into a result, the end of input being an error.
+fn expect<T>(state: ParsingState<T>, lexer: &dyn Lexer, field: &str) -> Result<T, String> {
+    match state {
+        ParsingState::Finished(value) => Ok(value),
+        ParsingState::Error(msg) => Err(msg),
+        ParsingState::EOF => Err(format!(
+            "Missing {} ({})",
+            field,
+            lexer.get_current_position()
+        )),
+    }
+}
+
+impl WorkloadTrace {
+    /// Parses a workload trace.
+    ///
+    /// # Parameters
+    ///
+    /// * `lexer` - The lexer providing the tokens of the trace.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, String>` - The trace, or an error message with the position of the error.
+    pub fn parse(lexer: &mut dyn Lexer) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        loop {
+            match lexer.consume_next_token() {
+                ParsingState::EOF => break,
+                ParsingState::Error(msg) => return Err(msg),
+                ParsingState::Finished(element_type) => {
+                    if element_type == "epoch" {
+                        parse_epoch_declaration(lexer)?;
+                        continue;
+                    }
+                    if element_type != "bundle" {
+                        return Err(format!(
+                            "Unrecognized element \"{}\" ({})",
+                            element_type,
+                            lexer.get_current_position()
+                        ));
+                    }
+                }
+            }
+
+            let time = expect(parse_date(lexer), lexer, "submission time")?;
+            let source = expect(<NodeID as Token<NodeID>>::parse(lexer), lexer, "source")?;
+            let destinations_token = expect(lexer.consume_next_token(), lexer, "destinations")?;
+            let mut destinations = Vec::new();
+            for destination in destinations_token.split(',') {
+                match destination.parse::<NodeID>() {
+                    Ok(destination) => destinations.push(destination),
+                    Err(_) => {
+                        return Err(format!(
+                            "Malformed destination \"{}\" ({})",
+                            destination,
+                            lexer.get_current_position()
+                        ))
+                    }
+                }
+            }
+            let size = expect(<Volume as Token<Volume>>::parse(lexer), lexer, "size")?;
+            let priority = expect(
+                <Priority as Token<Priority>>::parse(lexer),
+                lexer,
+                "priority",
+            )?;
+            let expiration = expect(parse_date(lexer), lexer, "expiration")?;
+
+            entries.push(TraceEntry {
+                time,
+                bundle: Bundle {
+                    source,
+                    destinations,
+                    priority,
+                    size,
+                    expiration,
+                    soft_deadline: None,
+                    critical: false,
+                    destination_priorities: Vec::new(),
+                },
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl fmt::Display for WorkloadTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let destinations: Vec<String> = entry
+                .bundle
+                .destinations
+                .iter()
+                .map(|destination| destination.to_string())
+                .collect();
+            writeln!(
+                f,
+                "bundle {} {} {} {} {} {}",
+                entry.time,
+                entry.bundle.source,
+                destinations.join(","),
+                entry.bundle.size,
+                entry.bundle.priority,
+                entry.bundle.expiration
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The aggregate results of `play_trace`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct TraceMetrics {
+    /// The count of bundles submitted.
+    pub submitted: usize,
+    /// The count of bundles routed (to at least one destination).
+    pub routed: usize,
+    /// The count of bundles the router failed to route.
+    pub unrouted: usize,
+    /// The count of destinations requested.
+    pub destinations: usize,
+    /// The count of destinations reached by the routes.
+    pub reached: usize,
+    /// The volume of the routed bundles.
+    pub routed_volume: Volume,
+    /// The mean delay between the submission and the delivery, over the reached destinations.
+    pub mean_latency: f64,
+    /// The maximum delay between the submission and the delivery.
+    pub max_latency: f64,
+    /// The mean hop count, over the reached destinations.
+    pub mean_hop_count: f64,
+    /// The count of distinct routes (contact sequences) used to reach the destinations.
+    pub unique_routes: usize,
+}
+
+/// The aggregation of the deliveries of a replay into `TraceMetrics`.
+#[derive(Default)]
+pub(crate) struct MetricsAccumulator {
+    /// The metrics, the means and the route count being set by `finish`.
+    metrics: TraceMetrics,
+    /// The sum of the latencies of the destinations reached.
+    total_latency: f64,
+    /// The sum of the hop counts of the destinations reached.
+    total_hop_count: f64,
+    /// The routes used to reach the destinations.
+    signatures: HashSet<RouteSignature>,
+}
+
+impl MetricsAccumulator {
+    /// Counts the submission of a bundle.
+    pub(crate) fn submit(&mut self, bundle: &Bundle) {
+        self.metrics.submitted += 1;
+        self.metrics.destinations += bundle.destinations.len();
+    }
+
+    /// Counts a destination reached by a route.
+    pub(crate) fn reach<NM: NodeManager, CM: ContactManager>(
+        &mut self,
+        latency: f64,
+        route: &Rc<RefCell<RouteStage<NM, CM>>>,
+    ) {
+        self.total_latency += latency;
+        if latency > self.metrics.max_latency {
+            self.metrics.max_latency = latency;
+        }
+        self.total_hop_count += route.borrow().hop_count as f64;
+        self.signatures.insert(RouteSignature::from_stage(route));
+    }
+
+    /// Counts the outcome of a bundle submitted, from the count of its destinations reached.
+    pub(crate) fn conclude(&mut self, bundle: &Bundle, reached: usize) {
+        if reached == 0 {
+            self.metrics.unrouted += 1;
+            return;
+        }
+        self.metrics.routed += 1;
+        self.metrics.reached += reached;
+        self.metrics.routed_volume += bundle.size;
+    }
+
+    /// Computes the means and returns the metrics.
+    pub(crate) fn finish(mut self) -> TraceMetrics {
+        self.metrics.unique_routes = self.signatures.len();
+        if self.metrics.reached > 0 {
+            self.metrics.mean_latency = self.total_latency / self.metrics.reached as f64;
+            self.metrics.mean_hop_count = self.total_hop_count / self.metrics.reached as f64;
+        }
+        self.metrics
+    }
+}
+
+/// Sorts the entries of a trace by increasing submission time (in the order of the trace for equal
+/// times).
+pub(crate) fn submission_order(trace: &WorkloadTrace) -> Vec<&TraceEntry> {
+    let mut entries: Vec<&TraceEntry> = trace.entries.iter().collect();
+    entries.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal));
+    entries
+}
+
+/// Replays a workload trace against a router and aggregates the results.
+///
+/// The entries are submitted by increasing submission time (in the order of the trace for equal
+/// times), the router being called with the submission time as the current time. The resources
+/// are consumed as for the actual routing, use a fresh router for each replay.
+///
+/// # Parameters
+///
+/// * `router` - The router.
+/// * `trace` - The workload trace.
+///
+/// # Returns
+///
+/// * `TraceMetrics` - The aggregate results.
+pub fn play_trace<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    trace: &WorkloadTrace,
+) -> TraceMetrics {
+    let mut accumulator = MetricsAccumulator::default();
+
+    for entry in submission_order(trace) {
+        let bundle = &entry.bundle;
+        accumulator.submit(bundle);
+
+        let Some(output) = router.route(bundle.source, bundle, entry.time, &Vec::new()) else {
+            accumulator.conclude(bundle, 0);
+            continue;
+        };
+
+        let destinations = resolve_destinations(router, bundle);
+        let mut reached = 0;
+        for (_contact, routes) in output.first_hops.values() {
+            for route in routes {
+                let at_time = {
+                    let route = route.borrow();
+                    if !destinations.contains(&route.to_node) {
+                        continue;
+                    }
+                    route.at_time
+                };
+                reached += 1;
+                accumulator.reach(time_to_f64(at_time - entry.time), route);
+            }
+        }
+        accumulator.conclude(bundle, reached);
+    }
+
+    accumulator.finish()
+}
+
+/// Replays a workload trace against a router like `play_trace`, and gathers the statistics of
+/// the pathfinding runs made during the replay (see `Router::pathfinding_stats`).
+///
+/// # Parameters
+///
+/// * `router` - The router.
+/// * `trace` - The workload trace.
+///
+/// # Returns
+///
+/// * `(TraceMetrics, Option<PathfindingStats>)` - The aggregate results, and the statistics of
+///   the pathfinding runs of the replay (`None` if the router does not gather them).
+pub fn profile_trace<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    trace: &WorkloadTrace,
+) -> (TraceMetrics, Option<PathfindingStats>) {
+    let before = router.pathfinding_stats().unwrap_or_default();
+    let metrics = play_trace(router, trace);
+    let stats = router.pathfinding_stats().map(|after| after.since(&before));
+    (metrics, stats)
+}
+
+/// Resolves the destinations of a bundle, the routes reaching the nodes of the aliased
+/// destinations.
+pub(crate) fn resolve_destinations<
+    NM: NodeManager,
+    CM: ContactManager,
+    R: Router<NM, CM> + ?Sized,
+>(
+    router: &R,
+    bundle: &Bundle,
+) -> Vec<NodeID> {
+    bundle
+        .destinations
+        .iter()
+        .map(|dest| router.resolve(&dest.to_string()).unwrap_or(*dest))
+        .collect()
+}
+
+/// The arrival times of the bundles of a trace, to check that several routers (or several builds)
+/// agree, see `trace_arrivals`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct TraceArrivals {
+    /// For each entry of the trace (in the order of the trace), the destinations reached and the
+    /// earliest arrival time at each of them, sorted by destination. Empty if the bundle was not
+    /// routed.
+    pub arrivals: Vec<Vec<(NodeID, Date)>>,
+}
+
+impl TraceArrivals {
+    /// Finds the first divergence from other arrivals, e.g. computed by another router.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The other arrivals, for the same trace.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - A description of the first entry whose arrivals differ, `None` if the
+    ///   arrivals are the same.
+    pub fn divergence(&self, other: &TraceArrivals) -> Option<String> {
+        if self.arrivals.len() != other.arrivals.len() {
+            return Some(format!(
+                "{} entries instead of {}",
+                other.arrivals.len(),
+                self.arrivals.len()
+            ));
+        }
+        self.arrivals
+            .iter()
+            .zip(&other.arrivals)
+            .position(|(expected, actual)| expected != actual)
+            .map(|idx| {
+                let format_arrivals = |arrivals: &Vec<(NodeID, Date)>| {
+                    let arrivals: Vec<String> = arrivals
+                        .iter()
+                        .map(|(node, time)| format!("{}@{}", node, time))
+                        .collect();
+                    format!("[{}]", arrivals.join(", "))
+                };
+                format!(
+                    "entry {}: {} instead of {}",
+                    idx,
+                    format_arrivals(&other.arrivals[idx]),
+                    format_arrivals(&self.arrivals[idx])
+                )
+            })
+    }
+}
+
+/// Replays a workload trace against a router like `play_trace`, and records the arrival times of
+/// each bundle.
+///
+/// # Parameters
+///
+/// * `router` - The router.
+/// * `trace` - The workload trace.
+///
+/// # Returns
+///
+/// * `TraceArrivals` - The arrival times, by entry of the trace.
+pub fn trace_arrivals<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    trace: &WorkloadTrace,
+) -> TraceArrivals {
+    let mut order: Vec<usize> = (0..trace.entries.len()).collect();
+    order.sort_by(|a, b| {
+        trace.entries[*a]
+            .time
+            .partial_cmp(&trace.entries[*b].time)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut arrivals = TraceArrivals {
+        arrivals: alloc::vec![Vec::new(); trace.entries.len()],
+    };
+    for idx in order {
+        let entry = &trace.entries[idx];
+        let bundle = &entry.bundle;
+        let Some(output) = router.route(bundle.source, bundle, entry.time, &Vec::new()) else {
+            continue;
+        };
+
+        let destinations = resolve_destinations(router, bundle);
+        let mut earliest: BTreeMap<NodeID, Date> = BTreeMap::new();
+        for (_contact, routes) in output.first_hops.values() {
+            for route in routes {
+                let route = route.borrow();
+                if !destinations.contains(&route.to_node) {
+                    continue;
+                }
+                earliest
+                    .entry(route.to_node)
+                    .and_modify(|time| {
+                        if route.at_time < *time {
+                            *time = route.at_time;
+                        }
+                    })
+                    .or_insert(route.at_time);
+            }
+        }
+        arrivals.arrivals[idx] = earliest.into_iter().collect();
+    }
+    arrivals
+}
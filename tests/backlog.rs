@@ -0,0 +1,126 @@
+//! The per-neighbor backlog (see `NeighborBacklog` and `BacklogAware`): the hops of the whole path
+//! wait for the backlog toward their receiving node, set by the application or learned from the
+//! scheduled transmissions.
+
+use std::{cell::RefCell, rc::Rc};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::{
+        backlog::{wrap_contacts, BacklogAware, NeighborBacklog},
+        legacy::evl::EVLManager,
+    },
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, capabilities, SpsnOptions},
+        Router,
+    },
+    types::{rate_from_f64, time_from_f64, volume_from_f64, RATE_ZERO, TIME_ZERO},
+};
+
+/// A single route from 0 to 2, via 1.
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 0 100 10 1\n";
+
+fn router(
+    router_type: &str,
+    backlog: &Rc<RefCell<NeighborBacklog>>,
+) -> Box<dyn Router<NoManagement, BacklogAware<EVLManager>>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    });
+    build_generic_router(
+        router_type,
+        nodes,
+        wrap_contacts(contacts, backlog),
+        options,
+    )
+    .unwrap()
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![2],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn remote_backlogs_delay_the_delivery() {
+    let mut estimated = false;
+    for router_type in capabilities().router_types {
+        let backlog = NeighborBacklog::new(false);
+        let mut router = router(router_type, &backlog);
+        let Some(free) = router.estimate(0, &bundle(), TIME_ZERO, &Vec::new()) else {
+            continue;
+        };
+        estimated = true;
+        assert!(free.delivery_time < time_from_f64(50.0), "{}", router_type);
+
+        // the backlog of the relay toward the destination, not known by the source contact
+        backlog.borrow_mut().set_eto(1, 2, time_from_f64(50.0));
+        let delayed = router
+            .estimate(0, &bundle(), TIME_ZERO, &Vec::new())
+            .unwrap();
+        assert!(
+            delayed.delivery_time > time_from_f64(50.0),
+            "{}",
+            router_type
+        );
+
+        backlog.borrow_mut().clear(1, 2);
+        let cleared = router
+            .estimate(0, &bundle(), TIME_ZERO, &Vec::new())
+            .unwrap();
+        assert!(
+            cleared.delivery_time == free.delivery_time,
+            "{}",
+            router_type
+        );
+    }
+    assert!(estimated);
+}
+
+#[test]
+fn learning_tables_push_the_eto_of_the_scheduled_hops() {
+    for learning in [false, true] {
+        let backlog = NeighborBacklog::new(learning);
+        let mut router = router("SpsnNodeParenting", &backlog);
+        assert!(router.route(0, &bundle(), TIME_ZERO, &Vec::new()).is_some());
+        let eto = backlog.borrow().get_eto(0, 1);
+        assert_eq!(eto.is_some(), learning);
+        if let Some(eto) = eto {
+            assert!(eto > TIME_ZERO);
+        }
+    }
+}
+
+#[test]
+fn backlog_volumes_are_drained_at_their_rate() {
+    let backlog = NeighborBacklog::new(false);
+    let mut backlog = backlog.borrow_mut();
+    backlog.set_backlog(
+        0,
+        1,
+        volume_from_f64(100.0),
+        rate_from_f64(10.0),
+        time_from_f64(5.0),
+    );
+    assert!(backlog.get_eto(0, 1) == Some(time_from_f64(15.0)));
+
+    // a backlog that is not drained is ignored
+    backlog.set_backlog(1, 2, volume_from_f64(100.0), RATE_ZERO, TIME_ZERO);
+    assert!(backlog.get_eto(1, 2).is_none());
+}
@@ -0,0 +1,83 @@
+//! Backup routes (see `Router::route_with_backup`): the backup avoids the first hop of the
+//! primary route, and is only computed when such a route exists.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, capabilities, SpsnOptions},
+        Router, RoutingOutput,
+    },
+    types::{time_from_f64, volume_from_f64, NodeID, TIME_ZERO},
+};
+
+/// Two routes from 0 to 3, via 1 (the best) and via 2.
+const PLAN: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 3 0 100 10 1\n\
+                    contact 0 2 0 100 10 2\ncontact 2 3 0 100 10 2\n";
+
+fn router(router_type: &str, plan: &str) -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(plan);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    });
+    build_generic_router(router_type, nodes, contacts, options).unwrap()
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![3],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+/// The neighbors of the first hops of a routing output.
+fn first_hops(output: &RoutingOutput<NoManagement, EVLManager>) -> Vec<NodeID> {
+    output
+        .first_hops
+        .values()
+        .map(|(contact, _)| contact.borrow().get_rx_node())
+        .collect()
+}
+
+#[test]
+fn backups_avoid_the_primary_first_hop() {
+    let mut backups = 0;
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type, PLAN);
+        let routing = router
+            .route_with_backup(0, &bundle(), TIME_ZERO, &Vec::new())
+            .unwrap();
+        assert_eq!(first_hops(&routing.primary), vec![1], "{}", router_type);
+        // the routers without backup support keep the default implementation
+        if let Some(backup) = routing.backup {
+            assert_eq!(first_hops(&backup), vec![2], "{}", router_type);
+            backups += 1;
+        }
+    }
+    assert!(backups > 0);
+}
+
+#[test]
+fn backups_need_another_first_hop() {
+    let plan = PLAN.replace("contact 0 2 0 100 10 2\n", "");
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type, &plan);
+        let routing = router
+            .route_with_backup(0, &bundle(), TIME_ZERO, &Vec::new())
+            .unwrap();
+        assert!(routing.backup.is_none(), "{}", router_type);
+    }
+}
@@ -0,0 +1,81 @@
+//! Critical bundles: a critical bundle is routed over all the feasible first hops, without
+//! altering the routing of the next bundles (see `route_critical_unicast`).
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, capabilities, SpsnOptions},
+        Router,
+    },
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+};
+
+/// Two routes from 0 to 2: direct, and via 1.
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 0 100 10 1\n\
+                    contact 0 2 0 100 10 1\n";
+
+fn router(router_type: &str) -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    });
+    build_generic_router(router_type, nodes, contacts, options).unwrap()
+}
+
+fn bundle(critical: bool) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![2],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn critical_bundles_use_every_first_hop() {
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type);
+        let output = router
+            .route(0, &bundle(true), TIME_ZERO, &Vec::new())
+            .unwrap();
+        // without the suppression, the neighbors are excluded but the destination cannot be: the
+        // direct route ends the computation
+        let expected = if cfg!(feature = "contact_suppression") {
+            2
+        } else {
+            1
+        };
+        assert_eq!(output.first_hops.len(), expected, "{}", router_type);
+    }
+}
+
+#[test]
+fn critical_bundles_leave_the_next_routings_untouched() {
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type);
+        assert!(router
+            .route(0, &bundle(true), TIME_ZERO, &Vec::new())
+            .is_some());
+        for _ in 0..3 {
+            assert!(
+                router
+                    .route(0, &bundle(false), TIME_ZERO, &Vec::new())
+                    .is_some(),
+                "{}",
+                router_type
+            );
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! The HTTP routing daemon (`asabr-daemon`), driven over TCP: plan loads, route requests, and
+//! the requests refused without disturbing the daemon.
+#![cfg(feature = "daemon")]
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+const PLAN: &str = "node 0 a\nnode 1 b\nnode 2 c\n\
+                    contact 0 1 0 100 evl 10 1\ncontact 1 2 0 100 evl 10 1\n";
+
+const ROUTE: &str = r#"{"source": 0, "destinations": [2], "priority": 0, "size": 1,
+                        "expiration": 1000, "curr_time": 0}"#;
+
+/// A daemon listening on a port picked by the system, killed on drop.
+struct Daemon {
+    child: Child,
+    address: String,
+}
+
+impl Daemon {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_asabr-daemon"))
+            .args(["127.0.0.1:0", "SpsnNodeParenting"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let address = line
+            .trim()
+            .trim_start_matches("A-SABR daemon listening on ")
+            .trim_end_matches('.')
+            .to_string();
+        Self { child, address }
+    }
+
+    /// Sends raw bytes, returns the status code and the body of the response.
+    fn send(&self, request: &[u8]) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(30)))
+            .unwrap();
+        stream.write_all(request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    fn request(&self, method: &str, target: &str, body: &str) -> (u16, String) {
+        self.send(
+            format!(
+                "{} {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                method,
+                target,
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )
+    }
+
+    fn stats(&self) -> serde_json::Value {
+        serde_json::from_str(&self.request("GET", "/stats", "").1).unwrap()
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn plans_are_loaded_and_bundles_routed() {
+    let daemon = Daemon::start();
+    assert_eq!(daemon.request("POST", "/route", ROUTE).0, 409);
+    assert_eq!(daemon.request("POST", "/plan", PLAN).0, 200);
+
+    let (status, body) = daemon.request("POST", "/route", ROUTE);
+    assert_eq!(status, 200);
+    assert!(body.contains("first_hops"));
+
+    // invalid endpoints
+    let empty = ROUTE.replace("[2]", "[]");
+    assert_eq!(daemon.request("POST", "/route", &empty).0, 400);
+    let unknown = ROUTE.replace("[2]", "[7]");
+    assert_eq!(daemon.request("POST", "/route", &unknown).0, 400);
+
+    let stats = daemon.stats();
+    assert_eq!(stats["node_count"], 3);
+    assert_eq!(stats["routed"], 1);
+}
+
+#[test]
+fn failed_loads_keep_the_previous_router() {
+    let daemon = Daemon::start();
+    assert_eq!(daemon.request("POST", "/plan", PLAN).0, 200);
+
+    // a router that cannot be built, then a plan that cannot be parsed
+    assert_eq!(
+        daemon.request("POST", "/plan?router=NoSuchRouter", PLAN).0,
+        400
+    );
+    assert_eq!(daemon.request("POST", "/plan", "contact 0\n").0, 400);
+
+    let stats = daemon.stats();
+    assert_eq!(stats["router"], "SpsnNodeParenting");
+    assert_eq!(stats["node_count"], 3);
+    assert_eq!(stats["plan_loads"], 1);
+    assert_eq!(daemon.request("POST", "/route", ROUTE).0, 200);
+}
+
+#[test]
+fn oversized_and_stalled_requests_are_dropped() {
+    let daemon = Daemon::start();
+    let (status, _) = daemon.send(b"POST /plan HTTP/1.1\r\nContent-Length: 1000000000\r\n\r\n");
+    assert_eq!(status, 413);
+
+    // a client that never sends its request does not block the next ones
+    let _stalled = TcpStream::connect(&daemon.address).unwrap();
+    assert_eq!(daemon.request("POST", "/plan", PLAN).0, 200);
+}
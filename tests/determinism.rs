@@ -0,0 +1,262 @@
+//! Determinism of the route computation: a canonical workload is routed on a fixture plan by the
+//! routers that are supposed to agree, and their arrival times are compared (see
+//! `workload::trace_arrivals`).
+//!
+//! The suite runs under the feature combination of the build, e.g. with `--features node_tx` or
+//! `--features contact_work_area,contact_suppression,first_depleted`, the routers compared being
+//! the ones compiled (see `capabilities`).
+
+use a_sabr::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::{legacy::evl::EVLManager, ContactManager},
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node::Node,
+    node_manager::{none::NoManagement, NodeManager},
+    parsing::Lexer,
+    routing::aliases::{build_generic_router, capabilities, SpsnOptions},
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+    workload::{trace_arrivals, TraceArrivals, WorkloadTrace},
+};
+
+#[cfg(feature = "node_tx")]
+use a_sabr::types::Date;
+
+/// Several routes toward each destination, with contacts starting and ending during the workload.
+const PLAN: &str = "node 0 a\nnode 1 b\nnode 2 c\nnode 3 d\nnode 4 e\nnode 5 f\n\
+                    contact 0 1 0 100 1000 1\ncontact 1 3 10 100 1000 2\n\
+                    contact 0 2 0 50 1000 5\ncontact 2 3 0 200 1000 1\n\
+                    contact 3 4 20 300 1000 1\ncontact 2 4 60 300 1000 10\n\
+                    contact 1 5 0 300 1000 3\ncontact 5 4 0 300 1000 1\n\
+                    contact 0 5 150 300 1000 1\ncontact 4 0 0 300 1000 1\n\
+                    contact 3 0 100 300 1000 2\n";
+
+/// Unicast bundles small enough for the volumes not to matter.
+const TRACE: &str = "bundle 0 0 3 1 0 1000\nbundle 0 0 4 1 0 1000\nbundle 5 0 5 1 0 1000\n\
+                     bundle 40 0 3 1 0 1000\nbundle 40 0 4 1 0 1000\nbundle 120 0 4 1 0 1000\n\
+                     bundle 120 4 5 1 0 1000\nbundle 160 0 4 1 0 1000\nbundle 160 3 4 1 0 1000\n\
+                     bundle 250 0 4 1 0 1000\nbundle 280 3 0 1 0 1000\n";
+
+fn parse() -> (
+    Vec<Node<NoManagement>>,
+    Vec<Contact<NoManagement, EVLManager>>,
+) {
+    let mut lexer = StrLexer::new(PLAN);
+    ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap()
+}
+
+fn trace() -> WorkloadTrace {
+    let mut lexer = StrLexer::new(TRACE);
+    WorkloadTrace::parse(&mut lexer as &mut dyn Lexer).unwrap()
+}
+
+fn spsn_options(router_type: &str) -> Option<SpsnOptions> {
+    router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    })
+}
+
+fn arrivals<NM: NodeManager + 'static, CM: ContactManager + 'static>(
+    router_type: &str,
+    nodes: Vec<Node<NM>>,
+    contacts: Vec<Contact<NM, CM>>,
+) -> TraceArrivals {
+    let mut router =
+        build_generic_router(router_type, nodes, contacts, spsn_options(router_type)).unwrap();
+    trace_arrivals(router.as_mut(), &trace())
+}
+
+/// The compiled routers minimizing the arrival time (SABR distance).
+fn earliest_arrival_routers() -> Vec<&'static str> {
+    capabilities()
+        .router_types
+        .into_iter()
+        .filter(|router_type| !router_type.ends_with("Hop") && *router_type != "CgrSabrCompliant")
+        .collect()
+}
+
+#[test]
+fn earliest_arrival_routers_agree() {
+    let (nodes, contacts) = parse();
+    let reference = arrivals("SpsnNodeParenting", nodes, contacts);
+    assert!(reference
+        .arrivals
+        .iter()
+        .all(|arrivals| !arrivals.is_empty()));
+
+    for router_type in earliest_arrival_routers() {
+        let (nodes, contacts) = parse();
+        let arrivals = arrivals(router_type, nodes, contacts);
+        if let Some(divergence) = reference.divergence(&arrivals) {
+            panic!(
+                "{} diverges from SpsnNodeParenting: {}",
+                router_type, divergence
+            );
+        }
+    }
+}
+
+#[test]
+fn route_digests_agree() {
+    let digests = |router_type: &str| {
+        let (nodes, contacts) = parse();
+        let mut router =
+            build_generic_router(router_type, nodes, contacts, spsn_options(router_type)).unwrap();
+        (1..6)
+            .map(|dest| router.route_digest(0, dest, TIME_ZERO))
+            .collect::<Vec<_>>()
+    };
+    let reference = digests("SpsnNodeParenting");
+    assert!(reference.iter().all(|digest| digest.is_some()));
+
+    for router_type in earliest_arrival_routers() {
+        assert_eq!(
+            digests(router_type),
+            reference,
+            "{} diverges from SpsnNodeParenting",
+            router_type
+        );
+    }
+}
+
+#[test]
+fn routing_is_repeatable() {
+    for router_type in capabilities().router_types {
+        let (nodes, contacts) = parse();
+        let first = arrivals(router_type, nodes, contacts);
+        let (nodes, contacts) = parse();
+        let second = arrivals(router_type, nodes, contacts);
+        if let Some(divergence) = first.divergence(&second) {
+            panic!("{} is not repeatable: {}", router_type, divergence);
+        }
+    }
+}
+
+#[test]
+fn multicast_bundles_do_not_panic() {
+    let bundle = Bundle {
+        source: 0,
+        destinations: vec![3, 4],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    };
+    for router_type in capabilities().router_types {
+        let (nodes, contacts) = parse();
+        let mut router =
+            build_generic_router(router_type, nodes, contacts, spsn_options(router_type)).unwrap();
+        // the routers not supporting multicast refuse the bundle
+        let _ = router.route(0, &bundle, TIME_ZERO, &Vec::new());
+    }
+}
+
+#[test]
+fn boxed_contact_managers_agree() {
+    for router_type in earliest_arrival_routers() {
+        let (nodes, contacts) = parse();
+        let reference = arrivals(router_type, nodes, contacts);
+
+        let (nodes, contacts) = parse();
+        let boxed: Vec<Contact<NoManagement, Box<dyn ContactManager>>> = contacts
+            .into_iter()
+            .map(|contact| {
+                Contact::try_new(
+                    contact.info,
+                    Box::new(contact.manager) as Box<dyn ContactManager>,
+                )
+                .unwrap()
+            })
+            .collect();
+        let arrivals = arrivals(router_type, nodes, boxed);
+        if let Some(divergence) = reference.divergence(&arrivals) {
+            panic!("{} diverges when boxed: {}", router_type, divergence);
+        }
+    }
+}
+
+/// A node manager transmitting a limited count of bundles, the schedulings must be delegated
+/// for the boxed managers to agree.
+#[cfg(feature = "node_tx")]
+#[derive(Debug)]
+struct LimitedTx {
+    remaining: usize,
+}
+
+#[cfg(feature = "node_tx")]
+impl NodeManager for LimitedTx {
+    #[cfg(feature = "node_proc")]
+    fn dry_run_process(&self, at_time: Date, _bundle: &mut Bundle) -> Date {
+        at_time
+    }
+    fn dry_run_tx(&self, _waiting_since: Date, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        self.remaining > 0
+    }
+    #[cfg(feature = "node_rx")]
+    fn dry_run_rx(&self, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+    #[cfg(feature = "node_proc")]
+    fn schedule_process(&self, at_time: Date, _bundle: &mut Bundle) -> Date {
+        at_time
+    }
+    fn schedule_tx(
+        &mut self,
+        _waiting_since: Date,
+        _start: Date,
+        _end: Date,
+        _bundle: &Bundle,
+    ) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+    #[cfg(feature = "node_rx")]
+    fn schedule_rx(&mut self, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "node_tx")]
+#[test]
+fn boxed_node_managers_agree() {
+    fn with_manager<NM: NodeManager>(make: impl Fn() -> NM) -> Vec<Node<NM>> {
+        parse()
+            .0
+            .into_iter()
+            .map(|node| Node::try_new(node.info, make()).unwrap())
+            .collect()
+    }
+    fn with_node_manager<NM: NodeManager>(
+        contacts: Vec<Contact<NoManagement, EVLManager>>,
+    ) -> Vec<Contact<NM, EVLManager>> {
+        contacts
+            .into_iter()
+            .map(|contact| Contact::try_new(contact.info, contact.manager).unwrap())
+            .collect()
+    }
+
+    for router_type in earliest_arrival_routers() {
+        let nodes = with_manager(|| LimitedTx { remaining: 3 });
+        let contacts = with_node_manager(parse().1);
+        let reference = arrivals(router_type, nodes, contacts);
+        // the transmissions are limited
+        assert!(reference
+            .arrivals
+            .iter()
+            .any(|arrivals| arrivals.is_empty()));
+
+        let nodes = with_manager(|| Box::new(LimitedTx { remaining: 3 }));
+        let contacts = with_node_manager(parse().1);
+        let arrivals = arrivals(router_type, nodes, contacts);
+        if let Some(divergence) = reference.divergence(&arrivals) {
+            panic!("{} diverges when boxed: {}", router_type, divergence);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Disjoint routes (see `disjoint_routes`): the routes computed share no intermediate node, or no
+//! contact, and the suppressions are restored afterwards.
+
+use std::{cell::RefCell, rc::Rc};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    distance::sabr::SABR,
+    multigraph::Multigraph,
+    node_manager::none::NoManagement,
+    pathfinding::{
+        disjoint::{disjoint_routes, Disjointness},
+        node_parenting::NodeParentingPathExcl,
+        Pathfinding,
+    },
+    route_storage::Route,
+    types::{time_from_f64, NodeID, TIME_ZERO, VOLUME_ZERO},
+};
+
+type Path = NodeParentingPathExcl<NoManagement, EVLManager, SABR>;
+
+/// Routes from 0 to 4 via 1 (two of them, with distinct contacts), via 2 and via 3, by
+/// increasing delay.
+const PLAN: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 c\nnode 4 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 4 0 100 10 1\n\
+                    contact 0 1 0 100 10 2\ncontact 1 4 0 100 10 2\n\
+                    contact 0 2 0 100 10 3\ncontact 2 4 0 100 10 3\n\
+                    contact 0 3 0 100 10 4\ncontact 3 4 0 100 10 4\n";
+
+fn pathfinding() -> Path {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    Path::new(Rc::new(RefCell::new(Multigraph::new(nodes, contacts))))
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![4],
+        priority: 0,
+        size: VOLUME_ZERO,
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+type Hops = Vec<(NodeID, Rc<RefCell<Contact<NoManagement, EVLManager>>>)>;
+
+/// The receiving nodes and the contacts of the hops of a route.
+fn hops(route: &Route<NoManagement, EVLManager>) -> Hops {
+    let mut hops: Hops = route
+        .destination_stage
+        .borrow()
+        .ancestors()
+        .chain(std::iter::once(route.destination_stage.clone()))
+        .filter_map(|stage| {
+            let stage = stage.borrow();
+            stage
+                .get_via_contact()
+                .map(|contact| (stage.to_node, contact))
+        })
+        .collect();
+    hops.sort_by_key(|(node, _)| *node);
+    hops
+}
+
+fn compute(pathfinding: &mut Path, count: usize, disjointness: Disjointness) -> Vec<Hops> {
+    disjoint_routes(
+        pathfinding,
+        TIME_ZERO,
+        0,
+        &bundle(),
+        &[],
+        count,
+        disjointness,
+    )
+    .iter()
+    .map(hops)
+    .collect()
+}
+
+/// Whether two routes share a contact.
+fn share_a_contact(a: &Hops, b: &Hops) -> bool {
+    a.iter()
+        .any(|(_, contact)| b.iter().any(|(_, other)| Rc::ptr_eq(contact, other)))
+}
+
+#[test]
+fn node_disjoint_routes_share_no_node() {
+    let mut pathfinding = pathfinding();
+    let routes = compute(&mut pathfinding, 5, Disjointness::Node);
+    let relays: Vec<NodeID> = routes.iter().map(|hops| hops[0].0).collect();
+    assert_eq!(relays, vec![1, 2, 3]);
+    for (i, a) in routes.iter().enumerate() {
+        for b in &routes[i + 1..] {
+            assert!(!share_a_contact(a, b));
+        }
+    }
+}
+
+#[cfg(feature = "contact_suppression")]
+#[test]
+fn contact_disjoint_routes_share_no_contact() {
+    let mut pathfinding = pathfinding();
+    let routes = compute(&mut pathfinding, 3, Disjointness::Contact);
+    // the second route goes through the relay of the first one, with other contacts
+    let relays: Vec<NodeID> = routes.iter().map(|hops| hops[0].0).collect();
+    assert_eq!(relays, vec![1, 1, 2]);
+    for (i, a) in routes.iter().enumerate() {
+        for b in &routes[i + 1..] {
+            assert!(!share_a_contact(a, b));
+        }
+    }
+
+    // the suppressions are restored, the same routes are computed again
+    let again = compute(&mut pathfinding, 3, Disjointness::Contact);
+    assert_eq!(again.len(), 3);
+    for (a, b) in routes.iter().zip(&again) {
+        assert!(a.iter().zip(b).all(|((_, a), (_, b))| Rc::ptr_eq(a, b)));
+    }
+}
@@ -0,0 +1,88 @@
+//! Absolute times: ISO 8601 timestamps, DTN epoch seconds (`dtn:`), and their conversion to
+//! dates relative to the epoch of a plan.
+
+use a_sabr::{
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    epoch::{parse_absolute_time, parse_iso8601, Epoch},
+    node_manager::none::NoManagement,
+    types::{time_from_f64, time_from_seconds},
+};
+
+#[test]
+fn iso_timestamps_are_parsed() {
+    assert_eq!(parse_iso8601("2000-01-01T00:00:00Z"), Some(0.0));
+    assert_eq!(parse_iso8601("2025-01-01T00:00:00Z"), Some(789004800.0));
+    assert_eq!(parse_iso8601("2025-01-01t00:01:40.5z"), Some(789004900.5));
+    // the offsets are subtracted
+    assert_eq!(
+        parse_iso8601("2025-01-01T02:00:00+02:00"),
+        Some(789004800.0)
+    );
+    assert_eq!(
+        parse_iso8601("2024-12-31T23:30:00-00:30"),
+        Some(789004800.0)
+    );
+
+    for malformed in [
+        "2025-01-01",
+        "2025-1-01T00:00:00Z",
+        "2025-13-01T00:00:00Z",
+        "2025-00-10T00:00:00Z",
+        "2025-01-01T24:00:00Z",
+        "2025-01-01T00:00:00.Z",
+        "2025-01-01T00:00:00",
+    ] {
+        assert_eq!(parse_iso8601(malformed), None, "{}", malformed);
+    }
+}
+
+#[test]
+fn iso_days_are_checked_against_the_month() {
+    assert!(parse_iso8601("2025-01-31T00:00:00Z").is_some());
+    assert!(parse_iso8601("2025-04-30T00:00:00Z").is_some());
+    assert!(parse_iso8601("2025-02-31T00:00:00Z").is_none());
+    assert!(parse_iso8601("2025-04-31T00:00:00Z").is_none());
+    // the leap years
+    assert!(parse_iso8601("2024-02-29T00:00:00Z").is_some());
+    assert!(parse_iso8601("2000-02-29T00:00:00Z").is_some());
+    assert!(parse_iso8601("2025-02-29T00:00:00Z").is_none());
+    assert!(parse_iso8601("1900-02-29T00:00:00Z").is_none());
+}
+
+#[test]
+fn dtn_times_are_parsed() {
+    assert_eq!(parse_absolute_time("dtn:789004800"), Some(789004800.0));
+    assert_eq!(parse_absolute_time("dtn:12.5"), Some(12.5));
+    assert_eq!(
+        parse_absolute_time("2025-01-01T00:00:00Z"),
+        Some(789004800.0)
+    );
+    assert_eq!(parse_absolute_time("dtn:soon"), None);
+    // a relative time is not absolute
+    assert_eq!(parse_absolute_time("60"), None);
+}
+
+#[test]
+fn times_are_relative_to_the_epoch() {
+    let epoch = Epoch::from_iso8601("2025-01-01T00:00:00Z").unwrap();
+    assert_eq!(epoch.dtn_seconds(), 789004800.0);
+    assert!(epoch.parse_time("2025-01-01T00:01:00Z") == Some(time_from_seconds(60.0)));
+    assert!(epoch.parse_time("dtn:789004700") == Some(time_from_seconds(-100.0)));
+    // the relative times are kept as is
+    assert!(epoch.parse_time("60") == Some(time_from_f64(60.0)));
+    assert!(epoch.parse_time("2025-02-30T00:00:00Z").is_none());
+
+    // the contact plans declaring an epoch
+    let plan = "epoch 2025-01-01T00:00:00Z\nnode 0 a\nnode 1 b\n\
+                contact 0 1 2025-01-01T00:01:40Z dtn:789005000 10 1\n";
+    let mut lexer = StrLexer::new(plan);
+    let (_, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    assert!(contacts[0].info.start == time_from_seconds(100.0));
+    assert!(contacts[0].info.end == time_from_seconds(200.0));
+
+    // without epoch, the absolute times are rejected
+    let mut lexer = StrLexer::new(&plan[plan.find('\n').unwrap() + 1..]);
+    assert!(ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).is_err());
+}
@@ -0,0 +1,63 @@
+//! Route estimations (see `Router::estimate`): the external requests toward unknown nodes are
+//! answered without a route instead of panicking.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, capabilities, SpsnOptions},
+        Router,
+    },
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+};
+
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 0 100 10 1\n";
+
+fn router(router_type: &str) -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    });
+    build_generic_router(router_type, nodes, contacts, options).unwrap()
+}
+
+fn bundle(dest: u16) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![dest],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn unknown_destinations_have_no_estimate() {
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type);
+        assert!(
+            router
+                .estimate(0, &bundle(7), TIME_ZERO, &Vec::new())
+                .is_none(),
+            "{}",
+            router_type
+        );
+        assert!(router.route_digest(0, 7, TIME_ZERO).is_none());
+        // the known destinations are still estimated (if supported)
+        let estimate = router.estimate(0, &bundle(2), TIME_ZERO, &Vec::new());
+        assert_eq!(
+            estimate.is_some(),
+            router.route_digest(0, 2, TIME_ZERO).is_some()
+        );
+    }
+}
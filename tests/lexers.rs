@@ -0,0 +1,77 @@
+//! The contact plan lexers (`StrLexer`, `FileLexer` and `BufferLexer`) tokenize the same input
+//! alike: tokens, positions, spans and epoch declarations.
+
+use a_sabr::{
+    contact_plan::{
+        asabr_buffer_lexer::BufferLexer, asabr_file_lexer::FileLexer, asabr_str_lexer::StrLexer,
+    },
+    epoch::Epoch,
+    parsing::{Lexer, ParsingState, Span},
+};
+
+const PLAN: &str = "# a comment\n\nnode 0 a\n  # an indented comment\r\n\
+                    contact 0 1\t0 100 10 1\r\n   \nnode 1 b";
+
+/// The tokens of a lexer, with their position and span, looked up before being consumed.
+fn tokens(lexer: &mut dyn Lexer) -> Vec<(String, String, Option<Span>)> {
+    let mut tokens = Vec::new();
+    loop {
+        let looked_up = match lexer.lookup() {
+            ParsingState::Finished(token) => token,
+            ParsingState::EOF => break,
+            ParsingState::Error(message) => panic!("{}", message),
+        };
+        let ParsingState::Finished(token) = lexer.consume_next_token() else {
+            panic!("the looked up token was not consumed");
+        };
+        assert_eq!(looked_up, token);
+        tokens.push((
+            token,
+            lexer.get_current_position(),
+            lexer.get_current_span(),
+        ));
+    }
+    assert!(matches!(lexer.consume_next_token(), ParsingState::EOF));
+    tokens
+}
+
+#[test]
+fn lexers_agree() {
+    let path = std::env::temp_dir().join(format!("a_sabr_lexers_{}.cp", std::process::id()));
+    std::fs::write(&path, PLAN).unwrap();
+    let mut file_lexer = FileLexer::new(path.to_str().unwrap()).unwrap();
+    let from_file = tokens(&mut file_lexer);
+    std::fs::remove_file(&path).unwrap();
+
+    let from_str = tokens(&mut StrLexer::new(PLAN));
+    assert_eq!(from_str.len(), 13);
+    assert_eq!(from_str, from_file);
+    assert_eq!(from_str, tokens(&mut BufferLexer::new(PLAN)));
+
+    let (token, position, span) = &from_str[5];
+    assert_eq!(token, "1");
+    assert_eq!(position, "line 5, token 3");
+    let span = span.as_ref().unwrap();
+    assert_eq!(&PLAN[span.start..span.end], "1");
+    assert_eq!(span.line_text, "contact 0 1\t0 100 10 1");
+}
+
+#[test]
+fn lexers_hold_the_epoch() {
+    let path = std::env::temp_dir().join(format!("a_sabr_epoch_{}.cp", std::process::id()));
+    std::fs::write(&path, PLAN).unwrap();
+    let mut file_lexer = FileLexer::new(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut str_lexer = StrLexer::new(PLAN);
+    let mut buffer_lexer = BufferLexer::new(PLAN);
+    let lexers: [&mut dyn Lexer; 3] = [&mut file_lexer, &mut str_lexer, &mut buffer_lexer];
+    for lexer in lexers {
+        assert!(lexer.get_epoch().is_none());
+        assert!(lexer.set_epoch(Epoch::from_dtn_seconds(1000.0)));
+        assert_eq!(
+            lexer.get_epoch().map(|epoch| epoch.dtn_seconds()),
+            Some(1000.0)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! The bounded multi-copy router: the copies take contact-disjoint routes, and the alternative
+//! pathfindings keep routing the next bundles alike.
+#![cfg(feature = "contact_suppression")]
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    distance::sabr::SABR,
+    node_manager::none::NoManagement,
+    pathfinding::{limiting_contact::FirstEnding, node_parenting::NodeParentingPath, Pathfinding},
+    routing::{multicopy::MultiCopy, Router},
+    types::{time_from_f64, volume_from_f64, NodeID, TIME_ZERO},
+};
+
+/// Two disjoint routes from 0 to 3, via 1 and via 2.
+const PLAN: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 3 0 100 10 1\n\
+                    contact 0 2 0 100 10 2\ncontact 2 3 0 100 10 2\n";
+
+fn router<P: Pathfinding<NoManagement, EVLManager>>() -> MultiCopy<NoManagement, EVLManager, P> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    MultiCopy::new(nodes, contacts, 2)
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![3],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+/// The neighbors receiving a copy, sorted.
+fn neighbors<P: Pathfinding<NoManagement, EVLManager>>(
+    router: &mut MultiCopy<NoManagement, EVLManager, P>,
+) -> Vec<NodeID> {
+    let output = router.route(0, &bundle(), TIME_ZERO, &Vec::new()).unwrap();
+    let mut neighbors: Vec<NodeID> = output
+        .first_hops
+        .values()
+        .map(|(contact, _)| contact.borrow().get_rx_node())
+        .collect();
+    neighbors.sort();
+    neighbors
+}
+
+#[test]
+fn copies_take_disjoint_routes() {
+    let mut router = router::<NodeParentingPath<NoManagement, EVLManager, SABR>>();
+    assert_eq!(neighbors(&mut router), vec![1, 2]);
+}
+
+#[test]
+fn alternative_pathfindings_route_the_next_bundles() {
+    let mut router = router::<
+        FirstEnding<NoManagement, EVLManager, NodeParentingPath<NoManagement, EVLManager, SABR>>,
+    >();
+    for _ in 0..3 {
+        assert_eq!(neighbors(&mut router), vec![1, 2]);
+    }
+}
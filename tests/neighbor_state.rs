@@ -0,0 +1,60 @@
+//! The liveness of the neighbors (see `Router::set_neighbor_state`): no route goes through a
+//! neighbor marked down, until it is back up.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, capabilities, SpsnOptions},
+        NeighborState, Router,
+    },
+    types::{time_from_f64, volume_from_f64, Date, TIME_ZERO},
+};
+
+/// A single route from 0 to 2, via 1.
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 0 100 10 1\n";
+
+fn router(router_type: &str) -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = router_type.starts_with("Spsn").then_some(SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    });
+    build_generic_router(router_type, nodes, contacts, options).unwrap()
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![2],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn neighbors_down_until_marked_up_are_avoided() {
+    for router_type in capabilities().router_types {
+        let mut router = router(router_type);
+        if !router.set_neighbor_state(1, NeighborState::Down, Date::MAX) {
+            continue;
+        }
+        assert!(
+            router.route(0, &bundle(), TIME_ZERO, &Vec::new()).is_none(),
+            "{}",
+            router_type
+        );
+        assert!(router.set_neighbor_state(1, NeighborState::Up, Date::MAX));
+        assert!(router.route(0, &bundle(), TIME_ZERO, &Vec::new()).is_some());
+    }
+}
@@ -0,0 +1,68 @@
+//! Protobuf contact plans: decoding, and the rejection of the malformed plans (see
+//! `convert_contact_plan`).
+#![cfg(feature = "interop-proto")]
+
+use a_sabr::{
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::semantics::ContactSemantics,
+    proto::{decode_contact_plan, Contact, ContactPlan, Node},
+};
+use prost::Message;
+
+fn node(id: u32) -> Node {
+    Node {
+        id,
+        name: format!("node{}", id),
+        excluded: false,
+    }
+}
+
+fn contact(tx_node: u32, rx_node: u32) -> Contact {
+    Contact {
+        tx_node,
+        rx_node,
+        start: 0.0,
+        end: 100.0,
+        data_rate: 10.0,
+        delay: 1.0,
+    }
+}
+
+fn decode(plan: &ContactPlan) -> Result<usize, String> {
+    let bytes = plan.encode_to_vec();
+    decode_contact_plan::<EVLManager>(&bytes, ContactSemantics::default())
+        .map(|(_, contacts, _)| contacts.len())
+}
+
+#[test]
+fn valid_plans_are_decoded() {
+    let plan = ContactPlan {
+        nodes: vec![node(1), node(0), node(2)],
+        contacts: vec![contact(0, 1), contact(1, 2)],
+    };
+    assert_eq!(decode(&plan), Ok(2));
+}
+
+#[test]
+fn bad_node_ids_are_rejected() {
+    // a contact toward an undeclared node
+    let plan = ContactPlan {
+        nodes: vec![node(0), node(1)],
+        contacts: vec![contact(0, 1), contact(1, 5)],
+    };
+    assert!(decode(&plan).is_err());
+
+    // a gap in the node ids
+    let plan = ContactPlan {
+        nodes: vec![node(0), node(2)],
+        contacts: vec![contact(0, 1)],
+    };
+    assert!(decode(&plan).is_err());
+
+    // a duplicated node id
+    let plan = ContactPlan {
+        nodes: vec![node(0), node(0)],
+        contacts: vec![contact(0, 1)],
+    };
+    assert!(decode(&plan).is_err());
+}
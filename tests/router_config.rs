@@ -0,0 +1,117 @@
+//! Routers described by a `RouterConfig`: the guard settings and the commit policy reach the
+//! router built, and the required features are checked against every Cargo feature.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{config::RouterConfig, Router},
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+};
+
+/// A route from 0 to 2 via 1, the contact toward 2 (100 units of volume) starting at 50.
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 50 60 10 1\n";
+
+fn build(json: &str) -> Box<dyn Router<NoManagement, EVLManager>> {
+    let config = RouterConfig::from_json(json).unwrap();
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    config.build(nodes, contacts).unwrap()
+}
+
+fn bundle(size: f64) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![2],
+        priority: 0,
+        size: volume_from_f64(size),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn commit_policy_sets_the_booking_horizon() {
+    // the contact toward 2 is booked by the first bundle
+    let mut router = build(r#"{"mainframe": "Spsn", "pathfinding": "NodeParenting"}"#);
+    assert!(router
+        .route(0, &bundle(100.0), TIME_ZERO, &Vec::new())
+        .is_some());
+    assert!(router
+        .route(0, &bundle(100.0), TIME_ZERO, &Vec::new())
+        .is_none());
+
+    // the contact toward 2 starts beyond the horizon, it is not booked
+    let mut router = build(
+        r#"{"mainframe": "Spsn", "pathfinding": "NodeParenting", "commit": {"Horizon": 10.0}}"#,
+    );
+    assert!(router
+        .route(0, &bundle(100.0), TIME_ZERO, &Vec::new())
+        .is_some());
+    assert!(router
+        .route(0, &bundle(100.0), TIME_ZERO, &Vec::new())
+        .is_some());
+
+    let config = r#"{"mainframe": "Spsn", "pathfinding": "NodeParenting",
+                     "commit": {"Horizon": -1.0}}"#;
+    assert!(RouterConfig::from_json(config).unwrap().validate().is_err());
+}
+
+#[test]
+fn guard_settings_are_applied() {
+    // the limit learned from an oversized bundle
+    let guard_limit = |json: &str| {
+        let mut router = build(json);
+        assert!(router
+            .route(0, &bundle(500.0), TIME_ZERO, &Vec::new())
+            .is_none());
+        router
+            .congestion(0, &bundle(10.0), TIME_ZERO, &Vec::new())
+            .unwrap()
+            .guard_limit
+    };
+
+    assert!(
+        guard_limit(r#"{"mainframe": "Spsn", "pathfinding": "NodeParenting"}"#)
+            == Some(volume_from_f64(500.0))
+    );
+    assert!(guard_limit(
+        r#"{"mainframe": "Spsn", "pathfinding": "NodeParenting", "guard": {"enabled": false}}"#
+    )
+    .is_none());
+}
+
+#[test]
+fn every_cargo_feature_is_known() {
+    let manifest = include_str!("../Cargo.toml");
+    let features = manifest
+        .split("[features]")
+        .nth(1)
+        .unwrap()
+        .split("\n[")
+        .next()
+        .unwrap();
+    let names: Vec<&str> = features
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+        .filter(|name| *name != "default")
+        .collect();
+    assert!(names.len() > 10);
+    for name in names {
+        let config = format!(
+            r#"{{"mainframe": "Spsn", "pathfinding": "NodeParenting",
+                 "required_features": ["{}"]}}"#,
+            name
+        );
+        // the feature may be missing from this build, it must not be unknown
+        if let Err(message) = RouterConfig::from_json(&config).unwrap().validate() {
+            assert!(!message.contains("Unknown"), "{}", message);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Storage policies of `RoutingTable`: duplicate detection, per-destination capacity and
+//! replacement of the worst routes, under the store/select cycles of `Cgr`.
+#![cfg(feature = "contact_suppression")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact::Contact,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    distance::sabr::SABR,
+    multigraph::Multigraph,
+    node::Node,
+    node_manager::none::NoManagement,
+    pathfinding::{limiting_contact::FirstEnding, node_parenting::NodeParentingPath, Pathfinding},
+    route_stage::RouteStage,
+    route_storage::{
+        table::{AgingPolicy, CandidateVerdict, RoutingTable},
+        Route, RouteStorage,
+    },
+    routing::{aliases::CgrFirstEndingNodeParenting, cgr::CgrBudget, refresh_routes, Router},
+    types::{duration_from_f64, time_from_f64, NodeID, TIME_ZERO, VOLUME_ZERO},
+};
+
+type Table = RoutingTable<NoManagement, EVLManager, SABR>;
+
+/// Three routes from 0 to 4, via 1 (delivery at 2), via 2 (at 4) and via 3 (at 6).
+const PLAN: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 c\nnode 4 dst\n\
+                    contact 0 1 0 1000 100 1\ncontact 1 4 0 1000 100 1\n\
+                    contact 0 2 0 1000 100 2\ncontact 2 4 0 1000 100 2\n\
+                    contact 0 3 0 1000 100 3\ncontact 3 4 0 1000 100 3\n";
+
+fn parse() -> (
+    Vec<Node<NoManagement>>,
+    Vec<Contact<NoManagement, EVLManager>>,
+) {
+    let mut lexer = StrLexer::new(PLAN);
+    ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap()
+}
+
+fn multigraph() -> Rc<RefCell<Multigraph<NoManagement, EVLManager>>> {
+    let (nodes, contacts) = parse();
+    Rc::new(RefCell::new(Multigraph::new(nodes, contacts)))
+}
+
+fn bundle() -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![4],
+        priority: 0,
+        size: VOLUME_ZERO,
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+fn next_route<P: Pathfinding<NoManagement, EVLManager>>(
+    pathfinding: &mut P,
+) -> Route<NoManagement, EVLManager> {
+    let tree = pathfinding.get_next(TIME_ZERO, 0, &bundle(), &vec![]);
+    let route = Route::from_tree(Rc::new(RefCell::new(tree)), 4).unwrap();
+    RouteStage::init_route(route.destination_stage.clone());
+    route
+}
+
+#[test]
+fn duplicates_are_stored_once() {
+    let mut pathfinding = NodeParentingPath::<NoManagement, EVLManager, SABR>::new(multigraph());
+    let mut table = Table::new();
+
+    for _ in 0..3 {
+        table.store(&bundle(), next_route(&mut pathfinding));
+    }
+    assert_eq!(table.route_count(4), 1);
+}
+
+#[test]
+fn capacity_keeps_the_best_routes() {
+    let multigraph = multigraph();
+    let mut pathfinding = FirstEnding::<
+        NoManagement,
+        EVLManager,
+        NodeParentingPath<NoManagement, EVLManager, SABR>,
+    >::new(multigraph.clone());
+    let routes: Vec<_> = (0..3).map(|_| next_route(&mut pathfinding)).collect();
+
+    let mut table = Table::with_audit();
+    table.set_max_routes(Some(2));
+    // from the worst to the best, the best one replaces the worst one
+    for route in routes.iter().rev() {
+        table.store(&bundle(), route.clone());
+    }
+    assert_eq!(table.route_count(4), 2);
+    // a route worse than the stored ones is dropped
+    table.store(&bundle(), routes[2].clone());
+    assert_eq!(table.route_count(4), 2);
+
+    let route = table
+        .select(&bundle(), TIME_ZERO, multigraph, &vec![])
+        .unwrap();
+    assert_eq!(route.destination_stage.borrow().at_time, time_from_f64(2.0));
+    let mut entry_nodes: Vec<_> = table
+        .last_audit()
+        .unwrap()
+        .iter()
+        .map(|record| (record.entry_node.unwrap(), record.verdict))
+        .collect();
+    entry_nodes.sort_by_key(|(node, _)| *node);
+    assert!(entry_nodes[0] == (1, CandidateVerdict::Selected));
+    assert!(entry_nodes[1] == (2, CandidateVerdict::Outranked));
+}
+
+#[test]
+fn cgr_cycles_stay_bounded() {
+    let (nodes, contacts) = parse();
+    let mut table = Table::with_max_routes(2);
+    // each route is used once, then recomputed (the refreshes store the same routes again)
+    table.set_aging(AgingPolicy {
+        max_uses: Some(1),
+        max_age: None,
+        refresh_top_k: 3,
+    });
+    let table = Rc::new(RefCell::new(table));
+    let mut router = CgrFirstEndingNodeParenting::new(nodes, contacts, table.clone());
+
+    for i in 0..20 {
+        let output = router.route(0, &bundle(), time_from_f64(i as f64), &vec![]);
+        assert!(output.is_some(), "bundle {} not routed", i);
+        assert!(table.borrow().route_count(4) <= 2);
+    }
+}
+
+#[test]
+fn refreshes_recompute_distinct_routes() {
+    let multigraph = multigraph();
+    let mut pathfinding = FirstEnding::<
+        NoManagement,
+        EVLManager,
+        NodeParentingPath<NoManagement, EVLManager, SABR>,
+    >::new(multigraph.clone());
+    // earlier routings left suppressions for the destination
+    next_route(&mut pathfinding);
+    next_route(&mut pathfinding);
+
+    let table = Rc::new(RefCell::new(Table::with_aging(AgingPolicy {
+        max_uses: None,
+        max_age: Some(duration_from_f64(1.0)),
+        refresh_top_k: 2,
+    })));
+    let mut checker = NodeParentingPath::<NoManagement, EVLManager, SABR>::new(multigraph);
+    table
+        .borrow_mut()
+        .store(&bundle(), next_route(&mut checker));
+
+    // the route computed at 0 is aged at 10, the two best routes are recomputed
+    let stored = refresh_routes(&mut pathfinding, &table, 0, time_from_f64(10.0));
+    assert_eq!(stored, 2);
+    let mut entries: Vec<NodeID> = table
+        .borrow()
+        .routes_to(4)
+        .map(|route| route.hops[0])
+        .collect();
+    entries.sort();
+    assert_eq!(entries, vec![1, 2]);
+}
+
+#[test]
+fn empty_budgets_compute_the_first_route() {
+    for budget in [
+        CgrBudget::routes(0),
+        CgrBudget {
+            max_routes: Some(0),
+            ..Default::default()
+        },
+    ] {
+        let (nodes, contacts) = parse();
+        let table = Rc::new(RefCell::new(Table::new()));
+        let mut router =
+            CgrFirstEndingNodeParenting::with_budget(nodes, contacts, table, Some(budget));
+        assert!(router.route(0, &bundle(), TIME_ZERO, &vec![]).is_some());
+    }
+}
@@ -0,0 +1,174 @@
+//! Route selection fixtures for the SABR compliance mode (`CgrSabrCompliant`).
+//!
+//! Each fixture exercises one rule of the CCSDS SABR route selection: the candidate routes are
+//! filtered (termination, capacity, backlog, delivery deadline), then ranked by delivery time,
+//! hop count, termination time and entry node number.
+#![cfg(feature = "contact_suppression")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::qd::QDManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    distance::sabr::SABRCompliant,
+    node_manager::none::NoManagement,
+    route_storage::table::{CandidateVerdict, RoutingTable},
+    routing::{aliases::CgrSabrCompliant, Router, RoutingOutput},
+    types::{time_from_f64, time_to_f64, volume_from_f64, Date, NodeID, TIME_ZERO},
+};
+
+const NODES: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 dst\n";
+
+struct Fixture {
+    name: &'static str,
+    contacts: &'static str,
+    size: f64,
+    expiration: f64,
+    /// The expected entry node and delivery time, `None` if the bundle must not be routed.
+    expected: Option<(NodeID, f64)>,
+}
+
+const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "earliest delivery time",
+        contacts: "contact 0 1 0 1000 100 1\ncontact 1 3 0 1000 100 5\n\
+                   contact 0 2 0 1000 100 1\ncontact 2 3 0 1000 100 1\n",
+        size: 0.0,
+        expiration: 1000.0,
+        expected: Some((2, 2.0)),
+    },
+    Fixture {
+        name: "fewest hops on equal delivery times",
+        contacts: "contact 0 1 0 1000 100 1\ncontact 1 3 0 1000 100 1\n\
+                   contact 0 3 0 1000 100 2\n",
+        size: 0.0,
+        expiration: 1000.0,
+        expected: Some((3, 2.0)),
+    },
+    Fixture {
+        name: "latest termination time on equal hops",
+        contacts: "contact 0 1 0 1000 100 1\ncontact 1 3 0 1000 100 1\n\
+                   contact 0 2 0 2000 100 1\ncontact 2 3 0 2000 100 1\n",
+        size: 0.0,
+        expiration: 1000.0,
+        expected: Some((2, 2.0)),
+    },
+    Fixture {
+        name: "lowest entry node on equal termination times",
+        contacts: "contact 0 2 0 1000 100 1\ncontact 2 3 0 1000 100 1\n\
+                   contact 0 1 0 1000 100 1\ncontact 1 3 0 1000 100 1\n",
+        size: 0.0,
+        expiration: 1000.0,
+        expected: Some((1, 2.0)),
+    },
+    Fixture {
+        name: "insufficient capacity",
+        contacts: "contact 0 1 0 10 1 1\ncontact 1 3 0 1000 100 1\n\
+                   contact 0 2 0 1000 20 5\ncontact 2 3 0 1000 20 1\n",
+        size: 20.0,
+        expiration: 1000.0,
+        expected: Some((2, 8.0)),
+    },
+    Fixture {
+        name: "delivery after the bundle expiration",
+        contacts: "contact 0 1 0 1000 100 5\ncontact 1 3 0 1000 100 5\n",
+        size: 1.0,
+        expiration: 5.0,
+        expected: None,
+    },
+];
+
+fn build_router(
+    contacts: &str,
+    routing_table: Rc<RefCell<RoutingTable<NoManagement, QDManager, SABRCompliant>>>,
+) -> CgrSabrCompliant<NoManagement, QDManager> {
+    let plan = format!("{}{}", NODES, contacts);
+    let mut lexer = StrLexer::new(&plan);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, QDManager>(&mut lexer, None, None).unwrap();
+    CgrSabrCompliant::sabr_compliant(nodes, contacts, routing_table)
+}
+
+fn bundle(size: f64, expiration: f64) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![3],
+        priority: 0,
+        size: volume_from_f64(size),
+        expiration: time_from_f64(expiration),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+fn entry_and_delivery(output: &RoutingOutput<NoManagement, QDManager>) -> (NodeID, Date) {
+    let (contact, route) = output.lazy_get_for_unicast(3).unwrap();
+    let entry_node = contact.borrow().get_rx_node();
+    let delivery_time = route.borrow().at_time;
+    (entry_node, delivery_time)
+}
+
+#[test]
+fn selection_order_fixtures() {
+    for fixture in FIXTURES {
+        let mut router = build_router(fixture.contacts, Rc::new(RefCell::new(RoutingTable::new())));
+        let output = router.route(
+            0,
+            &bundle(fixture.size, fixture.expiration),
+            TIME_ZERO,
+            &vec![],
+        );
+        match (output, fixture.expected) {
+            (Some(output), Some((entry_node, delivery_time))) => {
+                let (actual_entry, actual_delivery) = entry_and_delivery(&output);
+                assert_eq!(actual_entry, entry_node, "{}", fixture.name);
+                assert!(
+                    (time_to_f64(actual_delivery) - delivery_time).abs() < 1e-9,
+                    "{}: delivery at {} instead of {}",
+                    fixture.name,
+                    actual_delivery,
+                    delivery_time
+                );
+            }
+            (None, None) => {}
+            (output, _) => panic!(
+                "{}: unexpected routing result (routed: {})",
+                fixture.name,
+                output.is_some()
+            ),
+        }
+    }
+}
+
+#[test]
+fn backlog_moves_the_selection() {
+    let contacts = "contact 0 1 0 1000 1 1\ncontact 1 3 0 1000 1000 1\n\
+                    contact 0 2 0 1000 1000 15\ncontact 2 3 0 1000 1000 1\n";
+    let routing_table = Rc::new(RefCell::new(RoutingTable::with_audit()));
+    let mut router = build_router(contacts, routing_table.clone());
+
+    let first = router
+        .route(0, &bundle(10.0, 1000.0), TIME_ZERO, &vec![])
+        .unwrap();
+    assert_eq!(entry_and_delivery(&first).0, 1);
+
+    // the queue of the first contact now delays the route through node 1
+    let second = router
+        .route(0, &bundle(10.0, 1000.0), TIME_ZERO, &vec![])
+        .unwrap();
+    assert_eq!(entry_and_delivery(&second).0, 2);
+
+    let table = routing_table.borrow();
+    let audit = table.last_audit().unwrap();
+    assert_eq!(audit.len(), 2);
+    for record in audit {
+        match record.entry_node {
+            Some(1) => assert!(record.verdict == CandidateVerdict::Outranked),
+            Some(2) => assert!(record.verdict == CandidateVerdict::Selected),
+            other => panic!("unexpected entry node {:?}", other),
+        }
+        assert!(record.delivery_time.is_some());
+    }
+}
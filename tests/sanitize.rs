@@ -0,0 +1,92 @@
+//! Sanitized contact plans: the output is deterministic for a seed, and parses to the same
+//! contacts up to the jitter of their times (see `PlanSanitizer`).
+
+use a_sabr::{
+    contact::Contact,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{
+        asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan, sanitize::PlanSanitizer,
+    },
+    node::Node,
+    node_manager::none::NoManagement,
+    types::{duration_from_f64, time_from_seconds, time_to_f64, NodeID},
+};
+
+const PLAN: &str = "# mission plan\nnode 0 ground site=secret\nnode 1 orbiter\nnode 2 lander\n\
+                    alias 1 mro\n\
+                    contact 0 1 100 200 10 1\ncontact 1 2 150 300 20 2\n\
+                    contact 1 0 400 420 5 1 repeat 1000 until 3500\n";
+
+/// The same plan, with absolute times relative to a mission epoch.
+const EPOCH_PLAN: &str = "epoch 2025-01-01T00:00:00Z\nnode 0 ground\nnode 1 orbiter\n\
+                          contact 0 1 2025-01-01T00:01:40Z 2025-01-01T00:03:20Z 10 1\n\
+                          contact 1 0 100 200 10 1 repeat 1000 until 2025-01-01T00:50:00Z\n";
+
+fn parse(
+    plan: &str,
+) -> (
+    Vec<Node<NoManagement>>,
+    Vec<Contact<NoManagement, EVLManager>>,
+) {
+    let mut lexer = StrLexer::new(plan);
+    ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap()
+}
+
+/// The durations of the contacts (in microseconds), by sender and receiver.
+fn durations(plan: &str) -> Vec<(NodeID, NodeID, f64)> {
+    let (_, contacts) = parse(plan);
+    let mut durations: Vec<_> = contacts
+        .iter()
+        .map(|contact| {
+            (
+                contact.info.tx_node,
+                contact.info.rx_node,
+                // the jittered times may round differently
+                ((time_to_f64(contact.info.end) - time_to_f64(contact.info.start)) * 1e6).round(),
+            )
+        })
+        .collect();
+    durations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    durations
+}
+
+fn sanitizer(seed: u64) -> PlanSanitizer {
+    PlanSanitizer::new(seed).jitter_times(duration_from_f64(30.0))
+}
+
+#[test]
+fn sanitizing_is_deterministic() {
+    let sanitized = sanitizer(7).sanitize(PLAN).unwrap();
+    assert_eq!(sanitized, sanitizer(7).sanitize(PLAN).unwrap());
+    assert_ne!(sanitized, sanitizer(8).sanitize(PLAN).unwrap());
+}
+
+#[test]
+fn sanitized_plans_parse_to_the_same_contacts() {
+    let sanitized = sanitizer(7).sanitize(PLAN).unwrap();
+    for secret in ["mission", "ground", "site=secret", "mro"] {
+        assert!(!sanitized.contains(secret), "{} leaked", secret);
+    }
+    // the jitter keeps the durations and the count of repetitions
+    assert_eq!(durations(&sanitized), durations(PLAN));
+    let (nodes, _) = parse(&sanitized);
+    assert_eq!(nodes.len(), 3);
+}
+
+#[test]
+fn epochs_are_resolved_and_dropped() {
+    let sanitized = PlanSanitizer::new(7).sanitize(EPOCH_PLAN).unwrap();
+    assert!(!sanitized.contains("epoch") && !sanitized.contains("2025"));
+    assert_eq!(durations(&sanitized), durations(EPOCH_PLAN));
+
+    // without jitter, the times are the ones relative to the epoch
+    let (_, contacts) = parse(&sanitized);
+    assert!(contacts
+        .iter()
+        .any(|contact| contact.info.start == time_from_seconds(100.0)
+            && contact.info.end == time_from_seconds(200.0)));
+
+    // an absolute time needs the epoch
+    let no_epoch = EPOCH_PLAN.replace("epoch 2025-01-01T00:00:00Z\n", "");
+    assert!(PlanSanitizer::new(7).sanitize(&no_epoch).is_err());
+}
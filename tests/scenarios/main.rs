@@ -0,0 +1,86 @@
+//! The end-to-end scenarios of `examples/scenarios`, each replayed against its expected metrics
+//! (see `scenario::Scenario`).
+//!
+//! The plans of the catalog are written in seconds with fractional delays, the suite does not run
+//! with integer dates.
+#![cfg(not(feature = "integer_time"))]
+
+#[path = "../../examples/scenarios/catalog.rs"]
+mod catalog;
+
+use a_sabr::{
+    contact_manager::legacy::evl::EVLManager, faults::FaultModel, node_manager::none::NoManagement,
+    scenario::Scenario,
+};
+
+fn check(scenario: Scenario) {
+    let report = scenario.run::<NoManagement, EVLManager>().unwrap();
+    assert!(report.passed(), "{}", report);
+}
+
+#[test]
+fn leo_downlink() {
+    check(catalog::leo_downlink());
+}
+
+#[test]
+fn deep_space_relay() {
+    check(catalog::deep_space_relay());
+}
+
+#[test]
+fn disaster_response() {
+    check(catalog::disaster_response());
+}
+
+#[test]
+fn pathfinding_statistics_are_gathered() {
+    let report = catalog::leo_downlink()
+        .run::<NoManagement, EVLManager>()
+        .unwrap();
+    let stats = report.pathfinding.unwrap();
+    assert!(stats.runs > 0, "{}", report);
+    assert!(stats.expanded >= stats.runs, "{}", report);
+    // the source stage of each run is pushed
+    assert!(stats.pops <= stats.pushes, "{}", report);
+    assert!(stats.pushes >= stats.runs, "{}", report);
+}
+
+#[test]
+fn faults_degrade_the_deliveries() {
+    let router_types = ["SpsnNodeParenting", "VolCgrNodeParenting"];
+
+    // without fault, the routes deliver as planned
+    let report = catalog::leo_downlink()
+        .compare_robustness::<NoManagement, EVLManager>(&router_types, &FaultModel::new(7))
+        .unwrap();
+    for router in &report.routers {
+        assert!(router.replay.planned == router.replay.actual, "{}", report);
+    }
+
+    // a contact failing surely, no route delivers
+    let report = catalog::leo_downlink()
+        .compare_robustness::<NoManagement, EVLManager>(
+            &router_types,
+            &FaultModel::new(7).failures(1.0),
+        )
+        .unwrap();
+    for router in &report.routers {
+        let replay = &router.replay;
+        assert_eq!(replay.actual.reached, 0, "{}", report);
+        assert_eq!(replay.lost_to_failures, replay.planned.reached);
+        assert_eq!(replay.delivery_loss(), 1.0);
+    }
+
+    // the contacts delivering at a hundredth of their rate, the transmissions are truncated or
+    // delayed
+    let report = catalog::leo_downlink()
+        .compare_robustness::<NoManagement, EVLManager>(
+            &router_types,
+            &FaultModel::new(7).reduced_rates(1.0, 0.01),
+        )
+        .unwrap();
+    let replay = report.get("SpsnNodeParenting").unwrap();
+    assert_eq!(replay.lost_to_failures, 0);
+    assert!(replay.lost_to_truncations > 0 || replay.latency_increase() > 0.0);
+}
@@ -0,0 +1,104 @@
+//! The "store and re-route" loop of `Scheduler`: the parked bundles are re-routed when a contact
+//! of their source starts, and dropped once expired.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        scheduler::{Scheduler, SchedulerEvent, Submission},
+        NeighborState, Router,
+    },
+    types::{time_from_f64, volume_from_f64, Date, TIME_ZERO},
+};
+
+/// A route from 0 to 2 via 1, the contacts of the source starting at 10 and 50.
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 10 20 10 1\ncontact 0 1 50 60 10 1\n\
+                    contact 1 2 0 100 10 1\n";
+
+fn router() -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    };
+    build_generic_router("SpsnNodeParenting", nodes, contacts, Some(options)).unwrap()
+}
+
+fn scheduler(router: &dyn Router<NoManagement, EVLManager>) -> Scheduler {
+    Scheduler::from_multigraph(&router.multigraph().unwrap().borrow())
+}
+
+fn bundle(expiration: f64) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![2],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(expiration),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn routable_bundles_are_not_parked() {
+    let mut router = router();
+    let mut scheduler = scheduler(router.as_ref());
+    let submission = scheduler.submit(router.as_mut(), 0, bundle(1000.0), TIME_ZERO, Vec::new());
+    assert!(matches!(submission, Submission::Routed(_)));
+    assert!(scheduler.get_pending().is_empty());
+}
+
+#[test]
+fn parked_bundles_are_rerouted_when_their_source_contact_starts() {
+    let mut router = router();
+    let mut scheduler = scheduler(router.as_ref());
+
+    // the relay is down, the bundle is parked
+    assert!(router.set_neighbor_state(1, NeighborState::Down, Date::MAX));
+    let submission = scheduler.submit(router.as_mut(), 0, bundle(1000.0), TIME_ZERO, Vec::new());
+    let Submission::Parked(id) = submission else {
+        panic!("the bundle was routed toward a node down");
+    };
+    assert!(scheduler.next_event_time() == Some(time_from_f64(10.0)));
+
+    // the relay is back, but no contact of the source started yet
+    assert!(router.set_neighbor_state(1, NeighborState::Up, Date::MAX));
+    assert!(scheduler
+        .poll(router.as_mut(), time_from_f64(5.0))
+        .is_empty());
+    assert_eq!(scheduler.get_pending().len(), 1);
+
+    // the first contact of the source starts
+    let events = scheduler.poll(router.as_mut(), time_from_f64(10.0));
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], SchedulerEvent::Routed { pending, .. } if pending.id == id));
+    assert!(scheduler.get_pending().is_empty());
+    assert!(scheduler.next_event_time().is_none());
+}
+
+#[test]
+fn parked_bundles_expire() {
+    let mut router = router();
+    let mut scheduler = scheduler(router.as_ref());
+    assert!(router.set_neighbor_state(1, NeighborState::Down, Date::MAX));
+    scheduler.submit(router.as_mut(), 0, bundle(30.0), TIME_ZERO, Vec::new());
+    scheduler.submit(router.as_mut(), 0, bundle(1000.0), TIME_ZERO, Vec::new());
+
+    // the relay is still down at the next contacts of the source
+    assert!(scheduler
+        .poll(router.as_mut(), time_from_f64(10.0))
+        .is_empty());
+    let events = scheduler.poll(router.as_mut(), time_from_f64(50.0));
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], SchedulerEvent::Expired { .. }));
+    assert_eq!(scheduler.get_pending().len(), 1);
+}
@@ -0,0 +1,86 @@
+//! The router service (see `spawn_router_service`): the requests are routed by the router of the
+//! service thread, whatever the capacity of the request channel.
+#![cfg(feature = "service")]
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        Router,
+    },
+    service::spawn_router_service,
+    types::{time_from_f64, volume_from_f64, TIME_ZERO},
+};
+
+const PLAN: &str = "node 0 src\nnode 1 relay\nnode 2 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 2 0 100 10 1\n";
+
+/// Unparks the thread blocked on the future.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread, the tests need no async runtime.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn router() -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    };
+    build_generic_router("SpsnNodeParenting", nodes, contacts, Some(options)).unwrap()
+}
+
+fn bundle(source: u16, dest: u16) -> Bundle {
+    Bundle {
+        source,
+        destinations: vec![dest],
+        priority: 0,
+        size: volume_from_f64(1.0),
+        expiration: time_from_f64(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+#[test]
+fn requests_are_routed_by_the_service() {
+    // an unbuffered channel is raised to a single request
+    for buffer in [0, 4] {
+        let handle = spawn_router_service(router, buffer);
+        let routed = block_on(handle.route(0, bundle(0, 2), TIME_ZERO, Vec::new())).unwrap();
+        assert!(routed.is_some());
+        let unreachable = block_on(handle.route(2, bundle(2, 0), TIME_ZERO, Vec::new())).unwrap();
+        assert!(unreachable.is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Pre-routed bundles: the route carried by a bundle is validated and scheduled against the
+//! current resources, the router falling back to its own routing when the route cannot be
+//! followed (see `Router::route_pre_routed`).
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::{asabr_str_lexer::StrLexer, from_asabr_lexer::ASABRContactPlan},
+    node_manager::none::NoManagement,
+    routing::{
+        aliases::{build_generic_router, SpsnOptions},
+        source_route::{schedule_route_header, RouteHeader, RouteRejection},
+        Router, RoutingOutput,
+    },
+    types::{time_from_f64, volume_from_f64, NodeID, TIME_ZERO},
+};
+
+/// Two routes from 0 to 3: via 1 (delivery at 12), and via 2 (the contact toward 3 starting at
+/// 50).
+const PLAN: &str = "node 0 src\nnode 1 a\nnode 2 b\nnode 3 dst\n\
+                    contact 0 1 0 100 10 1\ncontact 1 3 0 100 10 1\n\
+                    contact 0 2 0 100 10 1\ncontact 2 3 50 100 10 1\n";
+
+fn router() -> Box<dyn Router<NoManagement, EVLManager>> {
+    let mut lexer = StrLexer::new(PLAN);
+    let (nodes, contacts) =
+        ASABRContactPlan::parse::<NoManagement, EVLManager>(&mut lexer, None, None).unwrap();
+    let options = SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    };
+    build_generic_router("SpsnNodeParenting", nodes, contacts, Some(options)).unwrap()
+}
+
+fn bundle(size: f64) -> Bundle {
+    Bundle {
+        source: 0,
+        destinations: vec![3],
+        priority: 0,
+        size: volume_from_f64(size),
+        expiration: time_from_f64(10000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    }
+}
+
+fn header(text: &str) -> RouteHeader {
+    RouteHeader::parse(&mut StrLexer::new(text)).unwrap()
+}
+
+/// The nodes of the route toward the destination.
+fn relays(output: &RoutingOutput<NoManagement, EVLManager>, bundle: &Bundle) -> Vec<NodeID> {
+    let route = output.source_route(3, bundle).unwrap();
+    route.hops.iter().map(|hop| hop.rx_node).collect()
+}
+
+#[test]
+fn route_headers_are_parsed() {
+    assert!(header("nodes 0,2,3") == RouteHeader::Nodes(vec![0, 2, 3]));
+    assert!(header("contacts 1,3") == RouteHeader::Contacts(vec![1, 3]));
+    assert_eq!(header("nodes 0,2,3").to_string(), "nodes 0,2,3");
+    assert!(RouteHeader::parse(&mut StrLexer::new("relays 0,2,3")).is_err());
+    assert!(RouteHeader::parse(&mut StrLexer::new("nodes 0,b,3")).is_err());
+    assert!(RouteHeader::parse(&mut StrLexer::new("nodes")).is_err());
+}
+
+#[test]
+fn carried_routes_are_followed() {
+    let mut router = router();
+    let bundle = bundle(10.0);
+
+    // the route via 2 is followed, though the route via 1 delivers earlier
+    let output = router
+        .route_pre_routed(0, &bundle, &header("nodes 0,2,3"), TIME_ZERO, &Vec::new())
+        .unwrap();
+    assert_eq!(relays(&output, &bundle), vec![2, 3]);
+
+    // a node hop not linked by a contact, the router routes the bundle itself
+    let output = router
+        .route_pre_routed(0, &bundle, &header("nodes 0,1,2,3"), TIME_ZERO, &Vec::new())
+        .unwrap();
+    assert_eq!(relays(&output, &bundle), vec![1, 3]);
+
+    // the carried route reaching an excluded node
+    let output = router
+        .route_pre_routed(0, &bundle, &header("nodes 0,2,3"), TIME_ZERO, &vec![2])
+        .unwrap();
+    assert_eq!(relays(&output, &bundle), vec![1, 3]);
+}
+
+#[test]
+fn carried_routes_are_validated() {
+    let router = router();
+    let multigraph = router.multigraph().unwrap();
+    let reject = |header: &RouteHeader, bundle: &Bundle| {
+        schedule_route_header(&multigraph, 0, bundle, header, TIME_ZERO, &[])
+            .err()
+            .unwrap()
+    };
+
+    assert!(reject(&header("nodes 0,2"), &bundle(10.0)) == RouteRejection::WrongEndpoints);
+    assert!(
+        reject(&header("nodes 0,1,2,3"), &bundle(10.0)) == RouteRejection::Discontinuous { hop: 1 }
+    );
+    assert!(
+        reject(&header("contacts 99"), &bundle(10.0))
+            == RouteRejection::UnknownContact { contact: 99 }
+    );
+    // a contact of 1000 units of volume cannot carry 2000 units
+    assert!(matches!(
+        reject(&header("nodes 0,2,3"), &bundle(2000.0)),
+        RouteRejection::HopRefused { hop: 0, .. }
+    ));
+}
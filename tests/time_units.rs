@@ -0,0 +1,58 @@
+//! The time units of the plans (see `TICKS_PER_SECOND`): the times in seconds of the epochs and
+//! of the external formats keep their sub-second resolution with the integer ticks.
+
+use a_sabr::{
+    bundle::Bundle,
+    contact_manager::legacy::evl::EVLManager,
+    contact_plan::from_ion_file::IONContactPlan,
+    epoch::Epoch,
+    node_manager::none::NoManagement,
+    routing::aliases::{build_generic_router, SpsnOptions},
+    types::{time_from_seconds, time_to_seconds, volume_from_f64, TICKS_PER_SECOND, TIME_ZERO},
+};
+
+/// Whether the dates are rounded to whole seconds.
+fn whole_seconds() -> bool {
+    cfg!(feature = "integer_time") && TICKS_PER_SECOND == 1.0
+}
+
+#[test]
+fn epochs_convert_the_seconds_to_ticks() {
+    let epoch = Epoch::from_dtn_seconds(1000.0);
+    let date = epoch.to_relative(1060.25);
+    assert!(date == time_from_seconds(60.25));
+    let expected = if whole_seconds() { 1060.0 } else { 1060.25 };
+    assert_eq!(epoch.to_dtn_seconds(date), expected);
+}
+
+#[test]
+fn imported_transmissions_keep_the_sub_second_times() {
+    // 10 bytes per second and 1 second of delay, in seconds as all ION plans
+    let path = std::env::temp_dir().join(format!("a_sabr_ticks_{}.ion", std::process::id()));
+    std::fs::write(&path, "a contact +0 +100 1 2 10\na range +0 +100 1 2 1\n").unwrap();
+    let (nodes, contacts) =
+        IONContactPlan::parse::<NoManagement, EVLManager>(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let options = SpsnOptions {
+        check_size: true,
+        check_priority: false,
+        max_entries: 10,
+    };
+    let mut router =
+        build_generic_router("SpsnNodeParenting", nodes, contacts, Some(options)).unwrap();
+    let bundle = Bundle {
+        source: 0,
+        destinations: vec![1],
+        priority: 0,
+        size: volume_from_f64(5.0),
+        expiration: time_from_seconds(1000.0),
+        soft_deadline: None,
+        critical: false,
+        destination_priorities: Vec::new(),
+    };
+    let estimate = router.estimate(0, &bundle, TIME_ZERO, &Vec::new()).unwrap();
+    // half a second of transmission, rounded up to the second without a finer tick
+    let expected = if whole_seconds() { 2.0 } else { 1.5 };
+    assert_eq!(time_to_seconds(estimate.delivery_time), expected);
+}
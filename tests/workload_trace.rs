@@ -0,0 +1,59 @@
+//! Workload traces: parsing, with relative or absolute times, and printing back with `Display`.
+
+use a_sabr::{
+    contact_plan::asabr_str_lexer::StrLexer,
+    parsing::Lexer,
+    types::{time_from_f64, time_from_seconds, volume_from_f64},
+    workload::WorkloadTrace,
+};
+
+const TRACE: &str = "# two submissions\nbundle 0 0 3 100 1 1000\nbundle 12 1 2,3 40 0 500\n";
+
+fn parse(text: &str) -> Result<WorkloadTrace, String> {
+    let mut lexer = StrLexer::new(text);
+    WorkloadTrace::parse(&mut lexer as &mut dyn Lexer)
+}
+
+#[test]
+fn traces_are_parsed() {
+    let trace = parse(TRACE).unwrap();
+    assert_eq!(trace.entries.len(), 2);
+    let entry = &trace.entries[1];
+    assert!(entry.time == time_from_f64(12.0));
+    assert_eq!(entry.bundle.source, 1);
+    assert_eq!(entry.bundle.destinations, vec![2, 3]);
+    assert!(entry.bundle.size == volume_from_f64(40.0));
+    assert!(entry.bundle.expiration == time_from_f64(500.0));
+
+    assert!(parse("contact 0 1 0 10 1 1\n").is_err());
+    assert!(parse("bundle 0 0 3,x 100 1 1000\n").is_err());
+    assert!(parse("bundle 0 0 3 100\n").is_err());
+}
+
+#[test]
+fn absolute_times_need_an_epoch() {
+    let trace = parse(
+        "epoch 2025-01-01T00:00:00Z\n\
+         bundle 2025-01-01T00:01:00Z 0 3 100 1 dtn:789004900\n",
+    )
+    .unwrap();
+    assert!(trace.entries[0].time == time_from_seconds(60.0));
+    assert!(trace.entries[0].bundle.expiration == time_from_seconds(100.0));
+
+    assert!(parse("bundle 2025-01-01T00:01:00Z 0 3 100 1 1000\n").is_err());
+    assert!(parse("epoch tomorrow\nbundle 0 0 3 100 1 1000\n").is_err());
+}
+
+#[test]
+fn traces_are_printed_back() {
+    let trace = parse(TRACE).unwrap();
+    let printed = trace.to_string();
+    assert_eq!(parse(&printed).unwrap().to_string(), printed);
+
+    // the absolute times are printed relative to the epoch
+    let trace = parse("epoch dtn:1000\nbundle dtn:1060 0 3 100 1 1200\n").unwrap();
+    let printed = trace.to_string();
+    assert!(!printed.contains("epoch"));
+    let reparsed = parse(&printed).unwrap();
+    assert!(reparsed.entries[0].time == time_from_seconds(60.0));
+}